@@ -0,0 +1,207 @@
+//! clipper-agent: the desktop app's clipboard monitor and sync, without a GUI
+//!
+//! Text, images and files copied here are saved as clips on the server, and
+//! clips copied on other devices or pushed to this one are put on the
+//! clipboard. Meant for window managers without a tray and for servers used
+//! over X forwarding, where the full desktop app isn't wanted.
+
+use anyhow::Result;
+use clap::Parser;
+use clipper_client::{ClipperClient, ConnectionStatus, ReconnectConfig};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use tokio::sync::mpsc;
+
+mod monitor;
+mod sync;
+
+use clipper_clipboard::MonitorOptions;
+use monitor::LastSynced;
+
+/// Bytes in a MB, as `--max-image-size-mb` counts them
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Parser)]
+#[command(name = "clipper-agent")]
+#[command(about = "Headless clipboard sync for Clipper", long_about = None)]
+struct Cli {
+    /// Server URL
+    #[arg(
+        short,
+        long,
+        env = "CLIPPER_URL",
+        default_value = "http://localhost:3000"
+    )]
+    url: String,
+
+    /// Bearer token for authentication
+    #[arg(short, long, env = "CLIPPER_TOKEN")]
+    token: Option<String>,
+
+    /// Only put clips from other devices with one of these tags on the
+    /// clipboard (comma-separated); everything copied here is still saved
+    #[arg(long, env = "CLIPPER_SYNC_TAGS", value_delimiter = ',')]
+    sync_tags: Vec<String>,
+
+    /// Don't put clips from other devices on the clipboard, only save what is
+    /// copied here (clips pushed to this device still arrive)
+    #[arg(long)]
+    send_only: bool,
+
+    /// Don't save copied images
+    #[arg(long)]
+    no_images: bool,
+
+    /// Largest copied image saved, in MB of PNG
+    #[arg(long, default_value_t = 10)]
+    max_image_size_mb: u64,
+
+    /// Also save text selected with the mouse (the primary selection)
+    #[cfg(target_os = "linux")]
+    #[arg(long)]
+    primary_selection: bool,
+}
+
+impl Cli {
+    /// `--max-image-size-mb` in bytes; absurd sizes mean no limit rather
+    /// than overflowing
+    fn max_image_size_bytes(&self) -> u64 {
+        self.max_image_size_mb.saturating_mul(BYTES_PER_MB)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Set restrictive permissions for newly created files and directories.
+    // On Unix: Sets umask to 0o077 (files 0600, directories 0700)
+    // On Windows: This is a no-op
+    clipper_security::set_restrictive_umask();
+
+    rustls::crypto::ring::default_provider()
+        .install_default()
+        .expect("Failed to install rustls crypto provider");
+
+    let cli = Cli::parse();
+
+    let mut client = match cli.token.clone() {
+        Some(token) => ClipperClient::new_with_token(&cli.url, token),
+        None => ClipperClient::new(&cli.url),
+    };
+    client.set_sync_tags(trimmed_tags(&cli.sync_tags));
+    // Named after the host, so clips can be pushed to this clipboard
+    client.set_device_name(Some(
+        gethostname::gethostname().to_string_lossy().to_string(),
+    ));
+
+    let options = MonitorOptions::default();
+    options
+        .capture_images
+        .store(!cli.no_images, Ordering::SeqCst);
+    #[cfg(target_os = "linux")]
+    options
+        .capture_primary_selection
+        .store(cli.primary_selection, Ordering::SeqCst);
+    let last_synced = Arc::new(LastSynced::default());
+    monitor::start_clipboard_monitor(
+        client.clone(),
+        options,
+        cli.max_image_size_bytes(),
+        Arc::clone(&last_synced),
+    );
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    // Clips missed while disconnected stay in the history; putting each of
+    // them on the clipboard after reconnecting would only flicker through them
+    let config = ReconnectConfig {
+        recover_missed: false,
+        ..ReconnectConfig::default()
+    };
+    let subscription =
+        client.subscribe_notifications_with_reconnect(tx, config, |status| match status {
+            ConnectionStatus::Connected => eprintln!("Connected to server"),
+            ConnectionStatus::Disconnected { error: Some(e) } => {
+                eprintln!("Disconnected from server: {}", e)
+            }
+            ConnectionStatus::Disconnected { error: None } => eprintln!("Disconnected from server"),
+            ConnectionStatus::Reconnecting { attempt, delay } => {
+                eprintln!(
+                    "Reconnecting in {:.1}s (attempt {})",
+                    delay.as_secs_f64(),
+                    attempt
+                );
+            }
+            // Recovery is off, so it can't fail
+            ConnectionStatus::Connecting | ConnectionStatus::RecoveryFailed { .. } => {}
+        });
+
+    eprintln!("Syncing the clipboard with {}", cli.url);
+    tokio::select! {
+        _ = sync::apply_notifications(client, rx, cli.send_only, last_synced) => {}
+        _ = shutdown_signal() => {}
+    }
+    subscription.abort();
+    Ok(())
+}
+
+/// `--sync-tags` without surrounding whitespace and empty entries
+fn trimmed_tags(tags: &[String]) -> Vec<String> {
+    tags.iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Resolve on Ctrl+C, or on SIGTERM from a service manager
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["clipper-agent"]).unwrap();
+        assert!(cli.sync_tags.is_empty());
+        assert!(!cli.send_only);
+        assert_eq!(cli.max_image_size_mb, 10);
+
+        let cli =
+            Cli::try_parse_from(["clipper-agent", "--sync-tags", " work, ,$host:laptop,"]).unwrap();
+        assert_eq!(trimmed_tags(&cli.sync_tags), ["work", "$host:laptop"]);
+    }
+
+    #[test]
+    fn test_max_image_size_does_not_overflow() {
+        let cli = Cli::try_parse_from(["clipper-agent", "--max-image-size-mb", "2"]).unwrap();
+        assert_eq!(cli.max_image_size_bytes(), 2 * 1024 * 1024);
+
+        let max = u64::MAX.to_string();
+        let cli = Cli::try_parse_from(["clipper-agent", "--max-image-size-mb", &max]).unwrap();
+        assert_eq!(cli.max_image_size_bytes(), u64::MAX);
+    }
+}
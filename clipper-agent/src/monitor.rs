@@ -0,0 +1,151 @@
+//! Saving what is copied here as clips
+
+use chrono::Utc;
+use clipper_client::ClipperClient;
+#[cfg(target_os = "linux")]
+use clipper_clipboard::primary::PRIMARY_SELECTION_TAG;
+use clipper_clipboard::{
+    ClipboardContent, ClipboardEvent, ClipboardEvents, ClipboardWatcher, MonitorOptions,
+    file_list_content, hostname_tag,
+};
+use std::sync::{Arc, Mutex};
+
+/// What the agent last put on the clipboard, so the monitor doesn't save it
+/// again
+#[derive(Default)]
+pub struct LastSynced {
+    text: Mutex<String>,
+    image: Mutex<Vec<u8>>,
+}
+
+impl LastSynced {
+    pub fn set_text(&self, text: String) {
+        *self.text.lock().unwrap() = text;
+    }
+
+    pub fn set_image(&self, png_bytes: Vec<u8>) {
+        *self.image.lock().unwrap() = png_bytes;
+    }
+
+    fn is_text(&self, text: &str) -> bool {
+        *self.text.lock().unwrap() == text
+    }
+
+    fn is_image(&self, png_bytes: &[u8]) -> bool {
+        *self.image.lock().unwrap() == png_bytes
+    }
+}
+
+/// Watch the clipboard on a thread of its own and save each new copy with
+/// `client`, on the current runtime. Images larger than
+/// `max_image_size_bytes` are skipped.
+pub fn start_clipboard_monitor(
+    client: ClipperClient,
+    options: MonitorOptions,
+    max_image_size_bytes: u64,
+    last_synced: Arc<LastSynced>,
+) {
+    let rt = tokio::runtime::Handle::current();
+
+    std::thread::spawn(move || {
+        // Last text saved from the primary selection, so copying the selected
+        // text doesn't save it again
+        #[cfg(target_os = "linux")]
+        let mut saved_primary_selection: Option<String> = None;
+
+        for event in ClipboardEvents::new(ClipboardWatcher::new(), options) {
+            let content = match event {
+                ClipboardEvent::Copied(content) => content,
+                #[cfg(target_os = "linux")]
+                ClipboardEvent::Selected(text) => {
+                    // Skip text that was just synced
+                    if !last_synced.is_text(&text) {
+                        saved_primary_selection = Some(text.clone());
+                        let tags = vec![PRIMARY_SELECTION_TAG.to_string(), hostname_tag()];
+                        rt.spawn(save_text(client.clone(), text, tags));
+                    }
+                    continue;
+                }
+                #[cfg(not(target_os = "linux"))]
+                ClipboardEvent::Selected(_) => continue,
+            };
+
+            // Skip what was just synced from the server (avoid loop)
+            let synced = match &content {
+                ClipboardContent::Text(text) => last_synced.is_text(text),
+                ClipboardContent::Image(png_bytes) => last_synced.is_image(png_bytes),
+                _ => false,
+            };
+            if synced {
+                continue;
+            }
+
+            match content {
+                ClipboardContent::Text(text) => {
+                    #[cfg(target_os = "linux")]
+                    if saved_primary_selection.as_ref() == Some(&text) {
+                        continue;
+                    }
+                    rt.spawn(save_text(client.clone(), text, vec![hostname_tag()]));
+                }
+                ClipboardContent::Image(png_bytes) => {
+                    if png_bytes.len() as u64 > max_image_size_bytes {
+                        eprintln!(
+                            "Image size ({:.2} MB) exceeds maximum allowed size ({:.2} MB), skipping upload",
+                            png_bytes.len() as f64 / (1024.0 * 1024.0),
+                            max_image_size_bytes as f64 / (1024.0 * 1024.0)
+                        );
+                        continue;
+                    }
+                    rt.spawn(save_image(client.clone(), png_bytes));
+                }
+                // Files are listed rather than uploaded, since they may not
+                // be readable from here (e.g. copied over X forwarding)
+                ClipboardContent::Files(paths) => {
+                    let tags = vec!["$filelist".to_string(), hostname_tag()];
+                    rt.spawn(save_text(client.clone(), file_list_content(&paths), tags));
+                }
+                ClipboardContent::Empty => {}
+            }
+        }
+    });
+}
+
+async fn save_text(client: ClipperClient, text: String, tags: Vec<String>) {
+    if let Err(e) = client.create_clip(text, tags, None, None).await {
+        eprintln!("Failed to create clip from text: {}", e);
+    }
+}
+
+async fn save_image(client: ClipperClient, png_bytes: Vec<u8>) {
+    let filename = format!("screenshot-{}.png", Utc::now().format("%Y-%m-%d-%H-%M-%S"));
+    let tags = vec!["$image".to_string(), hostname_tag()];
+    if let Err(e) = client
+        .upload_file_bytes(png_bytes, filename, tags, None)
+        .await
+    {
+        eprintln!("Failed to create clip from image: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_synced() {
+        let last_synced = LastSynced::default();
+        assert!(last_synced.is_text(""));
+        assert!(!last_synced.is_text("hello"));
+
+        last_synced.set_text("hello".to_string());
+        assert!(last_synced.is_text("hello"));
+        assert!(!last_synced.is_text("hello again"));
+
+        last_synced.set_image(vec![1, 2, 3]);
+        assert!(last_synced.is_image(&[1, 2, 3]));
+        assert!(!last_synced.is_image(&[1, 2]));
+        // Text and images are tracked apart
+        assert!(last_synced.is_text("hello"));
+    }
+}
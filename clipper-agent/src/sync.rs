@@ -0,0 +1,112 @@
+//! Putting clips from other devices on the clipboard
+
+use clipper_client::{ClipNotification, ClipperClient};
+use clipper_clipboard::{hostname_tag, set_clipboard_image, set_clipboard_text};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::monitor::LastSynced;
+
+/// Put new clips from other devices, and clips pushed to this one, on the
+/// clipboard until the notification channel closes. With `send_only`, only
+/// pushed clips are.
+pub async fn apply_notifications(
+    client: ClipperClient,
+    mut notifications: mpsc::UnboundedReceiver<ClipNotification>,
+    send_only: bool,
+    last_synced: Arc<LastSynced>,
+) {
+    let my_hostname_tag = hostname_tag();
+
+    while let Some(notification) = notifications.recv().await {
+        match notification {
+            ClipNotification::NewClip {
+                id, content, tags, ..
+            } => {
+                if send_only || !takes_new_clip(&client, &my_hostname_tag, &tags) {
+                    continue;
+                }
+                put_on_clipboard(&client, &last_synced, &id, content, &tags).await;
+            }
+            ClipNotification::SetClipboard { id, content, tags } => {
+                eprintln!("Clip {} pushed to the clipboard", id);
+                put_on_clipboard(&client, &last_synced, &id, content, &tags).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether a new clip from another device goes on the clipboard. Clips
+/// copied here are on the clipboard already, and servers without tag
+/// filtering send every clip.
+fn takes_new_clip(client: &ClipperClient, my_hostname_tag: &str, tags: &[String]) -> bool {
+    !tags.iter().any(|tag| tag == my_hostname_tag) && client.matches_sync_tags(tags)
+}
+
+/// Put a clip on the clipboard: its text, or the image of an `$image` clip.
+/// It's marked as synced first so the monitor doesn't save it again.
+async fn put_on_clipboard(
+    client: &ClipperClient,
+    last_synced: &LastSynced,
+    id: &str,
+    content: String,
+    tags: &[String],
+) {
+    let result = if tags.iter().any(|tag| tag == "$image") {
+        match client.download_file(id).await {
+            Ok(image_bytes) => {
+                last_synced.set_image(image_bytes.clone());
+                set_clipboard_image(&image_bytes)
+            }
+            Err(e) => {
+                eprintln!("Failed to download image for clipboard: {}", e);
+                return;
+            }
+        }
+    } else {
+        last_synced.set_text(content.clone());
+        set_clipboard_text(&content)
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to set clipboard: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn test_takes_new_clip() {
+        let mut client = ClipperClient::new("http://localhost:3000");
+        assert!(takes_new_clip(
+            &client,
+            "$host:laptop",
+            &tags(&["$host:phone"])
+        ));
+        assert!(takes_new_clip(&client, "$host:laptop", &[]));
+        // Copied here
+        assert!(!takes_new_clip(
+            &client,
+            "$host:laptop",
+            &tags(&["work", "$host:laptop"])
+        ));
+
+        client.set_sync_tags(tags(&["work"]));
+        assert!(takes_new_clip(
+            &client,
+            "$host:laptop",
+            &tags(&["work", "$host:phone"])
+        ));
+        assert!(!takes_new_clip(
+            &client,
+            "$host:laptop",
+            &tags(&["personal", "$host:phone"])
+        ));
+    }
+}
@@ -78,18 +78,15 @@ clipper_fzf_select() {
     # Build the list command
     local list_cmd
     if [[ -n "$query" ]]; then
-        list_cmd="$CLIPPER_CLI_PATH search '$query' --page-size $CLIPPER_FZF_PAGE_SIZE --format json"
+        list_cmd="$CLIPPER_CLI_PATH search '$query' --page-size $CLIPPER_FZF_PAGE_SIZE --format tsv"
     else
-        list_cmd="$CLIPPER_CLI_PATH list --page-size $CLIPPER_FZF_PAGE_SIZE --format json"
+        list_cmd="$CLIPPER_CLI_PATH list --page-size $CLIPPER_FZF_PAGE_SIZE --format tsv"
     fi
 
-    # Get clips and format for fzf
-    # Format: ID | CONTENT_PREVIEW | TAGS
+    # Get clips in fzf-friendly format
+    # Format: ID <TAB> CREATED_AT <TAB> FIRST_LINE
     local clips
-    clips=$(eval "$list_cmd" 2>/dev/null | jq -r '
-        .items[] |
-        "\(.id)\t\(.content | gsub("\n"; "↵") | .[0:80])\t[\(.tags | join(", "))]"
-    ' 2>/dev/null)
+    clips=$(eval "$list_cmd" 2>/dev/null)
 
     if [[ -z "$clips" ]]; then
         echo -e "${YELLOW}No clips found${NC}" >&2
@@ -103,7 +100,7 @@ clipper_fzf_select() {
         --header='Select a clip (TAB to preview, ENTER to select)'
         --preview-window='right:50%:wrap'
         --bind='ctrl-y:execute-silent(echo -n {1} | pbcopy 2>/dev/null || echo -n {1} | xclip -selection clipboard 2>/dev/null || true)'
-        --bind='ctrl-d:execute($CLIPPER_CLI_PATH delete {1} >/dev/null 2>&1)+reload('"$list_cmd"' | jq -r ".items[] | \"\(.id)\t\(.content | gsub(\"\n\"; \"↵\") | .[0:80])\t[\(.tags | join(\", \"))]\"" 2>/dev/null)'
+        --bind='ctrl-d:execute($CLIPPER_CLI_PATH delete {1} >/dev/null 2>&1)+reload('"$list_cmd"' 2>/dev/null)'
     )
 
     if [[ "$CLIPPER_FZF_PREVIEW" == "1" ]]; then
@@ -141,7 +138,7 @@ clipper_fzf_search() {
     check_deps
 
     # Use fzf with dynamic reloading based on query
-    local reload_cmd="$CLIPPER_CLI_PATH search {q} --page-size $CLIPPER_FZF_PAGE_SIZE --format json 2>/dev/null | jq -r '.items[] | \"\(.id)\t\(.content | gsub(\"\n\"; \"↵\") | .[0:80])\t[\(.tags | join(\", \"))]\"' 2>/dev/null || true"
+    local reload_cmd="$CLIPPER_CLI_PATH search {q} --page-size $CLIPPER_FZF_PAGE_SIZE --format tsv 2>/dev/null || true"
 
     local selected
     selected=$(: | fzf \
@@ -0,0 +1,107 @@
+//! Local system clipboard access for clipper-cli
+//!
+//! Thin wrapper around arboard used by the `copy` and `paste` subcommands.
+//! Mirrors the content priority used by the desktop app's clipboard monitor:
+//! files > images > text.
+
+use anyhow::{Context, Result};
+use arboard::Clipboard;
+use image::{ImageBuffer, Rgba};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+/// Represents the type of clipboard content
+pub enum ClipboardContent {
+    Text(String),
+    Image(Vec<u8>),      // PNG-encoded bytes
+    Files(Vec<PathBuf>), // File paths from clipboard (e.g., copied from Finder/Explorer)
+    Empty,
+}
+
+/// Convert arboard ImageData to PNG bytes
+fn image_data_to_png(image_data: &arboard::ImageData) -> Option<Vec<u8>> {
+    let width = image_data.width as u32;
+    let height = image_data.height as u32;
+
+    let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
+        ImageBuffer::from_raw(width, height, image_data.bytes.to_vec())?;
+
+    let mut png_bytes = Cursor::new(Vec::new());
+    img.write_to(&mut png_bytes, image::ImageFormat::Png).ok()?;
+
+    Some(png_bytes.into_inner())
+}
+
+/// Read the current clipboard content (files, image, or text)
+pub fn get_clipboard_content() -> Result<ClipboardContent> {
+    let mut clipboard = Clipboard::new().context("Failed to access system clipboard")?;
+
+    // Files take highest priority since copying files in Finder/Explorer
+    // also provides a text fallback with the filenames
+    match clipboard.get().file_list() {
+        Ok(files) if !files.is_empty() => return Ok(ClipboardContent::Files(files)),
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => {}
+        Err(e) => return Err(e).context("Failed to read file list from clipboard"),
+    }
+
+    match clipboard.get_image() {
+        Ok(image_data) => {
+            if let Some(png_bytes) = image_data_to_png(&image_data) {
+                return Ok(ClipboardContent::Image(png_bytes));
+            }
+        }
+        Err(arboard::Error::ContentNotAvailable) => {}
+        Err(e) => return Err(e).context("Failed to read image from clipboard"),
+    }
+
+    match clipboard.get_text() {
+        Ok(text) if !text.is_empty() => Ok(ClipboardContent::Text(text)),
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => Ok(ClipboardContent::Empty),
+        Err(e) => Err(e).context("Failed to read text from clipboard"),
+    }
+}
+
+/// Set text content to the system clipboard
+pub fn set_clipboard_text(content: &str) -> Result<()> {
+    let mut clipboard = Clipboard::new().context("Failed to access system clipboard")?;
+    set_and_hold(clipboard.set(), |set| set.text(content))
+}
+
+/// Set image content to the system clipboard from encoded image bytes (PNG, JPEG, ...)
+pub fn set_clipboard_image(image_bytes: &[u8]) -> Result<()> {
+    let img = image::ImageReader::new(Cursor::new(image_bytes))
+        .with_guessed_format()
+        .context("Failed to read image format")?
+        .decode()
+        .context("Failed to decode image")?;
+
+    let rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+
+    let image_data = arboard::ImageData {
+        width: width as usize,
+        height: height as usize,
+        bytes: rgba.into_raw().into(),
+    };
+
+    let mut clipboard = Clipboard::new().context("Failed to access system clipboard")?;
+    set_and_hold(clipboard.set(), |set| set.image(image_data))
+}
+
+/// Apply a clipboard write, keeping ownership of the selection on Linux.
+///
+/// On X11/Wayland the clipboard content is served by the owning process, so it
+/// would vanish as soon as the CLI exits. There we block until another
+/// application takes over the clipboard (the same behavior as `xclip -loops`).
+fn set_and_hold<'a>(
+    set: arboard::Set<'a>,
+    apply: impl FnOnce(arboard::Set<'a>) -> std::result::Result<(), arboard::Error>,
+) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    let set = {
+        use arboard::SetExtLinux;
+        set.wait()
+    };
+
+    apply(set).context("Failed to set clipboard content")
+}
@@ -3,7 +3,19 @@
 //! This module attempts to load configuration from the Clipper desktop app's
 //! settings file. If the settings file is not available, it falls back to
 //! default values which can be overridden by environment variables or CLI args.
+//!
+//! The desktop app keeps tokens and trusted certificates in the OS keychain
+//! (or a user-only `secrets` file next to the settings). The CLI reads the
+//! keychain only when built with the `keychain` feature, and the file
+//! otherwise. Values still found in plaintext in settings.json, written by
+//! older versions, take precedence; the desktop app moves them out when it
+//! starts.
+//!
+//! The desktop app's server profiles are read too, so `--profile` can pick
+//! one of them instead of the server the desktop app is using.
 
+use clipper_security::secrets::{CLIENT_SECRETS_SERVICE, SECRETS_FILE_NAME};
+use clipper_security::{ClientSecrets, SecretStore, SecretString};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -33,6 +45,24 @@ struct DesktopSettings {
     /// Trusted certificate fingerprints (host -> SHA-256 fingerprint)
     #[serde(default)]
     trusted_certificates: HashMap<String, String>,
+    /// Named external servers; their tokens are in the secret store
+    #[serde(default)]
+    server_profiles: Vec<DesktopServerProfile>,
+}
+
+/// A server profile in the desktop app's settings.json
+#[derive(Debug, Deserialize, Serialize)]
+struct DesktopServerProfile {
+    name: String,
+    url: String,
+}
+
+/// A named server from the desktop app's settings
+#[derive(Debug, Clone)]
+pub struct ServerProfile {
+    pub name: String,
+    pub url: String,
+    pub token: Option<String>,
 }
 
 /// Configuration resolved from the desktop app's settings
@@ -41,10 +71,19 @@ pub struct ResolvedConfig {
     pub server_url: String,
     pub token: Option<String>,
     pub trusted_certificates: HashMap<String, String>,
+    /// Server profiles, selectable with `--profile`
+    pub profiles: Vec<ServerProfile>,
     /// Path to the config file (for saving trusted certificates)
     pub config_path: Option<PathBuf>,
 }
 
+impl ResolvedConfig {
+    /// The server profile named `name`
+    pub fn profile(&self, name: &str) -> Option<&ServerProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+}
+
 /// Get the platform-specific config directory for the Clipper desktop app
 fn get_app_config_dir() -> Option<PathBuf> {
     #[cfg(target_os = "macos")]
@@ -71,11 +110,35 @@ fn get_app_config_dir() -> Option<PathBuf> {
 /// Default port for bundled server
 const DEFAULT_BUNDLED_SERVER_PORT: u16 = 3000;
 
+/// Open the secret store belonging to the settings file at `config_path`
+fn secret_store(config_path: &Path) -> SecretStore {
+    let dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    SecretStore::new(CLIENT_SECRETS_SERVICE, dir.join(SECRETS_FILE_NAME))
+}
+
 /// Load configuration from a specific file path.
 /// Returns None if the file doesn't exist or can't be parsed.
 pub fn load_config_from_path(path: &Path) -> Option<ResolvedConfig> {
     let contents = std::fs::read_to_string(path).ok()?;
-    let settings: DesktopSettings = serde_json::from_str(&contents).ok()?;
+    let mut settings: DesktopSettings = serde_json::from_str(&contents).ok()?;
+
+    // Fill in the secrets that aren't in the settings file
+    let mut profile_tokens = Default::default();
+    match ClientSecrets::read(&secret_store(path)) {
+        Ok(secrets) => {
+            profile_tokens = secrets.profile_tokens;
+            settings.external_server_token = settings
+                .external_server_token
+                .or(secrets.external_server_token.map(SecretString::into_string));
+            settings.bundled_server_token = settings
+                .bundled_server_token
+                .or(secrets.bundled_server_token.map(SecretString::into_string));
+            if settings.trusted_certificates.is_empty() {
+                settings.trusted_certificates = secrets.trusted_certificates;
+            }
+        }
+        Err(e) => eprintln!("[warning] Failed to read secrets: {}", e),
+    }
 
     // Determine server URL and token based on server mode
     let (server_url, token) = if settings.use_bundled_server {
@@ -88,10 +151,23 @@ pub fn load_config_from_path(path: &Path) -> Option<ResolvedConfig> {
         (settings.server_address, settings.external_server_token)
     };
 
+    let profiles = settings
+        .server_profiles
+        .into_iter()
+        .map(|profile| ServerProfile {
+            token: profile_tokens
+                .remove(&profile.name)
+                .map(SecretString::into_string),
+            name: profile.name,
+            url: profile.url,
+        })
+        .collect();
+
     Some(ResolvedConfig {
         server_url,
         token,
         trusted_certificates: settings.trusted_certificates,
+        profiles,
         config_path: Some(path.to_path_buf()),
     })
 }
@@ -109,8 +185,9 @@ pub fn get_default_config_path() -> Option<PathBuf> {
     get_app_config_dir().map(|dir| dir.join(SETTINGS_FILE_NAME))
 }
 
-/// Save a trusted certificate to the config file
-/// This updates the trustedCertificates field in the settings.json
+/// Save a trusted certificate for the config file at `config_path`
+/// This adds it to the secret store, or to the trustedCertificates field in
+/// settings.json while that still holds them in plaintext
 pub fn save_trusted_certificate(
     config_path: &Path,
     host: &str,
@@ -123,17 +200,9 @@ pub fn save_trusted_certificate(
     let mut json: serde_json::Value =
         serde_json::from_str(&contents).unwrap_or_else(|_| serde_json::json!({}));
 
-    // Ensure trustedCertificates exists
-    if json.get("trustedCertificates").is_none() {
-        json["trustedCertificates"] = serde_json::json!({});
-    }
-
-    // Add the new certificate
-    json["trustedCertificates"][host] = serde_json::Value::String(fingerprint.to_string());
-
     // Ensure parent directory exists
     if let Some(parent) = config_path.parent() {
-        std::fs::create_dir_all(parent)?;
+        clipper_security::secure_create_dir_all(parent)?;
 
         // Secure the config directory and fix any incorrect permissions
         match clipper_security::secure_directory_recursive(parent, |msg| eprintln!("[warning] {}", msg))
@@ -146,9 +215,24 @@ pub fn save_trusted_certificate(
         }
     }
 
-    // Write back
+    let has_plaintext = json
+        .get("trustedCertificates")
+        .and_then(|certificates| certificates.as_object())
+        .is_some_and(|certificates| !certificates.is_empty());
+    if !has_plaintext {
+        let store = secret_store(config_path);
+        let previous = ClientSecrets::read(&store)?;
+        let mut secrets = previous.clone();
+        secrets
+            .trusted_certificates
+            .insert(host.to_string(), fingerprint.to_string());
+        return secrets.save(&store, &previous);
+    }
+
+    // Add the new certificate and write back
+    json["trustedCertificates"][host] = serde_json::Value::String(fingerprint.to_string());
     let output = serde_json::to_string_pretty(&json).map_err(std::io::Error::other)?;
-    std::fs::write(config_path, output)
+    clipper_security::secure_write(config_path, output)
 }
 
 #[cfg(test)]
@@ -160,4 +244,29 @@ mod tests {
         // Just ensure it doesn't panic
         let _ = get_app_config_dir();
     }
+
+    #[test]
+    fn test_server_profiles_are_optional() {
+        let settings: DesktopSettings =
+            serde_json::from_str(r#"{"serverAddress": "http://localhost:3000"}"#).unwrap();
+        assert!(settings.server_profiles.is_empty());
+
+        let settings: DesktopSettings = serde_json::from_str(
+            r#"{
+                "serverAddress": "https://clips.example.com",
+                "serverProfiles": [
+                    {"name": "work", "url": "https://clips.example.com"},
+                    {"name": "home", "url": "http://nas.local:3000"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = settings
+            .server_profiles
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+        assert_eq!(names, ["work", "home"]);
+        assert_eq!(settings.server_profiles[1].url, "http://nas.local:3000");
+    }
 }
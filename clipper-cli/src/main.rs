@@ -1,14 +1,25 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use clap::{Parser, Subcommand};
-use clipper_client::{fetch_server_certificate, ClipperClient, SearchFilters};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use clipper_client::{
+    fetch_server_certificate, Clip, ClipNotification, ClipperClient, ConnectionStatus,
+    ContentFormat, PagedTagResult, ReconnectConfig, SearchFilters, SortDirection, SortField,
+    SortOrder, Transform,
+};
 use std::collections::HashMap;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::mpsc;
 use url::Url;
 
+mod clipboard;
 mod config;
+mod picker;
+#[cfg(feature = "serve")]
+mod serve;
 
 #[derive(Parser)]
 #[command(name = "clipper-cli")]
@@ -26,6 +37,10 @@ struct Cli {
     #[arg(short, long, env = "CLIPPER_TOKEN")]
     token: Option<String>,
 
+    /// Server profile from the Clipper desktop app settings to use instead of its current server (see `profiles`)
+    #[arg(long, env = "CLIPPER_PROFILE")]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -49,6 +64,10 @@ enum Commands {
         /// Language identifier (e.g., "en", "zh", "rust", "python")
         #[arg(short, long)]
         language: Option<String>,
+
+        /// Mark the content as Markdown so shared pages render it
+        #[arg(long)]
+        markdown: bool,
     },
 
     /// Get a clip by ID
@@ -62,7 +81,7 @@ enum Commands {
         format: String,
     },
 
-    /// Update a clip's tags, notes, and/or language
+    /// Update a clip's tags, notes, language, color and/or icon
     #[clap(alias = "u")]
     Update {
         /// Clip ID
@@ -79,18 +98,42 @@ enum Commands {
         /// New language identifier (e.g., "en", "zh", "rust", "python")
         #[arg(short, long)]
         language: Option<String>,
+
+        /// New color label: #rgb, #rrggbb or a color name (empty string clears it)
+        #[arg(long)]
+        color: Option<String>,
+
+        /// New icon, e.g. an emoji (empty string clears it)
+        #[arg(long)]
+        icon: Option<String>,
+    },
+
+    /// Transform a clip's text into a new clip
+    Transform {
+        /// Clip ID
+        id: String,
+
+        /// Transforms to apply in order: uppercase, lowercase, trim, base64_encode,
+        /// base64_decode, json_pretty, json_minify, url_encode or url_decode
+        #[arg(required = true)]
+        transforms: Vec<String>,
     },
 
     /// Search clips
     #[clap(alias = "s")]
     Search {
-        /// Search query
+        /// Search query. Supports operators: tag:NAME, -tag:NAME, "exact phrase",
+        /// after:YYYY-MM-DD, before:YYYY-MM-DD, has:attachment, -has:attachment
         query: String,
 
         /// Filter by tags (comma-separated)
         #[arg(short, long)]
         tags: Option<String>,
 
+        /// Only clips created on this device (its `$host:` tag)
+        #[arg(long)]
+        device: Option<String>,
+
         /// Filter by start date (ISO 8601 format)
         #[arg(long)]
         start_date: Option<String>,
@@ -99,6 +142,10 @@ enum Commands {
         #[arg(long)]
         end_date: Option<String>,
 
+        /// Result order: date (newest first) or relevance (best match first)
+        #[arg(long, default_value = "date")]
+        sort: String,
+
         /// Page number (starting from 1)
         #[arg(short, long, default_value = "1")]
         page: usize,
@@ -107,7 +154,7 @@ enum Commands {
         #[arg(long, default_value = "20")]
         page_size: usize,
 
-        /// Output format: json or text (content only with IDs)
+        /// Output format: json, text (content only with IDs), or tsv (id, date, first line)
         #[arg(short = 'f', long, default_value = "json")]
         format: String,
     },
@@ -121,7 +168,20 @@ enum Commands {
 
     /// Watch for real-time notifications via WebSocket (outputs NDJSON)
     #[clap(alias = "w")]
-    Watch,
+    Watch {
+        /// Only report clips that have all of these tags (comma-separated)
+        #[arg(short, long)]
+        tags: Option<String>,
+
+        /// Only report clips whose content contains this text (case-insensitive)
+        #[arg(short, long)]
+        query: Option<String>,
+
+        /// Shell command to run for each new or updated clip; it gets the clip
+        /// JSON on stdin, and `{}` is replaced with the path of a file holding it
+        #[arg(short = 'x', long)]
+        exec: Option<String>,
+    },
 
     /// List clips
     #[clap(alias = "l")]
@@ -130,6 +190,10 @@ enum Commands {
         #[arg(short, long)]
         tags: Option<String>,
 
+        /// Only clips created on this device (its `$host:` tag)
+        #[arg(long)]
+        device: Option<String>,
+
         /// Filter by start date (ISO 8601 format)
         #[arg(long)]
         start_date: Option<String>,
@@ -138,6 +202,14 @@ enum Commands {
         #[arg(long)]
         end_date: Option<String>,
 
+        /// Sort by: created_at, updated_at, content_length or original_filename
+        #[arg(long, default_value = "created_at")]
+        sort_by: String,
+
+        /// Sort direction: asc or desc
+        #[arg(long, default_value = "desc")]
+        order: String,
+
         /// Page number (starting from 1)
         #[arg(short, long, default_value = "1")]
         page: usize,
@@ -146,7 +218,7 @@ enum Commands {
         #[arg(long, default_value = "100")]
         page_size: usize,
 
-        /// Output format: json or text (content only with IDs)
+        /// Output format: json, text (content only with IDs), or tsv (id, date, first line)
         #[arg(short = 'f', long, default_value = "json")]
         format: String,
     },
@@ -189,6 +261,10 @@ enum Commands {
         /// Output file path (default: clipper_export_<timestamp>.tar.gz)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Leave out sensitive clips (tagged $sensitive)
+        #[arg(long)]
+        exclude_sensitive: bool,
     },
 
     /// Import clips from a tar.gz archive
@@ -202,6 +278,98 @@ enum Commands {
         format: String,
     },
 
+    /// Copy a clip's content (or image attachment) to the system clipboard
+    ///
+    /// On Linux the command keeps running until another application takes
+    /// over the clipboard, since the selection is served by its owner.
+    #[clap(alias = "cp")]
+    Copy {
+        /// Clip ID
+        id: String,
+    },
+
+    /// Create a clip from the current system clipboard content
+    #[clap(alias = "p")]
+    Paste {
+        /// Tags (comma-separated)
+        #[arg(short, long)]
+        tags: Option<String>,
+
+        /// Additional notes
+        #[arg(short, long)]
+        notes: Option<String>,
+    },
+
+    /// Interactively pick a clip with fuzzy search and preview
+    Pick {
+        /// Filter by tags (comma-separated)
+        #[arg(short, long)]
+        tags: Option<String>,
+
+        /// Number of recent clips to load into the picker
+        #[arg(short, long, default_value = "200")]
+        limit: usize,
+
+        /// Copy the selected clip to the system clipboard instead of printing it
+        #[arg(long)]
+        copy: bool,
+
+        /// Output format: text (content only), id, or json
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Run an embedded Clipper server
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Server listen port
+        #[arg(short, long, default_value = "3000")]
+        port: u16,
+
+        /// Server listen address (use 0.0.0.0 to accept remote connections)
+        #[arg(long, default_value = "127.0.0.1")]
+        listen_addr: String,
+
+        /// Directory for the database and file storage [default: <data dir>/clipper-cli]
+        #[arg(short, long, env = "CLIPPER_DATA_DIR")]
+        data_dir: Option<PathBuf>,
+
+        /// Bearer token required from clients (defaults to the global --token)
+        #[arg(long = "token", env = "CLIPPER_BEARER_TOKEN")]
+        server_token: Option<String>,
+    },
+
+    /// Find Clipper servers on the local network (mDNS)
+    Discover {
+        /// Seconds to wait for servers to answer
+        #[arg(short, long, default_value = "3")]
+        timeout: u64,
+
+        /// Output format: json or text
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// List the server profiles from the Clipper desktop app settings
+    Profiles {
+        /// Output format: json or text
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Generate shell completion script
+    Completions {
+        /// Target shell
+        shell: Shell,
+    },
+
+    /// Manage tags
+    #[clap(alias = "t")]
+    Tags {
+        #[command(subcommand)]
+        command: TagsCommand,
+    },
+
     /// Search tags
     #[clap(alias = "st")]
     SearchTag {
@@ -221,6 +389,112 @@ enum Commands {
         #[arg(short = 'f', long, default_value = "text")]
         format: String,
     },
+
+    /// List devices that created clips, most recently seen first
+    Devices {
+        /// Output format: json or text
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Put a clip on the clipboard of a connected device
+    Push {
+        /// Device name, as listed by `devices`
+        device: String,
+
+        /// Clip ID
+        id: String,
+    },
+
+    /// Pair devices with their own revocable tokens
+    Pair {
+        #[command(subcommand)]
+        command: PairCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum PairCommand {
+    /// Issue a pairing code for a new device (requires the shared token)
+    New,
+
+    /// Redeem a pairing code and print this device's token
+    Claim {
+        /// Pairing code shown by another client
+        code: String,
+
+        /// Name of this device in the paired device list (defaults to the hostname)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+
+    /// List paired devices (requires the shared token)
+    #[clap(alias = "ls")]
+    List {
+        /// Output format: json or text
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Revoke a paired device's token (requires the shared token)
+    Revoke {
+        /// Paired device ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    /// List all tags
+    #[clap(alias = "ls")]
+    List {
+        /// Page number (starting from 1)
+        #[arg(short, long, default_value = "1")]
+        page: usize,
+
+        /// Number of items per page
+        #[arg(long, default_value = "100")]
+        page_size: usize,
+
+        /// Output format: json or text (tag names only)
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Search tags by prefix
+    Search {
+        /// Tag prefix to search for
+        prefix: String,
+
+        /// Page number (starting from 1)
+        #[arg(short, long, default_value = "1")]
+        page: usize,
+
+        /// Number of items per page
+        #[arg(long, default_value = "100")]
+        page_size: usize,
+
+        /// Output format: json or text (tag names only)
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
+
+    /// Rename a tag on all clips that use it
+    #[clap(alias = "mv")]
+    Rename {
+        /// Current tag name
+        from: String,
+
+        /// New tag name
+        to: String,
+    },
+
+    /// Show the number of clips using each tag
+    Stats {
+        /// Output format: json or text
+        #[arg(short = 'f', long, default_value = "text")]
+        format: String,
+    },
 }
 
 #[tokio::main]
@@ -236,6 +510,59 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Completions don't need a server connection
+    if let Commands::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "clipper-cli", &mut io::stdout());
+        return Ok(());
+    }
+
+    // The embedded server doesn't connect to another server either
+    #[cfg(feature = "serve")]
+    if let Commands::Serve {
+        port,
+        listen_addr,
+        data_dir,
+        server_token,
+    } = cli.command
+    {
+        let data_dir = data_dir
+            .or_else(|| dirs::data_dir().map(|p| p.join("clipper-cli")))
+            .context("Could not determine data directory, use --data-dir")?;
+        return serve::run(listen_addr, port, &data_dir, server_token.or(cli.token)).await;
+    }
+
+    // Discovery browses the network instead of talking to a configured server
+    if let Commands::Discover { timeout, format } = &cli.command {
+        let servers = clipper_client::discover_servers(std::time::Duration::from_secs(*timeout))
+            .await
+            .context("Failed to browse the local network")?;
+        match format.as_str() {
+            "text" => {
+                for server in &servers {
+                    let auth = if server.auth_required { "  (token required)" } else { "" };
+                    println!("{}  {}{}", server.url, server.name, auth);
+                    if let Some(fingerprint) = &server.fingerprint {
+                        println!("    fingerprint: {}", fingerprint);
+                    }
+                }
+                if servers.is_empty() {
+                    eprintln!("No Clipper servers found");
+                }
+            }
+            "json" => {
+                println!("{}", serde_json::to_string_pretty(&servers)?);
+            }
+            _ => {
+                anyhow::bail!("Invalid format. Use 'json' or 'text'");
+            }
+        }
+        return Ok(());
+    }
+
+    // Trace requests to the server if an OTLP endpoint is set
+    #[cfg(feature = "otel")]
+    let _telemetry = clipper_client::init_otlp_tracing("clipper-cli").map_err(anyhow::Error::msg)?;
+
     // Load config from specified file, or fall back to Clipper desktop app config
     // Priority: CLI arg --config > CLIPPER_CONFIG env > desktop app config
     let file_config = if let Some(config_path) = &cli.config {
@@ -244,19 +571,74 @@ async fn main() -> Result<()> {
         config::load_desktop_config()
     };
 
-    // Resolve URL: CLI arg > env var > config file > default
-    let url = cli.url.unwrap_or_else(|| {
-        file_config
+    // Listing profiles only reads the config
+    if let Commands::Profiles { format } = &cli.command {
+        let profiles = file_config
+            .as_ref()
+            .map(|c| c.profiles.as_slice())
+            .unwrap_or_default();
+        let current_url = file_config.as_ref().map(|c| c.server_url.as_str());
+        match format.as_str() {
+            "text" => {
+                for profile in profiles {
+                    let current = current_url == Some(profile.url.as_str());
+                    let marker = if current { "*" } else { " " };
+                    println!("{} {}  {}", marker, profile.name, profile.url);
+                }
+                if profiles.is_empty() {
+                    eprintln!("No server profiles; add them in the Clipper desktop app settings");
+                }
+            }
+            "json" => {
+                let profiles: Vec<_> = profiles
+                    .iter()
+                    .map(|profile| {
+                        serde_json::json!({
+                            "name": profile.name,
+                            "url": profile.url,
+                            "current": current_url == Some(profile.url.as_str()),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&profiles)?);
+            }
+            _ => {
+                anyhow::bail!("Invalid format. Use 'json' or 'text'");
+            }
+        }
+        return Ok(());
+    }
+
+    // A profile replaces the server from the config file
+    let profile = match &cli.profile {
+        Some(name) => Some(
+            file_config
+                .as_ref()
+                .and_then(|c| c.profile(name))
+                .with_context(|| {
+                    format!(
+                        "Unknown server profile '{}'; run `clipper-cli profiles` to list them",
+                        name
+                    )
+                })?,
+        ),
+        None => None,
+    };
+
+    // Resolve URL: CLI arg > env var > profile > config file > default
+    let url = cli.url.unwrap_or_else(|| match profile {
+        Some(profile) => profile.url.clone(),
+        None => file_config
             .as_ref()
             .map(|c| c.server_url.clone())
-            .unwrap_or_else(|| "http://localhost:3000".to_string())
+            .unwrap_or_else(|| "http://localhost:3000".to_string()),
     });
 
-    // Resolve token: CLI arg > env var > config file > None
-    let token = cli
-        .token
-        .clone()
-        .or_else(|| file_config.as_ref().and_then(|c| c.token.clone()));
+    // Resolve token: CLI arg > env var > profile > config file > None
+    let token = cli.token.clone().or_else(|| match profile {
+        Some(profile) => profile.token.clone(),
+        None => file_config.as_ref().and_then(|c| c.token.clone()),
+    });
 
     // Get trusted certificates from config
     let mut trusted_certificates = file_config
@@ -287,6 +669,7 @@ async fn main() -> Result<()> {
             tags,
             notes,
             language,
+            markdown,
         } => {
             let mut tags_vec: Vec<String> = tags
                 .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
@@ -297,10 +680,20 @@ async fn main() -> Result<()> {
                 tags_vec.push(format!("$host:{}", hostname.to_string_lossy()));
             }
 
-            let clip = client
-                .create_clip(content, tags_vec, notes, language)
-                .await
-                .context("Failed to create clip")?;
+            let clip = if markdown {
+                client
+                    .create_clip_with_format(
+                        content,
+                        tags_vec,
+                        notes,
+                        language,
+                        ContentFormat::Markdown,
+                    )
+                    .await
+            } else {
+                client.create_clip(content, tags_vec, notes, language).await
+            }
+            .context("Failed to create clip")?;
 
             println!("{}", serde_json::to_string_pretty(&clip)?);
         }
@@ -310,7 +703,16 @@ async fn main() -> Result<()> {
 
             match format.as_str() {
                 "text" => {
-                    println!("{}", clip.content);
+                    if clip.truncated {
+                        // The clip only holds the beginning of the text
+                        let content = client
+                            .download_file(&id)
+                            .await
+                            .context("Failed to download the full text")?;
+                        println!("{}", String::from_utf8_lossy(&content));
+                    } else {
+                        println!("{}", clip.content);
+                    }
                 }
                 "json" => {
                     println!("{}", serde_json::to_string_pretty(&clip)?);
@@ -321,26 +723,55 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Update { id, tags, notes, language } => {
+        Commands::Update {
+            id,
+            tags,
+            notes,
+            language,
+            color,
+            icon,
+        } => {
             let tags_vec = tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
 
             let clip = client
-                .update_clip(&id, tags_vec, notes, language)
+                .update_clip(&id, tags_vec, notes, language, color, icon)
                 .await
                 .context("Failed to update clip")?;
 
             println!("{}", serde_json::to_string_pretty(&clip)?);
         }
 
+        Commands::Transform { id, transforms } => {
+            let transforms = transforms
+                .iter()
+                .map(|name| parse_transform(name))
+                .collect::<Result<Vec<_>>>()?;
+
+            let clip = client
+                .transform_clip(&id, &transforms)
+                .await
+                .context("Failed to transform clip")?;
+
+            println!("{}", serde_json::to_string_pretty(&clip)?);
+        }
+
         Commands::Search {
             query,
             tags,
+            device,
             start_date,
             end_date,
+            sort,
             page,
             page_size,
             format,
         } => {
+            let sort = match sort.as_str() {
+                "date" => SortOrder::Date,
+                "relevance" => SortOrder::Relevance,
+                _ => anyhow::bail!("Invalid sort. Use 'date' or 'relevance'"),
+            };
+
             let tags_vec = tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
 
             let start_date_parsed = start_date
@@ -363,6 +794,9 @@ async fn main() -> Result<()> {
                 start_date: start_date_parsed,
                 end_date: end_date_parsed,
                 tags: tags_vec,
+                source_device: device,
+                sort: Some(sort),
+                ..Default::default()
             };
 
             let result = client
@@ -380,11 +814,16 @@ async fn main() -> Result<()> {
                         result.page, result.total_pages, result.total
                     );
                 }
+                "tsv" => {
+                    for clip in &result.items {
+                        println!("{}", format_tsv_row(clip));
+                    }
+                }
                 "json" => {
                     println!("{}", serde_json::to_string_pretty(&result)?);
                 }
                 _ => {
-                    anyhow::bail!("Invalid format. Use 'json' or 'text'");
+                    anyhow::bail!("Invalid format. Use 'json', 'text' or 'tsv'");
                 }
             }
         }
@@ -398,29 +837,105 @@ async fn main() -> Result<()> {
             println!("Clip {} deleted successfully", id);
         }
 
-        Commands::Watch => {
+        Commands::Watch { tags, query, exec } => {
+            let tags_vec: Vec<String> = tags
+                .map(|t| {
+                    t.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let query = query.filter(|q| !q.trim().is_empty());
+            let filtered = !tags_vec.is_empty() || query.is_some();
+
             let (tx, mut rx) = mpsc::unbounded_channel();
 
-            let _handle = client
-                .subscribe_notifications(tx)
-                .await
-                .context("Failed to connect to WebSocket")?;
+            // Reconnect automatically (e.g. across server restarts); status goes to stderr
+            // so stdout stays a clean NDJSON stream
+            let reconnecting = Arc::new(AtomicBool::new(false));
+            let _handle = client.subscribe_notifications_with_reconnect(
+                tx,
+                ReconnectConfig::default(),
+                move |status| match status {
+                    ConnectionStatus::Connected => {
+                        if reconnecting.swap(false, Ordering::Relaxed) {
+                            eprintln!("Reconnected to server");
+                        }
+                    }
+                    ConnectionStatus::Disconnected { error } => {
+                        reconnecting.store(true, Ordering::Relaxed);
+                        match error {
+                            Some(e) => eprintln!("Disconnected from server: {}", e),
+                            None => eprintln!("Disconnected from server"),
+                        }
+                    }
+                    ConnectionStatus::Reconnecting { attempt, delay } => {
+                        eprintln!(
+                            "Reconnecting in {:.1}s (attempt {})",
+                            delay.as_secs_f64(),
+                            attempt
+                        );
+                    }
+                    ConnectionStatus::RecoveryFailed { error } => {
+                        eprintln!("Failed to fetch clips missed while disconnected: {}", error);
+                    }
+                    ConnectionStatus::Connecting => {}
+                },
+            );
 
             // Receive notifications and output as NDJSON (one JSON object per line)
             while let Some(notification) = rx.recv().await {
+                // Filters and hooks need the full clip, which only exists for new/updated clips
+                let clip = match &notification {
+                    ClipNotification::NewClip { id, .. } | ClipNotification::UpdatedClip { id, .. }
+                        if filtered || exec.is_some() =>
+                    {
+                        match client.get_clip(id).await {
+                            Ok(clip) => Some(clip),
+                            Err(e) => {
+                                eprintln!("Failed to get clip {}: {}", id, e);
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+
+                if filtered
+                    && !clip
+                        .as_ref()
+                        .is_some_and(|c| clip_matches_filter(c, &tags_vec, query.as_deref()))
+                {
+                    continue;
+                }
+
                 let json = serde_json::to_string(&notification)?;
                 println!("{}", json);
+
+                if let (Some(command), Some(clip)) = (&exec, &clip) {
+                    io::stdout().flush()?;
+                    // A failing hook shouldn't stop watching
+                    if let Err(e) = run_exec_hook(command, &notification, clip).await {
+                        eprintln!("Exec command failed for clip {}: {}", clip.id, e);
+                    }
+                }
             }
         }
 
         Commands::List {
             tags,
+            device,
             start_date,
             end_date,
+            sort_by,
+            order,
             page,
             page_size,
             format,
         } => {
+            let (sort_by, order) = parse_sort_by(&sort_by, &order)?;
+
             let tags_vec = tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
 
             let start_date_parsed = start_date
@@ -443,6 +958,10 @@ async fn main() -> Result<()> {
                 start_date: start_date_parsed,
                 end_date: end_date_parsed,
                 tags: tags_vec,
+                source_device: device,
+                sort_by: Some(sort_by),
+                order: Some(order),
+                ..Default::default()
             };
 
             let result = client
@@ -460,11 +979,16 @@ async fn main() -> Result<()> {
                         result.page, result.total_pages, result.total
                     );
                 }
+                "tsv" => {
+                    for clip in &result.items {
+                        println!("{}", format_tsv_row(clip));
+                    }
+                }
                 "json" => {
                     println!("{}", serde_json::to_string_pretty(&result)?);
                 }
                 _ => {
-                    anyhow::bail!("Invalid format. Use 'json' or 'text'");
+                    anyhow::bail!("Invalid format. Use 'json', 'text' or 'tsv'");
                 }
             }
         }
@@ -529,7 +1053,10 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Export { output } => {
+        Commands::Export {
+            output,
+            exclude_sensitive,
+        } => {
             // Check if server supports export/import
             let server_info = client
                 .get_server_info()
@@ -547,10 +1074,12 @@ async fn main() -> Result<()> {
 
             eprintln!("Exporting clips to {}...", output_path.display());
 
-            let bytes_written = client
-                .export_to_file(&output_path)
-                .await
-                .context("Failed to export clips")?;
+            let bytes_written = if exclude_sensitive {
+                client.export_non_sensitive_to_file(&output_path).await
+            } else {
+                client.export_to_file(&output_path).await
+            }
+            .context("Failed to export clips")?;
 
             let size_mb = bytes_written as f64 / (1024.0 * 1024.0);
             if size_mb >= 1.0 {
@@ -599,25 +1128,206 @@ async fn main() -> Result<()> {
             }
         }
 
+        Commands::Copy { id } => {
+            let clip = client.get_clip(&id).await.context("Failed to get clip")?;
+
+            let is_image = clip.file_attachment.is_some()
+                && clip
+                    .original_filename
+                    .as_deref()
+                    .is_some_and(is_image_filename);
+
+            if is_image {
+                let bytes = client
+                    .download_file(&id)
+                    .await
+                    .context("Failed to download attachment")?;
+                eprintln!("Copied image from clip {} to clipboard", id);
+                clipboard::set_clipboard_image(&bytes)?;
+            } else {
+                eprintln!("Copied clip {} to clipboard", id);
+                clipboard::set_clipboard_text(&clip.content)?;
+            }
+        }
+
+        Commands::Paste { tags, notes } => {
+            let mut tags_vec: Vec<String> = tags
+                .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default();
+
+            // Add host tag automatically
+            if let Ok(hostname) = hostname::get() {
+                tags_vec.push(format!("$host:{}", hostname.to_string_lossy()));
+            }
+
+            match clipboard::get_clipboard_content()? {
+                clipboard::ClipboardContent::Text(content) => {
+                    let clip = client
+                        .create_clip(content, tags_vec, notes, None)
+                        .await
+                        .context("Failed to create clip")?;
+
+                    println!("{}", serde_json::to_string_pretty(&clip)?);
+                }
+                clipboard::ClipboardContent::Image(png_bytes) => {
+                    tags_vec.push("$image".to_string());
+                    let filename = format!("screenshot-{}.png", Utc::now().format("%Y-%m-%d-%H-%M-%S"));
+
+                    let clip = client
+                        .upload_file_bytes(png_bytes, filename, tags_vec, notes)
+                        .await
+                        .context("Failed to upload clipboard image")?;
+
+                    println!("{}", serde_json::to_string_pretty(&clip)?);
+                }
+                clipboard::ClipboardContent::Files(paths) => {
+                    tags_vec.push("$file".to_string());
+
+                    for path in paths {
+                        if path.is_dir() {
+                            eprintln!("Skipping directory: {}", path.display());
+                            continue;
+                        }
+
+                        let filename = path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| "file".to_string());
+
+                        let bytes = tokio::fs::read(&path)
+                            .await
+                            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+                        let clip = client
+                            .upload_file_bytes_with_content(
+                                bytes,
+                                filename,
+                                tags_vec.clone(),
+                                notes.clone(),
+                                Some(path.to_string_lossy().to_string()),
+                            )
+                            .await
+                            .with_context(|| format!("Failed to upload file: {}", path.display()))?;
+
+                        println!("{}", serde_json::to_string_pretty(&clip)?);
+                    }
+                }
+                clipboard::ClipboardContent::Empty => {
+                    anyhow::bail!("Clipboard is empty");
+                }
+            }
+        }
+
+        Commands::Pick {
+            tags,
+            limit,
+            copy,
+            format,
+        } => {
+            if !matches!(format.as_str(), "text" | "id" | "json") {
+                anyhow::bail!("Invalid format. Use 'text', 'id' or 'json'");
+            }
+
+            let tags_vec = tags.map(|t| t.split(',').map(|s| s.trim().to_string()).collect());
+            let filters = SearchFilters {
+                tags: tags_vec,
+                ..Default::default()
+            };
+
+            let result = client
+                .list_clips(filters, 1, limit)
+                .await
+                .context("Failed to list clips")?;
+
+            let Some(clip) = picker::pick(&result.items)? else {
+                // Cancelled by the user
+                std::process::exit(130);
+            };
+
+            if copy {
+                clipboard::set_clipboard_text(&clip.content)?;
+            } else {
+                match format.as_str() {
+                    "id" => println!("{}", clip.id),
+                    "json" => println!("{}", serde_json::to_string_pretty(&clip)?),
+                    _ => println!("{}", clip.content),
+                }
+            }
+        }
+
+        Commands::Completions { .. } | Commands::Discover { .. } | Commands::Profiles { .. } => {
+            unreachable!("handled before connecting")
+        }
+
+        #[cfg(feature = "serve")]
+        Commands::Serve { .. } => unreachable!("handled before connecting"),
+
+        Commands::Tags { command } => {
+            ensure_tags_supported(&client).await?;
+
+            match command {
+                TagsCommand::List {
+                    page,
+                    page_size,
+                    format,
+                } => {
+                    let result = client
+                        .list_tags(page, page_size)
+                        .await
+                        .context("Failed to list tags")?;
+                    print_tag_page(&result, &format)?;
+                }
+                TagsCommand::Search {
+                    prefix,
+                    page,
+                    page_size,
+                    format,
+                } => {
+                    let result = client
+                        .search_tags(&prefix, page, page_size)
+                        .await
+                        .context("Failed to search tags")?;
+                    print_tag_page(&result, &format)?;
+                }
+                TagsCommand::Rename { from, to } => {
+                    let result = client
+                        .rename_tag(&from, &to)
+                        .await
+                        .context("Failed to rename tag")?;
+                    eprintln!(
+                        "Renamed tag '{}' to '{}' on {} clip(s)",
+                        from, to, result.updated_count
+                    );
+                }
+                TagsCommand::Stats { format } => {
+                    let stats = client
+                        .tag_stats()
+                        .await
+                        .context("Failed to get tag stats")?;
+                    match format.as_str() {
+                        "text" => {
+                            for tag in &stats {
+                                println!("{:>6}  {}", tag.count, tag.text);
+                            }
+                        }
+                        "json" => {
+                            println!("{}", serde_json::to_string_pretty(&stats)?);
+                        }
+                        _ => {
+                            anyhow::bail!("Invalid format. Use 'json' or 'text'");
+                        }
+                    }
+                }
+            }
+        }
+
         Commands::SearchTag {
             query,
             page,
             page_size,
             format,
         } => {
-            // Check if server supports tag search (requires index_version >= 2)
-            let server_info = client
-                .get_server_info()
-                .await
-                .context("Failed to get server info")?;
-            // If index_version is absent (default 0 from serde), assume version 1 for older servers
-            let index_version = if server_info.index_version == 0 { 1 } else { server_info.index_version };
-            if index_version < 2 {
-                anyhow::bail!(
-                    "Server does not support tag search (requires index version 2+, server has version {})",
-                    index_version
-                );
-            }
+            ensure_tags_supported(&client).await?;
 
             let result = if query.is_empty() {
                 client
@@ -631,29 +1341,146 @@ async fn main() -> Result<()> {
                     .context("Failed to search tags")?
             };
 
+            print_tag_page(&result, &format)?;
+        }
+
+        Commands::Pair { command } => match command {
+            PairCommand::New => {
+                let code = client
+                    .create_pairing_code()
+                    .await
+                    .context("Failed to create pairing code")?;
+                println!("{}", code.code);
+                eprintln!(
+                    "Redeem with `clipper-cli pair claim {}` before {}",
+                    code.code, code.expires_at
+                );
+            }
+            PairCommand::Claim { code, name } => {
+                let name = match name {
+                    Some(name) => name,
+                    None => hostname::get()
+                        .context("Failed to get hostname, use --name")?
+                        .to_string_lossy()
+                        .into_owned(),
+                };
+                let paired = client
+                    .claim_pairing_code(&code, &name)
+                    .await
+                    .context("Failed to claim pairing code")?;
+                println!("{}", paired.token);
+                eprintln!(
+                    "Paired as '{}' ({}). Use the token above with --token or CLIPPER_TOKEN.",
+                    paired.device.name, paired.device.id
+                );
+            }
+            PairCommand::List { format } => {
+                let devices = client
+                    .list_paired_devices()
+                    .await
+                    .context("Failed to list paired devices")?;
+                match format.as_str() {
+                    "text" => {
+                        for device in &devices {
+                            println!(
+                                "{}  {}  {}",
+                                device.id,
+                                device.last_used_at.as_deref().unwrap_or("never used"),
+                                device.name
+                            );
+                        }
+                    }
+                    "json" => {
+                        println!("{}", serde_json::to_string_pretty(&devices)?);
+                    }
+                    _ => {
+                        anyhow::bail!("Invalid format. Use 'json' or 'text'");
+                    }
+                }
+            }
+            PairCommand::Revoke { id } => {
+                client
+                    .revoke_paired_device(&id)
+                    .await
+                    .context("Failed to revoke device")?;
+                eprintln!("Paired device {} revoked", id);
+            }
+        },
+
+        Commands::Devices { format } => {
+            let devices = client
+                .list_devices()
+                .await
+                .context("Failed to list devices")?;
             match format.as_str() {
                 "text" => {
-                    for tag in &result.items {
-                        println!("{}", tag.text);
+                    for device in &devices {
+                        println!(
+                            "{:>6}  {}  {}",
+                            device.clip_count, device.last_seen, device.name
+                        );
                     }
-                    eprintln!(
-                        "Page {} of {} (Total: {} tags)",
-                        result.page, result.total_pages, result.total
-                    );
                 }
                 "json" => {
-                    println!("{}", serde_json::to_string_pretty(&result)?);
+                    println!("{}", serde_json::to_string_pretty(&devices)?);
                 }
                 _ => {
                     anyhow::bail!("Invalid format. Use 'json' or 'text'");
                 }
             }
         }
+
+        Commands::Push { device, id } => {
+            client
+                .push_to_device_clipboard(&device, &id)
+                .await
+                .context("Failed to push clip")?;
+            eprintln!("Clip {} pushed to {}", id, device);
+        }
     }
 
     Ok(())
 }
 
+/// Check if the server supports the tags API (requires index_version >= 2)
+async fn ensure_tags_supported(client: &ClipperClient) -> Result<()> {
+    let server_info = client
+        .get_server_info()
+        .await
+        .context("Failed to get server info")?;
+    // If index_version is absent (default 0 from serde), assume version 1 for older servers
+    let index_version = if server_info.index_version == 0 { 1 } else { server_info.index_version };
+    if index_version < 2 {
+        anyhow::bail!(
+            "Server does not support tags (requires index version 2+, server has version {})",
+            index_version
+        );
+    }
+    Ok(())
+}
+
+/// Print a page of tags in the given format (text or json)
+fn print_tag_page(result: &PagedTagResult, format: &str) -> Result<()> {
+    match format {
+        "text" => {
+            for tag in &result.items {
+                println!("{}", tag.text);
+            }
+            eprintln!(
+                "Page {} of {} (Total: {} tags)",
+                result.page, result.total_pages, result.total
+            );
+        }
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(result)?);
+        }
+        _ => {
+            anyhow::bail!("Invalid format. Use 'json' or 'text'");
+        }
+    }
+    Ok(())
+}
+
 /// Check if the server's certificate is trusted, and prompt user to trust if not.
 /// Returns the updated trusted certificates map.
 async fn check_and_trust_certificate(
@@ -760,8 +1587,304 @@ async fn check_and_trust_certificate(
     Ok(trusted_certificates)
 }
 
+/// Get the first non-empty line of the content, trimmed
+fn first_line(content: &str) -> &str {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("")
+}
+
+/// Format a clip as a single tab-separated line: id, creation date, first line of content.
+/// Tabs inside the content are replaced so the column count is always stable.
+fn format_tsv_row(clip: &Clip) -> String {
+    format!(
+        "{}\t{}\t{}",
+        clip.id,
+        clip.created_at,
+        first_line(&clip.content).replace('\t', " ")
+    )
+}
+
+/// Check whether a clip has all of the given tags and contains the query text
+/// Parse the `--sort-by` and `--order` arguments
+fn parse_sort_by(sort_by: &str, order: &str) -> Result<(SortField, SortDirection)> {
+    let sort_by = match sort_by {
+        "created_at" => SortField::CreatedAt,
+        "updated_at" => SortField::UpdatedAt,
+        "content_length" => SortField::ContentLength,
+        "original_filename" => SortField::OriginalFilename,
+        _ => anyhow::bail!(
+            "Invalid sort field. Use 'created_at', 'updated_at', 'content_length' or 'original_filename'"
+        ),
+    };
+    let order = match order {
+        "asc" => SortDirection::Asc,
+        "desc" => SortDirection::Desc,
+        _ => anyhow::bail!("Invalid order. Use 'asc' or 'desc'"),
+    };
+    Ok((sort_by, order))
+}
+
+/// Parse a transform name such as `base64_encode`
+fn parse_transform(name: &str) -> Result<Transform> {
+    Ok(match name {
+        "uppercase" => Transform::Uppercase,
+        "lowercase" => Transform::Lowercase,
+        "trim" => Transform::Trim,
+        "base64_encode" => Transform::Base64Encode,
+        "base64_decode" => Transform::Base64Decode,
+        "json_pretty" => Transform::JsonPretty,
+        "json_minify" => Transform::JsonMinify,
+        "url_encode" => Transform::UrlEncode,
+        "url_decode" => Transform::UrlDecode,
+        _ => anyhow::bail!(
+            "Invalid transform '{}'. Use uppercase, lowercase, trim, base64_encode, base64_decode, json_pretty, json_minify, url_encode or url_decode",
+            name
+        ),
+    })
+}
+
+fn clip_matches_filter(clip: &Clip, tags: &[String], query: Option<&str>) -> bool {
+    if !tags.iter().all(|tag| clip.tags.contains(tag)) {
+        return false;
+    }
+
+    match query {
+        Some(q) => clip.content.to_lowercase().contains(&q.to_lowercase()),
+        None => true,
+    }
+}
+
+/// Quote a path so it is passed as a single argument by the platform shell.
+/// Only for paths this program makes up: `cmd.exe` doesn't treat `""` as an
+/// escape, so quoting arbitrary text isn't safe on Windows.
+fn shell_quote(s: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// Run a `watch --exec` command for a clip.
+///
+/// The clip JSON never becomes part of the command line, where the shell
+/// would interpret it. The command reads it from stdin, or from the file
+/// whose path replaces `{}`, and gets the event type and clip ID as
+/// `CLIPPER_EVENT` and `CLIPPER_CLIP_ID`.
+async fn run_exec_hook(command: &str, notification: &ClipNotification, clip: &Clip) -> Result<()> {
+    let event = match notification {
+        ClipNotification::NewClip { .. } => "new_clip",
+        ClipNotification::UpdatedClip { .. } => "updated_clip",
+        ClipNotification::DeletedClip { .. } => "deleted_clip",
+        ClipNotification::ClipsCleanedUp { .. } => "clips_cleaned_up",
+        ClipNotification::SetClipboard { .. } => "set_clipboard",
+    };
+    let json = serde_json::to_string(clip)?;
+
+    let clip_file = command
+        .contains("{}")
+        .then(|| write_clip_file(&json))
+        .transpose()?;
+    let command = match &clip_file {
+        Some(path) => command.replace("{}", &shell_quote(&path.to_string_lossy())),
+        None => command.to_string(),
+    };
+    let result = run_shell_command(&command, event, &clip.id, &json).await;
+    if let Some(path) = clip_file {
+        let _ = std::fs::remove_file(path);
+    }
+
+    let status = result?;
+    if !status.success() {
+        eprintln!("Exec command exited with {} for clip {}", status, clip.id);
+    }
+
+    Ok(())
+}
+
+/// Write clip JSON for an exec command to a new file only the current user
+/// can read
+fn write_clip_file(json: &str) -> Result<PathBuf> {
+    static NEXT_FILE: AtomicU64 = AtomicU64::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "clipper-clip-{}-{}.json",
+        std::process::id(),
+        NEXT_FILE.fetch_add(1, Ordering::Relaxed)
+    ));
+    clipper_security::secure_write(&path, json)
+        .with_context(|| format!("Failed to write clip to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Run `command` through the platform shell with `json` on its stdin
+async fn run_shell_command(
+    command: &str,
+    event: &str,
+    clip_id: &str,
+    json: &str,
+) -> Result<std::process::ExitStatus> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+
+    let mut child = cmd
+        .arg(command)
+        .env("CLIPPER_EVENT", event)
+        .env("CLIPPER_CLIP_ID", clip_id)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to run exec command")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Commands that don't read stdin close it early
+        match stdin.write_all(json.as_bytes()).await {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+                eprintln!("Failed to pass clip to exec command: {}", e);
+            }
+            _ => {}
+        }
+    }
+
+    child.wait().await.context("Failed to run exec command")
+}
+
+/// Check whether a filename refers to an image format that can be placed on the clipboard
+fn is_image_filename(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".webp", ".bmp"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
 /// Format fingerprint in a shorter display format (first 16 bytes as base64-like)
 fn format_fingerprint_short(fingerprint: &str) -> String {
     // Just show the fingerprint in a condensed format
     fingerprint.replace(":", "").to_lowercase()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip_with_content(content: &str) -> Clip {
+        Clip {
+            id: "abc123".to_string(),
+            content: content.to_string(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: None,
+            tags: vec![],
+            additional_notes: None,
+            file_attachment: None,
+            original_filename: None,
+            language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: None,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
+            highlighted_content: None,
+        }
+    }
+
+    #[test]
+    fn test_format_tsv_row_uses_first_non_empty_line() {
+        let clip = clip_with_content("\n  \n  hello world \nsecond line");
+        assert_eq!(format_tsv_row(&clip), "abc123\t2025-01-01T00:00:00Z\thello world");
+    }
+
+    #[test]
+    fn test_format_tsv_row_replaces_tabs() {
+        let clip = clip_with_content("a\tb");
+        assert_eq!(format_tsv_row(&clip), "abc123\t2025-01-01T00:00:00Z\ta b");
+    }
+
+    #[test]
+    fn test_parse_sort_by() {
+        assert_eq!(
+            parse_sort_by("content_length", "asc").unwrap(),
+            (SortField::ContentLength, SortDirection::Asc)
+        );
+        assert!(parse_sort_by("size", "asc").is_err());
+        assert!(parse_sort_by("created_at", "up").is_err());
+    }
+
+    #[test]
+    fn test_parse_transform() {
+        assert_eq!(
+            parse_transform("base64_encode").unwrap(),
+            Transform::Base64Encode
+        );
+        assert!(parse_transform("rot13").is_err());
+    }
+
+    #[test]
+    fn test_clip_matches_filter() {
+        let mut clip = clip_with_content("See https://Example.com for details");
+        clip.tags = vec!["$file".to_string(), "work".to_string()];
+
+        assert!(clip_matches_filter(&clip, &[], None));
+        assert!(clip_matches_filter(&clip, &["$file".to_string()], Some("example.com")));
+        assert!(!clip_matches_filter(&clip, &["$file".to_string(), "home".to_string()], None));
+        assert!(!clip_matches_filter(&clip, &[], Some("missing")));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[tokio::test]
+    async fn test_exec_hook_does_not_run_clip_content() {
+        let dir = std::env::temp_dir().join(format!("clipper_cli_test_exec_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let pwned = dir.join("pwned");
+        let content = format!(
+            "\"&echo pwned&\" \"&echo pwned > {0}&\" '; echo pwned > {0}; ' $(echo pwned > {0})",
+            pwned.display()
+        );
+        let clip = clip_with_content(&content);
+        let notification = ClipNotification::NewClip {
+            id: clip.id.clone(),
+            content: clip.content.clone(),
+            tags: vec![],
+            updated_at: None,
+        };
+
+        let from_file = shell_quote(&dir.join("from_file.json").to_string_lossy());
+        let from_stdin = shell_quote(&dir.join("from_stdin.json").to_string_lossy());
+        let command = if cfg!(windows) {
+            format!("type {{}} > {} && more > {}", from_file, from_stdin)
+        } else {
+            format!("cat {{}} > {} && cat > {}", from_file, from_stdin)
+        };
+        run_exec_hook(&command, &notification, &clip).await.unwrap();
+
+        assert!(!pwned.exists());
+        for name in ["from_file.json", "from_stdin.json"] {
+            let json = std::fs::read_to_string(dir.join(name)).unwrap();
+            let received: Clip = serde_json::from_str(json.trim()).unwrap();
+            assert_eq!(received.content, content);
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
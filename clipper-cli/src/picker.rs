@@ -0,0 +1,202 @@
+//! Interactive fuzzy picker for clipper-cli
+//!
+//! Renders a ratatui UI with a query line, a filtered list of clips and a
+//! preview pane. The UI is drawn on stderr so that stdout stays clean for the
+//! selected clip, which allows `$(clipper-cli pick)` in shell scripts the same
+//! way fzf is used.
+
+use anyhow::Result;
+use clipper_client::Clip;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use std::io::{self, Stderr};
+
+/// State of the picker UI
+struct Picker<'a> {
+    clips: &'a [Clip],
+    query: String,
+    /// Indices into `clips` that match the current query, best match first
+    matches: Vec<usize>,
+    list_state: ListState,
+    matcher: SkimMatcherV2,
+}
+
+impl<'a> Picker<'a> {
+    fn new(clips: &'a [Clip]) -> Self {
+        let mut picker = Self {
+            clips,
+            query: String::new(),
+            matches: Vec::new(),
+            list_state: ListState::default(),
+            matcher: SkimMatcherV2::default(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// Recompute the matching clips for the current query
+    fn update_matches(&mut self) {
+        if self.query.is_empty() {
+            // Keep the server order (most recent first) when there is no query
+            self.matches = (0..self.clips.len()).collect();
+        } else {
+            let mut scored: Vec<(i64, usize)> = self
+                .clips
+                .iter()
+                .enumerate()
+                .filter_map(|(i, clip)| {
+                    let haystack = format!("{} {}", clip.content, clip.tags.join(" "));
+                    self.matcher
+                        .fuzzy_match(&haystack, &self.query)
+                        .map(|score| (score, i))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        }
+
+        self.list_state
+            .select(if self.matches.is_empty() { None } else { Some(0) });
+    }
+
+    fn selected(&self) -> Option<&'a Clip> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.matches.get(i))
+            .map(|&i| &self.clips[i])
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let last = self.matches.len() as isize - 1;
+        self.list_state
+            .select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame) {
+        let [query_area, body_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body_area);
+
+        let query = Paragraph::new(format!("> {}", self.query)).block(Block::bordered().title(
+            format!(" Search ({}/{}) ", self.matches.len(), self.clips.len()),
+        ));
+        frame.render_widget(query, query_area);
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&i| ListItem::new(crate::first_line(&self.clips[i].content).to_string()))
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Clips "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let preview_lines: Vec<Line> = match self.selected() {
+            Some(clip) => {
+                let mut lines = vec![
+                    Line::styled(
+                        format!("{}  {}", clip.id, clip.created_at),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ),
+                    Line::styled(
+                        clip.tags.join(", "),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ),
+                    Line::default(),
+                ];
+                lines.extend(clip.content.lines().map(|l| Line::raw(l.to_string())));
+                lines
+            }
+            None => Vec::new(),
+        };
+        let preview = Paragraph::new(preview_lines)
+            .block(Block::bordered().title(" Preview "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(preview, preview_area);
+    }
+
+    /// Handle a key press, returning `Some` when the picker should exit
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Option<&'a Clip>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(None),
+            KeyCode::Char('c') if ctrl => return Some(None),
+            KeyCode::Enter => return Some(self.selected()),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('p') if ctrl => self.move_selection(-1),
+            KeyCode::Char('n') if ctrl => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.update_matches();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.update_matches();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.update_matches();
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Run the interactive picker over the given clips.
+/// Returns the selected clip, or None if the user cancelled.
+pub fn pick(clips: &[Clip]) -> Result<Option<Clip>> {
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    if let Err(e) = execute!(stderr, EnterAlternateScreen) {
+        let _ = disable_raw_mode();
+        return Err(e.into());
+    }
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    let result = run(&mut terminal, clips);
+
+    // Always restore the terminal, even if the UI loop failed
+    let _ = disable_raw_mode();
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+    let _ = terminal.show_cursor();
+
+    result
+}
+
+fn run(terminal: &mut Terminal<CrosstermBackend<Stderr>>, clips: &[Clip]) -> Result<Option<Clip>> {
+    let mut picker = Picker::new(clips);
+
+    loop {
+        terminal.draw(|frame| picker.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Some(selection) = picker.handle_key(key)
+        {
+            return Ok(selection.cloned());
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Embedded server mode for clipper-cli
+//!
+//! Runs the same REST API and WebSocket endpoints as `clipper-server` (plain
+//! HTTP, without the web UI or TLS) so a single binary can act as both client
+//! and personal server on a headless machine.
+
+use anyhow::{Context, Result};
+use clipper_security::SecretString;
+use clipper_server::ServerConfig;
+use std::path::Path;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Run the embedded server until Ctrl+C is received
+pub async fn run(
+    listen_addr: String,
+    port: u16,
+    data_dir: &Path,
+    token: Option<String>,
+) -> Result<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "clipper_server=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    let db_path = data_dir.join("db");
+    let storage_path = data_dir.join("storage");
+    std::fs::create_dir_all(&db_path).context("Failed to create database directory")?;
+    std::fs::create_dir_all(&storage_path).context("Failed to create storage directory")?;
+
+    let mut config = ServerConfig::default();
+    config.database.path = db_path.to_string_lossy().into_owned();
+    config.storage.path = storage_path.to_string_lossy().into_owned();
+    config.server.listen_addr = listen_addr;
+    config.server.port = port;
+    config.auth.bearer_token = token.filter(|t| !t.is_empty()).map(SecretString::new);
+
+    eprintln!("Data directory: {}", data_dir.display());
+
+    clipper_server::serve::run_http(config, async {
+        let _ = tokio::signal::ctrl_c().await;
+        tracing::info!("Received Ctrl+C, starting graceful shutdown");
+    })
+    .await
+    .context("Server failed")
+}
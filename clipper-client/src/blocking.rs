@@ -0,0 +1,601 @@
+//! Synchronous wrapper around [`ClipperClient`](crate::ClipperClient)
+//!
+//! Enabled with the `blocking` feature. Each blocking client owns a small
+//! Tokio runtime and drives the async client on it, so it can be used from
+//! plain threads without setting up a runtime.
+//!
+//! Like `reqwest::blocking`, these methods must not be called from within an
+//! async runtime; use the async [`ClipperClient`](crate::ClipperClient) there.
+
+use crate::builder::ClientBuilder;
+use crate::error::Result;
+use crate::models::{
+    ActivityStats, CleanupResult, Clip, ClipLink, ClipNotification, Collection, ContentFormat,
+    Device, ImportResult, PagedResult, PagedTagResult, PairedDevice, PairedDeviceToken,
+    PairingCode, PushToClipboardResult, RenameTagResult, SearchFilters, ServerInfo, ShortUrl,
+    SplitSeparator, TagStats, Template, Transform, Visibility,
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+/// Blocking client for interacting with the Clipper server
+///
+/// # Example
+/// ```no_run
+/// use clipper_client::SearchFilters;
+/// use clipper_client::blocking::ClipperClient;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClipperClient::new("http://localhost:3000");
+/// let clip = client.create_clip("Hello".to_string(), vec![], None, None)?;
+/// let page = client.list_clips(SearchFilters::new(), 1, 20)?;
+/// println!("{} of {} clips", clip.id, page.total);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClipperClient {
+    inner: crate::ClipperClient,
+    runtime: Arc<Runtime>,
+}
+
+impl ClipperClient {
+    /// Create a new blocking Clipper client
+    ///
+    /// # Panics
+    /// Panics if the internal runtime cannot be created.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::from_async(crate::ClipperClient::new(base_url))
+    }
+
+    /// Create a new blocking Clipper client with Bearer token authentication
+    ///
+    /// # Panics
+    /// Panics if the internal runtime cannot be created.
+    pub fn new_with_token(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self::from_async(crate::ClipperClient::new_with_token(base_url, token))
+    }
+
+    /// Create a new blocking Clipper client with trusted certificate fingerprints
+    ///
+    /// # Panics
+    /// Panics if the internal runtime cannot be created.
+    pub fn new_with_trusted_certs(
+        base_url: impl Into<String>,
+        token: Option<String>,
+        trusted_fingerprints: HashMap<String, String>,
+    ) -> Self {
+        Self::from_async(crate::ClipperClient::new_with_trusted_certs(
+            base_url,
+            token,
+            trusted_fingerprints,
+        ))
+    }
+
+    /// Wrap an existing async client
+    ///
+    /// # Panics
+    /// Panics if the internal runtime cannot be created.
+    pub fn from_async(client: crate::ClipperClient) -> Self {
+        Self::try_from_async(client).expect("Failed to create runtime for blocking client")
+    }
+
+    pub(crate) fn try_from_async(client: crate::ClipperClient) -> Result<Self> {
+        // A dedicated worker keeps pooled connections serviced between calls
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("clipper-client-blocking")
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Get the wrapped async client
+    pub fn inner(&self) -> &crate::ClipperClient {
+        &self.inner
+    }
+
+    /// Get the base URL of the server
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// Set the Bearer token for authentication
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.inner.set_token(token);
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Get server version and configuration information
+    pub fn get_server_info(&self) -> Result<ServerInfo> {
+        self.block_on(self.inner.get_server_info())
+    }
+
+    /// Create a new clip
+    pub fn create_clip(
+        &self,
+        content: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+    ) -> Result<Clip> {
+        self.block_on(
+            self.inner
+                .create_clip(content, tags, additional_notes, language),
+        )
+    }
+
+    /// Create a new clip with a content format hint
+    pub fn create_clip_with_format(
+        &self,
+        content: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+        content_format: ContentFormat,
+    ) -> Result<Clip> {
+        self.block_on(self.inner.create_clip_with_format(
+            content,
+            tags,
+            additional_notes,
+            language,
+            content_format,
+        ))
+    }
+
+    /// Upload a file from disk to create a clip
+    pub fn upload_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+    ) -> Result<Clip> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+        let bytes = std::fs::read(path)?;
+        self.upload_file_bytes(bytes, filename, tags, additional_notes)
+    }
+
+    /// Upload file bytes to create a clip
+    pub fn upload_file_bytes(
+        &self,
+        bytes: Vec<u8>,
+        filename: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+    ) -> Result<Clip> {
+        self.block_on(
+            self.inner
+                .upload_file_bytes(bytes, filename, tags, additional_notes),
+        )
+    }
+
+    /// Upload file bytes to create a clip with optional content override
+    pub fn upload_file_bytes_with_content(
+        &self,
+        bytes: Vec<u8>,
+        filename: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        content: Option<String>,
+    ) -> Result<Clip> {
+        self.block_on(self.inner.upload_file_bytes_with_content(
+            bytes,
+            filename,
+            tags,
+            additional_notes,
+            content,
+        ))
+    }
+
+    /// Get a clip by ID
+    pub fn get_clip(&self, id: &str) -> Result<Clip> {
+        self.block_on(self.inner.get_clip(id))
+    }
+
+    /// Get several clips by ID, skipping IDs that don't exist
+    pub fn get_clips(&self, ids: &[String]) -> Result<Vec<Clip>> {
+        self.block_on(self.inner.get_clips(ids))
+    }
+
+    /// Update a clip's tags, additional notes, language, color and/or icon
+    pub fn update_clip(
+        &self,
+        id: &str,
+        tags: Option<Vec<String>>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+        color: Option<String>,
+        icon: Option<String>,
+    ) -> Result<Clip> {
+        self.block_on(
+            self.inner
+                .update_clip(id, tags, additional_notes, language, color, icon),
+        )
+    }
+
+    /// Set the content format hint of a clip
+    pub fn set_clip_content_format(&self, id: &str, content_format: ContentFormat) -> Result<Clip> {
+        self.block_on(self.inner.set_clip_content_format(id, content_format))
+    }
+
+    /// Set who can see a clip
+    pub fn set_clip_visibility(&self, id: &str, visibility: Visibility) -> Result<Clip> {
+        self.block_on(self.inner.set_clip_visibility(id, visibility))
+    }
+
+    /// Search clips with optional filters and paging
+    pub fn search_clips(
+        &self,
+        query: &str,
+        filters: SearchFilters,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PagedResult> {
+        self.block_on(self.inner.search_clips(query, filters, page, page_size))
+    }
+
+    /// List all clips with optional filters and paging
+    pub fn list_clips(
+        &self,
+        filters: SearchFilters,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PagedResult> {
+        self.block_on(self.inner.list_clips(filters, page, page_size))
+    }
+
+    /// Search clips, continuing after a previous page's `next_cursor`
+    pub fn search_clips_after(
+        &self,
+        query: &str,
+        filters: SearchFilters,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PagedResult> {
+        self.block_on(self.inner.search_clips_after(query, filters, cursor, limit))
+    }
+
+    /// List clips, continuing after a previous page's `next_cursor`
+    pub fn list_clips_after(
+        &self,
+        filters: SearchFilters,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PagedResult> {
+        self.block_on(self.inner.list_clips_after(filters, cursor, limit))
+    }
+
+    /// Download a clip's file attachment
+    pub fn download_file(&self, id: &str) -> Result<Vec<u8>> {
+        self.block_on(self.inner.download_file(id))
+    }
+
+    /// Delete a clip by ID
+    pub fn delete_clip(&self, id: &str) -> Result<()> {
+        self.block_on(self.inner.delete_clip(id))
+    }
+
+    /// Append text to a text clip
+    pub fn append_to_clip(&self, id: &str, content: String, separator: &str) -> Result<Clip> {
+        self.block_on(self.inner.append_to_clip(id, content, separator))
+    }
+
+    /// Apply text transforms to a clip, storing the result as a new clip
+    pub fn transform_clip(&self, id: &str, transforms: &[Transform]) -> Result<Clip> {
+        self.block_on(self.inner.transform_clip(id, transforms))
+    }
+
+    /// Translate a text clip, storing the translation as a new clip
+    pub fn translate_clip(&self, id: &str, to: &str) -> Result<Clip> {
+        self.block_on(self.inner.translate_clip(id, to))
+    }
+
+    /// Split a text clip into one new clip per part
+    pub fn split_clip(
+        &self,
+        id: &str,
+        separator: SplitSeparator,
+        delete_source: bool,
+    ) -> Result<Vec<Clip>> {
+        self.block_on(self.inner.split_clip(id, separator, delete_source))
+    }
+
+    /// Link a clip to another clip
+    pub fn link_clips(&self, id: &str, target_id: &str, relation: &str) -> Result<ClipLink> {
+        self.block_on(self.inner.link_clips(id, target_id, relation))
+    }
+
+    /// Get the links from and to a clip
+    pub fn get_clip_links(&self, id: &str) -> Result<Vec<ClipLink>> {
+        self.block_on(self.inner.get_clip_links(id))
+    }
+
+    /// Remove a link from a clip
+    pub fn delete_clip_link(&self, id: &str, link_id: &str) -> Result<()> {
+        self.block_on(self.inner.delete_clip_link(id, link_id))
+    }
+
+    /// List all collections
+    pub fn list_collections(&self) -> Result<Vec<Collection>> {
+        self.block_on(self.inner.list_collections())
+    }
+
+    /// Create an empty collection
+    pub fn create_collection(&self, name: &str, description: Option<String>) -> Result<Collection> {
+        self.block_on(self.inner.create_collection(name, description))
+    }
+
+    /// Get a collection by ID
+    pub fn get_collection(&self, id: &str) -> Result<Collection> {
+        self.block_on(self.inner.get_collection(id))
+    }
+
+    /// Rename a collection and/or change its description
+    pub fn update_collection(
+        &self,
+        id: &str,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<Collection> {
+        self.block_on(self.inner.update_collection(id, name, description))
+    }
+
+    /// Delete a collection
+    pub fn delete_collection(&self, id: &str) -> Result<()> {
+        self.block_on(self.inner.delete_collection(id))
+    }
+
+    /// List the clips of a collection in their manual order
+    pub fn list_collection_clips(
+        &self,
+        id: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PagedResult> {
+        self.block_on(self.inner.list_collection_clips(id, page, page_size))
+    }
+
+    /// Add a clip to a collection
+    pub fn add_clip_to_collection(
+        &self,
+        id: &str,
+        clip_id: &str,
+        position: Option<usize>,
+    ) -> Result<()> {
+        self.block_on(self.inner.add_clip_to_collection(id, clip_id, position))
+    }
+
+    /// Set the manual order of a collection
+    pub fn reorder_collection(&self, id: &str, clip_ids: &[String]) -> Result<()> {
+        self.block_on(self.inner.reorder_collection(id, clip_ids))
+    }
+
+    /// Remove a clip from a collection
+    pub fn remove_clip_from_collection(&self, id: &str, clip_id: &str) -> Result<()> {
+        self.block_on(self.inner.remove_clip_from_collection(id, clip_id))
+    }
+
+    /// Get the collections containing a clip
+    pub fn get_clip_collections(&self, id: &str) -> Result<Vec<Collection>> {
+        self.block_on(self.inner.get_clip_collections(id))
+    }
+
+    /// List all templates
+    pub fn list_templates(&self) -> Result<Vec<Template>> {
+        self.block_on(self.inner.list_templates())
+    }
+
+    /// Create a template
+    pub fn create_template(
+        &self,
+        name: &str,
+        description: Option<String>,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Template> {
+        self.block_on(self.inner.create_template(name, description, content, tags))
+    }
+
+    /// Get a template by ID
+    pub fn get_template(&self, id: &str) -> Result<Template> {
+        self.block_on(self.inner.get_template(id))
+    }
+
+    /// Change a template; fields left as None are kept
+    pub fn update_template(
+        &self,
+        id: &str,
+        name: Option<String>,
+        description: Option<String>,
+        content: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<Template> {
+        self.block_on(
+            self.inner
+                .update_template(id, name, description, content, tags),
+        )
+    }
+
+    /// Delete a template
+    pub fn delete_template(&self, id: &str) -> Result<()> {
+        self.block_on(self.inner.delete_template(id))
+    }
+
+    /// Create a text clip from a template
+    pub fn instantiate_template(
+        &self,
+        id: &str,
+        values: HashMap<String, String>,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+    ) -> Result<Clip> {
+        self.block_on(
+            self.inner
+                .instantiate_template(id, values, tags, additional_notes),
+        )
+    }
+
+    /// Create a short URL for a clip
+    pub fn create_short_url(&self, id: &str, expires_in_hours: Option<u32>) -> Result<ShortUrl> {
+        self.block_on(self.inner.create_short_url(id, expires_in_hours))
+    }
+
+    /// Export all clips to a tar.gz archive file
+    pub fn export_to_file<P: AsRef<Path>>(&self, output_path: P) -> Result<u64> {
+        self.block_on(self.inner.export_to_file(output_path))
+    }
+
+    /// Export all clips except sensitive ones to a tar.gz archive file
+    pub fn export_non_sensitive_to_file<P: AsRef<Path>>(&self, output_path: P) -> Result<u64> {
+        self.block_on(self.inner.export_non_sensitive_to_file(output_path))
+    }
+
+    /// Import clips from a tar.gz archive file
+    pub fn import_from_file<P: AsRef<Path>>(&self, input_path: P) -> Result<ImportResult> {
+        self.block_on(self.inner.import_from_file(input_path))
+    }
+
+    /// List all tags with pagination
+    pub fn list_tags(&self, page: usize, page_size: usize) -> Result<PagedTagResult> {
+        self.block_on(self.inner.list_tags(page, page_size))
+    }
+
+    /// Search tags by prefix with pagination
+    pub fn search_tags(
+        &self,
+        query: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PagedTagResult> {
+        self.block_on(self.inner.search_tags(query, page, page_size))
+    }
+
+    /// Get the number of clips using each tag, most used first
+    pub fn tag_stats(&self) -> Result<Vec<TagStats>> {
+        self.block_on(self.inner.tag_stats())
+    }
+
+    /// Get the clips created per day of the last `months` months and their
+    /// most used tags
+    pub fn activity_stats(
+        &self,
+        months: u32,
+        tz_offset_minutes: i32,
+        top_tags: usize,
+    ) -> Result<ActivityStats> {
+        self.block_on(
+            self.inner
+                .activity_stats(months, tz_offset_minutes, top_tags),
+        )
+    }
+
+    /// List the devices clips were created on, most recently seen first
+    pub fn list_devices(&self) -> Result<Vec<Device>> {
+        self.block_on(self.inner.list_devices())
+    }
+
+    /// Put a clip on the clipboard of a connected device
+    pub fn push_to_device_clipboard(
+        &self,
+        device: &str,
+        clip_id: &str,
+    ) -> Result<PushToClipboardResult> {
+        self.block_on(self.inner.push_to_device_clipboard(device, clip_id))
+    }
+
+    /// Issue a pairing code for a new device
+    pub fn create_pairing_code(&self) -> Result<PairingCode> {
+        self.block_on(self.inner.create_pairing_code())
+    }
+
+    /// Redeem a pairing code for a device token
+    pub fn claim_pairing_code(&self, code: &str, name: &str) -> Result<PairedDeviceToken> {
+        self.block_on(self.inner.claim_pairing_code(code, name))
+    }
+
+    /// List paired devices, most recently paired first
+    pub fn list_paired_devices(&self) -> Result<Vec<PairedDevice>> {
+        self.block_on(self.inner.list_paired_devices())
+    }
+
+    /// Revoke a paired device
+    pub fn revoke_paired_device(&self, id: &str) -> Result<()> {
+        self.block_on(self.inner.revoke_paired_device(id))
+    }
+
+    /// Rename a tag on all clips that use it
+    pub fn rename_tag(&self, from: &str, to: &str) -> Result<RenameTagResult> {
+        self.block_on(self.inner.rename_tag(from, to))
+    }
+
+    /// Run the server's cleanup now, or with `dry_run` list what it would delete
+    pub fn run_cleanup(&self, dry_run: bool) -> Result<CleanupResult> {
+        self.block_on(self.inner.run_cleanup(dry_run))
+    }
+
+    /// Subscribe to real-time notifications
+    ///
+    /// Notifications are delivered on the returned receiver until it is
+    /// dropped or the connection closes.
+    pub fn subscribe_notifications(&self) -> Result<std::sync::mpsc::Receiver<ClipNotification>> {
+        let (async_tx, mut async_rx) = mpsc::unbounded_channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        // The connection task keeps running on the runtime after its handle is dropped
+        self.block_on(self.inner.subscribe_notifications(async_tx))?;
+
+        self.runtime.spawn(async move {
+            while let Some(notification) = async_rx.recv().await {
+                if tx.send(notification).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+}
+
+impl ClientBuilder {
+    /// Build a blocking client
+    ///
+    /// Fails if the HTTP client or the internal runtime cannot be created.
+    pub fn build_blocking(self) -> Result<ClipperClient> {
+        ClipperClient::try_from_async(self.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::RetryPolicy;
+    use crate::error::ClientError;
+
+    #[test]
+    fn test_blocking_client_reports_connection_errors() {
+        let client = ClientBuilder::new("http://127.0.0.1:1")
+            .retry_policy(RetryPolicy::none())
+            .build_blocking()
+            .unwrap();
+
+        assert!(matches!(
+            client.get_server_info(),
+            Err(ClientError::Http(_))
+        ));
+    }
+}
@@ -0,0 +1,405 @@
+//! Client-side response cache with ETag revalidation
+//!
+//! Cached responses are always revalidated with `If-None-Match`, so the cache
+//! never serves stale data; it only avoids downloading a body the client
+//! already has when the server answers `304 Not Modified`.
+//!
+//! Entries are kept in memory (bounded, oldest evicted first) and can
+//! optionally be persisted to a directory so they survive restarts. The
+//! directory and its files are only accessible to the current user, and it is
+//! bounded too: the oldest files are removed once it holds too many entries or
+//! bytes.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Default number of responses kept in memory
+const DEFAULT_MAX_MEMORY_ENTRIES: usize = 256;
+
+/// Default number of responses kept on disk
+const DEFAULT_MAX_DISK_ENTRIES: usize = 1024;
+
+/// Default total size of the responses kept on disk (64 MiB)
+const DEFAULT_MAX_DISK_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Configuration for the response cache
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Maximum number of responses kept in memory
+    pub max_memory_entries: usize,
+    /// Directory for persisting cached responses (None = memory only)
+    pub disk_dir: Option<PathBuf>,
+    /// Maximum number of responses kept on disk
+    pub max_disk_entries: usize,
+    /// Maximum total size in bytes of the responses kept on disk
+    pub max_disk_bytes: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_memory_entries: DEFAULT_MAX_MEMORY_ENTRIES,
+            disk_dir: None,
+            max_disk_entries: DEFAULT_MAX_DISK_ENTRIES,
+            max_disk_bytes: DEFAULT_MAX_DISK_BYTES,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Create a memory-only cache configuration with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of responses kept in memory
+    pub fn with_max_memory_entries(mut self, max_memory_entries: usize) -> Self {
+        self.max_memory_entries = max_memory_entries;
+        self
+    }
+
+    /// Persist cached responses in the given directory
+    pub fn with_disk_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.disk_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the maximum number of responses kept on disk
+    pub fn with_max_disk_entries(mut self, max_disk_entries: usize) -> Self {
+        self.max_disk_entries = max_disk_entries;
+        self
+    }
+
+    /// Set the maximum total size in bytes of the responses kept on disk
+    pub fn with_max_disk_bytes(mut self, max_disk_bytes: u64) -> Self {
+        self.max_disk_bytes = max_disk_bytes;
+        self
+    }
+}
+
+/// A cached response body with the ETag it was served with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<String, CachedResponse>,
+    /// Keys in insertion order, used for eviction
+    order: VecDeque<String>,
+}
+
+/// In-memory and optional on-disk cache of JSON responses keyed by request URL
+pub struct ResponseCache {
+    config: CacheConfig,
+    memory: Mutex<MemoryCache>,
+}
+
+impl ResponseCache {
+    /// Create a new response cache
+    pub fn new(config: CacheConfig) -> Self {
+        if let Some(dir) = &config.disk_dir {
+            // Also tightens the permissions of a directory (and files) created
+            // by an older version
+            if let Err(e) = clipper_security::secure_create_dir_all(dir).and_then(|_| {
+                clipper_security::secure_directory_recursive(dir, |msg| {
+                    eprintln!("Response cache: {}", msg)
+                })
+            }) {
+                eprintln!(
+                    "Failed to prepare response cache directory {}: {}",
+                    dir.display(),
+                    e
+                );
+            }
+        }
+
+        Self {
+            config,
+            memory: Mutex::new(MemoryCache::default()),
+        }
+    }
+
+    /// Look up a cached response, falling back to the disk cache if configured
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        if let Some(entry) = self.memory.lock().unwrap().entries.get(key) {
+            return Some(entry.clone());
+        }
+
+        let path = self.disk_path(key)?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                eprintln!("Failed to read cached response {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        match serde_json::from_str::<CachedResponse>(&contents) {
+            Ok(entry) => {
+                self.insert_memory(key, entry.clone());
+                Some(entry)
+            }
+            Err(e) => {
+                eprintln!(
+                    "Discarding corrupt cached response {}: {}",
+                    path.display(),
+                    e
+                );
+                remove_cache_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Store a response in the cache
+    pub fn put(&self, key: &str, etag: String, body: String) {
+        let entry = CachedResponse { etag, body };
+
+        if let Some(path) = self.disk_path(key)
+            && let Ok(contents) = serde_json::to_string(&entry)
+        {
+            // The disk cache is best-effort; a failed write only costs a re-download
+            match clipper_security::secure_write(&path, contents) {
+                Ok(()) => self.evict_disk(),
+                Err(e) => eprintln!("Failed to write cached response {}: {}", path.display(), e),
+            }
+        }
+
+        self.insert_memory(key, entry);
+    }
+
+    /// Remove a single response from the cache
+    pub fn remove(&self, key: &str) {
+        let mut memory = self.memory.lock().unwrap();
+        memory.entries.remove(key);
+        memory.order.retain(|k| k != key);
+        drop(memory);
+
+        if let Some(path) = self.disk_path(key) {
+            remove_cache_file(&path);
+        }
+    }
+
+    /// Remove all cached responses, including the ones on disk
+    pub fn clear(&self) {
+        let mut memory = self.memory.lock().unwrap();
+        memory.entries.clear();
+        memory.order.clear();
+        drop(memory);
+
+        for file in self.disk_files() {
+            remove_cache_file(&file.path);
+        }
+    }
+
+    /// Number of responses currently held in memory
+    pub fn memory_len(&self) -> usize {
+        self.memory.lock().unwrap().entries.len()
+    }
+
+    fn insert_memory(&self, key: &str, entry: CachedResponse) {
+        if self.config.max_memory_entries == 0 {
+            return;
+        }
+
+        let mut memory = self.memory.lock().unwrap();
+        if memory.entries.insert(key.to_string(), entry).is_none() {
+            memory.order.push_back(key.to_string());
+        }
+
+        while memory.entries.len() > self.config.max_memory_entries {
+            match memory.order.pop_front() {
+                Some(oldest) => {
+                    memory.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Remove the oldest files until the disk cache is within its limits
+    fn evict_disk(&self) {
+        let mut files = self.disk_files();
+        let mut entries = files.len();
+        let mut bytes: u64 = files.iter().map(|file| file.len).sum();
+        if entries <= self.config.max_disk_entries && bytes <= self.config.max_disk_bytes {
+            return;
+        }
+
+        files.sort_by_key(|file| file.modified);
+        for file in files {
+            if entries <= self.config.max_disk_entries && bytes <= self.config.max_disk_bytes {
+                break;
+            }
+            remove_cache_file(&file.path);
+            entries -= 1;
+            bytes = bytes.saturating_sub(file.len);
+        }
+    }
+
+    /// The cached responses in the disk cache directory
+    fn disk_files(&self) -> Vec<DiskFile> {
+        let Some(dir) = &self.config.disk_dir else {
+            return Vec::new();
+        };
+        let read_dir = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                eprintln!(
+                    "Failed to read response cache directory {}: {}",
+                    dir.display(),
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        read_dir
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some(DiskFile {
+                    path: entry.path(),
+                    len: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                })
+            })
+            .collect()
+    }
+
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        let dir = self.config.disk_dir.as_ref()?;
+        let hash = hex::encode(Sha256::digest(key.as_bytes()));
+        Some(dir.join(format!("{}.json", hash)))
+    }
+}
+
+/// A cached response file, as seen by disk eviction
+struct DiskFile {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+}
+
+/// Remove a cached response file; a file that is already gone is not an error
+fn remove_cache_file(path: &Path) {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            eprintln!("Failed to remove cached response {}: {}", path.display(), e)
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_cache_evicts_oldest() {
+        let cache = ResponseCache::new(CacheConfig::new().with_max_memory_entries(2));
+
+        cache.put("a", "\"1\"".to_string(), "{}".to_string());
+        cache.put("b", "\"2\"".to_string(), "{}".to_string());
+        cache.put("c", "\"3\"".to_string(), "{}".to_string());
+
+        assert_eq!(cache.memory_len(), 2);
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("c").unwrap().etag, "\"3\"");
+    }
+
+    #[test]
+    fn test_disk_cache_survives_new_instance() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = CacheConfig::new().with_disk_dir(dir.path());
+
+        let cache = ResponseCache::new(config.clone());
+        cache.put(
+            "http://localhost/clips",
+            "W/\"abc\"".to_string(),
+            "[1]".to_string(),
+        );
+
+        let reopened = ResponseCache::new(config);
+        let entry = reopened.get("http://localhost/clips").unwrap();
+        assert_eq!(entry.etag, "W/\"abc\"");
+        assert_eq!(entry.body, "[1]");
+
+        reopened.clear();
+        assert!(
+            ResponseCache::new(CacheConfig::new().with_disk_dir(dir.path()))
+                .get("http://localhost/clips")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_disk_cache_evicts_oldest_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = ResponseCache::new(
+            CacheConfig::new()
+                .with_max_memory_entries(0)
+                .with_disk_dir(dir.path())
+                .with_max_disk_entries(2),
+        );
+
+        for key in ["a", "b", "c"] {
+            cache.put(key, format!("\"{}\"", key), "{}".to_string());
+            // Keep the modification times apart so "a" is clearly the oldest
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.get("b").unwrap().etag, "\"b\"");
+        assert_eq!(cache.get("c").unwrap().etag, "\"c\"");
+    }
+
+    #[test]
+    fn test_disk_cache_limits_total_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = ResponseCache::new(
+            CacheConfig::new()
+                .with_max_memory_entries(0)
+                .with_disk_dir(dir.path())
+                .with_max_disk_bytes(1000),
+        );
+
+        cache.put("small", "\"1\"".to_string(), "{}".to_string());
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put("large", "\"2\"".to_string(), "x".repeat(990));
+
+        assert!(cache.get("small").is_none());
+        assert!(cache.get("large").is_none());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_disk_cache_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache_dir = dir.path().join("responses");
+        let cache = ResponseCache::new(CacheConfig::new().with_disk_dir(&cache_dir));
+        cache.put("a", "\"1\"".to_string(), "{}".to_string());
+
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache_dir), 0o700);
+        let file = std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(mode(&file.path()), 0o600);
+    }
+}
@@ -53,6 +53,23 @@ pub fn calculate_fingerprint(der_bytes: &[u8]) -> String {
         .join(":")
 }
 
+/// Check whether an error, or any error in its source chain, is a TLS
+/// certificate verification failure
+pub(crate) fn is_certificate_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(err) = current {
+        if let Some(rustls::Error::InvalidCertificate(_)) = err.downcast_ref::<rustls::Error>() {
+            return true;
+        }
+        // Platform TLS backends (native-tls) only expose a message
+        if err.to_string().to_lowercase().contains("certificate") {
+            return true;
+        }
+        current = err.source();
+    }
+    false
+}
+
 /// Fetch the TLS certificate from a server
 ///
 /// This connects to the server and retrieves its certificate chain.
@@ -373,6 +390,17 @@ pub fn create_http_client_with_trusted_certs(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_certificate_error() {
+        let err = std::io::Error::other(rustls::Error::InvalidCertificate(
+            rustls::CertificateError::UnknownIssuer,
+        ));
+        assert!(is_certificate_error(&err));
+
+        let err = std::io::Error::other("connection refused");
+        assert!(!is_certificate_error(&err));
+    }
+
     #[test]
     fn test_fingerprint_calculation() {
         // Test with known data
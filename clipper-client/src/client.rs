@@ -1,35 +1,109 @@
-use crate::certificate::create_tls_config_with_trusted_certs;
+use crate::builder::{ClientBuilder, HttpConfig, RetryPolicy};
+use crate::cache::ResponseCache;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::certificate::{
+    create_tls_config_with_trusted_certs, fetch_server_certificate, is_certificate_error,
+};
 use crate::error::{ClientError, Result};
 use crate::models::{
-    Clip, ClipNotification, CreateClipRequest, CreateShortUrlRequest, ImportResult, PagedResult,
-    PagedTagResult, SearchFilters, ServerInfo, ShortUrl, UpdateClipRequest, WsAuthRequest,
-    WsAuthResponse,
+    ActivityStats, AddCollectionClipRequest, AppendClipRequest, BatchGetRequest, BatchGetResult,
+    ClaimPairingCodeRequest, CleanupResult, Clip, ClipLink, Collection, ContentFormat,
+    CreateClipLinkRequest, CreateClipRequest, CreateCollectionRequest, CreateShortUrlRequest,
+    CreateTemplateRequest, Device, InstantiateTemplateRequest, PagedResult, PagedTagResult,
+    PairedDevice, PairedDeviceToken, PairingCode, PushToClipboardRequest, PushToClipboardResult,
+    RenameTagRequest, RenameTagResult, ReorderCollectionRequest, SearchFilters, ServerInfo,
+    ShortUrl, SplitClipRequest, SplitSeparator, TagStats, Template, Transform,
+    TransformClipRequest, UpdateClipRequest, UpdateCollectionRequest, UpdateTemplateRequest,
+    Visibility,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::models::{
+    ClipNotification, ImportResult, SortDirection, SortField, WsAuthRequest, WsAuthResponse,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use crate::reconnect::{ConnectionStatus, ReconnectConfig};
+use crate::telemetry;
+#[cfg(not(target_arch = "wasm32"))]
+use chrono::{DateTime, Utc};
+use clipper_security::SecretString;
+#[cfg(not(target_arch = "wasm32"))]
 use futures_util::{SinkExt, StreamExt};
+use futures_util::{Stream, TryStreamExt, stream};
 use reqwest::StatusCode;
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::VecDeque;
+use std::future::Future;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
 use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+#[cfg(not(target_arch = "wasm32"))]
 use tokio::sync::mpsc;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+#[cfg(not(target_arch = "wasm32"))]
 use tokio_tungstenite::tungstenite::Message;
+#[cfg(not(target_arch = "wasm32"))]
 use tokio_util::io::ReaderStream;
 use url::Url;
 
+#[cfg(target_arch = "wasm32")]
+use crate::wasm::sleep;
+
 /// Connection timeout - if no message received within this time, consider connection dead
 /// Server sends ping every 30s, so we wait 60s (2x interval) before timing out
+#[cfg(not(target_arch = "wasm32"))]
 const CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Page size used when streaming all clips
+const STREAM_PAGE_SIZE: usize = 100;
+
+/// Most IDs the server accepts in one batch-get request
+const BATCH_GET_MAX_IDS: usize = 100;
+
+/// Next page to fetch in `list_clips_stream`
+enum StreamPage {
+    Number(usize),
+    Cursor(String),
+}
+
+/// Number of delivered clips remembered to de-duplicate recovered clips
+#[cfg(not(target_arch = "wasm32"))]
+const RECENT_CLIPS_CAPACITY: usize = 256;
+
+/// Recovery fetches clips changed this long before the watermark too, since
+/// changes aren't always published in the order they were stored
+#[cfg(not(target_arch = "wasm32"))]
+const RECOVERY_OVERLAP_SECS: i64 = 30;
+
+#[cfg(not(target_arch = "wasm32"))]
+type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
 /// Client for interacting with the Clipper server
 #[derive(Clone)]
 pub struct ClipperClient {
     base_url: String,
     client: reqwest::Client,
-    /// Optional Bearer token for authentication
-    token: Option<String>,
+    /// Optional Bearer token for authentication, zeroed when the client is dropped
+    token: Option<SecretString>,
     /// Trusted certificate fingerprints (host -> SHA-256 fingerprint)
     trusted_fingerprints: HashMap<String, String>,
+    /// Optional ETag response cache for clip list/get requests
+    cache: Option<Arc<ResponseCache>>,
+    /// Tags new clip notifications are limited to; empty for all of them
+    sync_tags: Vec<String>,
+    /// Name the device subscribes to notifications under, to receive
+    /// clipboard pushes
+    device_name: Option<String>,
+    /// Transport settings, kept to rebuild the HTTP client when trust changes
+    http: HttpConfig,
+    /// Retry policy for HTTP requests
+    retry_policy: RetryPolicy,
 }
 
 impl ClipperClient {
@@ -38,12 +112,7 @@ impl ClipperClient {
     /// # Arguments
     /// * `base_url` - Base URL of the Clipper server (e.g., "http://localhost:3000")
     pub fn new(base_url: impl Into<String>) -> Self {
-        Self {
-            base_url: base_url.into().trim_end_matches('/').to_string(),
-            client: reqwest::Client::new(),
-            token: None,
-            trusted_fingerprints: HashMap::new(),
-        }
+        Self::from_builder_lossy(ClientBuilder::new(base_url))
     }
 
     /// Create a new Clipper client with Bearer token authentication
@@ -52,12 +121,7 @@ impl ClipperClient {
     /// * `base_url` - Base URL of the Clipper server (e.g., "http://localhost:3000")
     /// * `token` - Bearer token for authentication
     pub fn new_with_token(base_url: impl Into<String>, token: impl Into<String>) -> Self {
-        Self {
-            base_url: base_url.into().trim_end_matches('/').to_string(),
-            client: reqwest::Client::new(),
-            token: Some(token.into()),
-            trusted_fingerprints: HashMap::new(),
-        }
+        Self::from_builder_lossy(ClientBuilder::new(base_url).token(token))
     }
 
     /// Create a new Clipper client with trusted certificate fingerprints
@@ -71,25 +135,43 @@ impl ClipperClient {
         token: Option<String>,
         trusted_fingerprints: HashMap<String, String>,
     ) -> Self {
-        // Create HTTP client that accepts certificates if we have trusted fingerprints
-        let client = if trusted_fingerprints.is_empty() {
-            reqwest::Client::new()
-        } else {
-            reqwest::Client::builder()
-                .danger_accept_invalid_certs(true)
-                .timeout(Duration::from_secs(30))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new())
-        };
+        let mut builder = ClientBuilder::new(base_url).trusted_fingerprints(trusted_fingerprints);
+        builder.token = token.map(SecretString::new);
+        Self::from_builder_lossy(builder)
+    }
+
+    /// Create a builder to configure timeouts, retries, proxy and connection pooling
+    ///
+    /// # Arguments
+    /// * `base_url` - Base URL of the Clipper server (e.g., "http://localhost:3000")
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(base_url)
+    }
 
+    pub(crate) fn from_builder(builder: ClientBuilder, client: reqwest::Client) -> Self {
         Self {
-            base_url: base_url.into().trim_end_matches('/').to_string(),
+            base_url: builder.base_url,
             client,
-            token,
-            trusted_fingerprints,
+            token: builder.token,
+            trusted_fingerprints: builder.trusted_fingerprints,
+            cache: builder.cache,
+            sync_tags: builder.sync_tags,
+            device_name: builder.device_name,
+            http: builder.http,
+            retry_policy: builder.retry_policy,
         }
     }
 
+    /// Build from a builder with default transport settings, which cannot fail
+    /// to validate; falls back to a plain reqwest client just in case.
+    fn from_builder_lossy(builder: ClientBuilder) -> Self {
+        let client = builder
+            .http
+            .build_client(!builder.trusted_fingerprints.is_empty())
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self::from_builder(builder, client)
+    }
+
     /// Get the trusted certificate fingerprints
     pub fn trusted_fingerprints(&self) -> &HashMap<String, String> {
         &self.trusted_fingerprints
@@ -100,10 +182,9 @@ impl ClipperClient {
         self.trusted_fingerprints = fingerprints.clone();
         // Rebuild client if we have trusted certs
         if !fingerprints.is_empty() {
-            self.client = reqwest::Client::builder()
-                .danger_accept_invalid_certs(true)
-                .timeout(Duration::from_secs(30))
-                .build()
+            self.client = self
+                .http
+                .build_client(true)
                 .unwrap_or_else(|_| reqwest::Client::new());
         }
     }
@@ -113,34 +194,198 @@ impl ClipperClient {
     /// # Arguments
     /// * `token` - Bearer token for authentication, or None to disable authentication
     pub fn set_token(&mut self, token: Option<String>) {
-        self.token = token;
+        self.token = token.map(SecretString::new);
     }
 
     /// Get the current Bearer token
     pub fn token(&self) -> Option<&str> {
-        self.token.as_deref()
+        self.token.as_ref().map(SecretString::expose)
+    }
+
+    /// Enable the response cache for clip list/get requests.
+    ///
+    /// The cache can be shared between clients (e.g. when the client is
+    /// recreated after a server URL change) since entries are keyed by URL.
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Set or remove the response cache
+    pub fn set_cache(&mut self, cache: Option<Arc<ResponseCache>>) {
+        self.cache = cache;
+    }
+
+    /// Get the response cache, if enabled
+    pub fn cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Limit `NewClip` notifications to clips with at least one of `tags`,
+    /// or receive all of them if `tags` is empty
+    ///
+    /// Takes effect the next time notifications are subscribed to. Servers
+    /// that don't support tag filtering send every new clip.
+    pub fn set_sync_tags(&mut self, tags: Vec<String>) {
+        self.sync_tags = tags;
+    }
+
+    /// Get the tags new clip notifications are limited to
+    pub fn sync_tags(&self) -> &[String] {
+        &self.sync_tags
+    }
+
+    /// Whether a new clip with `tags` passes the sync tag filter
+    pub fn matches_sync_tags(&self, tags: &[String]) -> bool {
+        self.sync_tags.is_empty() || tags.iter().any(|tag| self.sync_tags.contains(tag))
+    }
+
+    /// Set the name this device subscribes to notifications under, so clips
+    /// pushed to it arrive as `SetClipboard` notifications. Usually the
+    /// hostname, as in the `$host:` tag of its clips.
+    ///
+    /// Takes effect the next time notifications are subscribed to.
+    pub fn set_device_name(&mut self, name: Option<String>) {
+        self.device_name = name;
+    }
+
+    /// Get the name this device subscribes to notifications under
+    pub fn device_name(&self) -> Option<&str> {
+        self.device_name.as_deref()
+    }
+
+    /// Get the retry policy for HTTP requests
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Set the retry policy for HTTP requests
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
     }
 
     /// Apply authentication header to a request builder if a token is set
     fn apply_auth(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         match &self.token {
-            Some(token) => builder.header("Authorization", format!("Bearer {}", token)),
+            Some(token) => builder.bearer_auth(token.expose()),
             None => builder,
         }
     }
 
+    /// Apply the authentication and request ID headers to a request builder
+    fn apply_headers(
+        &self,
+        builder: reqwest::RequestBuilder,
+        request_id: &str,
+    ) -> reqwest::RequestBuilder {
+        self.apply_auth(builder)
+            .header(telemetry::REQUEST_ID_HEADER, request_id)
+    }
+
+    /// Send a single request, in a client span carrying the trace context
+    /// with the `otel` feature
+    async fn execute(&self, request: reqwest::Request) -> reqwest::Result<reqwest::Response> {
+        #[cfg(feature = "otel")]
+        {
+            use tracing::Instrument;
+
+            let mut request = request;
+            let request_id = request
+                .headers()
+                .get(telemetry::REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            let span = telemetry::request_span(&request, &request_id);
+            telemetry::inject_trace_context(&span, request.headers_mut());
+            self.client.execute(request).instrument(span).await
+        }
+
+        #[cfg(not(feature = "otel"))]
+        self.client.execute(request).await
+    }
+
+    /// Send a request with authentication, retrying according to the retry policy.
+    ///
+    /// `make_request` is called for every attempt so the request (including
+    /// multipart bodies) can be rebuilt.
+    async fn send(
+        &self,
+        make_request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let mut retries = 0;
+        // Retries are the same request as far as the server's logs go
+        let request_id = telemetry::new_request_id();
+
+        loop {
+            let request = self.apply_headers(make_request(), &request_id).build()?;
+            let idempotent = request.method().is_idempotent();
+            let result = self.execute(request).await;
+
+            // Retrying won't help until the user trusts the certificate
+            #[cfg(not(target_arch = "wasm32"))]
+            let result = match result {
+                Err(e) if is_certificate_error(&e) => {
+                    return Err(self.untrusted_certificate_error(e.into()).await);
+                }
+                result => result,
+            };
+
+            let should_retry = retries < self.retry_policy.max_retries
+                && match &result {
+                    Ok(response) => idempotent && is_retryable_status(response.status()),
+                    // A failed connect never reached the server, so any request can be retried
+                    Err(e) => {
+                        is_connect_error(e) || (idempotent && (e.is_timeout() || e.is_request()))
+                    }
+                };
+            if !should_retry {
+                return Ok(result?);
+            }
+
+            retries += 1;
+            sleep(self.retry_policy.backoff_for_retry(retries)).await;
+        }
+    }
+
     /// Get the base URL of the server
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
 
+    /// URL of the notification WebSocket endpoint
+    pub(crate) fn websocket_url(&self) -> String {
+        let ws_url = self
+            .base_url
+            .replace("http://", "ws://")
+            .replace("https://", "wss://");
+        let url = format!("{}/ws", ws_url);
+        if self.sync_tags.is_empty() && self.device_name.is_none() {
+            return url;
+        }
+        match Url::parse(&url) {
+            Ok(mut url) => {
+                let mut query = url.query_pairs_mut();
+                if !self.sync_tags.is_empty() {
+                    query.append_pair("tags", &self.sync_tags.join(","));
+                }
+                if let Some(device_name) = &self.device_name {
+                    query.append_pair("device", device_name);
+                }
+                drop(query);
+                url.to_string()
+            }
+            Err(_) => url,
+        }
+    }
+
     /// Get server version and configuration information
     ///
     /// # Returns
     /// Server info including version, uptime, and configuration (including max upload size)
     pub async fn get_server_info(&self) -> Result<ServerInfo> {
         let url = format!("{}/version", self.base_url);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        let response = self.send(|| self.client.get(&url)).await?;
 
         self.handle_response(response).await
     }
@@ -165,12 +410,39 @@ impl ClipperClient {
             tags,
             additional_notes,
             language,
+            content_format: None,
+            visibility: None,
         };
 
-        let response = self
-            .apply_auth(self.client.post(&url).json(&request))
-            .send()
-            .await?;
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Create a new clip with a content format hint
+    ///
+    /// Same as [`create_clip`](Self::create_clip), but marks how the content
+    /// is written. Shared pages of [`ContentFormat::Markdown`] clips render the
+    /// Markdown as HTML.
+    pub async fn create_clip_with_format(
+        &self,
+        content: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+        content_format: ContentFormat,
+    ) -> Result<Clip> {
+        let url = format!("{}/clips", self.base_url);
+        let request = CreateClipRequest {
+            content,
+            tags,
+            additional_notes,
+            language,
+            content_format: Some(content_format),
+            visibility: None,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
 
         self.handle_response(response).await
     }
@@ -201,6 +473,7 @@ impl ClipperClient {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn upload_file<R>(
         &self,
         reader: R,
@@ -226,6 +499,7 @@ impl ClipperClient {
     /// * `tags` - List of tags for the clip
     /// * `additional_notes` - Optional additional notes
     /// * `content` - Optional content override (e.g., full file path instead of filename)
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn upload_file_with_content<R>(
         &self,
         reader: R,
@@ -259,10 +533,14 @@ impl ClipperClient {
             form = form.text("content", content_value);
         }
 
-        let response = self
-            .apply_auth(self.client.post(&url).multipart(form))
-            .send()
-            .await?;
+        // A streamed body can't be replayed, so this request is sent only once
+        let request = self
+            .apply_headers(
+                self.client.post(&url).multipart(form),
+                &telemetry::new_request_id(),
+            )
+            .build()?;
+        let response = self.execute(request).await?;
 
         self.handle_response(response).await
     }
@@ -340,25 +618,39 @@ impl ClipperClient {
     ) -> Result<Clip> {
         let url = format!("{}/clips/upload", self.base_url);
 
-        let file_part = reqwest::multipart::Part::bytes(bytes).file_name(filename);
+        // Shared buffer so the form can be rebuilt for each attempt without copying
+        let bytes = bytes::Bytes::from(bytes);
+        let make_form = || {
+            #[cfg(not(target_arch = "wasm32"))]
+            let file_part = reqwest::multipart::Part::stream_with_length(
+                reqwest::Body::from(bytes.clone()),
+                bytes.len() as u64,
+            );
+            // Browser multipart forms only take in-memory parts
+            #[cfg(target_arch = "wasm32")]
+            let file_part = reqwest::multipart::Part::bytes(bytes.to_vec());
 
-        let mut form = reqwest::multipart::Form::new().part("file", file_part);
+            let file_part = file_part.file_name(filename.clone());
 
-        if !tags.is_empty() {
-            form = form.text("tags", tags.join(","));
-        }
+            let mut form = reqwest::multipart::Form::new().part("file", file_part);
 
-        if let Some(notes) = additional_notes {
-            form = form.text("additional_notes", notes);
-        }
+            if !tags.is_empty() {
+                form = form.text("tags", tags.join(","));
+            }
 
-        if let Some(content_value) = content {
-            form = form.text("content", content_value);
-        }
+            if let Some(notes) = &additional_notes {
+                form = form.text("additional_notes", notes.clone());
+            }
+
+            if let Some(content_value) = &content {
+                form = form.text("content", content_value.clone());
+            }
+
+            form
+        };
 
         let response = self
-            .apply_auth(self.client.post(&url).multipart(form))
-            .send()
+            .send(|| self.client.post(&url).multipart(make_form()))
             .await?;
 
         self.handle_response(response).await
@@ -370,36 +662,123 @@ impl ClipperClient {
     /// * `id` - The clip ID
     pub async fn get_clip(&self, id: &str) -> Result<Clip> {
         let url = format!("{}/clips/{}", self.base_url, id);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        self.get_json_cached(&url).await
+    }
 
-        self.handle_response(response).await
+    /// Get several clips by ID in as few requests as possible
+    ///
+    /// Clips are returned in the order of `ids`. IDs that don't exist are
+    /// left out instead of failing the call.
+    ///
+    /// # Arguments
+    /// * `ids` - The clip IDs; more than 100 are fetched in several requests
+    pub async fn get_clips(&self, ids: &[String]) -> Result<Vec<Clip>> {
+        let url = format!("{}/clips/batch-get", self.base_url);
+        let mut clips = Vec::with_capacity(ids.len());
+
+        for chunk in ids.chunks(BATCH_GET_MAX_IDS) {
+            let request = BatchGetRequest {
+                ids: chunk.to_vec(),
+            };
+            let response = self.send(|| self.client.post(&url).json(&request)).await?;
+            let result: BatchGetResult = self.handle_response(response).await?;
+            clips.extend(result.items);
+        }
+
+        Ok(clips)
     }
 
-    /// Update a clip's tags, additional notes, and/or language
+    /// Update a clip's tags, additional notes, language, color and/or icon
     ///
     /// # Arguments
     /// * `id` - The clip ID
     /// * `tags` - Optional new tags
     /// * `additional_notes` - Optional new additional notes
     /// * `language` - Optional new language identifier
+    /// * `color` - Optional new color label (`#rgb`, `#rrggbb` or a color name; empty string clears it)
+    /// * `icon` - Optional new icon, e.g. an emoji (empty string clears it)
     pub async fn update_clip(
         &self,
         id: &str,
         tags: Option<Vec<String>>,
         additional_notes: Option<String>,
         language: Option<String>,
+        color: Option<String>,
+        icon: Option<String>,
     ) -> Result<Clip> {
         let url = format!("{}/clips/{}", self.base_url, id);
         let request = UpdateClipRequest {
             tags,
             additional_notes,
             language,
+            color,
+            icon,
+            content_format: None,
+            visibility: None,
         };
 
-        let response = self
-            .apply_auth(self.client.put(&url).json(&request))
-            .send()
-            .await?;
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Set the content format hint of a clip
+    pub async fn set_clip_content_format(
+        &self,
+        id: &str,
+        content_format: ContentFormat,
+    ) -> Result<Clip> {
+        let url = format!("{}/clips/{}", self.base_url, id);
+        let request = UpdateClipRequest {
+            tags: None,
+            additional_notes: None,
+            language: None,
+            color: None,
+            icon: None,
+            content_format: Some(content_format),
+            visibility: None,
+        };
+
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Set who can see a clip
+    ///
+    /// Guests only see [`Visibility::Shared`] and [`Visibility::Public`]
+    /// clips, and short URLs only work for public clips.
+    pub async fn set_clip_visibility(&self, id: &str, visibility: Visibility) -> Result<Clip> {
+        let url = format!("{}/clips/{}", self.base_url, id);
+        let request = UpdateClipRequest {
+            tags: None,
+            additional_notes: None,
+            language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            visibility: Some(visibility),
+        };
+
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Append text to a text clip
+    ///
+    /// # Arguments
+    /// * `id` - The clip ID
+    /// * `content` - The text to add at the end
+    /// * `separator` - Put between the clip's text and `content`, e.g. `"\n"`
+    pub async fn append_to_clip(&self, id: &str, content: String, separator: &str) -> Result<Clip> {
+        let url = format!("{}/clips/{}/append", self.base_url, id);
+        let request = AppendClipRequest {
+            content,
+            separator: separator.to_string(),
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
 
         self.handle_response(response).await
     }
@@ -407,8 +786,10 @@ impl ClipperClient {
     /// Search clips with optional filters and paging
     ///
     /// # Arguments
-    /// * `query` - Search query string
-    /// * `filters` - Optional filters (date range, tags)
+    /// * `query` - Search query string; may contain operators such as
+    ///   `tag:work`, `-tag:private`, `"exact phrase"`, `before:2024-01-01`
+    ///   and `has:attachment`, which the server parses
+    /// * `filters` - Optional filters (date range, tags) and result order
     /// * `page` - Page number (starting from 1)
     /// * `page_size` - Number of items per page
     ///
@@ -421,33 +802,46 @@ impl ClipperClient {
         page: usize,
         page_size: usize,
     ) -> Result<PagedResult> {
-        let mut url = Url::parse(&format!("{}/clips/search", self.base_url))?;
+        let mut url = self.search_url(query, filters)?;
 
-        url.query_pairs_mut().append_pair("q", query);
         url.query_pairs_mut().append_pair("page", &page.to_string());
         url.query_pairs_mut()
             .append_pair("page_size", &page_size.to_string());
-        // Add highlight markers for search result highlighting
-        url.query_pairs_mut()
-            .append_pair("highlight_begin", "<mark>");
-        url.query_pairs_mut()
-            .append_pair("highlight_end", "</mark>");
 
-        if let Some(start_date) = filters.start_date {
-            url.query_pairs_mut()
-                .append_pair("start_date", &start_date.to_rfc3339());
-        }
+        let response = self.send(|| self.client.get(url.clone())).await?;
 
-        if let Some(end_date) = filters.end_date {
-            url.query_pairs_mut()
-                .append_pair("end_date", &end_date.to_rfc3339());
-        }
+        self.handle_response(response).await
+    }
+
+    /// Search clips, continuing after a previous page's `next_cursor`
+    ///
+    /// Unlike page numbers, cursors don't skip or repeat clips when clips are
+    /// added or deleted between requests. Cursors are only returned when more
+    /// clips follow and the results are sorted by date (`created_at` or
+    /// `updated_at`), not by relevance.
+    ///
+    /// # Arguments
+    /// * `query` - Search query string, as for [`search_clips`](Self::search_clips)
+    /// * `filters` - Optional filters and result order; must match the
+    ///   filters of the request that returned the cursor
+    /// * `cursor` - `next_cursor` of the previous page, or None for the first page
+    /// * `limit` - Number of items per page
+    pub async fn search_clips_after(
+        &self,
+        query: &str,
+        filters: SearchFilters,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PagedResult> {
+        let mut url = self.search_url(query, filters)?;
 
-        if let Some(tags) = filters.tags {
-            url.query_pairs_mut().append_pair("tags", &tags.join(","));
+        url.query_pairs_mut()
+            .append_pair("limit", &limit.to_string());
+        if let Some(cursor) = cursor {
+            url.query_pairs_mut().append_pair("cursor", cursor);
         }
 
-        let response = self.apply_auth(self.client.get(url)).send().await?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
 
         self.handle_response(response).await
     }
@@ -464,29 +858,153 @@ impl ClipperClient {
         page: usize,
         page_size: usize,
     ) -> Result<PagedResult> {
-        let mut url = Url::parse(&format!("{}/clips", self.base_url))?;
+        let mut url = self.list_url(filters)?;
 
         url.query_pairs_mut().append_pair("page", &page.to_string());
         url.query_pairs_mut()
             .append_pair("page_size", &page_size.to_string());
 
-        if let Some(start_date) = filters.start_date {
-            url.query_pairs_mut()
-                .append_pair("start_date", &start_date.to_rfc3339());
-        }
+        self.get_json_cached(url.as_str()).await
+    }
+
+    /// List clips, continuing after a previous page's `next_cursor`
+    ///
+    /// Unlike page numbers, cursors don't skip or repeat clips when clips are
+    /// added or deleted between requests. Cursors are only returned when more
+    /// clips follow and the list is sorted by `created_at` (the default) or
+    /// `updated_at`.
+    ///
+    /// # Arguments
+    /// * `filters` - Optional filters; must match the filters of the request
+    ///   that returned the cursor
+    /// * `cursor` - `next_cursor` of the previous page, or None for the first page
+    /// * `limit` - Number of items per page
+    pub async fn list_clips_after(
+        &self,
+        filters: SearchFilters,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<PagedResult> {
+        let mut url = self.list_url(filters)?;
 
-        if let Some(end_date) = filters.end_date {
-            url.query_pairs_mut()
-                .append_pair("end_date", &end_date.to_rfc3339());
+        url.query_pairs_mut()
+            .append_pair("limit", &limit.to_string());
+        if let Some(cursor) = cursor {
+            url.query_pairs_mut().append_pair("cursor", cursor);
         }
 
-        if let Some(tags) = filters.tags {
-            url.query_pairs_mut().append_pair("tags", &tags.join(","));
+        self.get_json_cached(url.as_str()).await
+    }
+
+    /// URL of the search endpoint with the query and filters, without paging
+    fn search_url(&self, query: &str, filters: SearchFilters) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/clips/search", self.base_url))?;
+
+        url.query_pairs_mut().append_pair("q", query);
+        // Add highlight markers for search result highlighting
+        url.query_pairs_mut()
+            .append_pair("highlight_begin", "<mark>");
+        url.query_pairs_mut()
+            .append_pair("highlight_end", "</mark>");
+
+        if let Some(sort) = filters.sort {
+            url.query_pairs_mut().append_pair("sort", sort.as_str());
         }
 
-        let response = self.apply_auth(self.client.get(url)).send().await?;
+        append_filters(&mut url, filters);
+        Ok(url)
+    }
 
-        self.handle_response(response).await
+    /// URL of the list endpoint with the filters, without paging
+    fn list_url(&self, filters: SearchFilters) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/clips", self.base_url))?;
+        append_filters(&mut url, filters);
+        Ok(url)
+    }
+
+    /// Stream all clips matching the filters, fetching pages as needed
+    ///
+    /// Clips are returned in the same order as [`list_clips`](Self::list_clips)
+    /// (newest first unless `filters.sort_by` says otherwise).
+    /// Pages are fetched by cursor when the server returns one (date sorts),
+    /// so clips created while the stream is consumed don't cause duplicates.
+    /// Otherwise pages are fetched by number, and clips created meanwhile may
+    /// shift between pages so a clip can occasionally be returned twice.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clipper_client::{ClipperClient, SearchFilters};
+    /// use futures_util::TryStreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClipperClient::new("http://localhost:3000");
+    /// let clips: Vec<_> = client
+    ///     .list_clips_stream(SearchFilters::new().with_tags(vec!["work".to_string()]))
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_clips_stream(
+        &self,
+        filters: SearchFilters,
+    ) -> impl Stream<Item = Result<Clip>> + 'static {
+        let client = self.clone();
+
+        stream::try_unfold(Some(StreamPage::Number(1)), move |next| {
+            let client = client.clone();
+            let filters = filters.clone();
+            async move {
+                let Some(next) = next else {
+                    return Ok::<_, ClientError>(None);
+                };
+                let result = match &next {
+                    StreamPage::Number(page) => {
+                        client.list_clips(filters, *page, STREAM_PAGE_SIZE).await?
+                    }
+                    StreamPage::Cursor(cursor) => {
+                        client
+                            .list_clips_after(filters, Some(cursor), STREAM_PAGE_SIZE)
+                            .await?
+                    }
+                };
+                let following = match (result.next_cursor, next) {
+                    (Some(cursor), _) => Some(StreamPage::Cursor(cursor)),
+                    (None, StreamPage::Number(page)) => result
+                        .has_more
+                        .unwrap_or(page < result.total_pages)
+                        .then_some(StreamPage::Number(page + 1)),
+                    (None, StreamPage::Cursor(_)) => None,
+                };
+                Ok(Some((
+                    stream::iter(result.items.into_iter().map(Ok)),
+                    following,
+                )))
+            }
+        })
+        .try_flatten()
+    }
+
+    /// Call `f` for every clip matching the filters, fetching pages as needed
+    ///
+    /// Stops at the first error, either from the server or returned by `f`.
+    ///
+    /// # Returns
+    /// The number of clips processed
+    pub async fn for_each_clip<F, Fut>(&self, filters: SearchFilters, mut f: F) -> Result<usize>
+    where
+        F: FnMut(Clip) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let clips = self.list_clips_stream(filters);
+        futures_util::pin_mut!(clips);
+
+        let mut count = 0;
+        while let Some(clip) = clips.try_next().await? {
+            f(clip).await?;
+            count += 1;
+        }
+        Ok(count)
     }
 
     /// Download a clip's file attachment as bytes
@@ -495,7 +1013,7 @@ impl ClipperClient {
     /// * `id` - The clip ID
     pub async fn download_file(&self, id: &str) -> Result<Vec<u8>> {
         let url = format!("{}/clips/{}/file", self.base_url, id);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        let response = self.send(|| self.client.get(&url)).await?;
 
         match response.status() {
             StatusCode::OK => {
@@ -506,13 +1024,7 @@ impl ClipperClient {
                 "File not found for clip {}",
                 id
             ))),
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
-            }
+            _ => Err(error_from_response(response).await),
         }
     }
 
@@ -524,12 +1036,13 @@ impl ClipperClient {
     /// # Arguments
     /// * `id` - The clip ID
     /// * `writer` - An async writer to stream the content to
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn download_file_to_writer<W>(&self, id: &str, writer: &mut W) -> Result<u64>
     where
         W: AsyncWrite + Unpin,
     {
         let url = format!("{}/clips/{}/file", self.base_url, id);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        let response = self.send(|| self.client.get(&url)).await?;
 
         match response.status() {
             StatusCode::OK => {
@@ -538,19 +1051,11 @@ impl ClipperClient {
 
                 while let Some(chunk_result) = stream.next().await {
                     let chunk = chunk_result?;
-                    writer.write_all(&chunk).await.map_err(|e| {
-                        ClientError::ServerError {
-                            status: 0,
-                            message: format!("Failed to write to file: {}", e),
-                        }
-                    })?;
+                    writer.write_all(&chunk).await?;
                     total_bytes += chunk.len() as u64;
                 }
 
-                writer.flush().await.map_err(|e| ClientError::ServerError {
-                    status: 0,
-                    message: format!("Failed to flush file: {}", e),
-                })?;
+                writer.flush().await?;
 
                 Ok(total_bytes)
             }
@@ -558,13 +1063,7 @@ impl ClipperClient {
                 "File not found for clip {}",
                 id
             ))),
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
-            }
+            _ => Err(error_from_response(response).await),
         }
     }
 
@@ -574,52 +1073,428 @@ impl ClipperClient {
     /// * `id` - The clip ID
     pub async fn delete_clip(&self, id: &str) -> Result<()> {
         let url = format!("{}/clips/{}", self.base_url, id);
-        let response = self.apply_auth(self.client.delete(&url)).send().await?;
+        let response = self.send(|| self.client.delete(&url)).await?;
 
         match response.status() {
             StatusCode::NO_CONTENT => Ok(()),
             StatusCode::NOT_FOUND => Err(ClientError::NotFound(format!("Clip {} not found", id))),
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
-            }
+            _ => Err(error_from_response(response).await),
         }
     }
 
-    /// Create a short URL for a clip
+    /// Apply text transforms to a clip, storing the result as a new clip
     ///
-    /// # Arguments
-    /// * `id` - The clip ID
-    /// * `expires_in_hours` - Optional expiration time in hours (0 = no expiration, None = server default)
+    /// The transforms run in order, e.g. `[Transform::Trim, Transform::Base64Encode]`.
+    /// The new clip gets the source clip's tags and a `derived_from` link to it;
+    /// the source clip is left unchanged.
     ///
-    /// # Returns
-    /// Short URL metadata including the full URL
-    pub async fn create_short_url(
-        &self,
-        id: &str,
-        expires_in_hours: Option<u32>,
-    ) -> Result<ShortUrl> {
-        let url = format!("{}/clips/{}/short-url", self.base_url, id);
-        let request = CreateShortUrlRequest { expires_in_hours };
+    /// # Arguments
+    /// * `id` - The source clip ID
+    /// * `transforms` - Transforms to apply, at least one
+    pub async fn transform_clip(&self, id: &str, transforms: &[Transform]) -> Result<Clip> {
+        let url = format!("{}/clips/{}/transform", self.base_url, id);
+        let request = TransformClipRequest {
+            transforms: transforms.to_vec(),
+        };
 
-        let response = self
-            .apply_auth(self.client.post(&url).json(&request))
-            .send()
-            .await?;
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
 
         self.handle_response(response).await
     }
 
-    /// Export all clips to a file (streaming)
+    /// Translate a text clip, storing the translation as a new clip
     ///
-    /// Downloads the export archive from the server and streams it directly to the
-    /// specified file, without loading the entire archive into memory.
+    /// The server sends the clip to its configured translation provider and
+    /// fails with status 503 when none is set. The new clip gets the source
+    /// clip's tags and a `derived_from` link to it. Sensitive clips can't be
+    /// translated.
     ///
     /// # Arguments
-    /// * `output_path` - Path where the tar.gz archive will be saved
+    /// * `id` - The source clip ID
+    /// * `to` - Language to translate into, e.g. "de" or "pt-BR"
+    pub async fn translate_clip(&self, id: &str, to: &str) -> Result<Clip> {
+        let url = format!("{}/clips/{}/translate", self.base_url, id);
+
+        let response = self
+            .send(|| self.client.post(&url).query(&[("to", to)]))
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Split a text clip into one new clip per part
+    ///
+    /// Parts are trimmed and blank ones are dropped. The new clips get the
+    /// source clip's tags, language, content format and visibility.
+    ///
+    /// # Arguments
+    /// * `id` - The source clip ID
+    /// * `separator` - Where to split: lines, a delimiter or a regex
+    /// * `delete_source` - Delete the source clip once it's split
+    ///
+    /// # Returns
+    /// The new clips in the order of their parts
+    pub async fn split_clip(
+        &self,
+        id: &str,
+        separator: SplitSeparator,
+        delete_source: bool,
+    ) -> Result<Vec<Clip>> {
+        let url = format!("{}/clips/{}/split", self.base_url, id);
+        let request = SplitClipRequest {
+            separator,
+            keep_whitespace: false,
+            delete_source,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Link a clip to another clip
+    ///
+    /// Linking the same clips with the same relation again returns the
+    /// existing link.
+    ///
+    /// # Arguments
+    /// * `id` - The clip the link starts from
+    /// * `target_id` - The clip the link points to
+    /// * `relation` - Kind of relationship, e.g. "derived_from" or "reply_to"
+    pub async fn link_clips(&self, id: &str, target_id: &str, relation: &str) -> Result<ClipLink> {
+        let url = format!("{}/clips/{}/links", self.base_url, id);
+        let request = CreateClipLinkRequest {
+            target_id: target_id.to_string(),
+            relation: relation.to_string(),
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Get the links from and to a clip, oldest first
+    ///
+    /// # Arguments
+    /// * `id` - The clip ID
+    pub async fn get_clip_links(&self, id: &str) -> Result<Vec<ClipLink>> {
+        let url = format!("{}/clips/{}/links", self.base_url, id);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Remove a link from a clip
+    ///
+    /// # Arguments
+    /// * `id` - The ID of a clip at either end of the link
+    /// * `link_id` - The link ID
+    pub async fn delete_clip_link(&self, id: &str, link_id: &str) -> Result<()> {
+        let url = format!("{}/clips/{}/links/{}", self.base_url, id, link_id);
+        let response = self.send(|| self.client.delete(&url)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// List all collections, ordered by name
+    pub async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let url = format!("{}/collections", self.base_url);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Create an empty collection
+    ///
+    /// # Arguments
+    /// * `name` - Unique name of the collection
+    /// * `description` - Optional description
+    pub async fn create_collection(
+        &self,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<Collection> {
+        let url = format!("{}/collections", self.base_url);
+        let request = CreateCollectionRequest {
+            name: name.to_string(),
+            description,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Get a collection by ID
+    pub async fn get_collection(&self, id: &str) -> Result<Collection> {
+        let url = format!("{}/collections/{}", self.base_url, id);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Rename a collection and/or change its description
+    ///
+    /// # Arguments
+    /// * `id` - The collection ID
+    /// * `name` - New unique name, or None to keep the name
+    /// * `description` - New description (empty string clears it), or None to keep it
+    pub async fn update_collection(
+        &self,
+        id: &str,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<Collection> {
+        let url = format!("{}/collections/{}", self.base_url, id);
+        let request = UpdateCollectionRequest { name, description };
+
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Delete a collection; its clips are kept
+    pub async fn delete_collection(&self, id: &str) -> Result<()> {
+        let url = format!("{}/collections/{}", self.base_url, id);
+        let response = self.send(|| self.client.delete(&url)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// List the clips of a collection in their manual order
+    ///
+    /// # Arguments
+    /// * `id` - The collection ID
+    /// * `page` - Page number (starting from 1)
+    /// * `page_size` - Number of items per page
+    pub async fn list_collection_clips(
+        &self,
+        id: &str,
+        page: usize,
+        page_size: usize,
+    ) -> Result<PagedResult> {
+        let mut url = Url::parse(&format!("{}/collections/{}/clips", self.base_url, id))?;
+
+        url.query_pairs_mut().append_pair("page", &page.to_string());
+        url.query_pairs_mut()
+            .append_pair("page_size", &page_size.to_string());
+
+        let response = self.send(|| self.client.get(url.clone())).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Add a clip to a collection
+    ///
+    /// A clip that is already in the collection is moved to `position`, or
+    /// left in place if no position is given.
+    ///
+    /// # Arguments
+    /// * `id` - The collection ID
+    /// * `clip_id` - The clip to add
+    /// * `position` - Zero-based index to insert the clip at, or None to append it
+    pub async fn add_clip_to_collection(
+        &self,
+        id: &str,
+        clip_id: &str,
+        position: Option<usize>,
+    ) -> Result<()> {
+        let url = format!("{}/collections/{}/clips", self.base_url, id);
+        let request = AddCollectionClipRequest {
+            clip_id: clip_id.to_string(),
+            position,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// Set the manual order of a collection
+    ///
+    /// # Arguments
+    /// * `id` - The collection ID
+    /// * `clip_ids` - Every clip of the collection exactly once, in the new order
+    pub async fn reorder_collection(&self, id: &str, clip_ids: &[String]) -> Result<()> {
+        let url = format!("{}/collections/{}/clips", self.base_url, id);
+        let request = ReorderCollectionRequest {
+            clip_ids: clip_ids.to_vec(),
+        };
+
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// Remove a clip from a collection; the clip itself is kept
+    pub async fn remove_clip_from_collection(&self, id: &str, clip_id: &str) -> Result<()> {
+        let url = format!("{}/collections/{}/clips/{}", self.base_url, id, clip_id);
+        let response = self.send(|| self.client.delete(&url)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// Get the collections containing a clip, ordered by name
+    pub async fn get_clip_collections(&self, id: &str) -> Result<Vec<Collection>> {
+        let url = format!("{}/clips/{}/collections", self.base_url, id);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// List all templates, ordered by name
+    pub async fn list_templates(&self) -> Result<Vec<Template>> {
+        let url = format!("{}/templates", self.base_url);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Create a template
+    ///
+    /// # Arguments
+    /// * `name` - Unique name of the template
+    /// * `description` - Optional description
+    /// * `content` - Text with `{{name}}` or `{{name|default}}` placeholders
+    /// * `tags` - Tags given to the clips created from the template
+    pub async fn create_template(
+        &self,
+        name: &str,
+        description: Option<String>,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Template> {
+        let url = format!("{}/templates", self.base_url);
+        let request = CreateTemplateRequest {
+            name: name.to_string(),
+            description,
+            content,
+            tags,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Get a template by ID
+    pub async fn get_template(&self, id: &str) -> Result<Template> {
+        let url = format!("{}/templates/{}", self.base_url, id);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Change a template; fields left as None are kept
+    ///
+    /// # Arguments
+    /// * `id` - The template ID
+    /// * `name` - New unique name
+    /// * `description` - New description (empty string clears it)
+    /// * `content` - New content
+    /// * `tags` - New tags for the clips created from the template
+    pub async fn update_template(
+        &self,
+        id: &str,
+        name: Option<String>,
+        description: Option<String>,
+        content: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<Template> {
+        let url = format!("{}/templates/{}", self.base_url, id);
+        let request = UpdateTemplateRequest {
+            name,
+            description,
+            content,
+            tags,
+        };
+
+        let response = self.send(|| self.client.put(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Delete a template; clips created from it are kept
+    pub async fn delete_template(&self, id: &str) -> Result<()> {
+        let url = format!("{}/templates/{}", self.base_url, id);
+        let response = self.send(|| self.client.delete(&url)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// Create a text clip from a template
+    ///
+    /// # Arguments
+    /// * `id` - The template ID
+    /// * `values` - Placeholder values by name; placeholders without a value
+    ///   use their default, and the server rejects any that have neither
+    /// * `tags` - Tags added to the template's own
+    /// * `additional_notes` - Optional additional notes for the clip
+    pub async fn instantiate_template(
+        &self,
+        id: &str,
+        values: HashMap<String, String>,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+    ) -> Result<Clip> {
+        let url = format!("{}/templates/{}/instantiate", self.base_url, id);
+        let request = InstantiateTemplateRequest {
+            values,
+            tags,
+            additional_notes,
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Create a short URL for a clip
+    ///
+    /// # Arguments
+    /// * `id` - The clip ID
+    /// * `expires_in_hours` - Optional expiration time in hours (0 = no expiration, None = server default)
+    ///
+    /// # Returns
+    /// Short URL metadata including the full URL
+    pub async fn create_short_url(
+        &self,
+        id: &str,
+        expires_in_hours: Option<u32>,
+    ) -> Result<ShortUrl> {
+        let url = format!("{}/clips/{}/short-url", self.base_url, id);
+        let request = CreateShortUrlRequest { expires_in_hours };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Export all clips to a file (streaming)
+    ///
+    /// Downloads the export archive from the server and streams it directly to the
+    /// specified file, without loading the entire archive into memory.
+    ///
+    /// # Arguments
+    /// * `output_path` - Path where the tar.gz archive will be saved
     ///
     /// # Returns
     /// The number of bytes written
@@ -635,9 +1510,27 @@ impl ClipperClient {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn export_to_file<P: AsRef<Path>>(&self, output_path: P) -> Result<u64> {
         let url = format!("{}/export", self.base_url);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        self.download_export(&url, output_path).await
+    }
+
+    /// Export all clips except sensitive ones (tagged `$sensitive`) to a file
+    /// (streaming), like [`export_to_file`](Self::export_to_file)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn export_non_sensitive_to_file<P: AsRef<Path>>(
+        &self,
+        output_path: P,
+    ) -> Result<u64> {
+        let url = format!("{}/export?exclude_sensitive=true", self.base_url);
+        self.download_export(&url, output_path).await
+    }
+
+    /// Stream the export archive at `url` to `output_path`
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn download_export<P: AsRef<Path>>(&self, url: &str, output_path: P) -> Result<u64> {
+        let response = self.send(|| self.client.get(url)).await?;
 
         match response.status() {
             StatusCode::OK => {
@@ -655,13 +1548,7 @@ impl ClipperClient {
                 file.flush().await?;
                 Ok(bytes_written)
             }
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
-            }
+            _ => Err(error_from_response(response).await),
         }
     }
 
@@ -675,9 +1562,10 @@ impl ClipperClient {
     ///
     /// # Returns
     /// The number of bytes written
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn export_to_writer<W: AsyncWrite + Unpin>(&self, mut writer: W) -> Result<u64> {
         let url = format!("{}/export", self.base_url);
-        let response = self.apply_auth(self.client.get(&url)).send().await?;
+        let response = self.send(|| self.client.get(&url)).await?;
 
         match response.status() {
             StatusCode::OK => {
@@ -694,13 +1582,7 @@ impl ClipperClient {
                 writer.flush().await?;
                 Ok(bytes_written)
             }
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
-            }
+            _ => Err(error_from_response(response).await),
         }
     }
 
@@ -725,6 +1607,7 @@ impl ClipperClient {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn import_from_file<P: AsRef<Path>>(&self, input_path: P) -> Result<ImportResult> {
         let url = format!("{}/import", self.base_url);
 
@@ -735,10 +1618,14 @@ impl ClipperClient {
         let file_part = reqwest::multipart::Part::stream(body).file_name("archive.tar.gz");
         let form = reqwest::multipart::Form::new().part("file", file_part);
 
-        let response = self
-            .apply_auth(self.client.post(&url).multipart(form))
-            .send()
-            .await?;
+        // A streamed body can't be replayed, so this request is sent only once
+        let request = self
+            .apply_headers(
+                self.client.post(&url).multipart(form),
+                &telemetry::new_request_id(),
+            )
+            .build()?;
+        let response = self.execute(request).await?;
 
         self.handle_response(response).await
     }
@@ -752,6 +1639,7 @@ impl ClipperClient {
     ///
     /// # Returns
     /// Import statistics including counts of imported and skipped clips
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn import_from_reader<R>(&self, reader: R) -> Result<ImportResult>
     where
         R: AsyncRead + Send + Sync + 'static,
@@ -764,10 +1652,14 @@ impl ClipperClient {
         let file_part = reqwest::multipart::Part::stream(body).file_name("archive.tar.gz");
         let form = reqwest::multipart::Form::new().part("file", file_part);
 
-        let response = self
-            .apply_auth(self.client.post(&url).multipart(form))
-            .send()
-            .await?;
+        // A streamed body can't be replayed, so this request is sent only once
+        let request = self
+            .apply_headers(
+                self.client.post(&url).multipart(form),
+                &telemetry::new_request_id(),
+            )
+            .build()?;
+        let response = self.execute(request).await?;
 
         self.handle_response(response).await
     }
@@ -798,7 +1690,7 @@ impl ClipperClient {
         url.query_pairs_mut()
             .append_pair("page_size", &page_size.to_string());
 
-        let response = self.apply_auth(self.client.get(url)).send().await?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
 
         self.handle_response(response).await
     }
@@ -836,7 +1728,157 @@ impl ClipperClient {
         url.query_pairs_mut()
             .append_pair("page_size", &page_size.to_string());
 
-        let response = self.apply_auth(self.client.get(url)).send().await?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Get the number of clips using each tag, most used first
+    pub async fn tag_stats(&self) -> Result<Vec<TagStats>> {
+        let url = format!("{}/tags/stats", self.base_url);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Get the number of clips created on each day of the last `months`
+    /// months and their most used tags, for an activity heatmap
+    ///
+    /// # Arguments
+    /// * `months` - Number of months to count, up to and including today (1 to 24)
+    /// * `tz_offset_minutes` - Time zone the days are counted in, in minutes east of UTC
+    /// * `top_tags` - Number of most used tags to return
+    pub async fn activity_stats(
+        &self,
+        months: u32,
+        tz_offset_minutes: i32,
+        top_tags: usize,
+    ) -> Result<ActivityStats> {
+        let url = format!("{}/stats/activity", self.base_url);
+        let response = self
+            .send(|| {
+                self.client.get(&url).query(&[
+                    ("months", months.to_string()),
+                    ("tz_offset", tz_offset_minutes.to_string()),
+                    ("top_tags", top_tags.to_string()),
+                ])
+            })
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// List the devices clips were created on, most recently seen first
+    pub async fn list_devices(&self) -> Result<Vec<Device>> {
+        let url = format!("{}/devices", self.base_url);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Put a clip on the clipboard of a connected device
+    ///
+    /// # Arguments
+    /// * `device` - Name the device subscribed to notifications under, as in `list_devices`
+    /// * `clip_id` - ID of the clip to push
+    ///
+    /// # Returns
+    /// The number of the device's connections the clip was sent to. Fails
+    /// with `NotFound` if the device isn't connected.
+    pub async fn push_to_device_clipboard(
+        &self,
+        device: &str,
+        clip_id: &str,
+    ) -> Result<PushToClipboardResult> {
+        // Device names are hostnames and may need escaping
+        let mut url = Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| ClientError::BadRequest(format!("Invalid server URL: {}", self.base_url)))?
+            .pop_if_empty()
+            .extend(["devices", device, "clipboard"]);
+        let request = PushToClipboardRequest {
+            clip_id: clip_id.to_string(),
+        };
+        let response = self
+            .send(|| self.client.post(url.clone()).json(&request))
+            .await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Issue a pairing code for a new device (requires the shared bearer token)
+    pub async fn create_pairing_code(&self) -> Result<PairingCode> {
+        let url = format!("{}/pairing", self.base_url);
+        let response = self.send(|| self.client.post(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Redeem a pairing code for a device token. The server doesn't require
+    /// a token for this, so a new device can call it before it has one.
+    ///
+    /// # Arguments
+    /// * `code` - The pairing code shown by another client
+    /// * `name` - Name of this device in the paired device list
+    pub async fn claim_pairing_code(&self, code: &str, name: &str) -> Result<PairedDeviceToken> {
+        let url = format!("{}/pairing/claim", self.base_url);
+        let request = ClaimPairingCodeRequest {
+            code: code.to_string(),
+            name: name.to_string(),
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// List paired devices, most recently paired first (requires the shared bearer token)
+    pub async fn list_paired_devices(&self) -> Result<Vec<PairedDevice>> {
+        let url = format!("{}/paired-devices", self.base_url);
+        let response = self.send(|| self.client.get(&url)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Revoke a paired device so its token stops working (requires the shared bearer token)
+    pub async fn revoke_paired_device(&self, id: &str) -> Result<()> {
+        let url = format!("{}/paired-devices/{}", self.base_url, id);
+        let response = self.send(|| self.client.delete(&url)).await?;
+
+        match response.status() {
+            StatusCode::NO_CONTENT => Ok(()),
+            _ => Err(error_from_response(response).await),
+        }
+    }
+
+    /// Rename a tag on all clips that use it
+    ///
+    /// # Arguments
+    /// * `from` - The current tag text
+    /// * `to` - The new tag text
+    pub async fn rename_tag(&self, from: &str, to: &str) -> Result<RenameTagResult> {
+        let url = format!("{}/tags/rename", self.base_url);
+        let request = RenameTagRequest {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+
+        let response = self.send(|| self.client.post(&url).json(&request)).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// Delete old clips under the server's cleanup policy now, whether or
+    /// not periodic cleanup is enabled (requires the admin token, or the
+    /// shared token if the server has none)
+    ///
+    /// # Arguments
+    /// * `dry_run` - List the clips that would be deleted without deleting them
+    pub async fn run_cleanup(&self, dry_run: bool) -> Result<CleanupResult> {
+        let url = format!("{}/admin/cleanup", self.base_url);
+        let response = self
+            .send(|| self.client.post(&url).query(&[("dry_run", dry_run)]))
+            .await?;
 
         self.handle_response(response).await
     }
@@ -848,29 +1890,218 @@ impl ClipperClient {
     ///
     /// # Returns
     /// A task handle that runs the WebSocket connection
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn subscribe_notifications(
         &self,
         channel: mpsc::UnboundedSender<ClipNotification>,
     ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-        let ws_url = self
-            .base_url
-            .replace("http://", "ws://")
-            .replace("https://", "wss://");
-        let ws_url = format!("{}/ws", ws_url);
+        let ws_stream = self.connect_notifications().await?;
+
+        let handle = tokio::spawn(async move {
+            forward_notifications(ws_stream, &channel, |_| {}).await?;
+            Ok(())
+        });
+
+        Ok(handle)
+    }
+
+    /// Subscribe to real-time notifications, reconnecting automatically when
+    /// the connection drops (e.g. when the server restarts).
+    ///
+    /// Reconnection uses exponential backoff as configured in `config`. When
+    /// `config.recover_missed` is set, clips created or edited while
+    /// disconnected are fetched after reconnecting and delivered as `NewClip`
+    /// or `UpdatedClip` notifications. Recovery starts from the latest server
+    /// timestamp received (minus a short overlap), so it doesn't depend on the
+    /// local clock, and clips that were already delivered are skipped. If it
+    /// fails, `RecoveryFailed` is reported. Deletions that happened while
+    /// disconnected cannot be recovered; use the `Connected` status to refresh
+    /// views if needed.
+    ///
+    /// # Arguments
+    /// * `channel` - A tokio mpsc sender to push notifications to
+    /// * `config` - Backoff and recovery settings
+    /// * `on_status` - Called whenever the connection status changes
+    ///
+    /// # Returns
+    /// A task handle that runs until the channel is closed, or fails once
+    /// `config.max_attempts` consecutive reconnection attempts have failed
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn subscribe_notifications_with_reconnect(
+        &self,
+        channel: mpsc::UnboundedSender<ClipNotification>,
+        config: ReconnectConfig,
+        on_status: impl Fn(ConnectionStatus) + Send + Sync + 'static,
+    ) -> tokio::task::JoinHandle<Result<()>> {
+        let client = self.clone();
+
+        tokio::spawn(async move {
+            let mut attempt: u32 = 0;
+            // Latest server time seen, from which clips may have been missed
+            let mut watermark: Option<DateTime<Utc>> = None;
+            // Recently delivered clips, to avoid duplicates after recovery
+            let mut recent: VecDeque<(String, Option<DateTime<Utc>>)> = VecDeque::new();
+
+            loop {
+                on_status(ConnectionStatus::Connecting);
+
+                let error = match client.connect_notifications().await {
+                    Ok(ws_stream) => {
+                        attempt = 0;
+                        on_status(ConnectionStatus::Connected);
+
+                        if config.recover_missed {
+                            let recovered = match watermark {
+                                Some(since) => client
+                                    .recover_missed_clips(
+                                        since - chrono::Duration::seconds(RECOVERY_OVERLAP_SECS),
+                                    )
+                                    .await
+                                    .map(Some),
+                                // First connection: only changes from now on are missed later
+                                None => client.latest_update().await.map(|latest| {
+                                    watermark = Some(latest.unwrap_or(DateTime::UNIX_EPOCH));
+                                    None
+                                }),
+                            };
+                            match recovered {
+                                Ok(missed) => {
+                                    for notification in missed.into_iter().flatten() {
+                                        let Some(notification) =
+                                            dedup_recovered(&recent, notification)
+                                        else {
+                                            continue;
+                                        };
+                                        track_notification(
+                                            &mut watermark,
+                                            &mut recent,
+                                            &notification,
+                                        );
+                                        if channel.send(notification).is_err() {
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+                                Err(e) => on_status(ConnectionStatus::RecoveryFailed {
+                                    error: e.to_string(),
+                                }),
+                            }
+                        }
+
+                        let result = forward_notifications(ws_stream, &channel, |notification| {
+                            track_notification(&mut watermark, &mut recent, notification);
+                        })
+                        .await;
+
+                        match result {
+                            Ok(StreamEnd::ChannelClosed) => return Ok(()),
+                            Ok(StreamEnd::ConnectionClosed) => None,
+                            Err(e) => Some(e.to_string()),
+                        }
+                    }
+                    Err(e) => Some(e.to_string()),
+                };
+
+                on_status(ConnectionStatus::Disconnected {
+                    error: error.clone(),
+                });
+
+                if channel.is_closed() {
+                    return Ok(());
+                }
+
+                attempt += 1;
+                if config.max_attempts.is_some_and(|max| attempt > max) {
+                    return Err(ClientError::WebSocket(
+                        error.unwrap_or_else(|| "Connection closed".to_string()),
+                    ));
+                }
+
+                let delay = config.delay_for_attempt(attempt);
+                on_status(ConnectionStatus::Reconnecting { attempt, delay });
+                tokio::time::sleep(delay).await;
+            }
+        })
+    }
+
+    /// Time of the most recent change on the server, or None if there are no clips
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn latest_update(&self) -> Result<Option<DateTime<Utc>>> {
+        let filters = SearchFilters::new()
+            .with_sort_by(SortField::UpdatedAt, SortDirection::Desc)
+            .with_skip_total();
+        let page = self.list_clips(filters, 1, 1).await?;
+        Ok(page.items.first().and_then(clip_updated_at))
+    }
+
+    /// Fetch clips created or updated since the given time as `NewClip` or
+    /// `UpdatedClip` notifications, least recently updated first
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn recover_missed_clips(&self, since: DateTime<Utc>) -> Result<Vec<ClipNotification>> {
+        let filters = SearchFilters::new()
+            .with_updated_since(since)
+            .with_sort_by(SortField::UpdatedAt, SortDirection::Asc);
+        let clips: Vec<Clip> = self.list_clips_stream(filters).try_collect().await?;
+
+        Ok(clips
+            .into_iter()
+            .filter_map(|clip| {
+                let updated_at = clip_updated_at(&clip).map(|time| time.to_rfc3339());
+                let created_since = DateTime::parse_from_rfc3339(&clip.created_at)
+                    .is_ok_and(|created_at| created_at >= since);
+                if !created_since {
+                    Some(ClipNotification::UpdatedClip {
+                        id: clip.id,
+                        updated_at,
+                    })
+                } else if self.matches_sync_tags(&clip.tags) {
+                    Some(ClipNotification::NewClip {
+                        id: clip.id,
+                        content: clip.content,
+                        tags: clip.tags,
+                        updated_at,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
 
-        let (ws_stream, _) = self.connect_websocket(&ws_url).await?;
+    /// Turn a certificate verification failure into `CertificateUntrusted` with the
+    /// fingerprint of the certificate the server presents, or return `fallback` if
+    /// the certificate can't be fetched.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn untrusted_certificate_error(&self, fallback: ClientError) -> ClientError {
+        let Ok(url) = Url::parse(&self.base_url) else {
+            return fallback;
+        };
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return fallback;
+        };
+
+        match fetch_server_certificate(host, port).await {
+            Ok(info) => ClientError::CertificateUntrusted {
+                fingerprint: info.fingerprint,
+            },
+            Err(_) => fallback,
+        }
+    }
 
-        let (mut write, mut read) = ws_stream.split();
+    /// Open the notification WebSocket and authenticate if a token is set
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn connect_notifications(&self) -> Result<WsStream> {
+        let (mut ws_stream, _) = self.connect_websocket(&self.websocket_url()).await?;
 
         // If we have a token, send auth message and wait for response
         if let Some(token) = &self.token {
             let auth_msg = WsAuthRequest::Auth {
-                token: token.clone(),
+                token: token.expose().to_string(),
             };
             let auth_json = serde_json::to_string(&auth_msg)
                 .map_err(|e| ClientError::WebSocket(format!("Failed to serialize auth: {}", e)))?;
 
-            write
+            ws_stream
                 .send(Message::Text(auth_json.into()))
                 .await
                 .map_err(|e| ClientError::WebSocket(format!("Failed to send auth: {}", e)))?;
@@ -878,7 +2109,7 @@ impl ClipperClient {
             // Wait for auth response with timeout
             let auth_timeout = Duration::from_secs(10);
             let auth_result = tokio::time::timeout(auth_timeout, async {
-                while let Some(msg) = read.next().await {
+                while let Some(msg) = ws_stream.next().await {
                     match msg {
                         Ok(Message::Text(text)) => {
                             // Try to parse as auth response
@@ -887,7 +2118,7 @@ impl ClipperClient {
                                     return Ok(());
                                 }
                                 Ok(WsAuthResponse::AuthError { message }) => {
-                                    return Err(ClientError::WebSocket(format!(
+                                    return Err(ClientError::Unauthorized(format!(
                                         "WebSocket auth failed: {}",
                                         message
                                     )));
@@ -930,70 +2161,19 @@ impl ClipperClient {
                 }
                 Ok(Err(e)) => return Err(e),
                 Err(_) => {
-                    return Err(ClientError::WebSocket("Auth timeout".to_string()));
+                    return Err(ClientError::Timeout);
                 }
             }
         }
 
-        let handle = tokio::spawn(async move {
-            loop {
-                // Use timeout to detect stale connections
-                // Server sends ping every 30s, so we should receive something within 60s
-                let msg = tokio::time::timeout(CONNECTION_TIMEOUT, read.next()).await;
-
-                match msg {
-                    Ok(Some(Ok(Message::Text(text)))) => {
-                        match serde_json::from_str::<ClipNotification>(&text) {
-                            Ok(notification) => {
-                                if channel.send(notification).is_err() {
-                                    // Channel closed, exit loop
-                                    break;
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("Failed to parse notification: {}", e);
-                            }
-                        }
-                    }
-                    Ok(Some(Ok(Message::Ping(data)))) => {
-                        // Respond to ping with pong to keep connection alive
-                        if write.send(Message::Pong(data)).await.is_err() {
-                            break;
-                        }
-                    }
-                    Ok(Some(Ok(Message::Pong(_)))) => {
-                        // Server responded to our ping (if we sent one), connection is alive
-                    }
-                    Ok(Some(Ok(Message::Close(_)))) => {
-                        break;
-                    }
-                    Ok(Some(Err(e))) => {
-                        return Err(ClientError::WebSocket(e.to_string()));
-                    }
-                    Ok(None) => {
-                        // Stream ended
-                        break;
-                    }
-                    Err(_) => {
-                        // Timeout - no message received within CONNECTION_TIMEOUT
-                        eprintln!("WebSocket connection timeout - no messages received");
-                        return Err(ClientError::WebSocket(
-                            "Connection timeout - no heartbeat received".to_string(),
-                        ));
-                    }
-                    _ => {}
-                }
-            }
-            Ok(())
-        });
-
-        Ok(handle)
+        Ok(ws_stream)
     }
 
     /// Connect to a WebSocket URL with proper TLS handling
     ///
     /// Note: Authentication is handled via message-based auth after connection,
     /// not via headers, since WebSocket doesn't reliably support Authorization headers.
+    #[cfg(not(target_arch = "wasm32"))]
     async fn connect_websocket(
         &self,
         url: &str,
@@ -1052,9 +2232,20 @@ impl ClipperClient {
 
             let connector = Connector::Rustls(config);
 
-            tokio_tungstenite::connect_async_tls_with_config(request, None, false, Some(connector))
-                .await
-                .map_err(|e| ClientError::WebSocket(e.to_string()))
+            match tokio_tungstenite::connect_async_tls_with_config(
+                request,
+                None,
+                false,
+                Some(connector),
+            )
+            .await
+            {
+                Ok(connection) => Ok(connection),
+                Err(e) if is_certificate_error(&e) => Err(self
+                    .untrusted_certificate_error(ClientError::WebSocket(e.to_string()))
+                    .await),
+                Err(e) => Err(ClientError::WebSocket(e.to_string())),
+            }
         } else {
             // For WS connections, use the simple connect_async
             tokio_tungstenite::connect_async(request)
@@ -1063,6 +2254,51 @@ impl ClipperClient {
         }
     }
 
+    /// GET a JSON resource, revalidating against the response cache if enabled.
+    ///
+    /// A cached body is only used after the server confirms it is still current
+    /// with `304 Not Modified`.
+    async fn get_json_cached<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let Some(cache) = &self.cache else {
+            let response = self.send(|| self.client.get(url)).await?;
+            return self.handle_response(response).await;
+        };
+
+        let cached = cache.get(url);
+        let response = self
+            .send(|| {
+                let request = self.client.get(url);
+                match &cached {
+                    Some(cached) => request.header(reqwest::header::IF_NONE_MATCH, &cached.etag),
+                    None => request,
+                }
+            })
+            .await?;
+
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => Ok(serde_json::from_str(&cached.body)?),
+            (StatusCode::OK, _) => {
+                let etag = response
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|v| v.to_string());
+                let body = response.text().await?;
+                let data = serde_json::from_str(&body)?;
+                match etag {
+                    Some(etag) => cache.put(url, etag, body),
+                    None => cache.remove(url),
+                }
+                Ok(data)
+            }
+            (StatusCode::NOT_FOUND, _) => {
+                cache.remove(url);
+                self.handle_response(response).await
+            }
+            _ => self.handle_response(response).await,
+        }
+    }
+
     async fn handle_response<T: serde::de::DeserializeOwned>(
         &self,
         response: reqwest::Response,
@@ -1072,35 +2308,242 @@ impl ClipperClient {
                 let data = response.json().await?;
                 Ok(data)
             }
-            StatusCode::NOT_FOUND => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::NotFound(error_text))
+            _ => Err(error_from_response(response).await),
+        }
+    }
+}
+
+/// Append the filter query parameters shared by listing and searching
+fn append_filters(url: &mut Url, filters: SearchFilters) {
+    if let Some(start_date) = filters.start_date {
+        url.query_pairs_mut()
+            .append_pair("start_date", &start_date.to_rfc3339());
+    }
+
+    if let Some(end_date) = filters.end_date {
+        url.query_pairs_mut()
+            .append_pair("end_date", &end_date.to_rfc3339());
+    }
+
+    if let Some(tags) = filters.tags {
+        url.query_pairs_mut().append_pair("tags", &tags.join(","));
+    }
+
+    if let Some(updated_since) = filters.updated_since {
+        url.query_pairs_mut()
+            .append_pair("updated_since", &updated_since.to_rfc3339());
+    }
+
+    if let Some(source_device) = filters.source_device {
+        url.query_pairs_mut()
+            .append_pair("source_device", &source_device);
+    }
+
+    if let Some(sort_by) = filters.sort_by {
+        url.query_pairs_mut()
+            .append_pair("sort_by", sort_by.as_str());
+    }
+
+    if let Some(order) = filters.order {
+        url.query_pairs_mut().append_pair("order", order.as_str());
+    }
+
+    if filters.skip_total {
+        url.query_pairs_mut().append_pair("skip_total", "true");
+    }
+}
+
+/// Convert an unsuccessful response into the matching error kind
+async fn error_from_response(response: reqwest::Response) -> ClientError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    match status {
+        StatusCode::NOT_FOUND => ClientError::NotFound(body),
+        StatusCode::BAD_REQUEST => ClientError::BadRequest(body),
+        StatusCode::UNAUTHORIZED => ClientError::Unauthorized(body),
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::TOO_MANY_REQUESTS => {
+            ClientError::QuotaExceeded(body)
+        }
+        StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => ClientError::Timeout,
+        _ => ClientError::ServerError {
+            status: status.as_u16(),
+            body,
+        },
+    }
+}
+
+/// Whether the connection to the server could not be established.
+/// Browsers don't report this separately from other request failures.
+fn is_connect_error(error: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    return error.is_connect();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = error;
+        false
+    }
+}
+
+/// Whether a response status indicates a transient failure between client and server.
+/// `503` is not included because the server uses it for disabled features.
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// How a notification stream ended without an error
+#[cfg(not(target_arch = "wasm32"))]
+enum StreamEnd {
+    /// The receiving side of the channel was dropped
+    ChannelClosed,
+    /// The server closed the connection
+    ConnectionClosed,
+}
+
+/// Forward notifications from a WebSocket to the channel until either side closes.
+/// `on_notification` is called for every notification before it is sent.
+#[cfg(not(target_arch = "wasm32"))]
+async fn forward_notifications(
+    ws_stream: WsStream,
+    channel: &mpsc::UnboundedSender<ClipNotification>,
+    mut on_notification: impl FnMut(&ClipNotification),
+) -> Result<StreamEnd> {
+    let (mut write, mut read) = ws_stream.split();
+
+    loop {
+        // Use timeout to detect stale connections
+        // Server sends ping every 30s, so we should receive something within 60s
+        let msg = tokio::time::timeout(CONNECTION_TIMEOUT, read.next()).await;
+
+        match msg {
+            Ok(Some(Ok(Message::Text(text)))) => {
+                match serde_json::from_str::<ClipNotification>(&text) {
+                    Ok(notification) => {
+                        on_notification(&notification);
+                        if channel.send(notification).is_err() {
+                            // Channel closed, exit loop
+                            return Ok(StreamEnd::ChannelClosed);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to parse notification: {}", e);
+                    }
+                }
+            }
+            Ok(Some(Ok(Message::Ping(data)))) => {
+                // Respond to ping with pong to keep connection alive
+                let sent = write.send(Message::Pong(data)).await;
+                if sent.is_err() {
+                    return Ok(StreamEnd::ConnectionClosed);
+                }
+            }
+            Ok(Some(Ok(Message::Pong(_)))) => {
+                // Server responded to our ping (if we sent one), connection is alive
             }
-            StatusCode::BAD_REQUEST => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::BadRequest(error_text))
+            Ok(Some(Ok(Message::Close(_)))) => {
+                return Ok(StreamEnd::ConnectionClosed);
             }
-            StatusCode::UNAUTHORIZED => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::Unauthorized(error_text))
+            Ok(Some(Err(e))) => {
+                return Err(ClientError::WebSocket(e.to_string()));
             }
-            status => {
-                let error_text = response.text().await.unwrap_or_default();
-                Err(ClientError::ServerError {
-                    status: status.as_u16(),
-                    message: error_text,
-                })
+            Ok(None) => {
+                // Stream ended
+                return Ok(StreamEnd::ConnectionClosed);
             }
+            Err(_) => {
+                // Timeout - no message received within CONNECTION_TIMEOUT
+                eprintln!("WebSocket connection timeout - no messages received");
+                return Err(ClientError::Timeout);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// When a clip was last changed, according to the server
+#[cfg(not(target_arch = "wasm32"))]
+fn clip_updated_at(clip: &Clip) -> Option<DateTime<Utc>> {
+    let time = clip.updated_at.as_deref().unwrap_or(&clip.created_at);
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// ID and server timestamp of a `NewClip` or `UpdatedClip` notification
+#[cfg(not(target_arch = "wasm32"))]
+fn notification_clip(notification: &ClipNotification) -> Option<(&str, Option<DateTime<Utc>>)> {
+    let (id, updated_at) = match notification {
+        ClipNotification::NewClip { id, updated_at, .. }
+        | ClipNotification::UpdatedClip { id, updated_at } => (id, updated_at),
+        _ => return None,
+    };
+    let updated_at = updated_at
+        .as_deref()
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+        .map(|time| time.with_timezone(&Utc));
+    Some((id, updated_at))
+}
+
+/// Advance the watermark past a delivered notification and remember its clip,
+/// keeping only the most recent ones
+#[cfg(not(target_arch = "wasm32"))]
+fn track_notification(
+    watermark: &mut Option<DateTime<Utc>>,
+    recent: &mut VecDeque<(String, Option<DateTime<Utc>>)>,
+    notification: &ClipNotification,
+) {
+    let Some((id, updated_at)) = notification_clip(notification) else {
+        return;
+    };
+    if let Some(updated_at) = updated_at
+        && watermark.is_none_or(|watermark| updated_at > watermark)
+    {
+        *watermark = Some(updated_at);
+    }
+    if recent.len() >= RECENT_CLIPS_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back((id.to_string(), updated_at));
+}
+
+/// Drop a recovered notification for a change that was already delivered.
+/// A recovered `NewClip` for a clip that was delivered before, but has changed
+/// since, becomes an `UpdatedClip`.
+#[cfg(not(target_arch = "wasm32"))]
+fn dedup_recovered(
+    recent: &VecDeque<(String, Option<DateTime<Utc>>)>,
+    notification: ClipNotification,
+) -> Option<ClipNotification> {
+    let Some((id, updated_at)) = notification_clip(&notification) else {
+        return Some(notification);
+    };
+    let Some((_, delivered_at)) = recent.iter().rev().find(|(recent_id, _)| recent_id == id) else {
+        return Some(notification);
+    };
+    if let (Some(delivered_at), Some(updated_at)) = (delivered_at, updated_at)
+        && *delivered_at >= updated_at
+    {
+        return None;
+    }
+
+    match notification {
+        ClipNotification::NewClip { id, updated_at, .. } => {
+            Some(ClipNotification::UpdatedClip { id, updated_at })
         }
+        notification => Some(notification),
     }
 }
 
 /// Certificate verifier that accepts any certificate (for development only)
-#[cfg(feature = "danger-accept-invalid-certs")]
+#[cfg(all(feature = "danger-accept-invalid-certs", not(target_arch = "wasm32")))]
 #[derive(Debug)]
 struct NoVerifier;
 
-#[cfg(feature = "danger-accept-invalid-certs")]
+#[cfg(all(feature = "danger-accept-invalid-certs", not(target_arch = "wasm32")))]
 impl rustls::client::danger::ServerCertVerifier for NoVerifier {
     fn verify_server_cert(
         &self,
@@ -1146,3 +2589,68 @@ impl rustls::client::danger::ServerCertVerifier for NoVerifier {
         ]
     }
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn new_clip(id: &str, updated_at: &str) -> ClipNotification {
+        ClipNotification::NewClip {
+            id: id.to_string(),
+            content: String::new(),
+            tags: vec![],
+            updated_at: Some(updated_at.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_track_notification_keeps_latest_server_time() {
+        let mut watermark = None;
+        let mut recent = VecDeque::new();
+
+        track_notification(
+            &mut watermark,
+            &mut recent,
+            &new_clip("a", "2024-01-01T00:00:05Z"),
+        );
+        track_notification(
+            &mut watermark,
+            &mut recent,
+            &new_clip("b", "2024-01-01T00:00:02Z"),
+        );
+        track_notification(
+            &mut watermark,
+            &mut recent,
+            &ClipNotification::DeletedClip {
+                id: "c".to_string(),
+            },
+        );
+
+        assert_eq!(watermark.unwrap().to_rfc3339(), "2024-01-01T00:00:05+00:00");
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn test_dedup_recovered_skips_delivered_changes() {
+        let mut watermark = None;
+        let mut recent = VecDeque::new();
+        track_notification(
+            &mut watermark,
+            &mut recent,
+            &new_clip("a", "2024-01-01T00:00:05Z"),
+        );
+
+        // Stored before the notification was published: already delivered
+        assert!(dedup_recovered(&recent, new_clip("a", "2024-01-01T00:00:04Z")).is_none());
+        // Changed again while disconnected: delivered as an update
+        assert!(matches!(
+            dedup_recovered(&recent, new_clip("a", "2024-01-01T00:01:00Z")),
+            Some(ClipNotification::UpdatedClip { id, .. }) if id == "a"
+        ));
+        // Never delivered
+        assert!(matches!(
+            dedup_recovered(&recent, new_clip("b", "2024-01-01T00:00:04Z")),
+            Some(ClipNotification::NewClip { id, .. }) if id == "b"
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+//! LAN server discovery over mDNS/DNS-SD
+//!
+//! Enabled with the `discovery` feature. clipper-server advertises itself as
+//! a `_clipper._tcp` service, with TXT records describing how to connect:
+//!
+//! - `version`: server version
+//! - `scheme`: `http` or `https`
+//! - `fp`: SHA-256 fingerprint of the TLS certificate (HTTPS only), in the
+//!   same format as [`calculate_fingerprint`](crate::calculate_fingerprint)
+//! - `auth`: `1` if the server requires a bearer token
+//! - `path`: path the server's routes live under, e.g. `/clipper` (empty at
+//!   the root)
+
+use crate::error::{ClientError, Result};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// DNS-SD service type advertised by clipper-server
+pub const SERVICE_TYPE: &str = "_clipper._tcp.local.";
+
+/// A server found on the local network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveredServer {
+    /// Service instance name, e.g. "Clipper on desktop-abc"
+    pub name: String,
+    /// mDNS host name, e.g. "desktop-abc.local."
+    pub hostname: String,
+    /// URL to connect to, using the first advertised IPv4 address if any and
+    /// including the server's base path
+    pub url: String,
+    /// All advertised addresses
+    pub addresses: Vec<IpAddr>,
+    pub port: u16,
+    pub version: Option<String>,
+    /// Certificate fingerprint to check against before trusting the server
+    pub fingerprint: Option<String>,
+    /// Whether the server requires a bearer token
+    pub auth_required: bool,
+}
+
+impl DiscoveredServer {
+    fn from_service_info(info: &ServiceInfo) -> Option<Self> {
+        let mut addresses: Vec<IpAddr> = info.get_addresses().iter().copied().collect();
+        // Prefer IPv4, then a stable order so repeated scans give the same URL
+        addresses.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+        let address = addresses.first()?;
+
+        let scheme = match info.get_property_val_str("scheme") {
+            Some("https") => "https",
+            _ => "http",
+        };
+        let host = match address {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        };
+        // Older servers don't send a path; ignore anything that isn't one
+        let path = info
+            .get_property_val_str("path")
+            .filter(|path| path.starts_with('/'))
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        let name = info
+            .get_fullname()
+            .strip_suffix(info.get_type())
+            .map(|name| name.trim_end_matches('.'))
+            .unwrap_or(info.get_fullname())
+            .to_string();
+
+        Some(Self {
+            name,
+            hostname: info.get_hostname().to_string(),
+            url: format!("{}://{}:{}{}", scheme, host, info.get_port(), path),
+            port: info.get_port(),
+            version: info.get_property_val_str("version").map(str::to_string),
+            fingerprint: info
+                .get_property_val_str("fp")
+                .filter(|fp| !fp.is_empty())
+                .map(str::to_string),
+            auth_required: info.get_property_val_str("auth") == Some("1"),
+            addresses,
+        })
+    }
+}
+
+/// Browse the local network for Clipper servers
+///
+/// Collects every server that answers within `timeout`, sorted by name.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # async fn example() -> clipper_client::Result<()> {
+/// for server in clipper_client::discover_servers(Duration::from_secs(3)).await? {
+///     println!("{} at {}", server.name, server.url);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn discover_servers(timeout: Duration) -> Result<Vec<DiscoveredServer>> {
+    let mdns = ServiceDaemon::new().map_err(mdns_error)?;
+    let receiver = mdns.browse(SERVICE_TYPE).map_err(mdns_error)?;
+
+    let mut servers = HashMap::new();
+    let _ = tokio::time::timeout(timeout, async {
+        while let Ok(event) = receiver.recv_async().await {
+            if let ServiceEvent::ServiceResolved(info) = event
+                && let Some(server) = DiscoveredServer::from_service_info(&info)
+            {
+                servers.insert(info.get_fullname().to_string(), server);
+            }
+        }
+    })
+    .await;
+
+    let _ = mdns.stop_browse(SERVICE_TYPE);
+    let _ = mdns.shutdown();
+
+    let mut servers: Vec<DiscoveredServer> = servers.into_values().collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(servers)
+}
+
+fn mdns_error(e: mdns_sd::Error) -> ClientError {
+    ClientError::Connection(format!("mDNS error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovered_server_from_service_info() {
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            "Clipper on desktop-abc",
+            "desktop-abc.local.",
+            "fe80::1,192.168.1.20",
+            3000,
+            &[
+                ("version", "1.2.3"),
+                ("scheme", "https"),
+                ("fp", "AB:CD"),
+                ("auth", "1"),
+                ("path", "/clipper"),
+            ][..],
+        )
+        .unwrap();
+
+        let server = DiscoveredServer::from_service_info(&info).unwrap();
+        assert_eq!(server.name, "Clipper on desktop-abc");
+        assert_eq!(server.hostname, "desktop-abc.local.");
+        assert_eq!(server.url, "https://192.168.1.20:3000/clipper");
+        assert_eq!(server.addresses.len(), 2);
+        assert_eq!(server.version.as_deref(), Some("1.2.3"));
+        assert_eq!(server.fingerprint.as_deref(), Some("AB:CD"));
+        assert!(server.auth_required);
+    }
+
+    #[test]
+    fn test_discovered_server_defaults_to_http() {
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            "Clipper on laptop",
+            "laptop.local.",
+            "10.0.0.5",
+            8080,
+            &[("fp", "")][..],
+        )
+        .unwrap();
+
+        let server = DiscoveredServer::from_service_info(&info).unwrap();
+        assert_eq!(server.url, "http://10.0.0.5:8080");
+        assert_eq!(server.fingerprint, None);
+        assert!(!server.auth_required);
+    }
+}
@@ -3,7 +3,7 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
 
     #[error("WebSocket error: {0}")]
     WebSocket(String),
@@ -17,8 +17,9 @@ pub enum ClientError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
-    #[error("Server returned error: {status} - {message}")]
-    ServerError { status: u16, message: String },
+    /// Any other unsuccessful response, with the raw response body
+    #[error("Server returned error: {status} - {body}")]
+    ServerError { status: u16, body: String },
 
     #[error("Not found: {0}")]
     NotFound(String),
@@ -26,14 +27,40 @@ pub enum ClientError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// Missing or invalid Bearer token (HTTP 401 or rejected WebSocket auth)
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    /// The server refused the request because of a size or rate limit
+    /// (HTTP 413 or 429), e.g. an upload larger than the server's maximum
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// The request or connection timed out
+    #[error("Request timed out")]
+    Timeout,
+
     #[error("Connection error: {0}")]
     Connection(String),
 
+    /// The server presented a certificate that is neither trusted by the
+    /// system nor in the client's trusted fingerprints. The fingerprint can be
+    /// shown to the user and added to the trusted fingerprints to proceed.
+    #[error("Server certificate is not trusted (fingerprint: {fingerprint})")]
+    CertificateUntrusted { fingerprint: String },
+
     #[error("Certificate error: {0}")]
     Certificate(String),
 }
 
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ClientError::Timeout
+        } else {
+            ClientError::Http(e)
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ClientError>;
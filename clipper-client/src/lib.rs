@@ -1,15 +1,46 @@
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod builder;
+pub mod cache;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod certificate;
 pub mod client;
+#[cfg(all(feature = "discovery", not(target_arch = "wasm32")))]
+pub mod discovery;
 pub mod error;
 pub mod models;
+pub mod multi;
+#[cfg(all(feature = "p2p", not(target_arch = "wasm32")))]
+pub mod p2p;
+pub mod reconnect;
+pub mod telemetry;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub use certificate::{
     calculate_fingerprint, create_http_client_with_trusted_certs, create_tls_config_with_trusted_certs,
     fetch_server_certificate, CertificateInfo, TrustedFingerprintVerifier,
 };
+pub use builder::{ClientBuilder, RetryPolicy};
+pub use cache::{CacheConfig, ResponseCache};
 pub use client::ClipperClient;
+#[cfg(all(feature = "discovery", not(target_arch = "wasm32")))]
+pub use discovery::{discover_servers, DiscoveredServer, SERVICE_TYPE};
 pub use error::{ClientError, Result};
 pub use models::{
-    Clip, ClipNotification, CreateClipRequest, ImportResult, PagedTagResult, SearchFilters,
-    ServerConfigInfo, ServerInfo, ShortUrl, Tag, UpdateClipRequest,
+    ActivityStats, CleanupCandidate, CleanupResult, Clip, ClipLink, ClipNotification, Collection,
+    ContentFormat, CreateClipRequest, DayActivity, Device, ImportResult, PagedTagResult,
+    PairedDevice, PairedDeviceToken, PairingCode, PushToClipboardResult, RenameTagResult,
+    SearchFilters, ServerConfigInfo, ServerInfo, ShortUrl, SortDirection, SortField, SortOrder,
+    SplitSeparator, Tag, TagStats, Template, Transform, UpdateClipRequest, Visibility,
 };
+#[cfg(all(feature = "p2p", not(target_arch = "wasm32")))]
+pub use p2p::{P2pConfig, P2pNode, PeerEvent, PeerInfo, SyncedClip, PEER_SERVICE_TYPE};
+pub use multi::{MergedCursor, MergedResult, MultiClipperClient, SourceError, SourcedClip};
+pub use reconnect::{ConnectionStatus, ReconnectConfig};
+pub use telemetry::REQUEST_ID_HEADER;
+#[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+pub use telemetry::{init_otlp_tracing, TelemetryGuard};
+#[cfg(target_arch = "wasm32")]
+pub use wasm::NotificationSubscription;
@@ -1,11 +1,16 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Clip {
     pub id: String,
     pub content: String,
     pub created_at: String,
+    /// When the tags, notes, language, color or icon were last changed (equals
+    /// `created_at` if never edited; None from servers that don't track it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_notes: Option<String>,
@@ -16,6 +21,42 @@ pub struct Clip {
     /// Optional language identifier for the clip content (e.g., "en", "zh", "rust", "python")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    /// Optional color label, a hex color ("#e11d48") or a color name ("red")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Optional icon, e.g. an emoji or an icon name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// How the content is written; Markdown clips are rendered on shared pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<ContentFormat>,
+    /// The device the clip was created on, from its `$host:` tag (None for
+    /// clips without one and from older servers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_device: Option<String>,
+    /// Set when the text was too long for the server to keep in the clip:
+    /// `content` is its beginning, and the whole text is the file attachment
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Who can see the clip (None from older servers, where every clip is
+    /// private)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+    /// Natural language the server detected in the content, as an ISO 639-1
+    /// code such as "en"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// One-line summary from the server's enrichment, if it's enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Tags the server's enrichment suggests; they aren't in `tags` until
+    /// added with `update_clip`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_tags: Vec<String>,
+    /// Where the URL in `content` leads, without tracking parameters, if the
+    /// server's URL canonicalization is enabled and found it different
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
     /// Highlighted content with search terms wrapped by highlight markers.
     /// Only present in search results when highlight params are provided.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,6 +71,10 @@ pub struct CreateClipRequest {
     pub additional_notes: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<ContentFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +85,42 @@ pub struct UpdateClipRequest {
     pub additional_notes: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<ContentFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+}
+
+/// Request to append text to a clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppendClipRequest {
+    pub content: String,
+    pub separator: String,
+}
+
+/// Format of a clip's text content
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    Plain,
+    Markdown,
+}
+
+/// Who can see a clip
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Only the owner: the shared token and paired devices
+    Private,
+    /// Any authenticated user, including guests
+    Shared,
+    /// Like shared, and also anyone with one of the clip's short URLs.
+    /// Creating a short URL makes a clip public.
+    Public,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -50,6 +131,88 @@ pub struct SearchFilters {
     pub end_date: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    /// Only clips updated (or created) at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only clips created on this device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_device: Option<String>,
+    /// Result order for `search_clips` (None = server default, by date).
+    /// Ignored when listing clips.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SortOrder>,
+    /// Field to sort lists and date-ordered searches by (None = created_at)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<SortField>,
+    /// Direction for `sort_by` (None = descending)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortDirection>,
+    /// Skip counting all matches, which is slow for large databases.
+    /// `PagedResult::total` is then only a lower bound; use `has_more`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_total: bool,
+}
+
+/// Order of search results
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Newest first
+    Date,
+    /// Best match first
+    Relevance,
+}
+
+impl SortOrder {
+    /// The value of the `sort` query parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Date => "date",
+            SortOrder::Relevance => "relevance",
+        }
+    }
+}
+
+/// Field to sort clips by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    CreatedAt,
+    UpdatedAt,
+    /// Length of the clip content in characters
+    ContentLength,
+    /// Original filename of the attachment
+    OriginalFilename,
+}
+
+impl SortField {
+    /// The value of the `sort_by` query parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortField::CreatedAt => "created_at",
+            SortField::UpdatedAt => "updated_at",
+            SortField::ContentLength => "content_length",
+            SortField::OriginalFilename => "original_filename",
+        }
+    }
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    /// The value of the `order` query parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        }
+    }
 }
 
 impl SearchFilters {
@@ -71,18 +234,50 @@ impl SearchFilters {
         self.tags = Some(tags);
         self
     }
+
+    pub fn with_updated_since(mut self, date: DateTime<Utc>) -> Self {
+        self.updated_since = Some(date);
+        self
+    }
+
+    pub fn with_source_device(mut self, source_device: impl Into<String>) -> Self {
+        self.source_device = Some(source_device.into());
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn with_sort_by(mut self, sort_by: SortField, order: SortDirection) -> Self {
+        self.sort_by = Some(sort_by);
+        self.order = Some(order);
+        self
+    }
+
+    pub fn with_skip_total(mut self) -> Self {
+        self.skip_total = true;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClipNotification {
+    /// `updated_at` (RFC 3339) is the server time the change was published;
+    /// None from servers that don't send it
     NewClip {
         id: String,
         content: String,
         tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        updated_at: Option<String>,
     },
     UpdatedClip {
         id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        updated_at: Option<String>,
     },
     DeletedClip {
         id: String,
@@ -91,6 +286,13 @@ pub enum ClipNotification {
         ids: Vec<String>,
         count: usize,
     },
+    /// A clip pushed to this device's clipboard with
+    /// `push_to_device_clipboard`; only sent to clients with a device name
+    SetClipboard {
+        id: String,
+        content: String,
+        tags: Vec<String>,
+    },
 }
 
 /// WebSocket authentication request message sent by client
@@ -118,6 +320,13 @@ pub struct PagedResult {
     pub page: usize,
     pub page_size: usize,
     pub total_pages: usize,
+    /// Whether another page follows (None for servers that don't report it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+    /// Cursor for the next page, for `list_clips_after` / `search_clips_after`.
+    /// Only returned when more clips follow and the results are sorted by date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Server configuration information returned by /version API
@@ -208,6 +417,180 @@ pub struct ShortUrl {
     pub expires_at: Option<String>,
 }
 
+/// Link from one clip to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipLink {
+    /// Link record ID
+    pub id: String,
+    /// The clip the link starts from
+    pub source_id: String,
+    /// The clip the link points to
+    pub target_id: String,
+    /// Kind of relationship, e.g. "derived_from" or "reply_to"
+    pub relation: String,
+    /// Creation timestamp (RFC3339)
+    pub created_at: String,
+}
+
+/// A built-in text transform applied by `POST /clips/{id}/transform`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Uppercase,
+    Lowercase,
+    /// Remove leading and trailing whitespace
+    Trim,
+    Base64Encode,
+    Base64Decode,
+    /// Re-indent JSON
+    JsonPretty,
+    /// Remove insignificant whitespace from JSON
+    JsonMinify,
+    UrlEncode,
+    UrlDecode,
+}
+
+/// Request to transform a clip into a new clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformClipRequest {
+    /// Transforms to apply, in order
+    pub transforms: Vec<Transform>,
+}
+
+/// Where a clip is split by [`split_clip`](crate::ClipperClient::split_clip)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum SplitSeparator {
+    /// At line breaks
+    Lines,
+    /// At every occurrence of a string
+    Delimiter { delimiter: String },
+    /// At every match of a regular expression
+    Regex { pattern: String },
+}
+
+/// Request to split a clip into several clips
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitClipRequest {
+    #[serde(flatten)]
+    pub separator: SplitSeparator,
+    /// Keep the whitespace around each part instead of trimming it
+    #[serde(default)]
+    pub keep_whitespace: bool,
+    /// Delete the source clip once it's split
+    #[serde(default)]
+    pub delete_source: bool,
+}
+
+/// Request to link a clip to another clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateClipLinkRequest {
+    pub target_id: String,
+    pub relation: String,
+}
+
+/// A named group of clips kept in a manual order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    /// Collection ID
+    pub id: String,
+    /// Unique name of the collection
+    pub name: String,
+    /// Optional description
+    pub description: Option<String>,
+    /// Creation timestamp (RFC3339)
+    pub created_at: String,
+    /// Last change to the collection or its clips (RFC3339)
+    pub updated_at: String,
+}
+
+/// Request to create a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Request to rename a collection or change its description
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCollectionRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Request to add a clip to a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddCollectionClipRequest {
+    pub clip_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+}
+
+/// Request to set the order of a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderCollectionRequest {
+    pub clip_ids: Vec<String>,
+}
+
+/// Named text with `{{placeholders}}` that clips are created from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    /// Template ID
+    pub id: String,
+    /// Unique name of the template
+    pub name: String,
+    /// Optional description
+    pub description: Option<String>,
+    /// Text with `{{name}}` or `{{name|default}}` placeholders
+    pub content: String,
+    /// Tags given to the clips created from the template
+    pub tags: Vec<String>,
+    /// Names of the placeholders in the content, in order of first use
+    #[serde(default)]
+    pub placeholders: Vec<String>,
+    /// Creation timestamp (RFC3339)
+    pub created_at: String,
+    /// Last update timestamp (RFC3339)
+    pub updated_at: String,
+}
+
+/// Request to create a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub content: String,
+    pub tags: Vec<String>,
+}
+
+/// Request to change a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTemplateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Request to create a clip from a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    /// Placeholder values by name
+    pub values: HashMap<String, String>,
+    /// Tags added to the template's own
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_notes: Option<String>,
+}
+
 /// Result of an import operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportResult {
@@ -243,3 +626,151 @@ pub struct PagedTagResult {
     pub page_size: usize,
     pub total_pages: usize,
 }
+
+/// Number of clips using a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagStats {
+    /// Tag text
+    pub text: String,
+    /// Number of clips with this tag
+    pub count: usize,
+}
+
+/// Clips created per day and their most used tags, returned by
+/// `GET /stats/activity`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityStats {
+    /// First day counted
+    pub start_date: NaiveDate,
+    /// Last day counted, today in the requested time zone
+    pub end_date: NaiveDate,
+    /// Clips created in the period
+    pub total: usize,
+    /// Every day of the period in order, including days without clips
+    pub days: Vec<DayActivity>,
+    /// Most used tags of the clips in the period, without `$host:` tags
+    pub top_tags: Vec<TagStats>,
+}
+
+/// Number of clips created on a day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// A device clips were created on, returned by `GET /devices`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    /// Device name, from the `$host:` tag of its clips
+    pub name: String,
+    /// Number of clips created on the device
+    pub clip_count: usize,
+    /// When the newest clip from the device was created (RFC3339)
+    pub last_seen: String,
+}
+
+/// Request to put a clip on a connected device's clipboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushToClipboardRequest {
+    pub clip_id: String,
+}
+
+/// Result of pushing a clip to a device's clipboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushToClipboardResult {
+    /// Number of the device's connections the clip was sent to
+    pub connections: usize,
+}
+
+/// A pairing code for a new device, returned by `POST /pairing`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingCode {
+    /// Single-use code to enter or scan on the new device
+    pub code: String,
+    /// When the code stops working (RFC3339)
+    pub expires_at: String,
+}
+
+/// Request to redeem a pairing code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimPairingCodeRequest {
+    pub code: String,
+    pub name: String,
+}
+
+/// A device token issued for a pairing code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDeviceToken {
+    /// Bearer token for the new device; the server doesn't show it again
+    pub token: String,
+    pub device: PairedDevice,
+}
+
+/// A device paired with the server, which signs in with its own token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDevice {
+    pub id: String,
+    pub name: String,
+    /// When the device was paired (RFC3339)
+    pub created_at: String,
+    /// When the device last used its token (RFC3339), updated at most once a minute
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
+/// Request to get several clips at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchGetRequest {
+    pub ids: Vec<String>,
+}
+
+/// Clips returned by `POST /clips/batch-get`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchGetResult {
+    pub items: Vec<Clip>,
+}
+
+/// Request to rename a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameTagRequest {
+    /// Current tag text
+    pub from: String,
+    /// New tag text
+    pub to: String,
+}
+
+/// Result of renaming a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameTagResult {
+    /// Number of clips that were updated
+    pub updated_count: usize,
+    /// IDs of the updated clips
+    pub clip_ids: Vec<String>,
+}
+
+/// Result of running cleanup on the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupResult {
+    /// Whether nothing was deleted, only listed
+    pub dry_run: bool,
+    /// Number of clips deleted, or that would be for a dry run
+    pub deleted_clips: usize,
+    /// Number of expired short URLs deleted, or that would be for a dry run
+    pub deleted_short_urls: usize,
+    /// The clips a dry run would delete, oldest first
+    #[serde(default)]
+    pub clips: Vec<CleanupCandidate>,
+}
+
+/// A clip that cleanup would delete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupCandidate {
+    pub id: String,
+    /// The start of the content on one line
+    pub preview: String,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub original_filename: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,512 @@
+//! Merged view of the clips on several servers
+//!
+//! `MultiClipperClient` sends a list or search request to every server at
+//! once and merges the results into one page, each clip labelled with the
+//! name of the server it came from. Servers keep their own clip IDs, so
+//! changes to a clip go through `source` to the server named by its label.
+//!
+//! Pages are continued with a `MergedCursor`, which counts the clips taken
+//! from each server so far. A server that fails is left out of the page and
+//! reported in `MergedResult::errors`; the request only fails when every
+//! server does.
+
+use crate::client::ClipperClient;
+use crate::error::Result;
+use crate::models::{Clip, PagedResult, SearchFilters, SortDirection, SortField, SortOrder};
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+
+/// A clip and the name of the server it came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourcedClip {
+    pub source: String,
+    #[serde(flatten)]
+    pub clip: Clip,
+}
+
+/// Where a merged page ends: the number of clips taken from each server
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MergedCursor {
+    pub offsets: BTreeMap<String, usize>,
+}
+
+/// A server that couldn't be read for a merged page
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceError {
+    pub source: String,
+    pub message: String,
+}
+
+/// One page of clips merged from several servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedResult {
+    pub items: Vec<SourcedClip>,
+    /// Sum of the servers' totals
+    pub total: usize,
+    /// Cursor for the next page (None when no server has more clips)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<MergedCursor>,
+    /// Servers left out of this page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<SourceError>,
+}
+
+/// Client for listing and searching the clips of several servers as one
+#[derive(Clone, Default)]
+pub struct MultiClipperClient {
+    sources: Vec<(String, ClipperClient)>,
+}
+
+impl MultiClipperClient {
+    /// Create a client without servers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a server, labelling its clips with `name`
+    pub fn with_source(mut self, name: impl Into<String>, client: ClipperClient) -> Self {
+        self.add_source(name, client);
+        self
+    }
+
+    /// Add a server, replacing the one already named `name`
+    pub fn add_source(&mut self, name: impl Into<String>, client: ClipperClient) {
+        let name = name.into();
+        match self.sources.iter_mut().find(|(n, _)| *n == name) {
+            Some(source) => source.1 = client,
+            None => self.sources.push((name, client)),
+        }
+    }
+
+    /// The client of the server named `name`, for changing its clips
+    pub fn source(&self, name: &str) -> Option<&ClipperClient> {
+        self.sources
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, client)| client)
+    }
+
+    /// Names of the servers, in the order they were added
+    pub fn source_names(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// List the clips of all servers, newest first unless `filters` sorts
+    /// them otherwise
+    ///
+    /// # Arguments
+    /// * `filters` - Filters sent to every server
+    /// * `cursor` - `next_cursor` of the previous page, or None for the first page
+    /// * `limit` - Number of items per page
+    pub async fn list_clips(
+        &self,
+        filters: SearchFilters,
+        cursor: Option<&MergedCursor>,
+        limit: usize,
+    ) -> Result<MergedResult> {
+        let order = MergeOrder::new(&filters, false);
+        self.fetch_merged(cursor, limit, order, |client, page, page_size| {
+            let filters = filters.clone();
+            async move { client.list_clips(filters, page, page_size).await }
+        })
+        .await
+    }
+
+    /// Search the clips of all servers
+    ///
+    /// Results sorted by relevance are interleaved, taking each server's
+    /// best remaining match in turn, since scores from different servers
+    /// can't be compared.
+    ///
+    /// # Arguments
+    /// * `query` - Search query sent to every server
+    /// * `filters` - Filters sent to every server
+    /// * `cursor` - `next_cursor` of the previous page, or None for the first page
+    /// * `limit` - Number of items per page
+    pub async fn search_clips(
+        &self,
+        query: &str,
+        filters: SearchFilters,
+        cursor: Option<&MergedCursor>,
+        limit: usize,
+    ) -> Result<MergedResult> {
+        let order = MergeOrder::new(&filters, true);
+        self.fetch_merged(cursor, limit, order, |client, page, page_size| {
+            let filters = filters.clone();
+            async move { client.search_clips(query, filters, page, page_size).await }
+        })
+        .await
+    }
+
+    /// Fetch the next `limit` clips of every server and merge them
+    async fn fetch_merged<'a, F, Fut>(
+        &'a self,
+        cursor: Option<&MergedCursor>,
+        limit: usize,
+        order: MergeOrder,
+        fetch: F,
+    ) -> Result<MergedResult>
+    where
+        F: Fn(&'a ClipperClient, usize, usize) -> Fut,
+        Fut: std::future::Future<Output = Result<PagedResult>>,
+    {
+        let limit = limit.max(1);
+        let windows = join_all(self.sources.iter().map(|(name, client)| {
+            let offset = cursor
+                .and_then(|cursor| cursor.offsets.get(name))
+                .copied()
+                .unwrap_or(0);
+            let fetch = &fetch;
+            async move {
+                let window = fetch_window(offset, limit, |page| fetch(client, page, limit)).await;
+                (name.clone(), offset, window)
+            }
+        }))
+        .await;
+
+        let mut errors = Vec::new();
+        let mut first_error = None;
+        let mut fetched = Vec::new();
+        for (name, offset, window) in windows {
+            match window {
+                Ok(window) => fetched.push((name, offset, window)),
+                Err(e) => {
+                    errors.push(SourceError {
+                        source: name,
+                        message: e.to_string(),
+                    });
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if fetched.is_empty()
+            && let Some(e) = first_error
+        {
+            return Err(e);
+        }
+
+        let mut result = merge_windows(fetched, limit, order);
+        // Servers that failed start again where they were on the next page
+        if let Some(next_cursor) = &mut result.next_cursor {
+            for error in &errors {
+                let offset = cursor
+                    .and_then(|cursor| cursor.offsets.get(&error.source))
+                    .copied()
+                    .unwrap_or(0);
+                next_cursor.offsets.insert(error.source.clone(), offset);
+            }
+        }
+        result.errors = errors;
+        Ok(result)
+    }
+}
+
+/// The clips of one server from an offset on, as far as they were fetched
+#[derive(Debug)]
+struct Window {
+    items: VecDeque<Clip>,
+    total: usize,
+    /// Whether the server has clips after `items`
+    has_more: bool,
+}
+
+/// Fetch up to `limit` clips starting at `offset` with pages of `limit`
+/// clips, which takes a second page when the offset isn't on a page boundary
+async fn fetch_window<F, Fut>(offset: usize, limit: usize, fetch_page: F) -> Result<Window>
+where
+    F: Fn(usize) -> Fut,
+    Fut: std::future::Future<Output = Result<PagedResult>>,
+{
+    let page = offset / limit + 1;
+    let skip = offset % limit;
+
+    let first = fetch_page(page).await?;
+    let mut has_more = page_has_more(&first);
+    let total = first.total;
+    let mut items: VecDeque<Clip> = first.items.into_iter().skip(skip).collect();
+    if skip > 0 && has_more {
+        let second = fetch_page(page + 1).await?;
+        has_more = page_has_more(&second);
+        items.extend(second.items);
+    }
+    if items.len() > limit {
+        // Clips of the second page past `limit` are left for the next page
+        items.truncate(limit);
+        has_more = true;
+    }
+    Ok(Window {
+        items,
+        total,
+        has_more,
+    })
+}
+
+/// Whether another page follows, for servers that don't report `has_more`
+fn page_has_more(result: &PagedResult) -> bool {
+    result.has_more.unwrap_or(result.page < result.total_pages)
+}
+
+/// How the servers' results are put together
+#[derive(Debug, Clone, Copy)]
+enum MergeOrder {
+    /// By a clip field, like the servers sorted them
+    Field(SortField, SortDirection),
+    /// Each server's next clip in turn
+    Interleave,
+}
+
+impl MergeOrder {
+    fn new(filters: &SearchFilters, search: bool) -> Self {
+        if search && filters.sort == Some(SortOrder::Relevance) {
+            return MergeOrder::Interleave;
+        }
+        MergeOrder::Field(
+            filters.sort_by.unwrap_or(SortField::CreatedAt),
+            filters.order.unwrap_or(SortDirection::Desc),
+        )
+    }
+
+    /// Whether `a` goes before `b`
+    fn precedes(&self, a: &Clip, b: &Clip) -> bool {
+        let MergeOrder::Field(field, direction) = *self else {
+            return false;
+        };
+        let ordering = match field {
+            SortField::CreatedAt => compare_times(&a.created_at, &b.created_at),
+            SortField::UpdatedAt => compare_times(
+                a.updated_at.as_deref().unwrap_or(&a.created_at),
+                b.updated_at.as_deref().unwrap_or(&b.created_at),
+            ),
+            SortField::ContentLength => a.content.chars().count().cmp(&b.content.chars().count()),
+            SortField::OriginalFilename => a.original_filename.cmp(&b.original_filename),
+        };
+        match direction {
+            SortDirection::Asc => ordering == Ordering::Less,
+            SortDirection::Desc => ordering == Ordering::Greater,
+        }
+    }
+}
+
+/// Compare RFC 3339 timestamps, which servers may write with different offsets
+fn compare_times(a: &str, b: &str) -> Ordering {
+    match (
+        DateTime::parse_from_rfc3339(a).map(|t| t.with_timezone(&Utc)),
+        DateTime::parse_from_rfc3339(b).map(|t| t.with_timezone(&Utc)),
+    ) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Take `limit` clips from the servers' windows in `order`; ties go to the
+/// server added first
+fn merge_windows(
+    mut windows: Vec<(String, usize, Window)>,
+    limit: usize,
+    order: MergeOrder,
+) -> MergedResult {
+    let total = windows.iter().map(|(_, _, window)| window.total).sum();
+    let mut taken = vec![0; windows.len()];
+    let mut items = Vec::with_capacity(limit);
+    let mut turn = 0;
+
+    while items.len() < limit {
+        let next = match order {
+            MergeOrder::Field(..) => {
+                let mut best: Option<usize> = None;
+                for (i, (_, _, window)) in windows.iter().enumerate() {
+                    let Some(clip) = window.items.front() else {
+                        continue;
+                    };
+                    if best.is_none_or(|b| order.precedes(clip, &windows[b].2.items[0])) {
+                        best = Some(i);
+                    }
+                }
+                best
+            }
+            MergeOrder::Interleave => {
+                let count = windows.len();
+                let next = (0..count)
+                    .map(|i| (turn + i) % count)
+                    .find(|&i| !windows[i].2.items.is_empty());
+                turn = next.map_or(turn, |i| i + 1);
+                next
+            }
+        };
+        let Some(i) = next else {
+            break;
+        };
+        let (source, _, window) = &mut windows[i];
+        let clip = window.items.pop_front().expect("window has a clip");
+        taken[i] += 1;
+        items.push(SourcedClip {
+            source: source.clone(),
+            clip,
+        });
+    }
+
+    let has_more = windows
+        .iter()
+        .any(|(_, _, window)| !window.items.is_empty() || window.has_more);
+    let next_cursor = has_more.then(|| MergedCursor {
+        offsets: windows
+            .iter()
+            .zip(taken)
+            .map(|((source, offset, _), taken)| (source.clone(), offset + taken))
+            .collect(),
+    });
+
+    MergedResult {
+        items,
+        total,
+        next_cursor,
+        errors: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(id: &str, created_at: &str) -> Clip {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "content": id,
+            "created_at": created_at,
+            "tags": [],
+        }))
+        .unwrap()
+    }
+
+    fn window(clips: Vec<Clip>, has_more: bool) -> Window {
+        Window {
+            total: clips.len() + usize::from(has_more),
+            items: clips.into(),
+            has_more,
+        }
+    }
+
+    fn ids(result: &MergedResult) -> Vec<(&str, &str)> {
+        result
+            .items
+            .iter()
+            .map(|item| (item.source.as_str(), item.clip.id.as_str()))
+            .collect()
+    }
+
+    fn page(ids: &[&str], page: usize, total: usize) -> PagedResult {
+        PagedResult {
+            items: ids
+                .iter()
+                .map(|id| clip(id, "2025-01-01T00:00:00Z"))
+                .collect(),
+            total,
+            page,
+            page_size: 2,
+            total_pages: total.div_ceil(2),
+            has_more: None,
+            next_cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_by_date() {
+        let windows = vec![
+            (
+                "work".to_string(),
+                0,
+                window(
+                    vec![
+                        clip("w1", "2025-01-03T00:00:00Z"),
+                        clip("w2", "2025-01-01T00:00:00Z"),
+                    ],
+                    true,
+                ),
+            ),
+            (
+                "home".to_string(),
+                4,
+                window(
+                    vec![
+                        // Same instant as w1, written with another offset
+                        clip("h1", "2025-01-03T02:00:00+02:00"),
+                        clip("h2", "2025-01-02T00:00:00Z"),
+                    ],
+                    false,
+                ),
+            ),
+        ];
+        let order = MergeOrder::Field(SortField::CreatedAt, SortDirection::Desc);
+        let result = merge_windows(windows, 3, order);
+
+        assert_eq!(
+            ids(&result),
+            [("work", "w1"), ("home", "h1"), ("home", "h2")]
+        );
+        assert_eq!(result.total, 5);
+        let cursor = result.next_cursor.unwrap();
+        assert_eq!(cursor.offsets["work"], 1);
+        assert_eq!(cursor.offsets["home"], 6);
+    }
+
+    #[test]
+    fn test_merge_interleaves_relevance() {
+        let windows = vec![
+            (
+                "work".to_string(),
+                0,
+                window(
+                    vec![
+                        clip("w1", "2025-01-01T00:00:00Z"),
+                        clip("w2", "2025-01-01T00:00:00Z"),
+                        clip("w3", "2025-01-01T00:00:00Z"),
+                    ],
+                    false,
+                ),
+            ),
+            (
+                "home".to_string(),
+                0,
+                window(vec![clip("h1", "2025-01-05T00:00:00Z")], false),
+            ),
+        ];
+        let result = merge_windows(windows, 10, MergeOrder::Interleave);
+
+        assert_eq!(
+            ids(&result),
+            [
+                ("work", "w1"),
+                ("home", "h1"),
+                ("work", "w2"),
+                ("work", "w3")
+            ]
+        );
+        assert!(result.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_window_across_pages() {
+        let pages = [
+            page(&["a", "b"], 1, 5),
+            page(&["c", "d"], 2, 5),
+            page(&["e"], 3, 5),
+        ];
+        let fetch = |page: usize| {
+            let result = pages[page - 1].clone();
+            async move { Ok(result) }
+        };
+
+        let window = fetch_window(3, 2, fetch).await.unwrap();
+        let ids: Vec<&str> = window.items.iter().map(|clip| clip.id.as_str()).collect();
+        assert_eq!(ids, ["d", "e"]);
+        assert!(!window.has_more);
+
+        let window = fetch_window(1, 2, fetch).await.unwrap();
+        let ids: Vec<&str> = window.items.iter().map(|clip| clip.id.as_str()).collect();
+        assert_eq!(ids, ["b", "c"]);
+        assert!(window.has_more);
+    }
+}
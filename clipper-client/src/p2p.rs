@@ -0,0 +1,733 @@
+//! Peer-to-peer clip sync without a server
+//!
+//! Enabled with the `p2p` feature. Instances on the same LAN advertise a
+//! `_clipper-p2p._tcp` service over mDNS, find each other and exchange clips
+//! directly over TCP. Every connection is encrypted and authenticated with the
+//! Noise protocol (`Noise_NNpsk0_25519_ChaChaPoly_BLAKE2s`), keyed by a sync
+//! key shared out of band: instances with a different key fail the handshake
+//! and never see each other's clips. The pre-shared key is derived from the
+//! sync key with Argon2id, which makes guessing a weak sync key from a
+//! recorded handshake slow, though a long random key is still best.
+//!
+//! After the handshake, each side sends a `hello` message with its node id and
+//! display name, then `clip` messages as clips are broadcast. Messages are
+//! JSON, encrypted in frames of at most 64 KiB (a `u16` big-endian length
+//! followed by the ciphertext). The first frame of every message carries its
+//! total length so larger clips can be split across frames.
+//!
+//! Only the peer with the smaller node id dials, so two nodes that discover
+//! each other at the same time end up with a single connection.
+
+use crate::error::{ClientError, Result};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use snow::{Builder, HandshakeState, TransportState};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// DNS-SD service type advertised by P2P nodes
+pub const PEER_SERVICE_TYPE: &str = "_clipper-p2p._tcp.local.";
+
+const NOISE_PARAMS: &str = "Noise_NNpsk0_25519_ChaChaPoly_BLAKE2s";
+/// Bound into the handshake so the protocol version is authenticated too
+const PROLOGUE: &[u8] = b"clipper-p2p/2";
+/// Argon2id salt for the pre-shared key. It is the same for every node, since
+/// nodes must derive the same key without talking to each other first.
+const PSK_SALT: &[u8] = b"clipper-p2p-v2 sync key";
+/// Argon2id memory (KiB), iterations and parallelism for the pre-shared key;
+/// changing them changes the key, so they are part of the protocol
+const PSK_MEMORY_KIB: u32 = 19 * 1024;
+const PSK_ITERATIONS: u32 = 2;
+const PSK_PARALLELISM: u32 = 1;
+
+/// Largest Noise message, including the authentication tag
+const MAX_FRAME_SIZE: usize = 65535;
+const TAG_SIZE: usize = 16;
+const MAX_CHUNK_SIZE: usize = MAX_FRAME_SIZE - TAG_SIZE;
+/// Largest message a peer may send
+const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often to retry peers that were discovered but aren't connected
+const REDIAL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Configuration for a [`P2pNode`]
+#[derive(Debug, Clone)]
+pub struct P2pConfig {
+    /// Name shown to other peers, e.g. the host name
+    pub name: String,
+    /// Shared secret; only nodes with the same key can connect to each other
+    pub sync_key: String,
+    /// TCP port to listen on, or 0 to pick any free port
+    pub port: u16,
+}
+
+/// A clip exchanged between peers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedClip {
+    pub content: String,
+    pub tags: Vec<String>,
+    pub additional_notes: Option<String>,
+    pub language: Option<String>,
+}
+
+/// A connected peer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    /// Random node id, regenerated every time the node starts
+    pub id: String,
+    pub name: String,
+    pub address: SocketAddr,
+}
+
+/// Events reported by a running [`P2pNode`]
+#[derive(Debug, Clone)]
+pub enum PeerEvent {
+    Connected(PeerInfo),
+    Disconnected(PeerInfo),
+    /// A peer sent a clip
+    Clip {
+        from: PeerInfo,
+        clip: SyncedClip,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PeerMessage {
+    Hello { id: String, name: String },
+    Clip { clip: SyncedClip },
+}
+
+struct Peer {
+    info: PeerInfo,
+    sender: mpsc::UnboundedSender<PeerMessage>,
+    conn_id: u64,
+}
+
+struct Shared {
+    id: String,
+    name: String,
+    psk: [u8; 32],
+    peers: Mutex<HashMap<String, Peer>>,
+    dialing: Mutex<HashSet<String>>,
+    events: mpsc::UnboundedSender<PeerEvent>,
+    next_conn_id: AtomicU64,
+    cancel: CancellationToken,
+}
+
+/// A running P2P sync node
+///
+/// The node advertises itself, connects to peers with the same sync key and
+/// keeps those connections open until it is dropped.
+///
+/// # Example
+/// ```no_run
+/// # use clipper_client::{P2pConfig, P2pNode, PeerEvent};
+/// # async fn example() -> clipper_client::Result<()> {
+/// let config = P2pConfig {
+///     name: "desktop-abc".to_string(),
+///     sync_key: "shared secret".to_string(),
+///     port: 0,
+/// };
+/// let (node, mut events) = P2pNode::start(config).await?;
+/// while let Some(event) = events.recv().await {
+///     if let PeerEvent::Clip { from, clip } = event {
+///         println!("{} sent {}", from.name, clip.content);
+///     }
+/// }
+/// # drop(node);
+/// # Ok(())
+/// # }
+/// ```
+pub struct P2pNode {
+    shared: Arc<Shared>,
+    mdns: ServiceDaemon,
+    fullname: String,
+    port: u16,
+}
+
+impl P2pNode {
+    /// Start listening, advertising and browsing for peers
+    ///
+    /// Returns the node and a receiver for peer events. Events are dropped if
+    /// the receiver is dropped, but the node keeps running.
+    pub async fn start(config: P2pConfig) -> Result<(P2pNode, mpsc::UnboundedReceiver<PeerEvent>)> {
+        if config.sync_key.trim().is_empty() {
+            return Err(ClientError::BadRequest(
+                "P2P sync requires a sync key".to_string(),
+            ));
+        }
+
+        let listener = TcpListener::bind(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            config.port,
+        ))
+        .await?;
+        let port = listener.local_addr()?.port();
+
+        let id = generate_node_id()?;
+        let (events, receiver) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            id: id.clone(),
+            name: config.name.clone(),
+            psk: derive_psk(&config.sync_key),
+            peers: Mutex::new(HashMap::new()),
+            dialing: Mutex::new(HashSet::new()),
+            events,
+            next_conn_id: AtomicU64::new(0),
+            cancel: CancellationToken::new(),
+        });
+
+        let mdns = ServiceDaemon::new().map_err(mdns_error)?;
+        let properties = [("id", id.as_str()), ("name", config.name.as_str())];
+        let info = ServiceInfo::new(
+            PEER_SERVICE_TYPE,
+            &id,
+            &format!("clipper-{}.local.", id),
+            "",
+            port,
+            &properties[..],
+        )
+        .map_err(mdns_error)?
+        .enable_addr_auto();
+        let fullname = info.get_fullname().to_string();
+        mdns.register(info).map_err(mdns_error)?;
+        let browser = mdns.browse(PEER_SERVICE_TYPE).map_err(mdns_error)?;
+
+        tokio::spawn(accept_loop(shared.clone(), listener));
+        tokio::spawn(discovery_loop(shared.clone(), browser));
+
+        Ok((
+            P2pNode {
+                shared,
+                mdns,
+                fullname,
+                port,
+            },
+            receiver,
+        ))
+    }
+
+    /// This node's id, as seen by peers
+    pub fn id(&self) -> &str {
+        &self.shared.id
+    }
+
+    /// The TCP port the node is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Currently connected peers, sorted by name
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        let peers = self.shared.peers.lock().unwrap();
+        let mut peers: Vec<PeerInfo> = peers.values().map(|peer| peer.info.clone()).collect();
+        peers.sort_by(|a, b| a.name.cmp(&b.name));
+        peers
+    }
+
+    /// Send a clip to every connected peer
+    ///
+    /// Returns the number of peers the clip was queued for.
+    pub fn broadcast(&self, clip: SyncedClip) -> usize {
+        let peers = self.shared.peers.lock().unwrap();
+        peers
+            .values()
+            .filter(|peer| {
+                peer.sender
+                    .send(PeerMessage::Clip { clip: clip.clone() })
+                    .is_ok()
+            })
+            .count()
+    }
+}
+
+impl Drop for P2pNode {
+    fn drop(&mut self) {
+        self.shared.cancel.cancel();
+        let _ = self.mdns.unregister(&self.fullname);
+        let _ = self.mdns.stop_browse(PEER_SERVICE_TYPE);
+        let _ = self.mdns.shutdown();
+    }
+}
+
+async fn accept_loop(shared: Arc<Shared>, listener: TcpListener) {
+    loop {
+        tokio::select! {
+            _ = shared.cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, address)) => {
+                    tokio::spawn(run_connection(shared.clone(), stream, address, false));
+                }
+                // Usually running out of file descriptors; back off briefly
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            },
+        }
+    }
+}
+
+async fn discovery_loop(shared: Arc<Shared>, browser: mdns_sd::Receiver<ServiceEvent>) {
+    // Peers seen on the network, by id, with their advertised addresses
+    let mut known: HashMap<String, Vec<SocketAddr>> = HashMap::new();
+    let mut redial = tokio::time::interval(REDIAL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = shared.cancel.cancelled() => break,
+            event = browser.recv_async() => match event {
+                Ok(ServiceEvent::ServiceResolved(info)) => {
+                    let Some(id) = info.get_property_val_str("id").map(str::to_string) else {
+                        continue;
+                    };
+                    if id == shared.id {
+                        continue;
+                    }
+                    let mut addresses: Vec<SocketAddr> = info
+                        .get_addresses()
+                        .iter()
+                        .map(|ip| SocketAddr::new(*ip, info.get_port()))
+                        .collect();
+                    addresses.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+                    maybe_dial(&shared, &id, &addresses);
+                    known.insert(id, addresses);
+                }
+                Ok(ServiceEvent::ServiceRemoved(_, fullname)) => {
+                    // The instance name is the node id
+                    if let Some(id) = fullname.strip_suffix(PEER_SERVICE_TYPE) {
+                        known.remove(id.trim_end_matches('.'));
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            },
+            _ = redial.tick() => {
+                for (id, addresses) in &known {
+                    maybe_dial(&shared, id, addresses);
+                }
+            }
+        }
+    }
+}
+
+/// Connect to a discovered peer unless it's already connected, being dialed,
+/// or expected to dial us
+fn maybe_dial(shared: &Arc<Shared>, id: &str, addresses: &[SocketAddr]) {
+    if id <= shared.id.as_str() || addresses.is_empty() {
+        return;
+    }
+    if shared.peers.lock().unwrap().contains_key(id) {
+        return;
+    }
+    if !shared.dialing.lock().unwrap().insert(id.to_string()) {
+        return;
+    }
+
+    let shared = shared.clone();
+    let id = id.to_string();
+    let addresses = addresses.to_vec();
+    tokio::spawn(async move {
+        for address in addresses {
+            // Try the next address if this one is unreachable
+            if let Ok(Ok(stream)) =
+                tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address)).await
+            {
+                run_connection(shared.clone(), stream, address, true).await;
+                break;
+            }
+        }
+        shared.dialing.lock().unwrap().remove(&id);
+    });
+}
+
+/// Authenticate a connection, then relay messages until either side closes it
+async fn run_connection(
+    shared: Arc<Shared>,
+    stream: TcpStream,
+    address: SocketAddr,
+    initiator: bool,
+) {
+    let _ = stream.set_nodelay(true);
+    let (mut reader, mut writer) = stream.into_split();
+
+    let hello = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
+        let transport = if initiator {
+            handshake_initiator(&mut reader, &mut writer, &shared.psk).await?
+        } else {
+            handshake_responder(&mut reader, &mut writer, &shared.psk).await?
+        };
+        let transport = Mutex::new(transport);
+        let hello = PeerMessage::Hello {
+            id: shared.id.clone(),
+            name: shared.name.clone(),
+        };
+        send_message(&mut writer, &transport, &hello).await?;
+        match recv_message(&mut reader, &transport).await? {
+            PeerMessage::Hello { id, name } => Ok((transport, id, name)),
+            _ => Err(ClientError::Connection(
+                "P2P peer did not introduce itself".to_string(),
+            )),
+        }
+    })
+    .await;
+
+    // Peers with a different sync key fail here and are simply ignored
+    let Ok(Ok((transport, id, name))) = hello else {
+        return;
+    };
+    if id == shared.id {
+        return;
+    }
+
+    let info = PeerInfo { id, name, address };
+    let conn_id = shared.next_conn_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, mut outgoing) = mpsc::unbounded_channel();
+    {
+        let mut peers = shared.peers.lock().unwrap();
+        if peers.contains_key(&info.id) {
+            // Already connected through another address
+            return;
+        }
+        peers.insert(
+            info.id.clone(),
+            Peer {
+                info: info.clone(),
+                sender,
+                conn_id,
+            },
+        );
+    }
+    let _ = shared.events.send(PeerEvent::Connected(info.clone()));
+
+    let transport = Arc::new(transport);
+    let writer_transport = transport.clone();
+    let writer_task = tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            if send_message(&mut writer, &writer_transport, &message)
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            _ = shared.cancel.cancelled() => break,
+            message = recv_message(&mut reader, &transport) => match message {
+                Ok(PeerMessage::Clip { clip }) => {
+                    let _ = shared.events.send(PeerEvent::Clip {
+                        from: info.clone(),
+                        clip,
+                    });
+                }
+                Ok(PeerMessage::Hello { .. }) => {}
+                Err(_) => break,
+            },
+        }
+    }
+
+    writer_task.abort();
+    {
+        let mut peers = shared.peers.lock().unwrap();
+        if peers
+            .get(&info.id)
+            .is_some_and(|peer| peer.conn_id == conn_id)
+        {
+            peers.remove(&info.id);
+        }
+    }
+    let _ = shared.events.send(PeerEvent::Disconnected(info));
+}
+
+/// Derive the Noise pre-shared key from a user-supplied sync key
+fn derive_psk(sync_key: &str) -> [u8; 32] {
+    let params = argon2::Params::new(PSK_MEMORY_KIB, PSK_ITERATIONS, PSK_PARALLELISM, Some(32))
+        .expect("valid Argon2 parameters");
+    let mut psk = [0u8; 32];
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(sync_key.trim().as_bytes(), PSK_SALT, &mut psk)
+        .expect("valid Argon2 input");
+    psk
+}
+
+fn generate_node_id() -> Result<String> {
+    let keypair = Builder::new(noise_params())
+        .generate_keypair()
+        .map_err(noise_error)?;
+    Ok(hex::encode(&keypair.private[..8]))
+}
+
+fn noise_params() -> snow::params::NoiseParams {
+    NOISE_PARAMS.parse().expect("valid Noise parameters")
+}
+
+fn noise_builder(psk: &[u8; 32]) -> Builder<'_> {
+    Builder::new(noise_params()).psk(0, psk).prologue(PROLOGUE)
+}
+
+async fn handshake_initiator<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    psk: &[u8; 32],
+) -> Result<TransportState>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut noise = noise_builder(psk).build_initiator().map_err(noise_error)?;
+    write_handshake(writer, &mut noise).await?;
+    read_handshake(reader, &mut noise).await?;
+    noise.into_transport_mode().map_err(noise_error)
+}
+
+async fn handshake_responder<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    psk: &[u8; 32],
+) -> Result<TransportState>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut noise = noise_builder(psk).build_responder().map_err(noise_error)?;
+    read_handshake(reader, &mut noise).await?;
+    write_handshake(writer, &mut noise).await?;
+    noise.into_transport_mode().map_err(noise_error)
+}
+
+async fn write_handshake<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    noise: &mut HandshakeState,
+) -> Result<()> {
+    let mut buf = vec![0u8; MAX_FRAME_SIZE];
+    let len = noise.write_message(&[], &mut buf).map_err(noise_error)?;
+    write_frame(writer, &buf[..len]).await
+}
+
+async fn read_handshake<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    noise: &mut HandshakeState,
+) -> Result<()> {
+    let frame = read_frame(reader).await?;
+    let mut buf = vec![0u8; MAX_FRAME_SIZE];
+    noise
+        .read_message(&frame, &mut buf)
+        .map_err(|_| ClientError::Unauthorized("P2P sync key mismatch".to_string()))?;
+    Ok(())
+}
+
+async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, frame: &[u8]) -> Result<()> {
+    writer.write_u16(frame.len() as u16).await?;
+    writer.write_all(frame).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = reader.read_u16().await? as usize;
+    let mut frame = vec![0u8; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(frame)
+}
+
+fn encrypt(transport: &Mutex<TransportState>, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; plaintext.len() + TAG_SIZE];
+    let len = transport
+        .lock()
+        .unwrap()
+        .write_message(plaintext, &mut buf)
+        .map_err(noise_error)?;
+    buf.truncate(len);
+    Ok(buf)
+}
+
+fn decrypt(transport: &Mutex<TransportState>, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; ciphertext.len()];
+    let len = transport
+        .lock()
+        .unwrap()
+        .read_message(ciphertext, &mut buf)
+        .map_err(noise_error)?;
+    buf.truncate(len);
+    Ok(buf)
+}
+
+async fn send_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    transport: &Mutex<TransportState>,
+    message: &PeerMessage,
+) -> Result<()> {
+    let payload = serde_json::to_vec(message)?;
+    if payload.len() > MAX_MESSAGE_SIZE {
+        return Err(ClientError::QuotaExceeded(format!(
+            "P2P message of {} bytes exceeds the {} byte limit",
+            payload.len(),
+            MAX_MESSAGE_SIZE
+        )));
+    }
+
+    let header = encrypt(transport, &(payload.len() as u32).to_be_bytes())?;
+    write_frame(writer, &header).await?;
+    for chunk in payload.chunks(MAX_CHUNK_SIZE) {
+        let frame = encrypt(transport, chunk)?;
+        write_frame(writer, &frame).await?;
+    }
+    Ok(())
+}
+
+async fn recv_message<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    transport: &Mutex<TransportState>,
+) -> Result<PeerMessage> {
+    let header = decrypt(transport, &read_frame(reader).await?)?;
+    let len = <[u8; 4]>::try_from(header.as_slice())
+        .map(u32::from_be_bytes)
+        .map_err(|_| ClientError::Connection("Invalid P2P message header".to_string()))?
+        as usize;
+    if len > MAX_MESSAGE_SIZE {
+        return Err(ClientError::QuotaExceeded(format!(
+            "P2P message of {} bytes exceeds the {} byte limit",
+            len, MAX_MESSAGE_SIZE
+        )));
+    }
+
+    let mut payload = Vec::with_capacity(len);
+    while payload.len() < len {
+        let chunk = decrypt(transport, &read_frame(reader).await?)?;
+        if chunk.is_empty() || payload.len() + chunk.len() > len {
+            return Err(ClientError::Connection(
+                "Invalid P2P message length".to_string(),
+            ));
+        }
+        payload.extend_from_slice(&chunk);
+    }
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+fn noise_error(e: snow::Error) -> ClientError {
+    ClientError::Connection(format!("Noise error: {}", e))
+}
+
+fn mdns_error(e: mdns_sd::Error) -> ClientError {
+    ClientError::Connection(format!("mDNS error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run both sides of the handshake over an in-memory pipe
+    async fn connect_pair(
+        initiator_key: &str,
+        responder_key: &str,
+    ) -> (
+        Result<(tokio::io::DuplexStream, Mutex<TransportState>)>,
+        Result<(tokio::io::DuplexStream, Mutex<TransportState>)>,
+    ) {
+        let (a, b) = tokio::io::duplex(MAX_FRAME_SIZE * 4);
+        let initiator_psk = derive_psk(initiator_key);
+        let responder_psk = derive_psk(responder_key);
+        // Each side owns its end, so a failed handshake closes the pipe
+        // instead of leaving the other side waiting
+        let initiator = async move {
+            let (mut reader, mut writer) = tokio::io::split(a);
+            let transport = handshake_initiator(&mut reader, &mut writer, &initiator_psk).await?;
+            Ok((reader.unsplit(writer), Mutex::new(transport)))
+        };
+        let responder = async move {
+            let (mut reader, mut writer) = tokio::io::split(b);
+            let transport = handshake_responder(&mut reader, &mut writer, &responder_psk).await?;
+            Ok((reader.unsplit(writer), Mutex::new(transport)))
+        };
+        tokio::join!(initiator, responder)
+    }
+
+    fn clip(content: String) -> SyncedClip {
+        SyncedClip {
+            content,
+            tags: vec!["$host:desktop-abc".to_string()],
+            additional_notes: None,
+            language: Some("rust".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_round_trip() {
+        let (a, b) = connect_pair("secret", " secret ").await;
+        let (mut a, a_transport) = a.unwrap();
+        let (mut b, b_transport) = b.unwrap();
+
+        let message = PeerMessage::Clip {
+            clip: clip("hello".to_string()),
+        };
+        send_message(&mut a, &a_transport, &message).await.unwrap();
+        let received = recv_message(&mut b, &b_transport).await.unwrap();
+        assert_eq!(received, message);
+
+        // And the other way
+        let hello = PeerMessage::Hello {
+            id: "abc".to_string(),
+            name: "laptop".to_string(),
+        };
+        send_message(&mut b, &b_transport, &hello).await.unwrap();
+        assert_eq!(recv_message(&mut a, &a_transport).await.unwrap(), hello);
+    }
+
+    #[tokio::test]
+    async fn test_large_message_is_split_into_frames() {
+        let (a, b) = connect_pair("secret", "secret").await;
+        let (a, a_transport) = a.unwrap();
+        let (b, b_transport) = b.unwrap();
+
+        let message = PeerMessage::Clip {
+            clip: clip("x".repeat(MAX_FRAME_SIZE * 3)),
+        };
+        let expected = message.clone();
+        // The pipe buffer is smaller than the message, so read concurrently
+        let sender = tokio::spawn(async move {
+            let mut a = a;
+            send_message(&mut a, &a_transport, &message).await.unwrap();
+            a
+        });
+        let mut b = b;
+        let received = recv_message(&mut b, &b_transport).await.unwrap();
+        sender.await.unwrap();
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_fails_with_wrong_key() {
+        let (a, b) = connect_pair("secret", "another secret").await;
+        assert!(a.is_err() || b.is_err());
+        assert!(matches!(b, Err(ClientError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn test_peer_message_format() {
+        let hello = PeerMessage::Hello {
+            id: "abc".to_string(),
+            name: "laptop".to_string(),
+        };
+        let json = serde_json::to_value(&hello).unwrap();
+        assert_eq!(json["type"], "hello");
+        assert_eq!(json["id"], "abc");
+    }
+
+    #[test]
+    fn test_node_ids_are_random() {
+        let a = generate_node_id().unwrap();
+        let b = generate_node_id().unwrap();
+        assert_eq!(a.len(), 16);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,74 @@
+//! Reconnection policy and status reporting for WebSocket notifications
+//!
+//! Used by [`ClipperClient::subscribe_notifications_with_reconnect`](crate::ClipperClient::subscribe_notifications_with_reconnect).
+
+use std::time::Duration;
+
+/// Exponential backoff settings for reconnecting the notification WebSocket
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Delay before the first reconnection attempt
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each failed attempt
+    pub multiplier: f64,
+    /// Give up after this many consecutive failed attempts (None = retry forever)
+    pub max_attempts: Option<u32>,
+    /// After reconnecting, fetch clips created or edited while disconnected
+    /// and deliver them as `NewClip` or `UpdatedClip` notifications
+    pub recover_missed: bool,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            max_attempts: None,
+            recover_missed: true,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Delay before the given reconnection attempt (starting from 1)
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        Duration::from_secs_f64(delay.min(self.max_delay.as_secs_f64()))
+    }
+}
+
+/// State of a reconnecting notification subscription
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionStatus {
+    /// Opening the WebSocket connection and authenticating
+    Connecting,
+    /// Connected and receiving notifications
+    Connected,
+    /// The connection was lost or could not be established
+    Disconnected { error: Option<String> },
+    /// Waiting before the next reconnection attempt
+    Reconnecting { attempt: u32, delay: Duration },
+    /// Connected, but the clips missed while disconnected could not be
+    /// fetched; views should be refreshed
+    RecoveryFailed { error: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_for_attempt_backs_off_to_max() {
+        let config = ReconnectConfig::default();
+
+        assert_eq!(config.delay_for_attempt(1), Duration::from_secs(1));
+        assert_eq!(config.delay_for_attempt(2), Duration::from_secs(2));
+        assert_eq!(config.delay_for_attempt(4), Duration::from_secs(8));
+        assert_eq!(config.delay_for_attempt(10), Duration::from_secs(60));
+        assert_eq!(config.delay_for_attempt(u32::MAX), Duration::from_secs(60));
+    }
+}
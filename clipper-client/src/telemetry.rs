@@ -0,0 +1,124 @@
+//! Request IDs and, with the `otel` feature, OpenTelemetry tracing.
+//!
+//! Every request carries an `x-request-id` header, which the server echoes
+//! back, logs and includes in error responses, so an error a user reports
+//! can be found in the server's logs. Retries of a request reuse its ID.
+//!
+//! With the `otel` feature each request also runs in a client span, and the
+//! span's W3C trace context (`traceparent`) is sent along so the server's
+//! spans join the caller's trace. Spans are only recorded once the
+//! application installs a tracing subscriber with an OpenTelemetry layer,
+//! e.g. with [`init_otlp_tracing`].
+
+/// Header carrying the request ID
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// A new random request ID
+pub(crate) fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Span covering one attempt of `request`
+#[cfg(feature = "otel")]
+pub(crate) fn request_span(request: &reqwest::Request, request_id: &str) -> tracing::Span {
+    tracing::info_span!(
+        "HTTP request",
+        otel.name = %format!("{} {}", request.method(), request.url().path()),
+        otel.kind = "client",
+        http.request.method = %request.method(),
+        url.path = request.url().path(),
+        request_id = request_id,
+    )
+}
+
+/// Add the trace context of `span` to `headers`
+#[cfg(feature = "otel")]
+pub(crate) fn inject_trace_context(span: &tracing::Span, headers: &mut reqwest::header::HeaderMap) {
+    use opentelemetry::propagation::Injector;
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let context = span.context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(headers))
+    });
+}
+
+/// Export spans over OTLP/HTTP if `OTEL_EXPORTER_OTLP_ENDPOINT` (or
+/// `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`) is set, e.g. to
+/// `http://localhost:4318`.
+///
+/// Installs the global tracing subscriber and W3C trace context propagator,
+/// so call it once at startup, and keep the returned guard alive until exit:
+/// dropping it flushes the spans that haven't been sent yet.
+///
+/// # Returns
+/// * `Ok(None)` if no endpoint is set
+/// * `Err` if the exporter or the subscriber couldn't be installed
+#[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+pub fn init_otlp_tracing(service_name: &'static str) -> Result<Option<TelemetryGuard>, String> {
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::{
+        Resource, propagation::TraceContextPropagator, trace::SdkTracerProvider,
+    };
+    use tracing_subscriber::{
+        Layer, filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt,
+    };
+
+    let configured = [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    ]
+    .iter()
+    .any(|name| std::env::var(name).is_ok_and(|value| !value.is_empty()));
+    if !configured {
+        return Ok(None);
+    }
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .map_err(|e| format!("Failed to create OTLP exporter: {}", e))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    tracing_subscriber::registry()
+        .with(
+            tracing_opentelemetry::layer()
+                .with_tracer(provider.tracer(service_name))
+                .with_filter(LevelFilter::INFO),
+        )
+        .try_init()
+        .map_err(|e| format!("Failed to install tracing subscriber: {}", e))?;
+
+    Ok(Some(TelemetryGuard { provider }))
+}
+
+/// Flushes and stops span export when dropped
+#[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+pub struct TelemetryGuard {
+    provider: opentelemetry_sdk::trace::SdkTracerProvider,
+}
+
+#[cfg(all(feature = "otel", not(target_arch = "wasm32")))]
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        let _ = self.provider.shutdown();
+    }
+}
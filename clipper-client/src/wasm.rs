@@ -0,0 +1,159 @@
+//! Browser transport for wasm32 targets
+//!
+//! HTTP requests go through `reqwest`, which uses `fetch` in the browser.
+//! This module provides the pieces that need browser APIs directly: the
+//! notification WebSocket and a timer for retry backoff. It works in both
+//! window and worker contexts (e.g. a browser extension service worker).
+
+use crate::client::ClipperClient;
+use crate::error::{ClientError, Result};
+use crate::models::{ClipNotification, WsAuthRequest, WsAuthResponse};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{MessageEvent, WebSocket};
+
+/// Wait for the given duration using the global `setTimeout`
+pub(crate) async fn sleep(duration: Duration) {
+    let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let set_timeout = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        match set_timeout {
+            Some(set_timeout) => {
+                let _ = set_timeout.call2(&JsValue::NULL, &resolve, &JsValue::from(millis));
+            }
+            None => {
+                let _ = resolve.call0(&JsValue::NULL);
+            }
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// An open notification WebSocket in the browser
+///
+/// Notifications are forwarded to the channel until the subscription is
+/// closed or dropped, or the server closes the connection.
+pub struct NotificationSubscription {
+    socket: WebSocket,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl NotificationSubscription {
+    /// Whether the connection is still open
+    pub fn is_open(&self) -> bool {
+        self.socket.ready_state() == WebSocket::OPEN
+    }
+
+    /// Close the connection
+    pub fn close(&self) {
+        let _ = self.socket.close();
+    }
+}
+
+impl Drop for NotificationSubscription {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.close();
+    }
+}
+
+/// Wait for the next event that resolves or rejects a promise wired to the socket
+async fn next_event(
+    socket: &WebSocket,
+    wire: impl FnOnce(&WebSocket, &js_sys::Function, &js_sys::Function),
+) -> std::result::Result<JsValue, JsValue> {
+    let mut wire = Some(wire);
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        if let Some(wire) = wire.take() {
+            wire(socket, &resolve, &reject);
+        }
+    });
+    JsFuture::from(promise).await
+}
+
+impl ClipperClient {
+    /// Connect to the server's WebSocket endpoint and receive real-time notifications
+    ///
+    /// Uses the browser's WebSocket API. There is no automatic reconnection;
+    /// check [`NotificationSubscription::is_open`] and subscribe again if needed.
+    ///
+    /// # Arguments
+    /// * `channel` - A tokio mpsc sender to push notifications to
+    pub async fn subscribe_notifications(
+        &self,
+        channel: mpsc::UnboundedSender<ClipNotification>,
+    ) -> Result<NotificationSubscription> {
+        let socket = WebSocket::new(&self.websocket_url())
+            .map_err(|e| ClientError::WebSocket(format!("{:?}", e)))?;
+
+        let opened = next_event(&socket, |socket, resolve, reject| {
+            socket.set_onopen(Some(resolve));
+            socket.set_onerror(Some(reject));
+        })
+        .await;
+        socket.set_onopen(None);
+        socket.set_onerror(None);
+        opened.map_err(|_| ClientError::Connection("WebSocket connection failed".to_string()))?;
+
+        // If we have a token, send auth message and wait for response
+        if let Some(token) = self.token() {
+            let auth_json = serde_json::to_string(&WsAuthRequest::Auth {
+                token: token.to_string(),
+            })?;
+
+            let response = next_event(&socket, |socket, resolve, reject| {
+                socket.set_onmessage(Some(resolve));
+                socket.set_onclose(Some(reject));
+                if socket.send_with_str(&auth_json).is_err() {
+                    let _ = reject.call0(&JsValue::NULL);
+                }
+            });
+            let response = response.await;
+            socket.set_onmessage(None);
+            socket.set_onclose(None);
+
+            let text = response
+                .map_err(|_| ClientError::WebSocket("Connection closed during auth".to_string()))?
+                .dyn_into::<MessageEvent>()
+                .ok()
+                .and_then(|event| event.data().as_string())
+                .unwrap_or_default();
+
+            match serde_json::from_str::<WsAuthResponse>(&text) {
+                Ok(WsAuthResponse::AuthSuccess) => {}
+                Ok(WsAuthResponse::AuthError { message }) => {
+                    let _ = socket.close();
+                    return Err(ClientError::Unauthorized(format!(
+                        "WebSocket auth failed: {}",
+                        message
+                    )));
+                }
+                Err(_) => {
+                    let _ = socket.close();
+                    return Err(ClientError::WebSocket(
+                        "Unexpected message during auth".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(text) = event.data().as_string()
+                && let Ok(notification) = serde_json::from_str::<ClipNotification>(&text)
+            {
+                let _ = channel.send(notification);
+            }
+        });
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        Ok(NotificationSubscription {
+            socket,
+            _on_message: on_message,
+        })
+    }
+}
@@ -1,4 +1,6 @@
-use clipper_client::{ClipNotification, ClipperClient, SearchFilters};
+use clipper_client::{
+    ClientError, ClipNotification, ClipperClient, ContentFormat, SearchFilters, Transform,
+};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -87,6 +89,131 @@ async fn test_get_clip() {
     assert_eq!(retrieved.tags, vec!["findme"]);
 }
 
+#[tokio::test]
+async fn test_get_clips() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let first = client
+        .create_clip("Batch one".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+    let second = client
+        .create_clip("Batch two".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+
+    let clips = client
+        .get_clips(&[
+            second.id.clone(),
+            "nonexistent123".to_string(),
+            first.id.clone(),
+        ])
+        .await
+        .expect("Failed to get clips");
+
+    let ids: Vec<&str> = clips.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec![second.id.as_str(), first.id.as_str()]);
+}
+
+#[tokio::test]
+async fn test_clip_links() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let screenshot = client
+        .create_clip("Screenshot".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+    let text = client
+        .create_clip("Extracted text".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+
+    let link = client
+        .link_clips(&text.id, &screenshot.id, "derived_from")
+        .await
+        .expect("Failed to link clips");
+    assert_eq!(link.target_id, screenshot.id);
+
+    let links = client
+        .get_clip_links(&screenshot.id)
+        .await
+        .expect("Failed to get links");
+    assert!(links.iter().any(|l| l.id == link.id));
+
+    client
+        .delete_clip_link(&text.id, &link.id)
+        .await
+        .expect("Failed to delete link");
+    let links = client
+        .get_clip_links(&text.id)
+        .await
+        .expect("Failed to get links");
+    assert!(links.is_empty());
+}
+
+#[tokio::test]
+async fn test_collections() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let first = client
+        .create_clip("First snippet".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+    let second = client
+        .create_clip("Second snippet".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+
+    // Collection names are unique, so derive one from a new clip ID
+    let collection = client
+        .create_collection(&format!("Board {}", first.id), None)
+        .await
+        .expect("Failed to create collection");
+
+    client
+        .add_clip_to_collection(&collection.id, &first.id, None)
+        .await
+        .expect("Failed to add clip");
+    client
+        .add_clip_to_collection(&collection.id, &second.id, Some(0))
+        .await
+        .expect("Failed to add clip");
+
+    let page = client
+        .list_collection_clips(&collection.id, 1, 20)
+        .await
+        .expect("Failed to list collection clips");
+    let ids: Vec<&str> = page.items.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, [second.id.as_str(), first.id.as_str()]);
+
+    client
+        .reorder_collection(&collection.id, &[first.id.clone(), second.id.clone()])
+        .await
+        .expect("Failed to reorder collection");
+    client
+        .remove_clip_from_collection(&collection.id, &second.id)
+        .await
+        .expect("Failed to remove clip");
+
+    let collections = client
+        .get_clip_collections(&first.id)
+        .await
+        .expect("Failed to get clip collections");
+    assert!(collections.iter().any(|c| c.id == collection.id));
+
+    client
+        .delete_collection(&collection.id)
+        .await
+        .expect("Failed to delete collection");
+    assert!(client.get_collection(&collection.id).await.is_err());
+}
+
 #[tokio::test]
 async fn test_get_nonexistent_clip() {
     wait_for_server().await;
@@ -126,6 +253,8 @@ async fn test_update_clip() {
             Some(vec!["updated".to_string(), "new".to_string()]),
             Some("Updated notes".to_string()),
             None,
+            None,
+            None,
         )
         .await
         .expect("Failed to update clip");
@@ -150,7 +279,14 @@ async fn test_update_clip_tags_only() {
 
     // Update only tags
     let updated = client
-        .update_clip(&created.id, Some(vec!["new".to_string()]), None, None)
+        .update_clip(
+            &created.id,
+            Some(vec!["new".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -244,6 +380,87 @@ async fn test_list_clips_with_tag_filter() {
     assert!(clips.items.iter().any(|c| c.content == "Important clip"));
 }
 
+#[tokio::test]
+async fn test_list_clips_stream_and_for_each_clip() {
+    use futures_util::TryStreamExt;
+
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+    let tag = format!(
+        "stream-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+
+    for i in 0..3 {
+        client
+            .create_clip(format!("Streamed clip {}", i), vec![tag.clone()], None, None)
+            .await
+            .expect("Failed to create clip");
+    }
+
+    let filters = SearchFilters::new().with_tags(vec![tag.clone()]);
+    let clips: Vec<_> = client
+        .list_clips_stream(filters.clone())
+        .try_collect()
+        .await
+        .expect("Failed to stream clips");
+    assert_eq!(clips.len(), 3);
+    assert!(clips.iter().all(|c| c.tags.contains(&tag)));
+
+    let mut contents = Vec::new();
+    let count = client
+        .for_each_clip(filters, |clip| {
+            contents.push(clip.content);
+            async { Ok(()) }
+        })
+        .await
+        .expect("Failed to iterate clips");
+    assert_eq!(count, 3);
+    assert!(contents.contains(&"Streamed clip 0".to_string()));
+}
+
+#[tokio::test]
+async fn test_list_clips_after_cursor() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+    let tag = format!(
+        "cursor-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    );
+
+    for i in 0..3 {
+        client
+            .create_clip(format!("Cursor clip {}", i), vec![tag.clone()], None, None)
+            .await
+            .expect("Failed to create clip");
+    }
+
+    let filters = SearchFilters::new().with_tags(vec![tag.clone()]);
+    let first = client
+        .list_clips_after(filters.clone(), None, 2)
+        .await
+        .expect("Failed to list clips");
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.total, 3);
+    let cursor = first.next_cursor.expect("More clips follow, so there should be a cursor");
+
+    let second = client
+        .list_clips_after(filters, Some(&cursor), 2)
+        .await
+        .expect("Failed to list clips");
+    assert_eq!(second.items.len(), 1);
+    assert!(second.next_cursor.is_none());
+    assert!(first.items.iter().all(|c| c.id != second.items[0].id));
+}
+
 #[tokio::test]
 async fn test_search_clips() {
     wait_for_server().await;
@@ -361,7 +578,9 @@ async fn test_websocket_notifications() {
         .expect("Channel closed");
 
     match notification {
-        ClipNotification::NewClip { id, content, tags } => {
+        ClipNotification::NewClip {
+            id, content, tags, ..
+        } => {
             assert_eq!(id, created.id);
             assert_eq!(content, "Notification test");
             assert_eq!(tags, vec!["notify"]);
@@ -370,6 +589,51 @@ async fn test_websocket_notifications() {
     }
 }
 
+#[tokio::test]
+async fn test_websocket_sync_tags() {
+    wait_for_server().await;
+
+    let client = ClipperClient::builder(test_server_url())
+        .sync_tags(vec!["sync-wanted".to_string()])
+        .build()
+        .expect("Failed to build client");
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let _handle = client
+        .subscribe_notifications(tx)
+        .await
+        .expect("Failed to subscribe to notifications");
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    client
+        .create_clip(
+            "Not synced".to_string(),
+            vec!["sync-unwanted".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create clip");
+    let wanted = client
+        .create_clip(
+            "Synced".to_string(),
+            vec!["sync-wanted".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create clip");
+
+    // The first notification is for the clip with the wanted tag
+    let notification = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("Timeout waiting for notification")
+        .expect("Channel closed");
+    match notification {
+        ClipNotification::NewClip { id, .. } => assert_eq!(id, wanted.id),
+        _ => panic!("Expected NewClip notification"),
+    }
+}
+
 #[tokio::test]
 async fn test_websocket_update_notification() {
     wait_for_server().await;
@@ -401,7 +665,14 @@ async fn test_websocket_update_notification() {
 
     // Update the clip
     client
-        .update_clip(&created.id, Some(vec!["updated".to_string()]), None, None)
+        .update_clip(
+            &created.id,
+            Some(vec!["updated".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -412,7 +683,7 @@ async fn test_websocket_update_notification() {
         .expect("Channel closed");
 
     match notification {
-        ClipNotification::UpdatedClip { id } => {
+        ClipNotification::UpdatedClip { id, .. } => {
             assert_eq!(id, created.id);
         }
         _ => panic!("Expected UpdatedClip notification"),
@@ -583,7 +854,9 @@ async fn test_upload_file_with_websocket_notification() {
         .expect("Channel closed");
 
     match notification {
-        ClipNotification::NewClip { id, content, tags } => {
+        ClipNotification::NewClip {
+            id, content, tags, ..
+        } => {
             assert_eq!(id, clip.id);
             assert_eq!(content, "File upload notification test");
             assert_eq!(tags, vec!["notify"]);
@@ -669,7 +942,14 @@ async fn test_update_clip_add_language() {
 
     // Update to add a language
     let updated = client
-        .update_clip(&created.id, None, None, Some("javascript".to_string()))
+        .update_clip(
+            &created.id,
+            None,
+            None,
+            Some("javascript".to_string()),
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -705,7 +985,14 @@ async fn test_update_clip_change_language() {
 
     // Update to change the language
     let updated = client
-        .update_clip(&created.id, None, None, Some("ruby".to_string()))
+        .update_clip(
+            &created.id,
+            None,
+            None,
+            Some("ruby".to_string()),
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -731,7 +1018,14 @@ async fn test_update_clip_language_preserves_other_fields() {
 
     // Update only the language
     let updated = client
-        .update_clip(&created.id, None, None, Some("javascript".to_string()))
+        .update_clip(
+            &created.id,
+            None,
+            None,
+            Some("javascript".to_string()),
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -762,7 +1056,14 @@ async fn test_update_clip_tags_preserves_language() {
 
     // Update only the tags (pass None for language)
     let updated = client
-        .update_clip(&created.id, Some(vec!["updated".to_string()]), None, None)
+        .update_clip(
+            &created.id,
+            Some(vec!["updated".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
         .await
         .expect("Failed to update clip");
 
@@ -771,3 +1072,180 @@ async fn test_update_clip_tags_preserves_language() {
     // Verify language is preserved
     assert_eq!(updated.language, Some("go".to_string()));
 }
+
+#[tokio::test]
+async fn test_update_clip_color_and_icon() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let created = client
+        .create_clip("Deploy checklist".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+    assert_eq!(created.color, None);
+
+    let updated = client
+        .update_clip(
+            &created.id,
+            None,
+            None,
+            None,
+            Some("red".to_string()),
+            Some("🚀".to_string()),
+        )
+        .await
+        .expect("Failed to update clip");
+    assert_eq!(updated.color, Some("red".to_string()));
+    assert_eq!(updated.icon, Some("🚀".to_string()));
+
+    // Empty strings clear the color and icon
+    let cleared = client
+        .update_clip(
+            &created.id,
+            None,
+            None,
+            None,
+            Some(String::new()),
+            Some(String::new()),
+        )
+        .await
+        .expect("Failed to update clip");
+    assert_eq!(cleared.color, None);
+    assert_eq!(cleared.icon, None);
+}
+
+#[tokio::test]
+async fn test_content_format() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let created = client
+        .create_clip_with_format(
+            "# Notes\n\n**bold**".to_string(),
+            vec![],
+            None,
+            None,
+            ContentFormat::Markdown,
+        )
+        .await
+        .expect("Failed to create clip");
+    assert_eq!(created.content_format, Some(ContentFormat::Markdown));
+
+    let updated = client
+        .set_clip_content_format(&created.id, ContentFormat::Plain)
+        .await
+        .expect("Failed to set content format");
+    assert_eq!(updated.content_format, Some(ContentFormat::Plain));
+    assert_eq!(updated.content, created.content);
+}
+
+#[tokio::test]
+async fn test_source_device() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+    // Unique per run, since the test server keeps clips between tests
+    let device = format!(
+        "test-device-{}",
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    );
+
+    let created = client
+        .create_clip(
+            "From a test device".to_string(),
+            vec![format!("$host:{}", device)],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create clip");
+    assert_eq!(created.source_device.as_deref(), Some(device.as_str()));
+
+    let clips = client
+        .list_clips(SearchFilters::new().with_source_device(&device), 1, 20)
+        .await
+        .expect("Failed to list clips");
+    assert_eq!(clips.total, 1);
+    assert_eq!(clips.items[0].id, created.id);
+
+    let devices = client.list_devices().await.expect("Failed to list devices");
+    let listed = devices
+        .iter()
+        .find(|d| d.name == device)
+        .expect("Device not listed");
+    assert_eq!(listed.clip_count, 1);
+}
+
+#[tokio::test]
+async fn test_pairing_requires_server_token() {
+    wait_for_server().await;
+
+    // The test server runs without a bearer token, so pairing is unavailable
+    let client = ClipperClient::new(test_server_url());
+    let result = client.create_pairing_code().await;
+    assert!(
+        matches!(result, Err(ClientError::ServerError { status: 503, .. })),
+        "Expected 503, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_transform_clip() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let source = client
+        .create_clip("  hello world  ".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to create clip");
+
+    let derived = client
+        .transform_clip(&source.id, &[Transform::Trim, Transform::Uppercase])
+        .await
+        .expect("Failed to transform clip");
+    assert_ne!(derived.id, source.id);
+    assert_eq!(derived.content, "HELLO WORLD");
+
+    let links = client.get_clip_links(&source.id).await.unwrap();
+    assert!(
+        links
+            .iter()
+            .any(|link| link.source_id == derived.id && link.relation == "derived_from")
+    );
+
+    // Undecodable input is rejected
+    assert!(
+        client
+            .transform_clip(&source.id, &[Transform::Base64Decode])
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_translate_clip_without_provider() {
+    wait_for_server().await;
+
+    let client = ClipperClient::new(test_server_url());
+
+    let source = client
+        .create_clip(
+            "The meeting is moved to Thursday afternoon, see you there.".to_string(),
+            vec![],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to create clip");
+    assert_eq!(source.detected_language.as_deref(), Some("en"));
+
+    // The test server has no translation provider
+    assert!(matches!(
+        client.translate_clip(&source.id, "de").await,
+        Err(ClientError::ServerError { status: 503, .. })
+    ));
+}
@@ -0,0 +1,211 @@
+//! System clipboard access for Clipper, shared by the desktop app and
+//! `clipper-agent`.
+//!
+//! [`read_clipboard_content`] reads what was copied: files copied in
+//! Finder/Explorer first, then images, then text. [`ClipboardWatcher`] polls
+//! it with a handle that is recreated when clipboard access fails, and
+//! [`set_clipboard_text`] and [`set_clipboard_image`] put clips back. On
+//! Linux, [`primary`] handles the primary selection too.
+//!
+//! [`ClipboardEvents`] turns the polls of a [`Monitor`] into copy events;
+//! [`watch_clipboard`] runs it for the system clipboard.
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use arboard::Clipboard;
+use gethostname::gethostname;
+use image::{ImageBuffer, Rgba};
+
+mod monitor;
+#[cfg(target_os = "linux")]
+pub mod primary;
+mod watcher;
+
+pub use monitor::{ClipboardEvent, ClipboardEvents, Monitor, MonitorOptions, watch_clipboard};
+pub use watcher::{ClipboardWatcher, POLL_INTERVAL};
+
+/// Errors from clipboard access
+#[derive(Debug, thiserror::Error)]
+pub enum ClipboardError {
+    #[error("Clipboard access error: {0}")]
+    Access(#[from] arboard::Error),
+    #[error("Failed to decode image: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+/// Get the hostname tag in the format `$host:<hostname>`
+pub fn hostname_tag() -> String {
+    let hostname = gethostname().to_string_lossy().to_string();
+    format!("$host:{}", hostname)
+}
+
+/// Represents the type of clipboard content
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardContent {
+    Text(String),
+    Image(Vec<u8>),      // PNG-encoded bytes
+    Files(Vec<PathBuf>), // File paths from clipboard (e.g., copied from Finder/Explorer)
+    Empty,
+}
+
+/// Convert arboard ImageData to PNG bytes
+pub fn image_data_to_png(image_data: &arboard::ImageData) -> Option<Vec<u8>> {
+    let width = image_data.width as u32;
+    let height = image_data.height as u32;
+
+    // Create an image buffer from RGBA bytes
+    let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
+        ImageBuffer::from_raw(width, height, image_data.bytes.to_vec())?;
+
+    // Encode to PNG
+    let mut png_bytes = Cursor::new(Vec::new());
+    img.write_to(&mut png_bytes, image::ImageFormat::Png).ok()?;
+
+    Some(png_bytes.into_inner())
+}
+
+/// Decode image bytes (PNG, JPEG, ...) into RGBA data for the clipboard
+fn decode_image(image_bytes: &[u8]) -> Result<arboard::ImageData<'static>> {
+    let img = image::ImageReader::new(Cursor::new(image_bytes))
+        .with_guessed_format()
+        .map_err(image::ImageError::IoError)?
+        .decode()?;
+
+    let rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+
+    Ok(arboard::ImageData {
+        width: width as usize,
+        height: height as usize,
+        bytes: rgba.into_raw().into(),
+    })
+}
+
+/// Get current clipboard content (text, image, or files)
+///
+/// Priority: files > images > text (files take highest priority since copying
+/// files in Finder/Explorer also provides text fallback with filenames).
+/// Images are skipped unless `capture_images` is set, which also saves
+/// encoding them. Fails when the text can't be read, in which case the
+/// clipboard handle may need to be recreated.
+pub fn read_clipboard_content(
+    clipboard: &mut Clipboard,
+    capture_images: bool,
+) -> Result<ClipboardContent> {
+    // Try to get file list first (highest priority)
+    // When copying files in Finder/Explorer, the clipboard contains both file URIs and text fallback
+    match clipboard.get().file_list() {
+        Ok(files) => {
+            if !files.is_empty() {
+                return Ok(ClipboardContent::Files(files));
+            }
+        }
+        Err(arboard::Error::ContentNotAvailable) => {
+            // No file content, this is normal - try image
+        }
+        Err(e) => {
+            // Other errors might indicate clipboard handle issues
+            eprintln!("[clipboard] File list access error: {}", e);
+        }
+    }
+
+    // Try to get image (second priority)
+    if capture_images {
+        match clipboard.get_image() {
+            Ok(image_data) => {
+                if let Some(png_bytes) = image_data_to_png(&image_data) {
+                    return Ok(ClipboardContent::Image(png_bytes));
+                }
+            }
+            Err(arboard::Error::ContentNotAvailable) => {
+                // No image content, this is normal - try text
+            }
+            Err(e) => {
+                // Other errors might indicate clipboard handle issues
+                eprintln!("[clipboard] Image access error: {}", e);
+            }
+        }
+    }
+
+    // Fall back to text (lowest priority)
+    match clipboard.get_text() {
+        Ok(text) if !text.is_empty() => Ok(ClipboardContent::Text(text)),
+        // No text content either
+        Ok(_) | Err(arboard::Error::ContentNotAvailable) => Ok(ClipboardContent::Empty),
+        // Access error - clipboard handle may be stale
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Text of a `$filelist` clip: the full paths of copied files, one per line
+pub fn file_list_content(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Set text content to the system clipboard
+pub fn set_clipboard_text(content: &str) -> Result<()> {
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_text(content)?;
+    Ok(())
+}
+
+/// Set image content to the system clipboard from encoded image bytes (PNG,
+/// JPEG, ...)
+pub fn set_clipboard_image(image_bytes: &[u8]) -> Result<()> {
+    let image_data = decode_image(image_bytes)?;
+    let mut clipboard = Clipboard::new()?;
+    clipboard.set_image(image_data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_list_content() {
+        let paths = [
+            PathBuf::from("/home/me/notes.txt"),
+            PathBuf::from("/home/me/photos"),
+        ];
+        assert_eq!(
+            file_list_content(&paths),
+            "/home/me/notes.txt\n/home/me/photos"
+        );
+        assert_eq!(file_list_content(&[]), "");
+    }
+
+    #[test]
+    fn test_image_round_trip() {
+        let image_data = arboard::ImageData {
+            width: 2,
+            height: 1,
+            bytes: vec![255, 0, 0, 255, 0, 0, 255, 128].into(),
+        };
+        let png_bytes = image_data_to_png(&image_data).unwrap();
+        assert!(png_bytes.starts_with(b"\x89PNG"));
+
+        let decoded = decode_image(&png_bytes).unwrap();
+        assert_eq!((decoded.width, decoded.height), (2, 1));
+        assert_eq!(decoded.bytes, image_data.bytes);
+
+        assert!(matches!(
+            decode_image(b"not an image"),
+            Err(ClipboardError::Image(_))
+        ));
+        // Fewer bytes than the size calls for
+        let truncated = arboard::ImageData {
+            width: 2,
+            height: 2,
+            bytes: vec![0; 4].into(),
+        };
+        assert!(image_data_to_png(&truncated).is_none());
+    }
+}
@@ -0,0 +1,309 @@
+//! Turning clipboard polls into a stream of copy events
+//!
+//! A [`Monitor`] reads the clipboard of one platform. [`ClipboardEvents`]
+//! polls it and yields a [`ClipboardEvent`] for each new copy (and each
+//! settled primary selection), leaving what to do with them to the app.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::{ClipboardContent, ClipboardWatcher, POLL_INTERVAL};
+
+/// A clipboard that can be polled for changes
+pub trait Monitor: Send {
+    /// Read the clipboard; `None` when it can't be read right now
+    fn read(&mut self, capture_images: bool) -> Option<ClipboardContent>;
+
+    /// Read the primary selection's text, on platforms that have one
+    fn read_primary_selection(&mut self) -> Option<String> {
+        None
+    }
+
+    /// How long to wait before the next poll
+    fn delay(&self) -> Duration {
+        POLL_INTERVAL
+    }
+}
+
+impl Monitor for ClipboardWatcher {
+    fn read(&mut self, capture_images: bool) -> Option<ClipboardContent> {
+        ClipboardWatcher::read(self, capture_images)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_primary_selection(&mut self) -> Option<String> {
+        self.clipboard()
+            .map(crate::primary::get_primary_selection_text)
+    }
+
+    fn delay(&self) -> Duration {
+        ClipboardWatcher::delay(self)
+    }
+}
+
+/// What a monitor captures; shared with the app so it can be changed while
+/// the monitor runs
+#[derive(Clone)]
+pub struct MonitorOptions {
+    /// Whether copied images are read, rather than skipped
+    pub capture_images: Arc<AtomicBool>,
+    /// Whether the primary selection is polled too
+    pub capture_primary_selection: Arc<AtomicBool>,
+}
+
+impl Default for MonitorOptions {
+    fn default() -> Self {
+        Self {
+            capture_images: Arc::new(AtomicBool::new(true)),
+            capture_primary_selection: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Something new on the clipboard
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardEvent {
+    /// Content different from the last poll was copied
+    Copied(ClipboardContent),
+    /// Text selected with the mouse that stayed selected between two polls,
+    /// and isn't on the clipboard too
+    Selected(String),
+}
+
+/// The primary selection as seen by a monitor. The selection changes while
+/// the mouse is dragged, so its text is only reported once it stays the same
+/// between two polls.
+struct PrimarySelection {
+    text: String,
+    /// Whether `text` was reported or skipped already
+    handled: bool,
+}
+
+impl PrimarySelection {
+    /// Start watching the selection, skipping what is selected now
+    fn new(text: String) -> Self {
+        Self {
+            text,
+            handled: true,
+        }
+    }
+
+    /// Take the selection's text from this poll; returns it the first time
+    /// it hasn't changed since the last poll
+    fn settled(&mut self, text: String) -> Option<String> {
+        if text != self.text {
+            self.text = text;
+            self.handled = false;
+            return None;
+        }
+        if self.handled {
+            return None;
+        }
+        self.handled = true;
+        Some(self.text.clone())
+    }
+}
+
+/// Copy events of a [`Monitor`]
+///
+/// What is on the clipboard (or selected) on the first poll isn't reported,
+/// nor is an empty clipboard. Iterating waits [`Monitor::delay`] between
+/// polls and never ends; [`poll`](Self::poll) polls once without waiting.
+pub struct ClipboardEvents<M> {
+    monitor: M,
+    options: MonitorOptions,
+    /// `None` until the first successful poll
+    last_content: Option<ClipboardContent>,
+    /// `None` while the primary selection isn't captured
+    primary_selection: Option<PrimarySelection>,
+    pending: VecDeque<ClipboardEvent>,
+}
+
+impl<M: Monitor> ClipboardEvents<M> {
+    pub fn new(monitor: M, options: MonitorOptions) -> Self {
+        Self {
+            monitor,
+            options,
+            last_content: None,
+            primary_selection: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Poll the clipboard once and return what is new since the last poll
+    pub fn poll(&mut self) -> Vec<ClipboardEvent> {
+        let capture_images = self.options.capture_images.load(Ordering::SeqCst);
+        let Some(content) = self.monitor.read(capture_images) else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        if !self
+            .options
+            .capture_primary_selection
+            .load(Ordering::SeqCst)
+        {
+            self.primary_selection = None;
+        } else if let Some(text) = self.monitor.read_primary_selection() {
+            match self.primary_selection.as_mut() {
+                // What is selected when capture is turned on isn't reported
+                None => self.primary_selection = Some(PrimarySelection::new(text)),
+                Some(selection) => {
+                    // Skip text on the clipboard too, because it was copied
+                    if let Some(text) = selection.settled(text)
+                        && !text.trim().is_empty()
+                        && content != ClipboardContent::Text(text.clone())
+                    {
+                        events.push(ClipboardEvent::Selected(text));
+                    }
+                }
+            }
+        }
+
+        if content != ClipboardContent::Empty {
+            let first_poll = self.last_content.is_none();
+            if self.last_content.as_ref() != Some(&content) {
+                self.last_content = Some(content.clone());
+                if !first_poll {
+                    events.push(ClipboardEvent::Copied(content));
+                }
+            }
+        } else if self.last_content.is_none() {
+            self.last_content = Some(content);
+        }
+        events
+    }
+
+    /// Poll on a thread of its own, sending events until the receiver is
+    /// dropped
+    pub fn spawn(mut self) -> mpsc::Receiver<ClipboardEvent>
+    where
+        M: 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for event in &mut self {
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+}
+
+impl<M: Monitor> Iterator for ClipboardEvents<M> {
+    type Item = ClipboardEvent;
+
+    fn next(&mut self) -> Option<ClipboardEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            std::thread::sleep(self.monitor.delay());
+            self.pending = self.poll().into();
+        }
+    }
+}
+
+/// Read the system clipboard with a [`ClipboardWatcher`] on a thread of its
+/// own and send its copy events
+pub fn watch_clipboard(options: MonitorOptions) -> mpsc::Receiver<ClipboardEvent> {
+    ClipboardEvents::new(ClipboardWatcher::new(), options).spawn()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clipboard that returns scripted polls, `None` meaning a failed read
+    struct ScriptedMonitor {
+        polls: VecDeque<(Option<ClipboardContent>, &'static str)>,
+        selection: Option<String>,
+    }
+
+    impl ScriptedMonitor {
+        fn new(polls: Vec<(Option<ClipboardContent>, &'static str)>) -> Self {
+            Self {
+                polls: polls.into(),
+                selection: None,
+            }
+        }
+    }
+
+    impl Monitor for ScriptedMonitor {
+        fn read(&mut self, _capture_images: bool) -> Option<ClipboardContent> {
+            let (content, selection) = self.polls.pop_front()?;
+            self.selection = Some(selection.to_string());
+            content
+        }
+
+        fn read_primary_selection(&mut self) -> Option<String> {
+            self.selection.take()
+        }
+    }
+
+    fn text(text: &str) -> Option<ClipboardContent> {
+        Some(ClipboardContent::Text(text.to_string()))
+    }
+
+    fn poll_all(events: &mut ClipboardEvents<ScriptedMonitor>) -> Vec<ClipboardEvent> {
+        let mut all = Vec::new();
+        while !events.monitor.polls.is_empty() {
+            all.extend(events.poll());
+        }
+        all
+    }
+
+    #[test]
+    fn test_copied_events() {
+        let monitor = ScriptedMonitor::new(vec![
+            (text("already copied"), ""),
+            (text("already copied"), ""),
+            (text("hello"), ""),
+            (text("hello"), ""),
+            (None, ""),
+            (Some(ClipboardContent::Empty), ""),
+            (text("hello"), ""),
+            (Some(ClipboardContent::Image(vec![1, 2, 3])), ""),
+        ]);
+        let mut events = ClipboardEvents::new(monitor, MonitorOptions::default());
+        assert_eq!(
+            poll_all(&mut events),
+            [
+                ClipboardEvent::Copied(ClipboardContent::Text("hello".to_string())),
+                ClipboardEvent::Copied(ClipboardContent::Image(vec![1, 2, 3])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_selected_events() {
+        let monitor = ScriptedMonitor::new(vec![
+            (Some(ClipboardContent::Empty), "already selected"),
+            (Some(ClipboardContent::Empty), "h"),
+            (Some(ClipboardContent::Empty), "hello"),
+            (Some(ClipboardContent::Empty), "hello"),
+            (Some(ClipboardContent::Empty), "hello"),
+            (Some(ClipboardContent::Empty), "  "),
+            (Some(ClipboardContent::Empty), "  "),
+            (Some(ClipboardContent::Empty), "copied"),
+            (text("copied"), "copied"),
+        ]);
+        let options = MonitorOptions::default();
+        options
+            .capture_primary_selection
+            .store(true, Ordering::SeqCst);
+        let mut events = ClipboardEvents::new(monitor, options);
+        assert_eq!(
+            poll_all(&mut events),
+            [
+                ClipboardEvent::Selected("hello".to_string()),
+                ClipboardEvent::Copied(ClipboardContent::Text("copied".to_string())),
+            ]
+        );
+    }
+}
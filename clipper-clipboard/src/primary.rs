@@ -0,0 +1,28 @@
+//! The primary selection on Linux: text selected with the mouse and pasted
+//! with the middle button, kept apart from the clipboard by X11 and Wayland
+
+use arboard::{Clipboard, GetExtLinux, LinuxClipboardKind, SetExtLinux};
+
+use crate::Result;
+
+/// Tag of clips saved from the primary selection
+pub const PRIMARY_SELECTION_TAG: &str = "$primary";
+
+/// Text of the primary selection; empty when nothing is selected
+pub fn get_primary_selection_text(clipboard: &mut Clipboard) -> String {
+    clipboard
+        .get()
+        .clipboard(LinuxClipboardKind::Primary)
+        .text()
+        .unwrap_or_default()
+}
+
+/// Put text on the primary selection
+pub fn set_primary_selection_text(content: &str) -> Result<()> {
+    let mut clipboard = Clipboard::new()?;
+    clipboard
+        .set()
+        .clipboard(LinuxClipboardKind::Primary)
+        .text(content)?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Polling the clipboard with a handle that outlives clipboard errors
+
+use std::time::Duration;
+
+use arboard::Clipboard;
+
+use crate::{ClipboardContent, read_clipboard_content};
+
+/// How often the clipboard is polled
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Added to the wait before the next poll for each failed poll in a row
+const ERROR_BACKOFF: Duration = Duration::from_millis(1000);
+
+/// Failed polls in a row after which failures are reported as persistent
+const MAX_CONSECUTIVE_ERRORS: u32 = 10;
+
+/// Try to create a new clipboard handle
+fn create_clipboard() -> Option<Clipboard> {
+    match Clipboard::new() {
+        Ok(cb) => Some(cb),
+        Err(e) => {
+            eprintln!("[clipboard] Failed to create clipboard handle: {}", e);
+            None
+        }
+    }
+}
+
+/// A clipboard handle for a monitor that polls the clipboard
+///
+/// Clipboard access can fail for a while, e.g. while the display server
+/// restarts. The handle is then dropped and recreated on the next read, and
+/// [`delay`](Self::delay) grows with each failed poll.
+pub struct ClipboardWatcher {
+    clipboard: Option<Clipboard>,
+    consecutive_errors: u32,
+}
+
+impl Default for ClipboardWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardWatcher {
+    pub fn new() -> Self {
+        Self {
+            clipboard: create_clipboard(),
+            consecutive_errors: 0,
+        }
+    }
+
+    /// How long to wait before the next poll
+    pub fn delay(&self) -> Duration {
+        if self.consecutive_errors > 0 {
+            ERROR_BACKOFF * self.consecutive_errors
+        } else {
+            POLL_INTERVAL
+        }
+    }
+
+    /// Read the clipboard (see [`read_clipboard_content`]), recreating the
+    /// handle if the last read failed
+    ///
+    /// Returns `None` when the clipboard can't be read.
+    pub fn read(&mut self, capture_images: bool) -> Option<ClipboardContent> {
+        // Ensure we have a valid clipboard handle
+        if self.clipboard.is_none() {
+            eprintln!("[clipboard] Attempting to recreate clipboard handle...");
+            self.clipboard = create_clipboard();
+            if self.clipboard.is_none() {
+                self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+                if self.consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                    eprintln!(
+                        "[clipboard] Failed to recreate clipboard handle after {} attempts, backing off",
+                        self.consecutive_errors
+                    );
+                }
+                return None;
+            }
+            eprintln!("[clipboard] Successfully recreated clipboard handle after error");
+            self.consecutive_errors = 0;
+        }
+
+        let clipboard = self.clipboard.as_mut()?;
+        match read_clipboard_content(clipboard, capture_images) {
+            Ok(content) => {
+                self.consecutive_errors = 0;
+                Some(content)
+            }
+            Err(err) => {
+                self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+                eprintln!(
+                    "[clipboard] Access error (attempt {}): {}",
+                    self.consecutive_errors, err
+                );
+
+                // Invalidate the clipboard handle so it gets recreated
+                self.clipboard = None;
+
+                if self.consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                    eprintln!(
+                        "[clipboard] Access failing repeatedly ({} times), will keep retrying with backoff",
+                        self.consecutive_errors
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    /// The clipboard handle, e.g. to read another selection; `None` after a
+    /// failed read until the next read recreates it
+    pub fn clipboard(&mut self) -> Option<&mut Clipboard> {
+        self.clipboard.as_mut()
+    }
+}
@@ -73,6 +73,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ]),
             Some("Updated with new information".to_string()),
             None,
+            None,
+            None,
         )
         .await?;
     println!("Updated entry tags: {:?}", updated.tags);
@@ -0,0 +1,190 @@
+//! Opaque cursors for keyset pagination
+//!
+//! Offset pages shift when clips are added or deleted while a client is
+//! paging, so clips can be skipped or returned twice. A cursor records the
+//! sort key and ID of the last clip of a page instead, and the next page
+//! starts strictly after that clip. The clip ID breaks ties between equal
+//! sort keys.
+//!
+//! Only the date sorts (`created_at` and `updated_at`) support cursors.
+
+use crate::error::{IndexerError, Result};
+use crate::models::{ClipboardEntry, SortDirection, SortField};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// A WHERE condition, along with the parameters it references
+pub(crate) type Condition = (String, Vec<(String, String)>);
+
+/// Position after a clip in a date-sorted listing
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Cursor {
+    sort_by: SortField,
+    at: DateTime<Utc>,
+    id: String,
+}
+
+impl Cursor {
+    /// Cursor pointing after `entry`, or None if `sort_by` doesn't support
+    /// cursors
+    pub(crate) fn after(entry: &ClipboardEntry, sort_by: SortField) -> Option<Self> {
+        let at = match sort_by {
+            SortField::CreatedAt => entry.created_at,
+            SortField::UpdatedAt => entry.updated_at,
+            SortField::ContentLength | SortField::OriginalFilename => return None,
+        };
+
+        Some(Self {
+            sort_by,
+            at,
+            id: entry.id.clone(),
+        })
+    }
+
+    /// Decode a cursor returned by [`Cursor::encode`]
+    ///
+    /// Fails with `InvalidInput` if the cursor is malformed or was created
+    /// for a different sort field.
+    pub(crate) fn decode(cursor: &str, sort_by: SortField) -> Result<Self> {
+        if field_name(sort_by).is_none() {
+            return Err(IndexerError::InvalidInput(
+                "Cursors are only supported when sorting by created_at or updated_at".to_string(),
+            ));
+        }
+
+        let invalid = || IndexerError::InvalidInput(format!("Invalid cursor: {}", cursor));
+
+        let text = decode_hex(cursor)
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid)?;
+        let mut parts = text.splitn(3, '|');
+        let (Some(field), Some(at), Some(id)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+
+        if field_name(sort_by) != Some(field) {
+            return Err(IndexerError::InvalidInput(format!(
+                "Cursor was created for sorting by {}",
+                field
+            )));
+        }
+
+        let at = DateTime::parse_from_rfc3339(at)
+            .map_err(|_| invalid())?
+            .with_timezone(&Utc);
+        if id.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            sort_by,
+            at,
+            id: id.to_string(),
+        })
+    }
+
+    /// Opaque string form of the cursor
+    pub(crate) fn encode(&self) -> String {
+        let text = format!(
+            "{}|{}|{}",
+            field_name(self.sort_by).unwrap_or_default(),
+            self.at.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            self.id
+        );
+        text.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// WHERE condition matching the clips of `table` that come after the
+    /// cursor, along with the parameters it references
+    pub(crate) fn condition(&self, table: &str, direction: SortDirection) -> Condition {
+        let field = field_name(self.sort_by).unwrap_or_default();
+        let op = match direction {
+            SortDirection::Asc => ">",
+            SortDirection::Desc => "<",
+        };
+
+        let condition = format!(
+            "({field} {op} <datetime>$cursor_at OR ({field} = <datetime>$cursor_at AND id {op} type::thing($cursor_table, $cursor_id)))"
+        );
+        let bindings = vec![
+            (
+                "cursor_at".to_string(),
+                self.at.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            ),
+            ("cursor_table".to_string(), table.to_string()),
+            ("cursor_id".to_string(), self.id.clone()),
+        ];
+
+        (condition, bindings)
+    }
+
+    /// Whether a clip with the given timestamps and ID comes after the cursor
+    pub(crate) fn precedes(
+        &self,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+        id: &str,
+        direction: SortDirection,
+    ) -> bool {
+        let at = match self.sort_by {
+            SortField::UpdatedAt => updated_at,
+            _ => created_at,
+        };
+        let ordering = (at, id).cmp(&(self.at, self.id.as_str()));
+        match direction {
+            SortDirection::Asc => ordering.is_gt(),
+            SortDirection::Desc => ordering.is_lt(),
+        }
+    }
+}
+
+fn field_name(sort_by: SortField) -> Option<&'static str> {
+    match sort_by {
+        SortField::CreatedAt => Some("created_at"),
+        SortField::UpdatedAt => Some("updated_at"),
+        SortField::ContentLength | SortField::OriginalFilename => None,
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let mut entry = ClipboardEntry::new("content".to_string(), vec![]);
+        entry.updated_at = entry.created_at + chrono::Duration::nanoseconds(1);
+
+        let cursor = Cursor::after(&entry, SortField::UpdatedAt).unwrap();
+        let decoded = Cursor::decode(&cursor.encode(), SortField::UpdatedAt).unwrap();
+        assert_eq!(decoded, cursor);
+
+        assert!(Cursor::decode(&cursor.encode(), SortField::CreatedAt).is_err());
+        assert!(Cursor::decode("not a cursor", SortField::UpdatedAt).is_err());
+        assert!(Cursor::after(&entry, SortField::ContentLength).is_none());
+    }
+
+    #[test]
+    fn test_cursor_precedes() {
+        let entry = ClipboardEntry::new("content".to_string(), vec![]);
+        let cursor = Cursor::after(&entry, SortField::CreatedAt).unwrap();
+        let now = entry.created_at;
+        let earlier = now - chrono::Duration::seconds(1);
+
+        assert!(cursor.precedes(earlier, now, &entry.id, SortDirection::Desc));
+        assert!(!cursor.precedes(earlier, now, &entry.id, SortDirection::Asc));
+        // Equal sort keys are ordered by ID
+        assert!(cursor.precedes(now, now, "~", SortDirection::Asc));
+        assert!(!cursor.precedes(now, now, &entry.id, SortDirection::Desc));
+    }
+}
@@ -4,7 +4,7 @@
 //! to a tar.gz archive, and to import from such an archive with deduplication.
 
 use crate::error::{IndexerError, Result};
-use crate::models::ClipboardEntry;
+use crate::models::{ClipboardEntry, ContentFormat, Visibility};
 use chrono::{DateTime, Utc};
 use flate2::Compression;
 use flate2::read::GzDecoder;
@@ -22,6 +22,9 @@ pub struct ExportedClip {
     pub id: String,
     pub content: String,
     pub created_at: DateTime<Utc>,
+    /// When the clip was last updated; missing in archives from older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_notes: Option<String>,
@@ -31,6 +34,38 @@ pub struct ExportedClip {
     /// Optional language identifier for the clip content
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    /// Optional color label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Optional icon
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Optional content format hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<ContentFormat>,
+    /// The device the clip was created on; missing in archives from older
+    /// versions, where it is taken from the `$host:` tag on import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_device: Option<String>,
+    /// Whether `content` is the beginning of the text in the attachment
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Who can see the clip; clips from older archives are private
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Natural language detected in the content; detected again on import
+    /// when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// Summary from the server's enrichment, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Tags suggested by the server's enrichment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_tags: Vec<String>,
+    /// Canonical URL found by the server, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
     /// The path within the archive where the file attachment is stored (if any)
     /// Format: "files/{id}_{original_filename}" or "files/{id}" if no original filename
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,10 +87,21 @@ impl From<ClipboardEntry> for ExportedClip {
             id: entry.id,
             content: entry.content,
             created_at: entry.created_at,
+            updated_at: Some(entry.updated_at),
             tags: entry.tags,
             additional_notes: entry.additional_notes,
             original_filename: entry.original_filename,
             language: entry.language,
+            color: entry.color,
+            icon: entry.icon,
+            content_format: entry.content_format,
+            source_device: entry.source_device,
+            truncated: entry.truncated,
+            visibility: entry.visibility,
+            detected_language: entry.detected_language,
+            summary: entry.summary,
+            suggested_tags: entry.suggested_tags,
+            canonical_url: entry.canonical_url,
             attachment_path,
         }
     }
@@ -324,10 +370,21 @@ mod tests {
             id: "test123".to_string(),
             content: "Hello, World!".to_string(),
             created_at: Utc::now(),
+            updated_at: None,
             tags: vec!["tag1".to_string(), "tag2".to_string()],
             additional_notes: Some("Some notes".to_string()),
             original_filename: None,
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: Visibility::Private,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             attachment_path: None,
         };
 
@@ -357,10 +414,21 @@ mod tests {
             id: "test456".to_string(),
             content: "File content".to_string(),
             created_at: Utc::now(),
+            updated_at: None,
             tags: vec![],
             additional_notes: None,
             original_filename: Some("test.txt".to_string()),
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: Visibility::Private,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             attachment_path: Some("files/test456_test.txt".to_string()),
         };
 
@@ -394,10 +462,21 @@ mod tests {
             created_at: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
+            updated_at: None,
             tags: vec!["tag".to_string()],
             additional_notes: None,
             original_filename: None,
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: Visibility::Private,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             attachment_path: None,
         };
 
@@ -407,10 +486,21 @@ mod tests {
             created_at: DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
+            updated_at: None,
             tags: vec!["tag".to_string()],
             additional_notes: None,
             original_filename: None,
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: Visibility::Private,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             attachment_path: None,
         };
 
@@ -433,10 +523,21 @@ mod tests {
             id: "12345678-1234-1234-1234-123456789012".to_string(),
             content: "File with long name".to_string(),
             created_at: Utc::now(),
+            updated_at: None,
             tags: vec![],
             additional_notes: None,
             original_filename: Some(long_filename.clone()),
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device: None,
+            truncated: false,
+            visibility: Visibility::Private,
+            detected_language: None,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             attachment_path: Some(format!(
                 "files/12345678-1234-1234-1234-123456789012_{}",
                 long_filename
@@ -0,0 +1,359 @@
+//! Text extraction from file attachments
+//!
+//! Attachments that aren't text themselves, like PDFs, are stored with their
+//! filename as content, so search can't find what's written in them. The
+//! text an [`ExtractorPipeline`] pulls out of them is added to the clip's
+//! search content instead. Extractors are tried in order and the first that
+//! handles a file provides its text; more can be added with
+//! [`ExtractorPipeline::with_extractor`].
+
+use quick_xml::events::Event;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Most text kept from one attachment, in bytes; the rest isn't searchable
+pub const MAX_EXTRACTED_TEXT_BYTES: usize = 1024 * 1024;
+
+/// Largest file inside a zip archive that is read, so a small archive
+/// can't expand into gigabytes
+const MAX_ZIP_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Pulls searchable text out of an attachment
+pub trait TextExtractor: Send + Sync {
+    /// The text of the file, or `None` if this extractor doesn't handle it
+    fn extract(&self, filename: &str, content: &[u8]) -> Option<String>;
+}
+
+/// Text extractors tried in order on each attachment
+pub struct ExtractorPipeline {
+    extractors: Vec<Box<dyn TextExtractor>>,
+}
+
+impl ExtractorPipeline {
+    /// A pipeline without extractors, which extracts nothing
+    pub fn empty() -> Self {
+        Self {
+            extractors: Vec::new(),
+        }
+    }
+
+    /// Add an extractor, tried after the ones already added
+    pub fn with_extractor(mut self, extractor: impl TextExtractor + 'static) -> Self {
+        self.extractors.push(Box::new(extractor));
+        self
+    }
+
+    /// The text of the first extractor that handles the file, trimmed and cut
+    /// to [`MAX_EXTRACTED_TEXT_BYTES`]; `None` if there is no text
+    pub fn extract(&self, filename: &str, content: &[u8]) -> Option<String> {
+        let text = self
+            .extractors
+            .iter()
+            .find_map(|extractor| extractor.extract(filename, content))?;
+        let text = truncate(text.trim(), MAX_EXTRACTED_TEXT_BYTES);
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// The default pipeline: PDF, DOCX, text files in zip archives and plain
+/// text, in that order
+impl Default for ExtractorPipeline {
+    fn default() -> Self {
+        Self::empty()
+            .with_extractor(PdfExtractor)
+            .with_extractor(DocxExtractor)
+            .with_extractor(ZipExtractor)
+            .with_extractor(PlainTextExtractor)
+    }
+}
+
+/// UTF-8 text files
+pub struct PlainTextExtractor;
+
+impl TextExtractor for PlainTextExtractor {
+    fn extract(&self, _filename: &str, content: &[u8]) -> Option<String> {
+        plain_text(content).map(str::to_string)
+    }
+}
+
+/// PDF documents, by their `%PDF-` header
+pub struct PdfExtractor;
+
+impl TextExtractor for PdfExtractor {
+    fn extract(&self, _filename: &str, content: &[u8]) -> Option<String> {
+        if !content.starts_with(b"%PDF-") {
+            return None;
+        }
+        // The PDF parser panics on some malformed files; a broken attachment
+        // shouldn't take the request down with it
+        std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(content))
+            .ok()?
+            .ok()
+    }
+}
+
+/// Word documents (`.docx`): the text of the paragraphs of the main document
+pub struct DocxExtractor;
+
+impl TextExtractor for DocxExtractor {
+    fn extract(&self, filename: &str, content: &[u8]) -> Option<String> {
+        if !has_extension(filename, "docx") {
+            return None;
+        }
+        let mut archive = ZipArchive::new(Cursor::new(content)).ok()?;
+        let document = archive.by_name("word/document.xml").ok()?;
+        let mut xml = String::new();
+        document
+            .take(MAX_ZIP_ENTRY_BYTES)
+            .read_to_string(&mut xml)
+            .ok()?;
+        docx_text(&xml)
+    }
+}
+
+/// Zip archives: the text files inside, each after its path
+pub struct ZipExtractor;
+
+impl TextExtractor for ZipExtractor {
+    fn extract(&self, filename: &str, content: &[u8]) -> Option<String> {
+        if !has_extension(filename, "zip") {
+            return None;
+        }
+        let mut archive = ZipArchive::new(Cursor::new(content)).ok()?;
+        let mut text = String::new();
+        for index in 0..archive.len() {
+            if text.len() >= MAX_EXTRACTED_TEXT_BYTES {
+                break;
+            }
+            let Ok(file) = archive.by_index(index) else {
+                continue;
+            };
+            if !file.is_file() || file.size() > MAX_ZIP_ENTRY_BYTES {
+                continue;
+            }
+            let name = file.name().to_string();
+            let mut bytes = Vec::new();
+            if file
+                .take(MAX_ZIP_ENTRY_BYTES)
+                .read_to_end(&mut bytes)
+                .is_err()
+            {
+                continue;
+            }
+            if let Some(file_text) = plain_text(&bytes) {
+                text.push_str(&name);
+                text.push('\n');
+                text.push_str(file_text);
+                text.push('\n');
+            }
+        }
+        Some(text)
+    }
+}
+
+/// `content` as text, if it's UTF-8 without NUL bytes, which text files
+/// don't have
+fn plain_text(content: &[u8]) -> Option<&str> {
+    if content.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(content).ok()
+}
+
+fn has_extension(filename: &str, extension: &str) -> bool {
+    std::path::Path::new(filename)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+/// The text runs (`<w:t>`) of a WordprocessingML document, a line per
+/// paragraph
+fn docx_text(xml: &str) -> Option<String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_run_text = false;
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) if e.name().as_ref() == b"w:t" => in_run_text = true,
+            Event::End(e) => match e.name().as_ref() {
+                b"w:t" => in_run_text = false,
+                b"w:p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"w:tab" => text.push('\t'),
+                b"w:br" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(t) if in_run_text => text.push_str(&t.decode().ok()?),
+            Event::GeneralRef(r) if in_run_text => {
+                if let Ok(Some(c)) = r.resolve_char_ref() {
+                    text.push(c);
+                } else if let Some(entity) =
+                    quick_xml::escape::resolve_predefined_entity(&r.decode().ok()?)
+                {
+                    text.push_str(entity);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Some(text)
+}
+
+/// `text` cut to at most `max_bytes`, at a character boundary
+pub(crate) fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_plain_text() {
+        let pipeline = ExtractorPipeline::default();
+        assert_eq!(
+            pipeline.extract("notes.txt", b"  Meeting notes\n"),
+            Some("Meeting notes".to_string())
+        );
+        assert_eq!(
+            pipeline.extract("image.png", b"\x89PNG\r\n\x1a\n\0\0"),
+            None
+        );
+        assert_eq!(pipeline.extract("empty.txt", b"   "), None);
+    }
+
+    #[test]
+    fn test_docx() {
+        let document = br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:body>
+<w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:t xml:space="preserve"> report &amp; plan</w:t></w:r></w:p>
+<w:p><w:r><w:t>Second</w:t><w:tab/><w:t>paragraph</w:t></w:r></w:p>
+</w:body>
+</w:document>"#;
+        let docx = zip_of(&[
+            ("[Content_Types].xml", b"<Types/>"),
+            ("word/document.xml", document),
+        ]);
+        assert_eq!(
+            DocxExtractor.extract("Report.DOCX", &docx),
+            Some("Quarterly report & plan\nSecond\tparagraph\n".to_string())
+        );
+        assert_eq!(DocxExtractor.extract("report.zip", &docx), None);
+    }
+
+    #[test]
+    fn test_zip() {
+        let archive = zip_of(&[
+            ("readme.md", b"# Project"),
+            ("logo.png", b"\x89PNG\r\n\x1a\n\0\0"),
+            ("src/main.rs", b"fn main() {}"),
+        ]);
+        assert_eq!(
+            ExtractorPipeline::default().extract("project.zip", &archive),
+            Some("readme.md\n# Project\nsrc/main.rs\nfn main() {}".to_string())
+        );
+    }
+
+    /// A one-page PDF showing `text` in Helvetica
+    fn pdf_of(text: &str) -> Vec<u8> {
+        let stream = format!("BT /F1 24 Tf 72 720 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R \
+             /Resources << /Font << /F1 5 0 R >> >> >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).bytes());
+        }
+        pdf.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .bytes(),
+        );
+        pdf
+    }
+
+    #[test]
+    fn test_pdf() {
+        let pdf = pdf_of("Invoice 2026-0042");
+        let text = ExtractorPipeline::default()
+            .extract("invoice.pdf", &pdf)
+            .unwrap();
+        assert!(text.contains("Invoice 2026-0042"), "{:?}", text);
+
+        assert_eq!(PdfExtractor.extract("fake.pdf", b"not a pdf"), None);
+        assert_eq!(
+            PdfExtractor.extract("broken.pdf", b"%PDF-1.4\ngarbage"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_custom_extractor() {
+        struct Upper;
+        impl TextExtractor for Upper {
+            fn extract(&self, filename: &str, content: &[u8]) -> Option<String> {
+                has_extension(filename, "up")
+                    .then(|| String::from_utf8_lossy(content).to_uppercase())
+            }
+        }
+
+        let pipeline = ExtractorPipeline::empty().with_extractor(Upper);
+        assert_eq!(
+            pipeline.extract("a.up", b"shout"),
+            Some("SHOUT".to_string())
+        );
+        assert_eq!(pipeline.extract("a.txt", b"shout"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("héllo", 2), "h");
+        assert_eq!(truncate("héllo", 3), "hé");
+        assert_eq!(truncate("hello", 10), "hello");
+    }
+}
@@ -0,0 +1,193 @@
+//! Typo-tolerant matching for fuzzy search
+//!
+//! The full-text index only finds exact words and substrings, so fuzzy
+//! search checks candidate clips here instead: every search term must match
+//! some word of the clip, either as a substring (like the index does) or
+//! within [`FuzzyOptions::max_distance`] edits. Adjacent swapped characters
+//! count as one edit, so "recieve" finds "receive" at distance 1.
+//!
+//! Candidates come from an n-gram index over the words of each clip: a word
+//! within a few edits of a term still shares some short substring with it,
+//! see [`grams`].
+
+use crate::models::FuzzyOptions;
+
+/// Longest n-gram in the fuzzy words index
+pub(crate) const GRAM_LENGTH: usize = 3;
+
+/// Lowercase words of a search string, used as fuzzy search terms
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    words(text).map(|(_, word)| word.to_lowercase()).collect()
+}
+
+/// Substrings of a lowercase term, one of which is in every word that matches
+/// it, or `None` when the term is too short for any to be certain
+///
+/// Each edit changes at most `n + 1` of the term's n-grams (a swap touches
+/// two characters), so a word within `max_distance` edits keeps one of them
+/// as long as the term has more n-grams than that.
+pub(crate) fn grams(term: &str, options: &FuzzyOptions) -> Option<Vec<String>> {
+    let chars: Vec<char> = term.chars().collect();
+    let edits = if chars.len() < options.min_term_length {
+        0
+    } else {
+        options.max_distance
+    };
+    let n = (1..=GRAM_LENGTH.min(chars.len()))
+        .rev()
+        .find(|n| chars.len() + 1 > n + edits * (n + 1))?;
+
+    let mut grams: Vec<String> = chars.windows(n).map(|gram| gram.iter().collect()).collect();
+    grams.sort();
+    grams.dedup();
+    Some(grams)
+}
+
+/// Total edits between the terms and the closest words of `text`, or `None`
+/// unless every term matches some word. Terms found inside a word count as
+/// zero edits.
+pub(crate) fn distance(text: &str, terms: &[String], options: &FuzzyOptions) -> Option<usize> {
+    let words: Vec<String> = words(text).map(|(_, word)| word.to_lowercase()).collect();
+    terms.iter().try_fold(0, |total, term| {
+        words
+            .iter()
+            .filter_map(|word| word_distance(word, term, options))
+            .min()
+            .map(|distance| total + distance)
+    })
+}
+
+/// Wrap the words of `text` that match any term with `prefix` and `suffix`
+pub(crate) fn highlight(
+    text: &str,
+    terms: &[String],
+    options: &FuzzyOptions,
+    prefix: &str,
+    suffix: &str,
+) -> String {
+    let mut highlighted = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for (start, word) in words(text) {
+        let lowercase = word.to_lowercase();
+        if terms
+            .iter()
+            .any(|term| word_distance(&lowercase, term, options).is_some())
+        {
+            highlighted.push_str(&text[last..start]);
+            highlighted.push_str(prefix);
+            highlighted.push_str(word);
+            highlighted.push_str(suffix);
+            last = start + word.len();
+        }
+    }
+
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
+
+/// Alphanumeric runs of `text` with their byte offsets
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Edits between a lowercase word and a lowercase term, if they match
+fn word_distance(word: &str, term: &str, options: &FuzzyOptions) -> Option<usize> {
+    if word.contains(term) {
+        return Some(0);
+    }
+
+    let term_len = term.chars().count();
+    if term_len < options.min_term_length
+        || word.chars().count().abs_diff(term_len) > options.max_distance
+    {
+        return None;
+    }
+
+    Some(strsim::osa_distance(word, term)).filter(|distance| *distance <= options.max_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_with_typos() {
+        let options = FuzzyOptions::default();
+        let text = "Please Receive the package";
+
+        assert_eq!(distance(text, &terms("recieve"), &options), Some(1));
+        assert_eq!(distance(text, &terms("packge receive"), &options), Some(1));
+        assert_eq!(distance(text, &terms("pack"), &options), Some(0));
+        assert_eq!(distance(text, &terms("recieve parcel"), &options), None);
+        // Short terms must match exactly
+        assert_eq!(distance(text, &terms("thw"), &options), None);
+        // Two edits need a larger distance
+        assert_eq!(distance(text, &terms("reseeve"), &options), None);
+        assert_eq!(
+            distance(text, &terms("reseeve"), &FuzzyOptions::new(2)),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_grams_shared_with_matching_words() {
+        let options = FuzzyOptions::default();
+
+        assert_eq!(grams("the", &options), Some(vec!["the".to_string()]));
+        assert_eq!(
+            grams("recieve", &options),
+            Some(
+                vec!["cie", "eci", "eve", "iev", "rec"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect()
+            )
+        );
+        assert_eq!(
+            grams("rcv", &FuzzyOptions::new(2)),
+            Some(vec!["rcv".to_string()])
+        );
+        // Two edits can change every n-gram of a four letter term
+        assert_eq!(
+            grams(
+                "rcve",
+                &FuzzyOptions {
+                    max_distance: 2,
+                    min_term_length: 4
+                }
+            ),
+            None
+        );
+
+        for (word, term) in [
+            ("receive", "recieve"),
+            ("receive", "receve"),
+            ("package", "pakcage"),
+        ] {
+            let grams = grams(term, &options).unwrap();
+            assert!(
+                grams.iter().any(|gram| word.contains(gram.as_str())),
+                "{term}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_highlight_matching_words() {
+        let options = FuzzyOptions::default();
+
+        assert_eq!(
+            highlight(
+                "Please Receive the package, receiver!",
+                &terms("recieve"),
+                &options,
+                "<mark>",
+                "</mark>"
+            ),
+            "Please <mark>Receive</mark> the package, receiver!"
+        );
+    }
+}
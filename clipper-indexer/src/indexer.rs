@@ -1,49 +1,167 @@
+use crate::cursor::{Condition, Cursor};
 use crate::error::{IndexerError, Result};
 use crate::export::{
     ExportBuilder, ExportedClip, ImportParser, ImportResult, calculate_content_hash,
 };
+use crate::extract::{ExtractorPipeline, truncate};
+use crate::language::detect_language;
 use crate::models::{
-    ClipboardEntry, HighlightOptions, PagedResult, PagingParams, SearchFilters, SearchResultItem,
-    ShortUrl, Tag,
+    ActivityStats, ClipLink, ClipboardEntry, Collection, ContentFormat, DayActivity, Device,
+    FuzzyOptions, HOST_TAG_PREFIX, HighlightOptions, LibraryStats, PagedResult, PagingParams,
+    PairedDevice, PushPlatform, PushToken, SearchFilters, SearchResultItem, ShortUrl,
+    SortDirection, SortField, SortOrder, TOKEN_SEPARATOR, Tag, TagStats, Template, Visibility,
+    WebPushSubscription, source_device_from_tags, tokenize,
 };
+use crate::query::SearchQuery;
 use crate::storage::FileStorage;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
+use std::sync::Arc;
 use surrealdb::Surreal;
 use surrealdb::engine::local::{Db, RocksDb};
 
 const TABLE_NAME: &str = "clipboard";
 const SHORT_URL_TABLE: &str = "short_url";
 const TAGS_TABLE: &str = "tags";
+const LINKS_TABLE: &str = "clip_link";
+const COLLECTIONS_TABLE: &str = "collection";
+const COLLECTION_ITEMS_TABLE: &str = "collection_item";
+const TEMPLATES_TABLE: &str = "template";
+const PAIRED_DEVICES_TABLE: &str = "paired_device";
+const PUSH_TOKENS_TABLE: &str = "push_token";
+const WEB_PUSH_SUBSCRIPTIONS_TABLE: &str = "web_push_subscription";
 const CONFIG_TABLE: &str = "config";
 const INDEX_VERSION_KEY: &str = "index_schema";
 const SEARCH_ANALYZER_NAME: &str = "clipper_analyzer";
 const TAGS_ANALYZER_NAME: &str = "clipper_tags_analyzer";
+const FUZZY_ANALYZER_NAME: &str = "clipper_fuzzy_analyzer";
 const SEARCH_INDEX_NAME: &str = "idx_search_content";
 const TAGS_SEARCH_INDEX_NAME: &str = "idx_tag_text";
+const FUZZY_INDEX_NAME: &str = "idx_fuzzy_words";
 const NAMESPACE: &str = "clipper";
 const DATABASE: &str = "library";
-const CURRENT_INDEX_VERSION: i64 = 2;
+const CURRENT_INDEX_VERSION: i64 = 6;
 
 /// Characters used for generating short codes (alphanumeric, excluding ambiguous characters)
 const SHORT_CODE_CHARS: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghjkmnpqrstuvwxyz";
 const SHORT_CODE_LENGTH: usize = 8;
 
+/// Longest accepted clip link relation
+const MAX_RELATION_LENGTH: usize = 64;
+
+/// Longest accepted collection name
+const MAX_COLLECTION_NAME_LENGTH: usize = 100;
+
+/// Longest accepted template name, in characters
+const MAX_TEMPLATE_NAME_LENGTH: usize = 100;
+
+/// Longest accepted clip color name
+const MAX_COLOR_LENGTH: usize = 32;
+
+/// Longest accepted clip icon, in characters
+const MAX_ICON_LENGTH: usize = 32;
+
+/// Longest accepted paired device name, in characters
+const MAX_DEVICE_NAME_LENGTH: usize = 64;
+
+/// Random bytes in a paired device token (hex encoded, so twice as many characters)
+const DEVICE_TOKEN_BYTES: usize = 32;
+
+/// Minimum time between updates of a paired device's last use
+const DEVICE_LAST_USED_INTERVAL_SECS: i64 = 60;
+
+/// Longest accepted push token, in characters. APNs tokens are 64 hex
+/// characters and FCM tokens a few hundred.
+const MAX_PUSH_TOKEN_LENGTH: usize = 1024;
+
+/// Longest accepted Web Push endpoint URL
+const MAX_WEB_PUSH_ENDPOINT_LENGTH: usize = 2048;
+
+/// Longest accepted Web Push subscription key, in characters
+const MAX_WEB_PUSH_KEY_LENGTH: usize = 256;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DbClipboardEntry {
     id: surrealdb::sql::Thing,
     content: String,
     created_at: surrealdb::sql::Datetime,
+    updated_at: surrealdb::sql::Datetime,
     tags: Vec<String>,
     additional_notes: Option<String>,
     file_attachment: Option<String>,
     original_filename: Option<String>,
     language: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+    content_format: Option<ContentFormat>,
+    source_device: Option<String>,
+    truncated: Option<bool>,
+    visibility: Option<Visibility>,
+    detected_language: Option<String>,
+    summary: Option<String>,
+    canonical_url: Option<String>,
+    suggested_tags: Option<Vec<String>>,
     search_content: String,
 }
 
+impl From<DbClipboardEntry> for ClipboardEntry {
+    fn from(db_entry: DbClipboardEntry) -> Self {
+        Self {
+            id: db_entry.id.id.to_string(),
+            content: db_entry.content,
+            created_at: *db_entry.created_at,
+            updated_at: *db_entry.updated_at,
+            tags: db_entry.tags,
+            additional_notes: db_entry.additional_notes,
+            file_attachment: db_entry.file_attachment,
+            original_filename: db_entry.original_filename,
+            language: db_entry.language,
+            color: db_entry.color,
+            icon: db_entry.icon,
+            content_format: db_entry.content_format,
+            source_device: db_entry.source_device,
+            truncated: db_entry.truncated.unwrap_or_default(),
+            visibility: db_entry.visibility.unwrap_or_default(),
+            detected_language: db_entry.detected_language,
+            summary: db_entry.summary,
+            canonical_url: db_entry.canonical_url,
+            suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
+            search_content: db_entry.search_content,
+        }
+    }
+}
+
+impl From<&ClipboardEntry> for DbClipboardEntry {
+    fn from(entry: &ClipboardEntry) -> Self {
+        Self {
+            id: surrealdb::sql::Thing::from((TABLE_NAME.to_string(), entry.id.clone())),
+            content: entry.content.clone(),
+            created_at: surrealdb::sql::Datetime::from(entry.created_at),
+            updated_at: surrealdb::sql::Datetime::from(entry.updated_at),
+            tags: entry.tags.clone(),
+            additional_notes: entry.additional_notes.clone(),
+            file_attachment: entry.file_attachment.clone(),
+            original_filename: entry.original_filename.clone(),
+            language: entry.language.clone(),
+            color: entry.color.clone(),
+            icon: entry.icon.clone(),
+            content_format: entry.content_format,
+            source_device: entry.source_device.clone(),
+            truncated: entry.truncated.then_some(true),
+            visibility: Some(entry.visibility),
+            detected_language: entry.detected_language.clone(),
+            summary: entry.summary.clone(),
+            canonical_url: entry.canonical_url.clone(),
+            suggested_tags: (!entry.suggested_tags.is_empty())
+                .then(|| entry.suggested_tags.clone()),
+            search_content: entry.search_content.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct IndexSchemaVersion {
     version: i64,
@@ -58,6 +176,148 @@ struct DbShortUrl {
     expires_at: Option<surrealdb::sql::Datetime>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbClipLink {
+    id: surrealdb::sql::Thing,
+    source_id: String,
+    target_id: String,
+    relation: String,
+    created_at: surrealdb::sql::Datetime,
+}
+
+impl From<DbClipLink> for ClipLink {
+    fn from(db_link: DbClipLink) -> Self {
+        Self {
+            id: db_link.id.id.to_string(),
+            source_id: db_link.source_id,
+            target_id: db_link.target_id,
+            relation: db_link.relation,
+            created_at: *db_link.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbCollection {
+    id: surrealdb::sql::Thing,
+    name: String,
+    description: Option<String>,
+    created_at: surrealdb::sql::Datetime,
+    updated_at: surrealdb::sql::Datetime,
+}
+
+impl From<DbCollection> for Collection {
+    fn from(db_collection: DbCollection) -> Self {
+        Self {
+            id: db_collection.id.id.to_string(),
+            name: db_collection.name,
+            description: db_collection.description,
+            created_at: *db_collection.created_at,
+            updated_at: *db_collection.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbTemplate {
+    id: surrealdb::sql::Thing,
+    name: String,
+    description: Option<String>,
+    content: String,
+    tags: Vec<String>,
+    created_at: surrealdb::sql::Datetime,
+    updated_at: surrealdb::sql::Datetime,
+}
+
+impl From<DbTemplate> for Template {
+    fn from(db_template: DbTemplate) -> Self {
+        Self {
+            id: db_template.id.id.to_string(),
+            name: db_template.name,
+            description: db_template.description,
+            content: db_template.content,
+            tags: db_template.tags,
+            created_at: *db_template.created_at,
+            updated_at: *db_template.updated_at,
+        }
+    }
+}
+
+/// Membership of a clip in a collection. Items are ordered by `position`,
+/// which may have gaps after clips are removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbCollectionItem {
+    id: surrealdb::sql::Thing,
+    collection_id: String,
+    clip_id: String,
+    position: i64,
+    added_at: surrealdb::sql::Datetime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbPairedDevice {
+    id: surrealdb::sql::Thing,
+    name: String,
+    token_hash: String,
+    created_at: surrealdb::sql::Datetime,
+    last_used_at: Option<surrealdb::sql::Datetime>,
+}
+
+impl From<DbPairedDevice> for PairedDevice {
+    fn from(db_device: DbPairedDevice) -> Self {
+        Self {
+            id: db_device.id.id.to_string(),
+            name: db_device.name,
+            created_at: *db_device.created_at,
+            last_used_at: db_device.last_used_at.map(|dt| *dt),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbPushToken {
+    id: surrealdb::sql::Thing,
+    token: String,
+    platform: PushPlatform,
+    device_name: Option<String>,
+    created_at: surrealdb::sql::Datetime,
+    updated_at: surrealdb::sql::Datetime,
+}
+
+impl From<DbPushToken> for PushToken {
+    fn from(db_token: DbPushToken) -> Self {
+        Self {
+            id: db_token.id.id.to_string(),
+            token: db_token.token,
+            platform: db_token.platform,
+            device_name: db_token.device_name,
+            created_at: *db_token.created_at,
+            updated_at: *db_token.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DbWebPushSubscription {
+    id: surrealdb::sql::Thing,
+    endpoint: String,
+    p256dh: String,
+    auth: String,
+    created_at: surrealdb::sql::Datetime,
+}
+
+impl From<DbWebPushSubscription> for WebPushSubscription {
+    fn from(db_subscription: DbWebPushSubscription) -> Self {
+        Self {
+            id: db_subscription.id.id.to_string(),
+            endpoint: db_subscription.endpoint,
+            p256dh: db_subscription.p256dh,
+            auth: db_subscription.auth,
+            created_at: *db_subscription.created_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DbTag {
     id: surrealdb::sql::Thing,
@@ -65,6 +325,49 @@ struct DbTag {
     created_at: surrealdb::sql::Datetime,
 }
 
+/// Validate a clip color label, returning None for an empty color
+///
+/// Accepts hex colors (`#rgb` or `#rrggbb`, stored lowercase) and color
+/// names made of ASCII letters (e.g. "red"), so the UIs can use the value
+/// directly in CSS.
+fn normalize_color(color: &str) -> Result<Option<String>> {
+    let color = color.trim();
+    if color.is_empty() {
+        return Ok(None);
+    }
+
+    let valid = match color.strip_prefix('#') {
+        Some(hex) => {
+            (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => color.len() <= MAX_COLOR_LENGTH && color.chars().all(|c| c.is_ascii_alphabetic()),
+    };
+    if !valid {
+        return Err(IndexerError::InvalidInput(format!(
+            "Invalid color {:?}: expected #rgb, #rrggbb or a color name",
+            color
+        )));
+    }
+
+    Ok(Some(color.to_ascii_lowercase()))
+}
+
+/// Validate a clip icon, returning None for an empty icon
+fn normalize_icon(icon: &str) -> Result<Option<String>> {
+    let icon = icon.trim();
+    if icon.is_empty() {
+        return Ok(None);
+    }
+    if icon.chars().count() > MAX_ICON_LENGTH {
+        return Err(IndexerError::InvalidInput(format!(
+            "Icon must be at most {} characters",
+            MAX_ICON_LENGTH
+        )));
+    }
+
+    Ok(Some(icon.to_string()))
+}
+
 /// Generate a random short code using alphanumeric characters
 fn generate_short_code() -> String {
     let mut rng = rand::rng();
@@ -76,9 +379,196 @@ fn generate_short_code() -> String {
         .collect()
 }
 
+/// Generate a random paired device token
+fn generate_device_token() -> String {
+    let mut bytes = [0u8; DEVICE_TOKEN_BYTES];
+    rand::rng().fill(&mut bytes[..]);
+    hex::encode(bytes)
+}
+
+/// Hash of a paired device token as stored in the database. Tokens are
+/// random, so an unsalted hash is enough to keep them out of backups.
+fn hash_device_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Build a WHERE condition limiting clips to the given visibility levels
+/// (`true` for all clips), along with the parameters it references
+fn visibility_condition(visibility: Option<&[Visibility]>) -> (String, Vec<(String, String)>) {
+    let Some(visibility) = visibility else {
+        return ("true".to_string(), Vec::new());
+    };
+    let params: Vec<String> = (0..visibility.len())
+        .map(|i| format!("$visibility{}", i))
+        .collect();
+    let bindings = visibility
+        .iter()
+        .enumerate()
+        .map(|(i, level)| (format!("visibility{}", i), level.as_str().to_string()))
+        .collect();
+    (format!("visibility IN [{}]", params.join(", ")), bindings)
+}
+
+/// WHERE condition limiting tags to `visible_tags`, bound as
+/// `$visible_tags` (`true` for all tags). Written as `CONTAINS` rather than
+/// `IN` so the planner keeps using the full-text index for `@@` searches.
+fn tags_condition(visible_tags: &Option<Vec<String>>) -> &'static str {
+    if visible_tags.is_some() {
+        "$visible_tags CONTAINS text"
+    } else {
+        "true"
+    }
+}
+
+/// Build WHERE conditions for search filters and query operators, along with
+/// the parameters they reference
+fn filter_conditions(
+    filters: &SearchFilters,
+    query: &SearchQuery,
+) -> (Vec<String>, Vec<(String, String)>) {
+    let mut conditions = Vec::new();
+    let mut bindings = Vec::new();
+
+    if let Some(start_date) = filters.start_date {
+        conditions.push("created_at >= <datetime>$start_date".to_string());
+        bindings.push(("start_date".to_string(), start_date.to_rfc3339()));
+    }
+
+    if let Some(end_date) = filters.end_date {
+        conditions.push("created_at <= <datetime>$end_date".to_string());
+        bindings.push(("end_date".to_string(), end_date.to_rfc3339()));
+    }
+
+    if let Some(updated_since) = filters.updated_since {
+        conditions.push("updated_at >= <datetime>$updated_since".to_string());
+        bindings.push(("updated_since".to_string(), updated_since.to_rfc3339()));
+    }
+
+    if let Some(source_device) = &filters.source_device {
+        conditions.push("source_device = $source_device".to_string());
+        bindings.push(("source_device".to_string(), source_device.clone()));
+    }
+
+    if filters.visibility.is_some() {
+        let (condition, visibility_bindings) = visibility_condition(filters.visibility.as_deref());
+        conditions.push(condition);
+        bindings.extend(visibility_bindings);
+    }
+
+    if let Some(after) = query.after {
+        conditions.push("created_at >= <datetime>$after".to_string());
+        bindings.push(("after".to_string(), after.to_rfc3339()));
+    }
+
+    if let Some(before) = query.before {
+        conditions.push("created_at < <datetime>$before".to_string());
+        bindings.push(("before".to_string(), before.to_rfc3339()));
+    }
+
+    // For tags, we need to check membership - use array contains
+    let tags: Vec<&String> = filters.tags.iter().flatten().chain(&query.tags).collect();
+    if !tags.is_empty() {
+        // Build tag conditions using indexed parameters
+        let tag_conditions: Vec<String> = (0..tags.len())
+            .map(|i| format!("$tag{} IN tags", i))
+            .collect();
+        conditions.push(format!("({})", tag_conditions.join(" AND ")));
+        for (i, tag) in tags.into_iter().enumerate() {
+            bindings.push((format!("tag{}", i), tag.clone()));
+        }
+    }
+
+    for (i, tag) in query.exclude_tags.iter().enumerate() {
+        conditions.push(format!("$exclude_tag{} NOT IN tags", i));
+        bindings.push((format!("exclude_tag{}", i), tag.clone()));
+    }
+
+    match query.has_attachment {
+        Some(true) => conditions.push("type::is::string(file_attachment)".to_string()),
+        Some(false) => conditions.push("!type::is::string(file_attachment)".to_string()),
+        None => {}
+    }
+
+    // Phrases are matched verbatim, case-insensitively, on the indexed text
+    // without the separators between its tokens
+    for (i, phrase) in query.phrases.iter().enumerate() {
+        conditions.push(format!(
+            "string::contains(string::lowercase(string::replace(search_content, $token_separator, '')), $phrase{})",
+            i
+        ));
+        bindings.push((format!("phrase{}", i), phrase.to_lowercase()));
+    }
+    if !query.phrases.is_empty() {
+        bindings.push(("token_separator".to_string(), TOKEN_SEPARATOR.to_string()));
+    }
+
+    (conditions, bindings)
+}
+
+/// ORDER BY clause for the requested sort field and direction, along with
+/// the computed field (if any) that must be added to the selection for it
+fn sort_clause(filters: &SearchFilters) -> (&'static str, String) {
+    let direction = match filters.order {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    };
+
+    match filters.sort_by {
+        // Date sorts are broken by ID so cursors have a total order
+        SortField::CreatedAt => ("", format!("created_at {0}, id {0}", direction)),
+        SortField::UpdatedAt => ("", format!("updated_at {0}, id {0}", direction)),
+        SortField::ContentLength => (
+            ", string::len(content) AS content_length",
+            format!("content_length {}, created_at DESC", direction),
+        ),
+        SortField::OriginalFilename => (
+            "",
+            format!("original_filename {}, created_at DESC", direction),
+        ),
+    }
+}
+
+/// WHERE condition for the clips after `paging.cursor`, if one is set
+fn cursor_condition(filters: &SearchFilters, paging: &PagingParams) -> Result<Option<Condition>> {
+    paging
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            Cursor::decode(cursor, filters.sort_by)
+                .map(|cursor| cursor.condition(TABLE_NAME, filters.order))
+        })
+        .transpose()
+}
+
+/// Cursor for the page after a page ending with `last`, if there is one
+fn next_cursor(
+    filters: &SearchFilters,
+    has_more: bool,
+    last: Option<&ClipboardEntry>,
+) -> Option<String> {
+    if !has_more {
+        return None;
+    }
+    last.and_then(|entry| Cursor::after(entry, filters.sort_by))
+        .map(|cursor| cursor.encode())
+}
+
+/// Drop the extra item fetched past the page, returning whether there was one
+fn split_has_more<T>(items: &mut Vec<T>, paging: &PagingParams) -> bool {
+    let has_more = items.len() > paging.page_size;
+    items.truncate(paging.page_size);
+    has_more
+}
+
+/// Lower bound for the total when counting was skipped
+fn estimated_total(paging: &PagingParams, page_len: usize, has_more: bool) -> usize {
+    paging.offset() + page_len + usize::from(has_more)
+}
+
 pub struct ClipperIndexer {
     db: Surreal<Db>,
     storage: FileStorage,
+    extractors: Arc<ExtractorPipeline>,
 }
 
 impl ClipperIndexer {
@@ -96,7 +586,36 @@ impl ClipperIndexer {
         // Initialize file storage
         let storage = FileStorage::new(storage_path)?;
 
-        Ok(Self { db, storage })
+        Ok(Self {
+            db,
+            storage,
+            extractors: Arc::new(ExtractorPipeline::default()),
+        })
+    }
+
+    /// Use `extractors` to get the searchable text of attachments instead of
+    /// the [default pipeline](ExtractorPipeline::default).
+    pub fn with_extractors(mut self, extractors: ExtractorPipeline) -> Self {
+        self.extractors = Arc::new(extractors);
+        self
+    }
+
+    /// The text of an attachment for search, unless it's the clip's content
+    /// already. Parsing documents can take a while, so it runs on a blocking
+    /// thread.
+    async fn attachment_text(
+        &self,
+        filename: &str,
+        file_content: bytes::Bytes,
+        clip_content: &str,
+    ) -> Option<String> {
+        let extractors = self.extractors.clone();
+        let filename = filename.to_string();
+        let text =
+            tokio::task::spawn_blocking(move || extractors.extract(&filename, &file_content))
+                .await
+                .ok()??;
+        (text != clip_content.trim()).then_some(text)
     }
 
     async fn initialize_schema(db: &Surreal<Db>) -> Result<()> {
@@ -107,12 +626,25 @@ impl ClipperIndexer {
 
             DEFINE FIELD IF NOT EXISTS content ON TABLE {TABLE_NAME} TYPE string;
             DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime;
             DEFINE FIELD IF NOT EXISTS tags ON TABLE {TABLE_NAME} TYPE array<string>;
             DEFINE FIELD IF NOT EXISTS additional_notes ON TABLE {TABLE_NAME} TYPE option<string>;
             DEFINE FIELD IF NOT EXISTS file_attachment ON TABLE {TABLE_NAME} TYPE option<string>;
             DEFINE FIELD IF NOT EXISTS original_filename ON TABLE {TABLE_NAME} TYPE option<string>;
             DEFINE FIELD IF NOT EXISTS language ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS color ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS icon ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS content_format ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS source_device ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS truncated ON TABLE {TABLE_NAME} TYPE option<bool>;
+            DEFINE FIELD IF NOT EXISTS visibility ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS detected_language ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS summary ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS canonical_url ON TABLE {TABLE_NAME} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS suggested_tags ON TABLE {TABLE_NAME} TYPE option<array<string>>;
             DEFINE FIELD IF NOT EXISTS search_content ON TABLE {TABLE_NAME} TYPE string;
+            DEFINE FIELD IF NOT EXISTS fuzzy_words ON TABLE {TABLE_NAME} TYPE option<array<string>>
+                VALUE array::distinct(string::split(string::lowercase(search_content), '{TOKEN_SEPARATOR}'));
 
             DEFINE TABLE IF NOT EXISTS {CONFIG_TABLE} SCHEMAFULL;
             DEFINE FIELD IF NOT EXISTS version ON TABLE {CONFIG_TABLE} TYPE int;
@@ -126,6 +658,38 @@ impl ClipperIndexer {
             DEFINE TABLE IF NOT EXISTS {TAGS_TABLE} SCHEMAFULL;
             DEFINE FIELD IF NOT EXISTS text ON TABLE {TAGS_TABLE} TYPE string;
             DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TAGS_TABLE} TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS {LINKS_TABLE} SCHEMAFULL;
+            DEFINE FIELD IF NOT EXISTS source_id ON TABLE {LINKS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS target_id ON TABLE {LINKS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS relation ON TABLE {LINKS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS created_at ON TABLE {LINKS_TABLE} TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS {COLLECTIONS_TABLE} SCHEMAFULL;
+            DEFINE FIELD IF NOT EXISTS name ON TABLE {COLLECTIONS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS description ON TABLE {COLLECTIONS_TABLE} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS created_at ON TABLE {COLLECTIONS_TABLE} TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {COLLECTIONS_TABLE} TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS {COLLECTION_ITEMS_TABLE} SCHEMAFULL;
+            DEFINE FIELD IF NOT EXISTS collection_id ON TABLE {COLLECTION_ITEMS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS clip_id ON TABLE {COLLECTION_ITEMS_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS position ON TABLE {COLLECTION_ITEMS_TABLE} TYPE int;
+            DEFINE FIELD IF NOT EXISTS added_at ON TABLE {COLLECTION_ITEMS_TABLE} TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS {TEMPLATES_TABLE} SCHEMAFULL;
+            DEFINE FIELD IF NOT EXISTS name ON TABLE {TEMPLATES_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS description ON TABLE {TEMPLATES_TABLE} TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS content ON TABLE {TEMPLATES_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS tags ON TABLE {TEMPLATES_TABLE} TYPE array<string>;
+            DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TEMPLATES_TABLE} TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TEMPLATES_TABLE} TYPE datetime;
+
+            DEFINE TABLE IF NOT EXISTS {PAIRED_DEVICES_TABLE} SCHEMAFULL;
+            DEFINE FIELD IF NOT EXISTS name ON TABLE {PAIRED_DEVICES_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS token_hash ON TABLE {PAIRED_DEVICES_TABLE} TYPE string;
+            DEFINE FIELD IF NOT EXISTS created_at ON TABLE {PAIRED_DEVICES_TABLE} TYPE datetime;
+            DEFINE FIELD IF NOT EXISTS last_used_at ON TABLE {PAIRED_DEVICES_TABLE} TYPE option<datetime>;
             "#
         );
 
@@ -135,12 +699,25 @@ impl ClipperIndexer {
         let index_query = format!(
             r#"
             DEFINE INDEX IF NOT EXISTS idx_created_at ON TABLE {TABLE_NAME} COLUMNS created_at;
+            DEFINE INDEX IF NOT EXISTS idx_updated_at ON TABLE {TABLE_NAME} COLUMNS updated_at;
             DEFINE INDEX IF NOT EXISTS idx_tags ON TABLE {TABLE_NAME} COLUMNS tags;
             DEFINE INDEX IF NOT EXISTS idx_language ON TABLE {TABLE_NAME} COLUMNS language;
+            DEFINE INDEX IF NOT EXISTS idx_source_device ON TABLE {TABLE_NAME} COLUMNS source_device;
+            DEFINE INDEX IF NOT EXISTS idx_visibility ON TABLE {TABLE_NAME} COLUMNS visibility;
             DEFINE INDEX IF NOT EXISTS idx_short_code ON TABLE {SHORT_URL_TABLE} COLUMNS short_code UNIQUE;
             DEFINE INDEX IF NOT EXISTS idx_short_url_clip_id ON TABLE {SHORT_URL_TABLE} COLUMNS clip_id;
             DEFINE INDEX IF NOT EXISTS idx_short_url_expires_at ON TABLE {SHORT_URL_TABLE} COLUMNS expires_at;
             DEFINE INDEX IF NOT EXISTS idx_tag_text_unique ON TABLE {TAGS_TABLE} COLUMNS text UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_link_source_id ON TABLE {LINKS_TABLE} COLUMNS source_id;
+            DEFINE INDEX IF NOT EXISTS idx_link_target_id ON TABLE {LINKS_TABLE} COLUMNS target_id;
+            DEFINE INDEX IF NOT EXISTS idx_link_unique ON TABLE {LINKS_TABLE} COLUMNS source_id, target_id, relation UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_collection_name_unique ON TABLE {COLLECTIONS_TABLE} COLUMNS name UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_collection_item_clip_id ON TABLE {COLLECTION_ITEMS_TABLE} COLUMNS clip_id;
+            DEFINE INDEX IF NOT EXISTS idx_collection_item_unique ON TABLE {COLLECTION_ITEMS_TABLE} COLUMNS collection_id, clip_id UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_template_name_unique ON TABLE {TEMPLATES_TABLE} COLUMNS name UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_paired_device_token_hash ON TABLE {PAIRED_DEVICES_TABLE} COLUMNS token_hash UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_push_token_unique ON TABLE {PUSH_TOKENS_TABLE} COLUMNS token UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_web_push_endpoint_unique ON TABLE {WEB_PUSH_SUBSCRIPTIONS_TABLE} COLUMNS endpoint UNIQUE;
             "#
         );
 
@@ -163,6 +740,26 @@ impl ClipperIndexer {
 
         if version < 2 {
             Self::migrate_to_v2(db).await?;
+            version = 2;
+        }
+
+        if version < 3 {
+            Self::migrate_to_v3(db).await?;
+            version = 3;
+        }
+
+        if version < 4 {
+            Self::migrate_to_v4(db).await?;
+            version = 4;
+        }
+
+        if version < 5 {
+            Self::migrate_to_v5(db).await?;
+            version = 5;
+        }
+
+        if version < 6 {
+            Self::migrate_to_v6(db).await?;
         }
 
         // Always save the version after migrations complete
@@ -277,6 +874,96 @@ impl ClipperIndexer {
         Ok(())
     }
 
+    async fn migrate_to_v3(db: &Surreal<Db>) -> Result<()> {
+        // Clips created before updated_at was tracked count as never updated
+        let migration_query = format!(
+            "UPDATE {} SET updated_at = created_at WHERE updated_at = NONE;",
+            TABLE_NAME
+        );
+        db.query(migration_query).await?;
+
+        Ok(())
+    }
+
+    async fn migrate_to_v4(db: &Surreal<Db>) -> Result<()> {
+        // Existing clips get their source device from the `$host:` tag
+        #[derive(Deserialize)]
+        struct HostTags {
+            id: surrealdb::sql::Thing,
+            tags: Vec<String>,
+        }
+
+        let query = format!(
+            "SELECT id, tags FROM {} WHERE source_device = NONE;",
+            TABLE_NAME
+        );
+        let mut response = db.query(query).await?;
+        let entries: Vec<HostTags> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        for entry in entries {
+            let Some(source_device) = source_device_from_tags(&entry.tags) else {
+                continue;
+            };
+            db.query("UPDATE type::thing($table, $id) SET source_device = $source_device;")
+                .bind(("table", TABLE_NAME))
+                .bind(("id", entry.id.id.to_string()))
+                .bind(("source_device", source_device))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn migrate_to_v5(db: &Surreal<Db>) -> Result<()> {
+        // Existing clips become private, except those shared with a short
+        // URL, which stay reachable through it
+        let migration_query = format!(
+            "UPDATE {} SET visibility = 'private' WHERE visibility = NONE;",
+            TABLE_NAME
+        );
+        db.query(migration_query).await?;
+
+        let query = format!("SELECT VALUE clip_id FROM {};", SHORT_URL_TABLE);
+        let mut response = db.query(query).await?;
+        let clip_ids: Vec<String> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        for clip_id in clip_ids {
+            db.query("UPDATE type::thing($table, $id) SET visibility = 'public';")
+                .bind(("table", TABLE_NAME))
+                .bind(("id", clip_id))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn migrate_to_v6(db: &Surreal<Db>) -> Result<()> {
+        // Existing clips get their fuzzy words, then fuzzy search finds
+        // candidates by the short n-grams of those words
+        let migration_query = format!(
+            r#"
+            UPDATE {table};
+
+            REMOVE ANALYZER IF EXISTS {analyzer};
+            REMOVE INDEX IF EXISTS {index} ON TABLE {table};
+
+            DEFINE ANALYZER {analyzer} TOKENIZERS blank,class FILTERS lowercase,ngram(1, {gram_length});
+            DEFINE INDEX {index} ON TABLE {table} COLUMNS fuzzy_words SEARCH ANALYZER {analyzer};
+            "#,
+            analyzer = FUZZY_ANALYZER_NAME,
+            index = FUZZY_INDEX_NAME,
+            table = TABLE_NAME,
+            gram_length = crate::fuzzy::GRAM_LENGTH
+        );
+        db.query(migration_query).await?.check()?;
+
+        Ok(())
+    }
+
     /// Sync tags to the tags table. This ensures all tags from the given list
     /// exist in the tags table. Tags that already exist are skipped.
     async fn sync_tags(&self, tags: &[String]) -> Result<()> {
@@ -324,6 +1011,8 @@ impl ClipperIndexer {
     /// - Version 0: Initial schema (no FTS)
     /// - Version 1: Full-text search with ngram analyzer
     /// - Version 2: Tags table with edgengram FTS
+    /// - Version 3: `updated_at` on clipboard entries
+    /// - Version 4: `source_device` on clipboard entries
     pub async fn get_index_version(&self) -> Result<i64> {
         Self::get_index_schema_version(&self.db).await
     }
@@ -334,6 +1023,23 @@ impl ClipperIndexer {
         tags: Vec<String>,
         additional_notes: Option<String>,
         language: Option<String>,
+    ) -> Result<ClipboardEntry> {
+        self.add_entry_from_text_with_format(content, tags, additional_notes, language, None)
+            .await
+    }
+
+    /// Add a new text entry with an optional content format hint.
+    ///
+    /// The hint tells clients how the content is written, e.g.
+    /// [`ContentFormat::Markdown`] to render the clip as Markdown when it is
+    /// shared.
+    pub async fn add_entry_from_text_with_format(
+        &self,
+        content: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+        content_format: Option<ContentFormat>,
     ) -> Result<ClipboardEntry> {
         let mut entry = ClipboardEntry::new(content, tags);
 
@@ -345,6 +1051,10 @@ impl ClipperIndexer {
             entry = entry.with_language(lang);
         }
 
+        if let Some(format) = content_format {
+            entry = entry.with_content_format(format);
+        }
+
         // Insert into database using SDK method
         let record_id = (TABLE_NAME, entry.id.as_str());
         let _: Option<DbClipboardEntry> = self
@@ -354,11 +1064,23 @@ impl ClipperIndexer {
                 id: surrealdb::sql::Thing::from((TABLE_NAME.to_string(), entry.id.clone())),
                 content: entry.content.clone(),
                 created_at: surrealdb::sql::Datetime::from(entry.created_at),
+                updated_at: surrealdb::sql::Datetime::from(entry.updated_at),
                 tags: entry.tags.clone(),
                 additional_notes: entry.additional_notes.clone(),
                 file_attachment: entry.file_attachment.clone(),
                 original_filename: entry.original_filename.clone(),
                 language: entry.language.clone(),
+                color: entry.color.clone(),
+                icon: entry.icon.clone(),
+                content_format: entry.content_format,
+                source_device: entry.source_device.clone(),
+                truncated: entry.truncated.then_some(true),
+                visibility: Some(entry.visibility),
+                detected_language: entry.detected_language.clone(),
+                summary: entry.summary.clone(),
+                canonical_url: entry.canonical_url.clone(),
+                suggested_tags: (!entry.suggested_tags.is_empty())
+                    .then(|| entry.suggested_tags.clone()),
                 search_content: entry.search_content.clone(),
             })
             .await?;
@@ -369,6 +1091,65 @@ impl ClipperIndexer {
         Ok(entry)
     }
 
+    /// Add a new text entry, keeping at most `max_bytes` of the text in the
+    /// clip.
+    ///
+    /// Longer text is cut at a character boundary, the whole text is stored
+    /// as a `clip.txt` attachment (`clip.md` for Markdown) and the clip is
+    /// marked [`ClipboardEntry::truncated`]. Only the part kept in the clip
+    /// is indexed for search.
+    pub async fn add_entry_from_text_truncated(
+        &self,
+        content: String,
+        tags: Vec<String>,
+        additional_notes: Option<String>,
+        language: Option<String>,
+        content_format: Option<ContentFormat>,
+        max_bytes: usize,
+    ) -> Result<ClipboardEntry> {
+        if content.len() <= max_bytes {
+            return self
+                .add_entry_from_text_with_format(
+                    content,
+                    tags,
+                    additional_notes,
+                    language,
+                    content_format,
+                )
+                .await;
+        }
+
+        let filename = match content_format {
+            Some(ContentFormat::Markdown) => "clip.md",
+            _ => "clip.txt",
+        };
+        let kept = truncate(&content, max_bytes).to_string();
+        let stored_file_key = self
+            .storage
+            .put_file_bytes(bytes::Bytes::from(content), filename)
+            .await?;
+
+        let mut entry = ClipboardEntry::new(kept, tags)
+            .with_file_attachment(stored_file_key)
+            .with_original_filename(filename.to_string());
+        entry.truncated = true;
+
+        if let Some(notes) = additional_notes {
+            entry = entry.with_notes(notes);
+        }
+
+        if let Some(lang) = language {
+            entry = entry.with_language(lang);
+        }
+
+        if let Some(format) = content_format {
+            entry = entry.with_content_format(format);
+        }
+
+        self.insert_entry_with_id(&entry).await?;
+        Ok(entry)
+    }
+
     pub async fn add_entry_from_file(
         &self,
         file_path: impl AsRef<Path>,
@@ -419,11 +1200,23 @@ impl ClipperIndexer {
                 id: surrealdb::sql::Thing::from((TABLE_NAME.to_string(), entry.id.clone())),
                 content: entry.content.clone(),
                 created_at: surrealdb::sql::Datetime::from(entry.created_at),
+                updated_at: surrealdb::sql::Datetime::from(entry.updated_at),
                 tags: entry.tags.clone(),
                 additional_notes: entry.additional_notes.clone(),
                 file_attachment: entry.file_attachment.clone(),
                 original_filename: entry.original_filename.clone(),
                 language: entry.language.clone(),
+                color: entry.color.clone(),
+                icon: entry.icon.clone(),
+                content_format: entry.content_format,
+                source_device: entry.source_device.clone(),
+                truncated: entry.truncated.then_some(true),
+                visibility: Some(entry.visibility),
+                detected_language: entry.detected_language.clone(),
+                summary: entry.summary.clone(),
+                canonical_url: entry.canonical_url.clone(),
+                suggested_tags: (!entry.suggested_tags.is_empty())
+                    .then(|| entry.suggested_tags.clone()),
                 search_content: entry.search_content.clone(),
             })
             .await?;
@@ -474,6 +1267,10 @@ impl ClipperIndexer {
         let text_content = content_override.unwrap_or_else(|| {
             String::from_utf8(file_content.to_vec()).unwrap_or_else(|_| original_filename.clone())
         });
+        // Make the text inside documents like PDFs searchable too
+        let attachment_text = self
+            .attachment_text(&original_filename, file_content, &text_content)
+            .await;
 
         let mut entry = ClipboardEntry::new(text_content, tags);
         entry = entry.with_file_attachment(stored_file_key);
@@ -482,6 +1279,9 @@ impl ClipperIndexer {
         if let Some(notes) = additional_notes {
             entry = entry.with_notes(notes);
         }
+        if let Some(text) = attachment_text {
+            entry.search_content = format!("{} {}", entry.search_content, text);
+        }
 
         // Insert into database using SDK method
         let record_id = (TABLE_NAME, entry.id.as_str());
@@ -492,11 +1292,23 @@ impl ClipperIndexer {
                 id: surrealdb::sql::Thing::from((TABLE_NAME.to_string(), entry.id.clone())),
                 content: entry.content.clone(),
                 created_at: surrealdb::sql::Datetime::from(entry.created_at),
+                updated_at: surrealdb::sql::Datetime::from(entry.updated_at),
                 tags: entry.tags.clone(),
                 additional_notes: entry.additional_notes.clone(),
                 file_attachment: entry.file_attachment.clone(),
                 original_filename: entry.original_filename.clone(),
                 language: entry.language.clone(),
+                color: entry.color.clone(),
+                icon: entry.icon.clone(),
+                content_format: entry.content_format,
+                source_device: entry.source_device.clone(),
+                truncated: entry.truncated.then_some(true),
+                visibility: Some(entry.visibility),
+                detected_language: entry.detected_language.clone(),
+                summary: entry.summary.clone(),
+                canonical_url: entry.canonical_url.clone(),
+                suggested_tags: (!entry.suggested_tags.is_empty())
+                    .then(|| entry.suggested_tags.clone()),
                 search_content: entry.search_content.clone(),
             })
             .await?;
@@ -516,35 +1328,115 @@ impl ClipperIndexer {
                 id: db_entry.id.id.to_string(),
                 content: db_entry.content,
                 created_at: *db_entry.created_at,
+                updated_at: *db_entry.updated_at,
                 tags: db_entry.tags,
                 additional_notes: db_entry.additional_notes,
                 file_attachment: db_entry.file_attachment,
                 original_filename: db_entry.original_filename,
                 language: db_entry.language,
+                color: db_entry.color,
+                icon: db_entry.icon,
+                content_format: db_entry.content_format,
+                source_device: db_entry.source_device,
+                truncated: db_entry.truncated.unwrap_or_default(),
+                visibility: db_entry.visibility.unwrap_or_default(),
+                detected_language: db_entry.detected_language,
+                summary: db_entry.summary,
+                canonical_url: db_entry.canonical_url,
+                suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
                 search_content: db_entry.search_content,
             })
             .ok_or_else(|| IndexerError::NotFound(format!("Entry with id {} not found", id)))
     }
 
-    /// Update an entry's tags, additional notes, and/or language.
+    /// Get several entries in one query.
     ///
-    /// # Arguments
-    /// * `id` - The ID of the entry to update
-    /// * `tags` - If Some, replaces the tags (empty vec clears tags); if None, leaves tags unchanged
-    /// * `additional_notes` - If Some, replaces the notes (empty string clears to None); if None, leaves notes unchanged
-    /// * `language` - If Some, sets the language (empty string clears to None); if None, leaves unchanged
-    ///
-    /// # Empty value handling
-    /// - `tags: Some(vec![])` - clears tags to empty array
+    /// Entries are returned in the order of `ids`. IDs that don't exist are
+    /// skipped, and repeated IDs are only returned once.
+    pub async fn get_entries(&self, ids: &[String]) -> Result<Vec<ClipboardEntry>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let targets: Vec<String> = (0..ids.len())
+            .map(|i| format!("type::thing($table, $id{})", i))
+            .collect();
+        let query = format!("SELECT * FROM {};", targets.join(", "));
+
+        let mut query_builder = self.db.query(&query).bind(("table", TABLE_NAME));
+        for (i, id) in ids.iter().enumerate() {
+            query_builder = query_builder.bind((format!("id{}", i), id.clone()));
+        }
+        let mut response = query_builder.await?;
+
+        let entries: Vec<DbClipboardEntry> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let mut entries_by_id: HashMap<String, ClipboardEntry> = entries
+            .into_iter()
+            .map(|db_entry| {
+                let entry = ClipboardEntry {
+                    id: db_entry.id.id.to_string(),
+                    content: db_entry.content,
+                    created_at: *db_entry.created_at,
+                    updated_at: *db_entry.updated_at,
+                    tags: db_entry.tags,
+                    additional_notes: db_entry.additional_notes,
+                    file_attachment: db_entry.file_attachment,
+                    original_filename: db_entry.original_filename,
+                    language: db_entry.language,
+                    color: db_entry.color,
+                    icon: db_entry.icon,
+                    content_format: db_entry.content_format,
+                    source_device: db_entry.source_device,
+                    truncated: db_entry.truncated.unwrap_or_default(),
+                    visibility: db_entry.visibility.unwrap_or_default(),
+                    detected_language: db_entry.detected_language,
+                    summary: db_entry.summary,
+                    canonical_url: db_entry.canonical_url,
+                    suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
+                    search_content: db_entry.search_content,
+                };
+                (entry.id.clone(), entry)
+            })
+            .collect();
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| entries_by_id.remove(id))
+            .collect())
+    }
+
+    /// Update an entry's tags, additional notes, language, color and/or icon.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the entry to update
+    /// * `tags` - If Some, replaces the tags (empty vec clears tags); if None, leaves tags unchanged
+    /// * `additional_notes` - If Some, replaces the notes (empty string clears to None); if None, leaves notes unchanged
+    /// * `language` - If Some, sets the language (empty string clears to None); if None, leaves unchanged
+    /// * `color` - If Some, sets the color label (empty string clears to None); if None, leaves unchanged.
+    ///   Must be a hex color (`#rgb` or `#rrggbb`) or a color name of ASCII letters
+    /// * `icon` - If Some, sets the icon (empty string clears to None); if None, leaves unchanged.
+    ///   At most 32 characters, e.g. an emoji or an icon name
+    ///
+    /// # Empty value handling
+    /// - `tags: Some(vec![])` - clears tags to empty array
     /// - `additional_notes: Some("")` - clears notes to None in database
     /// - `language: Some("")` - clears language to None in database
+    /// - `color: Some("")` and `icon: Some("")` - clear the color and icon to None in database
     pub async fn update_entry(
         &self,
         id: &str,
         tags: Option<Vec<String>>,
         additional_notes: Option<String>,
         language: Option<String>,
+        color: Option<String>,
+        icon: Option<String>,
     ) -> Result<ClipboardEntry> {
+        let color_normalized = color.map(|color| normalize_color(&color)).transpose()?;
+        let icon_normalized = icon.map(|icon| normalize_icon(&icon)).transpose()?;
+
         // First, retrieve the existing entry to get the content
         let existing_entry = self.get_entry(id).await?;
 
@@ -566,7 +1458,7 @@ impl ClipperIndexer {
         });
 
         // Calculate new search_content if additional_notes is being updated
-        let new_search_content = match &additional_notes_normalized {
+        let mut new_search_content = match &additional_notes_normalized {
             Some(Some(notes)) => format!("{} {}", existing_entry.content, notes),
             Some(None) => existing_entry.content.clone(), // Clearing notes
             None => match &existing_entry.additional_notes {
@@ -574,6 +1466,21 @@ impl ClipperIndexer {
                 None => existing_entry.content.clone(),
             },
         };
+        // Keep the attachment's text searchable, unless it's the full text of
+        // a truncated clip, which is left out of the index on purpose
+        if additional_notes_normalized.is_some()
+            && !existing_entry.truncated
+            && let (Some(file_key), Some(filename)) = (
+                &existing_entry.file_attachment,
+                &existing_entry.original_filename,
+            )
+            && let Ok(file_content) = self.storage.get_file(file_key).await
+            && let Some(text) = self
+                .attachment_text(filename, file_content, &existing_entry.content)
+                .await
+        {
+            new_search_content = format!("{} {}", new_search_content, text);
+        }
 
         // Build update query
         let mut updates = Vec::new();
@@ -593,9 +1500,18 @@ impl ClipperIndexer {
             updates.push("language = $language");
         }
 
+        if color_normalized.is_some() {
+            updates.push("color = $color");
+        }
+
+        if icon_normalized.is_some() {
+            updates.push("icon = $icon");
+        }
+
         if updates.is_empty() {
             return Ok(existing_entry);
         }
+        updates.push("updated_at = time::now()");
 
         let query_string = format!("{}{};", query_string, updates.join(", "));
 
@@ -618,6 +1534,14 @@ impl ClipperIndexer {
             query = query.bind(("language", lang_opt));
         }
 
+        if let Some(color_opt) = color_normalized {
+            query = query.bind(("color", color_opt));
+        }
+
+        if let Some(icon_opt) = icon_normalized {
+            query = query.bind(("icon", icon_opt));
+        }
+
         query.await?;
 
         // Sync tags to the tags table if tags were updated
@@ -629,6 +1553,143 @@ impl ClipperIndexer {
         self.get_entry(id).await
     }
 
+    /// Set the content format hint of an entry.
+    pub async fn set_content_format(
+        &self,
+        id: &str,
+        content_format: ContentFormat,
+    ) -> Result<ClipboardEntry> {
+        let existing_entry = self.get_entry(id).await?;
+        if existing_entry.content_format == Some(content_format) {
+            return Ok(existing_entry);
+        }
+
+        self.db
+            .query(
+                "UPDATE type::thing($table, $id) SET content_format = $content_format, updated_at = time::now();",
+            )
+            .bind(("table", TABLE_NAME))
+            .bind(("id", id.to_string()))
+            .bind(("content_format", content_format))
+            .await?;
+
+        self.get_entry(id).await
+    }
+
+    /// Set who can see an entry.
+    pub async fn set_visibility(&self, id: &str, visibility: Visibility) -> Result<ClipboardEntry> {
+        let existing_entry = self.get_entry(id).await?;
+        if existing_entry.visibility == visibility {
+            return Ok(existing_entry);
+        }
+
+        self.db
+            .query(
+                "UPDATE type::thing($table, $id) SET visibility = $visibility, updated_at = time::now();",
+            )
+            .bind(("table", TABLE_NAME))
+            .bind(("id", id.to_string()))
+            .bind(("visibility", visibility))
+            .await?;
+
+        self.get_entry(id).await
+    }
+
+    /// Append text to a text entry.
+    ///
+    /// # Arguments
+    /// * `id` - The entry; entries with file attachments can't be appended to
+    /// * `text` - The text to add at the end
+    /// * `separator` - Put between the existing content and `text`, unless the content is empty
+    pub async fn append_to_entry(
+        &self,
+        id: &str,
+        text: &str,
+        separator: &str,
+    ) -> Result<ClipboardEntry> {
+        let existing_entry = self.get_entry(id).await?;
+        if existing_entry.file_attachment.is_some() {
+            return Err(IndexerError::InvalidInput(
+                "Entries with file attachments cannot be appended to".to_string(),
+            ));
+        }
+
+        let mut content = existing_entry.content;
+        if !content.is_empty() {
+            content.push_str(separator);
+        }
+        content.push_str(text);
+        let search_content = match &existing_entry.additional_notes {
+            Some(notes) => format!("{} {}", tokenize(&content), notes),
+            None => tokenize(&content),
+        };
+        let detected_language = detect_language(&content);
+
+        // The canonical URL was for the content before, which was a single URL
+        self.db
+            .query(
+                "UPDATE type::thing($table, $id) SET content = $content, search_content = $search_content, detected_language = $detected_language, canonical_url = NONE, updated_at = time::now();",
+            )
+            .bind(("table", TABLE_NAME))
+            .bind(("id", id.to_string()))
+            .bind(("content", content))
+            .bind(("detected_language", detected_language))
+            .bind(("search_content", search_content))
+            .await?;
+
+        self.get_entry(id).await
+    }
+
+    /// Store the summary and suggested tags written for an entry by an
+    /// enrichment service; `None` and an empty list clear them.
+    pub async fn set_enrichment(
+        &self,
+        id: &str,
+        summary: Option<String>,
+        suggested_tags: Vec<String>,
+    ) -> Result<ClipboardEntry> {
+        let existing_entry = self.get_entry(id).await?;
+        if existing_entry.summary == summary && existing_entry.suggested_tags == suggested_tags {
+            return Ok(existing_entry);
+        }
+
+        self.db
+            .query(
+                "UPDATE type::thing($table, $id) SET summary = $summary, suggested_tags = $suggested_tags, updated_at = time::now();",
+            )
+            .bind(("table", TABLE_NAME))
+            .bind(("id", id.to_string()))
+            .bind(("summary", summary))
+            .bind(("suggested_tags", (!suggested_tags.is_empty()).then_some(suggested_tags)))
+            .await?;
+
+        self.get_entry(id).await
+    }
+
+    /// Store the canonical URL of an entry holding a URL, i.e. where its
+    /// redirects lead without tracking parameters; `None` clears it.
+    pub async fn set_canonical_url(
+        &self,
+        id: &str,
+        canonical_url: Option<String>,
+    ) -> Result<ClipboardEntry> {
+        let existing_entry = self.get_entry(id).await?;
+        if existing_entry.canonical_url == canonical_url {
+            return Ok(existing_entry);
+        }
+
+        self.db
+            .query(
+                "UPDATE type::thing($table, $id) SET canonical_url = $canonical_url, updated_at = time::now();",
+            )
+            .bind(("table", TABLE_NAME))
+            .bind(("id", id.to_string()))
+            .bind(("canonical_url", canonical_url))
+            .await?;
+
+        self.get_entry(id).await
+    }
+
     pub async fn search_entries(
         &self,
         search_query: &str,
@@ -636,31 +1697,49 @@ impl ClipperIndexer {
         paging: PagingParams,
     ) -> Result<PagedResult<ClipboardEntry>> {
         let result = self
-            .search_entries_with_highlight(search_query, filters, paging, None)
+            .search_entries_with_highlight(search_query, filters, paging, None, None)
             .await?;
 
         // Convert SearchResultItem back to ClipboardEntry
         let items: Vec<ClipboardEntry> = result.items.into_iter().map(|item| item.entry).collect();
 
-        Ok(PagedResult::new(
-            items,
-            result.total,
-            result.page,
-            result.page_size,
-        ))
+        Ok(
+            PagedResult::new(items, result.total, result.page, result.page_size)
+                .with_has_more(result.has_more)
+                .with_next_cursor(result.next_cursor),
+        )
     }
 
     /// Search entries with optional highlighting support.
     ///
+    /// The search string may contain operators such as `tag:work`,
+    /// `-tag:private`, `"exact phrase"`, `before:2024-01-01` and
+    /// `has:attachment`; see [`crate::query`]. Operators are combined with
+    /// `filters`, and a malformed operator returns `InvalidInput`.
+    ///
+    /// Results are ordered newest first unless `filters.sort` is
+    /// [`SortOrder::Relevance`], which orders them by BM25 score, or for fuzzy
+    /// search by the number of edits between the terms and the clip's words.
+    /// Searches with only operators have no score and stay newest first.
+    ///
+    /// `paging.cursor` continues after a previous page's `next_cursor`. Cursors
+    /// are not available when sorting by relevance.
+    ///
+    /// When `fuzzy` is provided, search terms also match words within the
+    /// configured edit distance (e.g. "recieve" finds "receive"). Fuzzy search
+    /// finds candidates through an n-gram index of the clips' words and checks
+    /// them one by one, so it is slower than exact search.
+    ///
     /// When `highlight` is provided with both prefix and suffix, the returned
     /// `SearchResultItem` will include `highlighted_content` with matching terms
     /// wrapped by the prefix and suffix strings.
     ///
     /// # Arguments
-    /// * `search_query` - The search query string
+    /// * `search_query` - The search string, with optional query operators
     /// * `filters` - Optional filters for date range and tags
     /// * `paging` - Pagination parameters
     /// * `highlight` - Optional highlight options (prefix/suffix for matched terms)
+    /// * `fuzzy` - Optional typo tolerance for search terms
     ///
     /// # Returns
     /// A paged result containing search result items with optional highlighted content
@@ -670,10 +1749,14 @@ impl ClipperIndexer {
         filters: SearchFilters,
         paging: PagingParams,
         highlight: Option<HighlightOptions>,
+        fuzzy: Option<FuzzyOptions>,
     ) -> Result<PagedResult<SearchResultItem>> {
-        // Return all entries if search query is empty
-        if search_query.trim().is_empty() {
-            let result = self.list_entries(filters, paging).await?;
+        let query = SearchQuery::parse(search_query)?;
+        let full_text = query.full_text();
+
+        // Without words to match, only the filters and operators apply
+        if full_text.trim().is_empty() {
+            let result = self.list_entries_matching(filters, &query, paging).await?;
             let items: Vec<SearchResultItem> = result
                 .items
                 .into_iter()
@@ -682,107 +1765,87 @@ impl ClipperIndexer {
                     highlighted_content: None,
                 })
                 .collect();
-            return Ok(PagedResult::new(
-                items,
-                result.total,
-                result.page,
-                result.page_size,
-            ));
+            return Ok(
+                PagedResult::new(items, result.total, result.page, result.page_size)
+                    .with_has_more(result.has_more)
+                    .with_next_cursor(result.next_cursor),
+            );
+        }
+
+        if let Some(fuzzy) = fuzzy {
+            return self
+                .fuzzy_search_entries(filters, &query, paging, highlight, &fuzzy)
+                .await;
         }
 
         let highlight_enabled = highlight.as_ref().map(|h| h.is_enabled()).unwrap_or(false);
 
         // Pre-tokenize search query for better Chinese search
-        let tokenized_query = crate::models::tokenize(search_query);
+        let tokenized_query = crate::models::tokenize(&full_text);
+
+        let sort_by_relevance = filters.sort == SortOrder::Relevance;
 
         // Use reference number 0 for the matches operator
-        let match_operator = if highlight_enabled { "@0@" } else { "@@" };
+        let match_operator = if highlight_enabled || sort_by_relevance {
+            "@0@"
+        } else {
+            "@@"
+        };
+        let (conditions, mut bindings) = filter_conditions(&filters, &query);
         let mut where_clauses = vec![format!("search_content {} $query", match_operator)];
+        where_clauses.extend(conditions);
+        bindings.push(("query".to_string(), tokenized_query));
 
-        if filters.start_date.is_some() {
-            where_clauses.push("created_at >= <datetime>$start_date".to_string());
+        if sort_by_relevance && paging.cursor.is_some() {
+            return Err(IndexerError::InvalidInput(
+                "Cursors are not supported when sorting by relevance".to_string(),
+            ));
         }
+        let cursor = cursor_condition(&filters, &paging)?;
 
-        if filters.end_date.is_some() {
-            where_clauses.push("created_at <= <datetime>$end_date".to_string());
-        }
+        let total = if paging.skip_total {
+            None
+        } else {
+            Some(self.count_entries(&where_clauses, &bindings).await?)
+        };
 
-        // For tags, we need to check membership - use array contains
-        let filter_tags = filters.tags.clone();
-        if let Some(ref tags) = filter_tags
-            && !tags.is_empty()
-        {
-            // Build tag conditions using indexed parameters
-            let tag_conditions: Vec<String> = (0..tags.len())
-                .map(|i| format!("$tag{} IN tags", i))
-                .collect();
-            where_clauses.push(format!("({})", tag_conditions.join(" AND ")));
+        // The total covers all matches, the page only those after the cursor
+        if let Some((condition, cursor_bindings)) = cursor {
+            where_clauses.push(condition);
+            bindings.extend(cursor_bindings);
         }
-
         let where_clause = where_clauses.join(" AND ");
 
-        // Get total count
-        let count_query = format!(
-            "SELECT count() FROM {} WHERE {} GROUP ALL;",
-            TABLE_NAME, where_clause
-        );
-        let mut count_query_builder = self
-            .db
-            .query(&count_query)
-            .bind(("query", tokenized_query.clone()));
-
-        if let Some(start_date) = filters.start_date {
-            count_query_builder = count_query_builder.bind(("start_date", start_date.to_rfc3339()));
-        }
-        if let Some(end_date) = filters.end_date {
-            count_query_builder = count_query_builder.bind(("end_date", end_date.to_rfc3339()));
-        }
-        if let Some(ref tags) = filter_tags {
-            for (i, tag) in tags.iter().enumerate() {
-                count_query_builder = count_query_builder.bind((format!("tag{}", i), tag.clone()));
-            }
-        }
-
-        let mut count_response = count_query_builder.await?;
-
-        #[derive(Deserialize)]
-        struct CountResult {
-            count: i64,
+        // Build select clause with optional highlight and BM25 score
+        let mut select_clause = "*".to_string();
+        if highlight_enabled {
+            select_clause
+                .push_str(", search::highlight($hl_prefix, $hl_suffix, 0) AS highlighted_content");
         }
-
-        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
-        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
-
-        // Build select clause with optional highlight
-        let select_clause = if highlight_enabled {
-            "*, search::highlight($hl_prefix, $hl_suffix, 0) AS highlighted_content".to_string()
+        let order_clause = if sort_by_relevance {
+            select_clause.push_str(", search::score(0) AS relevance_score");
+            "relevance_score DESC, created_at DESC".to_string()
         } else {
-            "*".to_string()
+            let (sort_field, order_clause) = sort_clause(&filters);
+            select_clause.push_str(sort_field);
+            order_clause
         };
 
         // Get paginated results
         let query = format!(
-            "SELECT {} FROM {} WHERE {} ORDER BY created_at DESC LIMIT $limit START $offset;",
-            select_clause, TABLE_NAME, where_clause
+            "SELECT {} FROM {} WHERE {} ORDER BY {} LIMIT $limit START $offset;",
+            select_clause, TABLE_NAME, where_clause, order_clause
         );
 
+        // Fetch one more than the page to tell whether another page follows
         let mut query_builder = self
             .db
             .query(&query)
-            .bind(("query", tokenized_query))
-            .bind(("limit", paging.page_size as i64))
+            .bind(("limit", paging.page_size as i64 + 1))
             .bind(("offset", paging.offset() as i64));
 
-        if let Some(start_date) = filters.start_date {
-            query_builder = query_builder.bind(("start_date", start_date.to_rfc3339()));
-        }
-        if let Some(end_date) = filters.end_date {
-            query_builder = query_builder.bind(("end_date", end_date.to_rfc3339()));
-        }
-        if let Some(ref tags) = filter_tags {
-            for (i, tag) in tags.iter().enumerate() {
-                query_builder = query_builder.bind((format!("tag{}", i), tag.clone()));
-            }
+        for (name, value) in &bindings {
+            query_builder = query_builder.bind((name.clone(), value.clone()));
         }
         if highlight_enabled {
             let h = highlight.as_ref().unwrap();
@@ -793,17 +1856,28 @@ impl ClipperIndexer {
         let mut response = query_builder.await?;
 
         // Use a different struct when highlight is enabled
-        if highlight_enabled {
+        let mut items: Vec<SearchResultItem> = if highlight_enabled {
             #[derive(Deserialize)]
             struct DbClipboardEntryWithHighlight {
                 id: surrealdb::sql::Thing,
                 content: String,
                 created_at: surrealdb::sql::Datetime,
+                updated_at: surrealdb::sql::Datetime,
                 tags: Vec<String>,
                 additional_notes: Option<String>,
                 file_attachment: Option<String>,
                 original_filename: Option<String>,
                 language: Option<String>,
+                color: Option<String>,
+                icon: Option<String>,
+                content_format: Option<ContentFormat>,
+                source_device: Option<String>,
+                truncated: Option<bool>,
+                visibility: Option<Visibility>,
+                detected_language: Option<String>,
+                summary: Option<String>,
+                canonical_url: Option<String>,
+                suggested_tags: Option<Vec<String>>,
                 search_content: String,
                 highlighted_content: Option<String>,
             }
@@ -812,149 +1886,302 @@ impl ClipperIndexer {
                 .take(0)
                 .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-            let items: Vec<SearchResultItem> = entries
+            entries
                 .into_iter()
                 .map(|db_entry| SearchResultItem {
                     entry: ClipboardEntry {
                         id: db_entry.id.id.to_string(),
                         content: db_entry.content,
                         created_at: *db_entry.created_at,
+                        updated_at: *db_entry.updated_at,
                         tags: db_entry.tags,
                         additional_notes: db_entry.additional_notes,
                         file_attachment: db_entry.file_attachment,
                         original_filename: db_entry.original_filename,
                         language: db_entry.language,
+                        color: db_entry.color,
+                        icon: db_entry.icon,
+                        content_format: db_entry.content_format,
+                        source_device: db_entry.source_device,
+                        truncated: db_entry.truncated.unwrap_or_default(),
+                        visibility: db_entry.visibility.unwrap_or_default(),
+                        detected_language: db_entry.detected_language,
+                        summary: db_entry.summary,
+                        canonical_url: db_entry.canonical_url,
+                        suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
                         search_content: db_entry.search_content,
                     },
                     highlighted_content: db_entry.highlighted_content,
                 })
-                .collect();
-
-            Ok(PagedResult::new(
-                items,
-                total,
-                paging.page,
-                paging.page_size,
-            ))
+                .collect()
         } else {
             let entries: Vec<DbClipboardEntry> = response
                 .take(0)
                 .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-            let items: Vec<SearchResultItem> = entries
+            entries
                 .into_iter()
                 .map(|db_entry| SearchResultItem {
                     entry: ClipboardEntry {
                         id: db_entry.id.id.to_string(),
                         content: db_entry.content,
                         created_at: *db_entry.created_at,
+                        updated_at: *db_entry.updated_at,
                         tags: db_entry.tags,
                         additional_notes: db_entry.additional_notes,
                         file_attachment: db_entry.file_attachment,
                         original_filename: db_entry.original_filename,
                         language: db_entry.language,
+                        color: db_entry.color,
+                        icon: db_entry.icon,
+                        content_format: db_entry.content_format,
+                        source_device: db_entry.source_device,
+                        truncated: db_entry.truncated.unwrap_or_default(),
+                        visibility: db_entry.visibility.unwrap_or_default(),
+                        detected_language: db_entry.detected_language,
+                        summary: db_entry.summary,
+                        canonical_url: db_entry.canonical_url,
+                        suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
                         search_content: db_entry.search_content,
                     },
                     highlighted_content: None,
                 })
-                .collect();
+                .collect()
+        };
 
-            Ok(PagedResult::new(
-                items,
-                total,
-                paging.page,
-                paging.page_size,
-            ))
-        }
+        let has_more = split_has_more(&mut items, &paging);
+        let total = total.unwrap_or_else(|| estimated_total(&paging, items.len(), has_more));
+        let next_cursor = if sort_by_relevance {
+            None
+        } else {
+            next_cursor(&filters, has_more, items.last().map(|item| &item.entry))
+        };
+
+        Ok(
+            PagedResult::new(items, total, paging.page, paging.page_size)
+                .with_has_more(has_more)
+                .with_next_cursor(next_cursor),
+        )
     }
 
-    pub async fn list_entries(
+    /// Typo-tolerant search over the clips that pass the filters and query
+    /// operators, closest matches first when sorting by relevance and
+    /// otherwise in the order given by `filters.sort_by`
+    async fn fuzzy_search_entries(
         &self,
         filters: SearchFilters,
+        search_query: &SearchQuery,
         paging: PagingParams,
-    ) -> Result<PagedResult<ClipboardEntry>> {
-        let mut where_clauses = Vec::new();
-
-        if filters.start_date.is_some() {
-            where_clauses.push("created_at >= <datetime>$start_date".to_string());
-        }
-
-        if filters.end_date.is_some() {
-            where_clauses.push("created_at <= <datetime>$end_date".to_string());
+        highlight: Option<HighlightOptions>,
+        fuzzy: &FuzzyOptions,
+    ) -> Result<PagedResult<SearchResultItem>> {
+        let terms = crate::fuzzy::terms(&search_query.terms.join(" "));
+        let sort_by_relevance = filters.sort == SortOrder::Relevance;
+        if sort_by_relevance && paging.cursor.is_some() {
+            return Err(IndexerError::InvalidInput(
+                "Cursors are not supported when sorting by relevance".to_string(),
+            ));
         }
-
-        let filter_tags = filters.tags.clone();
-        if let Some(ref tags) = filter_tags
-            && !tags.is_empty()
-        {
-            let tag_conditions: Vec<String> = (0..tags.len())
-                .map(|i| format!("$tag{} IN tags", i))
-                .collect();
-            where_clauses.push(format!("({})", tag_conditions.join(" AND ")));
+        let cursor = paging
+            .cursor
+            .as_deref()
+            .map(|cursor| Cursor::decode(cursor, filters.sort_by))
+            .transpose()?;
+        let (where_clauses, mut bindings) = filter_conditions(&filters, search_query);
+        let (sort_field, order_clause) = sort_clause(&filters);
+
+        // Only clips sharing an n-gram with every term can match
+        let mut candidate_ids = None;
+        for (i, term) in terms.iter().enumerate() {
+            let Some(grams) = crate::fuzzy::grams(term, fuzzy) else {
+                continue;
+            };
+            let mut conditions = Vec::new();
+            for (j, gram) in grams.into_iter().enumerate() {
+                conditions.push(format!("fuzzy_words @@ $fuzzy{i}_{j}"));
+                bindings.push((format!("fuzzy{i}_{j}"), gram));
+            }
+            let ids = format!(
+                "(SELECT VALUE id FROM {} WHERE {})",
+                TABLE_NAME,
+                conditions.join(" OR ")
+            );
+            candidate_ids = Some(match candidate_ids {
+                Some(previous) => format!("array::intersect({previous}, {ids})"),
+                None => ids,
+            });
         }
 
-        // Get total count
-        let count_query = if where_clauses.is_empty() {
-            format!("SELECT count() FROM {} GROUP ALL;", TABLE_NAME)
+        let (let_statement, source) = match &candidate_ids {
+            Some(ids) => (format!("LET $candidates = {ids}; "), "$candidates"),
+            None => (String::new(), TABLE_NAME),
+        };
+        let select_clause = format!(
+            "{}SELECT id, created_at, updated_at, original_filename, fuzzy_words{} FROM {}",
+            let_statement, sort_field, source
+        );
+        let query = if where_clauses.is_empty() {
+            format!("{} ORDER BY {};", select_clause, order_clause)
         } else {
-            let where_clause = where_clauses.join(" AND ");
             format!(
-                "SELECT count() FROM {} WHERE {} GROUP ALL;",
-                TABLE_NAME, where_clause
+                "{} WHERE {} ORDER BY {};",
+                select_clause,
+                where_clauses.join(" AND "),
+                order_clause
             )
         };
 
-        let mut count_query_builder = self.db.query(&count_query);
-        if let Some(start_date) = filters.start_date {
-            count_query_builder = count_query_builder.bind(("start_date", start_date.to_rfc3339()));
+        let mut query_builder = self.db.query(&query);
+        for (name, value) in &bindings {
+            query_builder = query_builder.bind((name.clone(), value.clone()));
         }
-        if let Some(end_date) = filters.end_date {
-            count_query_builder = count_query_builder.bind(("end_date", end_date.to_rfc3339()));
+        let mut response = query_builder.await?;
+
+        #[derive(Deserialize)]
+        struct Candidate {
+            id: surrealdb::sql::Thing,
+            created_at: surrealdb::sql::Datetime,
+            updated_at: surrealdb::sql::Datetime,
+            fuzzy_words: Option<Vec<String>>,
         }
-        if let Some(ref tags) = filter_tags {
-            for (i, tag) in tags.iter().enumerate() {
-                count_query_builder = count_query_builder.bind((format!("tag{}", i), tag.clone()));
-            }
+
+        let candidates: Vec<Candidate> = response
+            .take(usize::from(candidate_ids.is_some()))
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let mut matching: Vec<(usize, Candidate)> = candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                let words = candidate
+                    .fuzzy_words
+                    .as_deref()
+                    .unwrap_or_default()
+                    .join(" ");
+                crate::fuzzy::distance(&words, &terms, fuzzy).map(|distance| (distance, candidate))
+            })
+            .collect();
+        let total = matching.len();
+        // Ties keep the order of `filters.sort_by`
+        if sort_by_relevance {
+            matching.sort_by_key(|(distance, _)| *distance);
         }
-        let mut count_response = count_query_builder.await?;
 
-        #[derive(Deserialize)]
-        struct CountResult {
-            count: i64,
+        // The total covers all matches, the page only those after the cursor
+        let mut page_ids: Vec<String> = matching
+            .into_iter()
+            .map(|(_, candidate)| {
+                (
+                    *candidate.created_at,
+                    *candidate.updated_at,
+                    candidate.id.id.to_string(),
+                )
+            })
+            .filter(|(created_at, updated_at, id)| {
+                cursor.as_ref().is_none_or(|cursor| {
+                    cursor.precedes(*created_at, *updated_at, id, filters.order)
+                })
+            })
+            .skip(paging.offset())
+            .take(paging.page_size + 1)
+            .map(|(_, _, id)| id)
+            .collect();
+        let has_more = split_has_more(&mut page_ids, &paging);
+
+        let highlight = highlight.filter(|h| h.is_enabled());
+        let mut items = Vec::new();
+        for id in &page_ids {
+            let entry = self.get_entry(id).await?;
+            let highlighted_content = highlight.as_ref().map(|h| {
+                crate::fuzzy::highlight(
+                    &entry.content,
+                    &terms,
+                    fuzzy,
+                    h.prefix.as_deref().unwrap_or_default(),
+                    h.suffix.as_deref().unwrap_or_default(),
+                )
+            });
+            items.push(SearchResultItem {
+                entry,
+                highlighted_content,
+            });
         }
 
-        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
-        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+        let next_cursor = if sort_by_relevance {
+            None
+        } else {
+            next_cursor(&filters, has_more, items.last().map(|item| &item.entry))
+        };
+
+        Ok(
+            PagedResult::new(items, total, paging.page, paging.page_size)
+                .with_has_more(has_more)
+                .with_next_cursor(next_cursor),
+        )
+    }
+
+    /// List entries matching the filters, in the order given by
+    /// `filters.sort_by`.
+    ///
+    /// For `created_at` and `updated_at` sorts, pages followed by more entries
+    /// come with a `next_cursor`; passing it back in `paging.cursor` continues exactly
+    /// after the last entry, even if entries were added in the meantime.
+    /// A cursor for another sort field returns `InvalidInput`.
+    pub async fn list_entries(
+        &self,
+        filters: SearchFilters,
+        paging: PagingParams,
+    ) -> Result<PagedResult<ClipboardEntry>> {
+        self.list_entries_matching(filters, &SearchQuery::default(), paging)
+            .await
+    }
+
+    /// List entries matching the filters and the operators of a parsed search
+    /// query, in the order given by `filters.sort_by`. Free-text terms in the
+    /// query are not used.
+    async fn list_entries_matching(
+        &self,
+        filters: SearchFilters,
+        search_query: &SearchQuery,
+        paging: PagingParams,
+    ) -> Result<PagedResult<ClipboardEntry>> {
+        let cursor = cursor_condition(&filters, &paging)?;
+        let (mut where_clauses, mut bindings) = filter_conditions(&filters, search_query);
+
+        let total = if paging.skip_total {
+            None
+        } else {
+            Some(self.count_entries(&where_clauses, &bindings).await?)
+        };
+
+        // The total covers all matches, the page only those after the cursor
+        if let Some((condition, cursor_bindings)) = cursor {
+            where_clauses.push(condition);
+            bindings.extend(cursor_bindings);
+        }
 
         // Get paginated results
+        let (sort_field, order_clause) = sort_clause(&filters);
         let query = if where_clauses.is_empty() {
             format!(
-                "SELECT * FROM {} ORDER BY created_at DESC LIMIT $limit START $offset;",
-                TABLE_NAME
+                "SELECT *{} FROM {} ORDER BY {} LIMIT $limit START $offset;",
+                sort_field, TABLE_NAME, order_clause
             )
         } else {
             let where_clause = where_clauses.join(" AND ");
             format!(
-                "SELECT * FROM {} WHERE {} ORDER BY created_at DESC LIMIT $limit START $offset;",
-                TABLE_NAME, where_clause
+                "SELECT *{} FROM {} WHERE {} ORDER BY {} LIMIT $limit START $offset;",
+                sort_field, TABLE_NAME, where_clause, order_clause
             )
         };
 
+        // Fetch one more than the page to tell whether another page follows
         let mut query_builder = self
             .db
             .query(&query)
-            .bind(("limit", paging.page_size as i64))
+            .bind(("limit", paging.page_size as i64 + 1))
             .bind(("offset", paging.offset() as i64));
-        if let Some(start_date) = filters.start_date {
-            query_builder = query_builder.bind(("start_date", start_date.to_rfc3339()));
-        }
-        if let Some(end_date) = filters.end_date {
-            query_builder = query_builder.bind(("end_date", end_date.to_rfc3339()));
-        }
-        if let Some(ref tags) = filter_tags {
-            for (i, tag) in tags.iter().enumerate() {
-                query_builder = query_builder.bind((format!("tag{}", i), tag.clone()));
-            }
+        for (name, value) in &bindings {
+            query_builder = query_builder.bind((name.clone(), value.clone()));
         }
 
         let mut response = query_builder.await?;
@@ -963,33 +2190,88 @@ impl ClipperIndexer {
             .take(0)
             .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        let items: Vec<ClipboardEntry> = entries
+        let mut items: Vec<ClipboardEntry> = entries
             .into_iter()
             .map(|db_entry| ClipboardEntry {
                 id: db_entry.id.id.to_string(),
                 content: db_entry.content,
                 created_at: *db_entry.created_at,
+                updated_at: *db_entry.updated_at,
                 tags: db_entry.tags,
                 additional_notes: db_entry.additional_notes,
                 file_attachment: db_entry.file_attachment,
                 original_filename: db_entry.original_filename,
                 language: db_entry.language,
+                color: db_entry.color,
+                icon: db_entry.icon,
+                content_format: db_entry.content_format,
+                source_device: db_entry.source_device,
+                truncated: db_entry.truncated.unwrap_or_default(),
+                visibility: db_entry.visibility.unwrap_or_default(),
+                detected_language: db_entry.detected_language,
+                summary: db_entry.summary,
+                canonical_url: db_entry.canonical_url,
+                suggested_tags: db_entry.suggested_tags.unwrap_or_default(),
                 search_content: db_entry.search_content,
             })
             .collect();
 
-        Ok(PagedResult::new(
-            items,
-            total,
-            paging.page,
-            paging.page_size,
-        ))
+        let has_more = split_has_more(&mut items, &paging);
+        let total = total.unwrap_or_else(|| estimated_total(&paging, items.len(), has_more));
+        let next_cursor = next_cursor(&filters, has_more, items.last());
+
+        Ok(
+            PagedResult::new(items, total, paging.page, paging.page_size)
+                .with_has_more(has_more)
+                .with_next_cursor(next_cursor),
+        )
+    }
+
+    /// Number of clips matching all `conditions`
+    async fn count_entries(
+        &self,
+        conditions: &[String],
+        bindings: &[(String, String)],
+    ) -> Result<usize> {
+        let count_query = if conditions.is_empty() {
+            format!("SELECT count() FROM {} GROUP ALL;", TABLE_NAME)
+        } else {
+            format!(
+                "SELECT count() FROM {} WHERE {} GROUP ALL;",
+                TABLE_NAME,
+                conditions.join(" AND ")
+            )
+        };
+
+        let mut count_query_builder = self.db.query(&count_query);
+        for (name, value) in bindings {
+            count_query_builder = count_query_builder.bind((name.clone(), value.clone()));
+        }
+        let mut count_response = count_query_builder.await?;
+
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
+
+        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
+        Ok(count_results.first().map(|c| c.count as usize).unwrap_or(0))
     }
 
     pub async fn get_file_content(&self, file_key: &str) -> Result<bytes::Bytes> {
         self.storage.get_file(file_key).await
     }
 
+    /// Check that attachments can be stored by writing and deleting a small
+    /// file in the storage directory.
+    pub async fn check_storage(&self) -> Result<()> {
+        let file_key = self
+            .storage
+            .put_file_bytes(bytes::Bytes::from_static(b"ok"), "storage-check")
+            .await?;
+        self.storage.delete_file(&file_key).await
+    }
+
     pub async fn delete_entry(&self, id: &str) -> Result<()> {
         // Get the entry to check if it has a file attachment
         let entry = self.get_entry(id).await?;
@@ -1007,9 +2289,93 @@ impl ClipperIndexer {
             .bind(("id", id.to_string()))
             .await?;
 
+        self.delete_links_for_clips(vec![id.to_string()]).await?;
+        self.delete_collection_items_for_clips(vec![id.to_string()])
+            .await?;
+
         Ok(())
     }
 
+    /// Split a text entry into one new entry per part, in one transaction.
+    ///
+    /// The new entries get the source's tags, language, content format and
+    /// visibility, and are created one microsecond apart in the order of
+    /// `parts`. Blank parts are skipped.
+    ///
+    /// # Arguments
+    /// * `id` - The entry to split; entries with file attachments can't be split
+    /// * `parts` - The text of the new entries, at least two after skipping blank ones
+    /// * `keep_source` - If false, the source entry is deleted in the same transaction
+    pub async fn split_entry(
+        &self,
+        id: &str,
+        parts: Vec<String>,
+        keep_source: bool,
+    ) -> Result<Vec<ClipboardEntry>> {
+        let source = self.get_entry(id).await?;
+        if source.file_attachment.is_some() {
+            return Err(IndexerError::InvalidInput(
+                "Entries with file attachments cannot be split".to_string(),
+            ));
+        }
+        let parts: Vec<String> = parts
+            .into_iter()
+            .filter(|part| !part.trim().is_empty())
+            .collect();
+        if parts.len() < 2 {
+            return Err(IndexerError::InvalidInput(
+                "Splitting must give at least two parts".to_string(),
+            ));
+        }
+
+        let now = chrono::Utc::now();
+        let entries: Vec<ClipboardEntry> = parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let mut entry = ClipboardEntry::new(part, source.tags.clone())
+                    .with_visibility(source.visibility);
+                entry.language = source.language.clone();
+                entry.content_format = source.content_format;
+                entry.created_at = now + chrono::Duration::microseconds(index as i64);
+                entry.updated_at = entry.created_at;
+                entry
+            })
+            .collect();
+
+        let mut query = String::from("BEGIN TRANSACTION;\n");
+        for index in 0..entries.len() {
+            query.push_str(&format!(
+                "CREATE type::thing($table, $id{index}) CONTENT $entry{index};\n"
+            ));
+        }
+        if !keep_source {
+            query.push_str("DELETE type::thing($table, $source_id);\n");
+        }
+        query.push_str("COMMIT TRANSACTION;");
+
+        let mut request = self
+            .db
+            .query(query)
+            .bind(("table", TABLE_NAME))
+            .bind(("source_id", id.to_string()));
+        for (index, entry) in entries.iter().enumerate() {
+            request = request
+                .bind((format!("id{index}"), entry.id.clone()))
+                .bind((format!("entry{index}"), DbClipboardEntry::from(entry)));
+        }
+        request.await?.check()?;
+
+        if !keep_source {
+            self.delete_links_for_clips(vec![id.to_string()]).await?;
+            self.delete_collection_items_for_clips(vec![id.to_string()])
+                .await?;
+        }
+        self.sync_tags(&source.tags).await?;
+
+        Ok(entries)
+    }
+
     /// Delete all clip entries without any tags (except host tags) within a given time range.
     ///
     /// This function finds entries where:
@@ -1029,6 +2395,61 @@ impl ClipperIndexer {
         start_date: Option<chrono::DateTime<chrono::Utc>>,
         end_date: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Vec<String>> {
+        let where_clause = Self::cleanup_where_clause(start_date, end_date);
+
+        // First, get all entries that match the criteria to delete their files
+        let select_query = format!("SELECT * FROM {} WHERE {};", TABLE_NAME, where_clause);
+
+        let mut response = self.db.query(select_query).await?;
+        let entries: Vec<DbClipboardEntry> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        // Collect the IDs of entries to be deleted
+        let deleted_ids: Vec<String> = entries.iter().map(|e| e.id.id.to_string()).collect();
+
+        // Delete all matching entries from the database
+        let delete_query = format!("DELETE FROM {} WHERE {};", TABLE_NAME, where_clause);
+        self.db.query(delete_query).await?;
+        self.delete_links_for_clips(deleted_ids.clone()).await?;
+        self.delete_collection_items_for_clips(deleted_ids.clone())
+            .await?;
+
+        // Delete file attachments for all matching entries
+        for entry in &entries {
+            if let Some(ref file_key) = entry.file_attachment {
+                let _ = self.storage.delete_file(file_key).await;
+            }
+        }
+
+        Ok(deleted_ids)
+    }
+
+    /// The clip entries [`cleanup_entries`](Self::cleanup_entries) would
+    /// delete for the same time range, oldest first, without deleting them.
+    pub async fn find_cleanup_entries(
+        &self,
+        start_date: Option<chrono::DateTime<chrono::Utc>>,
+        end_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<ClipboardEntry>> {
+        let select_query = format!(
+            "SELECT * FROM {} WHERE {} ORDER BY created_at ASC;",
+            TABLE_NAME,
+            Self::cleanup_where_clause(start_date, end_date)
+        );
+
+        let mut response = self.db.query(select_query).await?;
+        let entries: Vec<DbClipboardEntry> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+        Ok(entries.into_iter().map(ClipboardEntry::from).collect())
+    }
+
+    /// The condition for entries without meaningful tags in a time range
+    fn cleanup_where_clause(
+        start_date: Option<chrono::DateTime<chrono::Utc>>,
+        end_date: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> String {
         let mut where_clauses = Vec::new();
 
         // Entries with no tags OR all tags start with "$host:"
@@ -1045,31 +2466,109 @@ impl ClipperIndexer {
             where_clauses.push(format!("created_at <= <datetime>'{}'", end.to_rfc3339()));
         }
 
-        let where_clause = where_clauses.join(" AND ");
+        where_clauses.join(" AND ")
+    }
 
-        // First, get all entries that match the criteria to delete their files
-        let select_query = format!("SELECT * FROM {} WHERE {};", TABLE_NAME, where_clause);
+    /// Delete the clip entries created up to `end_date` that `expired` picks,
+    /// given each entry's tags and creation time.
+    ///
+    /// Unlike [`cleanup_entries`](Self::cleanup_entries), this deletes tagged
+    /// entries too, so retention can depend on the tags.
+    ///
+    /// # Arguments
+    /// * `end_date` - Only entries created up to this time (inclusive) are considered
+    /// * `expired` - Called with the tags and creation time of each of them
+    ///
+    /// # Returns
+    /// A vector of IDs of the deleted entries
+    pub async fn cleanup_entries_where<F>(
+        &self,
+        end_date: chrono::DateTime<chrono::Utc>,
+        expired: F,
+    ) -> Result<Vec<String>>
+    where
+        F: Fn(&[String], chrono::DateTime<chrono::Utc>) -> bool,
+    {
+        let entries = self.find_entries_where(end_date, expired).await?;
+        self.delete_entries(&entries).await
+    }
+
+    /// Delete the given clip entries with their attachments, links and
+    /// collection memberships, e.g. ones found for cleanup and archived first.
+    ///
+    /// # Returns
+    /// A vector of IDs of the deleted entries
+    pub async fn delete_entries(&self, entries: &[ClipboardEntry]) -> Result<Vec<String>> {
+        let mut deleted_ids = Vec::with_capacity(entries.len());
+        for entry in entries {
+            self.db
+                .query("DELETE type::thing($table, $id);")
+                .bind(("table", TABLE_NAME))
+                .bind(("id", entry.id.clone()))
+                .await?;
+            if let Some(ref file_key) = entry.file_attachment {
+                let _ = self.storage.delete_file(file_key).await;
+            }
+            deleted_ids.push(entry.id.clone());
+        }
+
+        self.delete_links_for_clips(deleted_ids.clone()).await?;
+        self.delete_collection_items_for_clips(deleted_ids.clone())
+            .await?;
+
+        Ok(deleted_ids)
+    }
 
+    /// The clip entries [`cleanup_entries_where`](Self::cleanup_entries_where)
+    /// would delete for the same arguments, oldest first, without deleting
+    /// them.
+    pub async fn find_entries_where<F>(
+        &self,
+        end_date: chrono::DateTime<chrono::Utc>,
+        expired: F,
+    ) -> Result<Vec<ClipboardEntry>>
+    where
+        F: Fn(&[String], chrono::DateTime<chrono::Utc>) -> bool,
+    {
+        let select_query = format!(
+            "SELECT * FROM {} WHERE created_at <= <datetime>'{}' ORDER BY created_at ASC;",
+            TABLE_NAME,
+            end_date.to_rfc3339()
+        );
         let mut response = self.db.query(select_query).await?;
         let entries: Vec<DbClipboardEntry> = response
             .take(0)
             .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        // Collect the IDs of entries to be deleted
-        let deleted_ids: Vec<String> = entries.iter().map(|e| e.id.id.to_string()).collect();
-
-        // Delete all matching entries from the database
-        let delete_query = format!("DELETE FROM {} WHERE {};", TABLE_NAME, where_clause);
-        self.db.query(delete_query).await?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| expired(&entry.tags, *entry.created_at))
+            .map(ClipboardEntry::from)
+            .collect())
+    }
 
-        // Delete file attachments for all matching entries
-        for entry in &entries {
-            if let Some(ref file_key) = entry.file_attachment {
-                let _ = self.storage.delete_file(file_key).await;
-            }
-        }
+    /// The clip entries tagged `tag` created at or before `end_date`, oldest
+    /// first, e.g. sensitive clips due to expire.
+    pub async fn find_tagged_entries(
+        &self,
+        tag: &str,
+        end_date: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ClipboardEntry>> {
+        let select_query = format!(
+            "SELECT * FROM {} WHERE $tag IN tags AND created_at <= $end_date ORDER BY created_at ASC;",
+            TABLE_NAME
+        );
+        let mut response = self
+            .db
+            .query(select_query)
+            .bind(("tag", tag.to_string()))
+            .bind(("end_date", surrealdb::sql::Datetime::from(end_date)))
+            .await?;
+        let entries: Vec<DbClipboardEntry> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        Ok(deleted_ids)
+        Ok(entries.into_iter().map(ClipboardEntry::from).collect())
     }
 
     // ==================== Short URL Functions ====================
@@ -1290,8 +2789,29 @@ impl ClipperIndexer {
     /// The number of expired short URLs that were deleted
     pub async fn cleanup_expired_short_urls(&self) -> Result<usize> {
         let now = chrono::Utc::now();
+        let count = self.count_short_urls_expired_at(now).await?;
+
+        // Delete expired short URLs
+        let delete_query = format!(
+            "DELETE FROM {} WHERE expires_at != NONE AND expires_at < <datetime>'{}';",
+            SHORT_URL_TABLE,
+            now.to_rfc3339()
+        );
+        self.db.query(delete_query).await?;
+
+        Ok(count)
+    }
 
-        // Count expired short URLs
+    /// Count the short URLs that have expired, which
+    /// [`cleanup_expired_short_urls`](Self::cleanup_expired_short_urls) would delete
+    pub async fn count_expired_short_urls(&self) -> Result<usize> {
+        self.count_short_urls_expired_at(chrono::Utc::now()).await
+    }
+
+    async fn count_short_urls_expired_at(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize> {
         let count_query = format!(
             "SELECT count() FROM {} WHERE expires_at != NONE AND expires_at < <datetime>'{}' GROUP ALL;",
             SHORT_URL_TABLE,
@@ -1304,176 +2824,1569 @@ impl ClipperIndexer {
             count: i64,
         }
 
-        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
-        let count = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
+        Ok(count_results.first().map(|c| c.count as usize).unwrap_or(0))
+    }
+
+    // ==================== Clip Link Functions ====================
+
+    /// Link one clip to another.
+    ///
+    /// Linking the same clips with the same relation again returns the
+    /// existing link.
+    ///
+    /// # Arguments
+    /// * `source_id` - The clip the link starts from
+    /// * `target_id` - The clip the link points to
+    /// * `relation` - Kind of relationship, e.g. "derived_from" or "reply_to"
+    ///
+    /// # Returns
+    /// The created or existing ClipLink
+    pub async fn add_link(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation: &str,
+    ) -> Result<ClipLink> {
+        let relation = relation.trim();
+        if relation.is_empty() || relation.chars().count() > MAX_RELATION_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Link relation must be 1 to {} characters",
+                MAX_RELATION_LENGTH
+            )));
+        }
+        if source_id == target_id {
+            return Err(IndexerError::InvalidInput(
+                "A clip cannot be linked to itself".to_string(),
+            ));
+        }
+
+        // Verify both clips exist
+        let _ = self.get_entry(source_id).await?;
+        let _ = self.get_entry(target_id).await?;
+
+        let query = format!(
+            "SELECT * FROM {} WHERE source_id = $source_id AND target_id = $target_id AND relation = $relation;",
+            LINKS_TABLE
+        );
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("source_id", source_id.to_string()))
+            .bind(("target_id", target_id.to_string()))
+            .bind(("relation", relation.to_string()))
+            .await?;
+        let existing: Vec<DbClipLink> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+        if let Some(db_link) = existing.into_iter().next() {
+            return Ok(db_link.into());
+        }
+
+        let link = ClipLink::new(
+            source_id.to_string(),
+            target_id.to_string(),
+            relation.to_string(),
+        );
+
+        let record_id = (LINKS_TABLE, link.id.as_str());
+        let _: Option<DbClipLink> = self
+            .db
+            .create(record_id)
+            .content(DbClipLink {
+                id: surrealdb::sql::Thing::from((LINKS_TABLE.to_string(), link.id.clone())),
+                source_id: link.source_id.clone(),
+                target_id: link.target_id.clone(),
+                relation: link.relation.clone(),
+                created_at: surrealdb::sql::Datetime::from(link.created_at),
+            })
+            .await?;
+
+        Ok(link)
+    }
+
+    /// Get all links from or to a clip, oldest first.
+    ///
+    /// # Arguments
+    /// * `clip_id` - The ID of the clip
+    pub async fn get_links_for_clip(&self, clip_id: &str) -> Result<Vec<ClipLink>> {
+        let query = format!(
+            "SELECT * FROM {} WHERE source_id = $clip_id OR target_id = $clip_id ORDER BY created_at ASC;",
+            LINKS_TABLE
+        );
+
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("clip_id", clip_id.to_string()))
+            .await?;
+        let results: Vec<DbClipLink> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        Ok(results.into_iter().map(ClipLink::from).collect())
+    }
+
+    /// Delete a link of a clip.
+    ///
+    /// # Arguments
+    /// * `clip_id` - The ID of a clip at either end of the link
+    /// * `link_id` - The ID of the link to delete
+    pub async fn delete_link(&self, clip_id: &str, link_id: &str) -> Result<()> {
+        let record_id = (LINKS_TABLE, link_id);
+        let db_link: Option<DbClipLink> = self.db.select(record_id).await?;
+
+        match db_link {
+            Some(db_link) if db_link.source_id == clip_id || db_link.target_id == clip_id => {}
+            _ => {
+                return Err(IndexerError::NotFound(format!(
+                    "Link with id {} not found for clip {}",
+                    link_id, clip_id
+                )));
+            }
+        }
+
+        let query = "DELETE type::thing($table, $id);";
+        self.db
+            .query(query)
+            .bind(("table", LINKS_TABLE))
+            .bind(("id", link_id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    /// Delete all links from or to the given clips
+    async fn delete_links_for_clips(&self, clip_ids: Vec<String>) -> Result<()> {
+        if clip_ids.is_empty() {
+            return Ok(());
+        }
+
+        let query = format!(
+            "DELETE FROM {} WHERE source_id IN $ids OR target_id IN $ids;",
+            LINKS_TABLE
+        );
+        self.db.query(query).bind(("ids", clip_ids)).await?;
+        Ok(())
+    }
+
+    // ==================== Collection Functions ====================
+
+    /// Trim a collection name and check its length
+    fn normalize_collection_name(name: &str) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_COLLECTION_NAME_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Collection name must be 1 to {} characters",
+                MAX_COLLECTION_NAME_LENGTH
+            )));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Fail with `InvalidInput` if another collection already uses `name`
+    async fn check_collection_name_available(
+        &self,
+        name: &str,
+        own_id: Option<&str>,
+    ) -> Result<()> {
+        let query = format!("SELECT * FROM {} WHERE name = $name;", COLLECTIONS_TABLE);
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("name", name.to_string()))
+            .await?;
+        let existing: Vec<DbCollection> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        if existing
+            .iter()
+            .any(|db_collection| Some(db_collection.id.id.to_string().as_str()) != own_id)
+        {
+            return Err(IndexerError::InvalidInput(format!(
+                "A collection named {} already exists",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Create an empty collection.
+    ///
+    /// # Arguments
+    /// * `name` - Unique name of the collection
+    /// * `description` - Optional description (empty string means none)
+    pub async fn create_collection(
+        &self,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<Collection> {
+        let name = Self::normalize_collection_name(name)?;
+        self.check_collection_name_available(&name, None).await?;
+
+        let description = description.filter(|d| !d.trim().is_empty());
+        let collection = Collection::new(name, description);
+
+        let record_id = (COLLECTIONS_TABLE, collection.id.as_str());
+        let _: Option<DbCollection> = self
+            .db
+            .create(record_id)
+            .content(DbCollection {
+                id: surrealdb::sql::Thing::from((
+                    COLLECTIONS_TABLE.to_string(),
+                    collection.id.clone(),
+                )),
+                name: collection.name.clone(),
+                description: collection.description.clone(),
+                created_at: surrealdb::sql::Datetime::from(collection.created_at),
+                updated_at: surrealdb::sql::Datetime::from(collection.updated_at),
+            })
+            .await?;
+
+        Ok(collection)
+    }
+
+    /// List all collections ordered by name.
+    pub async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let query = format!("SELECT * FROM {} ORDER BY name ASC;", COLLECTIONS_TABLE);
+        let mut response = self.db.query(query).await?;
+        let results: Vec<DbCollection> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        Ok(results.into_iter().map(Collection::from).collect())
+    }
+
+    pub async fn get_collection(&self, id: &str) -> Result<Collection> {
+        let record_id = (COLLECTIONS_TABLE, id);
+        let db_collection: Option<DbCollection> = self.db.select(record_id).await?;
+
+        db_collection
+            .map(Collection::from)
+            .ok_or_else(|| IndexerError::NotFound(format!("Collection with id {} not found", id)))
+    }
+
+    /// Rename a collection and/or change its description.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the collection
+    /// * `name` - If Some, the new unique name; if None, leaves the name unchanged
+    /// * `description` - If Some, replaces the description (empty string clears to None); if None, leaves it unchanged
+    pub async fn update_collection(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        description: Option<String>,
+    ) -> Result<Collection> {
+        let existing = self.get_collection(id).await?;
+
+        let name = match name {
+            Some(name) => {
+                let name = Self::normalize_collection_name(name)?;
+                self.check_collection_name_available(&name, Some(id))
+                    .await?;
+                name
+            }
+            None => existing.name,
+        };
+        let description = match description {
+            Some(description) if description.trim().is_empty() => None,
+            Some(description) => Some(description),
+            None => existing.description,
+        };
+
+        let query = "UPDATE type::thing($table, $id) SET name = $name, description = $description, updated_at = time::now();";
+        self.db
+            .query(query)
+            .bind(("table", COLLECTIONS_TABLE))
+            .bind(("id", id.to_string()))
+            .bind(("name", name))
+            .bind(("description", description))
+            .await?;
+
+        self.get_collection(id).await
+    }
+
+    /// Delete a collection. The clips in it are not deleted.
+    pub async fn delete_collection(&self, id: &str) -> Result<()> {
+        let _ = self.get_collection(id).await?;
+
+        let query = format!(
+            "DELETE FROM {} WHERE collection_id = $id; DELETE type::thing($table, $id);",
+            COLLECTION_ITEMS_TABLE
+        );
+        self.db
+            .query(query)
+            .bind(("table", COLLECTIONS_TABLE))
+            .bind(("id", id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    /// Items of a collection in their manual order
+    async fn collection_items(&self, collection_id: &str) -> Result<Vec<DbCollectionItem>> {
+        let query = format!(
+            "SELECT * FROM {} WHERE collection_id = $collection_id ORDER BY position ASC, added_at ASC;",
+            COLLECTION_ITEMS_TABLE
+        );
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("collection_id", collection_id.to_string()))
+            .await?;
+        response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))
+    }
+
+    /// Store the order of `items` as their positions, then mark the
+    /// collection as updated
+    async fn save_collection_order(
+        &self,
+        collection_id: &str,
+        items: &[DbCollectionItem],
+    ) -> Result<()> {
+        for (position, item) in items.iter().enumerate() {
+            let position = position as i64;
+            if item.position == position {
+                continue;
+            }
+
+            let query = "UPDATE type::thing($table, $id) SET position = $position;";
+            self.db
+                .query(query)
+                .bind(("table", COLLECTION_ITEMS_TABLE))
+                .bind(("id", item.id.id.to_string()))
+                .bind(("position", position))
+                .await?;
+        }
+
+        self.touch_collection(collection_id).await
+    }
+
+    /// Set the updated_at of a collection to now
+    async fn touch_collection(&self, collection_id: &str) -> Result<()> {
+        let query = "UPDATE type::thing($table, $id) SET updated_at = time::now();";
+        self.db
+            .query(query)
+            .bind(("table", COLLECTIONS_TABLE))
+            .bind(("id", collection_id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    /// Add a clip to a collection.
+    ///
+    /// If the clip is already in the collection it is moved to `position`,
+    /// or left where it is if no position is given.
+    ///
+    /// # Arguments
+    /// * `collection_id` - The ID of the collection
+    /// * `clip_id` - The ID of the clip to add
+    /// * `position` - Zero-based index to insert the clip at; None or an index past the end appends it
+    pub async fn add_to_collection(
+        &self,
+        collection_id: &str,
+        clip_id: &str,
+        position: Option<usize>,
+    ) -> Result<()> {
+        let _ = self.get_collection(collection_id).await?;
+        let _ = self.get_entry(clip_id).await?;
+
+        let mut items = self.collection_items(collection_id).await?;
+        let item = match items.iter().position(|item| item.clip_id == clip_id) {
+            Some(_) if position.is_none() => return Ok(()),
+            Some(index) => items.remove(index),
+            None => {
+                let item_id = uuid::Uuid::new_v4().simple().to_string();
+                let item = DbCollectionItem {
+                    id: surrealdb::sql::Thing::from((
+                        COLLECTION_ITEMS_TABLE.to_string(),
+                        item_id.clone(),
+                    )),
+                    collection_id: collection_id.to_string(),
+                    clip_id: clip_id.to_string(),
+                    // Placeholder, stored by save_collection_order
+                    position: -1,
+                    added_at: surrealdb::sql::Datetime::from(chrono::Utc::now()),
+                };
+                let _: Option<DbCollectionItem> = self
+                    .db
+                    .create((COLLECTION_ITEMS_TABLE, item_id.as_str()))
+                    .content(item.clone())
+                    .await?;
+                item
+            }
+        };
+
+        let index = position.unwrap_or(items.len()).min(items.len());
+        items.insert(index, item);
+        self.save_collection_order(collection_id, &items).await
+    }
+
+    /// Remove a clip from a collection. The clip itself is not deleted.
+    pub async fn remove_from_collection(&self, collection_id: &str, clip_id: &str) -> Result<()> {
+        let items = self.collection_items(collection_id).await?;
+        let Some(item) = items.iter().find(|item| item.clip_id == clip_id) else {
+            return Err(IndexerError::NotFound(format!(
+                "Clip {} is not in collection {}",
+                clip_id, collection_id
+            )));
+        };
+
+        let query = "DELETE type::thing($table, $id);";
+        self.db
+            .query(query)
+            .bind(("table", COLLECTION_ITEMS_TABLE))
+            .bind(("id", item.id.id.to_string()))
+            .await?;
+
+        self.touch_collection(collection_id).await
+    }
+
+    /// Set the manual order of a collection.
+    ///
+    /// # Arguments
+    /// * `collection_id` - The ID of the collection
+    /// * `clip_ids` - Every clip of the collection exactly once, in the new order
+    pub async fn reorder_collection(&self, collection_id: &str, clip_ids: &[String]) -> Result<()> {
+        let _ = self.get_collection(collection_id).await?;
+
+        let mut items_by_clip: HashMap<String, DbCollectionItem> = self
+            .collection_items(collection_id)
+            .await?
+            .into_iter()
+            .map(|item| (item.clip_id.clone(), item))
+            .collect();
+
+        let invalid = || {
+            IndexerError::InvalidInput(
+                "The new order must list every clip of the collection exactly once".to_string(),
+            )
+        };
+        if clip_ids.len() != items_by_clip.len() {
+            return Err(invalid());
+        }
+        let items = clip_ids
+            .iter()
+            .map(|clip_id| items_by_clip.remove(clip_id).ok_or_else(invalid))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.save_collection_order(collection_id, &items).await
+    }
+
+    /// List the clips of a collection in their manual order.
+    ///
+    /// # Arguments
+    /// * `collection_id` - The ID of the collection
+    /// * `paging` - Pagination parameters
+    pub async fn list_collection_entries(
+        &self,
+        collection_id: &str,
+        paging: PagingParams,
+    ) -> Result<PagedResult<ClipboardEntry>> {
+        let _ = self.get_collection(collection_id).await?;
+
+        let items = self.collection_items(collection_id).await?;
+        let page_ids: Vec<String> = items
+            .iter()
+            .skip(paging.offset())
+            .take(paging.page_size)
+            .map(|item| item.clip_id.clone())
+            .collect();
+        let entries = self.get_entries(&page_ids).await?;
+
+        Ok(PagedResult::new(
+            entries,
+            items.len(),
+            paging.page,
+            paging.page_size,
+        ))
+    }
+
+    /// Get the collections containing a clip, ordered by name.
+    pub async fn get_collections_for_clip(&self, clip_id: &str) -> Result<Vec<Collection>> {
+        let query = format!(
+            "SELECT * FROM {} WHERE clip_id = $clip_id;",
+            COLLECTION_ITEMS_TABLE
+        );
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("clip_id", clip_id.to_string()))
+            .await?;
+        let items: Vec<DbCollectionItem> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+        let collection_ids: HashSet<String> =
+            items.into_iter().map(|item| item.collection_id).collect();
+
+        let mut collections = self.list_collections().await?;
+        collections.retain(|collection| collection_ids.contains(&collection.id));
+        Ok(collections)
+    }
+
+    /// Remove the given clips from all collections
+    async fn delete_collection_items_for_clips(&self, clip_ids: Vec<String>) -> Result<()> {
+        if clip_ids.is_empty() {
+            return Ok(());
+        }
+
+        let query = format!(
+            "DELETE FROM {} WHERE clip_id IN $ids;",
+            COLLECTION_ITEMS_TABLE
+        );
+        self.db.query(query).bind(("ids", clip_ids)).await?;
+        Ok(())
+    }
+
+    // ==================== Template Functions ====================
+
+    /// Trim a template name and check its length
+    fn normalize_template_name(name: &str) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_TEMPLATE_NAME_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Template name must be 1 to {} characters",
+                MAX_TEMPLATE_NAME_LENGTH
+            )));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Fail with `InvalidInput` if the template content is blank
+    fn check_template_content(content: &str) -> Result<()> {
+        if content.trim().is_empty() {
+            return Err(IndexerError::InvalidInput(
+                "Template content cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fail with `InvalidInput` if another template already uses `name`
+    async fn check_template_name_available(&self, name: &str, own_id: Option<&str>) -> Result<()> {
+        let query = format!("SELECT * FROM {} WHERE name = $name;", TEMPLATES_TABLE);
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("name", name.to_string()))
+            .await?;
+        let existing: Vec<DbTemplate> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        if existing
+            .iter()
+            .any(|db_template| Some(db_template.id.id.to_string().as_str()) != own_id)
+        {
+            return Err(IndexerError::InvalidInput(format!(
+                "A template named {} already exists",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Create a template.
+    ///
+    /// # Arguments
+    /// * `name` - Unique name of the template
+    /// * `description` - Optional description (empty string means none)
+    /// * `content` - Text with `{{name}}` or `{{name|default}}` placeholders
+    /// * `tags` - Tags given to the clips created from the template
+    pub async fn create_template(
+        &self,
+        name: &str,
+        description: Option<String>,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Template> {
+        let name = Self::normalize_template_name(name)?;
+        Self::check_template_content(&content)?;
+        self.check_template_name_available(&name, None).await?;
+
+        let description = description.filter(|d| !d.trim().is_empty());
+        let template = Template::new(name, description, content, tags);
+
+        let record_id = (TEMPLATES_TABLE, template.id.as_str());
+        let _: Option<DbTemplate> = self
+            .db
+            .create(record_id)
+            .content(DbTemplate {
+                id: surrealdb::sql::Thing::from((TEMPLATES_TABLE.to_string(), template.id.clone())),
+                name: template.name.clone(),
+                description: template.description.clone(),
+                content: template.content.clone(),
+                tags: template.tags.clone(),
+                created_at: surrealdb::sql::Datetime::from(template.created_at),
+                updated_at: surrealdb::sql::Datetime::from(template.updated_at),
+            })
+            .await?;
+
+        Ok(template)
+    }
+
+    /// List all templates ordered by name.
+    pub async fn list_templates(&self) -> Result<Vec<Template>> {
+        let query = format!("SELECT * FROM {} ORDER BY name ASC;", TEMPLATES_TABLE);
+        let mut response = self.db.query(query).await?;
+        let results: Vec<DbTemplate> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        Ok(results.into_iter().map(Template::from).collect())
+    }
+
+    pub async fn get_template(&self, id: &str) -> Result<Template> {
+        let record_id = (TEMPLATES_TABLE, id);
+        let db_template: Option<DbTemplate> = self.db.select(record_id).await?;
+
+        db_template
+            .map(Template::from)
+            .ok_or_else(|| IndexerError::NotFound(format!("Template with id {} not found", id)))
+    }
+
+    /// Change any of a template's fields.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the template
+    /// * `name` - If Some, the new unique name; if None, leaves the name unchanged
+    /// * `description` - If Some, replaces the description (empty string clears to None); if None, leaves it unchanged
+    /// * `content` - If Some, replaces the content; if None, leaves it unchanged
+    /// * `tags` - If Some, replaces the tags; if None, leaves them unchanged
+    pub async fn update_template(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        description: Option<String>,
+        content: Option<String>,
+        tags: Option<Vec<String>>,
+    ) -> Result<Template> {
+        let existing = self.get_template(id).await?;
+
+        let name = match name {
+            Some(name) => {
+                let name = Self::normalize_template_name(name)?;
+                self.check_template_name_available(&name, Some(id)).await?;
+                name
+            }
+            None => existing.name,
+        };
+        let description = match description {
+            Some(description) if description.trim().is_empty() => None,
+            Some(description) => Some(description),
+            None => existing.description,
+        };
+        let content = match content {
+            Some(content) => {
+                Self::check_template_content(&content)?;
+                content
+            }
+            None => existing.content,
+        };
+        let tags = tags.unwrap_or(existing.tags);
+
+        let query = "UPDATE type::thing($table, $id) SET name = $name, description = $description, content = $content, tags = $tags, updated_at = time::now();";
+        self.db
+            .query(query)
+            .bind(("table", TEMPLATES_TABLE))
+            .bind(("id", id.to_string()))
+            .bind(("name", name))
+            .bind(("description", description))
+            .bind(("content", content))
+            .bind(("tags", tags))
+            .await?;
+
+        self.get_template(id).await
+    }
+
+    /// Delete a template. Clips created from it are not deleted.
+    pub async fn delete_template(&self, id: &str) -> Result<()> {
+        let _ = self.get_template(id).await?;
+
+        let query = "DELETE type::thing($table, $id);";
+        self.db
+            .query(query)
+            .bind(("table", TEMPLATES_TABLE))
+            .bind(("id", id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    // ==================== Tags Functions ====================
+
+    /// List all tags with optional pagination.
+    ///
+    /// # Arguments
+    /// * `paging` - Pagination parameters
+    /// * `visibility` - Only list tags of clips with these visibility levels;
+    ///   all tags if `None`
+    ///
+    /// # Returns
+    /// A paged result containing all tags ordered by creation date
+    pub async fn list_tags(
+        &self,
+        paging: PagingParams,
+        visibility: Option<&[Visibility]>,
+    ) -> Result<PagedResult<Tag>> {
+        let visible_tags = self.visible_tags(visibility).await?;
+        let where_clause = tags_condition(&visible_tags);
+
+        // Get total count
+        let count_query = format!(
+            "SELECT count() FROM {} WHERE {} GROUP ALL;",
+            TAGS_TABLE, where_clause
+        );
+        let mut count_response = self
+            .db
+            .query(count_query)
+            .bind(("visible_tags", visible_tags.clone()))
+            .await?;
+
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
+
+        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
+        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+
+        // Get paginated results - order by created_at instead of text to avoid
+        // conflicts with the FTS SEARCH index on the text field
+        let query = format!(
+            "SELECT * FROM {} WHERE {} ORDER BY created_at DESC LIMIT {} START {};",
+            TAGS_TABLE,
+            where_clause,
+            paging.page_size,
+            paging.offset()
+        );
+
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("visible_tags", visible_tags))
+            .await?;
+        let db_tags: Vec<DbTag> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let items: Vec<Tag> = db_tags
+            .into_iter()
+            .map(|db_tag| Tag {
+                id: db_tag.id.id.to_string(),
+                text: db_tag.text,
+                created_at: *db_tag.created_at,
+            })
+            .collect();
+
+        Ok(PagedResult::new(
+            items,
+            total,
+            paging.page,
+            paging.page_size,
+        ))
+    }
+
+    /// Search tags using full-text search.
+    ///
+    /// # Arguments
+    /// * `search_query` - The search string, with optional query operators
+    /// * `paging` - Pagination parameters
+    /// * `visibility` - Only search tags of clips with these visibility
+    ///   levels; all tags if `None`
+    ///
+    /// # Returns
+    /// A paged result containing matching tags
+    pub async fn search_tags(
+        &self,
+        search_query: &str,
+        paging: PagingParams,
+        visibility: Option<&[Visibility]>,
+    ) -> Result<PagedResult<Tag>> {
+        // Return all tags if search query is empty
+        if search_query.trim().is_empty() {
+            return self.list_tags(paging, visibility).await;
+        }
+
+        let visible_tags = self.visible_tags(visibility).await?;
+        let where_clause = format!("text @@ $query AND {}", tags_condition(&visible_tags));
+        let search_query_owned = search_query.to_string();
+
+        // Get total count
+        let count_query = format!(
+            "SELECT count() FROM {} WHERE {} GROUP ALL;",
+            TAGS_TABLE, where_clause
+        );
+        let mut count_response = self
+            .db
+            .query(count_query)
+            .bind(("query", search_query_owned.clone()))
+            .bind(("visible_tags", visible_tags.clone()))
+            .await?;
+
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
+
+        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
+        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+
+        // Get paginated results
+        let query = format!(
+            "SELECT * FROM {} WHERE {} ORDER BY text ASC LIMIT $limit START $offset;",
+            TAGS_TABLE, where_clause
+        );
+
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("query", search_query_owned))
+            .bind(("visible_tags", visible_tags))
+            .bind(("limit", paging.page_size as i64))
+            .bind(("offset", paging.offset() as i64))
+            .await?;
+        let db_tags: Vec<DbTag> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let items: Vec<Tag> = db_tags
+            .into_iter()
+            .map(|db_tag| Tag {
+                id: db_tag.id.id.to_string(),
+                text: db_tag.text,
+                created_at: *db_tag.created_at,
+            })
+            .collect();
+
+        Ok(PagedResult::new(
+            items,
+            total,
+            paging.page,
+            paging.page_size,
+        ))
+    }
+
+    /// Get a tag by its text.
+    ///
+    /// # Arguments
+    /// * `text` - The tag text
+    ///
+    /// # Returns
+    /// The Tag if found
+    pub async fn get_tag_by_text(&self, text: &str) -> Result<Tag> {
+        let query = format!("SELECT * FROM {} WHERE text = $text;", TAGS_TABLE);
+
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("text", text.to_string()))
+            .await?;
+        let results: Vec<DbTag> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let db_tag = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| IndexerError::NotFound(format!("Tag '{}' not found", text)))?;
+
+        Ok(Tag {
+            id: db_tag.id.id.to_string(),
+            text: db_tag.text,
+            created_at: *db_tag.created_at,
+        })
+    }
+
+    /// Rename a tag on all clip entries that use it.
+    ///
+    /// If a clip already has the target tag, the old tag is simply removed so
+    /// the clip does not end up with duplicate tags.
+    ///
+    /// # Arguments
+    /// * `from` - The current tag text
+    /// * `to` - The new tag text
+    ///
+    /// # Returns
+    /// The IDs of the clip entries that were updated
+    pub async fn rename_tag(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        if from.trim().is_empty() || to.trim().is_empty() {
+            return Err(IndexerError::InvalidInput(
+                "Tag text cannot be empty".to_string(),
+            ));
+        }
+
+        // Make sure the source tag exists
+        self.get_tag_by_text(from).await?;
+
+        if from == to {
+            return Ok(Vec::new());
+        }
+
+        let select_query = format!("SELECT * FROM {} WHERE tags CONTAINS $from;", TABLE_NAME);
+        let mut response = self
+            .db
+            .query(select_query)
+            .bind(("from", from.to_string()))
+            .await?;
+        let entries: Vec<DbClipboardEntry> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let mut updated_ids = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut new_tags: Vec<String> = Vec::with_capacity(entry.tags.len());
+            for tag in entry.tags {
+                let tag = if tag == from { to.to_string() } else { tag };
+                if !new_tags.contains(&tag) {
+                    new_tags.push(tag);
+                }
+            }
+
+            let id = entry.id.id.to_string();
+            self.db
+                .query(
+                    "UPDATE type::thing($table, $id) SET tags = $tags, updated_at = time::now();",
+                )
+                .bind(("table", TABLE_NAME))
+                .bind(("id", id.clone()))
+                .bind(("tags", new_tags))
+                .await?;
+            updated_ids.push(id);
+        }
+
+        // Replace the tag record itself
+        self.db
+            .query("DELETE type::thing($table, $id);")
+            .bind(("table", TAGS_TABLE))
+            .bind(("id", Self::tag_text_to_id(from)))
+            .await?;
+        self.sync_tags(&[to.to_string()]).await?;
+
+        Ok(updated_ids)
+    }
+
+    /// Count the clip entries using each tag.
+    ///
+    /// # Arguments
+    /// * `visibility` - Only count clips with these visibility levels; all
+    ///   clips if `None`
+    ///
+    /// # Returns
+    /// Tag usage counts ordered by count (descending), then by tag text
+    pub async fn tag_stats(&self, visibility: Option<&[Visibility]>) -> Result<Vec<TagStats>> {
+        let all_tags = self.clip_tags(visibility).await?;
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for tags in all_tags {
+            for tag in tags {
+                *counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+
+        let mut stats: Vec<TagStats> = counts
+            .into_iter()
+            .map(|(text, count)| TagStats { text, count })
+            .collect();
+        stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+
+        Ok(stats)
+    }
+
+    /// The tags of each clip with one of the visibility levels, or of every
+    /// clip if `None`
+    async fn clip_tags(&self, visibility: Option<&[Visibility]>) -> Result<Vec<Vec<String>>> {
+        let (visibility_condition, bindings) = visibility_condition(visibility);
+        let query = format!(
+            "SELECT VALUE tags FROM {} WHERE {};",
+            TABLE_NAME, visibility_condition
+        );
+        let mut query_builder = self.db.query(query);
+        for (name, value) in bindings {
+            query_builder = query_builder.bind((name, value));
+        }
+        let mut response = query_builder.await?;
+        response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))
+    }
+
+    /// The tags used by clips with one of the visibility levels, `None` if
+    /// every tag is visible
+    async fn visible_tags(&self, visibility: Option<&[Visibility]>) -> Result<Option<Vec<String>>> {
+        if visibility.is_none() {
+            return Ok(None);
+        }
+        let tags: BTreeSet<String> = self
+            .clip_tags(visibility)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok(Some(tags.into_iter().collect()))
+    }
+
+    /// Count the clips created on each day from `start_date` to today, and
+    /// the tags they use most.
+    ///
+    /// # Arguments
+    /// * `start_date` - The first day counted
+    /// * `offset` - Time zone the days are counted in
+    /// * `top_tags` - Maximum number of tags to return
+    /// * `visibility` - Only count clips with these visibility levels; all
+    ///   clips if `None`
+    pub async fn activity_stats(
+        &self,
+        start_date: chrono::NaiveDate,
+        offset: chrono::FixedOffset,
+        top_tags: usize,
+        visibility: Option<&[Visibility]>,
+    ) -> Result<ActivityStats> {
+        let start = start_date
+            .and_time(chrono::NaiveTime::MIN)
+            .and_local_timezone(offset)
+            .single()
+            .map(|start| start.with_timezone(&chrono::Utc))
+            .ok_or_else(|| IndexerError::InvalidInput("Invalid start date".to_string()))?;
+        let today = chrono::Utc::now().with_timezone(&offset).date_naive();
+
+        let (visibility_condition, bindings) = visibility_condition(visibility);
+        let query = format!(
+            "SELECT created_at, tags FROM {} WHERE created_at >= <datetime>$start AND {};",
+            TABLE_NAME, visibility_condition
+        );
+        let mut query_builder = self.db.query(query).bind(("start", start.to_rfc3339()));
+        for (name, value) in bindings {
+            query_builder = query_builder.bind((name, value));
+        }
+        let mut response = query_builder.await?;
+
+        #[derive(Deserialize)]
+        struct ActivityRow {
+            created_at: surrealdb::sql::Datetime,
+            tags: Vec<String>,
+        }
+
+        let rows: Vec<ActivityRow> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let mut day_counts: BTreeMap<chrono::NaiveDate, usize> = start_date
+            .iter_days()
+            .take_while(|date| *date <= today)
+            .map(|date| (date, 0))
+            .collect();
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        for row in rows {
+            let date = row.created_at.with_timezone(&offset).date_naive();
+            *day_counts.entry(date).or_insert(0) += 1;
+            for tag in row.tags {
+                if !tag.starts_with(HOST_TAG_PREFIX) {
+                    *tag_counts.entry(tag).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut tags: Vec<TagStats> = tag_counts
+            .into_iter()
+            .map(|(text, count)| TagStats { text, count })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+        tags.truncate(top_tags);
+
+        Ok(ActivityStats {
+            days: day_counts
+                .into_iter()
+                .map(|(date, count)| DayActivity { date, count })
+                .collect(),
+            top_tags: tags,
+        })
+    }
+
+    /// Count the clips, attachments, tags, collections, short URLs and paired
+    /// devices in the index.
+    pub async fn library_stats(&self) -> Result<LibraryStats> {
+        let query = format!(
+            r#"
+            SELECT count() FROM {clips} GROUP ALL;
+            SELECT count() FROM {clips} WHERE type::is::string(file_attachment) GROUP ALL;
+            SELECT count() FROM {tags} GROUP ALL;
+            SELECT count() FROM {collections} GROUP ALL;
+            SELECT count() FROM {short_urls} GROUP ALL;
+            SELECT count() FROM {paired_devices} GROUP ALL;
+            "#,
+            clips = TABLE_NAME,
+            tags = TAGS_TABLE,
+            collections = COLLECTIONS_TABLE,
+            short_urls = SHORT_URL_TABLE,
+            paired_devices = PAIRED_DEVICES_TABLE,
+        );
+        let mut response = self.db.query(query).await?;
+
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
+
+        let mut count = |index: usize| -> Result<usize> {
+            let results: Vec<CountResult> = response
+                .take(index)
+                .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+            // Empty tables have no group, so no count
+            Ok(results.first().map(|c| c.count as usize).unwrap_or(0))
+        };
+
+        Ok(LibraryStats {
+            clips: count(0)?,
+            attachments: count(1)?,
+            tags: count(2)?,
+            collections: count(3)?,
+            short_urls: count(4)?,
+            paired_devices: count(5)?,
+        })
+    }
+
+    /// Rebuild the full-text search indexes of clips and tags from the
+    /// stored records, for when search results seem to be missing clips.
+    pub async fn rebuild_search_indexes(&self) -> Result<()> {
+        let query = format!(
+            r#"
+            REBUILD INDEX IF EXISTS {search_index} ON TABLE {clips};
+            REBUILD INDEX IF EXISTS {tags_index} ON TABLE {tags};
+            "#,
+            search_index = SEARCH_INDEX_NAME,
+            clips = TABLE_NAME,
+            tags_index = TAGS_SEARCH_INDEX_NAME,
+            tags = TAGS_TABLE,
+        );
+        self.db.query(query).await?.check()?;
+
+        Ok(())
+    }
+
+    /// List the devices clips were created on.
+    ///
+    /// # Arguments
+    /// * `visibility` - Only count clips with these visibility levels; all
+    ///   clips if `None`
+    ///
+    /// # Returns
+    /// Devices ordered by when they were last seen, most recent first
+    pub async fn list_devices(&self, visibility: Option<&[Visibility]>) -> Result<Vec<Device>> {
+        #[derive(Deserialize)]
+        struct DeviceClip {
+            source_device: String,
+            created_at: surrealdb::sql::Datetime,
+        }
+
+        let (visibility_condition, bindings) = visibility_condition(visibility);
+        let query = format!(
+            "SELECT source_device, created_at FROM {} WHERE source_device != NONE AND {};",
+            TABLE_NAME, visibility_condition
+        );
+        let mut query_builder = self.db.query(query);
+        for (name, value) in bindings {
+            query_builder = query_builder.bind((name, value));
+        }
+        let mut response = query_builder.await?;
+        let clips: Vec<DeviceClip> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        let mut devices: HashMap<String, Device> = HashMap::new();
+        for clip in clips {
+            let created_at = *clip.created_at;
+            devices
+                .entry(clip.source_device.clone())
+                .and_modify(|device| {
+                    device.clip_count += 1;
+                    device.last_seen = device.last_seen.max(created_at);
+                })
+                .or_insert(Device {
+                    name: clip.source_device,
+                    clip_count: 1,
+                    last_seen: created_at,
+                });
+        }
+
+        let mut devices: Vec<Device> = devices.into_values().collect();
+        devices.sort_by(|a, b| {
+            b.last_seen
+                .cmp(&a.last_seen)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(devices)
+    }
+
+    // ==================== Paired Device Functions ====================
+
+    /// Register a paired device and issue its token.
+    ///
+    /// Only a hash of the token is stored, so the token can't be shown again
+    /// after it is returned here.
+    ///
+    /// # Arguments
+    /// * `name` - Name shown in the device list, e.g. the device's hostname
+    ///
+    /// # Returns
+    /// The new device and its token
+    pub async fn create_paired_device(&self, name: &str) -> Result<(PairedDevice, String)> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_DEVICE_NAME_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Device name must be 1 to {} characters",
+                MAX_DEVICE_NAME_LENGTH
+            )));
+        }
+
+        let token = generate_device_token();
+        let device = PairedDevice {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            name: name.to_string(),
+            created_at: chrono::Utc::now(),
+            last_used_at: None,
+        };
 
-        // Delete expired short URLs
-        let delete_query = format!(
-            "DELETE FROM {} WHERE expires_at != NONE AND expires_at < <datetime>'{}';",
-            SHORT_URL_TABLE,
-            now.to_rfc3339()
-        );
-        self.db.query(delete_query).await?;
+        let record_id = (PAIRED_DEVICES_TABLE, device.id.as_str());
+        let _: Option<DbPairedDevice> = self
+            .db
+            .create(record_id)
+            .content(DbPairedDevice {
+                id: surrealdb::sql::Thing::from((
+                    PAIRED_DEVICES_TABLE.to_string(),
+                    device.id.clone(),
+                )),
+                name: device.name.clone(),
+                token_hash: hash_device_token(&token),
+                created_at: surrealdb::sql::Datetime::from(device.created_at),
+                last_used_at: None,
+            })
+            .await?;
 
-        Ok(count)
+        Ok((device, token))
     }
 
-    // ==================== Tags Functions ====================
-
-    /// List all tags with optional pagination.
+    /// Find the paired device a token was issued to.
     ///
-    /// # Arguments
-    /// * `paging` - Pagination parameters
+    /// Also records when the device was last used, at most once a minute so
+    /// that busy clients don't write on every request.
     ///
     /// # Returns
-    /// A paged result containing all tags ordered by creation date
-    pub async fn list_tags(&self, paging: PagingParams) -> Result<PagedResult<Tag>> {
-        // Get total count
-        let count_query = format!("SELECT count() FROM {} GROUP ALL;", TAGS_TABLE);
-        let mut count_response = self.db.query(count_query).await?;
+    /// The device, or None if the token doesn't belong to a paired device
+    pub async fn authenticate_paired_device(&self, token: &str) -> Result<Option<PairedDevice>> {
+        let query = format!(
+            "SELECT * FROM {} WHERE token_hash = $token_hash;",
+            PAIRED_DEVICES_TABLE
+        );
+        let mut response = self
+            .db
+            .query(query)
+            .bind(("token_hash", hash_device_token(token)))
+            .await?;
+        let results: Vec<DbPairedDevice> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        #[derive(Deserialize)]
-        struct CountResult {
-            count: i64,
+        let Some(mut device) = results.into_iter().next().map(PairedDevice::from) else {
+            return Ok(None);
+        };
+
+        let now = chrono::Utc::now();
+        let stale = device.last_used_at.is_none_or(|last_used| {
+            now - last_used >= chrono::Duration::seconds(DEVICE_LAST_USED_INTERVAL_SECS)
+        });
+        if stale {
+            let query = "UPDATE type::thing($table, $id) SET last_used_at = $now;";
+            self.db
+                .query(query)
+                .bind(("table", PAIRED_DEVICES_TABLE))
+                .bind(("id", device.id.clone()))
+                .bind(("now", surrealdb::sql::Datetime::from(now)))
+                .await?;
+            device.last_used_at = Some(now);
         }
 
-        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
-        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+        Ok(Some(device))
+    }
 
-        // Get paginated results - order by created_at instead of text to avoid
-        // conflicts with the FTS SEARCH index on the text field
+    /// List paired devices, most recently paired first.
+    pub async fn list_paired_devices(&self) -> Result<Vec<PairedDevice>> {
         let query = format!(
-            "SELECT * FROM {} ORDER BY created_at DESC LIMIT {} START {};",
-            TAGS_TABLE,
-            paging.page_size,
-            paging.offset()
+            "SELECT * FROM {} ORDER BY created_at DESC;",
+            PAIRED_DEVICES_TABLE
         );
-
         let mut response = self.db.query(query).await?;
-        let db_tags: Vec<DbTag> = response
+        let results: Vec<DbPairedDevice> = response
             .take(0)
             .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        let items: Vec<Tag> = db_tags
-            .into_iter()
-            .map(|db_tag| Tag {
-                id: db_tag.id.id.to_string(),
-                text: db_tag.text,
-                created_at: *db_tag.created_at,
-            })
-            .collect();
-
-        Ok(PagedResult::new(
-            items,
-            total,
-            paging.page,
-            paging.page_size,
-        ))
+        Ok(results.into_iter().map(PairedDevice::from).collect())
     }
 
-    /// Search tags using full-text search.
+    /// Revoke a paired device. Its token stops working immediately.
     ///
     /// # Arguments
-    /// * `search_query` - The search query string
-    /// * `paging` - Pagination parameters
+    /// * `id` - The ID of the paired device
+    pub async fn revoke_paired_device(&self, id: &str) -> Result<()> {
+        let record_id = (PAIRED_DEVICES_TABLE, id);
+        let existing: Option<DbPairedDevice> = self.db.select(record_id).await?;
+        if existing.is_none() {
+            return Err(IndexerError::NotFound(format!(
+                "Paired device with id {} not found",
+                id
+            )));
+        }
+
+        let query = "DELETE type::thing($table, $id);";
+        self.db
+            .query(query)
+            .bind(("table", PAIRED_DEVICES_TABLE))
+            .bind(("id", id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    // ==================== Push Token Functions ====================
+
+    /// Register a mobile device's push token.
     ///
-    /// # Returns
-    /// A paged result containing matching tags
-    pub async fn search_tags(
+    /// Registering a token that is already known updates its platform and
+    /// device name instead of adding it again, so devices can simply register
+    /// on every launch.
+    ///
+    /// # Arguments
+    /// * `token` - Token issued to the device by the push service
+    /// * `platform` - Push service the token belongs to
+    /// * `device_name` - Optional name shown for the device
+    pub async fn register_push_token(
         &self,
-        search_query: &str,
-        paging: PagingParams,
-    ) -> Result<PagedResult<Tag>> {
-        // Return all tags if search query is empty
-        if search_query.trim().is_empty() {
-            return self.list_tags(paging).await;
+        token: &str,
+        platform: PushPlatform,
+        device_name: Option<&str>,
+    ) -> Result<PushToken> {
+        let token = token.trim();
+        if token.is_empty() || token.chars().count() > MAX_PUSH_TOKEN_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Push token must be 1 to {} characters",
+                MAX_PUSH_TOKEN_LENGTH
+            )));
+        }
+        let device_name = device_name.map(str::trim).filter(|name| !name.is_empty());
+        if device_name.is_some_and(|name| name.chars().count() > MAX_DEVICE_NAME_LENGTH) {
+            return Err(IndexerError::InvalidInput(format!(
+                "Device name must be at most {} characters",
+                MAX_DEVICE_NAME_LENGTH
+            )));
         }
 
-        let where_clause = "text @@ $query";
-        let search_query_owned = search_query.to_string();
-
-        // Get total count
-        let count_query = format!(
-            "SELECT count() FROM {} WHERE {} GROUP ALL;",
-            TAGS_TABLE, where_clause
+        // `$token` is reserved by SurrealDB
+        let query = format!(
+            "SELECT * FROM {} WHERE token = $push_token;",
+            PUSH_TOKENS_TABLE
         );
-        let mut count_response = self
+        let mut response = self
             .db
-            .query(count_query)
-            .bind(("query", search_query_owned.clone()))
+            .query(query)
+            .bind(("push_token", token.to_string()))
             .await?;
+        let existing: Vec<DbPushToken> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        #[derive(Deserialize)]
-        struct CountResult {
-            count: i64,
-        }
+        let now = chrono::Utc::now();
+        let (id, created_at) = match existing.into_iter().next() {
+            Some(existing) => (existing.id.id.to_string(), *existing.created_at),
+            None => (uuid::Uuid::new_v4().simple().to_string(), now),
+        };
+        let push_token = PushToken {
+            id,
+            token: token.to_string(),
+            platform,
+            device_name: device_name.map(str::to_string),
+            created_at,
+            updated_at: now,
+        };
 
-        let count_results: Vec<CountResult> = count_response.take(0).unwrap_or_default();
-        let total = count_results.first().map(|c| c.count as usize).unwrap_or(0);
+        let record_id = (PUSH_TOKENS_TABLE, push_token.id.as_str());
+        let _: Option<DbPushToken> = self
+            .db
+            .upsert(record_id)
+            .content(DbPushToken {
+                id: surrealdb::sql::Thing::from((
+                    PUSH_TOKENS_TABLE.to_string(),
+                    push_token.id.clone(),
+                )),
+                token: push_token.token.clone(),
+                platform,
+                device_name: push_token.device_name.clone(),
+                created_at: surrealdb::sql::Datetime::from(push_token.created_at),
+                updated_at: surrealdb::sql::Datetime::from(now),
+            })
+            .await?;
 
-        // Get paginated results
+        Ok(push_token)
+    }
+
+    /// List registered push tokens, most recently registered first.
+    pub async fn list_push_tokens(&self) -> Result<Vec<PushToken>> {
         let query = format!(
-            "SELECT * FROM {} WHERE {} ORDER BY text ASC LIMIT $limit START $offset;",
-            TAGS_TABLE, where_clause
+            "SELECT * FROM {} ORDER BY updated_at DESC;",
+            PUSH_TOKENS_TABLE
         );
+        let mut response = self.db.query(query).await?;
+        let results: Vec<DbPushToken> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        Ok(results.into_iter().map(PushToken::from).collect())
+    }
+
+    /// Remove a push token, e.g. when the app is signed out or the push
+    /// service reports the token as no longer valid.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the push token
+    pub async fn delete_push_token(&self, id: &str) -> Result<()> {
+        let record_id = (PUSH_TOKENS_TABLE, id);
+        let existing: Option<DbPushToken> = self.db.select(record_id).await?;
+        if existing.is_none() {
+            return Err(IndexerError::NotFound(format!(
+                "Push token with id {} not found",
+                id
+            )));
+        }
+
+        let query = "DELETE type::thing($table, $id);";
+        self.db
+            .query(query)
+            .bind(("table", PUSH_TOKENS_TABLE))
+            .bind(("id", id.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    // ==================== Web Push Subscription Functions ====================
+
+    /// Store a browser's Web Push subscription.
+    ///
+    /// Subscriptions are identified by their endpoint. Subscribing again with
+    /// the same endpoint replaces its keys, since browsers may rotate them.
+    ///
+    /// # Arguments
+    /// * `endpoint` - Push service URL from the browser's `PushSubscription`
+    /// * `p256dh` - The subscription's `p256dh` key, base64url encoded
+    /// * `auth` - The subscription's `auth` secret, base64url encoded
+    pub async fn add_web_push_subscription(
+        &self,
+        endpoint: &str,
+        p256dh: &str,
+        auth: &str,
+    ) -> Result<WebPushSubscription> {
+        let endpoint = endpoint.trim();
+        if !endpoint.starts_with("https://") || endpoint.len() > MAX_WEB_PUSH_ENDPOINT_LENGTH {
+            return Err(IndexerError::InvalidInput(format!(
+                "Web Push endpoint must be an https URL of at most {} characters",
+                MAX_WEB_PUSH_ENDPOINT_LENGTH
+            )));
+        }
+        for (name, key) in [("p256dh", p256dh), ("auth", auth)] {
+            if key.is_empty() || key.len() > MAX_WEB_PUSH_KEY_LENGTH {
+                return Err(IndexerError::InvalidInput(format!(
+                    "Web Push {} key must be 1 to {} characters",
+                    name, MAX_WEB_PUSH_KEY_LENGTH
+                )));
+            }
+        }
 
+        let query = format!(
+            "SELECT * FROM {} WHERE endpoint = $endpoint;",
+            WEB_PUSH_SUBSCRIPTIONS_TABLE
+        );
         let mut response = self
             .db
             .query(query)
-            .bind(("query", search_query_owned))
-            .bind(("limit", paging.page_size as i64))
-            .bind(("offset", paging.offset() as i64))
+            .bind(("endpoint", endpoint.to_string()))
             .await?;
-        let db_tags: Vec<DbTag> = response
+        let existing: Vec<DbWebPushSubscription> = response
             .take(0)
             .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        let items: Vec<Tag> = db_tags
-            .into_iter()
-            .map(|db_tag| Tag {
-                id: db_tag.id.id.to_string(),
-                text: db_tag.text,
-                created_at: *db_tag.created_at,
+        let (id, created_at) = match existing.into_iter().next() {
+            Some(existing) => (existing.id.id.to_string(), *existing.created_at),
+            None => (
+                uuid::Uuid::new_v4().simple().to_string(),
+                chrono::Utc::now(),
+            ),
+        };
+        let subscription = WebPushSubscription {
+            id,
+            endpoint: endpoint.to_string(),
+            p256dh: p256dh.to_string(),
+            auth: auth.to_string(),
+            created_at,
+        };
+
+        let record_id = (WEB_PUSH_SUBSCRIPTIONS_TABLE, subscription.id.as_str());
+        let _: Option<DbWebPushSubscription> = self
+            .db
+            .upsert(record_id)
+            .content(DbWebPushSubscription {
+                id: surrealdb::sql::Thing::from((
+                    WEB_PUSH_SUBSCRIPTIONS_TABLE.to_string(),
+                    subscription.id.clone(),
+                )),
+                endpoint: subscription.endpoint.clone(),
+                p256dh: subscription.p256dh.clone(),
+                auth: subscription.auth.clone(),
+                created_at: surrealdb::sql::Datetime::from(created_at),
             })
-            .collect();
+            .await?;
 
-        Ok(PagedResult::new(
-            items,
-            total,
-            paging.page,
-            paging.page_size,
-        ))
+        Ok(subscription)
     }
 
-    /// Get a tag by its text.
+    /// List all Web Push subscriptions, oldest first.
+    pub async fn list_web_push_subscriptions(&self) -> Result<Vec<WebPushSubscription>> {
+        let query = format!(
+            "SELECT * FROM {} ORDER BY created_at ASC;",
+            WEB_PUSH_SUBSCRIPTIONS_TABLE
+        );
+        let mut response = self.db.query(query).await?;
+        let results: Vec<DbWebPushSubscription> = response
+            .take(0)
+            .map_err(|e| IndexerError::Serialization(e.to_string()))?;
+
+        Ok(results.into_iter().map(WebPushSubscription::from).collect())
+    }
+
+    /// Remove a Web Push subscription, e.g. when the browser unsubscribes or
+    /// the push service reports that the subscription has expired.
     ///
     /// # Arguments
-    /// * `text` - The tag text
-    ///
-    /// # Returns
-    /// The Tag if found
-    pub async fn get_tag_by_text(&self, text: &str) -> Result<Tag> {
-        let query = format!("SELECT * FROM {} WHERE text = $text;", TAGS_TABLE);
-
+    /// * `endpoint` - The subscription's endpoint URL
+    pub async fn delete_web_push_subscription(&self, endpoint: &str) -> Result<()> {
+        let query = format!(
+            "DELETE FROM {} WHERE endpoint = $endpoint RETURN BEFORE;",
+            WEB_PUSH_SUBSCRIPTIONS_TABLE
+        );
         let mut response = self
             .db
             .query(query)
-            .bind(("text", text.to_string()))
+            .bind(("endpoint", endpoint.trim().to_string()))
             .await?;
-        let results: Vec<DbTag> = response
+        let deleted: Vec<DbWebPushSubscription> = response
             .take(0)
             .map_err(|e| IndexerError::Serialization(e.to_string()))?;
 
-        let db_tag = results
-            .into_iter()
-            .next()
-            .ok_or_else(|| IndexerError::NotFound(format!("Tag '{}' not found", text)))?;
-
-        Ok(Tag {
-            id: db_tag.id.id.to_string(),
-            text: db_tag.text,
-            created_at: *db_tag.created_at,
-        })
+        if deleted.is_empty() {
+            return Err(IndexerError::NotFound(format!(
+                "Web Push subscription for {} not found",
+                endpoint
+            )));
+        }
+        Ok(())
     }
 
     // ==================== Export/Import Functions ====================
@@ -1496,8 +4409,47 @@ impl ClipperIndexer {
         builder.build_to_file(path)
     }
 
+    /// Export all clips except those tagged `tag`, e.g.
+    /// [`SENSITIVE_TAG`](crate::SENSITIVE_TAG), to a tar.gz archive file, in
+    /// the same format as [`export_all_to_file`](Self::export_all_to_file).
+    pub async fn export_all_except_tag_to_file<P: AsRef<std::path::Path>>(
+        &self,
+        tag: &str,
+        path: P,
+    ) -> Result<()> {
+        let entries = self
+            .all_entries()
+            .await?
+            .into_iter()
+            .filter(|entry| !entry.tags.iter().any(|t| t == tag))
+            .collect();
+        let builder = self.build_export_of(entries).await?;
+        builder.build_to_file(path)
+    }
+
+    /// Export the given clipboard entries to a tar.gz archive file, in the
+    /// same format as [`export_all_to_file`](Self::export_all_to_file).
+    ///
+    /// # Arguments
+    /// * `entries` - The entries to export, e.g. ones about to be cleaned up
+    /// * `path` - Path where the tar.gz archive will be written
+    pub async fn export_entries_to_file<P: AsRef<std::path::Path>>(
+        &self,
+        entries: &[ClipboardEntry],
+        path: P,
+    ) -> Result<()> {
+        let builder = self.build_export_of(entries.to_vec()).await?;
+        builder.build_to_file(path)
+    }
+
     /// Build an ExportBuilder with all clips and their attachments.
     async fn build_export(&self) -> Result<ExportBuilder> {
+        let all_entries = self.all_entries().await?;
+        self.build_export_of(all_entries).await
+    }
+
+    /// All clips, for exports
+    async fn all_entries(&self) -> Result<Vec<ClipboardEntry>> {
         // Get all entries (no filters, large page size to get all)
         let mut all_entries = Vec::new();
         let mut page = 1;
@@ -1520,9 +4472,14 @@ impl ClipperIndexer {
             page += 1;
         }
 
+        Ok(all_entries)
+    }
+
+    /// Build an ExportBuilder with `entries` and their attachments.
+    async fn build_export_of(&self, entries: Vec<ClipboardEntry>) -> Result<ExportBuilder> {
         let mut builder = ExportBuilder::new();
 
-        for entry in all_entries {
+        for entry in entries {
             let attachment_content = if let Some(ref file_key) = entry.file_attachment {
                 self.storage.get_file(file_key).await.ok()
             } else {
@@ -1632,17 +4589,46 @@ impl ClipperIndexer {
                         id: clip.id.clone(),
                         content: clip.content.clone(),
                         created_at: clip.created_at,
+                        updated_at: clip.updated_at.unwrap_or(clip.created_at),
                         tags: clip.tags.clone(),
                         additional_notes: clip.additional_notes.clone(),
                         file_attachment: None,
                         original_filename: Some(original_filename.clone()),
                         language: clip.language.clone(),
+                        color: clip.color.clone(),
+                        icon: clip.icon.clone(),
+                        content_format: clip.content_format,
+                        source_device: clip
+                            .source_device
+                            .clone()
+                            .or_else(|| source_device_from_tags(&clip.tags)),
+                        truncated: clip.truncated,
+                        visibility: clip.visibility,
+                        detected_language: clip
+                            .detected_language
+                            .clone()
+                            .or_else(|| detect_language(&clip.content)),
+                        summary: clip.summary.clone(),
+                        canonical_url: clip.canonical_url.clone(),
+                        suggested_tags: clip.suggested_tags.clone(),
                         search_content: match &clip.additional_notes {
                             Some(notes) => format!("{} {}", clip.content, notes),
                             None => clip.content.clone(),
                         },
                     };
 
+                    if !clip.truncated
+                        && let Some(text) = self
+                            .attachment_text(
+                                &original_filename,
+                                attachment_content.clone(),
+                                &entry.content,
+                            )
+                            .await
+                    {
+                        entry.search_content = format!("{} {}", entry.search_content, text);
+                    }
+
                     // Store the file
                     let stored_file_key = self
                         .storage
@@ -1659,11 +4645,28 @@ impl ClipperIndexer {
                         id: clip.id.clone(),
                         content: clip.content.clone(),
                         created_at: clip.created_at,
+                        updated_at: clip.updated_at.unwrap_or(clip.created_at),
                         tags: clip.tags.clone(),
                         additional_notes: clip.additional_notes.clone(),
                         file_attachment: None,
                         original_filename: clip.original_filename.clone(),
                         language: clip.language.clone(),
+                        color: clip.color.clone(),
+                        icon: clip.icon.clone(),
+                        content_format: clip.content_format,
+                        source_device: clip
+                            .source_device
+                            .clone()
+                            .or_else(|| source_device_from_tags(&clip.tags)),
+                        truncated: clip.truncated,
+                        visibility: clip.visibility,
+                        detected_language: clip
+                            .detected_language
+                            .clone()
+                            .or_else(|| detect_language(&clip.content)),
+                        summary: clip.summary.clone(),
+                        canonical_url: clip.canonical_url.clone(),
+                        suggested_tags: clip.suggested_tags.clone(),
                         search_content: match &clip.additional_notes {
                             Some(notes) => format!("{} {}", clip.content, notes),
                             None => clip.content.clone(),
@@ -1677,11 +4680,28 @@ impl ClipperIndexer {
                     id: clip.id.clone(),
                     content: clip.content.clone(),
                     created_at: clip.created_at,
+                    updated_at: clip.updated_at.unwrap_or(clip.created_at),
                     tags: clip.tags.clone(),
                     additional_notes: clip.additional_notes.clone(),
                     file_attachment: None,
                     original_filename: None,
                     language: clip.language.clone(),
+                    color: clip.color.clone(),
+                    icon: clip.icon.clone(),
+                    content_format: clip.content_format,
+                    source_device: clip
+                        .source_device
+                        .clone()
+                        .or_else(|| source_device_from_tags(&clip.tags)),
+                    truncated: clip.truncated,
+                    visibility: clip.visibility,
+                    detected_language: clip
+                        .detected_language
+                        .clone()
+                        .or_else(|| detect_language(&clip.content)),
+                    summary: clip.summary.clone(),
+                    canonical_url: clip.canonical_url.clone(),
+                    suggested_tags: clip.suggested_tags.clone(),
                     search_content: match &clip.additional_notes {
                         Some(notes) => format!("{} {}", clip.content, notes),
                         None => clip.content.clone(),
@@ -1704,23 +4724,14 @@ impl ClipperIndexer {
         })
     }
 
-    /// Insert an entry with a specific ID (used during import)
+    /// Insert an entry with a specific ID (used during import and for
+    /// truncated text)
     async fn insert_entry_with_id(&self, entry: &ClipboardEntry) -> Result<()> {
         let record_id = (TABLE_NAME, entry.id.as_str());
         let _: Option<DbClipboardEntry> = self
             .db
             .create(record_id)
-            .content(DbClipboardEntry {
-                id: surrealdb::sql::Thing::from((TABLE_NAME.to_string(), entry.id.clone())),
-                content: entry.content.clone(),
-                created_at: surrealdb::sql::Datetime::from(entry.created_at),
-                tags: entry.tags.clone(),
-                additional_notes: entry.additional_notes.clone(),
-                file_attachment: entry.file_attachment.clone(),
-                original_filename: entry.original_filename.clone(),
-                language: entry.language.clone(),
-                search_content: entry.search_content.clone(),
-            })
+            .content(DbClipboardEntry::from(entry))
             .await?;
 
         // Sync tags to the tags table
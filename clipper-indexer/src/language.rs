@@ -0,0 +1,132 @@
+//! Natural language detection for clip text
+//!
+//! Detection uses whatlang's trigram model on the start of the text and only
+//! keeps results whatlang deems reliable, so short snippets, code and file
+//! names are usually left without a language. Languages are reported as ISO
+//! 639-1 codes, the form translation APIs take.
+
+use whatlang::Lang;
+
+/// Characters looked at; the start of a long clip is enough
+const MAX_DETECT_CHARS: usize = 4096;
+
+/// Shortest text, in characters, a language is detected for
+const MIN_DETECT_CHARS: usize = 16;
+
+/// ISO 639-1 code of the language `text` is written in, such as "en", or
+/// `None` when it can't be told reliably
+pub(crate) fn detect_language(text: &str) -> Option<String> {
+    let text = match text.char_indices().nth(MAX_DETECT_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+    if text.trim().chars().count() < MIN_DETECT_CHARS {
+        return None;
+    }
+
+    let info = whatlang::detect(text)?;
+    info.is_reliable()
+        .then(|| iso_639_1(info.lang()).to_string())
+}
+
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Epo => "eo",
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Yid => "yi",
+        Lang::Pol => "pl",
+        Lang::Amh => "am",
+        Lang::Jav => "jv",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Mar => "mr",
+        Lang::Kan => "kn",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Tam => "ta",
+        Lang::Vie => "vi",
+        Lang::Urd => "ur",
+        Lang::Tha => "th",
+        Lang::Guj => "gu",
+        Lang::Uzb => "uz",
+        Lang::Pan => "pa",
+        Lang::Aze => "az",
+        Lang::Ind => "id",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Mal => "ml",
+        Lang::Ori => "or",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Sin => "si",
+        Lang::Khm => "km",
+        Lang::Tuk => "tk",
+        Lang::Aka => "ak",
+        Lang::Zul => "zu",
+        Lang::Sna => "sn",
+        Lang::Afr => "af",
+        Lang::Lat => "la",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("The quick brown fox jumps over the lazy dog, again and again.")
+                .as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language(
+                "Ich habe heute keine Zeit, weil ich noch die Wohnung aufräumen und einkaufen muss."
+            )
+            .as_deref(),
+            Some("de")
+        );
+        assert_eq!(
+            detect_language("今天天气很好，我们一起去公园散步吧。").as_deref(),
+            Some("zh")
+        );
+        // Too short to tell
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(detect_language(""), None);
+    }
+}
@@ -1,13 +1,24 @@
+mod cursor;
 pub mod error;
 pub mod export;
+pub mod extract;
+mod fuzzy;
 pub mod indexer;
+mod language;
 pub mod models;
+pub mod query;
 pub mod storage;
+mod template;
 
 pub use error::{IndexerError, Result};
 pub use export::{ExportBuilder, ExportManifest, ExportedClip, ImportParser, ImportResult};
+pub use extract::{ExtractorPipeline, TextExtractor};
 pub use indexer::ClipperIndexer;
 pub use models::{
-    ClipboardEntry, HighlightOptions, PagedResult, PagingParams, SearchFilters, SearchResultItem,
-    ShortUrl, Tag,
+    ActivityStats, ClipLink, ClipboardEntry, Collection, ContentFormat, DayActivity, Device,
+    FuzzyOptions, HOST_TAG_PREFIX, HighlightOptions, LibraryStats, PagedResult, PagingParams,
+    PairedDevice, PushPlatform, PushToken, SENSITIVE_TAG, SearchFilters, SearchResultItem,
+    ShortUrl, SortDirection, SortField, SortOrder, Tag, TagStats, Template, Visibility,
+    WebPushSubscription, source_device_from_tags,
 };
+pub use query::SearchQuery;
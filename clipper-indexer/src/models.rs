@@ -1,9 +1,31 @@
-use chrono::{DateTime, Utc};
+use crate::language::detect_language;
+use chrono::{DateTime, NaiveDate, Utc};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 static JIEBA: OnceCell<jieba_rs::Jieba> = OnceCell::new();
 
+/// Joins the tokens of `search_content`; see [`tokenize`]
+pub(crate) const TOKEN_SEPARATOR: &str = "\u{200B}";
+
+/// Prefix of the tag naming the device a clip was created on, e.g.
+/// `$host:laptop`; see [`ClipboardEntry::source_device`]
+pub const HOST_TAG_PREFIX: &str = "$host:";
+
+/// Tag marking a clip as sensitive, e.g. a password or an API key. The server
+/// expires sensitive clips quickly, UIs mask their content until clicked, and
+/// exports can leave them out.
+pub const SENSITIVE_TAG: &str = "$sensitive";
+
+/// The device named by the first `$host:` tag, if any
+pub fn source_device_from_tags(tags: &[String]) -> Option<String> {
+    tags.iter()
+        .find_map(|tag| tag.strip_prefix(HOST_TAG_PREFIX))
+        .filter(|device| !device.is_empty())
+        .map(str::to_string)
+}
+
 pub(crate) fn tokenize(text: &str) -> String {
     // Use jieba-rs for Chinese text segmentation
     let jieba = JIEBA.get_or_init(jieba_rs::Jieba::new);
@@ -13,7 +35,7 @@ pub(crate) fn tokenize(text: &str) -> String {
         .into_iter()
         .map(|s| s.to_string())
         .collect::<Vec<String>>()
-        .join("\u{200B}")
+        .join(TOKEN_SEPARATOR)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +44,10 @@ pub struct ClipboardEntry {
     pub content: String,
     #[serde(with = "datetime_conversion")]
     pub created_at: DateTime<Utc>,
+    /// When the clip was last changed (tags, notes, language, color or icon); equals
+    /// `created_at` for clips that were never updated
+    #[serde(with = "datetime_conversion")]
+    pub updated_at: DateTime<Utc>,
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub additional_notes: Option<String>,
@@ -32,10 +58,80 @@ pub struct ClipboardEntry {
     /// Optional language identifier for the clip content (e.g., "en", "zh", "rust", "python")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    /// Optional color label used to flag the clip in the UIs, either a hex
+    /// color ("#e11d48") or a color name ("red")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Optional icon shown next to the clip, e.g. an emoji or an icon name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Optional hint for how the text content is written, used to render
+    /// Markdown clips when they are shared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<ContentFormat>,
+    /// The device the clip was created on, taken from its `$host:` tag when
+    /// the clip is created. It is kept when the tags change later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_device: Option<String>,
+    /// Set when the text was too long to keep in full: `content` holds its
+    /// beginning, and the whole text is the file attachment
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Who can see the clip; see [`Visibility`]
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Natural language the content is written in, as an ISO 639-1 code such
+    /// as "en", when it could be detected (see `language` for the code or
+    /// document language set by users)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// One-line summary written by the server's enrichment, if enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Tags the server's enrichment suggests; they aren't in `tags` until
+    /// someone adds them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_tags: Vec<String>,
+    /// Where the URL in the content leads, without tracking parameters, when
+    /// the server's URL canonicalization found it different from the URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
     #[serde(skip_serializing)]
     pub search_content: String,
 }
 
+/// Format of a clip's text content
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    #[default]
+    Plain,
+    Markdown,
+}
+
+/// Who can see a clip
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Only the owner: the shared token and paired devices
+    #[default]
+    Private,
+    /// Any authenticated user, including guests
+    Shared,
+    /// Like shared, and also anyone with one of the clip's short URLs
+    Public,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Private => "private",
+            Visibility::Shared => "shared",
+            Visibility::Public => "public",
+        }
+    }
+}
+
 mod datetime_conversion {
     use chrono::{DateTime, Utc};
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -64,16 +160,30 @@ impl ClipboardEntry {
         let id = uuid::Uuid::new_v4().simple().to_string();
         // Pre-tokenize content for search indexing
         let search_content = tokenize(&content);
+        let source_device = source_device_from_tags(&tags);
+        let detected_language = detect_language(&content);
+        let now = Utc::now();
 
         Self {
             id,
             content,
-            created_at: Utc::now(),
+            created_at: now,
+            updated_at: now,
             tags,
             additional_notes: None,
             file_attachment: None,
             original_filename: None,
             language: None,
+            color: None,
+            icon: None,
+            content_format: None,
+            source_device,
+            truncated: false,
+            visibility: Visibility::default(),
+            detected_language,
+            summary: None,
+            suggested_tags: Vec::new(),
+            canonical_url: None,
             search_content,
         }
     }
@@ -99,6 +209,16 @@ impl ClipboardEntry {
         self
     }
 
+    pub fn with_content_format(mut self, content_format: ContentFormat) -> Self {
+        self.content_format = Some(content_format);
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
     pub fn update_search_content(&mut self) {
         self.search_content = match &self.additional_notes {
             Some(notes) => format!("{} {}", self.content, notes),
@@ -115,6 +235,58 @@ pub struct SearchFilters {
     pub end_date: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    /// Only clips updated at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only clips created on this device
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_device: Option<String>,
+    /// Only clips with one of these visibilities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Vec<Visibility>>,
+    /// Result order for full-text search: by date (see `sort_by`) or relevance
+    #[serde(default)]
+    pub sort: SortOrder,
+    /// Field to sort lists and date-ordered searches by
+    #[serde(default)]
+    pub sort_by: SortField,
+    /// Direction for `sort_by`
+    #[serde(default)]
+    pub order: SortDirection,
+}
+
+/// Order of search results
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Newest first
+    #[default]
+    Date,
+    /// Best match first, by BM25 score
+    Relevance,
+}
+
+/// Field to sort clips by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    /// Length of the clip content in characters
+    ContentLength,
+    /// Original filename of the attachment; clips without one sort first
+    /// in ascending order
+    OriginalFilename,
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
 }
 
 impl SearchFilters {
@@ -132,6 +304,32 @@ impl SearchFilters {
         self.tags = Some(tags);
         self
     }
+
+    pub fn with_updated_since(mut self, updated_since: DateTime<Utc>) -> Self {
+        self.updated_since = Some(updated_since);
+        self
+    }
+
+    pub fn with_source_device(mut self, source_device: impl Into<String>) -> Self {
+        self.source_device = Some(source_device.into());
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: Vec<Visibility>) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    pub fn with_sort_by(mut self, sort_by: SortField, order: SortDirection) -> Self {
+        self.sort_by = sort_by;
+        self.order = order;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +338,14 @@ pub struct PagingParams {
     pub page: usize,
     /// Number of items per page
     pub page_size: usize,
+    /// Continue after the clip this cursor points at, taken from
+    /// `PagedResult::next_cursor`; `page` is ignored when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Skip counting all matches, which is slow for large databases.
+    /// `PagedResult::has_more` still tells whether another page follows.
+    #[serde(default)]
+    pub skip_total: bool,
 }
 
 impl Default for PagingParams {
@@ -147,6 +353,8 @@ impl Default for PagingParams {
         Self {
             page: 1,
             page_size: 20,
+            cursor: None,
+            skip_total: false,
         }
     }
 }
@@ -156,10 +364,25 @@ impl PagingParams {
         Self {
             page: page.max(1),
             page_size: page_size.clamp(1, 500),
+            cursor: None,
+            skip_total: false,
         }
     }
 
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    pub fn with_skip_total(mut self, skip_total: bool) -> Self {
+        self.skip_total = skip_total;
+        self
+    }
+
     pub fn offset(&self) -> usize {
+        if self.cursor.is_some() {
+            return 0;
+        }
         (self.page - 1) * self.page_size
     }
 }
@@ -167,10 +390,20 @@ impl PagingParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PagedResult<T> {
     pub items: Vec<T>,
+    /// Number of matching items. With `PagingParams::skip_total` this is only
+    /// a lower bound: the items up to the end of this page, plus one if
+    /// `has_more`.
     pub total: usize,
     pub page: usize,
     pub page_size: usize,
     pub total_pages: usize,
+    /// Whether another page follows this one
+    #[serde(default)]
+    pub has_more: bool,
+    /// Cursor for the page after this one. Only set when `has_more` and
+    /// sorted by `created_at` or `updated_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 impl<T> PagedResult<T> {
@@ -182,8 +415,20 @@ impl<T> PagedResult<T> {
             page,
             page_size,
             total_pages,
+            has_more: page < total_pages,
+            next_cursor: None,
         }
     }
+
+    pub fn with_has_more(mut self, has_more: bool) -> Self {
+        self.has_more = has_more;
+        self
+    }
+
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
 }
 
 /// Represents a short URL that maps to a clipboard entry
@@ -247,6 +492,110 @@ impl ShortUrl {
     }
 }
 
+/// A directed link from one clip to another, e.g. from the text extracted
+/// from a screenshot to the screenshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipLink {
+    pub id: String,
+    /// The clip the link starts from
+    pub source_id: String,
+    /// The clip the link points to
+    pub target_id: String,
+    /// Kind of relationship, e.g. "derived_from" or "reply_to"
+    pub relation: String,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClipLink {
+    pub fn new(source_id: String, target_id: String, relation: String) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            id,
+            source_id,
+            target_id,
+            relation,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A named group of clips kept in a manual order, e.g. a board of
+/// snippets for one project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "datetime_conversion")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Collection {
+    pub fn new(name: String, description: Option<String>) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let now = Utc::now();
+        Self {
+            id,
+            name,
+            description,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Text with `{{placeholders}}` that clips are created from, e.g. an email
+/// reply; see [`Template::render`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+    /// Tags given to the clips created from the template
+    pub tags: Vec<String>,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "datetime_conversion")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Template {
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        content: String,
+        tags: Vec<String>,
+    ) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let now = Utc::now();
+        Self {
+            id,
+            name,
+            description,
+            content,
+            tags,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Names of the placeholders in the content, in order of first use
+    pub fn placeholders(&self) -> Vec<String> {
+        crate::template::placeholder_names(&self.content)
+    }
+
+    /// The content with every `{{name}}` replaced by `values[name]`, or by
+    /// the default in `{{name|default}}` when there is no value. Fails with
+    /// `InvalidInput` if a placeholder has neither.
+    pub fn render(&self, values: &HashMap<String, String>) -> crate::error::Result<String> {
+        crate::template::render(&self.content, values)
+    }
+}
+
 /// Options for highlighting search results
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HighlightOptions {
@@ -271,6 +620,36 @@ impl HighlightOptions {
     }
 }
 
+/// Options for typo-tolerant (fuzzy) search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzyOptions {
+    /// Maximum number of edits (inserted, deleted or substituted characters,
+    /// or swapped adjacent characters) between a search term and a word
+    pub max_distance: usize,
+    /// Terms shorter than this must match exactly, since short words are
+    /// within a few edits of too many others
+    pub min_term_length: usize,
+}
+
+impl Default for FuzzyOptions {
+    fn default() -> Self {
+        Self {
+            max_distance: 1,
+            min_term_length: 4,
+        }
+    }
+}
+
+impl FuzzyOptions {
+    /// Create fuzzy options with the given maximum edit distance
+    pub fn new(max_distance: usize) -> Self {
+        Self {
+            max_distance,
+            ..Self::default()
+        }
+    }
+}
+
 /// A search result item with optional highlighted content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResultItem {
@@ -290,3 +669,103 @@ pub struct Tag {
     #[serde(with = "datetime_conversion")]
     pub created_at: DateTime<Utc>,
 }
+
+/// A device that clips were created on, see [`ClipboardEntry::source_device`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    pub name: String,
+    /// Number of clips created on the device
+    pub clip_count: usize,
+    /// When the newest clip from the device was created
+    pub last_seen: DateTime<Utc>,
+}
+
+/// A device paired with the server through a pairing code. It signs in
+/// with its own token, so it can be revoked without changing the shared one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDevice {
+    pub id: String,
+    pub name: String,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+    /// When the device last authenticated, updated at most once a minute
+    #[serde(with = "option_datetime_conversion")]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Push service a mobile push token belongs to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PushPlatform {
+    /// Apple Push Notification service (iOS)
+    Apns,
+    /// Firebase Cloud Messaging (Android)
+    Fcm,
+}
+
+/// A mobile device's push token, registered so a notification relay can
+/// tell the device about new clips
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushToken {
+    pub id: String,
+    pub token: String,
+    pub platform: PushPlatform,
+    /// Name shown for the device, e.g. "iPhone"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+    /// When the token was last registered; devices re-register on launch
+    #[serde(with = "datetime_conversion")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A browser's Web Push subscription, as returned by the Push API's
+/// `PushSubscription`, used to send new clip notifications to the web UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebPushSubscription {
+    pub id: String,
+    /// Push service URL the notifications are posted to
+    pub endpoint: String,
+    /// The browser's P-256 public key, base64url encoded
+    pub p256dh: String,
+    /// The browser's authentication secret, base64url encoded
+    pub auth: String,
+    #[serde(with = "datetime_conversion")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Number of clip entries using a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagStats {
+    pub text: String,
+    pub count: usize,
+}
+
+/// Number of records of each kind in the index
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryStats {
+    pub clips: usize,
+    /// Clips with a file attachment
+    pub attachments: usize,
+    pub tags: usize,
+    pub collections: usize,
+    pub short_urls: usize,
+    pub paired_devices: usize,
+}
+
+/// Number of clips created on a day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// Clip activity over a period: clips per day and the most used tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityStats {
+    /// Every day of the period in order, including days without clips
+    pub days: Vec<DayActivity>,
+    /// Most used tags of the clips in the period, without `$host:` tags
+    pub top_tags: Vec<TagStats>,
+}
@@ -0,0 +1,222 @@
+//! Search query language
+//!
+//! A search string can mix free text with operators:
+//!
+//! | Syntax                              | Matches clips that...                              |
+//! |-------------------------------------|----------------------------------------------------|
+//! | `word`                              | contain the word (full-text search)                |
+//! | `"exact phrase"`                    | contain the phrase as written (case-insensitive)   |
+//! | `tag:work`, `tag:"two words"`       | have the tag                                       |
+//! | `-tag:private`                      | don't have the tag                                 |
+//! | `after:2024-01-01`                  | were created on or after the date                  |
+//! | `before:2024-02-01`                 | were created before the date                       |
+//! | `has:attachment`, `-has:attachment` | have (or don't have) a file attachment             |
+//!
+//! Dates are `YYYY-MM-DD` (midnight UTC) or RFC 3339 timestamps. Operator
+//! names are case-insensitive. Words with an unknown `key:` prefix (URLs,
+//! `C:\paths`, `key:value` pairs) are searched as plain text.
+
+use crate::error::{IndexerError, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+/// A parsed search string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    /// Free-text words
+    pub terms: Vec<String>,
+    /// Quoted phrases that must appear verbatim
+    pub phrases: Vec<String>,
+    /// Tags the clip must have
+    pub tags: Vec<String>,
+    /// Tags the clip must not have
+    pub exclude_tags: Vec<String>,
+    /// Only clips created at or after this time
+    pub after: Option<DateTime<Utc>>,
+    /// Only clips created before this time
+    pub before: Option<DateTime<Utc>>,
+    /// Only clips with (true) or without (false) a file attachment
+    pub has_attachment: Option<bool>,
+}
+
+/// A whitespace-separated word with quotes removed
+struct Word {
+    text: String,
+    /// The word started with a quote, i.e. it's a phrase
+    quoted: bool,
+}
+
+impl SearchQuery {
+    /// Parse a search string
+    ///
+    /// Fails with `InvalidInput` for malformed operator values, e.g. an
+    /// unparseable date or an unknown `has:` value.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut query = SearchQuery::default();
+
+        for word in split_words(input) {
+            if word.quoted {
+                if !word.text.trim().is_empty() {
+                    query.phrases.push(word.text);
+                }
+                continue;
+            }
+
+            let (negated, body) = match word.text.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, word.text.as_str()),
+            };
+
+            let Some((key, value)) = body.split_once(':') else {
+                query.terms.push(word.text);
+                continue;
+            };
+
+            let key = key.to_lowercase();
+            let value = value.trim();
+            if value.is_empty() || !matches!(key.as_str(), "tag" | "before" | "after" | "has") {
+                query.terms.push(word.text);
+                continue;
+            }
+
+            match (key.as_str(), negated) {
+                ("tag", false) => query.tags.push(value.to_string()),
+                ("tag", true) => query.exclude_tags.push(value.to_string()),
+                ("has", _) => {
+                    if !value.eq_ignore_ascii_case("attachment") {
+                        return Err(IndexerError::InvalidInput(format!(
+                            "Unknown search operator has:{} (expected has:attachment)",
+                            value
+                        )));
+                    }
+                    query.has_attachment = Some(!negated);
+                }
+                (_, true) => {
+                    return Err(IndexerError::InvalidInput(format!(
+                        "Search operator {}: cannot be negated",
+                        key
+                    )));
+                }
+                ("before", false) => {
+                    let date = parse_date(&key, value)?;
+                    query.before = Some(query.before.map_or(date, |d| d.min(date)));
+                }
+                _ => {
+                    let date = parse_date(&key, value)?;
+                    query.after = Some(query.after.map_or(date, |d| d.max(date)));
+                }
+            }
+        }
+
+        Ok(query)
+    }
+
+    /// Text for the full-text index: the free words plus the words of all
+    /// phrases, so phrase matches are also ranked and highlighted
+    pub fn full_text(&self) -> String {
+        self.terms
+            .iter()
+            .chain(self.phrases.iter())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Split on whitespace, keeping quoted sections (which may contain
+/// whitespace) together and removing the quotes
+fn split_words(input: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+
+        let mut text = String::new();
+        let mut in_quotes = false;
+        while let Some(c) = chars.next_if(|c| in_quotes || !c.is_whitespace()) {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else {
+                text.push(c);
+            }
+        }
+
+        words.push(Word {
+            text,
+            quoted: first == '"',
+        });
+    }
+
+    words
+}
+
+fn parse_date(key: &str, value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .map_err(|_| {
+            IndexerError::InvalidInput(format!(
+                "Invalid date for {}: '{}' (expected YYYY-MM-DD)",
+                key, value
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_mixed_query() {
+        let query = SearchQuery::parse(
+            r#"tag:work -tag:private "exact phrase" before:2024-01-01 has:attachment report"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.terms, vec!["report"]);
+        assert_eq!(query.phrases, vec!["exact phrase"]);
+        assert_eq!(query.tags, vec!["work"]);
+        assert_eq!(query.exclude_tags, vec!["private"]);
+        assert_eq!(
+            query.before,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(query.after, None);
+        assert_eq!(query.has_attachment, Some(true));
+        assert_eq!(query.full_text(), "report exact phrase");
+    }
+
+    #[test]
+    fn test_parse_quoted_tag_and_plain_colons() {
+        let query =
+            SearchQuery::parse(r#"TAG:"two words" https://example.com -has:attachment tag:"#)
+                .unwrap();
+
+        assert_eq!(query.tags, vec!["two words"]);
+        assert_eq!(query.terms, vec!["https://example.com", "tag:"]);
+        assert_eq!(query.has_attachment, Some(false));
+    }
+
+    #[test]
+    fn test_parse_invalid_operators() {
+        assert!(matches!(
+            SearchQuery::parse("before:yesterday"),
+            Err(IndexerError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            SearchQuery::parse("has:pictures"),
+            Err(IndexerError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            SearchQuery::parse("-after:2024-01-01"),
+            Err(IndexerError::InvalidInput(_))
+        ));
+    }
+}
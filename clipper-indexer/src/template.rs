@@ -0,0 +1,151 @@
+//! Placeholders in clip templates
+//!
+//! A placeholder is a name in double braces, `{{name}}`, optionally with a
+//! default after a bar, `{{name|there}}`. Names are made of letters, digits,
+//! `_`, `-` and `.`, and spaces around the name are ignored. Anything else in
+//! braces, such as `{{ not a name! }}`, is left as it is.
+
+use std::collections::HashMap;
+
+use crate::error::{IndexerError, Result};
+
+/// A placeholder found in a template
+struct Placeholder<'a> {
+    /// Byte range of the whole `{{...}}` in the template
+    start: usize,
+    end: usize,
+    name: &'a str,
+    default: Option<&'a str>,
+}
+
+/// Names of the placeholders in `content`, in order of first use
+pub(crate) fn placeholder_names(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for placeholder in placeholders(content) {
+        if !names.iter().any(|name| name == placeholder.name) {
+            names.push(placeholder.name.to_string());
+        }
+    }
+    names
+}
+
+/// Replace the placeholders in `content` with their values, or their defaults
+/// when no value is given. Fails with `InvalidInput` naming the placeholders
+/// that have neither.
+pub(crate) fn render(content: &str, values: &HashMap<String, String>) -> Result<String> {
+    let mut rendered = String::with_capacity(content.len());
+    let mut missing: Vec<&str> = Vec::new();
+    let mut last = 0;
+
+    for placeholder in placeholders(content) {
+        rendered.push_str(&content[last..placeholder.start]);
+        match values
+            .get(placeholder.name)
+            .map(String::as_str)
+            .or(placeholder.default)
+        {
+            Some(value) => rendered.push_str(value),
+            None if !missing.contains(&placeholder.name) => missing.push(placeholder.name),
+            None => {}
+        }
+        last = placeholder.end;
+    }
+    rendered.push_str(&content[last..]);
+
+    if !missing.is_empty() {
+        return Err(IndexerError::InvalidInput(format!(
+            "Missing values for placeholders: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(rendered)
+}
+
+fn placeholders(content: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(open) = content[offset..].find("{{") {
+        let start = offset + open;
+        let Some(close) = content[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + close + 2;
+        let inner = &content[start + 2..end - 2];
+        let (name, default) = match inner.split_once('|') {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (inner.trim(), None),
+        };
+
+        if is_placeholder_name(name) {
+            found.push(Placeholder {
+                start,
+                end,
+                name,
+                default,
+            });
+            offset = end;
+        } else {
+            // Not a placeholder; a later `{{` may still start one
+            offset = start + 1;
+        }
+    }
+    found
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_placeholder_names() {
+        assert_eq!(
+            placeholder_names("Hi {{name}}, re: {{ subject }}. Thanks, {{name}} {{sign-off|Bob}}"),
+            vec!["name", "subject", "sign-off"]
+        );
+        assert!(placeholder_names("No {{ placeholders! }} or {{}} here").is_empty());
+    }
+
+    #[test]
+    fn test_render_fills_values_and_defaults() {
+        let rendered = render(
+            "Hi {{ name }},\n\nThanks for {{topic|your email}}.\n{{sig|}}",
+            &values(&[("name", "Ann")]),
+        )
+        .unwrap();
+        assert_eq!(rendered, "Hi Ann,\n\nThanks for your email.\n");
+
+        // Values win over defaults, and text that isn't a placeholder stays
+        let rendered = render(
+            "{{topic|x}} {{ not valid! }} {{{{id}}",
+            &values(&[("topic", "the report"), ("id", "42")]),
+        )
+        .unwrap();
+        assert_eq!(rendered, "the report {{ not valid! }} {{42");
+    }
+
+    #[test]
+    fn test_render_reports_missing_values() {
+        let error = render("{{a}} {{b}} {{a}} {{c|}}", &values(&[])).unwrap_err();
+        match error {
+            IndexerError::InvalidInput(message) => {
+                assert_eq!(message, "Missing values for placeholders: a, b")
+            }
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use chrono::{Duration, Utc};
-use clipper_indexer::{ClipperIndexer, IndexerError, PagingParams, SearchFilters};
+use clipper_indexer::{
+    ClipperIndexer, ContentFormat, ExtractorPipeline, FuzzyOptions, HighlightOptions,
+    IndexerError, LibraryStats, PagingParams, PushPlatform, SENSITIVE_TAG, SearchFilters,
+    SortDirection, SortField, SortOrder, Tag, TextExtractor, Visibility,
+};
+use std::collections::HashMap;
 use std::fs;
 use tempfile::TempDir;
 
@@ -56,6 +61,34 @@ async fn test_get_entry() {
     assert_eq!(retrieved.tags, vec!["test"]);
 }
 
+#[tokio::test]
+async fn test_get_entries() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let first = indexer
+        .add_entry_from_text("First".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let second = indexer
+        .add_entry_from_text("Second".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    let entries = indexer
+        .get_entries(&[
+            second.id.clone(),
+            "missing".to_string(),
+            first.id.clone(),
+            second.id.clone(),
+        ])
+        .await
+        .expect("Failed to get entries");
+
+    let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+    assert_eq!(contents, vec!["Second", "First"]);
+    assert!(indexer.get_entries(&[]).await.unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_update_entry() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
@@ -76,6 +109,8 @@ async fn test_update_entry() {
             Some(vec!["updated".to_string(), "test".to_string()]),
             Some("Updated notes".to_string()),
             None,
+            None,
+            None,
         )
         .await
         .expect("Failed to update entry");
@@ -85,6 +120,74 @@ async fn test_update_entry() {
     assert_eq!(updated.search_content, "Original content Updated notes");
 }
 
+#[tokio::test]
+async fn test_update_entry_tracks_updated_at() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let edited = indexer
+        .add_entry_from_text("Edited later".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_text("Never edited".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    assert_eq!(edited.updated_at, edited.created_at);
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    let since = Utc::now();
+
+    let updated = indexer
+        .update_entry(
+            &edited.id,
+            None,
+            Some("New notes".to_string()),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(updated.updated_at >= since);
+    assert_eq!(updated.created_at, edited.created_at);
+
+    let changed = indexer
+        .list_entries(
+            SearchFilters::new().with_updated_since(since),
+            PagingParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(changed.total, 1);
+    assert_eq!(changed.items[0].id, edited.id);
+
+    // Renaming a tag updates every clip carrying it
+    indexer
+        .update_entry(
+            &edited.id,
+            Some(vec!["old".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let before_rename = indexer.get_entry(&edited.id).await.unwrap().updated_at;
+    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    indexer.rename_tag("old", "new").await.unwrap();
+    assert!(indexer.get_entry(&edited.id).await.unwrap().updated_at > before_rename);
+
+    let recently_updated = indexer
+        .list_entries(
+            SearchFilters::new().with_sort_by(SortField::UpdatedAt, SortDirection::Desc),
+            PagingParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(recently_updated.items[0].id, edited.id);
+}
+
 #[tokio::test]
 async fn test_add_entry_from_file() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
@@ -267,6 +370,169 @@ async fn test_list_entries_with_tag_filter() {
     assert_eq!(filtered.total, 2);
 }
 
+#[tokio::test]
+async fn test_list_entries_sorted_by_content_length() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    for content in ["medium text", "a much longer piece of text", "short"] {
+        indexer
+            .add_entry_from_text(content.to_string(), vec![], None, None)
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+    }
+
+    let list = |sort_by, order| {
+        indexer.list_entries(
+            SearchFilters::new().with_sort_by(sort_by, order),
+            PagingParams::default(),
+        )
+    };
+
+    let contents = |result: clipper_indexer::PagedResult<clipper_indexer::ClipboardEntry>| {
+        result
+            .items
+            .into_iter()
+            .map(|e| e.content)
+            .collect::<Vec<_>>()
+    };
+
+    let ascending = list(SortField::ContentLength, SortDirection::Asc)
+        .await
+        .unwrap();
+    assert_eq!(
+        contents(ascending),
+        vec!["short", "medium text", "a much longer piece of text"]
+    );
+
+    let oldest_first = list(SortField::CreatedAt, SortDirection::Asc)
+        .await
+        .unwrap();
+    assert_eq!(
+        contents(oldest_first),
+        vec!["medium text", "a much longer piece of text", "short"]
+    );
+}
+
+#[tokio::test]
+async fn test_list_entries_with_cursor() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    for i in 0..5 {
+        indexer
+            .add_entry_from_text(format!("Entry {}", i), vec![], None, None)
+            .await
+            .unwrap();
+    }
+
+    let first = indexer
+        .list_entries(SearchFilters::new(), PagingParams::new(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(first.items.len(), 2);
+    let mut seen: Vec<String> = first.items.iter().map(|e| e.content.clone()).collect();
+
+    // A clip added mid-scroll doesn't shift the following pages
+    indexer
+        .add_entry_from_text("Added later".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    let mut cursor = first.next_cursor;
+    while let Some(next) = cursor {
+        let page = indexer
+            .list_entries(
+                SearchFilters::new(),
+                PagingParams::new(1, 2).with_cursor(next),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 6);
+        seen.extend(page.items.iter().map(|e| e.content.clone()));
+        cursor = page.next_cursor;
+    }
+
+    assert_eq!(
+        seen,
+        vec!["Entry 4", "Entry 3", "Entry 2", "Entry 1", "Entry 0"]
+    );
+
+    // Cursors only work with the sort they were created for
+    let by_length = indexer
+        .list_entries(
+            SearchFilters::new().with_sort_by(SortField::ContentLength, SortDirection::Asc),
+            PagingParams::new(1, 2),
+        )
+        .await
+        .unwrap();
+    assert!(by_length.next_cursor.is_none());
+
+    let first = indexer
+        .list_entries(SearchFilters::new(), PagingParams::new(1, 2))
+        .await
+        .unwrap();
+    let result = indexer
+        .list_entries(
+            SearchFilters::new().with_sort_by(SortField::UpdatedAt, SortDirection::Desc),
+            PagingParams::new(1, 2).with_cursor(first.next_cursor.unwrap()),
+        )
+        .await;
+    assert!(matches!(result, Err(IndexerError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_list_entries_skip_total() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    for i in 0..5 {
+        indexer
+            .add_entry_from_text(format!("Entry {}", i), vec![], None, None)
+            .await
+            .unwrap();
+    }
+
+    let counted = indexer
+        .list_entries(SearchFilters::new(), PagingParams::new(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(counted.total, 5);
+    assert!(counted.has_more);
+
+    let page = indexer
+        .list_entries(
+            SearchFilters::new(),
+            PagingParams::new(2, 2).with_skip_total(true),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert!(page.has_more);
+    assert_eq!(page.total_pages, 3);
+
+    let last = indexer
+        .list_entries(
+            SearchFilters::new(),
+            PagingParams::new(3, 2).with_skip_total(true),
+        )
+        .await
+        .unwrap();
+    assert_eq!(last.items.len(), 1);
+    assert!(!last.has_more);
+    assert_eq!(last.total, 5);
+    assert!(last.next_cursor.is_none());
+
+    let search = indexer
+        .search_entries(
+            "Entry",
+            SearchFilters::new(),
+            PagingParams::new(1, 4).with_skip_total(true),
+        )
+        .await
+        .unwrap();
+    assert_eq!(search.items.len(), 4);
+    assert!(search.has_more);
+}
+
 #[tokio::test]
 async fn test_delete_entry() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
@@ -297,137 +563,779 @@ async fn test_delete_entry() {
 }
 
 #[tokio::test]
-async fn test_search_with_combined_filters() {
+async fn test_clip_links() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    let now = Utc::now();
-
-    // Add entries
-    indexer
-        .add_entry_from_text(
-            "Rust is awesome".to_string(),
-            vec!["rust".to_string()],
-            None,
-            None,
-        )
+    let screenshot = indexer
+        .add_entry_from_text("Screenshot".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let text = indexer
+        .add_entry_from_text("Extracted text".to_string(), vec![], None, None)
         .await
         .unwrap();
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let link = indexer
+        .add_link(&text.id, &screenshot.id, "derived_from")
+        .await
+        .expect("Failed to add link");
+    assert_eq!(link.source_id, text.id);
+    assert_eq!(link.target_id, screenshot.id);
 
-    indexer
-        .add_entry_from_text(
-            "Rust programming tips".to_string(),
-            vec!["rust".to_string(), "tips".to_string()],
-            None,
-            None,
-        )
+    // Adding the same link again returns the existing one
+    let again = indexer
+        .add_link(&text.id, &screenshot.id, " derived_from ")
+        .await
+        .unwrap();
+    assert_eq!(again.id, link.id);
+
+    // Links are listed from both ends
+    let links = indexer.get_links_for_clip(&screenshot.id).await.unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].relation, "derived_from");
+
+    assert!(matches!(
+        indexer.add_link(&text.id, &text.id, "related").await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        indexer.add_link(&text.id, "missing", "related").await,
+        Err(IndexerError::NotFound(_))
+    ));
+    assert!(matches!(
+        indexer.delete_link("missing", &link.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
+
+    // Deleting a clip removes its links
+    indexer.delete_entry(&screenshot.id).await.unwrap();
+    let links = indexer.get_links_for_clip(&text.id).await.unwrap();
+    assert!(links.is_empty());
+
+    let other = indexer
+        .add_entry_from_text("Other".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let link = indexer
+        .add_link(&text.id, &other.id, "related")
         .await
         .unwrap();
+    indexer.delete_link(&other.id, &link.id).await.unwrap();
+    let links = indexer.get_links_for_clip(&text.id).await.unwrap();
+    assert!(links.is_empty());
+}
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+#[tokio::test]
+async fn test_collections() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
+    let mut clip_ids = Vec::new();
+    for content in ["First", "Second", "Third"] {
+        let entry = indexer
+            .add_entry_from_text(content.to_string(), vec![], None, None)
+            .await
+            .unwrap();
+        clip_ids.push(entry.id);
+    }
+
+    let collection = indexer
+        .create_collection(" Project X ", Some("Snippets".to_string()))
+        .await
+        .expect("Failed to create collection");
+    assert_eq!(collection.name, "Project X");
+    assert!(matches!(
+        indexer.create_collection("Project X", None).await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    for clip_id in &clip_ids {
+        indexer
+            .add_to_collection(&collection.id, clip_id, None)
+            .await
+            .unwrap();
+    }
+    // Adding a clip at a position moves it there
     indexer
-        .add_entry_from_text(
-            "Python programming".to_string(),
-            vec!["python".to_string()],
-            None,
-            None,
-        )
+        .add_to_collection(&collection.id, &clip_ids[2], Some(0))
         .await
         .unwrap();
 
-    // Wait for indexing
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-    // Search with tag filter
-    let filters = SearchFilters::new()
-        .with_tags(vec!["rust".to_string()])
-        .with_date_range(now - Duration::hours(1), now + Duration::hours(1));
-    let paging_params = PagingParams::default();
+    let page = indexer
+        .list_collection_entries(&collection.id, PagingParams::default())
+        .await
+        .unwrap();
+    let contents: Vec<&str> = page.items.iter().map(|e| e.content.as_str()).collect();
+    assert_eq!(contents, ["Third", "First", "Second"]);
+    assert_eq!(page.total, 3);
+
+    let order = vec![
+        clip_ids[1].clone(),
+        clip_ids[0].clone(),
+        clip_ids[2].clone(),
+    ];
+    indexer
+        .reorder_collection(&collection.id, &order)
+        .await
+        .unwrap();
+    assert!(matches!(
+        indexer
+            .reorder_collection(&collection.id, &order[..2])
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    // Removing or deleting clips keeps the order of the rest
+    indexer
+        .remove_from_collection(&collection.id, &clip_ids[1])
+        .await
+        .unwrap();
+    indexer.delete_entry(&clip_ids[0]).await.unwrap();
+    let page = indexer
+        .list_collection_entries(&collection.id, PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].id, clip_ids[2]);
 
-    let results = indexer
-        .search_entries("programming", filters, paging_params)
+    let collections = indexer
+        .get_collections_for_clip(&clip_ids[2])
         .await
-        .expect("Failed to search");
+        .unwrap();
+    assert_eq!(collections.len(), 1);
 
-    assert!(!results.items.is_empty());
-    assert!(results
-        .items
-        .iter()
-        .any(|e| e.tags.contains(&"rust".to_string())));
+    let renamed = indexer
+        .update_collection(&collection.id, Some("Project Y"), Some(String::new()))
+        .await
+        .unwrap();
+    assert_eq!(renamed.name, "Project Y");
+    assert_eq!(renamed.description, None);
+
+    // Deleting a collection keeps its clips
+    indexer.delete_collection(&collection.id).await.unwrap();
+    assert!(indexer.list_collections().await.unwrap().is_empty());
+    assert!(indexer.get_entry(&clip_ids[2]).await.is_ok());
+    assert!(matches!(
+        indexer.get_collection(&collection.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
 }
 
 #[tokio::test]
-async fn test_cleanup_entries_no_tags() {
+async fn test_templates() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add entry with no tags
-    let entry_no_tags = indexer
-        .add_entry_from_text("No tags entry".to_string(), vec![], None, None)
+    let template = indexer
+        .create_template(
+            " Reply ",
+            Some("Email reply".to_string()),
+            "Hi {{name}},\n\nThanks for {{topic|your email}}.".to_string(),
+            vec!["email".to_string()],
+        )
+        .await
+        .expect("Failed to create template");
+    assert_eq!(template.name, "Reply");
+    assert_eq!(template.placeholders(), ["name", "topic"]);
+    assert!(matches!(
+        indexer
+            .create_template("Reply", None, "Other".to_string(), vec![])
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        indexer
+            .create_template("Blank", None, "  ".to_string(), vec![])
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    let values = HashMap::from([("name".to_string(), "Ann".to_string())]);
+    let fetched = indexer.get_template(&template.id).await.unwrap();
+    assert_eq!(
+        fetched.render(&values).unwrap(),
+        "Hi Ann,\n\nThanks for your email."
+    );
+    assert!(matches!(
+        fetched.render(&HashMap::new()),
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    let updated = indexer
+        .update_template(
+            &template.id,
+            Some("Short reply"),
+            Some(String::new()),
+            Some("Thanks, {{name}}!".to_string()),
+            None,
+        )
         .await
         .unwrap();
+    assert_eq!(updated.name, "Short reply");
+    assert_eq!(updated.description, None);
+    assert_eq!(updated.tags, ["email"]);
+    assert_eq!(updated.render(&values).unwrap(), "Thanks, Ann!");
+
+    indexer.delete_template(&template.id).await.unwrap();
+    assert!(indexer.list_templates().await.unwrap().is_empty());
+    assert!(matches!(
+        indexer.get_template(&template.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
+}
 
-    // Add entry with meaningful tag
-    let entry_with_tag = indexer
+#[tokio::test]
+async fn test_split_entry() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let source = indexer
         .add_entry_from_text(
-            "With tag entry".to_string(),
-            vec!["important".to_string()],
-            None,
+            "milk\neggs\n\nbread".to_string(),
+            vec!["todo".to_string()],
             None,
+            Some("en".to_string()),
         )
         .await
         .unwrap();
+    let parts = ["milk", "eggs", " ", "bread"].map(String::from).to_vec();
+
+    let entries = indexer
+        .split_entry(&source.id, parts.clone(), true)
+        .await
+        .expect("Failed to split entry");
+    let contents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+    assert_eq!(contents, ["milk", "eggs", "bread"]);
+    assert!(entries[0].created_at < entries[1].created_at);
+    for entry in &entries {
+        let stored = indexer.get_entry(&entry.id).await.unwrap();
+        assert_eq!(stored.tags, ["todo"]);
+        assert_eq!(stored.language.as_deref(), Some("en"));
+    }
+    assert!(indexer.get_entry(&source.id).await.is_ok());
+
+    // One part is not a split
+    assert!(matches!(
+        indexer
+            .split_entry(&source.id, vec!["milk".to_string()], true)
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    // Splitting without keeping the source deletes it
+    indexer.split_entry(&source.id, parts, false).await.unwrap();
+    assert!(matches!(
+        indexer.get_entry(&source.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
+    let page = indexer
+        .list_entries(SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(page.total, 6);
+}
 
-    // Cleanup should delete the entry with no tags
-    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
+#[tokio::test]
+async fn test_append_to_entry() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    assert_eq!(deleted_ids.len(), 1);
-    assert!(deleted_ids.contains(&entry_no_tags.id));
+    let entry = indexer
+        .add_entry_from_text("first line".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let appended = indexer
+        .append_to_entry(&entry.id, "second line", "\n")
+        .await
+        .expect("Failed to append to entry");
+    assert_eq!(appended.content, "first line\nsecond line");
+    assert!(appended.updated_at > entry.updated_at);
 
-    // Verify entry with no tags is deleted
-    let result = indexer.get_entry(&entry_no_tags.id).await;
-    assert!(result.is_err());
+    // The appended text is searchable
+    let result = indexer
+        .search_entries("second", SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].id, entry.id);
 
-    // Verify entry with tag still exists
-    let result = indexer.get_entry(&entry_with_tag.id).await;
-    assert!(result.is_ok());
+    assert!(matches!(
+        indexer.append_to_entry("missing", "text", "\n").await,
+        Err(IndexerError::NotFound(_))
+    ));
 }
 
 #[tokio::test]
-async fn test_cleanup_entries_only_host_tag() {
+async fn test_set_enrichment() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add entry with only host tag
-    let entry_host_only = indexer
+    let entry = indexer
         .add_entry_from_text(
-            "Host only entry".to_string(),
-            vec!["$host:my-machine".to_string()],
+            "fn main() {}".to_string(),
+            vec!["code".to_string()],
             None,
             None,
         )
         .await
         .unwrap();
+    assert_eq!(entry.summary, None);
+    assert!(entry.suggested_tags.is_empty());
 
-    // Add entry with host tag and other tag
-    let entry_host_and_other = indexer
-        .add_entry_from_text(
-            "Host and other entry".to_string(),
-            vec!["$host:my-machine".to_string(), "important".to_string()],
-            None,
-            None,
+    let enriched = indexer
+        .set_enrichment(
+            &entry.id,
+            Some("An empty Rust program".to_string()),
+            vec!["rust".to_string()],
         )
         .await
-        .unwrap();
+        .expect("Failed to set enrichment");
+    assert_eq!(enriched.summary.as_deref(), Some("An empty Rust program"));
+    assert_eq!(enriched.suggested_tags, vec!["rust"]);
+    // Suggested tags are kept apart from the clip's tags
+    assert_eq!(enriched.tags, vec!["code"]);
 
-    // Cleanup should delete entry with only host tag
-    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
+    let fetched = indexer.get_entry(&entry.id).await.unwrap();
+    assert_eq!(fetched.summary, enriched.summary);
+    assert_eq!(fetched.suggested_tags, enriched.suggested_tags);
 
-    assert_eq!(deleted_ids.len(), 1);
-    assert!(deleted_ids.contains(&entry_host_only.id));
+    let cleared = indexer
+        .set_enrichment(&entry.id, None, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(cleared.summary, None);
+    assert!(cleared.suggested_tags.is_empty());
+}
 
-    // Verify entry with only host tag is deleted
-    let result = indexer.get_entry(&entry_host_only.id).await;
+#[tokio::test]
+async fn test_detected_language() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry = indexer
+        .add_entry_from_text("ok".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    assert_eq!(entry.detected_language, None);
+
+    // Detected again once the content is long enough to tell
+    let appended = indexer
+        .append_to_entry(
+            &entry.id,
+            "thanks, the meeting is moved to Thursday afternoon",
+            ", ",
+        )
+        .await
+        .unwrap();
+    assert_eq!(appended.detected_language.as_deref(), Some("en"));
+    let fetched = indexer.get_entry(&entry.id).await.unwrap();
+    assert_eq!(fetched.detected_language, appended.detected_language);
+}
+
+#[tokio::test]
+async fn test_set_canonical_url() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry = indexer
+        .add_entry_from_text(
+            "https://example.com/page?utm_source=feed".to_string(),
+            vec![],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(entry.canonical_url, None);
+
+    let canonicalized = indexer
+        .set_canonical_url(&entry.id, Some("https://example.com/page".to_string()))
+        .await
+        .expect("Failed to set canonical URL");
+    assert_eq!(
+        canonicalized.canonical_url.as_deref(),
+        Some("https://example.com/page")
+    );
+    // The original URL is kept
+    assert_eq!(
+        canonicalized.content,
+        "https://example.com/page?utm_source=feed"
+    );
+    let fetched = indexer.get_entry(&entry.id).await.unwrap();
+    assert_eq!(fetched.canonical_url, canonicalized.canonical_url);
+
+    // Appending makes it more than a URL
+    let appended = indexer
+        .append_to_entry(&entry.id, "see this", "\n")
+        .await
+        .unwrap();
+    assert_eq!(appended.canonical_url, None);
+
+    assert!(matches!(
+        indexer.set_canonical_url("nonexistent", None).await,
+        Err(IndexerError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_search_with_combined_filters() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let now = Utc::now();
+
+    // Add entries
+    indexer
+        .add_entry_from_text(
+            "Rust is awesome".to_string(),
+            vec!["rust".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    indexer
+        .add_entry_from_text(
+            "Rust programming tips".to_string(),
+            vec!["rust".to_string(), "tips".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    indexer
+        .add_entry_from_text(
+            "Python programming".to_string(),
+            vec!["python".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Wait for indexing
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    // Search with tag filter
+    let filters = SearchFilters::new()
+        .with_tags(vec!["rust".to_string()])
+        .with_date_range(now - Duration::hours(1), now + Duration::hours(1));
+    let paging_params = PagingParams::default();
+
+    let results = indexer
+        .search_entries("programming", filters, paging_params)
+        .await
+        .expect("Failed to search");
+
+    assert!(!results.items.is_empty());
+    assert!(results
+        .items
+        .iter()
+        .any(|e| e.tags.contains(&"rust".to_string())));
+}
+
+#[tokio::test]
+async fn test_search_query_operators() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let report = indexer
+        .add_entry_from_text(
+            "Quarterly report draft for the team".to_string(),
+            vec!["work".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_text(
+            "Report on private finances".to_string(),
+            vec!["work".to_string(), "private".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let temp_file = TempDir::new().unwrap();
+    let file_path = temp_file.path().join("report.txt");
+    fs::write(&file_path, "Attached report for the team").unwrap();
+    let attached = indexer
+        .add_entry_from_file(&file_path, vec!["work".to_string()], None)
+        .await
+        .unwrap();
+
+    // Wait for indexing
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let search = |query: &'static str| {
+        indexer.search_entries(query, SearchFilters::new(), PagingParams::default())
+    };
+
+    let results = search("report tag:work -tag:private").await.unwrap();
+    assert_eq!(results.total, 2);
+    assert!(
+        results
+            .items
+            .iter()
+            .all(|e| !e.tags.contains(&"private".to_string()))
+    );
+
+    let results = search("\"report draft\"").await.unwrap();
+    assert_eq!(results.total, 1);
+    assert_eq!(results.items[0].id, report.id);
+
+    let results = search("tag:work has:attachment").await.unwrap();
+    assert_eq!(results.total, 1);
+    assert_eq!(results.items[0].id, attached.id);
+
+    let results = search("report -has:attachment before:2000-01-01")
+        .await
+        .unwrap();
+    assert_eq!(results.total, 0);
+
+    let results = search("after:2000-01-01 -tag:private").await.unwrap();
+    assert_eq!(results.total, 2);
+
+    let result = search("report before:yesterday").await;
+    assert!(matches!(result, Err(IndexerError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_fuzzy_search() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry = indexer
+        .add_entry_from_text(
+            "Please receive the package".to_string(),
+            vec!["mail".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_text("Unrelated text".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    // Wait for indexing
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let exact = indexer
+        .search_entries("recieve", SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(exact.total, 0);
+
+    let fuzzy = indexer
+        .search_entries_with_highlight(
+            "recieve tag:mail",
+            SearchFilters::new(),
+            PagingParams::default(),
+            Some(HighlightOptions::new("<b>".to_string(), "</b>".to_string())),
+            Some(FuzzyOptions::default()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(fuzzy.total, 1);
+    assert_eq!(fuzzy.items[0].entry.id, entry.id);
+    assert_eq!(
+        fuzzy.items[0].highlighted_content.as_deref(),
+        Some("Please <b>receive</b> the package")
+    );
+
+    let too_far = indexer
+        .search_entries_with_highlight(
+            "reseeve",
+            SearchFilters::new(),
+            PagingParams::default(),
+            None,
+            Some(FuzzyOptions::new(1)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(too_far.total, 0);
+}
+
+#[tokio::test]
+async fn test_fuzzy_search_sorted_by_relevance() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let exact = indexer
+        .add_entry_from_text("Please receive the package".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let typo = indexer
+        .add_entry_from_text("Please recieve the pakage".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_text("Unrelated text".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    // Wait for indexing
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let by_date = indexer
+        .search_entries_with_highlight(
+            "receive package",
+            SearchFilters::new(),
+            PagingParams::default(),
+            None,
+            Some(FuzzyOptions::default()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_date.total, 2);
+    assert_eq!(by_date.items[0].entry.id, typo.id);
+
+    let by_relevance = indexer
+        .search_entries_with_highlight(
+            "receive package",
+            SearchFilters::new().with_sort(SortOrder::Relevance),
+            PagingParams::default(),
+            None,
+            Some(FuzzyOptions::default()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_relevance.total, 2);
+    assert_eq!(by_relevance.items[0].entry.id, exact.id);
+    assert_eq!(by_relevance.items[1].entry.id, typo.id);
+    assert!(by_relevance.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn test_search_sorted_by_relevance() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let best_match = indexer
+        .add_entry_from_text(
+            "kubernetes kubernetes kubernetes".to_string(),
+            vec![],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let newest = indexer
+        .add_entry_from_text(
+            "Notes from a long meeting about budgets, hiring, the office move and, briefly, kubernetes"
+                .to_string(),
+            vec![],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // BM25 only ranks terms that are rare in the library; one that is in
+    // most clips gets a negative weight
+    for content in ["Grocery list", "Dentist on Friday", "Flight details"] {
+        indexer
+            .add_entry_from_text(content.to_string(), vec![], None, None)
+            .await
+            .unwrap();
+    }
+
+    // Wait for indexing
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let by_date = indexer
+        .search_entries("kubernetes", SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(by_date.total, 2);
+    assert_eq!(by_date.items[0].id, newest.id);
+
+    let by_relevance = indexer
+        .search_entries(
+            "kubernetes",
+            SearchFilters::new().with_sort(SortOrder::Relevance),
+            PagingParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_relevance.total, 2);
+    assert_eq!(by_relevance.items[0].id, best_match.id);
+}
+
+#[tokio::test]
+async fn test_cleanup_entries_no_tags() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add entry with no tags
+    let entry_no_tags = indexer
+        .add_entry_from_text("No tags entry".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    // Add entry with meaningful tag
+    let entry_with_tag = indexer
+        .add_entry_from_text(
+            "With tag entry".to_string(),
+            vec!["important".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Cleanup should delete the entry with no tags
+    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
+
+    assert_eq!(deleted_ids.len(), 1);
+    assert!(deleted_ids.contains(&entry_no_tags.id));
+
+    // Verify entry with no tags is deleted
+    let result = indexer.get_entry(&entry_no_tags.id).await;
+    assert!(result.is_err());
+
+    // Verify entry with tag still exists
+    let result = indexer.get_entry(&entry_with_tag.id).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_cleanup_entries_only_host_tag() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add entry with only host tag
+    let entry_host_only = indexer
+        .add_entry_from_text(
+            "Host only entry".to_string(),
+            vec!["$host:my-machine".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Add entry with host tag and other tag
+    let entry_host_and_other = indexer
+        .add_entry_from_text(
+            "Host and other entry".to_string(),
+            vec!["$host:my-machine".to_string(), "important".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Cleanup should delete entry with only host tag
+    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
+
+    assert_eq!(deleted_ids.len(), 1);
+    assert!(deleted_ids.contains(&entry_host_only.id));
+
+    // Verify entry with only host tag is deleted
+    let result = indexer.get_entry(&entry_host_only.id).await;
     assert!(result.is_err());
 
     // Verify entry with host + other tag still exists
@@ -557,6 +1465,221 @@ async fn test_cleanup_entries_with_file_attachment() {
     assert!(file_content.is_err());
 }
 
+#[tokio::test]
+async fn test_check_storage() {
+    let (indexer, _db_dir, storage_dir) = setup_test_indexer().await;
+
+    indexer.check_storage().await.unwrap();
+
+    // The check leaves nothing behind
+    assert_eq!(fs::read_dir(storage_dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_library_stats() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    assert_eq!(
+        indexer.library_stats().await.unwrap(),
+        LibraryStats::default()
+    );
+
+    indexer
+        .add_entry_from_text("Text".to_string(), vec!["a".to_string()], None, None)
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_file_content(
+            bytes::Bytes::from_static(b"file"),
+            "file.txt".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+            None,
+        )
+        .await
+        .unwrap();
+    indexer.create_collection("Project", None).await.unwrap();
+    indexer.create_paired_device("laptop").await.unwrap();
+
+    let stats = indexer.library_stats().await.unwrap();
+    assert_eq!(stats.clips, 2);
+    assert_eq!(stats.attachments, 1);
+    assert_eq!(stats.tags, 2);
+    assert_eq!(stats.collections, 1);
+    assert_eq!(stats.short_urls, 0);
+    assert_eq!(stats.paired_devices, 1);
+}
+
+#[tokio::test]
+async fn test_rebuild_search_indexes() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    indexer
+        .add_entry_from_text(
+            "Rust programming is fun".to_string(),
+            vec!["rust".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    indexer.rebuild_search_indexes().await.unwrap();
+
+    let results = indexer
+        .search_entries("programming", SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(results.total, 1);
+}
+
+#[tokio::test]
+async fn test_search_attachment_text() {
+    use std::io::Write;
+
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    writer
+        .start_file(
+            "notes/minutes.txt",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+    writer
+        .write_all(b"Quarterly budget approved by the committee")
+        .unwrap();
+    let archive = writer.finish().unwrap().into_inner();
+
+    let entry = indexer
+        .add_entry_from_file_content(
+            bytes::Bytes::from(archive),
+            "meeting.zip".to_string(),
+            vec![],
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(entry.content, "meeting.zip");
+
+    let search = |query: &'static str| {
+        indexer.search_entries(query, SearchFilters::new(), PagingParams::default())
+    };
+    let results = search("committee").await.unwrap();
+    assert_eq!(results.items.len(), 1);
+    assert_eq!(results.items[0].id, entry.id);
+
+    // Still found after the notes change
+    indexer
+        .update_entry(
+            &entry.id,
+            None,
+            Some("Budget review".to_string()),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let results = search("committee").await.unwrap();
+    assert_eq!(results.items.len(), 1);
+    let results = search("review").await.unwrap();
+    assert_eq!(results.items.len(), 1);
+}
+
+#[tokio::test]
+async fn test_add_entry_from_text_truncated() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Short text is stored as usual
+    let entry = indexer
+        .add_entry_from_text_truncated("short".to_string(), vec![], None, None, None, 16)
+        .await
+        .unwrap();
+    assert!(!entry.truncated);
+    assert!(entry.file_attachment.is_none());
+
+    let content = format!("{} tail-marker", "héllo ".repeat(10));
+    let entry = indexer
+        .add_entry_from_text_truncated(
+            content.clone(),
+            vec!["log".to_string()],
+            Some("Build output".to_string()),
+            None,
+            None,
+            16,
+        )
+        .await
+        .unwrap();
+    assert!(entry.truncated);
+    // Cut at a character boundary within the limit
+    assert_eq!(entry.content, "héllo héllo h");
+    assert_eq!(entry.original_filename.as_deref(), Some("clip.txt"));
+
+    let stored = indexer.get_entry(&entry.id).await.unwrap();
+    assert!(stored.truncated);
+    let file = indexer
+        .get_file_content(stored.file_attachment.as_deref().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(file, content.as_bytes());
+
+    // Only the kept text is searchable, also after the notes change
+    let search = |query: &'static str| {
+        indexer.search_entries(query, SearchFilters::new(), PagingParams::default())
+    };
+    assert_eq!(search("output").await.unwrap().items.len(), 1);
+    assert_eq!(search("marker").await.unwrap().items.len(), 0);
+    indexer
+        .update_entry(
+            &entry.id,
+            None,
+            Some("Release build".to_string()),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(search("marker").await.unwrap().items.len(), 0);
+    assert_eq!(search("release").await.unwrap().items.len(), 1);
+}
+
+#[tokio::test]
+async fn test_custom_extractors() {
+    struct Reversed;
+    impl TextExtractor for Reversed {
+        fn extract(&self, filename: &str, content: &[u8]) -> Option<String> {
+            filename
+                .ends_with(".rev")
+                .then(|| String::from_utf8_lossy(content).chars().rev().collect())
+        }
+    }
+
+    let db_dir = TempDir::new().unwrap();
+    let storage_dir = TempDir::new().unwrap();
+    let indexer = ClipperIndexer::new(db_dir.path(), storage_dir.path())
+        .await
+        .unwrap()
+        .with_extractors(ExtractorPipeline::empty().with_extractor(Reversed));
+
+    let entry = indexer
+        .add_entry_from_file_content(
+            bytes::Bytes::from_static(b"elbissimda"),
+            "secret.rev".to_string(),
+            vec![],
+            None,
+        )
+        .await
+        .unwrap();
+
+    let results = indexer
+        .search_entries("admissible", SearchFilters::new(), PagingParams::default())
+        .await
+        .unwrap();
+    assert_eq!(results.items.len(), 1);
+    assert_eq!(results.items[0].id, entry.id);
+}
+
 #[tokio::test]
 async fn test_cleanup_entries_none_to_delete() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
@@ -582,19 +1705,186 @@ async fn test_cleanup_entries_none_to_delete() {
         .await
         .unwrap();
 
-    // Cleanup should delete nothing
-    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
-
-    assert!(deleted_ids.is_empty());
-
-    // Verify all entries still exist
-    let paging = PagingParams::default();
-    let all_entries = indexer
-        .list_entries(SearchFilters::new(), paging)
+    // Cleanup should delete nothing
+    let deleted_ids = indexer.cleanup_entries(None, None).await.unwrap();
+
+    assert!(deleted_ids.is_empty());
+
+    // Verify all entries still exist
+    let paging = PagingParams::default();
+    let all_entries = indexer
+        .list_entries(SearchFilters::new(), paging)
+        .await
+        .unwrap();
+
+    assert_eq!(all_entries.total, 2);
+}
+
+#[tokio::test]
+async fn test_cleanup_entries_where() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let screenshot = indexer
+        .add_entry_from_text(
+            "Screenshot".to_string(),
+            vec!["$screenshot".to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let work = indexer
+        .add_entry_from_text("Work".to_string(), vec!["work".to_string()], None, None)
+        .await
+        .unwrap();
+
+    // Entries created after the end date aren't considered
+    let deleted_ids = indexer
+        .cleanup_entries_where(Utc::now() - Duration::hours(1), |_, _| true)
+        .await
+        .unwrap();
+    assert!(deleted_ids.is_empty());
+
+    let deleted_ids = indexer
+        .cleanup_entries_where(Utc::now(), |tags, _| {
+            tags.contains(&"$screenshot".to_string())
+        })
+        .await
+        .unwrap();
+    assert_eq!(deleted_ids, vec![screenshot.id.clone()]);
+
+    assert!(indexer.get_entry(&screenshot.id).await.is_err());
+    assert!(indexer.get_entry(&work.id).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_find_cleanup_entries() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let untagged = indexer
+        .add_entry_from_text("Untagged".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let tagged = indexer
+        .add_entry_from_text("Tagged".to_string(), vec!["work".to_string()], None, None)
+        .await
+        .unwrap();
+
+    let entries = indexer.find_cleanup_entries(None, None).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, untagged.id);
+
+    let entries = indexer
+        .find_entries_where(Utc::now(), |tags, _| tags.contains(&"work".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, tagged.id);
+
+    // Nothing was deleted
+    assert!(indexer.get_entry(&untagged.id).await.is_ok());
+    assert!(indexer.get_entry(&tagged.id).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_export_and_delete_entries() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+    let archive_dir = TempDir::new().unwrap();
+    let archive_path = archive_dir.path().join("archive.tar.gz");
+
+    let untagged = indexer
+        .add_entry_from_text("Untagged".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    let tagged = indexer
+        .add_entry_from_text("Tagged".to_string(), vec!["work".to_string()], None, None)
+        .await
+        .unwrap();
+
+    let entries = indexer.find_cleanup_entries(None, None).await.unwrap();
+    indexer
+        .export_entries_to_file(&entries, &archive_path)
+        .await
+        .unwrap();
+    let deleted_ids = indexer.delete_entries(&entries).await.unwrap();
+    assert_eq!(deleted_ids, vec![untagged.id.clone()]);
+    assert!(indexer.get_entry(&untagged.id).await.is_err());
+    assert!(indexer.get_entry(&tagged.id).await.is_ok());
+
+    // The archive holds only the deleted clip and restores it
+    let result = indexer
+        .import_archive_from_file(&archive_path)
+        .await
+        .unwrap();
+    assert_eq!(result.imported_ids, vec![untagged.id.clone()]);
+    assert_eq!(result.skipped_count, 0);
+    assert_eq!(
+        indexer.get_entry(&untagged.id).await.unwrap().content,
+        "Untagged"
+    );
+}
+
+#[tokio::test]
+async fn test_find_tagged_entries() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let sensitive = indexer
+        .add_entry_from_text(
+            "hunter2".to_string(),
+            vec![SENSITIVE_TAG.to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    indexer
+        .add_entry_from_text("Public".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    // Entries created after the end date aren't found
+    let entries = indexer
+        .find_tagged_entries(SENSITIVE_TAG, Utc::now() - Duration::hours(1))
+        .await
+        .unwrap();
+    assert!(entries.is_empty());
+
+    let entries = indexer
+        .find_tagged_entries(SENSITIVE_TAG, Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, sensitive.id);
+}
+
+#[tokio::test]
+async fn test_export_all_except_tag() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+    let archive_dir = TempDir::new().unwrap();
+    let archive_path = archive_dir.path().join("export.tar.gz");
+
+    indexer
+        .add_entry_from_text(
+            "hunter2".to_string(),
+            vec![SENSITIVE_TAG.to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let public = indexer
+        .add_entry_from_text("Public".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+
+    indexer
+        .export_all_except_tag_to_file(SENSITIVE_TAG, &archive_path)
         .await
         .unwrap();
 
-    assert_eq!(all_entries.total, 2);
+    let (other, _other_db_dir, _other_storage_dir) = setup_test_indexer().await;
+    let result = other.import_archive_from_file(&archive_path).await.unwrap();
+    assert_eq!(result.imported_ids, vec![public.id.clone()]);
 }
 
 // ==================== Short URL Tests ====================
@@ -930,196 +2220,716 @@ async fn test_tags_synced_on_add_entry() {
         .await
         .expect("Failed to add entry");
 
-    // List tags and verify they were synced
-    let tags = indexer
-        .list_tags(PagingParams::default())
+    // List tags and verify they were synced
+    let tags = indexer
+        .list_tags(PagingParams::default(), None)
+        .await
+        .expect("Failed to list tags");
+
+    assert_eq!(tags.total, 2);
+    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
+    assert!(tag_texts.contains(&"rust"));
+    assert!(tag_texts.contains(&"programming"));
+}
+
+#[tokio::test]
+async fn test_tags_synced_on_update_entry() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add an entry with initial tags
+    let entry = indexer
+        .add_entry_from_text(
+            "Test content".to_string(),
+            vec!["initial".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+
+    // Update with new tags
+    indexer
+        .update_entry(
+            &entry.id,
+            Some(vec!["initial".to_string(), "updated".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to update entry");
+
+    // List tags and verify new tag was synced
+    let tags = indexer
+        .list_tags(PagingParams::default(), None)
+        .await
+        .expect("Failed to list tags");
+
+    assert_eq!(tags.total, 2);
+    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
+    assert!(tag_texts.contains(&"initial"));
+    assert!(tag_texts.contains(&"updated"));
+}
+
+#[tokio::test]
+async fn test_tags_deduplication() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add multiple entries with overlapping tags
+    indexer
+        .add_entry_from_text(
+            "Test 1".to_string(),
+            vec!["common".to_string(), "unique1".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry 1");
+
+    indexer
+        .add_entry_from_text(
+            "Test 2".to_string(),
+            vec!["common".to_string(), "unique2".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry 2");
+
+    // List tags - "common" should only appear once
+    let tags = indexer
+        .list_tags(PagingParams::default(), None)
+        .await
+        .expect("Failed to list tags");
+
+    assert_eq!(tags.total, 3); // common, unique1, unique2
+    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
+    assert!(tag_texts.contains(&"common"));
+    assert!(tag_texts.contains(&"unique1"));
+    assert!(tag_texts.contains(&"unique2"));
+}
+
+#[tokio::test]
+async fn test_search_tags() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add entries with various tags
+    indexer
+        .add_entry_from_text(
+            "Test".to_string(),
+            vec![
+                "rust-programming".to_string(),
+                "typescript".to_string(),
+                "database".to_string(),
+            ],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+
+    // Search for tags containing "rust"
+    let results = indexer
+        .search_tags("rust", PagingParams::default(), None)
+        .await
+        .expect("Failed to search tags");
+
+    assert!(results.total >= 1);
+    let tag_texts: Vec<&str> = results.items.iter().map(|t| t.text.as_str()).collect();
+    assert!(tag_texts.contains(&"rust-programming"));
+}
+
+#[tokio::test]
+async fn test_get_tag_by_text() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add an entry with a tag
+    indexer
+        .add_entry_from_text(
+            "Test content".to_string(),
+            vec!["test-tag".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+
+    // Get the tag by text
+    let tag = indexer
+        .get_tag_by_text("test-tag")
+        .await
+        .expect("Failed to get tag");
+
+    assert_eq!(tag.text, "test-tag");
+}
+
+#[tokio::test]
+async fn test_get_tag_by_text_not_found() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Try to get a nonexistent tag
+    let result = indexer.get_tag_by_text("nonexistent").await;
+
+    assert!(result.is_err());
+    assert!(matches!(result.unwrap_err(), IndexerError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_list_tags_pagination() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    // Add an entry with many tags
+    let tags: Vec<String> = (0..25).map(|i| format!("tag{:02}", i)).collect();
+    indexer
+        .add_entry_from_text("Test".to_string(), tags, None, None)
+        .await
+        .expect("Failed to add entry");
+
+    // Test pagination
+    let page1 = indexer
+        .list_tags(PagingParams::new(1, 10), None)
+        .await
+        .expect("Failed to list tags page 1");
+
+    assert_eq!(page1.items.len(), 10);
+    assert_eq!(page1.total, 25);
+    assert_eq!(page1.page, 1);
+    assert_eq!(page1.total_pages, 3);
+
+    let page2 = indexer
+        .list_tags(PagingParams::new(2, 10), None)
+        .await
+        .expect("Failed to list tags page 2");
+
+    assert_eq!(page2.items.len(), 10);
+    assert_eq!(page2.page, 2);
+
+    let page3 = indexer
+        .list_tags(PagingParams::new(3, 10), None)
+        .await
+        .expect("Failed to list tags page 3");
+
+    assert_eq!(page3.items.len(), 5);
+    assert_eq!(page3.page, 3);
+}
+
+#[tokio::test]
+async fn test_rename_tag() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry1 = indexer
+        .add_entry_from_text(
+            "First".to_string(),
+            vec!["old".to_string(), "keep".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+    let entry2 = indexer
+        .add_entry_from_text(
+            "Second".to_string(),
+            vec!["old".to_string(), "new".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+    let entry3 = indexer
+        .add_entry_from_text("Third".to_string(), vec!["keep".to_string()], None, None)
+        .await
+        .expect("Failed to add entry");
+
+    let mut updated = indexer
+        .rename_tag("old", "new")
+        .await
+        .expect("Failed to rename tag");
+    updated.sort();
+    let mut expected = vec![entry1.id.clone(), entry2.id.clone()];
+    expected.sort();
+    assert_eq!(updated, expected);
+
+    let entry1 = indexer.get_entry(&entry1.id).await.unwrap();
+    assert_eq!(entry1.tags, vec!["new", "keep"]);
+
+    // The clip that already had the target tag must not get a duplicate
+    let entry2 = indexer.get_entry(&entry2.id).await.unwrap();
+    assert_eq!(entry2.tags, vec!["new"]);
+
+    let entry3 = indexer.get_entry(&entry3.id).await.unwrap();
+    assert_eq!(entry3.tags, vec!["keep"]);
+
+    // The tags table is updated as well
+    assert!(matches!(
+        indexer.get_tag_by_text("old").await,
+        Err(IndexerError::NotFound(_))
+    ));
+    assert!(indexer.get_tag_by_text("new").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_rename_tag_not_found() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let result = indexer.rename_tag("missing", "other").await;
+    assert!(matches!(result, Err(IndexerError::NotFound(_))));
+
+    let result = indexer.rename_tag("missing", "").await;
+    assert!(matches!(result, Err(IndexerError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_tag_stats() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    indexer
+        .add_entry_from_text(
+            "First".to_string(),
+            vec!["rust".to_string(), "code".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+    indexer
+        .add_entry_from_text("Second".to_string(), vec!["rust".to_string()], None, None)
+        .await
+        .expect("Failed to add entry");
+    indexer
+        .add_entry_from_text("Third".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to add entry");
+
+    let stats = indexer.tag_stats(None).await.expect("Failed to get tag stats");
+
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].text, "rust");
+    assert_eq!(stats[0].count, 2);
+    assert_eq!(stats[1].text, "code");
+    assert_eq!(stats[1].count, 1);
+}
+
+#[tokio::test]
+async fn test_activity_stats() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    for tags in [
+        vec!["rust", "$host:laptop"],
+        vec!["rust", "code"],
+        vec!["$host:laptop"],
+    ] {
+        indexer
+            .add_entry_from_text(
+                "Content".to_string(),
+                tags.into_iter().map(String::from).collect(),
+                None,
+                None,
+            )
+            .await
+            .expect("Failed to add entry");
+    }
+
+    let utc = chrono::FixedOffset::east_opt(0).unwrap();
+    let today = Utc::now().date_naive();
+    let stats = indexer
+        .activity_stats(today - Duration::days(6), utc, 1, None)
         .await
-        .expect("Failed to list tags");
+        .expect("Failed to get activity stats");
 
-    assert_eq!(tags.total, 2);
-    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
-    assert!(tag_texts.contains(&"rust"));
-    assert!(tag_texts.contains(&"programming"));
+    // A week of days, with all clips created today
+    assert_eq!(stats.days.len(), 7);
+    assert_eq!(stats.days[0].date, today - Duration::days(6));
+    assert_eq!(stats.days[6].date, today);
+    assert_eq!(stats.days[6].count, 3);
+    assert!(stats.days[..6].iter().all(|day| day.count == 0));
+
+    // $host: tags aren't counted, and only the top tag is returned
+    assert_eq!(stats.top_tags.len(), 1);
+    assert_eq!(stats.top_tags[0].text, "rust");
+    assert_eq!(stats.top_tags[0].count, 2);
+
+    // Clips created before the period aren't counted
+    let stats = indexer
+        .activity_stats(today + Duration::days(1), utc, 10, None)
+        .await
+        .expect("Failed to get activity stats");
+    assert!(stats.days.is_empty());
+    assert!(stats.top_tags.is_empty());
 }
 
+// ==================== Source Device Tests ====================
+
 #[tokio::test]
-async fn test_tags_synced_on_update_entry() {
+async fn test_source_device_from_host_tag() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add an entry with initial tags
     let entry = indexer
         .add_entry_from_text(
-            "Test content".to_string(),
-            vec!["initial".to_string()],
+            "From the laptop".to_string(),
+            vec!["work".to_string(), "$host:laptop".to_string()],
             None,
             None,
         )
         .await
         .expect("Failed to add entry");
+    assert_eq!(entry.source_device.as_deref(), Some("laptop"));
 
-    // Update with new tags
+    let untagged = indexer
+        .add_entry_from_text("No host".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to add entry");
+    assert_eq!(untagged.source_device, None);
+
+    // The source device stays when the host tag is removed
     indexer
-        .update_entry(
-            &entry.id,
-            Some(vec!["initial".to_string(), "updated".to_string()]),
-            None,
-            None,
-        )
+        .update_entry(&entry.id, Some(vec![]), None, None, None, None)
         .await
         .expect("Failed to update entry");
-
-    // List tags and verify new tag was synced
-    let tags = indexer
-        .list_tags(PagingParams::default())
+    let fetched = indexer
+        .get_entry(&entry.id)
         .await
-        .expect("Failed to list tags");
-
-    assert_eq!(tags.total, 2);
-    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
-    assert!(tag_texts.contains(&"initial"));
-    assert!(tag_texts.contains(&"updated"));
+        .expect("Failed to get entry");
+    assert_eq!(fetched.source_device.as_deref(), Some("laptop"));
 }
 
 #[tokio::test]
-async fn test_tags_deduplication() {
+async fn test_filter_by_source_device() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add multiple entries with overlapping tags
-    indexer
-        .add_entry_from_text(
-            "Test 1".to_string(),
-            vec!["common".to_string(), "unique1".to_string()],
-            None,
-            None,
-        )
-        .await
-        .expect("Failed to add entry 1");
+    for (content, host) in [
+        ("rust on laptop", "$host:laptop"),
+        ("rust on desktop", "$host:desktop"),
+        ("python on laptop", "$host:laptop"),
+    ] {
+        indexer
+            .add_entry_from_text(content.to_string(), vec![host.to_string()], None, None)
+            .await
+            .expect("Failed to add entry");
+    }
 
-    indexer
-        .add_entry_from_text(
-            "Test 2".to_string(),
-            vec!["common".to_string(), "unique2".to_string()],
-            None,
-            None,
-        )
+    let filters = SearchFilters::new().with_source_device("laptop");
+    let listed = indexer
+        .list_entries(filters.clone(), PagingParams::default())
         .await
-        .expect("Failed to add entry 2");
+        .expect("Failed to list entries");
+    assert_eq!(listed.total, 2);
+    assert!(
+        listed
+            .items
+            .iter()
+            .all(|e| e.source_device.as_deref() == Some("laptop"))
+    );
 
-    // List tags - "common" should only appear once
-    let tags = indexer
-        .list_tags(PagingParams::default())
+    let found = indexer
+        .search_entries("rust", filters, PagingParams::default())
         .await
-        .expect("Failed to list tags");
-
-    assert_eq!(tags.total, 3); // common, unique1, unique2
-    let tag_texts: Vec<&str> = tags.items.iter().map(|t| t.text.as_str()).collect();
-    assert!(tag_texts.contains(&"common"));
-    assert!(tag_texts.contains(&"unique1"));
-    assert!(tag_texts.contains(&"unique2"));
+        .expect("Failed to search entries");
+    assert_eq!(found.items.len(), 1);
+    assert_eq!(found.items[0].content, "rust on laptop");
 }
 
 #[tokio::test]
-async fn test_search_tags() {
+async fn test_visibility() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add entries with various tags
-    indexer
-        .add_entry_from_text(
-            "Test".to_string(),
-            vec![
-                "rust-programming".to_string(),
-                "typescript".to_string(),
-                "database".to_string(),
-            ],
-            None,
-            None,
-        )
+    let private = indexer
+        .add_entry_from_text("private note".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to add entry");
+    assert_eq!(private.visibility, Visibility::Private);
+    let shared = indexer
+        .add_entry_from_text("shared note".to_string(), vec![], None, None)
         .await
         .expect("Failed to add entry");
+    let shared = indexer
+        .set_visibility(&shared.id, Visibility::Shared)
+        .await
+        .expect("Failed to set visibility");
+    assert_eq!(shared.visibility, Visibility::Shared);
+    let fetched = indexer
+        .get_entry(&shared.id)
+        .await
+        .expect("Failed to get entry");
+    assert_eq!(fetched.visibility, Visibility::Shared);
 
-    // Search for tags containing "rust"
-    let results = indexer
-        .search_tags("rust", PagingParams::default())
+    let filters =
+        SearchFilters::new().with_visibility(vec![Visibility::Shared, Visibility::Public]);
+    let listed = indexer
+        .list_entries(filters.clone(), PagingParams::default())
         .await
-        .expect("Failed to search tags");
+        .expect("Failed to list entries");
+    assert_eq!(listed.total, 1);
+    assert_eq!(listed.items[0].id, shared.id);
 
-    assert!(results.total >= 1);
-    let tag_texts: Vec<&str> = results.items.iter().map(|t| t.text.as_str()).collect();
-    assert!(tag_texts.contains(&"rust-programming"));
+    let found = indexer
+        .search_entries("note", filters, PagingParams::default())
+        .await
+        .expect("Failed to search entries");
+    assert_eq!(found.items.len(), 1);
+    assert_eq!(found.items[0].id, shared.id);
 }
 
 #[tokio::test]
-async fn test_get_tag_by_text() {
+async fn test_aggregates_by_visibility() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add an entry with a tag
     indexer
         .add_entry_from_text(
-            "Test content".to_string(),
-            vec!["test-tag".to_string()],
+            "private note".to_string(),
+            vec!["secret-project".to_string(), "$host:laptop".to_string()],
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to add entry");
+    let shared = indexer
+        .add_entry_from_text(
+            "shared note".to_string(),
+            vec!["team".to_string(), "$host:desktop".to_string()],
             None,
             None,
         )
         .await
         .expect("Failed to add entry");
+    indexer
+        .set_visibility(&shared.id, Visibility::Shared)
+        .await
+        .expect("Failed to set visibility");
 
-    // Get the tag by text
-    let tag = indexer
-        .get_tag_by_text("test-tag")
+    let visible: &[Visibility] = &[Visibility::Shared, Visibility::Public];
+    let tag_texts = |tags: Vec<Tag>| -> Vec<String> {
+        let mut texts: Vec<String> = tags.into_iter().map(|tag| tag.text).collect();
+        texts.sort();
+        texts
+    };
+
+    let tags = indexer
+        .list_tags(PagingParams::default(), Some(visible))
         .await
-        .expect("Failed to get tag");
+        .expect("Failed to list tags");
+    assert_eq!(tags.total, 2);
+    assert_eq!(tag_texts(tags.items), ["$host:desktop", "team"]);
+    let all_tags = indexer
+        .list_tags(PagingParams::default(), None)
+        .await
+        .expect("Failed to list tags");
+    assert_eq!(all_tags.total, 4);
 
-    assert_eq!(tag.text, "test-tag");
+    let found = indexer
+        .search_tags("secret", PagingParams::default(), Some(visible))
+        .await
+        .expect("Failed to search tags");
+    assert_eq!(found.total, 0);
+
+    let stats = indexer
+        .tag_stats(Some(visible))
+        .await
+        .expect("Failed to get tag stats");
+    let mut stat_texts: Vec<&str> = stats.iter().map(|stat| stat.text.as_str()).collect();
+    stat_texts.sort();
+    assert_eq!(stat_texts, ["$host:desktop", "team"]);
+
+    let today = chrono::Utc::now().date_naive();
+    let activity = indexer
+        .activity_stats(today, chrono::FixedOffset::east_opt(0).unwrap(), 10, Some(visible))
+        .await
+        .expect("Failed to get activity stats");
+    assert_eq!(activity.days.iter().map(|day| day.count).sum::<usize>(), 1);
+    assert_eq!(activity.top_tags.len(), 1);
+    assert_eq!(activity.top_tags[0].text, "team");
+
+    let devices = indexer
+        .list_devices(Some(visible))
+        .await
+        .expect("Failed to list devices");
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].name, "desktop");
 }
 
 #[tokio::test]
-async fn test_get_tag_by_text_not_found() {
+async fn test_list_devices() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Try to get a nonexistent tag
-    let result = indexer.get_tag_by_text("nonexistent").await;
+    for (content, tags) in [
+        ("one", vec!["$host:laptop"]),
+        ("two", vec!["$host:desktop"]),
+        ("three", vec!["$host:laptop"]),
+        ("four", vec![]),
+    ] {
+        indexer
+            .add_entry_from_text(
+                content.to_string(),
+                tags.into_iter().map(String::from).collect(),
+                None,
+                None,
+            )
+            .await
+            .expect("Failed to add entry");
+        // Keep the creation times apart so last_seen orders the devices
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
 
-    assert!(result.is_err());
-    assert!(matches!(result.unwrap_err(), IndexerError::NotFound(_)));
+    let devices = indexer.list_devices(None).await.expect("Failed to list devices");
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0].name, "laptop");
+    assert_eq!(devices[0].clip_count, 2);
+    assert_eq!(devices[1].name, "desktop");
+    assert_eq!(devices[1].clip_count, 1);
+    assert!(devices[0].last_seen > devices[1].last_seen);
 }
 
 #[tokio::test]
-async fn test_list_tags_pagination() {
+async fn test_paired_devices() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
 
-    // Add an entry with many tags
-    let tags: Vec<String> = (0..25).map(|i| format!("tag{:02}", i)).collect();
-    indexer
-        .add_entry_from_text("Test".to_string(), tags, None, None)
+    let (device, token) = indexer
+        .create_paired_device("  laptop  ")
         .await
-        .expect("Failed to add entry");
+        .expect("Failed to pair device");
+    assert_eq!(device.name, "laptop");
+    assert!(device.last_used_at.is_none());
 
-    // Test pagination
-    let page1 = indexer
-        .list_tags(PagingParams::new(1, 10))
+    let authenticated = indexer
+        .authenticate_paired_device(&token)
         .await
-        .expect("Failed to list tags page 1");
+        .expect("Failed to authenticate")
+        .expect("Token should belong to the device");
+    assert_eq!(authenticated.id, device.id);
+    assert!(authenticated.last_used_at.is_some());
 
-    assert_eq!(page1.items.len(), 10);
-    assert_eq!(page1.total, 25);
-    assert_eq!(page1.page, 1);
-    assert_eq!(page1.total_pages, 3);
+    assert!(
+        indexer
+            .authenticate_paired_device("not-a-token")
+            .await
+            .expect("Failed to authenticate")
+            .is_none()
+    );
 
-    let page2 = indexer
-        .list_tags(PagingParams::new(2, 10))
+    let devices = indexer
+        .list_paired_devices()
         .await
-        .expect("Failed to list tags page 2");
+        .expect("Failed to list paired devices");
+    assert_eq!(devices.len(), 1);
+    assert!(devices[0].last_used_at.is_some());
 
-    assert_eq!(page2.items.len(), 10);
-    assert_eq!(page2.page, 2);
+    indexer
+        .revoke_paired_device(&device.id)
+        .await
+        .expect("Failed to revoke device");
+    assert!(
+        indexer
+            .authenticate_paired_device(&token)
+            .await
+            .expect("Failed to authenticate")
+            .is_none()
+    );
+    assert!(matches!(
+        indexer.revoke_paired_device(&device.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
+    assert!(matches!(
+        indexer.create_paired_device(" ").await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+}
 
-    let page3 = indexer
-        .list_tags(PagingParams::new(3, 10))
+#[tokio::test]
+async fn test_push_tokens() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let registered = indexer
+        .register_push_token(" abc123 ", PushPlatform::Apns, Some("iPhone"))
         .await
-        .expect("Failed to list tags page 3");
+        .expect("Failed to register push token");
+    assert_eq!(registered.token, "abc123");
+    assert_eq!(registered.device_name.as_deref(), Some("iPhone"));
 
-    assert_eq!(page3.items.len(), 5);
-    assert_eq!(page3.page, 3);
+    // Registering the same token again updates it in place
+    let updated = indexer
+        .register_push_token("abc123", PushPlatform::Fcm, None)
+        .await
+        .expect("Failed to register push token");
+    assert_eq!(updated.id, registered.id);
+    assert_eq!(updated.platform, PushPlatform::Fcm);
+    assert!(updated.device_name.is_none());
+
+    indexer
+        .register_push_token("def456", PushPlatform::Apns, None)
+        .await
+        .expect("Failed to register push token");
+    let tokens = indexer
+        .list_push_tokens()
+        .await
+        .expect("Failed to list push tokens");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[0].token, "def456");
+
+    indexer
+        .delete_push_token(&registered.id)
+        .await
+        .expect("Failed to delete push token");
+    assert_eq!(indexer.list_push_tokens().await.unwrap().len(), 1);
+    assert!(matches!(
+        indexer.delete_push_token(&registered.id).await,
+        Err(IndexerError::NotFound(_))
+    ));
+    assert!(matches!(
+        indexer
+            .register_push_token("  ", PushPlatform::Apns, None)
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_web_push_subscriptions() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+    let endpoint = "https://push.example.com/send/abc";
+
+    let subscription = indexer
+        .add_web_push_subscription(endpoint, "key-1", "auth-1")
+        .await
+        .expect("Failed to add subscription");
+    assert_eq!(subscription.endpoint, endpoint);
+
+    // Subscribing again with the same endpoint replaces the keys
+    let resubscribed = indexer
+        .add_web_push_subscription(endpoint, "key-2", "auth-2")
+        .await
+        .expect("Failed to add subscription");
+    assert_eq!(resubscribed.id, subscription.id);
+
+    let subscriptions = indexer
+        .list_web_push_subscriptions()
+        .await
+        .expect("Failed to list subscriptions");
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].p256dh, "key-2");
+    assert_eq!(subscriptions[0].auth, "auth-2");
+
+    assert!(matches!(
+        indexer
+            .add_web_push_subscription("http://push.example.com/send/abc", "key", "auth")
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        indexer
+            .add_web_push_subscription("https://push.example.com/send/def", "", "auth")
+            .await,
+        Err(IndexerError::InvalidInput(_))
+    ));
+
+    indexer
+        .delete_web_push_subscription(endpoint)
+        .await
+        .expect("Failed to delete subscription");
+    assert!(indexer.list_web_push_subscriptions().await.unwrap().is_empty());
+    assert!(matches!(
+        indexer.delete_web_push_subscription(endpoint).await,
+        Err(IndexerError::NotFound(_))
+    ));
 }
 
 // ==================== Language Persistence Tests ====================
@@ -1195,7 +3005,14 @@ async fn test_update_entry_language() {
 
     // Update the entry with a language
     let updated = indexer
-        .update_entry(&entry.id, None, None, Some("javascript".to_string()))
+        .update_entry(
+            &entry.id,
+            None,
+            None,
+            Some("javascript".to_string()),
+            None,
+            None,
+        )
         .await
         .expect("Failed to update entry");
 
@@ -1229,7 +3046,7 @@ async fn test_update_entry_change_language() {
 
     // Change the language
     let updated = indexer
-        .update_entry(&entry.id, None, None, Some("ruby".to_string()))
+        .update_entry(&entry.id, None, None, Some("ruby".to_string()), None, None)
         .await
         .expect("Failed to update entry");
 
@@ -1263,7 +3080,7 @@ async fn test_update_entry_clear_language() {
 
     // Clear the language by passing an empty string
     let updated = indexer
-        .update_entry(&entry.id, None, None, Some("".to_string()))
+        .update_entry(&entry.id, None, None, Some("".to_string()), None, None)
         .await
         .expect("Failed to update entry");
 
@@ -1278,6 +3095,116 @@ async fn test_update_entry_clear_language() {
     assert_eq!(retrieved.language, None);
 }
 
+#[tokio::test]
+async fn test_update_entry_color_and_icon() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry = indexer
+        .add_entry_from_text("Deploy checklist".to_string(), vec![], None, None)
+        .await
+        .expect("Failed to add entry");
+    assert_eq!(entry.color, None);
+    assert_eq!(entry.icon, None);
+
+    let updated = indexer
+        .update_entry(
+            &entry.id,
+            None,
+            None,
+            None,
+            Some("#E11D48".to_string()),
+            Some("🚀".to_string()),
+        )
+        .await
+        .expect("Failed to update entry");
+    assert_eq!(updated.color, Some("#e11d48".to_string()));
+    assert_eq!(updated.icon, Some("🚀".to_string()));
+
+    // Other updates keep the color and icon
+    let updated = indexer
+        .update_entry(
+            &entry.id,
+            Some(vec!["ops".to_string()]),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.color, Some("#e11d48".to_string()));
+
+    for color in ["#12", "rgb(0, 0, 0)", "red;"] {
+        assert!(matches!(
+            indexer
+                .update_entry(&entry.id, None, None, None, Some(color.to_string()), None)
+                .await,
+            Err(IndexerError::InvalidInput(_))
+        ));
+    }
+
+    // Empty strings clear the color and icon
+    let updated = indexer
+        .update_entry(
+            &entry.id,
+            None,
+            None,
+            None,
+            Some(String::new()),
+            Some(String::new()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.color, None);
+    assert_eq!(updated.icon, None);
+}
+
+#[tokio::test]
+async fn test_content_format() {
+    let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
+
+    let entry = indexer
+        .add_entry_from_text_with_format(
+            "# Release notes\n\n**Bold** change".to_string(),
+            vec![],
+            None,
+            None,
+            Some(ContentFormat::Markdown),
+        )
+        .await
+        .expect("Failed to add entry");
+    assert_eq!(entry.content_format, Some(ContentFormat::Markdown));
+
+    let retrieved = indexer.get_entry(&entry.id).await.unwrap();
+    assert_eq!(retrieved.content_format, Some(ContentFormat::Markdown));
+
+    let plain = indexer
+        .add_entry_from_text("Plain text".to_string(), vec![], None, None)
+        .await
+        .unwrap();
+    assert_eq!(plain.content_format, None);
+
+    let updated = indexer
+        .set_content_format(&plain.id, ContentFormat::Markdown)
+        .await
+        .unwrap();
+    assert_eq!(updated.content_format, Some(ContentFormat::Markdown));
+    assert!(updated.updated_at > plain.updated_at);
+
+    let updated = indexer
+        .set_content_format(&plain.id, ContentFormat::Plain)
+        .await
+        .unwrap();
+    assert_eq!(updated.content_format, Some(ContentFormat::Plain));
+
+    assert!(matches!(
+        indexer
+            .set_content_format("nonexistent", ContentFormat::Markdown)
+            .await,
+        Err(IndexerError::NotFound(_))
+    ));
+}
+
 #[tokio::test]
 async fn test_update_entry_language_preserves_other_fields() {
     let (indexer, _db_dir, _storage_dir) = setup_test_indexer().await;
@@ -1295,7 +3222,14 @@ async fn test_update_entry_language_preserves_other_fields() {
 
     // Update only the language (pass None for tags and notes)
     let updated = indexer
-        .update_entry(&entry.id, None, None, Some("javascript".to_string()))
+        .update_entry(
+            &entry.id,
+            None,
+            None,
+            Some("javascript".to_string()),
+            None,
+            None,
+        )
         .await
         .expect("Failed to update entry");
 
@@ -1332,6 +3266,8 @@ async fn test_update_entry_only_tags_preserves_language() {
             Some(vec!["updated".to_string()]),
             None,
             None,
+            None,
+            None,
         )
         .await
         .expect("Failed to update entry");
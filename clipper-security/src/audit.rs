@@ -0,0 +1,265 @@
+//! Checking permissions without changing them.
+//!
+//! [`audit_directory_recursive`] reports what [`secure_directory_recursive`]
+//! would fix, plus problems it can't fix, for when fixing isn't possible
+//! (e.g. on shared filesystems where chmod fails) or wanted.
+//!
+//! [`secure_directory_recursive`]: crate::secure_directory_recursive
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A problem found by [`audit_directory_recursive`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecurityIssue {
+    /// Other users have access: the mode isn't 0700 (directories) or 0600
+    /// (files). Unix only.
+    WrongMode {
+        path: PathBuf,
+        mode: u32,
+        expected: u32,
+    },
+    /// Owned by another user
+    WrongOwner { path: PathBuf },
+    /// A directory above the audited one that every user can read, though
+    /// the current user owns it, or write to without the sticky bit, which
+    /// lets anyone replace what's below it. Unix only.
+    InsecureAncestor { path: PathBuf, mode: u32 },
+    /// Couldn't be checked
+    Unreadable { path: PathBuf, error: String },
+}
+
+impl fmt::Display for SecurityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityIssue::WrongMode {
+                path,
+                mode,
+                expected,
+            } => write!(
+                f,
+                "{} has mode {:o}, expected {:o}",
+                path.display(),
+                mode,
+                expected
+            ),
+            SecurityIssue::WrongOwner { path } => {
+                write!(f, "{} is owned by another user", path.display())
+            }
+            SecurityIssue::InsecureAncestor { path, mode } => {
+                write!(f, "parent directory {} has mode {:o}", path.display(), mode)
+            }
+            SecurityIssue::Unreadable { path, error } => {
+                write!(f, "could not check {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+/// Result of [`audit_directory_recursive`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityReport {
+    /// Number of files and directories checked
+    pub checked: usize,
+    pub issues: Vec<SecurityIssue>,
+}
+
+impl SecurityReport {
+    /// Whether no issue was found
+    pub fn is_secure(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check a directory, its contents and its ancestors for problems without
+/// fixing anything.
+///
+/// On Windows, where DACLs aren't inspected (`secure_directory` always
+/// rewrites them), only ownership is checked.
+///
+/// # Arguments
+/// * `path` - Path to the directory to audit recursively
+///
+/// # Returns
+/// A report of the problems found; a missing directory has none.
+pub fn audit_directory_recursive(path: &Path) -> SecurityReport {
+    let mut report = SecurityReport::default();
+    if !path.exists() {
+        return report;
+    }
+
+    audit_ancestors(path, &mut report);
+    audit_recursive(path, &mut report);
+    report
+}
+
+fn audit_recursive(path: &Path, report: &mut SecurityReport) {
+    let is_dir = path.is_dir();
+    audit_item(path, is_dir, report);
+    if !is_dir {
+        return;
+    }
+
+    match std::fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                let entry_path = entry.path();
+                // Follows symlinks, like secure_directory_recursive
+                if entry_path.is_dir() || entry_path.is_file() {
+                    audit_recursive(&entry_path, report);
+                }
+            }
+        }
+        Err(e) => report.issues.push(SecurityIssue::Unreadable {
+            path: path.to_path_buf(),
+            error: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(unix)]
+fn audit_item(path: &Path, is_dir: bool, report: &mut SecurityReport) {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            report.issues.push(SecurityIssue::Unreadable {
+                path: path.to_path_buf(),
+                error: e.to_string(),
+            });
+            return;
+        }
+    };
+    report.checked += 1;
+
+    let mode = metadata.permissions().mode() & 0o777;
+    let expected = if is_dir { 0o700 } else { 0o600 };
+    if mode != expected {
+        report.issues.push(SecurityIssue::WrongMode {
+            path: path.to_path_buf(),
+            mode,
+            expected,
+        });
+    }
+    if metadata.uid() != current_uid() {
+        report.issues.push(SecurityIssue::WrongOwner {
+            path: path.to_path_buf(),
+        });
+    }
+}
+
+#[cfg(windows)]
+fn audit_item(path: &Path, _is_dir: bool, report: &mut SecurityReport) {
+    match crate::windows::is_owned_by_current_user(path) {
+        Ok(true) => {}
+        Ok(false) => report.issues.push(SecurityIssue::WrongOwner {
+            path: path.to_path_buf(),
+        }),
+        Err(e) => {
+            report.issues.push(SecurityIssue::Unreadable {
+                path: path.to_path_buf(),
+                error: e.to_string(),
+            });
+            return;
+        }
+    }
+    report.checked += 1;
+}
+
+#[cfg(not(any(unix, windows)))]
+fn audit_item(_path: &Path, _is_dir: bool, report: &mut SecurityReport) {
+    report.checked += 1;
+}
+
+#[cfg(unix)]
+fn audit_ancestors(path: &Path, report: &mut SecurityReport) {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    const WORLD_READABLE: u32 = 0o004;
+    const WORLD_WRITABLE: u32 = 0o002;
+    const STICKY: u32 = 0o1000;
+
+    let Ok(path) = std::fs::canonicalize(path) else {
+        return;
+    };
+    for ancestor in path.ancestors().skip(1) {
+        let Ok(metadata) = std::fs::metadata(ancestor) else {
+            continue;
+        };
+        let mode = metadata.permissions().mode() & 0o7777;
+        let replaceable = mode & WORLD_WRITABLE != 0 && mode & STICKY == 0;
+        // Directories like /home are world-readable by design; only flag
+        // the ones the user could restrict
+        let readable = mode & WORLD_READABLE != 0 && metadata.uid() == current_uid();
+        if replaceable || readable {
+            report.issues.push(SecurityIssue::InsecureAncestor {
+                path: ancestor.to_path_buf(),
+                mode: mode & 0o777,
+            });
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn audit_ancestors(_path: &Path, _report: &mut SecurityReport) {}
+
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: geteuid has no preconditions and cannot fail
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_audit_directory_recursive() {
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_audit");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("sub")).unwrap();
+        fs::write(temp_dir.join("sub/file.txt"), "x").unwrap();
+        fs::set_permissions(&temp_dir, fs::Permissions::from_mode(0o700)).unwrap();
+        fs::set_permissions(temp_dir.join("sub"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(
+            temp_dir.join("sub/file.txt"),
+            fs::Permissions::from_mode(0o600),
+        )
+        .unwrap();
+
+        let report = audit_directory_recursive(&temp_dir);
+        assert_eq!(report.checked, 3);
+        let wrong_modes: Vec<_> = report
+            .issues
+            .iter()
+            .filter(|issue| matches!(issue, SecurityIssue::WrongMode { .. }))
+            .collect();
+        assert_eq!(
+            wrong_modes,
+            vec![&SecurityIssue::WrongMode {
+                path: temp_dir.join("sub"),
+                mode: 0o755,
+                expected: 0o700,
+            }]
+        );
+        // Nothing was changed
+        let mode = fs::metadata(temp_dir.join("sub"))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777;
+        assert_eq!(mode, 0o755);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_audit_nonexistent() {
+        let report = audit_directory_recursive(Path::new("/nonexistent/path/12345"));
+        assert!(report.is_secure());
+        assert_eq!(report.checked, 0);
+    }
+}
@@ -4,11 +4,37 @@
 //! only accessible by the current user.
 //!
 //! - On Unix: Sets umask to 0o077 at process startup, and fixes existing permissions
-//! - On Windows: Sets DACL on directories to grant access only to the current user
+//! - On Windows: Sets DACL on directories to grant access only to the current user,
+//!   and provides the same DACL as security attributes for named pipes
+//!
+//! New files and directories can also be created with the right permissions
+//! from the start (`secure_create_file`, `secure_create_dir_all`,
+//! `secure_write`), leaving no window in which another user could open them.
+//! [`audit_directory_recursive`] reports problems without fixing them.
+//!
+//! Bearer tokens and trusted certificate fingerprints belong in a
+//! [`SecretStore`] rather than in settings files; see [`secrets`]. In memory
+//! they are held as [`SecretString`]s, which are zeroed on drop; see [`memory`].
+//!
+//! With the `tokio` feature, [`nonblocking`] has async variants that keep the
+//! filesystem work off the runtime threads.
 
-use std::io;
+use std::fs::File;
+use std::io::{self, Write};
 use std::path::Path;
 
+pub mod audit;
+pub mod memory;
+#[cfg(feature = "tokio")]
+pub mod nonblocking;
+pub mod secrets;
+
+pub use audit::{audit_directory_recursive, SecurityIssue, SecurityReport};
+pub use memory::{SecretBytes, SecretString};
+#[cfg(feature = "tokio")]
+pub use nonblocking::secure_directory_recursive_async;
+pub use secrets::{ClientSecrets, SecretBackend, SecretStore};
+
 /// Result of a security fix operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SecurityFixResult {
@@ -20,6 +46,9 @@ pub enum SecurityFixResult {
     NotFound,
 }
 
+#[cfg(windows)]
+pub use windows::UserOnlySecurityAttributes;
+
 /// Set restrictive umask on Unix systems.
 ///
 /// This should be called early in main() before any files are created.
@@ -109,6 +138,112 @@ pub fn secure_file(path: &Path) -> io::Result<SecurityFixResult> {
     }
 }
 
+/// Create a new file that only the current user can access, failing if it
+/// already exists.
+///
+/// On Unix: Opens with O_CREAT | O_EXCL and mode 0600.
+/// On Windows: Opens with CREATE_NEW and a DACL granting access only to the
+/// current user.
+///
+/// # Arguments
+/// * `path` - Path of the file to create
+///
+/// # Returns
+/// * `Ok(File)` opened for reading and writing
+/// * `Err(io::Error)` if the file exists or couldn't be created
+pub fn secure_create_file(path: &Path) -> io::Result<File> {
+    #[cfg(unix)]
+    {
+        unix::create_file_unix(path)
+    }
+
+    #[cfg(windows)]
+    {
+        windows::create_file_windows(path)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)
+    }
+}
+
+/// Create a directory and any missing parents, each only accessible by the
+/// current user. Directories that already exist are left as they are; use
+/// `secure_directory` to fix those.
+///
+/// On Unix: Creates the directories with mode 0700.
+/// On Windows: Creates the directories with a DACL granting full control only
+/// to the current user, inherited by child objects.
+///
+/// # Arguments
+/// * `path` - Path of the directory to create
+///
+/// # Returns
+/// * `Ok(())` if the directory exists afterwards
+/// * `Err(io::Error)` if a directory couldn't be created
+pub fn secure_create_dir_all(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        unix::create_dir_all_unix(path)
+    }
+
+    #[cfg(windows)]
+    {
+        windows::create_dir_all_windows(path)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        std::fs::create_dir_all(path)
+    }
+}
+
+/// Replace the contents of a file, which only the current user can access
+/// afterwards.
+///
+/// The contents are written to a new file next to `path` with
+/// `secure_create_file`, which is then renamed over `path`, so readers never
+/// see a partially written file.
+///
+/// # Arguments
+/// * `path` - Path of the file to write
+/// * `contents` - The new contents
+///
+/// # Returns
+/// * `Ok(())` once the file has been replaced
+/// * `Err(io::Error)` if writing or renaming failed; `path` is unchanged
+pub fn secure_write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    // Left behind if an earlier write by this process was interrupted
+    match std::fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let result = secure_create_file(&temp_path).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&temp_path, path)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
 /// Recursively secure a directory and all its contents.
 /// Logs warnings for each item that needs fixing.
 ///
@@ -184,9 +319,9 @@ fn secure_directory_recursive_inner(path: &Path, warn_fn: &dyn Fn(&str)) -> io::
 
 #[cfg(unix)]
 mod unix {
-    use std::fs;
+    use std::fs::{self, DirBuilder, File, OpenOptions};
     use std::io;
-    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
     use std::path::Path;
 
     use super::SecurityFixResult;
@@ -231,21 +366,52 @@ mod unix {
 
         Ok(SecurityFixResult::Fixed)
     }
+
+    /// Create a new file with mode 0600
+    pub fn create_file_unix(path: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .mode(SECURE_FILE_MODE)
+            .open(path)
+    }
+
+    /// Create a directory and its missing parents with mode 0700
+    pub fn create_dir_all_unix(path: &Path) -> io::Result<()> {
+        DirBuilder::new()
+            .recursive(true)
+            .mode(SECURE_DIR_MODE)
+            .create(path)
+    }
 }
 
 #[cfg(windows)]
 mod windows {
+    use std::fs::File;
     use std::io;
+    use std::os::windows::io::{FromRawHandle, RawHandle};
     use std::path::Path;
     use std::ptr;
 
     use super::SecurityFixResult;
-    use windows_sys::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, INVALID_HANDLE_VALUE};
-    use windows_sys::Win32::Security::Authorization::{SetNamedSecurityInfoW, SE_FILE_OBJECT};
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, LocalFree, GENERIC_READ, GENERIC_WRITE, HANDLE, INVALID_HANDLE_VALUE,
+    };
+    use windows_sys::Win32::Security::Authorization::{
+        GetNamedSecurityInfoW, SetNamedSecurityInfoW, SE_FILE_OBJECT,
+    };
     use windows_sys::Win32::Security::{
-        AddAccessAllowedAceEx, GetTokenInformation, InitializeAcl, CONTAINER_INHERIT_ACE,
-        DACL_SECURITY_INFORMATION, OBJECT_INHERIT_ACE, PROTECTED_DACL_SECURITY_INFORMATION, PSID,
-        TOKEN_QUERY, TOKEN_USER, TokenUser, ACL as WIN_ACL, ACL_REVISION,
+        AddAccessAllowedAceEx, EqualSid, GetTokenInformation, InitializeAcl,
+        InitializeSecurityDescriptor, SetSecurityDescriptorDacl, CONTAINER_INHERIT_ACE,
+        DACL_SECURITY_INFORMATION, OBJECT_INHERIT_ACE, OWNER_SECURITY_INFORMATION,
+        PROTECTED_DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID,
+        SECURITY_ATTRIBUTES, SECURITY_DESCRIPTOR, TOKEN_QUERY, TOKEN_USER, TokenUser,
+        ACL as WIN_ACL, ACL_REVISION,
+    };
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateDirectoryW, CreateFileW, CREATE_NEW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_DELETE,
+        FILE_SHARE_READ, FILE_SHARE_WRITE,
     };
     use windows_sys::Win32::System::Memory::LocalAlloc;
     use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
@@ -253,6 +419,7 @@ mod windows {
     // Access rights for files and directories
     const FILE_ALL_ACCESS: u32 = 0x1F01FF;
     const LPTR: u32 = 0x0040;
+    const SECURITY_DESCRIPTOR_REVISION: u32 = 1;
 
     /// Get the SID of the current user
     fn get_current_user_sid() -> io::Result<Vec<u8>> {
@@ -413,6 +580,147 @@ mod windows {
         // On Windows we always apply the ACL, report as Fixed
         Ok(SecurityFixResult::Fixed)
     }
+
+    /// Whether the current user owns `path`
+    pub fn is_owned_by_current_user(path: &Path) -> io::Result<bool> {
+        let user_sid = get_current_user_sid()?;
+        let wide_path = path_to_wide(path);
+        let mut owner: PSID = ptr::null_mut();
+        let mut descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+
+        // SAFETY: `wide_path` is NUL-terminated; `owner` points into
+        // `descriptor`, which is only freed after the comparison
+        unsafe {
+            let result = GetNamedSecurityInfoW(
+                wide_path.as_ptr(),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION,
+                &mut owner,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut descriptor,
+            );
+            if result != 0 {
+                return Err(io::Error::from_raw_os_error(result as i32));
+            }
+
+            let owned = EqualSid(owner, user_sid.as_ptr() as PSID) != 0;
+            LocalFree(descriptor);
+            Ok(owned)
+        }
+    }
+
+    /// Create a new file with a DACL granting access only to the current user
+    pub fn create_file_windows(path: &Path) -> io::Result<File> {
+        let mut attributes = UserOnlySecurityAttributes::with_inheritance(false)?;
+        let wide_path = path_to_wide(path);
+
+        // SAFETY: `wide_path` is NUL-terminated and `attributes` outlives the
+        // call; on success we own the returned handle
+        unsafe {
+            let handle = CreateFileW(
+                wide_path.as_ptr(),
+                GENERIC_READ | GENERIC_WRITE,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                attributes.as_mut_ptr() as *const SECURITY_ATTRIBUTES,
+                CREATE_NEW,
+                FILE_ATTRIBUTE_NORMAL,
+                ptr::null_mut(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(File::from_raw_handle(handle as RawHandle))
+        }
+    }
+
+    /// Create a directory and its missing parents, each with a DACL granting
+    /// access only to the current user that child objects inherit
+    pub fn create_dir_all_windows(path: &Path) -> io::Result<()> {
+        if path.is_dir() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all_windows(parent)?;
+        }
+
+        let mut attributes = UserOnlySecurityAttributes::with_inheritance(true)?;
+        let wide_path = path_to_wide(path);
+
+        // SAFETY: `wide_path` is NUL-terminated and `attributes` outlives the call
+        let created = unsafe {
+            CreateDirectoryW(
+                wide_path.as_ptr(),
+                attributes.as_mut_ptr() as *const SECURITY_ATTRIBUTES,
+            )
+        };
+        if created == 0 {
+            let err = io::Error::last_os_error();
+            // Someone else created it in the meantime
+            if err.kind() == io::ErrorKind::AlreadyExists && path.is_dir() {
+                return Ok(());
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Security attributes granting access only to the current user, for
+    /// objects created with a `SECURITY_ATTRIBUTES` pointer such as named pipes
+    pub struct UserOnlySecurityAttributes {
+        attributes: Box<SECURITY_ATTRIBUTES>,
+        // Referenced by `attributes`, so they must live as long as it does
+        _descriptor: Box<SECURITY_DESCRIPTOR>,
+        _acl: Vec<u8>,
+    }
+
+    impl UserOnlySecurityAttributes {
+        /// Build attributes with the same user-only DACL as `secure_file`
+        pub fn new() -> io::Result<Self> {
+            Self::with_inheritance(false)
+        }
+
+        /// Build attributes whose DACL is inherited by child objects if
+        /// `inherit` is set, as for directories
+        fn with_inheritance(inherit: bool) -> io::Result<Self> {
+            let user_sid = get_current_user_sid()?;
+            let acl = create_user_only_acl(&user_sid, inherit)?;
+            let mut descriptor = Box::new(SECURITY_DESCRIPTOR::default());
+            let descriptor_ptr =
+                &mut *descriptor as *mut SECURITY_DESCRIPTOR as PSECURITY_DESCRIPTOR;
+
+            unsafe {
+                if InitializeSecurityDescriptor(descriptor_ptr, SECURITY_DESCRIPTOR_REVISION) == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                if SetSecurityDescriptorDacl(descriptor_ptr, 1, acl.as_ptr() as *const WIN_ACL, 0)
+                    == 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            let attributes = Box::new(SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor_ptr,
+                bInheritHandle: 0,
+            });
+
+            Ok(Self {
+                attributes,
+                _descriptor: descriptor,
+                _acl: acl,
+            })
+        }
+
+        /// Pointer to the `SECURITY_ATTRIBUTES`, valid while `self` is alive
+        pub fn as_mut_ptr(&mut self) -> *mut std::ffi::c_void {
+            &mut *self.attributes as *mut SECURITY_ATTRIBUTES as *mut std::ffi::c_void
+        }
+    }
 }
 
 #[cfg(test)]
@@ -439,6 +747,14 @@ mod tests {
         assert_eq!(result.unwrap(), SecurityFixResult::NotFound);
     }
 
+    #[cfg(windows)]
+    #[test]
+    fn test_user_only_security_attributes() {
+        // Requires permission to query the process token, which every user has
+        let mut attributes = UserOnlySecurityAttributes::new().unwrap();
+        assert!(!attributes.as_mut_ptr().is_null());
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_secure_directory_fixes_permissions() {
@@ -469,6 +785,75 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_secure_create_file() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_file = std::env::temp_dir().join("clipper_security_test_create_file.txt");
+        let _ = fs::remove_file(&temp_file);
+
+        let mut file = secure_create_file(&temp_file).unwrap();
+        file.write_all(b"test").unwrap();
+        let mode = fs::metadata(&temp_file).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        // Never opens a file that already exists
+        let err = secure_create_file(&temp_file).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        let _ = fs::remove_file(&temp_file);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secure_create_dir_all() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_create_dir");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let nested = temp_dir.join("a").join("b");
+
+        secure_create_dir_all(&nested).unwrap();
+        for dir in [&temp_dir, &temp_dir.join("a"), &nested] {
+            let mode = fs::metadata(dir).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o700);
+        }
+
+        // Existing directories are fine
+        secure_create_dir_all(&nested).unwrap();
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secure_write() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_write");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let path = temp_dir.join("settings.json");
+
+        // Replaces a file with loose permissions
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        secure_write(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+
+        // No temporary file is left behind
+        assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_secure_file_fixes_permissions() {
@@ -0,0 +1,208 @@
+//! Wrappers for secrets held in memory, such as bearer tokens.
+//!
+//! [`SecretString`] and [`SecretBytes`] overwrite their contents with zeros
+//! when dropped, so a token doesn't linger in freed memory (and from there in
+//! core dumps or swap), and print as `[REDACTED]` with `{:?}`, so a token
+//! doesn't end up in logs through a derived `Debug` of the struct holding it.
+//!
+//! Moving a `String` or `Vec<u8>` in doesn't copy its buffer, but every copy
+//! made from [`SecretString::expose`] is a plain string again; keep those
+//! short-lived.
+//!
+//! With the `serde` feature they (de)serialize as their plain value, so they
+//! can stand in for the strings in config and settings files.
+
+use std::fmt;
+
+use zeroize::Zeroize;
+
+/// A string that is zeroed on drop and redacted in `Debug` output
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// The secret itself
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Unwrap the secret, which is no longer zeroed on drop
+    pub fn into_string(mut self) -> String {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Compares in constant time, so comparing against a guessed token doesn't
+/// reveal how much of it was right
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(self.0.as_bytes(), other.0.as_bytes())
+    }
+}
+
+impl Eq for SecretString {}
+
+impl PartialEq<str> for SecretString {
+    fn eq(&self, other: &str) -> bool {
+        constant_time_eq(self.0.as_bytes(), other.as_bytes())
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// Bytes that are zeroed on drop and redacted in `Debug` output
+#[derive(Clone, Default)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+
+    /// The secret itself
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Unwrap the secret, which is no longer zeroed on drop
+    pub fn into_vec(mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SecretBytes {}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(value: &[u8]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+/// Compare without returning early at the first difference; only the
+/// lengths can be told apart by timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::{SecretBytes, SecretString};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for SecretString {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.expose())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SecretString {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            String::deserialize(deserializer).map(SecretString::new)
+        }
+    }
+
+    impl Serialize for SecretBytes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.expose())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SecretBytes {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Vec::<u8>::deserialize(deserializer).map(SecretBytes::new)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let token = SecretString::from("hunter2");
+        assert_eq!(format!("{:?}", Some(&token)), "Some([REDACTED])");
+        assert_eq!(
+            format!("{:?}", SecretBytes::from(&b"key"[..])),
+            "[REDACTED]"
+        );
+        assert_eq!(token.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_eq() {
+        let token = SecretString::from("token");
+        assert_eq!(token, SecretString::from("token"));
+        assert_ne!(token, SecretString::from("tokeN"));
+        assert_ne!(token, SecretString::from("token2"));
+        assert!(token == *"token");
+        assert!(SecretBytes::from(vec![1, 2]) != SecretBytes::from(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_into_string() {
+        let token = SecretString::from("token".to_string());
+        assert_eq!(token.into_string(), "token");
+    }
+}
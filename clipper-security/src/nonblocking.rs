@@ -0,0 +1,202 @@
+//! Async variants of the blocking functions, for use on a tokio runtime.
+//!
+//! Securing a large storage directory touches every file in it; doing that
+//! on a runtime thread at startup stalls every other task. These run the
+//! filesystem work on tokio's blocking pool instead, a bounded number of
+//! files at a time so a big directory doesn't flood the pool either.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::{secure_directory, secure_file, SecurityFixResult};
+
+/// Number of files and directories [`secure_directory_recursive_async`]
+/// secures at the same time
+pub const DEFAULT_PARALLELISM: usize = 16;
+
+/// Async version of [`secure_directory_recursive`](crate::secure_directory_recursive),
+/// securing up to [`DEFAULT_PARALLELISM`] items at a time.
+///
+/// # Returns
+/// * `Ok(usize)` - Number of items that were fixed
+/// * `Err(io::Error)` if a critical operation failed
+pub async fn secure_directory_recursive_async<F>(path: &Path, warn_fn: F) -> io::Result<usize>
+where
+    F: Fn(&str) + Send + Sync + 'static,
+{
+    secure_directory_recursive_async_with_parallelism(path, DEFAULT_PARALLELISM, warn_fn).await
+}
+
+/// [`secure_directory_recursive_async`] securing up to `parallelism` items at
+/// a time
+pub async fn secure_directory_recursive_async_with_parallelism<F>(
+    path: &Path,
+    parallelism: usize,
+    warn_fn: F,
+) -> io::Result<usize>
+where
+    F: Fn(&str) + Send + Sync + 'static,
+{
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(0);
+    }
+
+    let warn_fn: Arc<dyn Fn(&str) + Send + Sync> = Arc::new(warn_fn);
+    let semaphore = Arc::new(Semaphore::new(parallelism.max(1)));
+    let mut tasks = JoinSet::new();
+    let mut directories = vec![path.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        spawn_secure(&mut tasks, &semaphore, directory.clone(), Item::Directory).await;
+
+        let mut entries = match tokio::fs::read_dir(&directory).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn_fn(&format!(
+                    "Failed to read directory {}: {}",
+                    directory.display(),
+                    e
+                ));
+                continue;
+            }
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let entry_path = entry.path();
+            // Follows symlinks, like the blocking version
+            match tokio::fs::metadata(&entry_path).await {
+                Ok(metadata) if metadata.is_dir() => directories.push(entry_path),
+                Ok(metadata) if metadata.is_file() => {
+                    spawn_secure(&mut tasks, &semaphore, entry_path, Item::File).await;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut fixed_count = 0;
+    let mut first_error = None;
+    while let Some(result) = tasks.join_next().await {
+        let (path, item, result) = result.map_err(io::Error::other)?;
+        match result {
+            Ok(SecurityFixResult::Fixed) => {
+                warn_fn(&format!(
+                    "Fixed permissions on {}: {}",
+                    item.name(),
+                    path.display()
+                ));
+                fixed_count += 1;
+            }
+            Ok(SecurityFixResult::AlreadySecure | SecurityFixResult::NotFound) => {}
+            // Let the other items finish rather than leave them half done
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(fixed_count),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Item {
+    Directory,
+    File,
+}
+
+impl Item {
+    fn name(self) -> &'static str {
+        match self {
+            Item::Directory => "directory",
+            Item::File => "file",
+        }
+    }
+}
+
+type SecureResult = (PathBuf, Item, io::Result<SecurityFixResult>);
+
+/// Secure `path` on the blocking pool once a permit is free
+async fn spawn_secure(
+    tasks: &mut JoinSet<SecureResult>,
+    semaphore: &Arc<Semaphore>,
+    path: PathBuf,
+    item: Item,
+) {
+    // The semaphore is never closed
+    let permit = semaphore
+        .clone()
+        .acquire_owned()
+        .await
+        .expect("semaphore closed");
+    tasks.spawn_blocking(move || {
+        let result = match item {
+            Item::Directory => secure_directory(&path),
+            Item::File => secure_file(&path),
+        };
+        drop(permit);
+        (path, item, result)
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_secure_directory_recursive_async() {
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_recursive_async");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("a/b")).unwrap();
+        for (path, mode) in [
+            ("", 0o755),
+            ("a", 0o700),
+            ("a/b", 0o777),
+            ("one.txt", 0o644),
+            ("a/two.txt", 0o600),
+            ("a/b/three.txt", 0o666),
+        ] {
+            let path = temp_dir.join(path);
+            if !path.exists() {
+                fs::write(&path, "x").unwrap();
+            }
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let counter = warnings.clone();
+        let fixed = secure_directory_recursive_async_with_parallelism(&temp_dir, 2, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+        assert_eq!(fixed, 4);
+        assert_eq!(warnings.load(Ordering::SeqCst), 4);
+
+        let mode = |path: &str| {
+            fs::metadata(temp_dir.join(path))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o777
+        };
+        assert_eq!(mode("a/b"), 0o700);
+        assert_eq!(mode("a/b/three.txt"), 0o600);
+
+        // Nothing left to fix
+        let fixed = secure_directory_recursive_async(&temp_dir, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(fixed, 0);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+}
@@ -0,0 +1,522 @@
+//! Storage for secrets such as bearer tokens and trusted certificate
+//! fingerprints, so they don't have to sit in plaintext settings files.
+//!
+//! With the `keychain` feature, secrets are kept in the OS credential store
+//! (macOS Keychain, Windows Credential Manager, Secret Service/libsecret on
+//! Linux). Without it, or when no credential store is available (e.g. a
+//! headless Linux machine without a Secret Service), they are kept in a file
+//! that only the current user can access.
+//!
+//! Values are plain strings; store structured values (like a map of
+//! fingerprints) serialized, e.g. as JSON.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::PathBuf;
+
+use crate::{secure_write, SecretString};
+
+/// Name of the fallback file, kept next to the settings file
+pub const SECRETS_FILE_NAME: &str = "secrets";
+
+/// Service name of the Clipper desktop apps and the CLI, which share their
+/// settings and so their secrets
+pub const CLIENT_SECRETS_SERVICE: &str = "codes.unwritten.clipper";
+
+const EXTERNAL_SERVER_TOKEN_KEY: &str = "external_server_token";
+const BUNDLED_SERVER_TOKEN_KEY: &str = "bundled_server_token";
+/// JSON object of host to fingerprint
+const TRUSTED_CERTIFICATES_KEY: &str = "trusted_certificates";
+/// JSON object of profile name to token
+const PROFILE_TOKENS_KEY: &str = "profile_tokens";
+
+/// Where a [`SecretStore`] keeps its secrets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretBackend {
+    /// The OS credential store
+    Keychain,
+    /// A file only the current user can access
+    File,
+}
+
+/// Secrets of one application, stored in the OS credential store when
+/// possible and in a fallback file otherwise.
+///
+/// Secrets found in the fallback file are moved to the credential store when
+/// it becomes available, and [`SecretStore::migrate`] moves secrets out of
+/// plaintext settings.
+#[derive(Debug, Clone)]
+pub struct SecretStore {
+    /// Service name the credential store entries are filed under
+    #[cfg_attr(not(feature = "keychain"), allow(dead_code))]
+    service: String,
+    /// Fallback file
+    file: PathBuf,
+    backend: SecretBackend,
+}
+
+impl SecretStore {
+    /// Open the secrets of `service` (e.g. the application identifier),
+    /// falling back to `file` if the credential store can't be used.
+    pub fn new(service: impl Into<String>, file: impl Into<PathBuf>) -> Self {
+        let service = service.into();
+        #[cfg(feature = "keychain")]
+        let backend = if keychain::is_available(&service) {
+            SecretBackend::Keychain
+        } else {
+            SecretBackend::File
+        };
+        #[cfg(not(feature = "keychain"))]
+        let backend = SecretBackend::File;
+
+        Self {
+            service,
+            file: file.into(),
+            backend,
+        }
+    }
+
+    /// Where secrets are stored
+    pub fn backend(&self) -> SecretBackend {
+        self.backend
+    }
+
+    /// Get a secret, or `None` if it isn't set
+    pub fn get(&self, key: &str) -> io::Result<Option<String>> {
+        #[cfg(feature = "keychain")]
+        if self.backend == SecretBackend::Keychain {
+            if let Some(value) = keychain::get(&self.service, key)? {
+                return Ok(Some(value));
+            }
+
+            // Saved to the file while the credential store was unavailable
+            let Some(value) = self.read_file()?.remove(key) else {
+                return Ok(None);
+            };
+            self.set(key, &value)?;
+            return Ok(Some(value));
+        }
+
+        Ok(self.read_file()?.remove(key))
+    }
+
+    /// Set a secret, replacing any previous value
+    pub fn set(&self, key: &str, value: &str) -> io::Result<()> {
+        #[cfg(feature = "keychain")]
+        if self.backend == SecretBackend::Keychain {
+            keychain::set(&self.service, key, value)?;
+            return self.remove_from_file(key);
+        }
+
+        let mut secrets = self.read_file()?;
+        secrets.insert(key.to_string(), value.to_string());
+        self.write_file(&secrets)
+    }
+
+    /// Remove a secret. Removing a secret that isn't set is not an error.
+    pub fn delete(&self, key: &str) -> io::Result<()> {
+        #[cfg(feature = "keychain")]
+        if self.backend == SecretBackend::Keychain {
+            keychain::delete(&self.service, key)?;
+        }
+
+        self.remove_from_file(key)
+    }
+
+    /// Set a secret, or remove it for `None`
+    pub fn set_or_delete(&self, key: &str, value: Option<&str>) -> io::Result<()> {
+        match value {
+            Some(value) => self.set(key, value),
+            None => self.delete(key),
+        }
+    }
+
+    /// Take over a secret that was kept in plaintext elsewhere.
+    ///
+    /// A `plaintext` value is stored (it was written more recently than
+    /// anything in the store) and returned; the caller should then remove it
+    /// from where it came from. Without one, the stored value is returned.
+    pub fn migrate(&self, key: &str, plaintext: Option<String>) -> io::Result<Option<String>> {
+        match plaintext {
+            Some(value) => {
+                self.set(key, &value)?;
+                Ok(Some(value))
+            }
+            None => self.get(key),
+        }
+    }
+
+    fn read_file(&self) -> io::Result<BTreeMap<String, String>> {
+        match std::fs::read_to_string(&self.file) {
+            Ok(contents) => Ok(parse_secrets(&contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write_file(&self, secrets: &BTreeMap<String, String>) -> io::Result<()> {
+        if secrets.is_empty() {
+            return match std::fs::remove_file(&self.file) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        secure_write(&self.file, format_secrets(secrets))
+    }
+
+    fn remove_from_file(&self, key: &str) -> io::Result<()> {
+        let mut secrets = self.read_file()?;
+        if secrets.remove(key).is_some() {
+            self.write_file(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+/// The secrets in the settings shared by the Clipper desktop apps and the CLI
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientSecrets {
+    /// Bearer token for the external server
+    pub external_server_token: Option<SecretString>,
+    /// Bearer token for the bundled server when external access is enabled
+    pub bundled_server_token: Option<SecretString>,
+    /// Trusted certificate fingerprints for self-signed HTTPS servers, by host
+    pub trusted_certificates: HashMap<String, String>,
+    /// Bearer tokens of the named server profiles, by profile name
+    pub profile_tokens: BTreeMap<String, SecretString>,
+}
+
+impl ClientSecrets {
+    /// Whether no secret is set
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Read the secrets from `store`, for programs that don't own the
+    /// settings file and so mustn't migrate it
+    pub fn read(store: &SecretStore) -> io::Result<Self> {
+        Ok(Self {
+            external_server_token: store.get(EXTERNAL_SERVER_TOKEN_KEY)?.map(SecretString::new),
+            bundled_server_token: store.get(BUNDLED_SERVER_TOKEN_KEY)?.map(SecretString::new),
+            trusted_certificates: store
+                .get(TRUSTED_CERTIFICATES_KEY)?
+                .map(|value| parse_trusted_certificates(&value))
+                .unwrap_or_default(),
+            profile_tokens: store
+                .get(PROFILE_TOKENS_KEY)?
+                .map(|value| parse_profile_tokens(&value))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Load the secrets from `store`, taking over the `plaintext` ones found
+    /// in a settings file written before secrets were moved out of it
+    pub fn load(store: &SecretStore, plaintext: ClientSecrets) -> io::Result<Self> {
+        let trusted_certificates = (!plaintext.trusted_certificates.is_empty())
+            .then(|| format_trusted_certificates(&plaintext.trusted_certificates));
+        let profile_tokens = (!plaintext.profile_tokens.is_empty())
+            .then(|| format_profile_tokens(&plaintext.profile_tokens));
+
+        Ok(Self {
+            external_server_token: store
+                .migrate(
+                    EXTERNAL_SERVER_TOKEN_KEY,
+                    plaintext
+                        .external_server_token
+                        .map(SecretString::into_string),
+                )?
+                .map(SecretString::new),
+            bundled_server_token: store
+                .migrate(
+                    BUNDLED_SERVER_TOKEN_KEY,
+                    plaintext
+                        .bundled_server_token
+                        .map(SecretString::into_string),
+                )?
+                .map(SecretString::new),
+            trusted_certificates: store
+                .migrate(TRUSTED_CERTIFICATES_KEY, trusted_certificates)?
+                .map(|value| parse_trusted_certificates(&value))
+                .unwrap_or_default(),
+            profile_tokens: store
+                .migrate(PROFILE_TOKENS_KEY, profile_tokens)?
+                .map(|value| parse_profile_tokens(&value))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Save the secrets that changed since `previous` was loaded or saved
+    pub fn save(&self, store: &SecretStore, previous: &ClientSecrets) -> io::Result<()> {
+        if self.external_server_token != previous.external_server_token {
+            store.set_or_delete(
+                EXTERNAL_SERVER_TOKEN_KEY,
+                self.external_server_token
+                    .as_ref()
+                    .map(SecretString::expose),
+            )?;
+        }
+        if self.bundled_server_token != previous.bundled_server_token {
+            store.set_or_delete(
+                BUNDLED_SERVER_TOKEN_KEY,
+                self.bundled_server_token.as_ref().map(SecretString::expose),
+            )?;
+        }
+        if self.trusted_certificates != previous.trusted_certificates {
+            let value = (!self.trusted_certificates.is_empty())
+                .then(|| format_trusted_certificates(&self.trusted_certificates));
+            store.set_or_delete(TRUSTED_CERTIFICATES_KEY, value.as_deref())?;
+        }
+        if self.profile_tokens != previous.profile_tokens {
+            let value = (!self.profile_tokens.is_empty())
+                .then(|| format_profile_tokens(&self.profile_tokens));
+            store.set_or_delete(PROFILE_TOKENS_KEY, value.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_trusted_certificates(value: &str) -> HashMap<String, String> {
+    serde_json::from_str(value).unwrap_or_else(|_| parse_lines(value, ' '))
+}
+
+fn format_trusted_certificates(certificates: &HashMap<String, String>) -> String {
+    let sorted: BTreeMap<_, _> = certificates.iter().collect();
+    serde_json::to_string(&sorted).expect("a map of strings serializes")
+}
+
+fn parse_profile_tokens(value: &str) -> BTreeMap<String, SecretString> {
+    let tokens: BTreeMap<String, String> =
+        serde_json::from_str(value).unwrap_or_else(|_| parse_lines(value, '\t'));
+    tokens
+        .into_iter()
+        .map(|(profile, token)| (profile, SecretString::new(token)))
+        .collect()
+}
+
+fn format_profile_tokens(tokens: &BTreeMap<String, SecretString>) -> String {
+    let exposed: BTreeMap<_, _> = tokens
+        .iter()
+        .map(|(profile, token)| (profile, token.expose()))
+        .collect();
+    serde_json::to_string(&exposed).expect("a map of strings serializes")
+}
+
+/// Parse a value stored by older versions, which kept one
+/// `name<separator>value` per line instead of JSON
+fn parse_lines<M: FromIterator<(String, String)>>(value: &str, separator: char) -> M {
+    value
+        .lines()
+        .filter_map(|line| line.split_once(separator))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Parse the fallback file: one `key=value` per line, with backslashes and
+/// newlines in values escaped
+fn parse_secrets(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), unescape(value)))
+        .collect()
+}
+
+fn format_secrets(secrets: &BTreeMap<String, String>) -> String {
+    secrets
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, escape(value)))
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(feature = "keychain")]
+mod keychain {
+    use std::io;
+
+    /// Entry used to check whether the credential store works at all
+    const PROBE_KEY: &str = "keychain-probe";
+
+    fn entry(service: &str, key: &str) -> io::Result<keyring::Entry> {
+        keyring::Entry::new(service, key).map_err(io::Error::other)
+    }
+
+    /// Whether the credential store can be used; looking up a missing entry
+    /// fails differently when there is no store to look in
+    pub fn is_available(service: &str) -> bool {
+        match entry(service, PROBE_KEY).map(|entry| entry.get_password()) {
+            Ok(Ok(_)) | Ok(Err(keyring::Error::NoEntry)) => true,
+            Ok(Err(_)) | Err(_) => false,
+        }
+    }
+
+    pub fn get(service: &str, key: &str) -> io::Result<Option<String>> {
+        match entry(service, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(io::Error::other(e)),
+        }
+    }
+
+    pub fn set(service: &str, key: &str, value: &str) -> io::Result<()> {
+        entry(service, key)?
+            .set_password(value)
+            .map_err(io::Error::other)
+    }
+
+    pub fn delete(service: &str, key: &str) -> io::Result<()> {
+        match entry(service, key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(io::Error::other(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_roundtrip() {
+        for value in [
+            "token",
+            "a=b",
+            "back\\slash",
+            "two\nlines\r\n",
+            "trailing\\",
+        ] {
+            assert_eq!(unescape(&escape(value)), value);
+        }
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    #[test]
+    fn test_file_backend() {
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_secrets");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let file = temp_dir.join("secrets");
+
+        let store = SecretStore::new("clipper-test", &file);
+        assert_eq!(store.backend(), SecretBackend::File);
+        assert_eq!(store.get("token").unwrap(), None);
+
+        store.set("token", "secret=value\n").unwrap();
+        store.set("other", "x").unwrap();
+        assert_eq!(
+            store.get("token").unwrap().as_deref(),
+            Some("secret=value\n")
+        );
+
+        // Plaintext values replace stored ones
+        assert_eq!(
+            store.migrate("other", Some("y".to_string())).unwrap(),
+            Some("y".to_string())
+        );
+        assert_eq!(store.migrate("other", None).unwrap(), Some("y".to_string()));
+
+        store.set_or_delete("token", None).unwrap();
+        assert_eq!(store.get("token").unwrap(), None);
+        store.delete("other").unwrap();
+        assert!(!file.exists());
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    #[test]
+    fn test_client_secrets() {
+        let temp_dir = std::env::temp_dir().join("clipper_security_test_client_secrets");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let store = SecretStore::new("clipper-test", temp_dir.join(SECRETS_FILE_NAME));
+
+        assert!(ClientSecrets::load(&store, ClientSecrets::default())
+            .unwrap()
+            .is_empty());
+
+        // Plaintext secrets from an old settings file are taken over
+        let plaintext = ClientSecrets {
+            external_server_token: Some("external".into()),
+            bundled_server_token: None,
+            trusted_certificates: HashMap::from([
+                ("example.com".to_string(), "AB:CD".to_string()),
+                ("192.168.1.2".to_string(), "EF:01".to_string()),
+            ]),
+            profile_tokens: BTreeMap::from([("work".to_string(), "work-token".into())]),
+        };
+        let loaded = ClientSecrets::load(&store, plaintext.clone()).unwrap();
+        assert_eq!(loaded, plaintext);
+        assert_eq!(
+            ClientSecrets::load(&store, ClientSecrets::default()).unwrap(),
+            plaintext
+        );
+
+        let mut changed = loaded.clone();
+        changed.external_server_token = None;
+        changed.bundled_server_token = Some("bundled".into());
+        changed.trusted_certificates.remove("example.com");
+        changed
+            .profile_tokens
+            .insert("home server".to_string(), "home-token".into());
+        changed.save(&store, &loaded).unwrap();
+        assert_eq!(ClientSecrets::read(&store).unwrap(), changed);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_structured_secrets_format() {
+        // Names with separators of the old line format survive
+        let tokens = BTreeMap::from([
+            ("tab\tname".to_string(), SecretString::from("token 1")),
+            ("line\nbreak".to_string(), SecretString::from("token\t2")),
+        ]);
+        let value = format_profile_tokens(&tokens);
+        assert!(serde_json::from_str::<serde_json::Value>(&value)
+            .unwrap()
+            .is_object());
+        assert_eq!(parse_profile_tokens(&value), tokens);
+
+        let certificates = HashMap::from([("example.com".to_string(), "AB:CD".to_string())]);
+        let value = format_trusted_certificates(&certificates);
+        assert_eq!(value, r#"{"example.com":"AB:CD"}"#);
+        assert_eq!(parse_trusted_certificates(&value), certificates);
+
+        // Values stored by older versions are still read
+        assert_eq!(
+            parse_profile_tokens("work\twork-token\nhome\thome-token\n"),
+            BTreeMap::from([
+                ("home".to_string(), SecretString::from("home-token")),
+                ("work".to_string(), SecretString::from("work-token")),
+            ])
+        );
+        assert_eq!(
+            parse_trusted_certificates("example.com AB:CD\n"),
+            certificates
+        );
+    }
+}
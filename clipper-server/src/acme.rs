@@ -3,9 +3,12 @@
 //! This module provides automatic TLS certificate provisioning via Let's Encrypt
 //! using the ACME protocol. It handles:
 //! - Account registration and key management
-//! - Certificate ordering and validation (HTTP-01 challenge)
+//! - Certificate ordering and validation (HTTP-01 challenge, or DNS-01
+//!   through a hook command for wildcard domains)
 //! - Certificate storage and renewal
 
+#[cfg(feature = "acme")]
+use std::path::Path;
 #[cfg(feature = "acme")]
 use std::sync::Arc;
 #[cfg(feature = "acme")]
@@ -14,7 +17,7 @@ use std::time::Duration;
 #[cfg(feature = "acme")]
 use instant_acme::{
     Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, LetsEncrypt,
-    NewAccount, NewOrder, OrderStatus, RetryPolicy,
+    NewAccount, NewOrder, Order, OrderStatus, RetryPolicy,
 };
 #[cfg(feature = "acme")]
 use thiserror::Error;
@@ -177,88 +180,57 @@ impl AcmeManager {
         self.pending_challenges.clone()
     }
 
-    /// Provision a certificate for the configured domain.
+    /// Provision a certificate covering the configured domains.
     ///
     /// Returns (certificate_pem, private_key_pem).
     pub async fn provision_certificate(&self) -> AcmeResult<(String, String)> {
-        let domain = self.config.domain.as_ref().ok_or_else(|| {
-            AcmeError::Configuration("Domain is required for certificate provisioning".to_string())
-        })?;
+        let domains = self.config.domains();
+        let name = storage_name(&domains)?;
 
-        tracing::info!("Provisioning certificate for {}", domain);
+        tracing::info!("Provisioning certificate for {}", domains.join(", "));
 
         // Check if we have a valid cached certificate
-        if let Some((cert_pem, key_pem)) = self.load_cached_certificate(domain).await? {
-            if !self.certificate_needs_renewal(&cert_pem)? {
-                tracing::info!("Using cached certificate for {}", domain);
+        if let Some((cert_pem, key_pem)) = self.load_cached_certificate(&name).await? {
+            if !certificate_covers(&cert_pem, &domains)? {
+                tracing::info!("Cached certificate doesn't cover all configured domains");
+            } else if !self.certificate_needs_renewal(&cert_pem)? {
+                tracing::info!("Using cached certificate for {}", name);
                 return Ok((cert_pem, key_pem));
+            } else {
+                tracing::info!("Cached certificate needs renewal");
             }
-            tracing::info!("Cached certificate needs renewal");
         }
 
         // Get or create account
         let account = self.get_or_create_account().await?;
 
-        // Create order
-        let identifiers = vec![Identifier::Dns(domain.to_string())];
+        // Create order for all domains, so they end up in one SAN certificate
+        let identifiers: Vec<Identifier> = domains
+            .iter()
+            .map(|domain| Identifier::Dns(domain.clone()))
+            .collect();
         let mut order = account
             .new_order(&NewOrder::new(identifiers.as_slice()))
             .await
             .map_err(|e| AcmeError::Protocol(e.to_string()))?;
 
-        // Process authorizations and HTTP-01 challenges
-        let mut authorizations = order.authorizations();
-        while let Some(result) = authorizations.next().await {
-            let mut authz = result.map_err(|e| AcmeError::Protocol(e.to_string()))?;
-
-            match authz.status {
-                AuthorizationStatus::Valid => continue,
-                AuthorizationStatus::Pending => {}
-                _ => {
-                    return Err(AcmeError::ChallengeFailed(format!(
-                        "Unexpected authorization status: {:?}",
-                        authz.status
-                    )));
-                }
-            }
-
-            let mut challenge = authz.challenge(ChallengeType::Http01).ok_or_else(|| {
-                AcmeError::ChallengeFailed("No HTTP-01 challenge found".to_string())
-            })?;
-
-            let token = challenge.token.clone();
-            let key_auth = challenge.key_authorization().as_str().to_string();
-
-            tracing::info!("Setting up HTTP-01 challenge for token: {}", token);
-
-            // Store the challenge for the HTTP handler
-            {
-                let mut challenges = self.pending_challenges.write().await;
-                challenges.insert(token.clone(), key_auth.clone());
-            }
-
-            // Notify ACME server that we're ready
-            challenge
-                .set_ready()
-                .await
-                .map_err(|e| AcmeError::Protocol(e.to_string()))?;
-
-            // Note: Do NOT remove the challenge here - the ACME server will validate asynchronously
-            // and needs to be able to fetch the key authorization from our HTTP endpoint
-        }
-
-        // Wait for order to be ready using poll_ready with retry policy
-        let status = order
-            .poll_ready(&RetryPolicy::default())
-            .await
-            .map_err(|e| AcmeError::Protocol(e.to_string()))?;
+        let mut dns_records = Vec::new();
+        let status = self.validate_order(&mut order, &mut dns_records).await;
 
         // Clean up pending challenges after validation is complete
         {
             let mut challenges = self.pending_challenges.write().await;
             challenges.clear();
         }
+        if let Some(hook) = &self.config.dns_hook {
+            for (record, value) in &dns_records {
+                if let Err(e) = run_dns_hook(hook, "clear", record, value).await {
+                    tracing::warn!("Failed to remove DNS record {}: {}", record, e);
+                }
+            }
+        }
 
+        let status = status?;
         if status != OrderStatus::Ready {
             return Err(AcmeError::OrderFailed(format!(
                 "Unexpected order status: {:?}",
@@ -279,14 +251,102 @@ impl AcmeManager {
             .map_err(|e| AcmeError::Protocol(e.to_string()))?;
 
         // Store certificate
-        self.storage.store_certificate(domain, &cert_chain)?;
-        self.storage.store_private_key(domain, &key_pem)?;
+        self.storage.store_certificate(&name, &cert_chain)?;
+        self.storage.store_private_key(&name, &key_pem)?;
 
-        tracing::info!("Certificate provisioned and stored for {}", domain);
+        tracing::info!(
+            "Certificate provisioned and stored for {}",
+            domains.join(", ")
+        );
 
         Ok((cert_chain, key_pem))
     }
 
+    /// Answer the order's challenges and wait until it's ready. DNS-01 records
+    /// are added to `dns_records` as they are published, so the caller can
+    /// remove them whatever the outcome.
+    async fn validate_order(
+        &self,
+        order: &mut Order,
+        dns_records: &mut Vec<(String, String)>,
+    ) -> AcmeResult<OrderStatus> {
+        let mut authorizations = order.authorizations();
+        while let Some(result) = authorizations.next().await {
+            let mut authz = result.map_err(|e| AcmeError::Protocol(e.to_string()))?;
+
+            match authz.status {
+                AuthorizationStatus::Valid => continue,
+                AuthorizationStatus::Pending => {}
+                _ => {
+                    return Err(AcmeError::ChallengeFailed(format!(
+                        "Unexpected authorization status: {:?}",
+                        authz.status
+                    )));
+                }
+            }
+
+            // For a wildcard this is the base domain, which is where the
+            // DNS-01 record goes
+            let domain = match &authz.identifier().identifier {
+                Identifier::Dns(domain) => domain.clone(),
+                other => {
+                    return Err(AcmeError::ChallengeFailed(format!(
+                        "Unsupported identifier: {:?}",
+                        other
+                    )));
+                }
+            };
+
+            if let Some(hook) = &self.config.dns_hook {
+                let mut challenge = authz.challenge(ChallengeType::Dns01).ok_or_else(|| {
+                    AcmeError::ChallengeFailed(format!("No DNS-01 challenge found for {}", domain))
+                })?;
+
+                let record = format!("_acme-challenge.{}", domain);
+                let value = challenge.key_authorization().dns_value();
+
+                tracing::info!("Setting up DNS-01 challenge record {}", record);
+                run_dns_hook(hook, "set", &record, &value).await?;
+                dns_records.push((record, value));
+
+                challenge
+                    .set_ready()
+                    .await
+                    .map_err(|e| AcmeError::Protocol(e.to_string()))?;
+            } else {
+                let mut challenge = authz.challenge(ChallengeType::Http01).ok_or_else(|| {
+                    AcmeError::ChallengeFailed(format!("No HTTP-01 challenge found for {}", domain))
+                })?;
+
+                let token = challenge.token.clone();
+                let key_auth = challenge.key_authorization().as_str().to_string();
+
+                tracing::info!("Setting up HTTP-01 challenge for token: {}", token);
+
+                // Store the challenge for the HTTP handler
+                {
+                    let mut challenges = self.pending_challenges.write().await;
+                    challenges.insert(token.clone(), key_auth.clone());
+                }
+
+                // Notify ACME server that we're ready
+                challenge
+                    .set_ready()
+                    .await
+                    .map_err(|e| AcmeError::Protocol(e.to_string()))?;
+
+                // Note: Do NOT remove the challenge here - the ACME server will validate asynchronously
+                // and needs to be able to fetch the key authorization from our HTTP endpoint
+            }
+        }
+
+        // Wait for order to be ready using poll_ready with retry policy
+        order
+            .poll_ready(&RetryPolicy::default())
+            .await
+            .map_err(|e| AcmeError::Protocol(e.to_string()))
+    }
+
     /// Load cached certificate from storage.
     async fn load_cached_certificate(&self, domain: &str) -> AcmeResult<Option<(String, String)>> {
         if !self.storage.has_certificate(domain)? {
@@ -341,18 +401,20 @@ impl AcmeManager {
         Ok(needs_renewal)
     }
 
-    /// Renew the certificate if needed.
+    /// Renew the certificate if needed, or if the configured domains changed.
     pub async fn renew_if_needed(&self) -> AcmeResult<Option<(String, String)>> {
-        let domain = match &self.config.domain {
-            Some(d) => d.clone(),
-            None => return Ok(None),
+        let domains = self.config.domains();
+        let Ok(name) = storage_name(&domains) else {
+            return Ok(None);
         };
 
-        if let Some((cert_pem, key_pem)) = self.load_cached_certificate(&domain).await? {
-            if self.certificate_needs_renewal(&cert_pem)? {
-                tracing::info!("Renewing certificate for {}", domain);
+        if let Some((cert_pem, key_pem)) = self.load_cached_certificate(&name).await? {
+            if !certificate_covers(&cert_pem, &domains)?
+                || self.certificate_needs_renewal(&cert_pem)?
+            {
+                tracing::info!("Renewing certificate for {}", domains.join(", "));
                 // Delete old certificate and provision new one
-                self.storage.delete_certificate(&domain)?;
+                self.storage.delete_certificate(&name)?;
                 return Ok(Some(self.provision_certificate().await?));
             }
             return Ok(Some((cert_pem, key_pem)));
@@ -362,6 +424,69 @@ impl AcmeManager {
     }
 }
 
+/// Name the certificate is stored under: the first domain, with a wildcard's
+/// `*` replaced since it isn't allowed in file names everywhere
+#[cfg(feature = "acme")]
+fn storage_name(domains: &[String]) -> AcmeResult<String> {
+    let domain = domains.first().ok_or_else(|| {
+        AcmeError::Configuration("Domain is required for certificate provisioning".to_string())
+    })?;
+    Ok(domain.replace('*', "_"))
+}
+
+/// Check that the first certificate in a PEM chain lists every one of
+/// `domains` as a subject alternative name.
+#[cfg(feature = "acme")]
+fn certificate_covers(cert_pem: &str, domains: &[String]) -> AcmeResult<bool> {
+    let (_, pem) = x509_parser::pem::parse_x509_pem(cert_pem.as_bytes())
+        .map_err(|e| AcmeError::CertificateParsing(e.to_string()))?;
+
+    let (_, cert) = X509Certificate::from_der(&pem.contents)
+        .map_err(|e| AcmeError::CertificateParsing(e.to_string()))?;
+
+    let names: Vec<String> = match cert
+        .subject_alternative_name()
+        .map_err(|e| AcmeError::CertificateParsing(e.to_string()))?
+    {
+        Some(san) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name) => Some(name.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(domains
+        .iter()
+        .all(|domain| names.contains(&domain.to_ascii_lowercase())))
+}
+
+/// Run the DNS-01 hook to `set` or `clear` a challenge TXT record.
+#[cfg(feature = "acme")]
+async fn run_dns_hook(hook: &Path, action: &str, record: &str, value: &str) -> AcmeResult<()> {
+    let mut command = std::process::Command::new(hook);
+    command.args([action, record, value]);
+
+    let status = tokio::task::spawn_blocking(move || command.status())
+        .await
+        .map_err(|e| AcmeError::ChallengeFailed(format!("DNS hook failed: {}", e)))??;
+
+    if !status.success() {
+        return Err(AcmeError::ChallengeFailed(format!(
+            "DNS hook {} {} {} exited with {}",
+            hook.display(),
+            action,
+            record,
+            status
+        )));
+    }
+    Ok(())
+}
+
 /// Axum handler for ACME HTTP-01 challenges.
 ///
 /// This handler responds to requests at `/.well-known/acme-challenge/{token}`
@@ -436,4 +561,32 @@ mod tests {
         assert!(LetsEncrypt::Production.url().contains("acme-v02"));
         assert!(LetsEncrypt::Staging.url().contains("staging"));
     }
+
+    #[test]
+    fn test_storage_name() {
+        let domains = vec!["*.example.com".to_string(), "example.com".to_string()];
+        assert_eq!(storage_name(&domains).unwrap(), "_.example.com");
+        assert!(storage_name(&[]).is_err());
+    }
+
+    #[test]
+    fn test_certificate_covers() {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let cert = rcgen::CertificateParams::new(vec![
+            "clipper.example.com".to_string(),
+            "*.example.com".to_string(),
+        ])
+        .unwrap()
+        .self_signed(&key_pair)
+        .unwrap();
+        let cert_pem = cert.pem();
+
+        let covered = |domains: &[&str]| {
+            let domains: Vec<String> = domains.iter().map(|d| d.to_string()).collect();
+            certificate_covers(&cert_pem, &domains).unwrap()
+        };
+        assert!(covered(&["clipper.example.com"]));
+        assert!(covered(&["CLIPPER.example.com", "*.example.com"]));
+        assert!(!covered(&["clipper.example.com", "clip.example.com"]));
+    }
 }
@@ -0,0 +1,577 @@
+//! Server administration endpoints.
+//!
+//! These act on the running server rather than on clips. When `auth.admin_token`
+//! is set they require it, and the shared and paired device tokens are
+//! rejected; otherwise they require the shared token when a bearer token is
+//! configured.
+
+use std::path::{Path, PathBuf};
+
+use axum::{
+    body::Body,
+    extract::{Path as UrlPath, Query, State},
+    http::{header, StatusCode},
+    response::Response,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use clipper_indexer::LibraryStats;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::Credential,
+    cleanup::{cleanup_clips, preview_cleanup},
+    config::{ConfigChanges, ServerConfig, TagRetentionRule},
+    error::{Result, ServerError},
+    mobile::preview,
+    pairing::{require_auth_enabled, PairedDeviceResponse},
+    state::AppState,
+};
+
+/// Backups are named `<prefix><timestamp><suffix>`
+const BACKUP_PREFIX: &str = "clipper_backup_";
+const BACKUP_SUFFIX: &str = ".tar.gz";
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/config", get(get_config))
+        .route("/admin/stats", get(get_stats))
+        .route("/admin/devices", get(list_devices))
+        .route("/admin/devices", post(create_device))
+        .route("/admin/devices/{id}", delete(revoke_device))
+        .route("/admin/cleanup", post(run_cleanup))
+        .route("/admin/cleanup/rules", get(get_cleanup_rules))
+        .route("/admin/cleanup/rules", put(set_cleanup_rules))
+        .route("/admin/reindex", post(reindex))
+        .route("/admin/backup", post(create_backup))
+        .route("/admin/backups", get(list_backups))
+        .route("/admin/backups/{name}", get(download_backup))
+        .route("/admin/reload-config", post(reload_config))
+        .route("/admin/reload-tls", post(reload_tls))
+}
+
+/// Fail unless the request used the admin token or, without one configured,
+/// the shared token (if the server requires a token at all)
+fn require_admin(state: &AppState, credential: Option<&Credential>) -> Result<()> {
+    let config = state.config.get();
+    let auth = &config.auth;
+    match credential {
+        Some(Credential::AdminToken) => Ok(()),
+        None if auth.admin_token_enabled() => Err(ServerError::Unauthorized(
+            "Server administration requires the admin token".to_string(),
+        )),
+        _ if auth.admin_token_enabled() => Err(ServerError::Forbidden(
+            "Server administration requires the admin token".to_string(),
+        )),
+        _ if !auth.is_enabled() => Ok(()),
+        Some(Credential::SharedToken) => Ok(()),
+        _ => Err(ServerError::Forbidden(
+            "Server administration requires the shared bearer token".to_string(),
+        )),
+    }
+}
+
+/// The server configuration, with tokens and keys redacted
+async fn get_config(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<ServerConfig>> {
+    require_admin(&state, credential.as_deref())?;
+
+    Ok(Json(state.config.get().redacted()))
+}
+
+#[derive(Debug, Serialize)]
+struct StatsResponse {
+    #[serde(flatten)]
+    library: LibraryStats,
+    /// Space used by the database directory
+    database_size_bytes: u64,
+    /// Space used by the attachment storage directory
+    storage_size_bytes: u64,
+    index_version: i64,
+    uptime_secs: u64,
+    active_ws_connections: usize,
+}
+
+/// Record counts and disk usage
+async fn get_stats(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<StatsResponse>> {
+    require_admin(&state, credential.as_deref())?;
+
+    let library = state.indexer.library_stats().await?;
+    let index_version = state.indexer.get_index_version().await?;
+
+    let config = state.config.get();
+    let database_path = PathBuf::from(&config.database.path);
+    let storage_path = PathBuf::from(&config.storage.path);
+    let (database_size_bytes, storage_size_bytes) = tokio::task::spawn_blocking(move || {
+        (
+            directory_size(&database_path),
+            directory_size(&storage_path),
+        )
+    })
+    .await
+    .map_err(|e| ServerError::Internal(format!("Failed to measure disk usage: {}", e)))?;
+
+    Ok(Json(StatsResponse {
+        library,
+        database_size_bytes,
+        storage_size_bytes,
+        index_version,
+        uptime_secs: state.uptime_secs(),
+        active_ws_connections: state.active_ws_connections(),
+    }))
+}
+
+/// Total size of the files below `path`; unreadable entries are skipped
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => directory_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+/// List paired devices, most recently paired first
+async fn list_devices(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<PairedDeviceResponse>>> {
+    require_admin(&state, credential.as_deref())?;
+    require_auth_enabled(&state)?;
+
+    let devices = state.indexer.list_paired_devices().await?;
+    Ok(Json(devices.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateDeviceRequest {
+    /// Name shown in the paired device list
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateDeviceResponse {
+    /// The device's bearer token; it is not shown again
+    token: String,
+    device: PairedDeviceResponse,
+}
+
+/// Create a device token directly, without a pairing code, e.g. for a
+/// headless client
+async fn create_device(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Json(payload): Json<CreateDeviceRequest>,
+) -> Result<(StatusCode, Json<CreateDeviceResponse>)> {
+    require_admin(&state, credential.as_deref())?;
+    require_auth_enabled(&state)?;
+
+    let (device, token) = state.indexer.create_paired_device(&payload.name).await?;
+    tracing::info!("Created token for device {} ({})", device.name, device.id);
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateDeviceResponse {
+            token,
+            device: device.into(),
+        }),
+    ))
+}
+
+/// Revoke a device token. Its WebSocket connections are closed.
+async fn revoke_device(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    UrlPath(id): UrlPath<String>,
+) -> Result<StatusCode> {
+    require_admin(&state, credential.as_deref())?;
+    require_auth_enabled(&state)?;
+
+    state.indexer.revoke_paired_device(&id).await?;
+    state.notify_device_revoked(id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CleanupRequest {
+    /// Delete clips older than this many days instead of
+    /// `cleanup.retention_days`
+    retention_days: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CleanupQuery {
+    /// Report what would be deleted without deleting it
+    dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CleanupResponse {
+    dry_run: bool,
+    /// Clips deleted, or that would be for a dry run
+    deleted_clips: usize,
+    /// Expired short URLs deleted, or that would be for a dry run
+    deleted_short_urls: usize,
+    /// The clips a dry run would delete, oldest first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clips: Option<Vec<CleanupCandidate>>,
+}
+
+/// A clip that cleanup would delete
+#[derive(Debug, Serialize)]
+struct CleanupCandidate {
+    id: String,
+    /// The start of the content on one line
+    preview: String,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_filename: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Delete old clips without meaningful tags, clips past their tag
+/// retention and expired short URLs now, whether or not periodic cleanup is
+/// enabled. With `?dry_run=true`, list what would be deleted instead.
+async fn run_cleanup(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Query(query): Query<CleanupQuery>,
+    payload: Option<Json<CleanupRequest>>,
+) -> Result<Json<CleanupResponse>> {
+    require_admin(&state, credential.as_deref())?;
+
+    let mut cleanup = state.config.get().cleanup.clone();
+    if let Some(retention_days) = payload.and_then(|Json(payload)| payload.retention_days) {
+        cleanup.retention_days = retention_days;
+    }
+    if cleanup.retention_days == 0 {
+        return Err(ServerError::InvalidInput(
+            "retention_days must be at least 1".to_string(),
+        ));
+    }
+
+    if query.dry_run {
+        let clips: Vec<CleanupCandidate> = preview_cleanup(&state, &cleanup)
+            .await?
+            .into_iter()
+            .map(|entry| CleanupCandidate {
+                preview: preview(&entry.content).0,
+                id: entry.id,
+                tags: entry.tags,
+                original_filename: entry.original_filename,
+                created_at: entry.created_at,
+            })
+            .collect();
+        return Ok(Json(CleanupResponse {
+            dry_run: true,
+            deleted_clips: clips.len(),
+            deleted_short_urls: state.indexer.count_expired_short_urls().await?,
+            clips: Some(clips),
+        }));
+    }
+
+    let deleted_clips = cleanup_clips(&state, &cleanup).await?.len();
+    let deleted_short_urls = state.indexer.cleanup_expired_short_urls().await?;
+    Ok(Json(CleanupResponse {
+        dry_run: false,
+        deleted_clips,
+        deleted_short_urls,
+        clips: None,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CleanupRulesBody {
+    tag_rules: Vec<TagRetentionRule>,
+}
+
+/// The retention rules for tagged clips
+async fn get_cleanup_rules(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<CleanupRulesBody>> {
+    require_admin(&state, credential.as_deref())?;
+
+    Ok(Json(CleanupRulesBody {
+        tag_rules: state.config.get().cleanup.tag_rules.clone(),
+    }))
+}
+
+/// Replace the retention rules for tagged clips. They apply from the next
+/// cleanup run until the server restarts or reloads its configuration;
+/// `cleanup.tag_rules` in the config file keeps them.
+async fn set_cleanup_rules(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Json(body): Json<CleanupRulesBody>,
+) -> Result<Json<CleanupRulesBody>> {
+    require_admin(&state, credential.as_deref())?;
+
+    let mut tags = std::collections::HashSet::new();
+    for rule in &body.tag_rules {
+        if rule.tag.trim().is_empty() {
+            return Err(ServerError::InvalidInput(
+                "Tag rules need a tag".to_string(),
+            ));
+        }
+        if !tags.insert(rule.tag.as_str()) {
+            return Err(ServerError::InvalidInput(format!(
+                "More than one rule for tag '{}'",
+                rule.tag
+            )));
+        }
+    }
+
+    let mut config = (*state.config.get()).clone();
+    config.cleanup.tag_rules = body.tag_rules;
+    tracing::info!(
+        "Cleanup tag rules changed: {} rules",
+        config.cleanup.tag_rules.len()
+    );
+    let tag_rules = config.cleanup.tag_rules.clone();
+    state.config.set(config);
+    Ok(Json(CleanupRulesBody { tag_rules }))
+}
+
+#[derive(Debug, Serialize)]
+struct ReindexResponse {
+    reindexed: bool,
+}
+
+/// Rebuild the full-text search indexes
+async fn reindex(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<ReindexResponse>> {
+    require_admin(&state, credential.as_deref())?;
+
+    tracing::info!("Rebuilding search indexes");
+    state.indexer.rebuild_search_indexes().await?;
+    tracing::info!("Search indexes rebuilt");
+    Ok(Json(ReindexResponse { reindexed: true }))
+}
+
+#[derive(Debug, Serialize)]
+struct BackupInfo {
+    name: String,
+    size_bytes: u64,
+    created_at: DateTime<Utc>,
+}
+
+/// Write an export archive of all clips to `admin.backup_dir`, deleting the
+/// oldest backups beyond `admin.max_backups`. The archive can be restored
+/// with `POST /import`.
+async fn create_backup(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<(StatusCode, Json<BackupInfo>)> {
+    require_admin(&state, credential.as_deref())?;
+
+    let config = state.config.get();
+    let backup_dir = PathBuf::from(&config.admin.backup_dir);
+    tokio::fs::create_dir_all(&backup_dir)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to create backup directory: {}", e)))?;
+
+    // Written under a temporary name, so a failed backup isn't listed
+    let name = format!(
+        "{}{}{}",
+        BACKUP_PREFIX,
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        BACKUP_SUFFIX
+    );
+    let partial_path = backup_dir.join(format!("{}.partial", name));
+    if let Err(e) = state.indexer.export_all_to_file(&partial_path).await {
+        let _ = tokio::fs::remove_file(&partial_path).await;
+        return Err(e.into());
+    }
+    let path = backup_dir.join(&name);
+    tokio::fs::rename(&partial_path, &path)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to save backup: {}", e)))?;
+    tracing::info!("Created backup {}", path.display());
+
+    let max_backups = config.admin.max_backups;
+    let mut backups = read_backups(&backup_dir).await?;
+    if max_backups > 0 && backups.len() > max_backups {
+        for old in backups.split_off(max_backups) {
+            match tokio::fs::remove_file(backup_dir.join(&old.name)).await {
+                Ok(()) => tracing::info!("Deleted old backup {}", old.name),
+                Err(e) => tracing::warn!("Failed to delete old backup {}: {}", old.name, e),
+            }
+        }
+    }
+
+    let info = backups
+        .into_iter()
+        .find(|backup| backup.name == name)
+        .ok_or_else(|| ServerError::Internal("Backup disappeared".to_string()))?;
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+/// List the backups in `admin.backup_dir`, newest first
+async fn list_backups(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<BackupInfo>>> {
+    require_admin(&state, credential.as_deref())?;
+
+    let backup_dir = PathBuf::from(&state.config.get().admin.backup_dir);
+    if !backup_dir.exists() {
+        return Ok(Json(Vec::new()));
+    }
+    Ok(Json(read_backups(&backup_dir).await?))
+}
+
+/// Download a backup archive
+async fn download_backup(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    UrlPath(name): UrlPath<String>,
+) -> Result<Response> {
+    require_admin(&state, credential.as_deref())?;
+
+    // Only names of backups, so the path can't leave the backup directory
+    if !is_backup_name(&name) {
+        return Err(ServerError::NotFound(format!("Backup not found: {}", name)));
+    }
+    let path = Path::new(&state.config.get().admin.backup_dir).join(&name);
+    let file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|_| ServerError::NotFound(format!("Backup not found: {}", name)))?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to read backup: {}", e)))?
+        .len();
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/gzip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", name),
+        )
+        .header(header::CONTENT_LENGTH, size)
+        .body(Body::from_stream(tokio_util::io::ReaderStream::new(file)))
+        .unwrap())
+}
+
+/// Whether `name` is a backup file name: the prefix, a timestamp and the
+/// suffix, with no path separators
+fn is_backup_name(name: &str) -> bool {
+    name.strip_prefix(BACKUP_PREFIX)
+        .and_then(|rest| rest.strip_suffix(BACKUP_SUFFIX))
+        .is_some_and(|timestamp| {
+            !timestamp.is_empty() && timestamp.chars().all(|c| c.is_ascii_digit() || c == '_')
+        })
+}
+
+/// The backups in `backup_dir`, newest first
+async fn read_backups(backup_dir: &Path) -> Result<Vec<BackupInfo>> {
+    let read_error =
+        |e: std::io::Error| ServerError::Internal(format!("Failed to read backups: {}", e));
+
+    let mut backups = Vec::new();
+    let mut entries = tokio::fs::read_dir(backup_dir).await.map_err(read_error)?;
+    while let Some(entry) = entries.next_entry().await.map_err(read_error)? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !is_backup_name(&name) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            name,
+            size_bytes: metadata.len(),
+            created_at: metadata
+                .modified()
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_default(),
+        });
+    }
+    // Names sort by their timestamps
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Reload the configuration file now, like SIGHUP. Only the sections in
+/// `config::RELOADABLE_SECTIONS` take effect; the response lists what
+/// changed.
+async fn reload_config(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<ConfigChanges>> {
+    require_admin(&state, credential.as_deref())?;
+
+    let Some(reloader) = state.config_reloader.get() else {
+        return Err(ServerError::FeatureDisabled(
+            "Configuration reload is only available in the standalone server".to_string(),
+        ));
+    };
+    let changes = reloader.reload().map_err(|e| {
+        tracing::error!("Failed to reload configuration: {}", e);
+        ServerError::Internal(format!("Failed to reload configuration: {}", e))
+    })?;
+    Ok(Json(changes))
+}
+
+#[derive(Debug, Serialize)]
+struct ReloadTlsResponse {
+    reloaded: bool,
+}
+
+/// Reload the TLS certificate and key from disk now, e.g. from a certbot
+/// deploy hook, instead of waiting for `tls.reload_interval_secs`
+async fn reload_tls(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<ReloadTlsResponse>> {
+    require_admin(&state, credential.as_deref())?;
+
+    #[cfg(feature = "tls")]
+    if let Some(reloader) = state.tls_reloader.get() {
+        reloader.reload().await.map_err(|e| {
+            tracing::error!("Failed to reload certificate: {}", e);
+            ServerError::Internal(format!("Failed to reload certificate: {}", e))
+        })?;
+        return Ok(Json(ReloadTlsResponse { reloaded: true }));
+    }
+
+    Err(ServerError::FeatureDisabled(
+        "TLS reload requires HTTPS with tls.cert_path and tls.key_path set".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_backup_name() {
+        assert!(is_backup_name("clipper_backup_20261016_120000.tar.gz"));
+        assert!(!is_backup_name(
+            "clipper_backup_20261016_120000.tar.gz.partial"
+        ));
+        assert!(!is_backup_name("clipper_backup_.tar.gz"));
+        assert!(!is_backup_name("clipper_backup_../../etc/passwd.tar.gz"));
+        assert!(!is_backup_name("other.tar.gz"));
+    }
+}
@@ -1,37 +1,103 @@
+use std::collections::HashMap;
+
 use axum::{
     body::Body,
-    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    extract::{
+        multipart::MultipartError, DefaultBodyLimit, Multipart, Path, Query, Request, State,
+    },
     http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
     response::{IntoResponse, Json, Response},
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
 use clipper_indexer::{
-    ClipboardEntry, HighlightOptions, ImportResult, PagedResult, PagingParams, SearchFilters,
-    SearchResultItem, ShortUrl, Tag,
+    ActivityStats, ClipLink, ClipboardEntry, Collection, ContentFormat, DayActivity, Device,
+    FuzzyOptions, HighlightOptions, ImportResult, PagedResult, PagingParams, SearchFilters,
+    SearchResultItem, ShortUrl, SortDirection, SortField, SortOrder, Tag, TagStats, Template,
+    Visibility, SENSITIVE_TAG,
 };
+use pulldown_cmark::{Options, Parser};
 use serde::{Deserialize, Serialize};
+use tower::{Layer, ServiceExt};
+
+use crate::{
+    auth::Credential,
+    config::{SharedConfig, UploadConfig},
+    error::{Result, ServerError},
+    request_id, secrets,
+    split::Separator,
+    state::AppState,
+    transform::{self, Transform},
+    translation,
+};
+
+/// Largest edit distance accepted for fuzzy search; beyond this nearly
+/// every word matches
+const MAX_FUZZY_DISTANCE: usize = 3;
+
+/// Most IDs accepted by one `POST /clips/batch-get` request
+const MAX_BATCH_GET_IDS: usize = 100;
 
-use crate::{error::Result, state::AppState};
+/// Most months `GET /stats/activity` counts
+const MAX_ACTIVITY_MONTHS: u32 = 24;
 
-pub fn routes(max_upload_size_bytes: u64) -> Router<AppState> {
-    Router::new()
+/// Relation of the link from a transformed clip to its source clip
+const DERIVED_FROM_RELATION: &str = "derived_from";
+
+/// `Cache-Control` for attachment downloads. A stored attachment never
+/// changes, so clients can reuse it for a day without asking again.
+const ATTACHMENT_CACHE_CONTROL: &str = "private, max-age=86400";
+
+/// `Cache-Control` for attachments downloaded through a short URL, which can
+/// expire or be deleted, so clients revalidate with the ETag every time
+const SHARED_ATTACHMENT_CACHE_CONTROL: &str = "private, no-cache";
+
+pub fn routes(config: &SharedConfig) -> Router<AppState> {
+    let routes = Router::new()
         .route("/auth/check", get(check_auth))
         .route("/version", get(get_version))
-        .route("/clips", post(create_clip))
-        .route(
-            "/clips/upload",
-            post(upload_clip_file).layer(DefaultBodyLimit::max(max_upload_size_bytes as usize)),
-        )
         .route("/clips", get(list_clips))
         .route("/clips/search", get(search_clips))
+        .route("/clips/batch-get", post(batch_get_clips))
         .route("/clips/{id}", get(get_clip))
         .route("/clips/{id}", put(update_clip))
         .route("/clips/{id}", delete(delete_clip))
         .route("/clips/{id}/file", get(get_clip_file))
+        .route("/clips/{id}/transform", post(transform_clip))
+        .route("/clips/{id}/split", post(split_clip))
+        .route("/clips/{id}/translate", post(translate_clip))
+        // Clip link endpoints
+        .route("/clips/{id}/links", get(list_clip_links))
+        .route("/clips/{id}/links", post(create_clip_link))
+        .route("/clips/{id}/links/{link_id}", delete(delete_clip_link))
+        .route("/clips/{id}/collections", get(list_clip_collections))
+        // Collection endpoints
+        .route("/collections", get(list_collections))
+        .route("/collections", post(create_collection))
+        .route("/collections/{id}", get(get_collection))
+        .route("/collections/{id}", put(update_collection))
+        .route("/collections/{id}", delete(delete_collection))
+        .route("/collections/{id}/clips", get(list_collection_clips))
+        .route("/collections/{id}/clips", post(add_collection_clip))
+        .route("/collections/{id}/clips", put(reorder_collection_clips))
+        .route(
+            "/collections/{id}/clips/{clip_id}",
+            delete(remove_collection_clip),
+        )
+        // Template endpoints
+        .route("/templates", get(list_templates))
+        .route("/templates", post(create_template))
+        .route("/templates/{id}", get(get_template))
+        .route("/templates/{id}", put(update_template))
+        .route("/templates/{id}", delete(delete_template))
         // Tags endpoints
         .route("/tags", get(list_tags))
         .route("/tags/search", get(search_tags))
+        .route("/tags/stats", get(tag_stats))
+        .route("/tags/rename", post(rename_tag))
+        .route("/devices", get(list_devices))
+        .route("/stats/activity", get(activity_stats))
         // Short URL endpoints
         .route("/clips/{id}/short-url", post(create_short_url))
         .route("/short/{code}", get(get_short_url_redirect))
@@ -40,11 +106,82 @@ pub fn routes(max_upload_size_bytes: u64) -> Router<AppState> {
         // Static assets for shared clip page (no auth required)
         .route("/shared-assets/{filename}", get(serve_asset))
         // Export/Import endpoints
-        .route("/export", get(export_clips))
-        .route(
-            "/import",
-            post(import_clips).layer(DefaultBodyLimit::disable()),
-        )
+        .route("/export", get(export_clips));
+
+    // Only text clips, file uploads and imports get large bodies
+    let text_clip_routes = Router::new()
+        .route("/clips", post(create_clip))
+        .route("/clips/{id}/append", post(append_to_clip))
+        .route("/templates/{id}/instantiate", post(instantiate_template));
+    let upload_routes = Router::new().route("/clips/upload", post(upload_clip_file));
+    let import_routes = Router::new().route("/import", post(import_clips));
+
+    with_body_limit(routes, config, |upload| upload.max_json_size_bytes)
+        .merge(with_body_limit(
+            text_clip_routes,
+            config,
+            UploadConfig::max_text_clip_body_bytes,
+        ))
+        .merge(with_body_limit(upload_routes, config, |upload| {
+            upload.max_size_bytes
+        }))
+        .merge(with_body_limit(import_routes, config, |upload| {
+            upload.max_import_size_bytes
+        }))
+}
+
+/// Limit request bodies on `router`'s routes to the size `limit` picks from
+/// the upload configuration, answering larger ones with a JSON 413 error
+/// (`ServerError::RequestTooLarge`). The limit is looked up on every
+/// request, so it follows configuration reloads.
+fn with_body_limit(
+    router: Router<AppState>,
+    config: &SharedConfig,
+    limit: fn(&UploadConfig) -> u64,
+) -> Router<AppState> {
+    let config = config.clone();
+    router.layer(middleware::from_fn(move |request: Request, next: Next| {
+        let max_size_bytes = limit(&config.get().upload);
+        enforce_body_limit(max_size_bytes, request, next)
+    }))
+}
+
+async fn enforce_body_limit(max_size_bytes: u64, request: Request, next: Next) -> Response {
+    // Refuse a body that is declared too large before reading any of it
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if content_length.is_some_and(|length| length > max_size_bytes) {
+        return ServerError::request_too_large(max_size_bytes).into_response();
+    }
+
+    // Extractors read their limit from the request extensions
+    let Ok(response) = DefaultBodyLimit::max(max_size_bytes as usize)
+        .layer(next)
+        .oneshot(request)
+        .await;
+
+    // Extractors cut off longer streamed bodies with a plain text 413
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return ServerError::request_too_large(max_size_bytes).into_response();
+    }
+    response
+}
+
+/// Convert an error reading a multipart upload, reporting a body over
+/// `max_size_bytes` as `RequestTooLarge` rather than as bad input
+fn multipart_error(context: &str, e: MultipartError, max_size_bytes: u64) -> ServerError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ServerError::request_too_large(max_size_bytes)
+    } else {
+        ServerError::InvalidInput(format!("{}: {}", context, e))
+    }
 }
 
 /// Version information response
@@ -111,13 +248,13 @@ pub struct AuthCheckResponse {
 /// Check if authentication is required
 async fn check_auth(State(state): State<AppState>) -> Json<AuthCheckResponse> {
     Json(AuthCheckResponse {
-        auth_required: state.config.auth.is_enabled(),
+        auth_required: state.config.get().auth.is_enabled(),
     })
 }
 
 /// Get server version and status information
 async fn get_version(State(state): State<AppState>) -> Json<VersionResponse> {
-    let config = &state.config;
+    let config = state.config.get();
 
     let config_info = ConfigInfo {
         port: config.server.port,
@@ -180,6 +317,12 @@ struct CreateClipRequest {
     additional_notes: Option<String>,
     #[serde(default)]
     language: Option<String>,
+    /// How the content is written: plain (default) or markdown
+    #[serde(default)]
+    content_format: Option<ContentFormat>,
+    /// Who can see the clip: private (default), shared or public
+    #[serde(default)]
+    visibility: Option<Visibility>,
 }
 
 #[derive(Debug, Serialize)]
@@ -187,6 +330,7 @@ struct ClipResponse {
     id: String,
     content: String,
     created_at: String,
+    updated_at: String,
     tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     additional_notes: Option<String>,
@@ -196,6 +340,32 @@ struct ClipResponse {
     original_filename: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_format: Option<ContentFormat>,
+    /// The device the clip was created on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_device: Option<String>,
+    /// `content` is the beginning of the text, which is the attachment
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// Who can see the clip: private, shared or public
+    visibility: Visibility,
+    /// Natural language detected in the content, e.g. "en"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detected_language: Option<String>,
+    /// One-line summary from the enrichment, if enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    /// Tags the enrichment suggests, not yet in `tags`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggested_tags: Vec<String>,
+    /// Where the URL in `content` leads, without tracking parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_url: Option<String>,
 }
 
 impl From<ClipboardEntry> for ClipResponse {
@@ -204,11 +374,22 @@ impl From<ClipboardEntry> for ClipResponse {
             id: entry.id,
             content: entry.content,
             created_at: entry.created_at.to_rfc3339(),
+            updated_at: entry.updated_at.to_rfc3339(),
             tags: entry.tags,
             additional_notes: entry.additional_notes,
             file_attachment: entry.file_attachment,
             original_filename: entry.original_filename,
             language: entry.language,
+            color: entry.color,
+            icon: entry.icon,
+            content_format: entry.content_format,
+            source_device: entry.source_device,
+            truncated: entry.truncated,
+            visibility: entry.visibility,
+            detected_language: entry.detected_language,
+            summary: entry.summary,
+            suggested_tags: entry.suggested_tags,
+            canonical_url: entry.canonical_url,
         }
     }
 }
@@ -220,6 +401,11 @@ struct PagedClipResponse {
     page: usize,
     page_size: usize,
     total_pages: usize,
+    /// Whether another page follows; exact even when `skip_total` is set
+    has_more: bool,
+    /// Pass as `cursor` to fetch the next page (date-sorted pages only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
 }
 
 impl From<PagedResult<ClipboardEntry>> for PagedClipResponse {
@@ -230,6 +416,8 @@ impl From<PagedResult<ClipboardEntry>> for PagedClipResponse {
             page: result.page,
             page_size: result.page_size,
             total_pages: result.total_pages,
+            has_more: result.has_more,
+            next_cursor: result.next_cursor,
         }
     }
 }
@@ -240,6 +428,7 @@ struct SearchClipResponse {
     id: String,
     content: String,
     created_at: String,
+    updated_at: String,
     tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     additional_notes: Option<String>,
@@ -249,6 +438,32 @@ struct SearchClipResponse {
     original_filename: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_format: Option<ContentFormat>,
+    /// The device the clip was created on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_device: Option<String>,
+    /// `content` is the beginning of the text, which is the attachment
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// Who can see the clip: private, shared or public
+    visibility: Visibility,
+    /// Natural language detected in the content, e.g. "en"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detected_language: Option<String>,
+    /// One-line summary from the enrichment, if enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    /// Tags the enrichment suggests, not yet in `tags`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggested_tags: Vec<String>,
+    /// Where the URL in `content` leads, without tracking parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_url: Option<String>,
     /// Highlighted content with search terms wrapped by highlight markers.
     /// Only present when highlight_begin and highlight_end query params are provided.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -261,11 +476,22 @@ impl From<SearchResultItem> for SearchClipResponse {
             id: item.entry.id,
             content: item.entry.content,
             created_at: item.entry.created_at.to_rfc3339(),
+            updated_at: item.entry.updated_at.to_rfc3339(),
             tags: item.entry.tags,
             additional_notes: item.entry.additional_notes,
             file_attachment: item.entry.file_attachment,
             original_filename: item.entry.original_filename,
             language: item.entry.language,
+            color: item.entry.color,
+            icon: item.entry.icon,
+            content_format: item.entry.content_format,
+            source_device: item.entry.source_device,
+            truncated: item.entry.truncated,
+            visibility: item.entry.visibility,
+            detected_language: item.entry.detected_language,
+            summary: item.entry.summary,
+            suggested_tags: item.entry.suggested_tags,
+            canonical_url: item.entry.canonical_url,
             highlighted_content: item.highlighted_content,
         }
     }
@@ -278,6 +504,11 @@ struct PagedSearchClipResponse {
     page: usize,
     page_size: usize,
     total_pages: usize,
+    /// Whether another page follows; exact even when `skip_total` is set
+    has_more: bool,
+    /// Pass as `cursor` to fetch the next page (date-sorted pages only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
 }
 
 impl From<PagedResult<SearchResultItem>> for PagedSearchClipResponse {
@@ -292,6 +523,8 @@ impl From<PagedResult<SearchResultItem>> for PagedSearchClipResponse {
             page: result.page,
             page_size: result.page_size,
             total_pages: result.total_pages,
+            has_more: result.has_more,
+            next_cursor: result.next_cursor,
         }
     }
 }
@@ -300,20 +533,97 @@ async fn create_clip(
     State(state): State<AppState>,
     Json(payload): Json<CreateClipRequest>,
 ) -> Result<(StatusCode, Json<ClipResponse>)> {
+    let entry = add_text_clip(&state, payload).await?;
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+/// Store a text clip the way `POST /clips` does: tagged sensitive if it
+/// looks like a secret, cut to the text size limit, then published
+async fn add_text_clip(state: &AppState, payload: CreateClipRequest) -> Result<ClipboardEntry> {
+    let config = state.config.get();
+    // Text over the limit is cut, with the whole text kept as an attachment
+    let max_text_size = match config.upload.max_text_size_bytes {
+        0 => usize::MAX,
+        max_text_size => max_text_size as usize,
+    };
+    let mut tags = payload.tags;
+    if config.sensitive.detect_secrets
+        && !tags.iter().any(|tag| tag == SENSITIVE_TAG)
+        && secrets::contains_secret(&payload.content)
+    {
+        tags.push(SENSITIVE_TAG.to_string());
+    }
     let entry = state
         .indexer
-        .add_entry_from_text(
-            payload.content.clone(),
-            payload.tags.clone(),
+        .add_entry_from_text_truncated(
+            payload.content,
+            tags,
             payload.additional_notes,
             payload.language,
+            payload.content_format,
+            max_text_size,
         )
         .await?;
+    let entry = match payload.visibility {
+        Some(visibility) => state.indexer.set_visibility(&entry.id, visibility).await?,
+        None => entry,
+    };
+
+    publish_new_clip(state, entry).await
+}
+
+/// Run the `on_clip_created` plugin hooks on a new clip, apply the changes
+/// they ask for and notify WebSocket clients
+///
+/// A clip rejected by a plugin is deleted again and the request fails.
+async fn publish_new_clip(state: &AppState, mut entry: ClipboardEntry) -> Result<ClipboardEntry> {
+    request_id::record_clip_id(&entry.id);
+    if !state.plugins.is_empty() {
+        let plugins = state.plugins.clone();
+        let clip = clip_json(entry.clone())?;
+        let changes = tokio::task::spawn_blocking(move || plugins.on_clip_created(clip))
+            .await
+            .map_err(|e| ServerError::Internal(format!("Plugin task failed: {}", e)))?;
+
+        if let Some(reason) = changes.reject {
+            state.indexer.delete_entry(&entry.id).await?;
+            return Err(ServerError::InvalidInput(format!(
+                "Clip rejected by plugin {}",
+                reason
+            )));
+        }
+        if !changes.is_empty() {
+            // Keep the clip as created if a plugin asked for an invalid change
+            match state
+                .indexer
+                .update_entry(
+                    &entry.id,
+                    changes.tags,
+                    changes.additional_notes,
+                    changes.language,
+                    changes.color,
+                    changes.icon,
+                )
+                .await
+            {
+                Ok(updated) => entry = updated,
+                Err(e) => {
+                    tracing::warn!(clip_id = %entry.id, "Failed to apply plugin changes to clip: {}", e)
+                }
+            }
+        }
+    }
 
     // Notify WebSocket clients
     state.notify_new_clip(entry.id.clone(), entry.content.clone(), entry.tags.clone());
 
-    Ok((StatusCode::CREATED, Json(entry.into())))
+    Ok(entry)
+}
+
+/// The clip as the REST API returns it, for plugin hooks
+fn clip_json(entry: ClipboardEntry) -> Result<serde_json::Value> {
+    serde_json::to_value(ClipResponse::from(entry))
+        .map_err(|e| ServerError::Internal(e.to_string()))
 }
 
 #[derive(Debug, Deserialize)]
@@ -324,10 +634,28 @@ struct ListClipsQuery {
     end_date: Option<String>,
     #[serde(default)]
     tags: Option<String>,
+    /// Only clips updated (or created) at or after this RFC 3339 time
+    #[serde(default)]
+    updated_since: Option<String>,
+    /// Only clips created on this device
+    #[serde(default)]
+    source_device: Option<String>,
+    /// Field to sort by: created_at (default), updated_at, content_length or original_filename
+    #[serde(default)]
+    sort_by: SortField,
+    /// Sort direction: asc or desc (default)
+    #[serde(default)]
+    order: SortDirection,
     #[serde(default = "default_page")]
     page: usize,
-    #[serde(default = "default_page_size")]
+    #[serde(default = "default_page_size", alias = "limit")]
     page_size: usize,
+    /// Continue after the `next_cursor` of a previous page instead of using `page`
+    #[serde(default)]
+    cursor: Option<String>,
+    /// Skip counting all matches; `total` is then only a lower bound
+    #[serde(default)]
+    skip_total: bool,
 }
 
 fn default_page() -> usize {
@@ -338,10 +666,149 @@ fn default_page_size() -> usize {
     20
 }
 
+/// Serialize a JSON response with a weak ETag computed from the body.
+/// Returns `304 Not Modified` without a body if the request's `If-None-Match`
+/// already matches, so clients can revalidate cached pages cheaply.
+fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Result<Response> {
+    let body = serde_json::to_vec(value)
+        .map_err(|e| crate::error::ServerError::Internal(e.to_string()))?;
+    let etag = format!("W/\"{}\"", etag_hash(&body));
+
+    if etag_matches(headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::ETAG, etag),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Whether the request's `If-None-Match` matches `etag` (weak comparison)
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',').map(str::trim).any(|candidate| {
+                candidate == "*"
+                    || candidate.trim_start_matches("W/") == etag.trim_start_matches("W/")
+            })
+        })
+}
+
+/// Strong ETag for a stored attachment. Every upload gets a new file key, so
+/// the key identifies the content.
+fn attachment_etag(file_key: &str) -> String {
+    format!("\"{}\"", etag_hash(file_key.as_bytes()))
+}
+
+/// Opaque ETag value for `data`: the hex SHA-256 digest, truncated to 128
+/// bits. Unlike `DefaultHasher`, it is the same across Rust versions and
+/// server restarts, so cached ETags stay valid after an upgrade.
+fn etag_hash(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(data)[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Response with an attachment's content and caching headers, or
+/// `304 Not Modified` if the client already has it
+async fn attachment_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    file_key: &str,
+    cache_control: &'static str,
+    content_disposition: Option<String>,
+) -> Result<Response> {
+    let etag = attachment_etag(file_key);
+    if etag_matches(headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    let bytes = state.indexer.get_file_content(file_key).await?;
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        Body::from(bytes),
+    )
+        .into_response();
+    if let Some(disposition) = content_disposition
+        && let Ok(value) = disposition.parse()
+    {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+    Ok(response)
+}
+
+/// Fail as if the clip didn't exist unless the request may see it. Requests
+/// without a credential reach a server without auth, which shows every clip.
+pub(crate) fn ensure_visible(
+    credential: Option<&Credential>,
+    entry: &ClipboardEntry,
+) -> Result<()> {
+    if credential.is_none_or(|credential| credential.can_see(entry.visibility)) {
+        Ok(())
+    } else {
+        Err(ServerError::NotFound(format!(
+            "Entry with id {} not found",
+            entry.id
+        )))
+    }
+}
+
+/// Visibility levels of the clips aggregates like tag lists and stats may
+/// count for the request, `None` for every clip
+fn visible_levels(credential: Option<&Credential>) -> Option<Vec<Visibility>> {
+    credential.and_then(Credential::visibility_filter)
+}
+
+/// Fail unless the request came from more than a guest, for data that
+/// isn't filtered by clip visibility
+fn forbid_guest(credential: Option<&Credential>, message: &str) -> Result<()> {
+    if matches!(credential, Some(Credential::Guest)) {
+        Err(ServerError::Forbidden(message.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Only the clips the request may see
+pub(crate) fn visible_filters(
+    credential: Option<&Credential>,
+    filters: SearchFilters,
+) -> SearchFilters {
+    match visible_levels(credential) {
+        Some(visibility) => filters.with_visibility(visibility),
+        None => filters,
+    }
+}
+
 async fn list_clips(
     State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    headers: HeaderMap,
     Query(query): Query<ListClipsQuery>,
-) -> Result<Json<PagedClipResponse>> {
+) -> Result<Response> {
     let mut filters = SearchFilters::new();
 
     if let Some(start_date) = query.start_date {
@@ -375,9 +842,29 @@ async fn list_clips(
         }
     }
 
-    let paging = PagingParams::new(query.page, query.page_size);
+    if let Some(updated_since) = query.updated_since {
+        let since = chrono::DateTime::parse_from_rfc3339(&updated_since)
+            .map_err(|e| {
+                crate::error::ServerError::InvalidInput(format!("Invalid updated_since: {}", e))
+            })?
+            .with_timezone(&chrono::Utc);
+        filters = filters.with_updated_since(since);
+    }
+
+    if let Some(source_device) = query.source_device.filter(|d| !d.is_empty()) {
+        filters = filters.with_source_device(source_device);
+    }
+
+    filters = visible_filters(credential.as_deref(), filters);
+    filters = filters.with_sort_by(query.sort_by, query.order);
+
+    let mut paging =
+        PagingParams::new(query.page, query.page_size).with_skip_total(query.skip_total);
+    if let Some(cursor) = query.cursor {
+        paging = paging.with_cursor(cursor);
+    }
     let result = state.indexer.list_entries(filters, paging).await?;
-    Ok(Json(result.into()))
+    json_with_etag(&headers, &PagedClipResponse::from(result))
 }
 
 #[derive(Debug, Deserialize)]
@@ -389,20 +876,48 @@ struct SearchClipsQuery {
     end_date: Option<String>,
     #[serde(default)]
     tags: Option<String>,
+    /// Only clips updated (or created) at or after this RFC 3339 time
+    #[serde(default)]
+    updated_since: Option<String>,
+    /// Only clips created on this device
+    #[serde(default)]
+    source_device: Option<String>,
     #[serde(default = "default_page")]
     page: usize,
-    #[serde(default = "default_page_size")]
+    #[serde(default = "default_page_size", alias = "limit")]
     page_size: usize,
+    /// Continue after the `next_cursor` of a previous page instead of using `page`
+    #[serde(default)]
+    cursor: Option<String>,
+    /// Skip counting all matches; `total` is then only a lower bound
+    #[serde(default)]
+    skip_total: bool,
     /// Optional highlight begin marker (e.g., "<mark>"). Both begin and end must be provided to enable highlighting.
     #[serde(default)]
     highlight_begin: Option<String>,
     /// Optional highlight end marker (e.g., "</mark>"). Both begin and end must be provided to enable highlighting.
     #[serde(default)]
     highlight_end: Option<String>,
+    /// Enable typo-tolerant matching of search terms
+    #[serde(default)]
+    fuzzy: bool,
+    /// Maximum edit distance for fuzzy matching (default: 1). Implies `fuzzy`.
+    #[serde(default)]
+    fuzzy_distance: Option<usize>,
+    /// Result order: "date" (by `sort_by`, default) or "relevance" (best match first)
+    #[serde(default)]
+    sort: SortOrder,
+    /// Field to sort by when `sort` is "date": created_at (default), updated_at, content_length or original_filename
+    #[serde(default)]
+    sort_by: SortField,
+    /// Sort direction for `sort_by`: asc or desc (default)
+    #[serde(default)]
+    order: SortDirection,
 }
 
 async fn search_clips(
     State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
     Query(query): Query<SearchClipsQuery>,
 ) -> Result<Json<PagedSearchClipResponse>> {
     let mut filters = SearchFilters::new();
@@ -438,26 +953,98 @@ async fn search_clips(
         }
     }
 
+    if let Some(updated_since) = query.updated_since {
+        let since = chrono::DateTime::parse_from_rfc3339(&updated_since)
+            .map_err(|e| {
+                crate::error::ServerError::InvalidInput(format!("Invalid updated_since: {}", e))
+            })?
+            .with_timezone(&chrono::Utc);
+        filters = filters.with_updated_since(since);
+    }
+
+    if let Some(source_device) = query.source_device.filter(|d| !d.is_empty()) {
+        filters = filters.with_source_device(source_device);
+    }
+
+    filters = visible_filters(credential.as_deref(), filters);
+    filters = filters
+        .with_sort(query.sort)
+        .with_sort_by(query.sort_by, query.order);
+
     // Build highlight options if both begin and end markers are provided
     let highlight = match (query.highlight_begin, query.highlight_end) {
         (Some(begin), Some(end)) => Some(HighlightOptions::new(begin, end)),
         _ => None,
     };
 
-    let paging = PagingParams::new(query.page, query.page_size);
+    let fuzzy = match query.fuzzy_distance {
+        Some(distance) if distance > MAX_FUZZY_DISTANCE => {
+            return Err(crate::error::ServerError::InvalidInput(format!(
+                "fuzzy_distance must be at most {}",
+                MAX_FUZZY_DISTANCE
+            )));
+        }
+        Some(distance) => Some(FuzzyOptions::new(distance)),
+        None => query.fuzzy.then(FuzzyOptions::default),
+    };
+
+    let mut paging =
+        PagingParams::new(query.page, query.page_size).with_skip_total(query.skip_total);
+    if let Some(cursor) = query.cursor {
+        paging = paging.with_cursor(cursor);
+    }
     let result = state
         .indexer
-        .search_entries_with_highlight(&query.q, filters, paging, highlight)
+        .search_entries_with_highlight(&query.q, filters, paging, highlight, fuzzy)
         .await?;
     Ok(Json(result.into()))
 }
 
 async fn get_clip(
     State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    headers: HeaderMap,
     Path(id): Path<String>,
-) -> Result<Json<ClipResponse>> {
+) -> Result<Response> {
+    request_id::record_clip_id(&id);
     let entry = state.indexer.get_entry(&id).await?;
-    Ok(Json(entry.into()))
+    ensure_visible(credential.as_deref(), &entry)?;
+    json_with_etag(&headers, &ClipResponse::from(entry))
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchGetRequest {
+    ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchGetResponse {
+    /// Clips in the order of the requested IDs; unknown IDs and clips the
+    /// request may not see are left out
+    items: Vec<ClipResponse>,
+}
+
+/// Get several clips in one round trip
+async fn batch_get_clips(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Json(payload): Json<BatchGetRequest>,
+) -> Result<Json<BatchGetResponse>> {
+    if payload.ids.len() > MAX_BATCH_GET_IDS {
+        return Err(crate::error::ServerError::InvalidInput(format!(
+            "At most {} IDs can be requested at once",
+            MAX_BATCH_GET_IDS
+        )));
+    }
+
+    let entries = state.indexer.get_entries(&payload.ids).await?;
+    Ok(Json(BatchGetResponse {
+        items: entries
+            .into_iter()
+            .filter(|entry| ensure_visible(credential.as_deref(), entry).is_ok())
+            .map(ClipResponse::from)
+            .collect(),
+    }))
 }
 
 #[derive(Debug, Deserialize)]
@@ -468,6 +1055,18 @@ struct UpdateClipRequest {
     additional_notes: Option<String>,
     #[serde(default)]
     language: Option<String>,
+    /// Hex color (`#rgb` or `#rrggbb`) or color name; empty string clears it
+    #[serde(default)]
+    color: Option<String>,
+    /// Emoji or icon name; empty string clears it
+    #[serde(default)]
+    icon: Option<String>,
+    /// How the content is written: plain or markdown
+    #[serde(default)]
+    content_format: Option<ContentFormat>,
+    /// Who can see the clip: private, shared or public
+    #[serde(default)]
+    visibility: Option<Visibility>,
 }
 
 async fn update_clip(
@@ -475,10 +1074,87 @@ async fn update_clip(
     Path(id): Path<String>,
     Json(payload): Json<UpdateClipRequest>,
 ) -> Result<Json<ClipResponse>> {
-    let entry = state
+    request_id::record_clip_id(&id);
+    let mut entry = state
+        .indexer
+        .update_entry(
+            &id,
+            payload.tags,
+            payload.additional_notes,
+            payload.language,
+            payload.color,
+            payload.icon,
+        )
+        .await?;
+
+    if let Some(content_format) = payload.content_format {
+        entry = state
+            .indexer
+            .set_content_format(&id, content_format)
+            .await?;
+    }
+
+    if let Some(visibility) = payload.visibility {
+        entry = state.indexer.set_visibility(&id, visibility).await?;
+    }
+
+    // Notify WebSocket clients
+    state.notify_updated_clip(id);
+
+    Ok(Json(entry.into()))
+}
+
+#[derive(Debug, Deserialize)]
+struct AppendClipRequest {
+    content: String,
+    /// Put between the clip's text and `content`
+    #[serde(default = "default_append_separator")]
+    separator: String,
+}
+
+fn default_append_separator() -> String {
+    "\n".to_string()
+}
+
+/// Append text to a text clip, e.g. from the desktop app's append mode
+///
+/// The clip can't grow past `upload.max_text_size_bytes`, and text that
+/// looks like a secret tags it sensitive, as in `create_clip`.
+async fn append_to_clip(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AppendClipRequest>,
+) -> Result<Json<ClipResponse>> {
+    request_id::record_clip_id(&id);
+    let config = state.config.get();
+    let existing = state.indexer.get_entry(&id).await?;
+    let max_text_size = config.upload.max_text_size_bytes;
+    let appended_size = existing.content.len() + payload.separator.len() + payload.content.len();
+    if max_text_size > 0 && appended_size as u64 > max_text_size {
+        return Err(ServerError::RequestTooLarge {
+            message: format!(
+                "Appending would make the clip longer than the maximum text size ({} bytes)",
+                max_text_size
+            ),
+            max_size_bytes: max_text_size,
+        });
+    }
+
+    let mut entry = state
         .indexer
-        .update_entry(&id, payload.tags, payload.additional_notes, payload.language)
+        .append_to_entry(&id, &payload.content, &payload.separator)
         .await?;
+    if config.sensitive.detect_secrets
+        && !entry.tags.iter().any(|tag| tag == SENSITIVE_TAG)
+        && secrets::contains_secret(&payload.content)
+    {
+        let mut tags = entry.tags.clone();
+        tags.push(SENSITIVE_TAG.to_string());
+        entry = state
+            .indexer
+            .update_entry(&id, Some(tags), None, None, None, None)
+            .await?;
+    }
 
     // Notify WebSocket clients
     state.notify_updated_clip(id);
@@ -487,23 +1163,31 @@ async fn update_clip(
 }
 
 async fn delete_clip(State(state): State<AppState>, Path(id): Path<String>) -> Result<StatusCode> {
+    request_id::record_clip_id(&id);
+    let visibility = state.indexer.get_entry(&id).await?.visibility;
     state.indexer.delete_entry(&id).await?;
 
     // Notify WebSocket clients
-    state.notify_deleted_clip(id);
+    state.notify_deleted_clip(id, visibility);
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-async fn get_clip_file(State(state): State<AppState>, Path(id): Path<String>) -> Result<Vec<u8>> {
+async fn get_clip_file(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    request_id::record_clip_id(&id);
     let entry = state.indexer.get_entry(&id).await?;
+    ensure_visible(credential.as_deref(), &entry)?;
 
     let file_key = entry.file_attachment.ok_or_else(|| {
         crate::error::ServerError::NotFound("No file attachment for this clip".to_string())
     })?;
 
-    let bytes = state.indexer.get_file_content(&file_key).await?;
-    Ok(bytes.to_vec())
+    attachment_response(&state, &headers, &file_key, ATTACHMENT_CACHE_CONTROL, None).await
 }
 
 async fn upload_clip_file(
@@ -515,37 +1199,48 @@ async fn upload_clip_file(
     let mut tags: Vec<String> = Vec::new();
     let mut additional_notes: Option<String> = None;
     let mut content_override: Option<String> = None;
+    let max_size = state.config.get().upload.max_size_bytes;
 
     // Process multipart form data
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|e| crate::error::ServerError::InvalidInput(format!("Multipart error: {}", e)))?
+        .map_err(|e| multipart_error("Multipart error", e, max_size))?
     {
         let field_name = field.name().unwrap_or("").to_string();
 
         match field_name.as_str() {
             "file" => {
                 original_filename = field.file_name().map(|s| s.to_string());
-                file_data = Some(field.bytes().await.map_err(|e| {
-                    crate::error::ServerError::InvalidInput(format!("Failed to read file: {}", e))
-                })?);
+                file_data = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| multipart_error("Failed to read file", e, max_size))?,
+                );
             }
             "tags" => {
-                let tags_str = field.text().await.map_err(|e| {
-                    crate::error::ServerError::InvalidInput(format!("Failed to read tags: {}", e))
-                })?;
+                let tags_str = field
+                    .text()
+                    .await
+                    .map_err(|e| multipart_error("Failed to read tags", e, max_size))?;
                 tags = tags_str.split(',').map(|s| s.trim().to_string()).collect();
             }
             "additional_notes" => {
-                additional_notes = Some(field.text().await.map_err(|e| {
-                    crate::error::ServerError::InvalidInput(format!("Failed to read notes: {}", e))
-                })?);
+                additional_notes = Some(
+                    field
+                        .text()
+                        .await
+                        .map_err(|e| multipart_error("Failed to read notes", e, max_size))?,
+                );
             }
             "content" => {
-                content_override = Some(field.text().await.map_err(|e| {
-                    crate::error::ServerError::InvalidInput(format!("Failed to read content: {}", e))
-                })?);
+                content_override = Some(
+                    field
+                        .text()
+                        .await
+                        .map_err(|e| multipart_error("Failed to read content", e, max_size))?,
+                );
             }
             _ => {
                 // Ignore unknown fields
@@ -558,18 +1253,23 @@ async fn upload_clip_file(
         .ok_or_else(|| crate::error::ServerError::InvalidInput("Missing file field".to_string()))?;
 
     // Check file size limit
-    let max_size = state.config.upload.max_size_bytes;
     if file_data.len() as u64 > max_size {
         let max_size_mb = max_size as f64 / (1024.0 * 1024.0);
         let file_size_mb = file_data.len() as f64 / (1024.0 * 1024.0);
-        return Err(crate::error::ServerError::PayloadTooLarge(format!(
-            "File size ({:.2} MB) exceeds maximum allowed size ({:.2} MB)",
-            file_size_mb, max_size_mb
-        )));
+        return Err(crate::error::ServerError::RequestTooLarge {
+            message: format!(
+                "File size ({:.2} MB) exceeds maximum allowed size ({:.2} MB)",
+                file_size_mb, max_size_mb
+            ),
+            max_size_bytes: max_size,
+        });
     }
 
     let original_filename = original_filename.unwrap_or_else(|| "uploaded_file".to_string());
 
+    let scan_config = state.config.get().scan.clone();
+    crate::scan::check_upload(&scan_config, &original_filename, &file_data).await?;
+
     // Create entry from file content with optional content override
     let entry = state
         .indexer
@@ -582,8 +1282,7 @@ async fn upload_clip_file(
         )
         .await?;
 
-    // Notify WebSocket clients
-    state.notify_new_clip(entry.id.clone(), entry.content.clone(), entry.tags.clone());
+    let entry = publish_new_clip(&state, entry).await?;
 
     Ok((StatusCode::CREATED, Json(entry.into())))
 }
@@ -639,10 +1338,15 @@ struct ListTagsQuery {
 /// List all tags with pagination
 async fn list_tags(
     State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
     Query(query): Query<ListTagsQuery>,
 ) -> Result<Json<PagedTagResponse>> {
     let paging = PagingParams::new(query.page, query.page_size);
-    let result = state.indexer.list_tags(paging).await?;
+    let visibility = visible_levels(credential.as_deref());
+    let result = state
+        .indexer
+        .list_tags(paging, visibility.as_deref())
+        .await?;
     Ok(Json(result.into()))
 }
 
@@ -658,72 +1362,906 @@ struct SearchTagsQuery {
 /// Search tags using full-text search
 async fn search_tags(
     State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
     Query(query): Query<SearchTagsQuery>,
 ) -> Result<Json<PagedTagResponse>> {
     let paging = PagingParams::new(query.page, query.page_size);
-    let result = state.indexer.search_tags(&query.q, paging).await?;
+    let visibility = visible_levels(credential.as_deref());
+    let result = state
+        .indexer
+        .search_tags(&query.q, paging, visibility.as_deref())
+        .await?;
     Ok(Json(result.into()))
 }
 
-// ==================== Short URL Endpoints ====================
-
-#[derive(Debug, Deserialize)]
-struct CreateShortUrlRequest {
-    /// Optional expiration time in hours (overrides server default)
-    #[serde(default)]
-    expires_in_hours: Option<u32>,
-}
-
 #[derive(Debug, Serialize)]
-struct ShortUrlResponse {
-    id: String,
-    clip_id: String,
-    short_code: String,
-    full_url: String,
-    created_at: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    expires_at: Option<String>,
+struct TagStatsResponse {
+    text: String,
+    count: usize,
 }
 
-impl ShortUrlResponse {
-    fn from_short_url(short_url: ShortUrl, base_url: &str) -> Self {
-        let base = base_url.trim_end_matches('/');
+impl From<TagStats> for TagStatsResponse {
+    fn from(stats: TagStats) -> Self {
         Self {
-            id: short_url.id,
-            clip_id: short_url.clip_id,
-            short_code: short_url.short_code.clone(),
-            full_url: format!("{}/s/{}", base, short_url.short_code),
-            created_at: short_url.created_at.to_rfc3339(),
-            expires_at: short_url.expires_at.map(|dt| dt.to_rfc3339()),
+            text: stats.text,
+            count: stats.count,
         }
     }
 }
 
-/// Create a short URL for a clip
-async fn create_short_url(
+/// Get the number of clips using each tag, most used first
+async fn tag_stats(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-    Json(payload): Json<CreateShortUrlRequest>,
-) -> Result<(StatusCode, Json<ShortUrlResponse>)> {
-    // Check if short URL feature is enabled
-    if !state.config.short_url.is_enabled() {
-        return Err(crate::error::ServerError::FeatureDisabled(
-            "Short URL functionality is disabled. Set CLIPPER_SHORT_URL_BASE to enable.".to_string(),
-        ));
-    }
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<TagStatsResponse>>> {
+    let visibility = visible_levels(credential.as_deref());
+    let stats = state.indexer.tag_stats(visibility.as_deref()).await?;
+    let items = stats.into_iter().map(TagStatsResponse::from).collect();
+    Ok(Json(items))
+}
 
-    // Calculate expiration time
-    let expires_at = match payload.expires_in_hours {
-        Some(0) => None, // Explicit no expiration
-        Some(hours) => Some(chrono::Utc::now() + chrono::Duration::hours(hours as i64)),
+#[derive(Debug, Deserialize)]
+struct RenameTagRequest {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RenameTagResponse {
+    updated_count: usize,
+    clip_ids: Vec<String>,
+}
+
+/// Rename a tag on all clips that use it
+async fn rename_tag(
+    State(state): State<AppState>,
+    Json(payload): Json<RenameTagRequest>,
+) -> Result<Json<RenameTagResponse>> {
+    let from = payload.from.trim();
+    let to = payload.to.trim();
+    if from.is_empty() || to.is_empty() {
+        return Err(crate::error::ServerError::InvalidInput(
+            "Both 'from' and 'to' tags are required".to_string(),
+        ));
+    }
+
+    let clip_ids = state.indexer.rename_tag(from, to).await?;
+
+    // Notify WebSocket clients
+    for id in &clip_ids {
+        state.notify_updated_clip(id.clone());
+    }
+
+    Ok(Json(RenameTagResponse {
+        updated_count: clip_ids.len(),
+        clip_ids,
+    }))
+}
+
+// ==================== Device Endpoints ====================
+
+#[derive(Debug, Serialize)]
+struct DeviceResponse {
+    name: String,
+    clip_count: usize,
+    /// When the newest clip from the device was created
+    last_seen: String,
+}
+
+impl From<Device> for DeviceResponse {
+    fn from(device: Device) -> Self {
+        Self {
+            name: device.name,
+            clip_count: device.clip_count,
+            last_seen: device.last_seen.to_rfc3339(),
+        }
+    }
+}
+
+/// List the devices clips were created on, most recently seen first
+async fn list_devices(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<DeviceResponse>>> {
+    let visibility = visible_levels(credential.as_deref());
+    let devices = state.indexer.list_devices(visibility.as_deref()).await?;
+    let items = devices.into_iter().map(DeviceResponse::from).collect();
+    Ok(Json(items))
+}
+
+// ==================== Stats Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct ActivityQuery {
+    /// Number of months to count, up to and including today (default: 12)
+    #[serde(default = "default_activity_months")]
+    months: u32,
+    /// Time zone the days are counted in, in minutes east of UTC (default: 0)
+    #[serde(default)]
+    tz_offset: i32,
+    /// Number of most used tags to return (default: 10)
+    #[serde(default = "default_top_tags")]
+    top_tags: usize,
+}
+
+fn default_activity_months() -> u32 {
+    12
+}
+
+fn default_top_tags() -> usize {
+    10
+}
+
+#[derive(Debug, Serialize)]
+struct ActivityResponse {
+    /// First day counted
+    start_date: String,
+    /// Last day counted, today in the requested time zone
+    end_date: String,
+    /// Clips created in the period
+    total: usize,
+    /// Every day of the period in order, including days without clips
+    days: Vec<DayActivityResponse>,
+    /// Most used tags of the clips in the period, without `$host:` tags
+    top_tags: Vec<TagStatsResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct DayActivityResponse {
+    date: String,
+    count: usize,
+}
+
+impl From<DayActivity> for DayActivityResponse {
+    fn from(day: DayActivity) -> Self {
+        Self {
+            date: day.date.to_string(),
+            count: day.count,
+        }
+    }
+}
+
+impl ActivityResponse {
+    fn new(start_date: chrono::NaiveDate, stats: ActivityStats) -> Self {
+        let end_date = stats.days.last().map_or(start_date, |day| day.date);
+        Self {
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            total: stats.days.iter().map(|day| day.count).sum(),
+            days: stats
+                .days
+                .into_iter()
+                .map(DayActivityResponse::from)
+                .collect(),
+            top_tags: stats
+                .top_tags
+                .into_iter()
+                .map(TagStatsResponse::from)
+                .collect(),
+        }
+    }
+}
+
+/// Get the number of clips created on each day of the last months and their
+/// most used tags, for an activity heatmap
+async fn activity_stats(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<Json<ActivityResponse>> {
+    if query.months == 0 || query.months > MAX_ACTIVITY_MONTHS {
+        return Err(ServerError::InvalidInput(format!(
+            "months must be between 1 and {}",
+            MAX_ACTIVITY_MONTHS
+        )));
+    }
+    let offset =
+        chrono::FixedOffset::east_opt(query.tz_offset.saturating_mul(60)).ok_or_else(|| {
+            ServerError::InvalidInput(format!("Invalid tz_offset: {}", query.tz_offset))
+        })?;
+
+    let today = chrono::Utc::now().with_timezone(&offset).date_naive();
+    let start_date = today
+        .checked_sub_months(chrono::Months::new(query.months))
+        .and_then(|date| date.succ_opt())
+        .unwrap_or(today);
+
+    let visibility = visible_levels(credential.as_deref());
+    let stats = state
+        .indexer
+        .activity_stats(start_date, offset, query.top_tags, visibility.as_deref())
+        .await?;
+    Ok(Json(ActivityResponse::new(start_date, stats)))
+}
+
+// ==================== Transform Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct TransformClipRequest {
+    /// Transforms to apply, in order
+    transforms: Vec<Transform>,
+}
+
+/// Apply text transforms to a clip and store the result as a new clip
+///
+/// The new clip gets the source clip's tags and a `derived_from` link to the
+/// source clip, which is left unchanged.
+async fn transform_clip(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<TransformClipRequest>,
+) -> Result<(StatusCode, Json<ClipResponse>)> {
+    request_id::record_clip_id(&id);
+    if payload.transforms.is_empty() {
+        return Err(crate::error::ServerError::InvalidInput(
+            "At least one transform is required".to_string(),
+        ));
+    }
+
+    let source = state.indexer.get_entry(&id).await?;
+    if source.file_attachment.is_some() {
+        return Err(crate::error::ServerError::InvalidInput(
+            "Clips with file attachments cannot be transformed".to_string(),
+        ));
+    }
+
+    let content = transform::apply_all(&payload.transforms, &source.content)?;
+    let entry = state
+        .indexer
+        .add_entry_from_text(content, source.tags, None, None)
+        .await?;
+    state
+        .indexer
+        .add_link(&entry.id, &source.id, DERIVED_FROM_RELATION)
+        .await?;
+
+    let entry = publish_new_clip(&state, entry).await?;
+
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+// ==================== Translate Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct TranslateClipQuery {
+    /// Language to translate into, e.g. `de` or `pt-BR`
+    to: String,
+}
+
+/// Translate a text clip with the configured provider and store the
+/// translation as a new clip
+///
+/// As with transforms, the new clip gets the source clip's tags and a
+/// `derived_from` link to the source clip. Sensitive clips are never sent to
+/// the provider.
+async fn translate_clip(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<TranslateClipQuery>,
+) -> Result<(StatusCode, Json<ClipResponse>)> {
+    request_id::record_clip_id(&id);
+    let config = state.config.get().translation.clone();
+    if !config.is_enabled() {
+        return Err(ServerError::FeatureDisabled(
+            "Translation is not configured on this server".to_string(),
+        ));
+    }
+    translation::validate_language_code(&query.to)?;
+
+    let source = state.indexer.get_entry(&id).await?;
+    if source.file_attachment.is_some() {
+        return Err(ServerError::InvalidInput(
+            "Clips with file attachments cannot be translated".to_string(),
+        ));
+    }
+    if source.tags.iter().any(|tag| tag == SENSITIVE_TAG) {
+        return Err(ServerError::InvalidInput(
+            "Sensitive clips cannot be translated".to_string(),
+        ));
+    }
+    if source.content.trim().is_empty() {
+        return Err(ServerError::InvalidInput(
+            "The clip has no text to translate".to_string(),
+        ));
+    }
+    if source.content.chars().count() > config.max_input_chars {
+        return Err(ServerError::InvalidInput(format!(
+            "Clips longer than {} characters cannot be translated",
+            config.max_input_chars
+        )));
+    }
+
+    let translated = translation::translate(&config, &source.content, &query.to).await?;
+    let entry = state
+        .indexer
+        .add_entry_from_text(translated.text, source.tags, None, None)
+        .await?;
+    state
+        .indexer
+        .add_link(&entry.id, &source.id, DERIVED_FROM_RELATION)
+        .await?;
+
+    let entry = publish_new_clip(&state, entry).await?;
+
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+// ==================== Split Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct SplitClipRequest {
+    /// Where to split: `{"by": "lines"}`, `{"by": "delimiter", "delimiter": ","}`
+    /// or `{"by": "regex", "pattern": "..."}`
+    #[serde(flatten)]
+    separator: Separator,
+    /// Keep the whitespace around each part
+    #[serde(default)]
+    keep_whitespace: bool,
+    /// Delete the source clip once it's split
+    #[serde(default)]
+    delete_source: bool,
+}
+
+/// Split a text clip into one new clip per line, delimiter or regex match
+///
+/// The new clips get the source clip's tags, language, content format and
+/// visibility, and are stored in one transaction. Parts rejected by a plugin
+/// are left out of the response.
+async fn split_clip(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<SplitClipRequest>,
+) -> Result<(StatusCode, Json<Vec<ClipResponse>>)> {
+    request_id::record_clip_id(&id);
+    let source = state.indexer.get_entry(&id).await?;
+    if source.file_attachment.is_some() {
+        return Err(ServerError::InvalidInput(
+            "Clips with file attachments cannot be split".to_string(),
+        ));
+    }
+
+    let parts = payload
+        .separator
+        .split(&source.content, !payload.keep_whitespace)?;
+    let entries = state
+        .indexer
+        .split_entry(&id, parts, !payload.delete_source)
+        .await?;
+    if payload.delete_source {
+        state.notify_deleted_clip(id, source.visibility);
+    }
+
+    let mut clips = Vec::with_capacity(entries.len());
+    for entry in entries {
+        match publish_new_clip(&state, entry).await {
+            Ok(entry) => clips.push(ClipResponse::from(entry)),
+            Err(ServerError::InvalidInput(reason)) => {
+                tracing::info!("Skipped split part: {}", reason)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok((StatusCode::CREATED, Json(clips)))
+}
+
+// ==================== Clip Link Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct CreateClipLinkRequest {
+    /// The clip to link to
+    target_id: String,
+    /// Kind of relationship, e.g. "derived_from" or "reply_to"
+    relation: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ClipLinkResponse {
+    id: String,
+    source_id: String,
+    target_id: String,
+    relation: String,
+    created_at: String,
+}
+
+impl From<ClipLink> for ClipLinkResponse {
+    fn from(link: ClipLink) -> Self {
+        Self {
+            id: link.id,
+            source_id: link.source_id,
+            target_id: link.target_id,
+            relation: link.relation,
+            created_at: link.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// List the links from and to a clip
+async fn list_clip_links(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<ClipLinkResponse>>> {
+    request_id::record_clip_id(&id);
+    // Unknown clips are a 404 rather than an empty list
+    let entry = state.indexer.get_entry(&id).await?;
+    ensure_visible(credential.as_deref(), &entry)?;
+
+    let links = state.indexer.get_links_for_clip(&id).await?;
+    let items = links.into_iter().map(ClipLinkResponse::from).collect();
+    Ok(Json(items))
+}
+
+/// Link a clip to another clip
+async fn create_clip_link(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<CreateClipLinkRequest>,
+) -> Result<(StatusCode, Json<ClipLinkResponse>)> {
+    request_id::record_clip_id(&id);
+    let link = state
+        .indexer
+        .add_link(&id, &payload.target_id, &payload.relation)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(link.into())))
+}
+
+/// Remove a link from a clip
+async fn delete_clip_link(
+    State(state): State<AppState>,
+    Path((id, link_id)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    request_id::record_clip_id(&id);
+    state.indexer.delete_link(&id, &link_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ==================== Collection Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct CreateCollectionRequest {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateCollectionRequest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CollectionResponse {
+    id: String,
+    name: String,
+    description: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<Collection> for CollectionResponse {
+    fn from(collection: Collection) -> Self {
+        Self {
+            id: collection.id,
+            name: collection.name,
+            description: collection.description,
+            created_at: collection.created_at.to_rfc3339(),
+            updated_at: collection.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListCollectionClipsQuery {
+    #[serde(default = "default_page")]
+    page: usize,
+    #[serde(default = "default_page_size")]
+    page_size: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddCollectionClipRequest {
+    clip_id: String,
+    /// Zero-based index to insert the clip at; appended when omitted
+    #[serde(default)]
+    position: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReorderCollectionRequest {
+    /// Every clip of the collection exactly once, in the new order
+    clip_ids: Vec<String>,
+}
+
+/// List all collections
+async fn list_collections(State(state): State<AppState>) -> Result<Json<Vec<CollectionResponse>>> {
+    let collections = state.indexer.list_collections().await?;
+    let items = collections
+        .into_iter()
+        .map(CollectionResponse::from)
+        .collect();
+    Ok(Json(items))
+}
+
+/// Create an empty collection
+async fn create_collection(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateCollectionRequest>,
+) -> Result<(StatusCode, Json<CollectionResponse>)> {
+    let collection = state
+        .indexer
+        .create_collection(&payload.name, payload.description)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(collection.into())))
+}
+
+async fn get_collection(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<CollectionResponse>> {
+    let collection = state.indexer.get_collection(&id).await?;
+    Ok(Json(collection.into()))
+}
+
+/// Rename a collection or change its description
+async fn update_collection(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateCollectionRequest>,
+) -> Result<Json<CollectionResponse>> {
+    let collection = state
+        .indexer
+        .update_collection(&id, payload.name.as_deref(), payload.description)
+        .await?;
+
+    Ok(Json(collection.into()))
+}
+
+/// Delete a collection, keeping its clips
+async fn delete_collection(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.indexer.delete_collection(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the clips of a collection in their manual order
+async fn list_collection_clips(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+    Query(query): Query<ListCollectionClipsQuery>,
+) -> Result<Json<PagedClipResponse>> {
+    let paging = PagingParams::new(query.page, query.page_size);
+    let mut result = state.indexer.list_collection_entries(&id, paging).await?;
+    // Like deleted clips, clips the request may not see leave gaps in pages
+    result
+        .items
+        .retain(|entry| ensure_visible(credential.as_deref(), entry).is_ok());
+    Ok(Json(result.into()))
+}
+
+/// Add a clip to a collection, or move it if it is already there
+async fn add_collection_clip(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AddCollectionClipRequest>,
+) -> Result<StatusCode> {
+    state
+        .indexer
+        .add_to_collection(&id, &payload.clip_id, payload.position)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Set the manual order of a collection
+async fn reorder_collection_clips(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<ReorderCollectionRequest>,
+) -> Result<StatusCode> {
+    state
+        .indexer
+        .reorder_collection(&id, &payload.clip_ids)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a clip from a collection, keeping the clip
+async fn remove_collection_clip(
+    State(state): State<AppState>,
+    Path((id, clip_id)): Path<(String, String)>,
+) -> Result<StatusCode> {
+    state.indexer.remove_from_collection(&id, &clip_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the collections containing a clip
+async fn list_clip_collections(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<CollectionResponse>>> {
+    request_id::record_clip_id(&id);
+    // Unknown clips are a 404 rather than an empty list
+    let entry = state.indexer.get_entry(&id).await?;
+    ensure_visible(credential.as_deref(), &entry)?;
+
+    let collections = state.indexer.get_collections_for_clip(&id).await?;
+    let items = collections
+        .into_iter()
+        .map(CollectionResponse::from)
+        .collect();
+    Ok(Json(items))
+}
+
+// ==================== Template Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct CreateTemplateRequest {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    content: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateTemplateRequest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateResponse {
+    id: String,
+    name: String,
+    description: Option<String>,
+    content: String,
+    tags: Vec<String>,
+    /// Names of the `{{placeholders}}` in the content, in order of first use
+    placeholders: Vec<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<Template> for TemplateResponse {
+    fn from(template: Template) -> Self {
+        Self {
+            placeholders: template.placeholders(),
+            id: template.id,
+            name: template.name,
+            description: template.description,
+            content: template.content,
+            tags: template.tags,
+            created_at: template.created_at.to_rfc3339(),
+            updated_at: template.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InstantiateTemplateRequest {
+    /// Placeholder values by name
+    #[serde(default)]
+    values: HashMap<String, String>,
+    /// Tags added to the template's own
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    additional_notes: Option<String>,
+    #[serde(default)]
+    visibility: Option<Visibility>,
+}
+
+/// Templates have no visibility, so guests can't read them
+const GUEST_TEMPLATES_MESSAGE: &str = "The guest token can't read templates";
+
+/// List all templates
+async fn list_templates(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<TemplateResponse>>> {
+    forbid_guest(credential.as_deref(), GUEST_TEMPLATES_MESSAGE)?;
+    let templates = state.indexer.list_templates().await?;
+    let items = templates.into_iter().map(TemplateResponse::from).collect();
+    Ok(Json(items))
+}
+
+async fn create_template(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTemplateRequest>,
+) -> Result<(StatusCode, Json<TemplateResponse>)> {
+    let template = state
+        .indexer
+        .create_template(
+            &payload.name,
+            payload.description,
+            payload.content,
+            payload.tags,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(template.into())))
+}
+
+async fn get_template(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+) -> Result<Json<TemplateResponse>> {
+    forbid_guest(credential.as_deref(), GUEST_TEMPLATES_MESSAGE)?;
+    let template = state.indexer.get_template(&id).await?;
+    Ok(Json(template.into()))
+}
+
+async fn update_template(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateTemplateRequest>,
+) -> Result<Json<TemplateResponse>> {
+    let template = state
+        .indexer
+        .update_template(
+            &id,
+            payload.name.as_deref(),
+            payload.description,
+            payload.content,
+            payload.tags,
+        )
+        .await?;
+
+    Ok(Json(template.into()))
+}
+
+/// Delete a template, keeping the clips created from it
+async fn delete_template(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.indexer.delete_template(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a text clip from a template with its placeholders filled in
+async fn instantiate_template(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<InstantiateTemplateRequest>,
+) -> Result<(StatusCode, Json<ClipResponse>)> {
+    let template = state.indexer.get_template(&id).await?;
+    let content = template.render(&payload.values)?;
+
+    let mut tags = template.tags;
+    for tag in payload.tags {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    let request = CreateClipRequest {
+        content,
+        tags,
+        additional_notes: payload.additional_notes,
+        language: None,
+        content_format: None,
+        visibility: payload.visibility,
+    };
+    let entry = add_text_clip(&state, request).await?;
+
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+// ==================== Short URL Endpoints ====================
+
+#[derive(Debug, Deserialize)]
+struct CreateShortUrlRequest {
+    /// Optional expiration time in hours (overrides server default)
+    #[serde(default)]
+    expires_in_hours: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShortUrlResponse {
+    id: String,
+    clip_id: String,
+    short_code: String,
+    full_url: String,
+    created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+}
+
+impl ShortUrlResponse {
+    fn from_short_url(short_url: ShortUrl, base_url: &str) -> Self {
+        let base = base_url.trim_end_matches('/');
+        Self {
+            id: short_url.id,
+            clip_id: short_url.clip_id,
+            short_code: short_url.short_code.clone(),
+            full_url: format!("{}/s/{}", base, short_url.short_code),
+            created_at: short_url.created_at.to_rfc3339(),
+            expires_at: short_url.expires_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+/// Base for a short link's `full_url`: the configured `short_url.base_url`,
+/// with its scheme and host replaced by `X-Forwarded-Proto` and
+/// `X-Forwarded-Host` when a reverse proxy sets them. The path is kept, so
+/// it should include any `server.base_path`.
+fn short_url_base(base_url: &str, headers: &HeaderMap) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let (scheme, rest) = base_url.split_once("://").unwrap_or(("https", base_url));
+    let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+
+    let scheme = forwarded_header(headers, "x-forwarded-proto")
+        .map(str::to_ascii_lowercase)
+        .filter(|proto| proto == "http" || proto == "https")
+        .unwrap_or_else(|| scheme.to_string());
+    // Only accept host[:port], so the header can't inject a path or userinfo
+    let host = forwarded_header(headers, "x-forwarded-host")
+        .filter(|host| {
+            !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-.:[]".contains(c))
+        })
+        .unwrap_or(host);
+
+    format!("{}://{}{}", scheme, host, path)
+}
+
+/// The first value of a header that proxies append to, i.e. the one set by
+/// the proxy closest to the client
+fn forwarded_header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .next()
+        .map(str::trim)
+}
+
+/// Create a short URL for a clip
+async fn create_short_url(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateShortUrlRequest>,
+) -> Result<(StatusCode, Json<ShortUrlResponse>)> {
+    request_id::record_clip_id(&id);
+    // Check if short URL feature is enabled
+    let config = state.config.get();
+    if !config.short_url.is_enabled() {
+        return Err(crate::error::ServerError::FeatureDisabled(
+            "Short URL functionality is disabled. Set CLIPPER_SHORT_URL_BASE to enable.".to_string(),
+        ));
+    }
+
+    // Calculate expiration time
+    let expires_at = match payload.expires_in_hours {
+        Some(0) => None, // Explicit no expiration
+        Some(hours) => Some(chrono::Utc::now() + chrono::Duration::hours(hours as i64)),
         None => {
             // Use server default
-            if state.config.short_url.default_expiration_hours > 0 {
+            if config.short_url.default_expiration_hours > 0 {
                 Some(
                     chrono::Utc::now()
-                        + chrono::Duration::hours(
-                            state.config.short_url.default_expiration_hours as i64,
-                        ),
+                        + chrono::Duration::hours(config.short_url.default_expiration_hours as i64),
                 )
             } else {
                 None
@@ -731,10 +2269,31 @@ async fn create_short_url(
         }
     };
 
+    // Short URLs only resolve for public clips, so sharing makes the clip
+    // public
+    let entry = state.indexer.get_entry(&id).await?;
+    if entry.visibility != Visibility::Public {
+        state
+            .indexer
+            .set_visibility(&id, Visibility::Public)
+            .await?;
+        state.notify_updated_clip(id.clone());
+    }
+
     let short_url = state.indexer.create_short_url(&id, expires_at).await?;
 
-    let base_url = state.config.short_url.base_url.as_ref().unwrap();
-    let response = ShortUrlResponse::from_short_url(short_url, base_url);
+    let base_url = config.short_url.base_url.as_ref().unwrap();
+    let response = ShortUrlResponse::from_short_url(short_url, &short_url_base(base_url, &headers));
+
+    // Run the on_clip_shared plugin hooks in the background
+    if !state.plugins.is_empty() {
+        let entry = state.indexer.get_entry(&id).await?;
+        let clip = clip_json(entry)?;
+        let share =
+            serde_json::to_value(&response).map_err(|e| ServerError::Internal(e.to_string()))?;
+        let plugins = state.plugins.clone();
+        tokio::task::spawn_blocking(move || plugins.on_clip_shared(clip, share));
+    }
 
     Ok((StatusCode::CREATED, Json(response)))
 }
@@ -801,6 +2360,10 @@ struct ResolveShortUrlQuery {
     /// Override content type (useful for download links in HTML)
     #[serde(default)]
     accept: Option<String>,
+    /// How to render the HTML page: markdown or plain; defaults to the
+    /// clip's content format
+    #[serde(default)]
+    render: Option<ContentFormat>,
 }
 
 /// Resolve short URL and return content based on Accept header or query parameter
@@ -810,6 +2373,9 @@ struct ResolveShortUrlQuery {
 /// - `text/plain`: Plain text content
 /// - `application/json`: JSON with minimal metadata (no tags/notes)
 /// - `application/octet-stream`: File attachment if exists, otherwise error
+///
+/// The HTML page renders Markdown clips (or any text clip with
+/// `?render=markdown`) as sanitized HTML; `?render=plain` shows the raw text.
 async fn resolve_short_url(
     State(state): State<AppState>,
     Path(code): Path<String>,
@@ -819,8 +2385,14 @@ async fn resolve_short_url(
     // Get short URL and check if expired
     let short_url = state.indexer.get_short_url(&code).await?;
 
-    // Get the clip
+    // Get the clip, which only public clips give away
     let entry = state.indexer.get_entry(&short_url.clip_id).await?;
+    if entry.visibility != Visibility::Public {
+        return Err(ServerError::NotFound(format!(
+            "Short URL with code '{}' not found",
+            code
+        )));
+    }
 
     // Determine content type from query parameter first, then Accept header
     let accept = query.accept.as_deref().unwrap_or_else(|| {
@@ -834,21 +2406,16 @@ async fn resolve_short_url(
     let response = if accept.contains("application/octet-stream") {
         // Return file attachment
         if let Some(file_key) = &entry.file_attachment {
-            let bytes = state.indexer.get_file_content(file_key).await?;
-            let filename = entry
-                .original_filename
-                .as_deref()
-                .unwrap_or("attachment");
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/octet-stream")
-                .header(
-                    header::CONTENT_DISPOSITION,
-                    format!("attachment; filename=\"{}\"", filename),
-                )
-                .body(Body::from(bytes.to_vec()))
-                .unwrap()
+            let filename = entry.original_filename.as_deref().unwrap_or("attachment");
+
+            attachment_response(
+                &state,
+                &headers,
+                file_key,
+                SHARED_ATTACHMENT_CACHE_CONTROL,
+                Some(format!("attachment; filename=\"{}\"", filename)),
+            )
+            .await?
         } else {
             return Err(crate::error::ServerError::NotFound(
                 "This clip has no file attachment".to_string(),
@@ -901,7 +2468,7 @@ async fn resolve_short_url(
         // Build image HTML if it's an image file
         let image_html = if is_image {
             format!(
-                r#"<div class="image-container"><img src="/s/{}?accept=application/octet-stream" alt="{}" class="shared-image" /></div>"#,
+                r#"<div class="image-container"><img src="{}?accept=application/octet-stream" alt="{}" class="shared-image" /></div>"#,
                 code,
                 html_escape(&original_filename.clone().unwrap_or_default())
             )
@@ -913,7 +2480,7 @@ async fn resolve_short_url(
         // Use id="download-btn" so JavaScript can localize the text
         let download_link = if entry.file_attachment.is_some() {
             format!(
-                r#"<a class="btn" id="download-btn" href="/s/{}?accept=application/octet-stream">Download File</a>"#,
+                r#"<a class="btn" id="download-btn" href="{}?accept=application/octet-stream">Download File</a>"#,
                 code
             )
         } else {
@@ -939,10 +2506,19 @@ async fn resolve_short_url(
         // Check if this is a file attachment
         let is_file = entry.file_attachment.is_some();
 
+        // Render Markdown text clips, escape everything else
+        let render = query.render.or(entry.content_format).unwrap_or_default();
+        let (content_class, content_html) = if render == ContentFormat::Markdown && !is_file {
+            ("content markdown-content", render_markdown(&content))
+        } else {
+            ("content", html_escape(&content))
+        };
+
         // Load template and substitute placeholders
         let html = include_str!("templates/shared_clip.html")
             .replace("{{BUILD_VERSION}}", build_version())
-            .replace("{{CONTENT}}", &html_escape(&content))
+            .replace("{{CONTENT_CLASS}}", content_class)
+            .replace("{{CONTENT}}", &content_html)
             .replace("{{IMAGE_HTML}}", &image_html)
             .replace("{{IS_IMAGE}}", if is_image { "true" } else { "false" })
             .replace("{{IS_FILE}}", if is_file { "true" } else { "false" })
@@ -973,6 +2549,15 @@ fn html_escape(s: &str) -> String {
         .replace('\'', "&#39;")
 }
 
+/// Render Markdown to HTML, removing scripts, event handlers and other
+/// unsafe markup
+fn render_markdown(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, Parser::new_ext(markdown, options));
+    ammonia::clean(&html)
+}
+
 /// Check if a filename has an image extension
 fn is_image_file(filename: &str) -> bool {
     const IMAGE_EXTENSIONS: &[&str] = &[".png", ".jpg", ".jpeg", ".gif", ".webp", ".bmp", ".svg"];
@@ -1025,6 +2610,13 @@ async fn serve_asset(Path(filename): Path<String>) -> Result<Response> {
 
 // ==================== Export/Import Endpoints ====================
 
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    /// Leave out clips tagged `$sensitive`
+    #[serde(default)]
+    exclude_sensitive: bool,
+}
+
 /// Export all clips to a tar.gz archive
 ///
 /// Returns a tar.gz file containing:
@@ -1034,8 +2626,16 @@ async fn serve_asset(Path(filename): Path<String>) -> Result<Response> {
 /// The archive is written to a temporary file and streamed to the client,
 /// avoiding loading the entire archive into memory.
 ///
-/// Short URLs are NOT included in the export.
-async fn export_clips(State(state): State<AppState>) -> Result<Response> {
+/// Short URLs are NOT included in the export, nor are sensitive clips with
+/// `?exclude_sensitive=true`. Guests can't export, as the archive holds
+/// private clips too.
+async fn export_clips(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response> {
+    forbid_guest(credential.as_deref(), "The guest token can't export clips")?;
+
     // Create a temporary file to write the archive to
     let temp_file = tempfile::NamedTempFile::new().map_err(|e| {
         crate::error::ServerError::Internal(format!("Failed to create temp file: {}", e))
@@ -1043,7 +2643,14 @@ async fn export_clips(State(state): State<AppState>) -> Result<Response> {
     let temp_path = temp_file.path().to_path_buf();
 
     // Export directly to the temp file (memory-efficient for large archives)
-    state.indexer.export_all_to_file(&temp_path).await?;
+    if query.exclude_sensitive {
+        state
+            .indexer
+            .export_all_except_tag_to_file(SENSITIVE_TAG, &temp_path)
+            .await?;
+    } else {
+        state.indexer.export_all_to_file(&temp_path).await?;
+    }
 
     // Get the file size for Content-Length header
     let file_metadata = tokio::fs::metadata(&temp_path).await.map_err(|e| {
@@ -1115,12 +2722,13 @@ async fn import_clips(
             })?;
 
     let mut found_archive = false;
+    let max_size = state.config.get().upload.max_import_size_bytes;
 
     // Process multipart form data, streaming chunks directly to the temp file
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|e| crate::error::ServerError::InvalidInput(format!("Multipart error: {}", e)))?
+        .map_err(|e| multipart_error("Multipart error", e, max_size))?
     {
         let field_name = field.name().unwrap_or("").to_string();
 
@@ -1129,9 +2737,11 @@ async fn import_clips(
 
             // Stream chunks directly to the temp file
             let mut stream = field;
-            while let Some(chunk) = stream.chunk().await.map_err(|e| {
-                crate::error::ServerError::InvalidInput(format!("Failed to read chunk: {}", e))
-            })? {
+            while let Some(chunk) = stream
+                .chunk()
+                .await
+                .map_err(|e| multipart_error("Failed to read chunk", e, max_size))?
+            {
                 async_file.write_all(&chunk).await.map_err(|e| {
                     crate::error::ServerError::Internal(format!("Failed to write to temp file: {}", e))
                 })?;
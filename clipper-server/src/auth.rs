@@ -2,15 +2,66 @@
 
 use axum::{
     extract::{Request, State},
-    http::{header, StatusCode},
+    http::{header, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use clipper_indexer::{PairedDevice, Visibility};
 use serde_json::json;
+use tracing::warn;
 
+use crate::error::ServerError;
 use crate::state::AppState;
 
+/// How a request authenticated. The auth middleware stores it in the request
+/// extensions.
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// The shared bearer token from the server config
+    SharedToken,
+    /// The token of a paired device
+    PairedDevice(PairedDevice),
+    /// The admin token from the server config, only accepted by the `/admin`
+    /// endpoints
+    AdminToken,
+    /// The guest token from the server config, which can only read clips
+    Guest,
+}
+
+impl Credential {
+    /// Whether this credential may see a clip: guests only see shared and
+    /// public clips
+    pub fn can_see(&self, visibility: Visibility) -> bool {
+        !matches!(self, Credential::Guest) || visibility != Visibility::Private
+    }
+
+    /// Visibilities to filter lists and searches by, `None` if every clip
+    /// can be seen
+    pub fn visibility_filter(&self) -> Option<Vec<Visibility>> {
+        matches!(self, Credential::Guest).then(|| vec![Visibility::Shared, Visibility::Public])
+    }
+}
+
+/// Check a token against the shared bearer token and the paired device tokens
+pub async fn authenticate(state: &AppState, token: &str) -> Option<Credential> {
+    let config = state.config.get();
+    if config.auth.validate_token(token) {
+        return Some(Credential::SharedToken);
+    }
+    if config.auth.validate_guest_token(token) {
+        return Some(Credential::Guest);
+    }
+
+    match state.indexer.authenticate_paired_device(token).await {
+        Ok(device) => device.map(Credential::PairedDevice),
+        Err(e) => {
+            warn!("Failed to check paired device token: {}", e);
+            None
+        }
+    }
+}
+
 /// Extract token from query string (e.g., ?token=xxx)
 /// The token value is URL-decoded since it may contain special characters
 fn extract_query_token(query: Option<&str>) -> Option<String> {
@@ -37,18 +88,40 @@ fn extract_query_token(query: Option<&str>) -> Option<String> {
 /// - A valid `Authorization: Bearer <token>` header, OR
 /// - A valid `?token=<token>` query parameter (useful for file downloads, WebSocket, etc.)
 ///
+/// Valid tokens are the configured bearer token and the tokens of paired devices.
+/// The guest token, if configured, is also accepted, but only for requests
+/// that read (see `guest_may`); others are answered with 403 Forbidden.
+///
 /// Certain endpoints are always allowed without authentication:
-/// - GET /health - Health check endpoint
+/// - GET /health, /healthz, /readyz - Health check endpoints
 /// - GET /version - Version and configuration info
 /// - GET /auth/check - Authentication status check
 /// - GET /ws - WebSocket endpoint (handles its own message-based authentication)
 /// - GET /s/{code} - Public short URL resolver
+/// - POST /pairing/claim - Redeems a pairing code for a device token
+///
+/// When an admin token is configured, `/admin/*` requests may send it instead,
+/// in the `Authorization` header only; the endpoints check for it.
 pub async fn auth_middleware(
     State(state): State<AppState>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    let auth_config = &state.config.auth;
+    let config = state.config.get();
+    let auth_config = &config.auth;
+
+    if auth_config.admin_token_enabled()
+        && request.uri().path().starts_with("/admin/")
+        && request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| auth_config.validate_admin_token(token))
+    {
+        request.extensions_mut().insert(Credential::AdminToken);
+        return next.run(request).await;
+    }
 
     // If auth is not enabled, allow all requests
     if !auth_config.is_enabled() {
@@ -59,11 +132,15 @@ pub async fn auth_middleware(
     // WebSocket endpoint handles its own message-based authentication
     // /s/{code} is the public short URL resolver (no auth required)
     // /shared-assets/* serves static files for shared clip pages (no auth required)
+    // /pairing/claim checks the pairing code instead
     let path = request.uri().path();
     if path == "/health"
+        || path == "/healthz"
+        || path == "/readyz"
         || path == "/version"
         || path == "/auth/check"
         || path == "/ws"
+        || path == "/pairing/claim"
         || path.starts_with("/s/")
         || path.starts_with("/shared-assets/")
     {
@@ -88,8 +165,8 @@ pub async fn auth_middleware(
 
         let token = &header_str[7..]; // Skip "Bearer "
 
-        if auth_config.validate_token(token) {
-            return next.run(request).await;
+        if let Some(credential) = authenticate(&state, token).await {
+            return run_authenticated(request, next, credential).await;
         } else {
             return unauthorized_response("Invalid bearer token");
         }
@@ -97,8 +174,8 @@ pub async fn auth_middleware(
 
     // Fall back to query parameter token (useful for file downloads, images, etc.)
     if let Some(token) = extract_query_token(request.uri().query()) {
-        if auth_config.validate_token(&token) {
-            return next.run(request).await;
+        if let Some(credential) = authenticate(&state, &token).await {
+            return run_authenticated(request, next, credential).await;
         } else {
             return unauthorized_response("Invalid token");
         }
@@ -107,6 +184,28 @@ pub async fn auth_middleware(
     unauthorized_response("Missing Authorization header or token parameter")
 }
 
+/// Run an authenticated request with its credential in the extensions,
+/// unless it came from a guest and would change something
+async fn run_authenticated(mut request: Request, next: Next, credential: Credential) -> Response {
+    if matches!(credential, Credential::Guest) && !guest_may(request.method(), request.uri().path())
+    {
+        return ServerError::Forbidden("The guest token can only read clips".to_string())
+            .into_response();
+    }
+    request.extensions_mut().insert(credential);
+    next.run(request).await
+}
+
+/// Whether a guest may make a request: any read, including the batch get
+/// that is a POST, but nothing that creates, changes or deletes data
+fn guest_may(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        Method::POST => path == "/clips/batch-get",
+        _ => false,
+    }
+}
+
 /// Create an unauthorized response with a JSON body.
 fn unauthorized_response(message: &str) -> Response {
     (
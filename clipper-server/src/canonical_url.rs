@@ -0,0 +1,352 @@
+//! Canonical URLs of new URL clips.
+//!
+//! While `canonical_url.enabled` is set, a background task looks at each new
+//! clip holding a single http(s) URL, follows its redirects (short links,
+//! `http` to `https`, ...) and removes tracking parameters matching
+//! `strip_params`. When the result differs from the URL it is stored in the
+//! clip's `canonical_url`; the clip's content stays as it was copied.
+//!
+//! Redirects are followed with `HEAD` requests, and only to hosts whose
+//! addresses are all public, so URL clips can't make the server probe its
+//! own network (`allow_private_addresses` turns that check off). Each
+//! request connects to the address that was checked. Clips tagged
+//! `$sensitive` are left alone, since visiting them could use up one-time
+//! links.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use clipper_indexer::SENSITIVE_TAG;
+use reqwest::{header, redirect, StatusCode, Url};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    config::CanonicalUrlConfig,
+    error::{Result, ServerError},
+    state::{AppState, ClipUpdate},
+};
+
+/// Store the canonical URL of each new URL clip while canonicalization is
+/// enabled. Runs until the clip update channel is closed.
+pub async fn run_canonical_url_dispatcher(state: AppState) {
+    let mut updates = state.clip_updates.subscribe();
+
+    loop {
+        match updates.recv().await {
+            Ok(ClipUpdate::NewClip {
+                id, content, tags, ..
+            }) => {
+                let config = state.config.get().canonical_url.clone();
+                if !config.enabled || tags.iter().any(|tag| tag == SENSITIVE_TAG) {
+                    continue;
+                }
+                let Some(url) = parse_url_clip(&content) else {
+                    continue;
+                };
+                // Redirects can take a while, so don't hold up the next update
+                tokio::spawn(canonicalize_clip(state.clone(), config, id, url));
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Canonical URL dispatcher skipped {} clip updates", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn canonicalize_clip(state: AppState, config: CanonicalUrlConfig, id: String, url: Url) {
+    let mut canonical = url.clone();
+    if config.resolve_redirects {
+        match resolve_redirects(&config, url.clone()).await {
+            Ok(resolved) => canonical = resolved,
+            Err(e) => tracing::debug!(clip_id = %id, "Failed to resolve redirects of clip: {}", e),
+        }
+    }
+    strip_tracking_params(&mut canonical, &config.strip_params);
+    if canonical == url {
+        return;
+    }
+
+    match state
+        .indexer
+        .set_canonical_url(&id, Some(canonical.to_string()))
+        .await
+    {
+        Ok(_) => state.notify_updated_clip(id),
+        Err(e) => tracing::warn!(clip_id = %id, "Failed to store canonical URL of clip: {}", e),
+    }
+}
+
+/// The URL a clip holds, if its content is a single http(s) URL
+fn parse_url_clip(content: &str) -> Option<Url> {
+    let content = content.trim();
+    if content.is_empty() || content.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = Url::parse(content).ok()?;
+    (matches!(url.scheme(), "http" | "https") && url.host().is_some()).then_some(url)
+}
+
+/// Remove the query parameters whose names match one of `rules`: a name, or
+/// a prefix ending in `*`, ignoring case. The other parameters keep their
+/// order and encoding.
+fn strip_tracking_params(url: &mut Url, rules: &[String]) {
+    let Some(query) = url.query() else {
+        return;
+    };
+    let query = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            let name = urlencoding::decode(name).unwrap_or_else(|_| name.into());
+            !pair.is_empty() && !rules.iter().any(|rule| matches_rule(&name, rule))
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    url.set_query((!query.is_empty()).then_some(query.as_str()));
+}
+
+fn matches_rule(name: &str, rule: &str) -> bool {
+    let rule = rule.trim();
+    match rule.strip_suffix('*') {
+        Some(prefix) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => name.eq_ignore_ascii_case(rule),
+    }
+}
+
+/// Follow the redirects of `url` and return where they end
+async fn resolve_redirects(config: &CanonicalUrlConfig, mut url: Url) -> Result<Url> {
+    for _ in 0..=config.max_redirects {
+        let client = client_for(config, &url).await?;
+        let mut response = client
+            .head(url.clone())
+            .send()
+            .await
+            .map_err(request_failed)?;
+        // Some servers only answer GET
+        if matches!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+        ) {
+            response = client
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(request_failed)?;
+        }
+        if !response.status().is_redirection() {
+            return Ok(url);
+        }
+
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or_else(|| {
+                ServerError::Internal(format!("{} redirects without a location", url))
+            })?;
+        let next = url
+            .join(location)
+            .map_err(|e| ServerError::Internal(format!("Invalid redirect location: {}", e)))?;
+        if !matches!(next.scheme(), "http" | "https") {
+            return Err(ServerError::Internal(format!(
+                "Redirect to a {} URL",
+                next.scheme()
+            )));
+        }
+        url = next;
+    }
+    Err(ServerError::Internal(format!(
+        "More than {} redirects",
+        config.max_redirects
+    )))
+}
+
+/// An HTTP client for one request to `url`. Unless private addresses are
+/// allowed, the host must only resolve to public addresses, and the client
+/// connects to those rather than resolving the host again.
+async fn client_for(config: &CanonicalUrlConfig, url: &Url) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .timeout(config.timeout());
+
+    if !config.allow_private_addresses {
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addresses: Vec<SocketAddr> = match host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => {
+                let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+                    .await
+                    .map_err(|e| {
+                        ServerError::Internal(format!("Failed to resolve {}: {}", host, e))
+                    })?
+                    .collect();
+                builder = builder.resolve_to_addrs(host, &addresses);
+                addresses
+            }
+        };
+        if addresses.is_empty() || !addresses.iter().all(|address| is_public(address.ip())) {
+            return Err(ServerError::Internal(format!(
+                "{} is not a public address",
+                host
+            )));
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| ServerError::Internal(format!("Failed to create HTTP client: {}", e)))
+}
+
+fn request_failed(e: reqwest::Error) -> ServerError {
+    ServerError::Internal(format!("Request failed: {}", e))
+}
+
+/// Whether `ip` is reachable on the internet, i.e. not loopback, private,
+/// link-local, shared (CGNAT), documentation or otherwise reserved
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network"
+        || a == 0
+        // Shared address space (CGNAT), 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // NAT64 of an address that isn't public, 64:ff9b::/96
+        || (segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0]
+            && !is_public_v4(Ipv4Addr::from(
+                (u32::from(segments[6]) << 16) | u32::from(segments[7]),
+            ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_clip() {
+        assert_eq!(
+            parse_url_clip("  https://example.com/a?b=c\n").map(String::from),
+            Some("https://example.com/a?b=c".to_string())
+        );
+        assert!(parse_url_clip("http://sho.rt/x").is_some());
+        for content in [
+            "",
+            "example.com",
+            "ftp://example.com/file",
+            "mailto:someone@example.com",
+            "see https://example.com",
+            "https://example.com\nhttps://example.org",
+        ] {
+            assert!(parse_url_clip(content).is_none(), "{:?}", content);
+        }
+    }
+
+    #[test]
+    fn test_strip_tracking_params() {
+        let rules = CanonicalUrlConfig::default().strip_params;
+        let strip = |url: &str| {
+            let mut url = Url::parse(url).unwrap();
+            strip_tracking_params(&mut url, &rules);
+            url.to_string()
+        };
+        assert_eq!(
+            strip("https://example.com/a?id=1&utm_source=feed&UTM_Medium=x&fbclid=abc&q=a%20b#top"),
+            "https://example.com/a?id=1&q=a%20b#top"
+        );
+        assert_eq!(
+            strip("https://example.com/?utm_source=feed&gclid=1"),
+            "https://example.com/"
+        );
+        // utm_ is a prefix rule, fbclid isn't
+        assert_eq!(
+            strip("https://example.com/?utm=1&fbclid_x=2"),
+            "https://example.com/?utm=1&fbclid_x=2"
+        );
+        assert_eq!(strip("https://example.com/"), "https://example.com/");
+
+        let mut url = Url::parse("https://example.com/?ref=home&refresh=1").unwrap();
+        strip_tracking_params(&mut url, &["ref".to_string()]);
+        assert_eq!(url.as_str(), "https://example.com/?refresh=1");
+    }
+
+    #[test]
+    fn test_is_public() {
+        for ip in [
+            "93.184.216.34",
+            "2606:2800:220:1::1",
+            "::ffff:93.184.216.34",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a00:1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_redirects_rejects_private_addresses() {
+        let config = CanonicalUrlConfig::default();
+        let url = Url::parse("http://127.0.0.1:9/").unwrap();
+        assert!(matches!(
+            resolve_redirects(&config, url).await,
+            Err(ServerError::Internal(message)) if message.contains("not a public address")
+        ));
+    }
+}
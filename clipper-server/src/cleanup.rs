@@ -1,56 +1,226 @@
+use crate::config::{CleanupMode, Retention, SensitiveConfig};
 use crate::{AppState, CleanupConfig};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use clipper_indexer::{ClipboardEntry, SENSITIVE_TAG};
+use std::path::PathBuf;
+use tokio::time::Instant;
 
 /// Default interval for short URL cleanup (1 hour)
 const SHORT_URL_CLEANUP_INTERVAL_SECS: u64 = 3600;
 
+/// Interval between checks for expired sensitive clips (1 minute)
+const SENSITIVE_CLEANUP_INTERVAL_SECS: u64 = 60;
+
 /// Run the clip cleanup task periodically based on configuration.
-/// This task deletes old clips that have no meaningful tags (only $host: tags or no tags).
-pub async fn run_clip_cleanup_task(state: AppState, config: CleanupConfig) {
-    if !config.is_active() {
-        tracing::debug!("Clip cleanup task not active, skipping");
-        return;
+/// This task deletes old clips that have no meaningful tags (only $host: tags or no tags),
+/// and clips whose tags have a retention rule that has run out.
+///
+/// The cleanup settings are read again whenever the configuration is
+/// reloaded; a changed interval starts counting from the reload.
+pub async fn run_clip_cleanup_task(state: AppState) {
+    let mut config_changes = state.config.subscribe();
+    let mut config = state.config.get().cleanup.clone();
+    log_cleanup_config(&config);
+    let mut next_run = Instant::now() + config.interval();
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(next_run), if config.is_active() => {
+                if let Err(e) = cleanup_clips(&state, &config).await {
+                    tracing::error!("Clip cleanup failed: {}", e);
+                }
+                next_run = Instant::now() + config.interval();
+            }
+            changed = config_changes.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                let reloaded = state.config.get().cleanup.clone();
+                if reloaded != config {
+                    config = reloaded;
+                    log_cleanup_config(&config);
+                    next_run = Instant::now() + config.interval();
+                }
+            }
+        }
+    }
+}
+
+fn log_cleanup_config(config: &CleanupConfig) {
+    if config.is_active() {
+        tracing::info!(
+            "Auto-cleanup enabled: retention={} days, interval={} hours, tag rules={}, mode={:?}",
+            config.retention_days,
+            config.interval_hours,
+            config.tag_rules.len(),
+            config.mode
+        );
+    } else {
+        tracing::debug!("Auto-cleanup disabled");
     }
+}
+
+/// Delete the clips that have outlived their retention and notify connected
+/// clients. Clips without meaningful tags (only $host: tags or no tags) are
+/// kept for `retention_days`, others as `tag_rules` say (see
+/// [`CleanupConfig::retention_for`]). In archive mode they're exported to
+/// `archive_dir` first, and kept if that fails.
+///
+/// # Returns
+/// The IDs of the deleted clips
+pub async fn cleanup_clips(
+    state: &AppState,
+    config: &CleanupConfig,
+) -> clipper_indexer::Result<Vec<String>> {
+    let now = Utc::now();
+    // Calculate the cutoff date
+    let cutoff = retention_cutoff(now, config.retention_days);
 
-    let interval = config.interval();
     tracing::info!(
-        "Starting clip cleanup task: retention={} days, interval={} hours",
+        "Running clip cleanup: deleting clips older than {} (retention: {} days, tag rules: {})",
+        cutoff.format("%Y-%m-%d %H:%M:%S UTC"),
         config.retention_days,
-        config.interval_hours
+        config.tag_rules.len()
     );
 
-    loop {
-        // Wait for the configured interval
-        tokio::time::sleep(interval).await;
+    let entries = preview_cleanup(state, config).await?;
+    if entries.is_empty() {
+        tracing::info!("Clip cleanup completed: no clips to delete");
+        return Ok(Vec::new());
+    }
 
-        // Calculate the cutoff date
-        let cutoff = Utc::now() - Duration::days(config.retention_days as i64);
+    if config.mode == CleanupMode::Archive {
+        archive_clips(state, config, &entries).await?;
+    }
+    let deleted_ids = state.indexer.delete_entries(&entries).await?;
+    tracing::info!(
+        "Clip cleanup completed: deleted {} clips",
+        deleted_ids.len()
+    );
 
-        tracing::info!(
-            "Running clip cleanup: deleting clips older than {} (retention: {} days)",
-            cutoff.format("%Y-%m-%d %H:%M:%S UTC"),
-            config.retention_days
-        );
+    // Notify connected clients about cleaned up clips
+    state.notify_clips_cleaned_up(&entries);
+    Ok(deleted_ids)
+}
 
-        // Run clip cleanup
-        match state.indexer.cleanup_entries(None, Some(cutoff)).await {
-            Ok(deleted_ids) => {
-                if deleted_ids.is_empty() {
-                    tracing::info!("Clip cleanup completed: no clips to delete");
-                } else {
-                    tracing::info!("Clip cleanup completed: deleted {} clips", deleted_ids.len());
+/// Export expired clips into a dated archive in `archive_dir`
+async fn archive_clips(
+    state: &AppState,
+    config: &CleanupConfig,
+    entries: &[ClipboardEntry],
+) -> clipper_indexer::Result<()> {
+    let archive_dir = PathBuf::from(&config.archive_dir);
+    tokio::fs::create_dir_all(&archive_dir).await?;
 
-                    // Notify connected clients about cleaned up clips
-                    state.notify_clips_cleaned_up(deleted_ids);
-                }
-            }
-            Err(e) => {
-                tracing::error!("Clip cleanup failed: {}", e);
-            }
+    // Written under a temporary name, so a failed archive isn't mistaken for
+    // a complete one
+    let name = format!(
+        "clipper_archive_{}.tar.gz",
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+    let partial_path = archive_dir.join(format!("{}.partial", name));
+    if let Err(e) = state
+        .indexer
+        .export_entries_to_file(entries, &partial_path)
+        .await
+    {
+        let _ = tokio::fs::remove_file(&partial_path).await;
+        return Err(e);
+    }
+    let path = archive_dir.join(&name);
+    tokio::fs::rename(&partial_path, &path).await?;
+    tracing::info!("Archived {} clips to {}", entries.len(), path.display());
+    Ok(())
+}
+
+/// The clips [`cleanup_clips`] would delete now, oldest first, without
+/// deleting them
+pub async fn preview_cleanup(
+    state: &AppState,
+    config: &CleanupConfig,
+) -> clipper_indexer::Result<Vec<ClipboardEntry>> {
+    let now = Utc::now();
+    if config.tag_rules.is_empty() {
+        let cutoff = retention_cutoff(now, config.retention_days);
+        state.indexer.find_cleanup_entries(None, Some(cutoff)).await
+    } else {
+        state
+            .indexer
+            .find_entries_where(newest_expired(config, now), |tags, created_at| {
+                config.retention_for(tags).is_expired(created_at, now)
+            })
+            .await
+    }
+}
+
+/// The creation time of the newest clip that can have expired: nothing
+/// younger than the shortest retention has
+fn newest_expired(config: &CleanupConfig, now: DateTime<Utc>) -> DateTime<Utc> {
+    let shortest_days = config
+        .tag_rules
+        .iter()
+        .filter_map(|rule| match rule.retention {
+            Retention::Days(days) => Some(days),
+            Retention::Never => None,
+        })
+        .fold(config.retention_days, u32::min);
+    retention_cutoff(now, shortest_days)
+}
+
+/// The creation time of the newest clip `days` of retention have expired,
+/// or the earliest date if `days` reach back further
+fn retention_cutoff(now: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    now.checked_sub_signed(Duration::days(days as i64))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// Run the sensitive clip cleanup task: every minute, delete the clips
+/// tagged `$sensitive` that are older than `sensitive.retention_minutes`.
+/// Unlike clip cleanup this doesn't depend on `[cleanup]`, and the setting
+/// is read on every run, so reloading the configuration applies to it.
+pub async fn run_sensitive_cleanup_task(state: AppState) {
+    let interval = std::time::Duration::from_secs(SENSITIVE_CLEANUP_INTERVAL_SECS);
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let config = state.config.get().sensitive.clone();
+        if let Err(e) = cleanup_sensitive_clips(&state, &config).await {
+            tracing::error!("Sensitive clip cleanup failed: {}", e);
         }
     }
 }
 
+/// Delete the sensitive clips that have outlived `retention_minutes` and
+/// notify connected clients
+///
+/// # Returns
+/// The IDs of the deleted clips
+pub async fn cleanup_sensitive_clips(
+    state: &AppState,
+    config: &SensitiveConfig,
+) -> clipper_indexer::Result<Vec<String>> {
+    let Some(retention) = config.retention() else {
+        return Ok(Vec::new());
+    };
+
+    let entries = state
+        .indexer
+        .find_tagged_entries(SENSITIVE_TAG, Utc::now() - retention)
+        .await?;
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let deleted_ids = state.indexer.delete_entries(&entries).await?;
+    tracing::info!(
+        "Sensitive clip cleanup completed: deleted {} clips",
+        deleted_ids.len()
+    );
+    state.notify_clips_cleaned_up(&entries);
+    Ok(deleted_ids)
+}
+
 /// Run the short URL cleanup task periodically.
 /// This task deletes expired short URLs regardless of the clip cleanup configuration.
 /// Runs every hour by default.
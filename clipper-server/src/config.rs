@@ -1,16 +1,42 @@
-use clap::Parser;
+use crate::service::{InitArgs, InstallArgs, UninstallArgs};
+use clap::{Parser, Subcommand, ValueEnum};
+use clipper_security::SecretString;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::path::PathBuf;
-
-#[derive(Debug, Parser)]
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Sections of the configuration that reloading applies to the running
+/// server. The others only take effect on restart.
+pub const RELOADABLE_SECTIONS: &[&str] = &[
+    "cleanup",
+    "upload",
+    "scan",
+    "short_url",
+    "sensitive",
+    "enrichment",
+    "translation",
+    "canonical_url",
+    "admin",
+];
+
+#[derive(Debug, Clone, Parser)]
 #[command(name = "clipper-server")]
 #[command(about = "Clipper server with REST API and WebSocket support", long_about = None)]
 pub struct Cli {
-    /// Path to configuration file
-    #[arg(short, long, env = "CLIPPER_CONFIG")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to configuration file (TOML, YAML or JSON, by extension)
+    #[arg(short, long, env = "CLIPPER_CONFIG", global = true)]
     pub config: Option<PathBuf>,
 
+    /// Directory for the database, attachments, backups and certificates
+    /// (default: ./data). --db-path and the like still take precedence.
+    #[arg(long, env = "CLIPPER_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+
     /// Database path
     #[arg(long, env = "CLIPPER_DB_PATH")]
     pub db_path: Option<String>,
@@ -27,6 +53,24 @@ pub struct Cli {
     #[arg(short, long, env = "PORT")]
     pub port: Option<u16>,
 
+    /// Path prefix to serve everything under, e.g. "/clipper" behind a reverse proxy
+    #[arg(long, env = "CLIPPER_BASE_PATH")]
+    pub base_path: Option<String>,
+
+    /// Serve HTTP on this Windows named pipe (e.g. \\.\pipe\clipper) instead of TCP
+    #[arg(long, env = "CLIPPER_NAMED_PIPE")]
+    pub named_pipe: Option<String>,
+
+    /// Seconds to wait for open connections to close on shutdown (default: 5)
+    #[arg(long, env = "CLIPPER_SHUTDOWN_TIMEOUT")]
+    pub shutdown_timeout: Option<u64>,
+
+    /// Ask the server running with this configuration whether it's ready,
+    /// print the answer and exit, with status 0 if it is. For container
+    /// health checks, where there may be no curl.
+    #[arg(long)]
+    pub print_healthcheck: bool,
+
     // TLS options
     /// Enable HTTPS/TLS
     #[arg(long, env = "CLIPPER_TLS_ENABLED")]
@@ -62,6 +106,14 @@ pub struct Cli {
     #[arg(long, env = "CLIPPER_ACME_DOMAIN")]
     pub acme_domain: Option<String>,
 
+    /// More domain names for the ACME certificate, comma separated
+    #[arg(long, env = "CLIPPER_ACME_ADDITIONAL_DOMAINS", value_delimiter = ',')]
+    pub acme_additional_domains: Option<Vec<String>>,
+
+    /// Command that publishes ACME DNS-01 challenge records
+    #[arg(long, env = "CLIPPER_ACME_DNS_HOOK")]
+    pub acme_dns_hook: Option<PathBuf>,
+
     /// Contact email for ACME (Let's Encrypt notifications)
     #[arg(long, env = "CLIPPER_ACME_EMAIL")]
     pub acme_email: Option<String>,
@@ -79,6 +131,22 @@ pub struct Cli {
     #[arg(long, env = "CLIPPER_BEARER_TOKEN")]
     pub bearer_token: Option<String>,
 
+    /// Token for the /admin endpoints; must differ from the bearer token
+    #[arg(long, env = "CLIPPER_ADMIN_TOKEN")]
+    pub admin_token: Option<String>,
+
+    /// Read-only token: lists, searches and downloads clips but can't change them
+    #[arg(long, env = "CLIPPER_GUEST_TOKEN")]
+    pub guest_token: Option<String>,
+
+    /// Directory POST /admin/backup writes backups to
+    #[arg(long, env = "CLIPPER_BACKUP_DIR")]
+    pub backup_dir: Option<String>,
+
+    /// Number of backups to keep (default: 7, 0 = keep all)
+    #[arg(long, env = "CLIPPER_MAX_BACKUPS")]
+    pub max_backups: Option<usize>,
+
     // Cleanup options
     /// Enable automatic cleanup of old clips
     #[arg(long, env = "CLIPPER_CLEANUP_ENABLED")]
@@ -92,11 +160,54 @@ pub struct Cli {
     #[arg(long, env = "CLIPPER_CLEANUP_INTERVAL_HOURS")]
     pub cleanup_interval_hours: Option<u32>,
 
+    /// What to do with expired clips (default: delete)
+    #[arg(long, env = "CLIPPER_CLEANUP_MODE", value_enum)]
+    pub cleanup_mode: Option<CleanupMode>,
+
+    /// Directory for the archives of expired clips in archive mode
+    #[arg(long, env = "CLIPPER_CLEANUP_ARCHIVE_DIR")]
+    pub cleanup_archive_dir: Option<String>,
+
     // Upload options
     /// Maximum upload size in megabytes (default: 10)
     #[arg(long, env = "CLIPPER_MAX_UPLOAD_SIZE_MB")]
     pub max_upload_size_mb: Option<u64>,
 
+    /// Maximum body size for JSON requests in kilobytes (default: 2048)
+    #[arg(long, env = "CLIPPER_MAX_JSON_SIZE_KB")]
+    pub max_json_size_kb: Option<u64>,
+
+    /// Text kept in a text clip in kilobytes; longer text is truncated and
+    /// stored in full as an attachment (default: 256, 0 = no limit)
+    #[arg(long, env = "CLIPPER_MAX_TEXT_SIZE_KB")]
+    pub max_text_size_kb: Option<u64>,
+
+    /// Maximum import archive size in megabytes (default: 1024)
+    #[arg(long, env = "CLIPPER_MAX_IMPORT_SIZE_MB")]
+    pub max_import_size_mb: Option<u64>,
+
+    // Upload scanning options
+    /// ClamAV daemon to scan uploads with: the path of its Unix socket, or
+    /// host:port
+    #[arg(long, env = "CLIPPER_SCAN_CLAMD")]
+    pub scan_clamd: Option<String>,
+
+    /// Command to scan uploads with, run with the path of the file
+    #[arg(long, env = "CLIPPER_SCAN_COMMAND")]
+    pub scan_command: Option<PathBuf>,
+
+    /// What to do with uploads the scan flags (default: reject)
+    #[arg(long, env = "CLIPPER_SCAN_ACTION", value_enum)]
+    pub scan_action: Option<ScanAction>,
+
+    /// Directory for quarantined uploads
+    #[arg(long, env = "CLIPPER_SCAN_QUARANTINE_DIR")]
+    pub scan_quarantine_dir: Option<String>,
+
+    /// Accept uploads when the scanner fails instead of rejecting them
+    #[arg(long, env = "CLIPPER_SCAN_FAIL_OPEN")]
+    pub scan_fail_open: Option<bool>,
+
     // Short URL options
     /// Base URL for short URLs (e.g., "https://clip.example.com/s/")
     /// If not set, short URL functionality is disabled
@@ -107,12 +218,155 @@ pub struct Cli {
     #[arg(long, env = "CLIPPER_SHORT_URL_EXPIRATION_HOURS")]
     pub short_url_expiration_hours: Option<u32>,
 
+    // Sensitive clip options
+    /// Tag new text clips that look like they hold a secret as sensitive
+    /// (default: true)
+    #[arg(long, env = "CLIPPER_SENSITIVE_DETECT_SECRETS")]
+    pub sensitive_detect_secrets: Option<bool>,
+
+    /// Minutes to keep sensitive clips (default: 60, 0 = keep them like
+    /// other clips)
+    #[arg(long, env = "CLIPPER_SENSITIVE_RETENTION_MINUTES")]
+    pub sensitive_retention_minutes: Option<u32>,
+
+    // Enrichment options
+    /// Suggest tags and a summary for new text clips with an
+    /// OpenAI-compatible API (default: false)
+    #[arg(long, env = "CLIPPER_ENRICHMENT_ENABLED")]
+    pub enrichment_enabled: Option<bool>,
+
+    /// Base URL of the API (default: "http://localhost:11434/v1", Ollama)
+    #[arg(long, env = "CLIPPER_ENRICHMENT_ENDPOINT")]
+    pub enrichment_endpoint: Option<String>,
+
+    /// API key sent as a bearer token; Ollama doesn't need one
+    #[arg(long, env = "CLIPPER_ENRICHMENT_API_KEY")]
+    pub enrichment_api_key: Option<String>,
+
+    /// Model to ask (default: "llama3.2")
+    #[arg(long, env = "CLIPPER_ENRICHMENT_MODEL")]
+    pub enrichment_model: Option<String>,
+
+    // Translation options
+    /// Translation API for `POST /clips/{id}/translate` (default: none)
+    #[arg(long, env = "CLIPPER_TRANSLATION_PROVIDER")]
+    pub translation_provider: Option<TranslationProvider>,
+
+    /// Base URL of the translation API (default: the provider's)
+    #[arg(long, env = "CLIPPER_TRANSLATION_ENDPOINT")]
+    pub translation_endpoint: Option<String>,
+
+    /// API key for the translation API; DeepL requires one
+    #[arg(long, env = "CLIPPER_TRANSLATION_API_KEY")]
+    pub translation_api_key: Option<String>,
+
+    // Canonical URL options
+    /// Store a canonical URL for new URL clips, without tracking parameters
+    /// (default: false)
+    #[arg(long, env = "CLIPPER_CANONICAL_URL_ENABLED")]
+    pub canonical_url_enabled: Option<bool>,
+
+    /// Follow redirects, e.g. of short links, to the final URL (default: true)
+    #[arg(long, env = "CLIPPER_CANONICAL_URL_RESOLVE_REDIRECTS")]
+    pub canonical_url_resolve_redirects: Option<bool>,
+
+    // Plugin options
+    /// Directory of Rhai plugin scripts (*.rhai) to run on clip events
+    #[arg(long, env = "CLIPPER_PLUGINS_DIR")]
+    pub plugins_dir: Option<PathBuf>,
+
+    // Discovery options
+    /// Advertise the server on the local network over mDNS (default: true)
+    #[arg(long, env = "CLIPPER_MDNS_ENABLED")]
+    pub mdns_enabled: Option<bool>,
+
+    /// Service name shown to clients browsing the network (default: "Clipper on <hostname>")
+    #[arg(long, env = "CLIPPER_MDNS_NAME")]
+    pub mdns_name: Option<String>,
+
+    // Web Push options
+    /// VAPID private key for Web Push notifications, base64url encoded
+    /// If not set, Web Push is disabled
+    #[arg(long, env = "CLIPPER_WEB_PUSH_VAPID_KEY")]
+    pub web_push_vapid_key: Option<String>,
+
+    /// Contact for push services, e.g. "mailto:admin@example.com"
+    #[arg(long, env = "CLIPPER_WEB_PUSH_SUBJECT")]
+    pub web_push_subject: Option<String>,
+
+    // Logging options
+    /// Also write logs to this file, rotating it as configured
+    #[arg(long, env = "CLIPPER_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// When to start a new log file (default: daily)
+    #[arg(long, env = "CLIPPER_LOG_ROTATION", value_enum)]
+    pub log_rotation: Option<LogRotation>,
+
+    /// Start a new log file once it reaches this size in megabytes (default: 0 = no limit)
+    #[arg(long, env = "CLIPPER_LOG_MAX_SIZE_MB")]
+    pub log_max_size_mb: Option<u64>,
+
+    /// Number of rotated log files to keep (default: 7, 0 = keep all)
+    #[arg(long, env = "CLIPPER_LOG_MAX_FILES")]
+    pub log_max_files: Option<usize>,
+
+    /// Gzip rotated log files
+    #[arg(long, env = "CLIPPER_LOG_COMPRESS")]
+    pub log_compress: Option<bool>,
+
+    /// Format of log lines on stdout and in the log file (default: text)
+    #[arg(long, env = "CLIPPER_LOG_FORMAT", value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Log every request in Apache combined log format
+    #[arg(long, env = "CLIPPER_ACCESS_LOG", num_args = 0..=1, default_missing_value = "true")]
+    pub access_log: Option<bool>,
+
+    /// Export traces to this OTLP/HTTP collector, e.g. "http://localhost:4318"
+    /// (requires the `otel` feature)
+    #[arg(long, env = "CLIPPER_OTLP_ENDPOINT")]
+    pub otlp_endpoint: Option<String>,
+
     // Hidden option for parent process monitoring (used by bundled server in Tauri app)
     /// Pipe handle from parent process for lifecycle monitoring (internal use only)
     #[arg(long, hide = true)]
     pub parent_pipe_handle: Option<u64>,
 }
 
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Create the data directory and a config file for running as a service
+    Init(InitArgs),
+    /// Run `init`, then install and start the server as a systemd unit,
+    /// launchd job or Windows service
+    InstallService(InstallArgs),
+    /// Stop and remove the service, keeping its data and config
+    UninstallService(UninstallArgs),
+    /// Started by the Windows service control manager
+    #[cfg(windows)]
+    #[command(hide = true)]
+    RunService,
+    /// Print the configuration the server would run with, after merging the
+    /// config file, environment variables and command line options
+    PrintConfig {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ConfigFormat::Toml)]
+        format: ConfigFormat,
+
+        /// Show tokens and keys instead of "[REDACTED]"
+        #[arg(long)]
+        show_secrets: bool,
+    },
+}
+
+/// Output format of `print-config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub database: DatabaseConfig,
@@ -129,29 +383,91 @@ pub struct ServerConfig {
     #[serde(default)]
     pub upload: UploadConfig,
     #[serde(default)]
+    pub scan: ScanConfig,
+    #[serde(default)]
     pub short_url: ShortUrlConfig,
+    #[serde(default)]
+    pub sensitive: SensitiveConfig,
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    #[serde(default)]
+    pub canonical_url: CanonicalUrlConfig,
+    #[serde(default)]
+    pub plugins: PluginConfig,
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+    #[serde(default)]
+    pub web_push: WebPushConfig,
+    #[serde(default)]
+    pub push_relay: PushRelayConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
 }
 
 /// Authentication configuration
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// Bearer token for authentication (if set, all requests must include this token)
-    pub bearer_token: Option<String>,
+    pub bearer_token: Option<SecretString>,
+    /// Token for the `/admin` endpoints. If not set they take the bearer
+    /// token instead (or nothing if that isn't set either).
+    #[serde(default)]
+    pub admin_token: Option<SecretString>,
+    /// Read-only token for guests, e.g. to browse clips in the web UI without
+    /// being able to change or delete them. Needs `bearer_token` to be set.
+    #[serde(default)]
+    pub guest_token: Option<SecretString>,
 }
 
 impl AuthConfig {
     /// Check if authentication is required
     pub fn is_enabled(&self) -> bool {
-        !self.bearer_token.as_deref().unwrap_or("").is_empty()
+        self.bearer_token
+            .as_ref()
+            .is_some_and(|token| !token.is_empty())
     }
 
     /// Validate a token against the configured bearer token
     pub fn validate_token(&self, token: &str) -> bool {
         match &self.bearer_token {
-            Some(expected) if !expected.is_empty() => expected == token,
+            Some(expected) if !expected.is_empty() => *expected == *token,
             _ => true, // No auth required
         }
     }
+
+    /// Whether the `/admin` endpoints require their own token
+    pub fn admin_token_enabled(&self) -> bool {
+        self.admin_token
+            .as_ref()
+            .is_some_and(|token| !token.is_empty())
+    }
+
+    /// Check a token against the admin token
+    pub fn validate_admin_token(&self, token: &str) -> bool {
+        match &self.admin_token {
+            Some(expected) if !expected.is_empty() => *expected == *token,
+            _ => false,
+        }
+    }
+
+    /// Whether a read-only guest token is configured
+    pub fn guest_token_enabled(&self) -> bool {
+        self.guest_token
+            .as_ref()
+            .is_some_and(|token| !token.is_empty())
+    }
+
+    /// Check a token against the guest token
+    pub fn validate_guest_token(&self, token: &str) -> bool {
+        match &self.guest_token {
+            Some(expected) if !expected.is_empty() => *expected == *token,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,6 +484,30 @@ pub struct StorageConfig {
 pub struct NetworkConfig {
     pub listen_addr: String,
     pub port: u16,
+    /// Path prefix to serve the API and web UI under (e.g. "/clipper"), for
+    /// reverse proxies that forward a subpath. Empty serves at the root.
+    #[serde(default)]
+    pub base_path: String,
+    /// Windows named pipe to serve HTTP on instead of TCP. The pipe only
+    /// accepts local connections from the current user.
+    #[serde(default)]
+    pub named_pipe: Option<String>,
+    /// Seconds to wait for open connections, such as WebSockets, to close on
+    /// shutdown before closing them. Container runtimes kill the server 10
+    /// seconds after asking it to stop.
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    5
+}
+
+impl NetworkConfig {
+    /// How long to wait for connections to close on shutdown
+    pub fn shutdown_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.shutdown_timeout_secs)
+    }
 }
 
 /// TLS/HTTPS configuration
@@ -225,12 +565,22 @@ pub struct AcmeConfig {
     pub enabled: bool,
     /// Domain name for the certificate
     pub domain: Option<String>,
+    /// More names the certificate should cover, e.g. "clip.example.com".
+    /// Wildcards like "*.example.com" need `dns_hook`.
+    #[serde(default)]
+    pub additional_domains: Vec<String>,
     /// Contact email for Let's Encrypt notifications
     pub contact_email: Option<String>,
     /// Use staging environment (for testing, avoids rate limits)
     pub staging: bool,
     /// Directory for certificate cache
     pub certs_dir: Option<PathBuf>,
+    /// Command that publishes DNS-01 challenge records. It is run as
+    /// `<hook> set <name> <value>` and should return once the TXT record is
+    /// visible, then as `<hook> clear <name> <value>` after validation. When
+    /// set, every domain is validated over DNS instead of HTTP.
+    #[serde(default)]
+    pub dns_hook: Option<PathBuf>,
 }
 
 impl AcmeConfig {
@@ -243,10 +593,23 @@ impl AcmeConfig {
                 .join("certs")
         })
     }
+
+    /// All names the certificate should cover, starting with `domain`, without
+    /// blanks or duplicates
+    pub fn domains(&self) -> Vec<String> {
+        let mut domains: Vec<String> = Vec::new();
+        for domain in self.domain.iter().chain(&self.additional_domains) {
+            let domain = domain.trim().to_ascii_lowercase();
+            if !domain.is_empty() && !domains.contains(&domain) {
+                domains.push(domain);
+            }
+        }
+        domains
+    }
 }
 
 /// Auto-cleanup configuration for old clips
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CleanupConfig {
     /// Enable automatic cleanup of old clips
     pub enabled: bool,
@@ -254,6 +617,105 @@ pub struct CleanupConfig {
     pub retention_days: u32,
     /// Interval in hours between cleanup runs (default: 24)
     pub interval_hours: u32,
+    /// Retention for clips with particular tags, e.g. to expire `$screenshot`
+    /// clips after a week while keeping `work` clips
+    #[serde(default)]
+    pub tag_rules: Vec<TagRetentionRule>,
+    /// What to do with expired clips
+    #[serde(default)]
+    pub mode: CleanupMode,
+    /// Directory for the archives of expired clips in archive mode
+    #[serde(default = "default_archive_dir")]
+    pub archive_dir: String,
+}
+
+fn default_archive_dir() -> String {
+    "./data/archive".to_string()
+}
+
+/// What cleanup does with expired clips
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupMode {
+    /// Delete them
+    #[default]
+    Delete,
+    /// Export them into a dated tar.gz archive in `archive_dir`, then delete
+    /// them
+    Archive,
+}
+
+/// How long clips with `tag` are kept
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagRetentionRule {
+    pub tag: String,
+    pub retention: Retention,
+}
+
+/// Longest retention in days, about a century
+pub const MAX_RETENTION_DAYS: u32 = 36500;
+
+/// How long to keep a clip: a number of days up to [`MAX_RETENTION_DAYS`],
+/// written `"7d"`, or `"never"` to keep it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Retention {
+    Days(u32),
+    /// Never delete the clip
+    Never,
+}
+
+impl Retention {
+    /// Whether a clip created at `created_at` has outlived this retention
+    pub fn is_expired(
+        &self,
+        created_at: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        match self {
+            // Retentions reaching before the earliest date expire nothing
+            Retention::Days(days) => now
+                .checked_sub_signed(chrono::Duration::days(*days as i64))
+                .is_some_and(|cutoff| created_at <= cutoff),
+            Retention::Never => false,
+        }
+    }
+}
+
+impl std::str::FromStr for Retention {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("never") {
+            return Ok(Retention::Never);
+        }
+        let days = s.strip_suffix('d').unwrap_or(s);
+        match days.parse::<u32>() {
+            Ok(days) if (1..=MAX_RETENTION_DAYS).contains(&days) => Ok(Retention::Days(days)),
+            _ => Err(format!(
+                "Invalid retention '{}': expected a number of days up to {} such as \"7d\", or \"never\"",
+                s, MAX_RETENTION_DAYS
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Retention {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Retention> for String {
+    fn from(retention: Retention) -> Self {
+        match retention {
+            Retention::Days(days) => format!("{}d", days),
+            Retention::Never => "never".to_string(),
+        }
+    }
 }
 
 /// Upload configuration
@@ -261,12 +723,47 @@ pub struct CleanupConfig {
 pub struct UploadConfig {
     /// Maximum upload size in bytes (default: 10MB)
     pub max_size_bytes: u64,
+    /// Maximum body size in bytes for every other request, which are small
+    /// JSON documents (default: 2MB)
+    pub max_json_size_bytes: u64,
+    /// Maximum import archive size in bytes (default: 1GB)
+    pub max_import_size_bytes: u64,
+    /// Most text kept in a text clip, in bytes (default: 256KB, 0 = no
+    /// limit). Longer text is cut to this size, and the whole text is stored
+    /// as an attachment of the clip.
+    #[serde(default = "default_max_text_size_bytes")]
+    pub max_text_size_bytes: u64,
+}
+
+fn default_max_text_size_bytes() -> u64 {
+    256 * 1024
+}
+
+/// Largest size limit in bytes; no request body or file could be bigger
+const MAX_SIZE_BYTES: u64 = isize::MAX as u64;
+
+/// `value` `unit`s in bytes, or None if that's more than `MAX_SIZE_BYTES`
+fn size_in_bytes(value: u64, unit: u64) -> Option<u64> {
+    value
+        .checked_mul(unit)
+        .filter(|bytes| *bytes <= MAX_SIZE_BYTES)
+}
+
+/// Error for a size option whose value in bytes is out of range
+fn size_too_large(option: &str, value: u64) -> config::ConfigError {
+    config::ConfigError::Message(format!(
+        "{} {} is too large; the limit is {} bytes",
+        option, value, MAX_SIZE_BYTES
+    ))
 }
 
 impl Default for UploadConfig {
     fn default() -> Self {
         Self {
-            max_size_bytes: 10 * 1024 * 1024, // 10MB
+            max_size_bytes: 10 * 1024 * 1024,          // 10MB
+            max_json_size_bytes: 2 * 1024 * 1024,      // 2MB
+            max_import_size_bytes: 1024 * 1024 * 1024, // 1GB
+            max_text_size_bytes: default_max_text_size_bytes(),
         }
     }
 }
@@ -276,6 +773,92 @@ impl UploadConfig {
     pub fn max_size_mb(&self) -> f64 {
         self.max_size_bytes as f64 / (1024.0 * 1024.0)
     }
+
+    /// Largest body for creating a text clip. Text over `max_text_size_bytes`
+    /// ends up as an attachment, so it may be as large as a file upload.
+    pub fn max_text_clip_body_bytes(&self) -> u64 {
+        if self.max_text_size_bytes > 0 {
+            self.max_json_size_bytes.max(self.max_size_bytes)
+        } else {
+            self.max_json_size_bytes
+        }
+    }
+}
+
+/// Malware scanning of uploaded files, for servers shared by people who
+/// don't trust each other's files. Scanning is on when `clamd` or `command`
+/// is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanConfig {
+    /// ClamAV daemon to send uploads to: the path of its Unix socket, or
+    /// `host:port` for its TCP socket
+    #[serde(default)]
+    pub clamd: Option<String>,
+    /// Command run with the path of the uploaded file. Exit status 0 means
+    /// clean and 1 means flagged, as with `clamscan`; anything else is a
+    /// failed scan.
+    #[serde(default)]
+    pub command: Option<PathBuf>,
+    /// What to do with flagged uploads
+    #[serde(default)]
+    pub action: ScanAction,
+    /// Directory for quarantined uploads
+    #[serde(default = "default_quarantine_dir")]
+    pub quarantine_dir: String,
+    /// Accept uploads when the scanner can't be reached or fails, instead
+    /// of rejecting them
+    #[serde(default)]
+    pub fail_open: bool,
+    /// Seconds a scan may take before it counts as failed (default: 60)
+    #[serde(default = "default_scan_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_quarantine_dir() -> String {
+    "./data/quarantine".to_string()
+}
+
+fn default_scan_timeout_secs() -> u64 {
+    60
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            clamd: None,
+            command: None,
+            action: ScanAction::Reject,
+            quarantine_dir: default_quarantine_dir(),
+            fail_open: false,
+            timeout_secs: default_scan_timeout_secs(),
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Check if uploads are scanned
+    pub fn is_enabled(&self) -> bool {
+        self.clamd
+            .as_ref()
+            .is_some_and(|clamd| !clamd.trim().is_empty())
+            || self.command.is_some()
+    }
+
+    /// Get the scan timeout as Duration
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// What happens to uploads the scan flags
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanAction {
+    /// Reject them
+    #[default]
+    Reject,
+    /// Reject them, keeping a copy in `quarantine_dir` for an administrator
+    Quarantine,
 }
 
 /// Short URL configuration
@@ -297,6 +880,296 @@ impl Default for ShortUrlConfig {
     }
 }
 
+/// Sensitive clips: clips tagged `$sensitive`, by hand or because they look
+/// like they hold a password, an API key or another secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensitiveConfig {
+    /// Tag new text clips that look like they hold a secret as sensitive
+    #[serde(default = "default_detect_secrets")]
+    pub detect_secrets: bool,
+    /// Delete sensitive clips this many minutes after they were created
+    /// (0 = keep them like other clips)
+    #[serde(default = "default_sensitive_retention_minutes")]
+    pub retention_minutes: u32,
+}
+
+fn default_detect_secrets() -> bool {
+    true
+}
+
+fn default_sensitive_retention_minutes() -> u32 {
+    60
+}
+
+impl Default for SensitiveConfig {
+    fn default() -> Self {
+        Self {
+            detect_secrets: default_detect_secrets(),
+            retention_minutes: default_sensitive_retention_minutes(),
+        }
+    }
+}
+
+impl SensitiveConfig {
+    /// How long to keep sensitive clips, if they expire early
+    pub fn retention(&self) -> Option<chrono::Duration> {
+        (self.retention_minutes > 0)
+            .then(|| chrono::Duration::minutes(self.retention_minutes as i64))
+    }
+}
+
+/// Enrichment: an OpenAI-compatible chat API, such as OpenAI or a local
+/// Ollama, is asked for suggested tags and a one-line summary of each new
+/// text clip. Sensitive clips are never sent, and lines that look like
+/// secrets are redacted from the others.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnrichmentConfig {
+    /// Enrich new text clips
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the API; `/chat/completions` is appended
+    #[serde(default = "default_enrichment_endpoint")]
+    pub endpoint: String,
+    /// API key sent as a bearer token; Ollama doesn't need one
+    #[serde(default)]
+    pub api_key: Option<SecretString>,
+    /// Model to ask
+    #[serde(default = "default_enrichment_model")]
+    pub model: String,
+    /// Most requests per minute; clips beyond it aren't enriched
+    /// (0 = no limit)
+    #[serde(default = "default_enrichment_max_requests_per_minute")]
+    pub max_requests_per_minute: u32,
+    /// Longest text sent, in characters; longer clips are cut
+    #[serde(default = "default_enrichment_max_input_chars")]
+    pub max_input_chars: usize,
+    /// Seconds a request may take
+    #[serde(default = "default_enrichment_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_enrichment_endpoint() -> String {
+    "http://localhost:11434/v1".to_string()
+}
+
+fn default_enrichment_model() -> String {
+    "llama3.2".to_string()
+}
+
+fn default_enrichment_max_requests_per_minute() -> u32 {
+    10
+}
+
+fn default_enrichment_max_input_chars() -> usize {
+    4000
+}
+
+fn default_enrichment_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_enrichment_endpoint(),
+            api_key: None,
+            model: default_enrichment_model(),
+            max_requests_per_minute: default_enrichment_max_requests_per_minute(),
+            max_input_chars: default_enrichment_max_input_chars(),
+            timeout_secs: default_enrichment_timeout_secs(),
+        }
+    }
+}
+
+impl EnrichmentConfig {
+    /// Get the request timeout as Duration
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// Translation configuration
+///
+/// `POST /clips/{id}/translate` sends a clip to the provider and stores the
+/// translation as a new clip. Translation is off until a provider is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranslationConfig {
+    /// Translation API to use
+    #[serde(default)]
+    pub provider: TranslationProvider,
+    /// Base URL of the API; see [`TranslationConfig::endpoint`] for the
+    /// defaults
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// API key; DeepL requires one, LibreTranslate only on instances that
+    /// ask for it
+    #[serde(default)]
+    pub api_key: Option<SecretString>,
+    /// Longest clip that can be translated, in characters
+    #[serde(default = "default_translation_max_input_chars")]
+    pub max_input_chars: usize,
+    /// Seconds a request may take
+    #[serde(default = "default_translation_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// Translation API used by `POST /clips/{id}/translate`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationProvider {
+    /// Translation is off
+    #[default]
+    None,
+    /// A LibreTranslate instance, usually self-hosted
+    #[value(name = "libretranslate")]
+    LibreTranslate,
+    /// The DeepL API
+    #[value(name = "deepl")]
+    DeepL,
+}
+
+fn default_translation_max_input_chars() -> usize {
+    10000
+}
+
+fn default_translation_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            provider: TranslationProvider::None,
+            endpoint: None,
+            api_key: None,
+            max_input_chars: default_translation_max_input_chars(),
+            timeout_secs: default_translation_timeout_secs(),
+        }
+    }
+}
+
+impl TranslationConfig {
+    /// Check if clips can be translated
+    pub fn is_enabled(&self) -> bool {
+        self.provider != TranslationProvider::None
+    }
+
+    /// Base URL of the API, without a trailing slash. Defaults to a local
+    /// LibreTranslate, or for DeepL to its free API when the key ends in
+    /// `:fx`, as free keys do, and its paid API otherwise.
+    pub fn endpoint(&self) -> String {
+        let endpoint = match self.endpoint.as_deref().map(str::trim) {
+            Some(endpoint) if !endpoint.is_empty() => endpoint,
+            _ => match self.provider {
+                TranslationProvider::DeepL
+                    if self
+                        .api_key
+                        .as_ref()
+                        .is_some_and(|key| key.expose().ends_with(":fx")) =>
+                {
+                    "https://api-free.deepl.com"
+                }
+                TranslationProvider::DeepL => "https://api.deepl.com",
+                _ => "http://localhost:5000",
+            },
+        };
+        endpoint.trim_end_matches('/').to_string()
+    }
+
+    /// Get the request timeout as Duration
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// Canonical URLs: for new clips holding a single URL, the server follows
+/// redirects to the final URL and drops tracking parameters, and stores the
+/// result alongside the original
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanonicalUrlConfig {
+    /// Store canonical URLs of new URL clips
+    #[serde(default)]
+    pub enabled: bool,
+    /// Follow redirects, e.g. of short links. Only public addresses are
+    /// visited, with `HEAD` requests.
+    #[serde(default = "default_resolve_redirects")]
+    pub resolve_redirects: bool,
+    /// Most redirects followed; a longer chain keeps the original URL
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: usize,
+    /// Query parameters to remove: names, or prefixes ending in `*`, matched
+    /// ignoring case
+    #[serde(default = "default_strip_params")]
+    pub strip_params: Vec<String>,
+    /// Also follow redirects to loopback, private and link-local addresses.
+    /// Leave off unless the server only sees trusted clips: otherwise a
+    /// client can make it probe the local network.
+    #[serde(default)]
+    pub allow_private_addresses: bool,
+    /// Seconds each request may take
+    #[serde(default = "default_canonical_url_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_resolve_redirects() -> bool {
+    true
+}
+
+fn default_max_redirects() -> usize {
+    10
+}
+
+fn default_strip_params() -> Vec<String> {
+    [
+        "utm_*",
+        "fbclid",
+        "gclid",
+        "dclid",
+        "gbraid",
+        "wbraid",
+        "msclkid",
+        "yclid",
+        "twclid",
+        "igshid",
+        "mc_cid",
+        "mc_eid",
+        "_hsenc",
+        "_hsmi",
+        "mkt_tok",
+        "oly_anon_id",
+        "oly_enc_id",
+        "vero_id",
+    ]
+    .iter()
+    .map(|param| param.to_string())
+    .collect()
+}
+
+fn default_canonical_url_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for CanonicalUrlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            resolve_redirects: default_resolve_redirects(),
+            max_redirects: default_max_redirects(),
+            strip_params: default_strip_params(),
+            allow_private_addresses: false,
+            timeout_secs: default_canonical_url_timeout_secs(),
+        }
+    }
+}
+
+impl CanonicalUrlConfig {
+    /// Get the request timeout as Duration
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs)
+    }
+}
+
 impl ShortUrlConfig {
     /// Check if short URL functionality is enabled
     pub fn is_enabled(&self) -> bool {
@@ -325,33 +1198,241 @@ impl ShortUrlConfig {
     }
 }
 
-impl Default for CleanupConfig {
+/// Plugin configuration
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    /// Directory of Rhai plugin scripts; plugins are disabled if not set
+    pub dir: Option<PathBuf>,
+}
+
+/// LAN discovery (mDNS/DNS-SD) configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    /// Advertise the server as `_clipper._tcp` when listening on a non-loopback address
+    pub enabled: bool,
+    /// Service instance name; defaults to "Clipper on <hostname>"
+    pub instance_name: Option<String>,
+}
+
+impl Default for DiscoveryConfig {
     fn default() -> Self {
         Self {
-            enabled: false,
-            retention_days: 30,
-            interval_hours: 24,
+            enabled: true,
+            instance_name: None,
         }
     }
 }
 
-impl CleanupConfig {
-    /// Check if cleanup is enabled and properly configured
-    pub fn is_active(&self) -> bool {
-        self.enabled && self.retention_days > 0 && self.interval_hours > 0
+/// Web Push notification configuration
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WebPushConfig {
+    /// VAPID private key: a raw P-256 private key, base64url encoded.
+    /// Web Push is disabled if not set (None or empty).
+    pub vapid_private_key: Option<String>,
+    /// Contact for push service operators, a "mailto:" or "https:" URL.
+    /// Some push services (e.g. Apple's) reject notifications without it.
+    pub subject: Option<String>,
+}
+
+impl WebPushConfig {
+    /// Check if Web Push is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.vapid_private_key
+            .as_ref()
+            .is_some_and(|key| !key.trim().is_empty())
     }
+}
 
-    /// Get the cleanup interval as Duration
-    pub fn interval(&self) -> std::time::Duration {
-        std::time::Duration::from_secs(self.interval_hours as u64 * 3600)
+/// Mobile push notification relay configuration (used with the
+/// `push-relay` feature). Each push service is used only if configured.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PushRelayConfig {
+    /// Apple Push Notification service, for iOS devices
+    pub apns: Option<ApnsConfig>,
+    /// Firebase Cloud Messaging, for Android devices
+    pub fcm: Option<FcmConfig>,
+}
+
+impl PushRelayConfig {
+    /// Check if any push service is configured
+    pub fn is_configured(&self) -> bool {
+        self.apns.is_some() || self.fcm.is_some()
     }
 }
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
-            database: DatabaseConfig {
-                path: "./data/db".to_string(),
+/// APNs token-based authentication settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApnsConfig {
+    /// Path to the `.p8` signing key downloaded from the Apple Developer portal
+    pub key_path: PathBuf,
+    /// ID of the signing key
+    pub key_id: String,
+    /// Apple Developer team ID
+    pub team_id: String,
+    /// Bundle ID of the iOS app
+    pub topic: String,
+    /// Send to the APNs development environment instead of production
+    #[serde(default)]
+    pub sandbox: bool,
+}
+
+/// FCM HTTP v1 API settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FcmConfig {
+    /// Path to the Firebase project's service account JSON key file
+    pub service_account_path: PathBuf,
+}
+
+/// Logging configuration. Logs always go to stdout; a log file is for
+/// deployments where nothing collects stdout, e.g. systemd without journald
+/// retention.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Also write logs to this file; stdout only if not set
+    pub file: Option<PathBuf>,
+    /// When to start a new file, besides on reaching `max_size_mb`
+    pub rotation: LogRotation,
+    /// Start a new file once the current one reaches this size in megabytes
+    /// (0 = no limit)
+    pub max_size_mb: u64,
+    /// Number of rotated files to keep; older ones are deleted (0 = keep all)
+    pub max_files: usize,
+    /// Gzip rotated files
+    pub compress: bool,
+    /// Format of log lines, on stdout and in the file
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Log every request in Apache combined log format, under the
+    /// `clipper_server::access` target
+    pub access_log: bool,
+    /// OTLP/HTTP collector to export traces to, e.g. "http://localhost:4318"
+    /// (used with the `otel` feature). Traces aren't exported if not set.
+    pub otlp_endpoint: Option<String>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            rotation: LogRotation::Daily,
+            max_size_mb: 0,
+            max_files: 7,
+            compress: false,
+            format: LogFormat::Text,
+            access_log: false,
+            otlp_endpoint: None,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Get the size limit in bytes, if any
+    pub fn max_size_bytes(&self) -> Option<u64> {
+        (self.max_size_mb > 0).then(|| self.max_size_mb.saturating_mul(1024 * 1024))
+    }
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with span fields such as `request_id`, for
+    /// log shippers like Loki or Elasticsearch
+    Json,
+}
+
+/// Server administration through the `/admin` endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// Directory `POST /admin/backup` writes backup archives to
+    pub backup_dir: String,
+    /// Number of backups to keep; older ones are deleted (0 = keep all)
+    pub max_backups: usize,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            backup_dir: "./data/backups".to_string(),
+            max_backups: 7,
+        }
+    }
+}
+
+/// When to start a new log file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Daily,
+    Hourly,
+    /// Only on reaching the size limit
+    Never,
+}
+
+/// Normalize a base path to a leading slash and no trailing slash ("" for
+/// the root), so "clipper/" becomes "/clipper"
+pub fn normalize_base_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            interval_hours: 24,
+            tag_rules: Vec::new(),
+            mode: CleanupMode::Delete,
+            archive_dir: default_archive_dir(),
+        }
+    }
+}
+
+impl CleanupConfig {
+    /// Check if cleanup is enabled and properly configured
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.retention_days > 0 && self.interval_hours > 0
+    }
+
+    /// Get the cleanup interval as Duration
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.interval_hours as u64 * 3600)
+    }
+
+    /// The retention rule for `tag`, if there is one
+    pub fn rule_for(&self, tag: &str) -> Option<&TagRetentionRule> {
+        self.tag_rules.iter().find(|rule| rule.tag == tag)
+    }
+
+    /// How long to keep a clip with `tags`. Each tag with a rule asks for
+    /// the rule's retention, and any other tag except `$host:` tags asks to
+    /// keep the clip. The longest retention asked for wins; a clip whose
+    /// tags ask for nothing is kept for `retention_days`.
+    pub fn retention_for(&self, tags: &[String]) -> Retention {
+        tags.iter()
+            .filter_map(|tag| match self.rule_for(tag) {
+                Some(rule) => Some(rule.retention),
+                None if tag.starts_with("$host:") => None,
+                None => Some(Retention::Never),
+            })
+            .max()
+            .unwrap_or(Retention::Days(self.retention_days))
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            database: DatabaseConfig {
+                path: "./data/db".to_string(),
             },
             storage: StorageConfig {
                 path: "./data/storage".to_string(),
@@ -359,30 +1440,78 @@ impl Default for ServerConfig {
             server: NetworkConfig {
                 listen_addr: "0.0.0.0".to_string(),
                 port: 3000,
+                base_path: String::new(),
+                named_pipe: None,
+                shutdown_timeout_secs: default_shutdown_timeout_secs(),
             },
             tls: TlsConfig::default(),
             acme: AcmeConfig::default(),
             cleanup: CleanupConfig::default(),
             auth: AuthConfig::default(),
             upload: UploadConfig::default(),
+            scan: ScanConfig::default(),
             short_url: ShortUrlConfig::default(),
+            sensitive: SensitiveConfig::default(),
+            enrichment: EnrichmentConfig::default(),
+            translation: TranslationConfig::default(),
+            canonical_url: CanonicalUrlConfig::default(),
+            plugins: PluginConfig::default(),
+            discovery: DiscoveryConfig::default(),
+            web_push: WebPushConfig::default(),
+            push_relay: PushRelayConfig::default(),
+            log: LogConfig::default(),
+            admin: AdminConfig::default(),
         }
     }
 }
 
 impl ServerConfig {
-    /// Load configuration from multiple sources with priority:
-    /// 1. Command line arguments (highest priority)
-    /// 2. Environment variables
-    /// 3. Config file
-    /// 4. Defaults (lowest priority)
+    /// Load configuration from multiple sources, each overriding the ones
+    /// before it:
+    /// 1. Defaults, with the data paths under `--data-dir`/`CLIPPER_DATA_DIR`
+    ///    if given
+    /// 2. The config file: `--config`/`CLIPPER_CONFIG`, or else
+    ///    `clipper-server.*` or `config.*` in the working directory. The
+    ///    format follows the extension (`.toml`, `.yaml`/`.yml`, `.json`).
+    /// 3. `CLIPPER__<SECTION>__<KEY>` environment variables, which can set
+    ///    any setting, e.g. `CLIPPER__LOG__MAX_FILES=3` for `log.max_files`
+    /// 4. Command line options, and the environment variables `Cli` reads
+    ///    for them (e.g. `CLIPPER_DB_PATH`); an option beats its variable
+    ///
+    /// `clipper-server print-config` shows the result.
     pub fn load(cli: Cli) -> Result<Self, config::ConfigError> {
-        let mut builder = config::Config::builder()
-            .add_source(config::Config::try_from(&ServerConfig::default())?);
+        Self::load_with_env(cli, None)
+    }
 
-        // Load from config file if specified
+    /// The defaults, with everything the server stores kept under `data_dir`
+    pub fn default_in(data_dir: &std::path::Path) -> Self {
+        let path = |name: &str| data_dir.join(name).to_string_lossy().into_owned();
+        let mut config = Self::default();
+        config.database.path = path("db");
+        config.storage.path = path("storage");
+        config.admin.backup_dir = path("backups");
+        config.cleanup.archive_dir = path("archive");
+        config.scan.quarantine_dir = path("quarantine");
+        config.acme.certs_dir = Some(data_dir.join("certs"));
+        config
+    }
+
+    /// [`ServerConfig::load`] with the `CLIPPER__` variables taken from `env`
+    /// instead of the process environment, if given
+    fn load_with_env(
+        cli: Cli,
+        env: Option<config::Map<String, String>>,
+    ) -> Result<Self, config::ConfigError> {
+        let defaults = match &cli.data_dir {
+            Some(data_dir) => ServerConfig::default_in(data_dir),
+            None => ServerConfig::default(),
+        };
+        let mut builder =
+            config::Config::builder().add_source(config::Config::try_from(&defaults)?);
+
+        // Load from config file if specified. Logging isn't set up until the
+        // config is loaded, so the caller reports which file was used.
         if let Some(config_path) = &cli.config {
-            tracing::info!("Loading config from file: {}", config_path.display());
             builder = builder.add_source(config::File::from(config_path.as_ref()).required(false));
         } else {
             // Try to load from default locations
@@ -391,6 +1520,17 @@ impl ServerConfig {
                 .add_source(config::File::with_name("config").required(false));
         }
 
+        // Any setting from the environment, e.g. CLIPPER__CLEANUP__ENABLED=true.
+        // The double underscores keep these apart from the CLIPPER_* variables
+        // of the command line options.
+        builder = builder.add_source(
+            config::Environment::with_prefix("CLIPPER")
+                .prefix_separator("__")
+                .separator("__")
+                .try_parsing(true)
+                .source(env),
+        );
+
         // Build initial config
         let mut cfg: ServerConfig = builder.build()?.try_deserialize()?;
 
@@ -411,6 +1551,19 @@ impl ServerConfig {
             cfg.server.port = port;
         }
 
+        if let Some(base_path) = cli.base_path {
+            cfg.server.base_path = base_path;
+        }
+        cfg.server.base_path = normalize_base_path(&cfg.server.base_path);
+
+        if let Some(named_pipe) = cli.named_pipe {
+            cfg.server.named_pipe = Some(named_pipe).filter(|name| !name.is_empty());
+        }
+
+        if let Some(shutdown_timeout) = cli.shutdown_timeout {
+            cfg.server.shutdown_timeout_secs = shutdown_timeout;
+        }
+
         // TLS configuration overrides
         if let Some(tls_enabled) = cli.tls_enabled {
             cfg.tls.enabled = tls_enabled;
@@ -445,6 +1598,14 @@ impl ServerConfig {
             cfg.acme.domain = Some(acme_domain);
         }
 
+        if let Some(acme_additional_domains) = cli.acme_additional_domains {
+            cfg.acme.additional_domains = acme_additional_domains;
+        }
+
+        if let Some(acme_dns_hook) = cli.acme_dns_hook {
+            cfg.acme.dns_hook = Some(acme_dns_hook);
+        }
+
         if let Some(acme_email) = cli.acme_email {
             cfg.acme.contact_email = Some(acme_email);
         }
@@ -475,16 +1636,82 @@ impl ServerConfig {
             cfg.cleanup.interval_hours = interval_hours;
         }
 
+        if let Some(mode) = cli.cleanup_mode {
+            cfg.cleanup.mode = mode;
+        }
+
+        if let Some(archive_dir) = cli.cleanup_archive_dir {
+            cfg.cleanup.archive_dir = archive_dir;
+        }
+
         // Auth configuration overrides
         if let Some(bearer_token) = cli.bearer_token
             && !bearer_token.is_empty()
         {
-            cfg.auth.bearer_token = Some(bearer_token);
+            cfg.auth.bearer_token = Some(SecretString::new(bearer_token));
+        }
+
+        if let Some(admin_token) = cli.admin_token
+            && !admin_token.is_empty()
+        {
+            cfg.auth.admin_token = Some(SecretString::new(admin_token));
+        }
+
+        if let Some(guest_token) = cli.guest_token
+            && !guest_token.is_empty()
+        {
+            cfg.auth.guest_token = Some(SecretString::new(guest_token));
+        }
+
+        // Admin configuration overrides
+        if let Some(backup_dir) = cli.backup_dir {
+            cfg.admin.backup_dir = backup_dir;
+        }
+
+        if let Some(max_backups) = cli.max_backups {
+            cfg.admin.max_backups = max_backups;
         }
 
         // Upload configuration overrides
         if let Some(max_upload_size_mb) = cli.max_upload_size_mb {
-            cfg.upload.max_size_bytes = max_upload_size_mb * 1024 * 1024;
+            cfg.upload.max_size_bytes = size_in_bytes(max_upload_size_mb, 1024 * 1024)
+                .ok_or_else(|| size_too_large("--max-upload-size-mb", max_upload_size_mb))?;
+        }
+
+        if let Some(max_json_size_kb) = cli.max_json_size_kb {
+            cfg.upload.max_json_size_bytes = size_in_bytes(max_json_size_kb, 1024)
+                .ok_or_else(|| size_too_large("--max-json-size-kb", max_json_size_kb))?;
+        }
+
+        if let Some(max_text_size_kb) = cli.max_text_size_kb {
+            cfg.upload.max_text_size_bytes = size_in_bytes(max_text_size_kb, 1024)
+                .ok_or_else(|| size_too_large("--max-text-size-kb", max_text_size_kb))?;
+        }
+
+        if let Some(max_import_size_mb) = cli.max_import_size_mb {
+            cfg.upload.max_import_size_bytes = size_in_bytes(max_import_size_mb, 1024 * 1024)
+                .ok_or_else(|| size_too_large("--max-import-size-mb", max_import_size_mb))?;
+        }
+
+        // Upload scanning configuration overrides
+        if let Some(clamd) = cli.scan_clamd {
+            cfg.scan.clamd = Some(clamd);
+        }
+
+        if let Some(command) = cli.scan_command {
+            cfg.scan.command = Some(command);
+        }
+
+        if let Some(action) = cli.scan_action {
+            cfg.scan.action = action;
+        }
+
+        if let Some(quarantine_dir) = cli.scan_quarantine_dir {
+            cfg.scan.quarantine_dir = quarantine_dir;
+        }
+
+        if let Some(fail_open) = cli.scan_fail_open {
+            cfg.scan.fail_open = fail_open;
         }
 
         // Short URL configuration overrides
@@ -496,6 +1723,116 @@ impl ServerConfig {
             cfg.short_url.default_expiration_hours = short_url_expiration_hours;
         }
 
+        // Sensitive clip configuration overrides
+        if let Some(detect_secrets) = cli.sensitive_detect_secrets {
+            cfg.sensitive.detect_secrets = detect_secrets;
+        }
+
+        if let Some(retention_minutes) = cli.sensitive_retention_minutes {
+            cfg.sensitive.retention_minutes = retention_minutes;
+        }
+
+        // Enrichment configuration overrides
+        if let Some(enrichment_enabled) = cli.enrichment_enabled {
+            cfg.enrichment.enabled = enrichment_enabled;
+        }
+
+        if let Some(enrichment_endpoint) = cli.enrichment_endpoint {
+            cfg.enrichment.endpoint = enrichment_endpoint;
+        }
+
+        if let Some(enrichment_api_key) = cli.enrichment_api_key
+            && !enrichment_api_key.is_empty()
+        {
+            cfg.enrichment.api_key = Some(SecretString::new(enrichment_api_key));
+        }
+
+        if let Some(enrichment_model) = cli.enrichment_model {
+            cfg.enrichment.model = enrichment_model;
+        }
+
+        // Translation configuration overrides
+        if let Some(translation_provider) = cli.translation_provider {
+            cfg.translation.provider = translation_provider;
+        }
+
+        if let Some(translation_endpoint) = cli.translation_endpoint
+            && !translation_endpoint.is_empty()
+        {
+            cfg.translation.endpoint = Some(translation_endpoint);
+        }
+
+        if let Some(translation_api_key) = cli.translation_api_key
+            && !translation_api_key.is_empty()
+        {
+            cfg.translation.api_key = Some(SecretString::new(translation_api_key));
+        }
+
+        // Canonical URL configuration overrides
+        if let Some(canonical_url_enabled) = cli.canonical_url_enabled {
+            cfg.canonical_url.enabled = canonical_url_enabled;
+        }
+
+        if let Some(resolve_redirects) = cli.canonical_url_resolve_redirects {
+            cfg.canonical_url.resolve_redirects = resolve_redirects;
+        }
+
+        // Plugin configuration overrides
+        if let Some(plugins_dir) = cli.plugins_dir {
+            cfg.plugins.dir = Some(plugins_dir);
+        }
+
+        // Discovery configuration overrides
+        if let Some(mdns_enabled) = cli.mdns_enabled {
+            cfg.discovery.enabled = mdns_enabled;
+        }
+
+        if let Some(mdns_name) = cli.mdns_name {
+            cfg.discovery.instance_name = Some(mdns_name);
+        }
+
+        // Web Push configuration overrides
+        if let Some(web_push_vapid_key) = cli.web_push_vapid_key {
+            cfg.web_push.vapid_private_key = Some(web_push_vapid_key);
+        }
+
+        if let Some(web_push_subject) = cli.web_push_subject {
+            cfg.web_push.subject = Some(web_push_subject);
+        }
+
+        // Logging configuration overrides
+        if let Some(log_file) = cli.log_file {
+            cfg.log.file = Some(log_file).filter(|path| !path.as_os_str().is_empty());
+        }
+
+        if let Some(log_rotation) = cli.log_rotation {
+            cfg.log.rotation = log_rotation;
+        }
+
+        if let Some(log_max_size_mb) = cli.log_max_size_mb {
+            cfg.log.max_size_mb = log_max_size_mb;
+        }
+
+        if let Some(log_max_files) = cli.log_max_files {
+            cfg.log.max_files = log_max_files;
+        }
+
+        if let Some(log_compress) = cli.log_compress {
+            cfg.log.compress = log_compress;
+        }
+
+        if let Some(log_format) = cli.log_format {
+            cfg.log.format = log_format;
+        }
+
+        if let Some(access_log) = cli.access_log {
+            cfg.log.access_log = access_log;
+        }
+
+        if let Some(otlp_endpoint) = cli.otlp_endpoint {
+            cfg.log.otlp_endpoint = Some(otlp_endpoint).filter(|endpoint| !endpoint.is_empty());
+        }
+
         Ok(cfg)
     }
 
@@ -556,6 +1893,15 @@ impl ServerConfig {
                 if self.acme.domain.is_none() {
                     return Err("ACME enabled but no domain provided. Set acme.domain.".to_string());
                 }
+                if self.acme.dns_hook.is_none()
+                    && let Some(wildcard) = self.acme.domains().iter().find(|d| d.contains('*'))
+                {
+                    return Err(format!(
+                        "ACME domain {} is a wildcard, which can only be validated over DNS. \
+                         Set acme.dns_hook.",
+                        wildcard
+                    ));
+                }
                 if self.acme.contact_email.is_none() {
                     return Err(
                         "ACME enabled but no contact email provided. Set acme.contact_email."
@@ -575,9 +1921,257 @@ impl ServerConfig {
             }
         }
 
+        let base_path = &self.server.base_path;
+        if base_path.split('/').skip(1).any(|segment| {
+            segment.is_empty()
+                || segment == "."
+                || segment == ".."
+                || !segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c))
+        }) {
+            return Err(format!(
+                "Invalid base path {:?}. Use path segments of letters, digits and -._~, \
+                 e.g. \"/clipper\".",
+                base_path
+            ));
+        }
+
+        if let Some(named_pipe) = &self.server.named_pipe {
+            if !cfg!(windows) {
+                return Err("server.named_pipe is only supported on Windows.".to_string());
+            }
+            if !named_pipe.starts_with(r"\\.\pipe\") {
+                return Err(format!(
+                    r"Invalid named pipe {:?}. Pipe names start with \\.\pipe\",
+                    named_pipe
+                ));
+            }
+            if self.tls.enabled {
+                return Err("server.named_pipe can't be combined with TLS.".to_string());
+            }
+        }
+
+        for (name, bytes) in [
+            ("upload.max_size_bytes", self.upload.max_size_bytes),
+            ("upload.max_json_size_bytes", self.upload.max_json_size_bytes),
+            ("upload.max_text_size_bytes", self.upload.max_text_size_bytes),
+            ("upload.max_import_size_bytes", self.upload.max_import_size_bytes),
+        ] {
+            if bytes > MAX_SIZE_BYTES {
+                return Err(format!(
+                    "{} is {}, which is too large. The limit is {} bytes.",
+                    name, bytes, MAX_SIZE_BYTES
+                ));
+            }
+        }
+
+        if size_in_bytes(self.log.max_size_mb, 1024 * 1024).is_none() {
+            return Err(format!(
+                "log.max_size_mb is {}, which is too large. The limit is {} bytes.",
+                self.log.max_size_mb, MAX_SIZE_BYTES
+            ));
+        }
+
+        if self.cleanup.retention_days > MAX_RETENTION_DAYS {
+            return Err(format!(
+                "cleanup.retention_days is {}, which is too long. The limit is {} days.",
+                self.cleanup.retention_days, MAX_RETENTION_DAYS
+            ));
+        }
+
+        if self.cleanup.mode == CleanupMode::Archive && self.cleanup.archive_dir.trim().is_empty() {
+            return Err(
+                "cleanup.mode is archive but cleanup.archive_dir is empty. \
+                 Set the directory for the archives of expired clips."
+                    .to_string(),
+            );
+        }
+
+        if self.scan.clamd.is_some() && self.scan.command.is_some() {
+            return Err(
+                "scan.clamd and scan.command are both set. \
+                 Set one of them to choose how uploads are scanned."
+                    .to_string(),
+            );
+        }
+
+        if let Some(clamd) = &self.scan.clamd
+            && clamd.contains('/')
+            && !cfg!(unix)
+        {
+            return Err(format!(
+                "scan.clamd {:?} is a Unix socket, which isn't supported on this platform. \
+                 Use host:port instead.",
+                clamd
+            ));
+        }
+
+        if self.scan.is_enabled() {
+            if self.scan.timeout_secs == 0 {
+                return Err("scan.timeout_secs must be greater than 0.".to_string());
+            }
+            if self.scan.action == ScanAction::Quarantine
+                && self.scan.quarantine_dir.trim().is_empty()
+            {
+                return Err(
+                    "scan.action is quarantine but scan.quarantine_dir is empty. \
+                     Set the directory for quarantined uploads."
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.auth.admin_token_enabled() && self.auth.admin_token == self.auth.bearer_token {
+            return Err(
+                "auth.admin_token is the same as auth.bearer_token. \
+                 Set a different token so clients can't administer the server."
+                    .to_string(),
+            );
+        }
+
+        if self.auth.guest_token_enabled() {
+            if !self.auth.is_enabled() {
+                return Err(
+                    "auth.guest_token is set but auth.bearer_token isn't, so every request \
+                     could already change clips. Set a bearer token as well."
+                        .to_string(),
+                );
+            }
+            if self.auth.guest_token == self.auth.bearer_token
+                || self.auth.guest_token == self.auth.admin_token
+            {
+                return Err(
+                    "auth.guest_token is the same as auth.bearer_token or auth.admin_token. \
+                     Set a different token so guests can't change clips."
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.enrichment.enabled {
+            let endpoint = self.enrichment.endpoint.trim();
+            if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+                return Err(format!(
+                    "enrichment.endpoint {:?} is not an http:// or https:// URL",
+                    self.enrichment.endpoint
+                ));
+            }
+            if self.enrichment.model.trim().is_empty() {
+                return Err("enrichment is enabled but enrichment.model is empty".to_string());
+            }
+        }
+
+        if self.translation.is_enabled() {
+            let endpoint = self.translation.endpoint();
+            if !(endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+                return Err(format!(
+                    "translation.endpoint {:?} is not an http:// or https:// URL",
+                    endpoint
+                ));
+            }
+            if self.translation.provider == TranslationProvider::DeepL
+                && self.translation.api_key.is_none()
+            {
+                return Err(
+                    "translation.provider is deepl but translation.api_key is not set".to_string(),
+                );
+            }
+        }
+
+        if let Some(param) = self
+            .canonical_url
+            .strip_params
+            .iter()
+            .find(|param| param.trim_end_matches('*').is_empty())
+        {
+            return Err(format!(
+                "canonical_url.strip_params has {:?}, which would remove every parameter",
+                param
+            ));
+        }
+
+        #[cfg(not(feature = "otel"))]
+        if self.log.otlp_endpoint.is_some() {
+            return Err(
+                "log.otlp_endpoint is set but the 'otel' feature is not compiled in. \
+                 Rebuild with --features otel or unset log.otlp_endpoint."
+                    .to_string(),
+            );
+        }
+
         Ok(())
     }
 
+    /// A copy with the tokens and keys replaced by `"[REDACTED]"`, for
+    /// showing the configuration to administrators
+    pub fn redacted(&self) -> ServerConfig {
+        const REDACTED: &str = "[REDACTED]";
+
+        let mut config = self.clone();
+        for token in [
+            &mut config.auth.bearer_token,
+            &mut config.auth.admin_token,
+            &mut config.auth.guest_token,
+        ] {
+            if token.is_some() {
+                *token = Some(REDACTED.into());
+            }
+        }
+        if config.web_push.vapid_private_key.is_some() {
+            config.web_push.vapid_private_key = Some(REDACTED.to_string());
+        }
+        if config.enrichment.api_key.is_some() {
+            config.enrichment.api_key = Some(REDACTED.into());
+        }
+        if config.translation.api_key.is_some() {
+            config.translation.api_key = Some(REDACTED.into());
+        }
+        config
+    }
+
+    /// The configuration as a config file in `format`, for `print-config`
+    pub fn to_string_pretty(&self, format: ConfigFormat) -> Result<String, String> {
+        match format {
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(self).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// A copy with the [`RELOADABLE_SECTIONS`] taken from `new`, and which
+    /// sections of `new` differ from this configuration
+    pub fn with_reloaded(&self, new: ServerConfig) -> (ServerConfig, ConfigChanges) {
+        let mut changes = ConfigChanges::default();
+        if let (Ok(serde_json::Value::Object(current)), Ok(serde_json::Value::Object(reloaded))) =
+            (serde_json::to_value(self), serde_json::to_value(&new))
+        {
+            for (section, value) in reloaded {
+                if current.get(&section) == Some(&value) {
+                    continue;
+                }
+                if RELOADABLE_SECTIONS.contains(&section.as_str()) {
+                    changes.applied.push(section);
+                } else {
+                    changes.restart_required.push(section);
+                }
+            }
+        }
+
+        let config = ServerConfig {
+            cleanup: new.cleanup,
+            upload: new.upload,
+            scan: new.scan,
+            short_url: new.short_url,
+            sensitive: new.sensitive,
+            enrichment: new.enrichment,
+            translation: new.translation,
+            canonical_url: new.canonical_url,
+            admin: new.admin,
+            ..self.clone()
+        };
+        (config, changes)
+    }
+
     /// Check if TLS is available (feature compiled and enabled in config)
     pub fn tls_available(&self) -> bool {
         #[cfg(feature = "tls")]
@@ -603,6 +2197,81 @@ impl ServerConfig {
     }
 }
 
+/// The configuration of a running server, which reloading replaces
+#[derive(Clone)]
+pub struct SharedConfig(Arc<watch::Sender<Arc<ServerConfig>>>);
+
+impl SharedConfig {
+    pub fn new(config: ServerConfig) -> Self {
+        Self(Arc::new(watch::Sender::new(Arc::new(config))))
+    }
+
+    /// The current configuration
+    pub fn get(&self) -> Arc<ServerConfig> {
+        self.0.borrow().clone()
+    }
+
+    /// Replace the configuration
+    pub fn set(&self, config: ServerConfig) {
+        self.0.send_replace(Arc::new(config));
+    }
+
+    /// A receiver that is notified whenever the configuration is replaced
+    pub fn subscribe(&self) -> watch::Receiver<Arc<ServerConfig>> {
+        self.0.subscribe()
+    }
+}
+
+/// What reloading the configuration changed
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigChanges {
+    /// Changed sections that are in effect now
+    pub applied: Vec<String>,
+    /// Changed sections that only take effect when the server restarts
+    pub restart_required: Vec<String>,
+}
+
+/// Reloads the configuration from the same file, environment and command
+/// line the server started with
+#[derive(Clone)]
+pub struct ConfigReloader {
+    cli: Arc<Cli>,
+    config: SharedConfig,
+}
+
+impl ConfigReloader {
+    pub fn new(cli: Cli, config: SharedConfig) -> Self {
+        Self {
+            cli: Arc::new(cli),
+            config,
+        }
+    }
+
+    /// Load and validate the configuration again and apply its
+    /// [`RELOADABLE_SECTIONS`]. An invalid configuration leaves the current
+    /// one in place.
+    pub fn reload(&self) -> Result<ConfigChanges, String> {
+        let new = ServerConfig::load(Cli::clone(&self.cli)).map_err(|e| e.to_string())?;
+        new.validate()?;
+
+        let (config, changes) = self.config.get().with_reloaded(new);
+        if !changes.applied.is_empty() {
+            self.config.set(config);
+            tracing::info!("Reloaded configuration: {}", changes.applied.join(", "));
+        }
+        for section in &changes.restart_required {
+            tracing::warn!(
+                "Configuration section [{}] changed; restart the server to apply it",
+                section
+            );
+        }
+        if changes.applied.is_empty() && changes.restart_required.is_empty() {
+            tracing::info!("Reloaded configuration: nothing changed");
+        }
+        Ok(changes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -658,6 +2327,38 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_normalize_base_path() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path("/"), "");
+        assert_eq!(normalize_base_path("clipper"), "/clipper");
+        assert_eq!(normalize_base_path("/clipper/"), "/clipper");
+        assert_eq!(normalize_base_path("/tools/clipper"), "/tools/clipper");
+    }
+
+    #[test]
+    fn test_validate_base_path() {
+        let mut config = ServerConfig::default();
+        config.server.base_path = "/tools/clipper".to_string();
+        assert!(config.validate().is_ok());
+
+        for invalid in ["/{id}", "/a//b", "/../x", "/a b", "/a?b"] {
+            config.server.base_path = invalid.to_string();
+            assert!(config.validate().is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn test_validate_named_pipe() {
+        let mut config = ServerConfig::default();
+        config.server.named_pipe = Some(r"\\.\pipe\clipper".to_string());
+        // Named pipes only exist on Windows
+        assert_eq!(config.validate().is_ok(), cfg!(windows));
+
+        config.server.named_pipe = Some("clipper".to_string());
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_validate_acme_without_domain() {
         let mut config = ServerConfig::default();
@@ -688,7 +2389,40 @@ mod tests {
         config.tls.enabled = true;
         config.acme.domain = Some("example.com".to_string());
         config.acme.contact_email = Some("admin@example.com".to_string());
-        config.auth.bearer_token = Some("secret-token".to_string());
+        config.auth.bearer_token = Some("secret-token".into());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_acme_domains() {
+        let mut config = AcmeConfig::default();
+        assert!(config.domains().is_empty());
+
+        config.domain = Some("Clipper.example.com".to_string());
+        config.additional_domains = vec![
+            "clip.example.com".to_string(),
+            " ".to_string(),
+            "clipper.example.com".to_string(),
+        ];
+        assert_eq!(
+            config.domains(),
+            vec!["clipper.example.com", "clip.example.com"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "acme")]
+    fn test_validate_acme_wildcard_requires_dns_hook() {
+        let mut config = ServerConfig::default();
+        config.acme.enabled = true;
+        config.tls.enabled = true;
+        config.acme.domain = Some("example.com".to_string());
+        config.acme.additional_domains = vec!["*.example.com".to_string()];
+        config.acme.contact_email = Some("admin@example.com".to_string());
+        config.auth.bearer_token = Some("secret-token".into());
+        assert!(config.validate().unwrap_err().contains("dns_hook"));
+
+        config.acme.dns_hook = Some(PathBuf::from("/usr/local/bin/dns-hook"));
         assert!(config.validate().is_ok());
     }
 
@@ -762,11 +2496,637 @@ mod tests {
         assert_eq!(config.interval(), std::time::Duration::from_secs(12 * 3600));
     }
 
+    #[test]
+    fn test_retention_parse() {
+        assert_eq!("7d".parse(), Ok(Retention::Days(7)));
+        assert_eq!("30".parse(), Ok(Retention::Days(30)));
+        assert_eq!("never".parse(), Ok(Retention::Never));
+        assert!("0d".parse::<Retention>().is_err());
+        assert!("1w".parse::<Retention>().is_err());
+        assert_eq!("36500d".parse(), Ok(Retention::Days(MAX_RETENTION_DAYS)));
+        assert!("36501d".parse::<Retention>().is_err());
+        assert!("4000000000d".parse::<Retention>().is_err());
+        assert_eq!(String::from(Retention::Days(7)), "7d");
+        assert!(Retention::Days(365) < Retention::Never);
+    }
+
+    #[test]
+    fn test_retention_is_expired() {
+        let now = chrono::Utc::now();
+        let week_ago = now - chrono::Duration::days(7);
+
+        assert!(Retention::Days(7).is_expired(week_ago, now));
+        assert!(!Retention::Days(8).is_expired(week_ago, now));
+        assert!(!Retention::Never.is_expired(week_ago, now));
+        // Too long to subtract from `now`, e.g. from `retention_days`
+        assert!(!Retention::Days(u32::MAX).is_expired(week_ago, now));
+    }
+
+    #[test]
+    fn test_retention_for_tags() {
+        let rule = |tag: &str, retention: Retention| TagRetentionRule {
+            tag: tag.to_string(),
+            retention,
+        };
+        let config = CleanupConfig {
+            retention_days: 30,
+            tag_rules: vec![
+                rule("$screenshot", Retention::Days(7)),
+                rule("work", Retention::Never),
+                rule("$host:kiosk", Retention::Days(1)),
+            ],
+            ..Default::default()
+        };
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(config.retention_for(&[]), Retention::Days(30));
+        assert_eq!(
+            config.retention_for(&tags(&["$host:laptop"])),
+            Retention::Days(30)
+        );
+        assert_eq!(
+            config.retention_for(&tags(&["$host:kiosk"])),
+            Retention::Days(1)
+        );
+        assert_eq!(
+            config.retention_for(&tags(&["$screenshot", "$host:laptop"])),
+            Retention::Days(7)
+        );
+        // The longest retention wins, and tags without a rule keep the clip
+        assert_eq!(
+            config.retention_for(&tags(&["$screenshot", "work"])),
+            Retention::Never
+        );
+        assert_eq!(
+            config.retention_for(&tags(&["$screenshot", "ideas"])),
+            Retention::Never
+        );
+    }
+
+    #[test]
+    fn test_tag_rules_from_toml() {
+        let cleanup: CleanupConfig = toml::from_str(
+            r#"
+            enabled = true
+            retention_days = 30
+            interval_hours = 24
+
+            [[tag_rules]]
+            tag = "$screenshot"
+            retention = "7d"
+
+            [[tag_rules]]
+            tag = "work"
+            retention = "never"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cleanup.tag_rules.len(), 2);
+        assert_eq!(cleanup.tag_rules[0].retention, Retention::Days(7));
+        assert_eq!(cleanup.tag_rules[1].retention, Retention::Never);
+
+        let invalid = toml::from_str::<CleanupConfig>(
+            r#"
+            enabled = true
+            retention_days = 30
+            interval_hours = 24
+            tag_rules = [{ tag = "work", retention = "forever" }]
+            "#,
+        );
+        assert!(invalid.is_err());
+    }
+
     #[test]
     fn test_default_config_includes_cleanup() {
         let config = ServerConfig::default();
         assert!(!config.cleanup.enabled);
         assert_eq!(config.cleanup.retention_days, 30);
         assert_eq!(config.cleanup.interval_hours, 24);
+        assert_eq!(config.cleanup.mode, CleanupMode::Delete);
+    }
+
+    #[test]
+    fn test_cleanup_archive_mode() {
+        let cleanup: CleanupConfig = toml::from_str(
+            r#"
+            enabled = true
+            retention_days = 30
+            interval_hours = 24
+            mode = "archive"
+            archive_dir = "/srv/clipper-archive"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cleanup.mode, CleanupMode::Archive);
+        assert_eq!(cleanup.archive_dir, "/srv/clipper-archive");
+
+        let mut config = ServerConfig::default();
+        config.cleanup.mode = CleanupMode::Archive;
+        assert!(config.validate().is_ok());
+        config.cleanup.archive_dir = String::new();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_text_size_limit() {
+        let mut upload = UploadConfig::default();
+        assert_eq!(upload.max_text_size_bytes, 256 * 1024);
+        // Truncated text is kept as an attachment, so it may be upload-sized
+        assert_eq!(upload.max_text_clip_body_bytes(), upload.max_size_bytes);
+
+        upload.max_text_size_bytes = 0;
+        assert_eq!(
+            upload.max_text_clip_body_bytes(),
+            upload.max_json_size_bytes
+        );
+
+        let upload: UploadConfig = toml::from_str(
+            "max_size_bytes = 1000\nmax_json_size_bytes = 2000\nmax_import_size_bytes = 3000\n",
+        )
+        .unwrap();
+        assert_eq!(upload.max_text_size_bytes, 256 * 1024);
+        assert_eq!(upload.max_text_clip_body_bytes(), 2000);
+    }
+
+    #[test]
+    fn test_size_limits_out_of_range() {
+        // Would overflow u64 when converted to bytes
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--max-text-size-kb",
+            &u64::MAX.to_string(),
+        ]);
+        let error = ServerConfig::load_with_env(cli, Some(config::Map::new())).unwrap_err();
+        assert!(error.to_string().contains("--max-text-size-kb"));
+
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--max-upload-size-mb",
+            "1024",
+        ]);
+        let config = ServerConfig::load_with_env(cli, Some(config::Map::new())).unwrap();
+        assert_eq!(config.upload.max_size_bytes, 1024 * 1024 * 1024);
+
+        // The config file and CLIPPER__ variables set bytes directly
+        let mut config = ServerConfig::default();
+        config.upload.max_text_size_bytes = u64::MAX;
+        let error = config.validate().unwrap_err();
+        assert!(error.contains("upload.max_text_size_bytes"));
+
+        let mut config = ServerConfig::default();
+        config.log.max_size_mb = u64::MAX;
+        assert!(config.validate().unwrap_err().contains("log.max_size_mb"));
+        assert_eq!(config.log.max_size_bytes(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_scan_config() {
+        let config = ServerConfig::default();
+        assert!(!config.scan.is_enabled());
+        assert_eq!(config.scan.action, ScanAction::Reject);
+
+        let scan: ScanConfig = toml::from_str(
+            r#"
+            clamd = "/run/clamav/clamd.ctl"
+            action = "quarantine"
+            quarantine_dir = "/srv/clipper-quarantine"
+            "#,
+        )
+        .unwrap();
+        assert!(scan.is_enabled());
+        assert_eq!(scan.action, ScanAction::Quarantine);
+        assert!(!scan.fail_open);
+        assert_eq!(scan.timeout_secs, 60);
+
+        let mut config = ServerConfig::default();
+        config.scan.clamd = Some("127.0.0.1:3310".to_string());
+        assert!(config.validate().is_ok());
+        config.scan.command = Some(PathBuf::from("/usr/bin/clamscan"));
+        assert!(config.validate().is_err());
+        config.scan.clamd = None;
+        assert!(config.validate().is_ok());
+        config.scan.action = ScanAction::Quarantine;
+        config.scan.quarantine_dir = String::new();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_sensitive_config() {
+        let config = ServerConfig::default();
+        assert!(config.sensitive.detect_secrets);
+        assert_eq!(
+            config.sensitive.retention(),
+            Some(chrono::Duration::minutes(60))
+        );
+
+        let sensitive: SensitiveConfig = toml::from_str("retention_minutes = 0").unwrap();
+        assert!(sensitive.detect_secrets);
+        assert_eq!(sensitive.retention(), None);
+    }
+
+    #[test]
+    fn test_enrichment_config() {
+        let enrichment: EnrichmentConfig = toml::from_str(
+            r#"
+            enabled = true
+            endpoint = "https://api.openai.com/v1"
+            model = "gpt-4o-mini"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(enrichment.max_requests_per_minute, 10);
+        assert_eq!(enrichment.timeout(), std::time::Duration::from_secs(30));
+
+        let mut config = ServerConfig::default();
+        assert!(!config.enrichment.enabled);
+        config.enrichment = enrichment;
+        assert!(config.validate().is_ok());
+        config.enrichment.endpoint = "localhost:11434".to_string();
+        assert!(config.validate().is_err());
+        config.enrichment.endpoint = "http://localhost:11434/v1".to_string();
+        config.enrichment.model = " ".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_translation_config() {
+        let mut config = ServerConfig::default();
+        assert!(!config.translation.is_enabled());
+        assert!(config.validate().is_ok());
+
+        config.translation = toml::from_str(
+            r#"
+            provider = "deepl"
+            api_key = "0123-abcd:fx"
+            "#,
+        )
+        .unwrap();
+        assert!(config.translation.is_enabled());
+        assert_eq!(config.translation.endpoint(), "https://api-free.deepl.com");
+        assert_eq!(config.translation.max_input_chars, 10000);
+        assert!(config.validate().is_ok());
+
+        config.translation.api_key = Some("0123-abcd".into());
+        assert_eq!(config.translation.endpoint(), "https://api.deepl.com");
+        config.translation.api_key = None;
+        assert!(config.validate().is_err());
+
+        config.translation.provider = TranslationProvider::LibreTranslate;
+        assert_eq!(config.translation.endpoint(), "http://localhost:5000");
+        config.translation.endpoint = Some("https://translate.example/".to_string());
+        assert_eq!(config.translation.endpoint(), "https://translate.example");
+        assert!(config.validate().is_ok());
+        config.translation.endpoint = Some("translate.example".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_canonical_url_config() {
+        let config = ServerConfig::default();
+        assert!(!config.canonical_url.enabled);
+        assert!(config.canonical_url.resolve_redirects);
+        assert!(!config.canonical_url.allow_private_addresses);
+        assert!(config
+            .canonical_url
+            .strip_params
+            .contains(&"utm_*".to_string()));
+
+        let canonical_url: CanonicalUrlConfig = toml::from_str(
+            r#"
+            enabled = true
+            strip_params = ["ref"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(canonical_url.strip_params, vec!["ref"]);
+        assert_eq!(canonical_url.max_redirects, 10);
+        assert_eq!(canonical_url.timeout(), std::time::Duration::from_secs(10));
+
+        let mut config = ServerConfig::default();
+        config.canonical_url.strip_params = vec!["*".to_string()];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_log_default() {
+        let config = ServerConfig::default();
+        assert!(config.log.file.is_none());
+        assert_eq!(config.log.rotation, LogRotation::Daily);
+        assert_eq!(config.log.max_size_bytes(), None);
+        assert_eq!(config.log.max_files, 7);
+        assert_eq!(config.log.format, LogFormat::Text);
+        assert!(!config.log.access_log);
+        assert!(config.log.otlp_endpoint.is_none());
+    }
+
+    #[test]
+    fn test_validate_otlp_endpoint() {
+        let mut config = ServerConfig::default();
+        config.log.otlp_endpoint = Some("http://localhost:4318".to_string());
+        // Exporting traces needs the otel feature
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "otel"));
+    }
+
+    #[test]
+    fn test_log_cli_overrides() {
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--log-file",
+            "/var/log/clipper/server.log",
+            "--log-rotation",
+            "hourly",
+            "--log-max-size-mb",
+            "50",
+            "--log-compress",
+            "true",
+            "--log-format",
+            "json",
+            "--access-log",
+        ]);
+        let config = ServerConfig::load(cli).unwrap();
+        assert_eq!(
+            config.log.file,
+            Some(PathBuf::from("/var/log/clipper/server.log"))
+        );
+        assert_eq!(config.log.rotation, LogRotation::Hourly);
+        assert_eq!(config.log.max_size_bytes(), Some(50 * 1024 * 1024));
+        assert!(config.log.compress);
+        assert_eq!(config.log.format, LogFormat::Json);
+        assert!(config.log.access_log);
+    }
+
+    #[test]
+    fn test_admin_cli_overrides() {
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--admin-token",
+            "admin-secret",
+            "--backup-dir",
+            "/var/backups/clipper",
+            "--max-backups",
+            "3",
+        ]);
+        let config = ServerConfig::load(cli).unwrap();
+        assert!(config.auth.admin_token_enabled());
+        assert!(config.auth.validate_admin_token("admin-secret"));
+        assert!(!config.auth.validate_admin_token("other"));
+        assert_eq!(config.admin.backup_dir, "/var/backups/clipper");
+        assert_eq!(config.admin.max_backups, 3);
+    }
+
+    #[test]
+    fn test_load_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("clipper-server.toml");
+        std::fs::write(
+            &config_path,
+            "[log]\nmax_files = 5\n\n[server]\nport = 4000\n\n[upload]\nmax_size_bytes = 1000\n",
+        )
+        .unwrap();
+        let env = config::Map::from([
+            ("CLIPPER__LOG__MAX_FILES".to_string(), "3".to_string()),
+            ("CLIPPER__SERVER__PORT".to_string(), "5000".to_string()),
+            ("CLIPPER__CLEANUP__ENABLED".to_string(), "true".to_string()),
+        ]);
+
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--port",
+            "6000",
+        ]);
+        let config = ServerConfig::load_with_env(cli, Some(env)).unwrap();
+        assert_eq!(config.upload.max_size_bytes, 1000);
+        assert_eq!(config.log.max_files, 3);
+        assert!(config.cleanup.enabled);
+        assert_eq!(config.server.port, 6000);
+    }
+
+    #[test]
+    fn test_load_data_dir() {
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--data-dir",
+            "/data",
+            "--db-path",
+            "/db",
+        ]);
+        let config = ServerConfig::load_with_env(cli, Some(config::Map::new())).unwrap();
+        assert_eq!(config.database.path, "/db");
+        assert_eq!(
+            PathBuf::from(&config.storage.path),
+            PathBuf::from("/data").join("storage")
+        );
+        assert_eq!(
+            PathBuf::from(&config.admin.backup_dir),
+            PathBuf::from("/data").join("backups")
+        );
+        assert_eq!(
+            PathBuf::from(&config.cleanup.archive_dir),
+            PathBuf::from("/data").join("archive")
+        );
+        assert_eq!(
+            PathBuf::from(&config.scan.quarantine_dir),
+            PathBuf::from("/data").join("quarantine")
+        );
+        assert_eq!(
+            config.acme.get_certs_dir(),
+            PathBuf::from("/data").join("certs")
+        );
+    }
+
+    #[test]
+    fn test_shutdown_timeout() {
+        let config: NetworkConfig =
+            toml::from_str("listen_addr = \"0.0.0.0\"\nport = 3000\n").unwrap();
+        assert_eq!(config.shutdown_timeout(), std::time::Duration::from_secs(5));
+
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            "/nonexistent/clipper-server.toml",
+            "--shutdown-timeout",
+            "1",
+        ]);
+        let config = ServerConfig::load(cli).unwrap();
+        assert_eq!(config.server.shutdown_timeout_secs, 1);
+    }
+
+    #[test]
+    fn test_load_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("clipper-server.yaml");
+        std::fs::write(&config_path, "server:\n  port: 4000\n").unwrap();
+
+        let cli = Cli::parse_from(["clipper-server", "--config", config_path.to_str().unwrap()]);
+        let config = ServerConfig::load_with_env(cli, Some(config::Map::new())).unwrap();
+        assert_eq!(config.server.port, 4000);
+    }
+
+    #[test]
+    fn test_print_config_command() {
+        let cli = Cli::parse_from(["clipper-server", "print-config", "--config", "server.toml"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::PrintConfig {
+                format: ConfigFormat::Toml,
+                show_secrets: false,
+            })
+        ));
+        assert_eq!(cli.config, Some(PathBuf::from("server.toml")));
+
+        let cli = Cli::parse_from(["clipper-server", "print-config", "--format", "json"]);
+        assert!(matches!(
+            cli.command,
+            Some(Command::PrintConfig {
+                format: ConfigFormat::Json,
+                ..
+            })
+        ));
+
+        let cli = Cli::parse_from(["clipper-server"]);
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_to_string_pretty() {
+        let mut config = ServerConfig::default();
+        config.auth.bearer_token = Some("secret-token".into());
+        config.server.port = 4000;
+
+        let toml = config
+            .redacted()
+            .to_string_pretty(ConfigFormat::Toml)
+            .unwrap();
+        assert!(toml.contains("port = 4000"));
+        assert!(toml.contains("[REDACTED]"));
+        assert!(!toml.contains("secret-token"));
+        let parsed: ServerConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.server.port, 4000);
+
+        let json = config.to_string_pretty(ConfigFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["server"]["port"], 4000);
+        assert_eq!(parsed["auth"]["bearer_token"], "secret-token");
+    }
+
+    #[test]
+    fn test_validate_admin_token() {
+        let mut config = ServerConfig::default();
+        assert!(!config.auth.validate_admin_token(""));
+
+        config.auth.admin_token = Some("admin-secret".into());
+        assert!(config.validate().is_ok());
+
+        config.auth.bearer_token = Some("admin-secret".into());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_guest_token() {
+        let mut config = ServerConfig::default();
+        assert!(!config.auth.guest_token_enabled());
+        assert!(!config.auth.validate_guest_token(""));
+
+        // Without a bearer token everyone could already change clips
+        config.auth.guest_token = Some("guest-secret".into());
+        assert!(config.validate().is_err());
+
+        config.auth.bearer_token = Some("shared-secret".into());
+        assert!(config.validate().is_ok());
+        assert!(config.auth.validate_guest_token("guest-secret"));
+        assert!(!config.auth.validate_guest_token("shared-secret"));
+
+        config.auth.admin_token = Some("guest-secret".into());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_redacted() {
+        let mut config = ServerConfig::default();
+        config.auth.bearer_token = Some("secret-token".into());
+        config.web_push.vapid_private_key = Some("vapid-key".to_string());
+        config.enrichment.api_key = Some("sk-secret".into());
+        config.translation.api_key = Some("deepl-key".into());
+
+        let redacted = config.redacted();
+        assert_eq!(
+            redacted.auth.bearer_token.as_ref().map(|t| t.expose()),
+            Some("[REDACTED]")
+        );
+        assert!(redacted.auth.admin_token.is_none());
+        assert_eq!(
+            redacted.web_push.vapid_private_key.as_deref(),
+            Some("[REDACTED]")
+        );
+        assert_eq!(
+            redacted.enrichment.api_key.as_ref().map(|k| k.expose()),
+            Some("[REDACTED]")
+        );
+        assert_eq!(
+            redacted.translation.api_key.as_ref().map(|k| k.expose()),
+            Some("[REDACTED]")
+        );
+        assert_eq!(redacted.database.path, config.database.path);
+    }
+
+    #[test]
+    fn test_with_reloaded() {
+        let config = ServerConfig::default();
+        let mut new = ServerConfig::default();
+        new.upload.max_size_bytes = 1024;
+        new.cleanup.enabled = true;
+        new.server.port = 4000;
+
+        let (reloaded, changes) = config.with_reloaded(new);
+        assert_eq!(changes.applied, vec!["cleanup", "upload"]);
+        assert_eq!(changes.restart_required, vec!["server"]);
+        assert_eq!(reloaded.upload.max_size_bytes, 1024);
+        assert!(reloaded.cleanup.enabled);
+        assert_eq!(reloaded.server.port, config.server.port);
+
+        let (_, changes) = config.with_reloaded(config.clone());
+        assert_eq!(changes, ConfigChanges::default());
+    }
+
+    #[test]
+    fn test_config_reloader() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("clipper-server.toml");
+        std::fs::write(&config_path, "[upload]\nmax_size_bytes = 1000\n").unwrap();
+
+        let cli = Cli::parse_from(["clipper-server", "--config", config_path.to_str().unwrap()]);
+        let config = SharedConfig::new(ServerConfig::load(cli.clone()).unwrap());
+        let reloader = ConfigReloader::new(cli, config.clone());
+        let changes_rx = config.subscribe();
+        assert_eq!(config.get().upload.max_size_bytes, 1000);
+
+        std::fs::write(
+            &config_path,
+            "[upload]\nmax_size_bytes = 2000\n\n[server]\nport = 4000\n",
+        )
+        .unwrap();
+        let changes = reloader.reload().unwrap();
+        assert_eq!(changes.applied, vec!["upload"]);
+        assert_eq!(changes.restart_required, vec!["server"]);
+        assert_eq!(config.get().upload.max_size_bytes, 2000);
+        assert_ne!(config.get().server.port, 4000);
+        assert!(changes_rx.has_changed().unwrap());
+
+        // An invalid file leaves the configuration alone
+        std::fs::write(&config_path, "[upload]\nmax_size_bytes = \"lots\"\n").unwrap();
+        assert!(reloader.reload().is_err());
+        assert_eq!(config.get().upload.max_size_bytes, 2000);
     }
 }
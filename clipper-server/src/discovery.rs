@@ -0,0 +1,153 @@
+//! LAN discovery over mDNS/DNS-SD.
+//!
+//! The server advertises a `_clipper._tcp` service so clients on the local
+//! network can find it without typing an IP address. TXT records carry the
+//! server version, the URL scheme, the TLS certificate fingerprint (so the
+//! client can check it before trusting a self-signed certificate), whether
+//! a bearer token is required and the `server.base_path` the routes live under. See `clipper_client::discovery` for the
+//! browsing side.
+
+use std::net::IpAddr;
+
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+
+use crate::config::ServerConfig;
+
+/// DNS-SD service type advertised by the server
+pub const SERVICE_TYPE: &str = "_clipper._tcp.local.";
+
+/// Keeps the service registered; it is withdrawn when dropped.
+pub struct MdnsAdvertiser {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Drop for MdnsAdvertiser {
+    fn drop(&mut self) {
+        let _ = self.daemon.unregister(&self.fullname);
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// This machine's host name without any domain, e.g. "desktop-abc"
+fn local_hostname() -> String {
+    let hostname = gethostname::gethostname().to_string_lossy().to_string();
+    let hostname = hostname.split('.').next().unwrap_or_default().trim();
+    if hostname.is_empty() {
+        "clipper".to_string()
+    } else {
+        hostname.replace(' ', "-")
+    }
+}
+
+/// Advertise the server on the local network.
+///
+/// Nothing is advertised if discovery is disabled or the server only listens
+/// on a loopback address. Failures are logged rather than returned, since the
+/// server works fine without discovery.
+///
+/// # Arguments
+/// * `config` - Server configuration; HTTPS is advertised when TLS is enabled
+/// * `fingerprint` - SHA-256 fingerprint of the TLS certificate, if any
+pub fn advertise(config: &ServerConfig, fingerprint: Option<&str>) -> Option<MdnsAdvertiser> {
+    if !config.discovery.enabled {
+        return None;
+    }
+
+    let listen_ip: Option<IpAddr> = config.server.listen_addr.parse().ok();
+    if listen_ip.is_some_and(|ip| ip.is_loopback()) {
+        tracing::debug!("Not advertising over mDNS: server only listens on loopback");
+        return None;
+    }
+
+    let (scheme, port) = if config.tls_available() {
+        ("https", config.tls.port)
+    } else {
+        ("http", config.server.port)
+    };
+
+    let hostname = local_hostname();
+    let instance_name = config
+        .discovery
+        .instance_name
+        .clone()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| format!("Clipper on {}", hostname));
+    let auth = if config.auth.is_enabled() { "1" } else { "0" };
+    let properties = [
+        ("version", env!("CARGO_PKG_VERSION")),
+        ("scheme", scheme),
+        ("fp", fingerprint.unwrap_or_default()),
+        ("auth", auth),
+        ("path", config.server.base_path.as_str()),
+    ];
+
+    // Advertise the listen address if it's a specific one, otherwise every
+    // interface address (kept up to date as interfaces change)
+    let ip = match listen_ip {
+        Some(ip) if !ip.is_unspecified() => ip.to_string(),
+        _ => String::new(),
+    };
+
+    let result = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance_name,
+        &format!("{}.local.", hostname),
+        ip.as_str(),
+        port,
+        &properties[..],
+    )
+    .and_then(|info| {
+        let info = if ip.is_empty() {
+            info.enable_addr_auto()
+        } else {
+            info
+        };
+        let fullname = info.get_fullname().to_string();
+        let daemon = ServiceDaemon::new()?;
+        daemon.register(info)?;
+        Ok(MdnsAdvertiser { daemon, fullname })
+    });
+
+    match result {
+        Ok(advertiser) => {
+            tracing::info!(
+                "Advertising \"{}\" over mDNS ({}, port {})",
+                instance_name,
+                SERVICE_TYPE,
+                port
+            );
+            Some(advertiser)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to advertise over mDNS: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advertise_disabled() {
+        let mut config = ServerConfig::default();
+        config.discovery.enabled = false;
+        assert!(advertise(&config, None).is_none());
+    }
+
+    #[test]
+    fn test_advertise_skips_loopback() {
+        let mut config = ServerConfig::default();
+        config.server.listen_addr = "127.0.0.1".to_string();
+        assert!(advertise(&config, None).is_none());
+    }
+
+    #[test]
+    fn test_local_hostname_has_no_domain() {
+        let hostname = local_hostname();
+        assert!(!hostname.is_empty());
+        assert!(!hostname.contains('.'));
+    }
+}
@@ -0,0 +1,348 @@
+//! Enrichment of new text clips with suggested tags and a summary.
+//!
+//! While `enrichment.enabled` is set, a background task asks an
+//! OpenAI-compatible chat API (OpenAI, a local Ollama, ...) about each new
+//! text clip and stores the answer in the clip's `summary` and
+//! `suggested_tags`. Clips tagged `$sensitive` are never sent, lines that
+//! look like secrets are redacted from the others, and requests beyond
+//! `max_requests_per_minute` are skipped rather than queued. The section is
+//! reloadable, so enrichment can be turned on and off while the server runs.
+
+use std::time::{Duration, Instant};
+
+use clipper_indexer::{ClipboardEntry, SENSITIVE_TAG};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    config::EnrichmentConfig,
+    error::{Result, ServerError},
+    secrets,
+    state::{AppState, ClipUpdate},
+};
+
+/// Most tags kept from an answer
+const MAX_SUGGESTED_TAGS: usize = 5;
+
+/// Longest suggested tag kept, in characters
+const MAX_TAG_CHARS: usize = 32;
+
+/// Longest summary kept, in characters
+const MAX_SUMMARY_CHARS: usize = 200;
+
+const SYSTEM_PROMPT: &str =
+    "You label clipboard snippets. Reply with a JSON object with two keys: \
+    \"summary\", one plain sentence of at most 15 words saying what the snippet is, and \
+    \"tags\", up to 5 short lowercase tags for its topic or kind, such as \"rust\", \"recipe\" \
+    or \"url\". Reply with the JSON object only.";
+
+/// Counts requests per minute, against a limit that can change on reload
+#[derive(Debug)]
+struct RateLimiter {
+    window_start: Instant,
+    used: u32,
+}
+
+impl RateLimiter {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            used: 0,
+        }
+    }
+
+    /// Take one request if fewer than `per_minute` were made in the current
+    /// minute; 0 means no limit
+    fn try_acquire(&mut self, per_minute: u32, now: Instant) -> bool {
+        if now.duration_since(self.window_start) >= Duration::from_secs(60) {
+            self.window_start = now;
+            self.used = 0;
+        }
+        if per_minute > 0 && self.used >= per_minute {
+            return false;
+        }
+        self.used += 1;
+        true
+    }
+}
+
+/// Suggested tags and summary as the model answers them
+#[derive(Debug, Default, Deserialize)]
+struct Suggestion {
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Enrich each new text clip while enrichment is enabled. Runs until the
+/// clip update channel is closed.
+pub async fn run_enrichment_dispatcher(state: AppState) {
+    let client = match reqwest::Client::builder().build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Failed to create HTTP client for enrichment: {}", e);
+            return;
+        }
+    };
+    let mut updates = state.clip_updates.subscribe();
+    let mut limiter = RateLimiter::new(Instant::now());
+
+    loop {
+        match updates.recv().await {
+            Ok(ClipUpdate::NewClip { id, tags, .. }) => {
+                let config = state.config.get().enrichment.clone();
+                if !config.enabled || tags.iter().any(|tag| tag == SENSITIVE_TAG) {
+                    continue;
+                }
+                if !limiter.try_acquire(config.max_requests_per_minute, Instant::now()) {
+                    tracing::debug!(clip_id = %id, "Enrichment rate limit reached, skipping clip");
+                    continue;
+                }
+                // Models can be slow, so don't hold up the next update
+                tokio::spawn(enrich_clip(state.clone(), client.clone(), config, id));
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Enrichment dispatcher skipped {} clip updates", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn enrich_clip(
+    state: AppState,
+    client: reqwest::Client,
+    config: EnrichmentConfig,
+    id: String,
+) {
+    let entry = match state.indexer.get_entry(&id).await {
+        Ok(entry) => entry,
+        // Deleted in the meantime
+        Err(_) => return,
+    };
+    let Some(input) = enrichment_input(&entry, config.max_input_chars) else {
+        return;
+    };
+
+    let suggestion = match request_suggestion(&client, &config, &input).await {
+        Ok(suggestion) => suggestion,
+        Err(e) => {
+            tracing::warn!(clip_id = %id, "Failed to enrich clip: {}", e);
+            return;
+        }
+    };
+    let summary = suggestion.summary.as_deref().and_then(normalize_summary);
+    let suggested_tags = normalize_tags(&suggestion.tags, &entry.tags);
+
+    match state
+        .indexer
+        .set_enrichment(&id, summary, suggested_tags)
+        .await
+    {
+        Ok(_) => state.notify_updated_clip(id),
+        Err(e) => tracing::warn!(clip_id = %id, "Failed to store enrichment of clip: {}", e),
+    }
+}
+
+/// The text sent for a clip: its content with secrets redacted, cut to
+/// `max_chars`. `None` for clips with nothing to send, such as sensitive
+/// clips, file clips without text and blank clips.
+fn enrichment_input(entry: &ClipboardEntry, max_chars: usize) -> Option<String> {
+    if entry.tags.iter().any(|tag| tag == SENSITIVE_TAG) || entry.content.trim().is_empty() {
+        return None;
+    }
+    let content: String = entry.content.chars().take(max_chars).collect();
+    Some(secrets::redact(&content))
+}
+
+async fn request_suggestion(
+    client: &reqwest::Client,
+    config: &EnrichmentConfig,
+    input: &str,
+) -> Result<Suggestion> {
+    let url = format!(
+        "{}/chat/completions",
+        config.endpoint.trim().trim_end_matches('/')
+    );
+    let body = json!({
+        "model": config.model,
+        "messages": [
+            { "role": "system", "content": SYSTEM_PROMPT },
+            { "role": "user", "content": input },
+        ],
+        "temperature": 0.2,
+        "response_format": { "type": "json_object" },
+    });
+
+    let mut request = client
+        .post(&url)
+        .timeout(config.timeout())
+        .header("content-type", "application/json")
+        .body(body.to_string());
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key.expose());
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ServerError::Internal(format!("Enrichment request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(ServerError::Internal(format!(
+            "Enrichment API returned {}: {}",
+            status.as_u16(),
+            text.trim()
+        )));
+    }
+    parse_completion(&text)
+}
+
+/// The suggestion in a chat completion response body
+fn parse_completion(body: &str) -> Result<Suggestion> {
+    let response: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| ServerError::Internal(format!("Invalid enrichment response: {}", e)))?;
+    let content = response["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| ServerError::Internal("Enrichment response has no message".to_string()))?;
+
+    // Some models wrap the object in prose or a code block
+    let object = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => content,
+    };
+    serde_json::from_str(object)
+        .map_err(|e| ServerError::Internal(format!("Invalid enrichment answer: {}", e)))
+}
+
+/// The first line of `summary` with its spaces collapsed, shortened to
+/// `MAX_SUMMARY_CHARS`
+fn normalize_summary(summary: &str) -> Option<String> {
+    let line = summary
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    let mut summary = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.chars().count() > MAX_SUMMARY_CHARS {
+        summary = summary.chars().take(MAX_SUMMARY_CHARS - 1).collect();
+        summary.push('…');
+    }
+    Some(summary)
+}
+
+/// Suggested tags in lowercase with dashes for spaces, leaving out system
+/// (`$`) tags, tags the clip already has, repeats and overlong tags
+fn normalize_tags(tags: &[String], existing: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag
+            .trim()
+            .trim_start_matches('#')
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase();
+        if tag.is_empty()
+            || tag.starts_with('$')
+            || tag.chars().count() > MAX_TAG_CHARS
+            || existing
+                .iter()
+                .any(|existing| existing.to_lowercase() == tag)
+            || normalized.contains(&tag)
+        {
+            continue;
+        }
+        normalized.push(tag);
+        if normalized.len() == MAX_SUGGESTED_TAGS {
+            break;
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(start);
+        assert!(limiter.try_acquire(2, start));
+        assert!(limiter.try_acquire(2, start + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire(2, start + Duration::from_secs(2)));
+        // A new minute starts over
+        assert!(limiter.try_acquire(2, start + Duration::from_secs(61)));
+        // No limit
+        assert!(limiter.try_acquire(0, start + Duration::from_secs(62)));
+        assert!(limiter.try_acquire(0, start + Duration::from_secs(62)));
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let body = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": "```json\n{\"summary\": \"A Rust hello world\", \"tags\": [\"rust\"]}\n```"
+                }
+            }]
+        })
+        .to_string();
+        let suggestion = parse_completion(&body).unwrap();
+        assert_eq!(suggestion.summary.as_deref(), Some("A Rust hello world"));
+        assert_eq!(suggestion.tags, vec!["rust"]);
+
+        let no_tags = json!({ "choices": [{ "message": { "content": "{\"summary\": \"x\"}" } }] });
+        assert!(parse_completion(&no_tags.to_string())
+            .unwrap()
+            .tags
+            .is_empty());
+        assert!(parse_completion(r#"{"choices": []}"#).is_err());
+        assert!(parse_completion("not json").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize_summary("\n  A   shopping list\nfor Sunday").as_deref(),
+            Some("A shopping list")
+        );
+        assert_eq!(normalize_summary(" \n "), None);
+        let long = normalize_summary(&"word ".repeat(100)).unwrap();
+        assert_eq!(long.chars().count(), MAX_SUMMARY_CHARS);
+        assert!(long.ends_with('…'));
+
+        let tags: Vec<String> = ["#Rust", "rust", "Web Dev", "$sensitive", "code", ""]
+            .iter()
+            .map(|tag| tag.to_string())
+            .chain(std::iter::once("x".repeat(MAX_TAG_CHARS + 1)))
+            .collect();
+        assert_eq!(
+            normalize_tags(&tags, &["Code".to_string()]),
+            vec!["rust", "web-dev"]
+        );
+    }
+
+    #[test]
+    fn test_enrichment_input() {
+        let entry = ClipboardEntry::new(
+            "host: db.example\nDB_PASSWORD=correct-horse-battery".to_string(),
+            vec![],
+        );
+        assert_eq!(
+            enrichment_input(&entry, 100).as_deref(),
+            Some("host: db.example\n[REDACTED]")
+        );
+        assert_eq!(enrichment_input(&entry, 4).as_deref(), Some("host"));
+
+        let sensitive = ClipboardEntry::new("hunter2".to_string(), vec![SENSITIVE_TAG.to_string()]);
+        assert_eq!(enrichment_input(&sensitive, 100), None);
+        let blank = ClipboardEntry::new("  \n".to_string(), vec![]);
+        assert_eq!(enrichment_input(&blank, 100), None);
+    }
+}
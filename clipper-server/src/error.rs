@@ -23,14 +23,63 @@ pub enum ServerError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Payload too large: {0}")]
     PayloadTooLarge(String),
 
+    /// The request body is over the route's size limit. The response also
+    /// carries the limit, so clients can tell the user what fits.
+    #[error("Request too large: {message}")]
+    RequestTooLarge {
+        message: String,
+        max_size_bytes: u64,
+    },
+
     #[error("Feature disabled: {0}")]
     FeatureDisabled(String),
 
     #[error("Short URL expired: {0}")]
     ShortUrlExpired(String),
+
+    /// The malware scan flagged an upload
+    #[error("Upload rejected: {0}")]
+    UploadRejected(String),
+
+    /// An upload couldn't be scanned for malware
+    #[error("Scan failed: {0}")]
+    ScanFailed(String),
+
+    /// The translation provider failed or couldn't be reached
+    #[error("Translation failed: {0}")]
+    TranslationFailed(String),
+
+    /// A data directory can't be created or written to
+    #[error("{0}")]
+    DataDirectory(String),
+}
+
+impl ServerError {
+    /// A `RequestTooLarge` error with the default message
+    pub fn request_too_large(max_size_bytes: u64) -> Self {
+        ServerError::RequestTooLarge {
+            message: format!(
+                "Request body exceeds the maximum allowed size ({})",
+                format_size(max_size_bytes)
+            ),
+            max_size_bytes,
+        }
+    }
+}
+
+/// Format a size limit for error messages, e.g. "10.00 MB" or "64 KB"
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
 }
 
 impl IntoResponse for ServerError {
@@ -43,23 +92,51 @@ impl IntoResponse for ServerError {
                 clipper_indexer::IndexerError::ShortUrlExpired(_) => {
                     (StatusCode::GONE, e.to_string())
                 }
+                clipper_indexer::IndexerError::InvalidInput(_) => {
+                    (StatusCode::BAD_REQUEST, e.to_string())
+                }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             },
             ServerError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg),
             ServerError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             ServerError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             ServerError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ServerError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             ServerError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg),
+            ServerError::RequestTooLarge {
+                message,
+                max_size_bytes,
+            } => {
+                let body = json!({
+                    "error": message,
+                    "max_size_bytes": max_size_bytes,
+                });
+                return (StatusCode::PAYLOAD_TOO_LARGE, Json(with_request_id(body)))
+                    .into_response();
+            }
             ServerError::FeatureDisabled(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
             ServerError::ShortUrlExpired(msg) => (StatusCode::GONE, msg),
+            ServerError::UploadRejected(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+            ServerError::ScanFailed(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            ServerError::TranslationFailed(msg) => (StatusCode::BAD_GATEWAY, msg),
+            ServerError::DataDirectory(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
         };
 
-        let body = Json(json!({
+        let body = json!({
             "error": error_message,
-        }));
+        });
+
+        (status, Json(with_request_id(body))).into_response()
+    }
+}
 
-        (status, body).into_response()
+/// Add the ID of the request being handled, so a user reporting an error
+/// can point at the matching log lines
+fn with_request_id(mut body: serde_json::Value) -> serde_json::Value {
+    if let Some(request_id) = crate::request_id::current() {
+        body["request_id"] = request_id.into();
     }
+    body
 }
 
 pub type Result<T> = std::result::Result<T, ServerError>;
@@ -0,0 +1,269 @@
+//! Health check endpoints for load balancers, Kubernetes probes and uptime
+//! monitors. None of them require authentication.
+//!
+//! - `GET /health`: plain `OK`, kept for existing monitors
+//! - `GET /healthz`: liveness; the server is running and handling requests
+//! - `GET /readyz`: readiness; the database answers queries, attachments can
+//!   be stored, and the TLS certificate (when serving HTTPS) isn't about to
+//!   expire. Answers 503 if any check fails.
+//!
+//! `clipper-server --print-healthcheck` asks `/readyz` of a running server,
+//! for container images without curl.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use serde::Serialize;
+
+use crate::config::ServerConfig;
+use crate::state::AppState;
+
+/// A readiness check that takes longer than this fails
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long `--print-healthcheck` waits for the server to answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `/readyz` fails once the TLS certificate expires within this many days.
+/// ACME and certbot renew 30 days ahead, so this means renewal is failing.
+#[cfg(feature = "tls")]
+const TLS_EXPIRY_WINDOW_DAYS: i64 = 7;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+}
+
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct LivenessResponse {
+    status: Status,
+    version: &'static str,
+    uptime_secs: u64,
+}
+
+async fn liveness(State(state): State<AppState>) -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: Status::Ok,
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_secs: state.uptime_secs(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct CheckResult {
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// When the TLS certificate expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl CheckResult {
+    fn new(error: Option<String>) -> Self {
+        Self {
+            status: if error.is_some() {
+                Status::Fail
+            } else {
+                Status::Ok
+            },
+            duration_ms: None,
+            error,
+            expires_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ReadinessResponse {
+    status: Status,
+    checks: BTreeMap<&'static str, CheckResult>,
+}
+
+async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let (database, storage) = tokio::join!(
+        timed_check(async { state.indexer.get_index_version().await.map(|_| ()) }),
+        timed_check(state.indexer.check_storage()),
+    );
+
+    let mut checks = BTreeMap::new();
+    checks.insert("database", database);
+    checks.insert("storage", storage);
+    #[cfg(feature = "tls")]
+    if let Some(not_after) = state.tls_expiry.not_after() {
+        checks.insert("tls", tls_check(not_after, chrono::Utc::now()));
+    }
+
+    for (name, check) in &checks {
+        if let Some(error) = &check.error {
+            tracing::warn!("Readiness check {} failed: {}", name, error);
+        }
+    }
+
+    let ready = checks.values().all(|check| check.status == Status::Ok);
+    let (status_code, status) = if ready {
+        (StatusCode::OK, Status::Ok)
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Status::Fail)
+    };
+    (status_code, Json(ReadinessResponse { status, checks }))
+}
+
+/// Run a check, failing it if it takes longer than [`CHECK_TIMEOUT`]
+async fn timed_check<E: std::fmt::Display>(
+    check: impl Future<Output = Result<(), E>>,
+) -> CheckResult {
+    let start = Instant::now();
+    let error = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!(
+            "Timed out after {} seconds",
+            CHECK_TIMEOUT.as_secs()
+        )),
+    };
+    CheckResult {
+        duration_ms: Some(start.elapsed().as_millis() as u64),
+        ..CheckResult::new(error)
+    }
+}
+
+/// Ask the server running with `config` whether it's ready. Returns the
+/// `/readyz` response, as the error if the server isn't ready.
+pub async fn probe(config: &ServerConfig) -> Result<String, String> {
+    let url = probe_url(config)?;
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        // The certificate names the server's public address, not loopback
+        .danger_accept_invalid_certs(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("{}: {}", url, e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("{}: {}", url, e))?;
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(format!("{} answered {}: {}", url, status, body))
+    }
+}
+
+/// Where the server running with `config` answers readiness checks, over
+/// loopback if it listens on all addresses
+fn probe_url(config: &ServerConfig) -> Result<String, String> {
+    if config.server.named_pipe.is_some() {
+        return Err("The server listens on a named pipe, not TCP".to_string());
+    }
+
+    let (scheme, port) = if config.tls.enabled {
+        ("https", config.tls.port)
+    } else {
+        ("http", config.server.port)
+    };
+    let listen_addr = &config.server.listen_addr;
+    let host = match listen_addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) if ip.is_unspecified() => "127.0.0.1".to_string(),
+        Ok(IpAddr::V6(ip)) if ip.is_unspecified() => "[::1]".to_string(),
+        Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+        Ok(IpAddr::V4(ip)) => ip.to_string(),
+        // A host name
+        Err(_) => listen_addr.clone(),
+    };
+    Ok(format!(
+        "{}://{}:{}{}/readyz",
+        scheme, host, port, config.server.base_path
+    ))
+}
+
+#[cfg(feature = "tls")]
+fn tls_check(
+    not_after: chrono::DateTime<chrono::Utc>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> CheckResult {
+    let days_remaining = (not_after - now).num_days();
+    let error = if not_after <= now {
+        Some("Certificate has expired".to_string())
+    } else if days_remaining < TLS_EXPIRY_WINDOW_DAYS {
+        Some(format!("Certificate expires in {} days", days_remaining))
+    } else {
+        None
+    };
+    CheckResult {
+        expires_at: Some(not_after),
+        ..CheckResult::new(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_url() {
+        let mut config = ServerConfig::default();
+        assert_eq!(probe_url(&config).unwrap(), "http://127.0.0.1:3000/readyz");
+
+        config.server.listen_addr = "::".to_string();
+        config.server.base_path = "/clipper".to_string();
+        assert_eq!(
+            probe_url(&config).unwrap(),
+            "http://[::1]:3000/clipper/readyz"
+        );
+
+        config.server.listen_addr = "192.168.1.10".to_string();
+        config.tls.enabled = true;
+        assert_eq!(
+            probe_url(&config).unwrap(),
+            "https://192.168.1.10:443/clipper/readyz"
+        );
+
+        config.server.named_pipe = Some(r"\\.\pipe\clipper".to_string());
+        assert!(probe_url(&config).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "tls")]
+    fn test_tls_check() {
+        let now = chrono::Utc::now();
+
+        let check = tls_check(now + chrono::Duration::days(60), now);
+        assert_eq!(check.status, Status::Ok);
+        assert!(check.error.is_none());
+
+        let check = tls_check(now + chrono::Duration::days(3), now);
+        assert_eq!(check.status, Status::Fail);
+        assert_eq!(
+            check.error.as_deref(),
+            Some("Certificate expires in 3 days")
+        );
+
+        let check = tls_check(now - chrono::Duration::days(1), now);
+        assert_eq!(check.error.as_deref(), Some("Certificate has expired"));
+    }
+}
@@ -1,10 +1,31 @@
+pub mod admin;
 pub mod api;
 pub mod auth;
+pub mod canonical_url;
 pub mod cleanup;
 pub mod config;
+pub mod enrichment;
 pub mod error;
+pub mod health;
+pub mod logging;
+pub mod mobile;
+#[cfg(windows)]
+pub mod named_pipe;
+pub mod pairing;
 pub mod parent_monitor;
+pub mod plugins;
+pub mod remote_clipboard;
+pub mod request_id;
+pub mod scan;
+pub mod secrets;
+pub mod serve;
+pub mod service;
+pub mod socket_activation;
+pub mod split;
 pub mod state;
+pub mod transform;
+pub mod translation;
+pub mod web_push;
 pub mod websocket;
 
 // TLS and ACME modules (feature-gated)
@@ -16,14 +37,29 @@ pub mod acme;
 
 pub mod cert_storage;
 
-pub use auth::auth_middleware;
-pub use cleanup::{run_clip_cleanup_task, run_short_url_cleanup_task};
-pub use config::{AuthConfig, CleanupConfig, Cli, ServerConfig};
+#[cfg(feature = "mdns")]
+pub mod discovery;
+
+#[cfg(feature = "push-relay")]
+pub mod push_relay;
+
+pub use auth::{auth_middleware, Credential};
+pub use cleanup::{
+    run_clip_cleanup_task, run_sensitive_cleanup_task, run_short_url_cleanup_task,
+};
+pub use config::{
+    AuthConfig, CanonicalUrlConfig, CleanupConfig, CleanupMode, Cli, Command, ConfigChanges, ConfigFormat,
+    ConfigReloader, DiscoveryConfig, EnrichmentConfig, LogConfig, LogFormat, LogRotation, PushRelayConfig,
+    Retention, ScanAction, ScanConfig, SensitiveConfig, ServerConfig, SharedConfig,
+    TagRetentionRule, TranslationConfig, TranslationProvider, WebPushConfig,
+};
 pub use error::{Result, ServerError};
+pub use plugins::PluginManager;
+pub use remote_clipboard::{ClipboardPush, ConnectedDevices};
 pub use state::{AppState, ClipUpdate};
 
 #[cfg(feature = "tls")]
-pub use tls::{TlsManager, TlsState};
+pub use tls::{CertificateExpiry, CertificateReloader, TlsManager, TlsState};
 
 #[cfg(feature = "acme")]
 pub use acme::AcmeManager;
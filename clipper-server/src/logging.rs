@@ -0,0 +1,628 @@
+//! Log output: stdout, an optional rotating log file, access logs and, with
+//! the `otel` feature, OpenTelemetry traces exported over OTLP.
+//!
+//! Lines are text, or JSON with `log.format = "json"`. JSON lines carry the
+//! fields of the spans they're in, e.g. the `request_id` and `clip_id` of
+//! the request being handled.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use axum::{
+    body::HttpBody,
+    extract::{ConnectInfo, Request},
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, FixedOffset, Local};
+use flate2::{Compression, write::GzEncoder};
+use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
+use tracing::Subscriber;
+use tracing_subscriber::{
+    EnvFilter, Layer, fmt, fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan,
+    util::SubscriberInitExt,
+};
+
+use crate::config::{LogConfig, LogFormat, LogRotation};
+
+/// Target of access log events, for filtering them with `RUST_LOG`
+pub const ACCESS_LOG_TARGET: &str = "clipper_server::access";
+
+/// Filter used when `RUST_LOG` isn't set
+const DEFAULT_FILTER: &str = "clipper_server=debug,tower_http=debug";
+
+/// Flushes the log file and pending traces when dropped
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+    #[cfg(feature = "otel")]
+    tracer_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+#[cfg(feature = "otel")]
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Set up logging to stdout and, if configured, to a log file and an OTLP
+/// collector.
+///
+/// Keep the returned guard alive until the server exits.
+pub fn init(config: &LogConfig, use_color: bool) -> io::Result<LogGuard> {
+    let (file_layer, file_guard) = match &config.file {
+        Some(path) => {
+            let writer = RotatingFileWriter::open(path, config)?;
+            // Block rather than drop lines when the file can't keep up
+            let (writer, guard) = NonBlockingBuilder::default().lossy(false).finish(writer);
+            (Some(fmt_layer(config.format, writer, false)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    #[cfg(feature = "otel")]
+    let tracer_provider = config
+        .otlp_endpoint
+        .as_deref()
+        .map(otel::tracer_provider)
+        .transpose()?;
+    #[cfg(feature = "otel")]
+    let otel_layer = tracer_provider.as_ref().map(otel::layer);
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_FILTER.into()))
+        .with(fmt_layer(config.format, io::stdout, use_color))
+        .with(file_layer)
+        .with(otel_layer)
+        .init();
+
+    Ok(LogGuard {
+        _file: file_guard,
+        #[cfg(feature = "otel")]
+        tracer_provider,
+    })
+}
+
+/// A layer writing lines in `format` to `writer`
+fn fmt_layer<S, W>(format: LogFormat, writer: W, use_color: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::layer()
+            .with_ansi(use_color)
+            .with_writer(writer)
+            .boxed(),
+        // Event fields at the top level, next to `span`/`spans` with the
+        // fields of the spans the event is in
+        LogFormat::Json => fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::io;
+
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{
+        Resource, propagation::TraceContextPropagator, trace::SdkTracerProvider,
+    };
+    use tracing_subscriber::registry::LookupSpan;
+
+    const SERVICE_NAME: &str = "clipper-server";
+
+    /// Export spans to the OTLP/HTTP collector at `endpoint`, and accept
+    /// W3C trace context from clients
+    pub fn tracer_provider(endpoint: &str) -> io::Result<SdkTracerProvider> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(traces_endpoint(endpoint))
+            .build()
+            .map_err(io::Error::other)?;
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        Ok(SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build())
+    }
+
+    pub fn layer<S>(
+        provider: &SdkTracerProvider,
+    ) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME))
+    }
+
+    /// The traces URL of a collector, which is what the exporter wants
+    /// when given an endpoint explicitly
+    pub fn traces_endpoint(endpoint: &str) -> String {
+        let endpoint = endpoint.trim_end_matches('/');
+        if endpoint.ends_with("/v1/traces") {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/traces", endpoint)
+        }
+    }
+}
+
+/// A log file that's rotated by time and size.
+///
+/// The current file is renamed to `<name>.<timestamp>` when it's rotated, and
+/// gzipped to `<name>.<timestamp>.gz` in the background if compression is
+/// on. Only the newest `max_files` rotated files are kept.
+pub struct RotatingFileWriter {
+    path: PathBuf,
+    rotation: LogRotation,
+    max_size: Option<u64>,
+    max_files: usize,
+    compress: bool,
+    file: File,
+    size: u64,
+    period: Option<String>,
+}
+
+impl RotatingFileWriter {
+    /// Open `path` for appending, creating it and its directory if needed
+    pub fn open(path: &Path, config: &LogConfig) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = open_log_file(path)?;
+        let metadata = file.metadata()?;
+        // A file left over from yesterday is rotated on the first write
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Local>::from)
+            .unwrap_or_else(|_| Local::now());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            rotation: config.rotation,
+            max_size: config.max_size_bytes(),
+            max_files: config.max_files,
+            compress: config.compress,
+            file,
+            size: metadata.len(),
+            period: period(config.rotation, modified),
+        })
+    }
+
+    fn rotate(&mut self, now: DateTime<Local>) -> io::Result<()> {
+        self.file.flush()?;
+        let rotated = self.rotated_path(now);
+        fs::rename(&self.path, &rotated)?;
+        self.file = open_log_file(&self.path)?;
+        self.size = 0;
+
+        let path = self.path.clone();
+        let max_files = self.max_files;
+        if self.compress {
+            std::thread::spawn(move || {
+                if let Err(e) = compress_file(&rotated) {
+                    eprintln!("Failed to compress log file {}: {}", rotated.display(), e);
+                }
+                remove_old_files(&path, max_files);
+            });
+        } else {
+            remove_old_files(&path, max_files);
+        }
+        Ok(())
+    }
+
+    /// `<name>.<timestamp>`, with a counter if the file was already rotated
+    /// within the same second
+    fn rotated_path(&self, now: DateTime<Local>) -> PathBuf {
+        let mut base = self.path.as_os_str().to_owned();
+        base.push(now.format(".%Y%m%d-%H%M%S").to_string());
+        let mut candidate = PathBuf::from(&base);
+        let mut counter = 1;
+        while candidate.exists() || gz_path(&candidate).exists() {
+            let mut numbered = base.clone();
+            numbered.push(format!("-{}", counter));
+            candidate = PathBuf::from(numbered);
+            counter += 1;
+        }
+        candidate
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Local::now();
+        let period = period(self.rotation, now);
+        let expired = period != self.period;
+        let full = self
+            .max_size
+            .is_some_and(|max_size| self.size + buf.len() as u64 > max_size);
+        if self.size > 0
+            && (expired || full)
+            && let Err(e) = self.rotate(now)
+        {
+            // Keep writing to the current file rather than lose lines, and
+            // don't retry until the next period or size limit
+            eprintln!("Failed to rotate log file {}: {}", self.path.display(), e);
+            self.size = 0;
+        }
+        self.period = period;
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_log_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Identifies the time period a line written at `time` belongs to; a new
+/// file is started when it changes
+fn period(rotation: LogRotation, time: DateTime<Local>) -> Option<String> {
+    match rotation {
+        LogRotation::Daily => Some(time.format("%Y-%m-%d").to_string()),
+        LogRotation::Hourly => Some(time.format("%Y-%m-%d %H").to_string()),
+        LogRotation::Never => None,
+    }
+}
+
+/// `path` with `.gz` appended
+fn gz_path(path: &Path) -> PathBuf {
+    let mut gz_path = path.as_os_str().to_owned();
+    gz_path.push(".gz");
+    PathBuf::from(gz_path)
+}
+
+/// Replace `path` with a gzipped `<path>.gz`
+fn compress_file(path: &Path) -> io::Result<()> {
+    let compressed_path = gz_path(path);
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&compressed_path)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)
+}
+
+/// Delete all but the newest `max_files` rotated files of `path` (0 = keep
+/// all)
+fn remove_old_files(path: &Path, max_files: usize) {
+    if max_files == 0 {
+        return;
+    }
+    let (Some(directory), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let directory = if directory.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        directory
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+    // Timestamps sort in the order the files were rotated
+    let mut rotated: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path())
+        .collect();
+    rotated.sort();
+
+    let excess = rotated.len().saturating_sub(max_files);
+    for old in &rotated[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            eprintln!("Failed to remove old log file {}: {}", old.display(), e);
+        }
+    }
+}
+
+/// Middleware logging each request in Apache combined log format.
+///
+/// The client address is only known when the server was started with
+/// `ConnectInfo`, and is `-` otherwise (e.g. on a named pipe).
+pub async fn access_log(request: Request, next: Next) -> Response {
+    let remote = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let request_line = format!(
+        "{} {} {:?}",
+        request.method(),
+        request
+            .uri()
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or("/"),
+        request.version()
+    );
+    let referer = header_value(request.headers(), header::REFERER);
+    let user_agent = header_value(request.headers(), header::USER_AGENT);
+    let time = Local::now().fixed_offset();
+
+    let response = next.run(request).await;
+
+    // Content-Length is usually only added when the response is sent
+    let size = header_value(response.headers(), header::CONTENT_LENGTH).or_else(|| {
+        response
+            .body()
+            .size_hint()
+            .exact()
+            .map(|size| size.to_string())
+    });
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        "{}",
+        combined_log_line(
+            remote.as_deref(),
+            time,
+            &request_line,
+            response.status().as_u16(),
+            size.as_deref(),
+            referer.as_deref(),
+            user_agent.as_deref(),
+        )
+    );
+    response
+}
+
+fn header_value(headers: &HeaderMap, name: header::HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+}
+
+/// `host - - [time] "request" status size "referer" "user-agent"`, with
+/// missing values as `-`
+fn combined_log_line(
+    remote: Option<&str>,
+    time: DateTime<FixedOffset>,
+    request_line: &str,
+    status: u16,
+    size: Option<&str>,
+    referer: Option<&str>,
+    user_agent: Option<&str>,
+) -> String {
+    format!(
+        "{} - - [{}] \"{}\" {} {} \"{}\" \"{}\"",
+        remote.unwrap_or("-"),
+        time.format("%d/%b/%Y:%H:%M:%S %z"),
+        escape(request_line),
+        status,
+        size.unwrap_or("-"),
+        escape(referer.unwrap_or("-")),
+        escape(user_agent.unwrap_or("-")),
+    )
+}
+
+/// Escape quotes, backslashes and control characters like Apache does, so a
+/// request can't forge log lines
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    escaped.push_str(&format!("\\x{:02x}", byte));
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("clipper_server_test_logging_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| name != "server.log")
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotate_on_size() {
+        let dir = test_dir("size");
+        let path = dir.join("server.log");
+        let config = LogConfig {
+            file: Some(path.clone()),
+            rotation: LogRotation::Never,
+            max_size_mb: 1,
+            max_files: 2,
+            ..Default::default()
+        };
+        let mut writer = RotatingFileWriter::open(&path, &config).unwrap();
+
+        let line = vec![b'x'; 400 * 1024];
+        for _ in 0..8 {
+            writer.write_all(&line).unwrap();
+        }
+        writer.flush().unwrap();
+
+        // 8 writes of 400 KiB fill 4 files; only the newest 2 rotated remain
+        assert_eq!(fs::metadata(&path).unwrap().len(), 800 * 1024);
+        let rotated = rotated_files(&dir);
+        assert_eq!(rotated.len(), 2);
+        for name in &rotated {
+            assert!(name.starts_with("server.log."));
+            assert_eq!(fs::metadata(dir.join(name)).unwrap().len(), 800 * 1024);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rotate_on_new_period() {
+        let dir = test_dir("period");
+        let path = dir.join("server.log");
+        let config = LogConfig {
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        let mut writer = RotatingFileWriter::open(&path, &config).unwrap();
+        writer.write_all(b"today\n").unwrap();
+        assert!(rotated_files(&dir).is_empty());
+
+        // As if the last line had been written yesterday
+        writer.period = Some("2000-01-01".to_string());
+        writer.write_all(b"tomorrow\n").unwrap();
+        let rotated = rotated_files(&dir);
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(fs::read(dir.join(&rotated[0])).unwrap(), b"today\n");
+        assert_eq!(fs::read(&path).unwrap(), b"tomorrow\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compress_file() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let dir = test_dir("compress");
+        let path = dir.join("server.log.20261016-000000");
+        fs::write(&path, "compressed\n").unwrap();
+
+        compress_file(&path).unwrap();
+        assert!(!path.exists());
+        let mut contents = String::new();
+        GzDecoder::new(File::open(dir.join("server.log.20261016-000000.gz")).unwrap())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "compressed\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(feature = "otel")]
+    fn test_traces_endpoint() {
+        assert_eq!(
+            otel::traces_endpoint("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            otel::traces_endpoint("http://localhost:4318/v1/traces/"),
+            "http://localhost:4318/v1/traces"
+        );
+    }
+
+    /// Log lines written to memory
+    #[derive(Clone, Default)]
+    struct Lines(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Lines {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Lines {
+        type Writer = Lines;
+
+        fn make_writer(&'a self) -> Lines {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_format() {
+        use axum::{Router, body::Body, middleware, routing::get};
+        use tower::ServiceExt;
+
+        let lines = Lines::default();
+        let subscriber =
+            tracing_subscriber::registry().with(fmt_layer(LogFormat::Json, lines.clone(), false));
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        // A request through the request ID middleware to a clip handler
+        let app = Router::new()
+            .route(
+                "/clips/abc123",
+                get(|| async {
+                    crate::request_id::record_clip_id("abc123");
+                    tracing::info!(size = 42, "Clip served");
+                }),
+            )
+            .layer(middleware::from_fn(crate::request_id::propagate_request_id));
+        let request = axum::http::Request::builder()
+            .uri("/clips/abc123")
+            .header(crate::request_id::REQUEST_ID_HEADER, "req-1")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+
+        let output = String::from_utf8(lines.0.lock().unwrap().clone()).unwrap();
+        let line = output.lines().next().expect("a log line");
+        let json: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(json["level"], "INFO");
+        assert_eq!(json["message"], "Clip served");
+        assert_eq!(json["size"], 42);
+        assert_eq!(json["span"]["name"], "http");
+        assert_eq!(json["span"]["request_id"], "req-1");
+        assert_eq!(json["span"]["clip_id"], "abc123");
+    }
+
+    #[test]
+    fn test_combined_log_line() {
+        let time = DateTime::parse_from_rfc3339("2026-10-16T13:55:36-07:00").unwrap();
+        assert_eq!(
+            combined_log_line(
+                Some("127.0.0.1"),
+                time,
+                "GET /clips?q=a HTTP/1.1",
+                200,
+                Some("2326"),
+                Some("http://example.com/"),
+                Some("curl/8.0 \"quoted\"\n"),
+            ),
+            "127.0.0.1 - - [16/Oct/2026:13:55:36 -0700] \"GET /clips?q=a HTTP/1.1\" 200 2326 \
+             \"http://example.com/\" \"curl/8.0 \\\"quoted\\\"\\x0a\""
+        );
+        assert_eq!(
+            combined_log_line(None, time, "POST /clips HTTP/1.1", 201, None, None, None),
+            "- - - [16/Oct/2026:13:55:36 -0700] \"POST /clips HTTP/1.1\" 201 - \"-\" \"-\""
+        );
+    }
+}
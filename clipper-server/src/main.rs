@@ -3,31 +3,35 @@ use std::io::IsTerminal;
 use axum::{
     Router,
     body::Body,
-    http::{StatusCode, Uri, header},
-    middleware,
+    extract::Request,
+    http::{HeaderValue, StatusCode, Uri, header},
+    middleware::{self, Next},
     response::Response,
-    routing::get,
 };
 use clap::Parser;
-use clipper_indexer::ClipperIndexer;
 use clipper_server::{
-    AppState, Cli, ServerConfig, api, auth_middleware, run_clip_cleanup_task,
-    run_short_url_cleanup_task, websocket,
+    Cli, Command, ConfigFormat, ConfigReloader, ServerConfig, logging, request_id, serve, service,
 };
+use tower::ServiceBuilder;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[cfg(feature = "embed-web")]
+use axum::{http::HeaderMap, routing::any};
 
 #[cfg(not(feature = "embed-web"))]
-use {axum::http::Request, std::convert::Infallible, tower_http::services::ServeDir};
+use {std::convert::Infallible, tower_http::services::ServeDir};
 
 #[cfg(feature = "tls")]
-use clipper_server::TlsManager;
+use {
+    clipper_server::{CertificateExpiry, CertificateReloader, TlsManager},
+    std::sync::{Arc, OnceLock},
+};
 
 #[cfg(feature = "acme")]
 use {
+    axum::routing::get,
     clipper_server::acme::{AcmeManager, challenge_handler::AcmeChallengeState},
     clipper_server::cert_storage::create_storage,
-    std::sync::Arc,
 };
 
 // Embedded web UI files (only when embed-web feature is enabled)
@@ -46,52 +50,102 @@ async fn main() {
             .install_default()
             .expect("Failed to install rustls crypto provider");
     }
-    let use_color = std::io::stdout().is_terminal();
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "clipper_server=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer().with_ansi(use_color))
-        .init();
-
     // Set restrictive permissions for newly created files and directories.
     // On Unix: Sets umask to 0o077 (files 0600, directories 0700)
     // On Windows: This is a no-op; directories are secured after creation with ACLs
+    // Done first so the log file is created with these permissions too.
     clipper_security::set_restrictive_umask();
-    tracing::debug!("Set restrictive file permissions");
 
-    // Parse command line arguments
+    // Parse command line arguments. They're kept for reloading the
+    // configuration later.
     let cli = Cli::parse();
-
-    // Start parent process monitor if running in bundled mode
-    // This must be done early before the cli is consumed
-    let parent_shutdown_rx = if let Some(handle) = cli.parent_pipe_handle {
-        let rx = clipper_server::parent_monitor::init_shutdown_channel();
-        clipper_server::parent_monitor::start_parent_monitor(handle);
-        Some(rx)
-    } else {
-        None
-    };
+    let parent_pipe_handle = cli.parent_pipe_handle;
+    let config_path = cli.config.clone();
+
+    // Setup commands, which don't run the server
+    match &cli.command {
+        Some(Command::Init(args)) => finish_setup(service::init(args, config_path.as_deref())),
+        Some(Command::InstallService(args)) => {
+            finish_setup(service::install(args, config_path.as_deref()))
+        }
+        Some(Command::UninstallService(args)) => finish_setup(service::uninstall(args)),
+        _ => {}
+    }
 
     // Load configuration from all sources
-    let config = ServerConfig::load(cli).unwrap_or_else(|err| {
+    let config = ServerConfig::load(cli.clone()).unwrap_or_else(|err| {
         eprintln!("Failed to load configuration: {}", err);
         std::process::exit(1);
     });
 
+    if let Some(Command::PrintConfig {
+        format,
+        show_secrets,
+    }) = cli.command
+    {
+        print_config(&config, format, show_secrets);
+    }
+
+    if cli.print_healthcheck {
+        print_healthcheck(&config).await;
+    }
+
     // Validate configuration
     if let Err(err) = config.validate() {
         eprintln!("Configuration error: {}", err);
         std::process::exit(1);
     }
 
+    // Initialize tracing, which needs the log file and OTLP settings from the
+    // config. The guard flushes the log file and pending traces on exit.
+    let use_color = std::io::stdout().is_terminal();
+    let _log_guard = logging::init(&config.log, use_color).unwrap_or_else(|err| {
+        eprintln!("Failed to set up logging: {}", err);
+        std::process::exit(1);
+    });
+    tracing::debug!("Set restrictive file permissions");
+
+    // Start parent process monitor if running in bundled mode
+    let parent_shutdown_rx = if let Some(handle) = parent_pipe_handle {
+        let rx = clipper_server::parent_monitor::init_shutdown_channel();
+        clipper_server::parent_monitor::start_parent_monitor(handle);
+        Some(rx)
+    } else {
+        None
+    };
+
+    // Stopping the Windows service shuts the server down the same way the
+    // parent process exiting does
+    #[cfg(windows)]
+    let parent_shutdown_rx = if matches!(cli.command, Some(Command::RunService)) {
+        match service::windows::start() {
+            Ok(rx) => Some(rx),
+            Err(err) => {
+                tracing::error!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        parent_shutdown_rx
+    };
+
+    if let Some(config_path) = &config_path {
+        tracing::info!("Loaded config from file: {}", config_path.display());
+    }
     tracing::info!("Configuration loaded:");
     tracing::info!("  Database path: {}", config.database.path);
     tracing::info!("  Storage path: {}", config.storage.path);
     tracing::info!("  Listen address: {}", config.server.listen_addr);
     tracing::info!("  HTTP Port: {}", config.server.port);
+    if let Some(named_pipe) = &config.server.named_pipe {
+        tracing::info!("  Named pipe: {}", named_pipe);
+    }
+    if let Some(log_file) = &config.log.file {
+        tracing::info!("  Log file: {}", log_file.display());
+    }
+    if let Some(otlp_endpoint) = &config.log.otlp_endpoint {
+        tracing::info!("  OTLP endpoint: {}", otlp_endpoint);
+    }
     #[cfg(feature = "tls")]
     if config.tls.enabled {
         tracing::info!("  HTTPS Port: {}", config.tls.port);
@@ -99,10 +153,7 @@ async fn main() {
         #[cfg(feature = "acme")]
         if config.acme.enabled {
             tracing::info!("  ACME enabled: true");
-            tracing::info!(
-                "  ACME domain: {}",
-                config.acme.domain.as_deref().unwrap_or("not set")
-            );
+            tracing::info!("  ACME domains: {}", config.acme.domains().join(", "));
             tracing::info!(
                 "  ACME staging: {}",
                 if config.acme.staging { "yes" } else { "no" }
@@ -110,82 +161,38 @@ async fn main() {
         }
     }
 
-    // Initialize the indexer
-    let indexer = ClipperIndexer::new(&config.database.path, &config.storage.path)
-        .await
-        .expect("Failed to initialize indexer");
-
-    // Secure the data directories and fix any incorrect permissions
-    // On Unix: checks and fixes permissions to 0700/0600
-    // On Windows: sets DACL to grant access only to current user
-    let db_path = std::path::Path::new(&config.database.path);
-    let storage_path = std::path::Path::new(&config.storage.path);
-
-    match clipper_security::secure_directory_recursive(db_path, |msg| tracing::warn!("{}", msg)) {
-        Ok(count) if count > 0 => {
-            tracing::info!("Fixed permissions on {} items in database directory", count);
-        }
-        Err(e) => tracing::warn!("Failed to secure database directory: {}", e),
-        _ => {}
-    }
-
-    match clipper_security::secure_directory_recursive(storage_path, |msg| {
-        tracing::warn!("{}", msg)
-    }) {
-        Ok(count) if count > 0 => {
-            tracing::info!("Fixed permissions on {} items in storage directory", count);
-        }
-        Err(e) => tracing::warn!("Failed to secure storage directory: {}", e),
-        _ => {}
-    }
+    // Initialize the indexer and background tasks
+    let state = serve::create_state(&config).await.unwrap_or_else(|err| {
+        eprintln!("Failed to start: {}", err);
+        std::process::exit(1);
+    });
 
-    // Create application state
-    let state = AppState::new(indexer, config.clone());
+    // Apply configuration changes through `POST /admin/reload-config` or SIGHUP
+    let config_reloader = ConfigReloader::new(cli, state.config.clone());
+    let _ = state.config_reloader.set(config_reloader.clone());
+    #[cfg(unix)]
+    tokio::spawn(reload_config_on_sighup(config_reloader));
 
-    // Start clip cleanup task if enabled
-    if config.cleanup.is_active() {
-        tracing::info!(
-            "Auto-cleanup enabled: retention={} days, interval={} hours",
-            config.cleanup.retention_days,
-            config.cleanup.interval_hours
-        );
-        let cleanup_state = state.clone();
-        let cleanup_config = config.cleanup.clone();
-        tokio::spawn(async move {
-            run_clip_cleanup_task(cleanup_state, cleanup_config).await;
-        });
-    }
+    // Filled in once the HTTPS server has loaded a certificate from disk
+    #[cfg(feature = "tls")]
+    let tls_reloader = state.tls_reloader.clone();
+    #[cfg(feature = "tls")]
+    let tls_expiry = state.tls_expiry.clone();
 
-    // Start short URL cleanup task (always runs to clean expired short URLs)
-    {
-        let short_url_cleanup_state = state.clone();
-        tokio::spawn(async move {
-            run_short_url_cleanup_task(short_url_cleanup_state).await;
-        });
-    }
+    // Build the application with routes
+    let api_routes = serve::api_router(state);
 
-    // Log auth status
-    if config.auth.is_enabled() {
-        tracing::info!("Authentication enabled (Bearer token required)");
-    } else {
-        tracing::info!("Authentication disabled (open access)");
+    // Build the app with web UI serving, under the base path if one is set
+    let base_path = &config.server.base_path;
+    let app = serve::nest_under_base_path(build_app_with_web_ui(api_routes, base_path), base_path);
+    if !base_path.is_empty() {
+        tracing::info!("  Base path: {}", base_path);
     }
 
-    // Build the application with routes
-    #[allow(unused_mut)]
-    let mut api_routes = Router::new()
-        .route("/health", get(health_check))
-        .merge(api::routes(config.upload.max_size_bytes))
-        .merge(websocket::routes())
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            auth_middleware,
-        ))
-        .with_state(state);
-
-    // Add ACME challenge route if enabled
+    // Add ACME challenge route if enabled. Challenges are always served from
+    // the root, whatever the base path.
     #[cfg(feature = "acme")]
-    let acme_manager: Option<Arc<AcmeManager>> = if config.acme.enabled {
+    let (app, acme_manager): (Router, Option<Arc<AcmeManager>>) = if config.acme.enabled {
         let storage = create_storage(config.acme.get_certs_dir());
         let manager = Arc::new(AcmeManager::new(config.acme.clone(), storage));
 
@@ -193,19 +200,25 @@ async fn main() {
         let challenge_state = AcmeChallengeState {
             challenges: manager.pending_challenges(),
         };
-        api_routes = api_routes.route(
+        let app = app.route(
             "/.well-known/acme-challenge/{token}",
             get(clipper_server::acme::challenge_handler::handle_challenge)
                 .with_state(challenge_state),
         );
 
-        Some(manager)
+        (app, Some(manager))
     } else {
-        None
+        (app, None)
     };
 
-    // Build the app with web UI serving
-    let app = build_app_with_web_ui(api_routes);
+    // Log requests last so the ACME challenge route is covered too
+    let app = if config.log.access_log {
+        app.layer(middleware::from_fn(logging::access_log))
+    } else {
+        app
+    };
+    // Outermost, so every log line and error of a request carries its ID
+    let app = app.layer(middleware::from_fn(request_id::propagate_request_id));
 
     // Start the server(s)
     #[cfg(feature = "tls")]
@@ -223,6 +236,8 @@ async fn main() {
                     None::<()>
                 }
             },
+            tls_reloader,
+            tls_expiry,
             parent_shutdown_rx,
         )
         .await;
@@ -232,6 +247,9 @@ async fn main() {
 
     #[cfg(not(feature = "tls"))]
     start_http_only(config, app, parent_shutdown_rx).await;
+
+    #[cfg(windows)]
+    service::windows::stopped();
 }
 
 /// Start HTTP-only server (no TLS).
@@ -240,24 +258,98 @@ async fn start_http_only(
     app: Router,
     parent_shutdown_rx: Option<tokio::sync::broadcast::Receiver<()>>,
 ) {
-    let addr = config.socket_addr().unwrap_or_else(|err| {
-        eprintln!("Invalid listen address: {}", err);
-        std::process::exit(1);
-    });
+    // Local clients only, so there's nothing to advertise over mDNS
+    #[cfg(windows)]
+    if let Some(name) = &config.server.named_pipe {
+        let listener =
+            clipper_server::named_pipe::NamedPipeListener::bind(name).unwrap_or_else(|err| {
+                eprintln!("Failed to create named pipe {}: {}", name, err);
+                std::process::exit(1);
+            });
+
+        tracing::info!("HTTP server listening on {}", name);
+
+        let (stopping_tx, stopping_rx) = tokio::sync::watch::channel(false);
+        let server = axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown_started(parent_shutdown_rx, stopping_tx));
+        serve_with_shutdown_timeout(server, stopping_rx, config.server.shutdown_timeout()).await;
+        return;
+    }
 
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
+    // Either the socket systemd listens on, or one bound here
+    let passed_listener = clipper_server::socket_activation::take_listener()
+        .and_then(|listener| listener.map(tokio::net::TcpListener::from_std).transpose())
         .unwrap_or_else(|err| {
-            eprintln!("Failed to bind to {}: {}", addr, err);
+            eprintln!("Failed to use the socket passed by systemd: {}", err);
+            std::process::exit(1);
+        });
+    let listener = if let Some(listener) = passed_listener {
+        if let Ok(addr) = listener.local_addr() {
+            tracing::info!("HTTP server listening on {} (passed by systemd)", addr);
+        }
+        listener
+    } else {
+        let addr = config.socket_addr().unwrap_or_else(|err| {
+            eprintln!("Invalid listen address: {}", err);
             std::process::exit(1);
         });
 
-    tracing::info!("HTTP server listening on {}", addr);
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to bind to {}: {}", addr, err);
+                std::process::exit(1);
+            });
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(parent_shutdown_rx))
-        .await
-        .expect("Server failed");
+        tracing::info!("HTTP server listening on {}", addr);
+        listener
+    };
+
+    // Withdrawn from the network when the server stops
+    #[cfg(feature = "mdns")]
+    let _advertiser = clipper_server::discovery::advertise(&config, None);
+
+    // Client addresses are needed for the access log
+    let (stopping_tx, stopping_rx) = tokio::sync::watch::channel(false);
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_started(parent_shutdown_rx, stopping_tx));
+    serve_with_shutdown_timeout(server, stopping_rx, config.server.shutdown_timeout()).await;
+}
+
+/// Wait for a shutdown signal, then tell [`serve_with_shutdown_timeout`]
+async fn shutdown_started(
+    parent_shutdown_rx: Option<tokio::sync::broadcast::Receiver<()>>,
+    stopping_tx: tokio::sync::watch::Sender<bool>,
+) {
+    shutdown_signal(parent_shutdown_rx).await;
+    let _ = stopping_tx.send(true);
+}
+
+/// Run the server until it has shut down gracefully, but no longer than
+/// `timeout` after shutdown started. Open WebSockets would otherwise keep it
+/// running until the container runtime kills it.
+async fn serve_with_shutdown_timeout(
+    server: impl std::future::IntoFuture<Output = std::io::Result<()>>,
+    mut stopping_rx: tokio::sync::watch::Receiver<bool>,
+    timeout: std::time::Duration,
+) {
+    let deadline = async {
+        let _ = stopping_rx.wait_for(|stopping| *stopping).await;
+        tokio::time::sleep(timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => result.expect("Server failed"),
+        _ = deadline => {
+            tracing::info!(
+                "Closing connections still open after {} seconds",
+                timeout.as_secs()
+            );
+        }
+    }
 }
 
 /// Start server with TLS support.
@@ -266,6 +358,8 @@ async fn start_with_tls<T>(
     config: ServerConfig,
     app: Router,
     acme_manager: Option<T>,
+    tls_reloader: Arc<OnceLock<CertificateReloader>>,
+    tls_expiry: CertificateExpiry,
     parent_shutdown_rx: Option<tokio::sync::broadcast::Receiver<()>>,
 ) where
     T: std::any::Any + Send + Sync + 'static,
@@ -309,6 +403,7 @@ async fn start_with_tls<T>(
 
     // Get certificate and key
     let (cert_pem, key_pem) = get_certificate(&config, &acme_manager).await;
+    tls_expiry.update(&cert_pem);
 
     // Create TLS manager
     let tls_manager = TlsManager::from_pem(&cert_pem, &key_pem)
@@ -341,15 +436,18 @@ async fn start_with_tls<T>(
     {
         let acme_clone = acme.clone();
         let tls_config_clone = rustls_config.clone();
+        let tls_expiry = tls_expiry.clone();
         tokio::spawn(async move {
             clipper_server::acme::certificate_renewal_task(acme_clone, move |cert, key| {
                 let config = tls_config_clone.clone();
+                let tls_expiry = tls_expiry.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = config
+                    match config
                         .reload_from_pem(cert.as_bytes().to_vec(), key.as_bytes().to_vec())
                         .await
                     {
-                        tracing::error!("Failed to reload certificate: {}", e);
+                        Ok(()) => tls_expiry.update(&cert),
+                        Err(e) => tracing::error!("Failed to reload certificate: {}", e),
                     }
                 });
             })
@@ -363,30 +461,62 @@ async fn start_with_tls<T>(
             (config.tls.cert_path.clone(), config.tls.key_path.clone())
     {
         let tls_config_clone = rustls_config.clone();
+        let tls_expiry = tls_expiry.clone();
         tracing::info!(
             "Certificate reload enabled: checking every {} seconds",
             interval.as_secs()
         );
         tokio::spawn(async move {
-            run_certificate_reload_task(tls_config_clone, cert_path, key_path, interval).await;
+            run_certificate_reload_task(
+                tls_config_clone,
+                cert_path,
+                key_path,
+                interval,
+                tls_expiry,
+            )
+            .await;
+        });
+    }
+
+    // Allow reloading manually managed certificates on demand, through
+    // `POST /admin/reload-tls` or SIGHUP
+    if let (Some(cert_path), Some(key_path)) =
+        (config.tls.cert_path.clone(), config.tls.key_path.clone())
+    {
+        let reloader =
+            CertificateReloader::new(rustls_config.clone(), cert_path, key_path, tls_expiry);
+        let _ = tls_reloader.set(reloader.clone());
+
+        #[cfg(unix)]
+        tokio::spawn(async move {
+            reload_certificate_on_sighup(reloader).await;
         });
     }
 
     tracing::info!("HTTPS server listening on {}", tls_addr);
 
+    // Include the certificate fingerprint so clients can verify a
+    // self-signed certificate they found on the network
+    #[cfg(feature = "mdns")]
+    let _advertiser = clipper_server::discovery::advertise(
+        &config,
+        clipper_server::tls::certificate_fingerprint(&cert_pem).as_deref(),
+    );
+
     // Create a handle for graceful shutdown
     let handle = axum_server::Handle::new();
     let shutdown_handle = handle.clone();
 
     // Spawn shutdown signal listener
+    let shutdown_timeout = config.server.shutdown_timeout();
     tokio::spawn(async move {
         shutdown_signal(parent_shutdown_rx).await;
-        shutdown_handle.graceful_shutdown(Some(std::time::Duration::from_secs(10)));
+        shutdown_handle.graceful_shutdown(Some(shutdown_timeout));
     });
 
     axum_server::bind_rustls(tls_addr, rustls_config)
         .handle(handle)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .await
         .expect("HTTPS server failed");
 }
@@ -436,13 +566,15 @@ where
     {
         let domain = config.acme.domain.as_deref().unwrap_or("localhost");
         tracing::warn!(
-            "No certificate available, generating self-signed certificate for {}",
+            "No certificate available, using a self-signed certificate for {}",
             domain
         );
-        clipper_server::tls::generate_self_signed_cert(domain).unwrap_or_else(|err| {
-            eprintln!("Failed to generate self-signed certificate: {}", err);
-            std::process::exit(1);
-        })
+        let storage = create_storage(config.acme.get_certs_dir());
+        clipper_server::tls::load_or_generate_self_signed_cert(domain, storage.as_ref())
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to generate self-signed certificate: {}", err);
+                std::process::exit(1);
+            })
     }
 
     #[cfg(not(feature = "acme"))]
@@ -460,6 +592,7 @@ async fn run_certificate_reload_task(
     cert_path: std::path::PathBuf,
     key_path: std::path::PathBuf,
     interval: std::time::Duration,
+    tls_expiry: CertificateExpiry,
 ) {
     use std::time::SystemTime;
 
@@ -498,6 +631,7 @@ async fn run_certificate_reload_task(
             match tls_config.reload_from_pem_file(&cert_path, &key_path).await {
                 Ok(()) => {
                     tracing::info!("Certificate reloaded successfully");
+                    tls_expiry.update_from_file(&cert_path).await;
                     last_cert_modified = cert_modified;
                     last_key_modified = key_modified;
                 }
@@ -519,6 +653,98 @@ async fn run_certificate_reload_task(
     }
 }
 
+/// Exit after `init`, `install-service` or `uninstall-service`
+fn finish_setup(result: std::io::Result<()>) -> ! {
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// `clipper-server print-config`: print the merged configuration and exit,
+/// failing if it isn't valid
+fn print_config(config: &ServerConfig, format: ConfigFormat, show_secrets: bool) -> ! {
+    let printed = if show_secrets {
+        config.to_string_pretty(format)
+    } else {
+        config.redacted().to_string_pretty(format)
+    };
+    match printed {
+        Ok(printed) => println!("{}", printed.trim_end()),
+        Err(err) => {
+            eprintln!("Failed to print configuration: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    if let Err(err) = config.validate() {
+        eprintln!("Configuration error: {}", err);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// `clipper-server --print-healthcheck`: print whether the running server is
+/// ready and exit, with status 0 if it is
+async fn print_healthcheck(config: &ServerConfig) -> ! {
+    match clipper_server::health::probe(config).await {
+        Ok(response) => {
+            println!("{}", response);
+            std::process::exit(0);
+        }
+        Err(err) => {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reload the configuration whenever the process receives SIGHUP. Only the
+/// sections in `RELOADABLE_SECTIONS` change; an invalid file is logged and
+/// ignored.
+#[cfg(unix)]
+async fn reload_config_on_sighup(reloader: ConfigReloader) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        tracing::info!("Received SIGHUP, reloading configuration");
+        if let Err(e) = reloader.reload() {
+            tracing::error!("Failed to reload configuration: {}", e);
+        }
+    }
+}
+
+/// Reload certificates from disk whenever the process receives SIGHUP, so
+/// a certbot deploy hook can rotate them with `kill -HUP`.
+#[cfg(all(feature = "tls", unix))]
+async fn reload_certificate_on_sighup(reloader: CertificateReloader) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    while hangup.recv().await.is_some() {
+        tracing::info!("Received SIGHUP, reloading certificate");
+        if let Err(e) = reloader.reload().await {
+            tracing::error!("Failed to reload certificate: {}", e);
+        }
+    }
+}
+
 /// Run HTTP to HTTPS redirect server.
 /// Note: This variant does NOT handle ACME challenges - use run_http_redirect_server_with_acme instead.
 #[cfg(all(feature = "tls", not(feature = "acme")))]
@@ -634,21 +860,25 @@ async fn run_http_redirect_server(
     }
 }
 
-async fn health_check() -> &'static str {
-    "OK"
-}
-
 // ============================================================================
 // Embedded Web UI (when embed-web feature is enabled)
 // ============================================================================
 
 #[cfg(feature = "embed-web")]
-fn build_app_with_web_ui(api_routes: Router) -> Router {
+fn build_app_with_web_ui(api_routes: Router, base_path: &str) -> Router {
     tracing::info!("Serving embedded web UI");
 
+    let base_path = base_path.to_string();
+    let web_ui = ServiceBuilder::new()
+        .layer(serve::compression_layer())
+        .layer(middleware::from_fn(web_ui_cache_headers))
+        .service(any(move |uri: Uri, headers: HeaderMap| {
+            serve_embedded_file(uri, headers, base_path.clone())
+        }));
+
     let app = Router::new()
         .merge(api_routes)
-        .fallback(serve_embedded_file)
+        .fallback_service(web_ui)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http());
 
@@ -656,32 +886,65 @@ fn build_app_with_web_ui(api_routes: Router) -> Router {
 }
 
 #[cfg(feature = "embed-web")]
-async fn serve_embedded_file(uri: Uri) -> Response<Body> {
+async fn serve_embedded_file(uri: Uri, headers: HeaderMap, base_path: String) -> Response<Body> {
     let path = uri.path().trim_start_matches('/');
 
-    // Try to serve the exact file first
-    if let Some(content) = WebAssets::get(path) {
-        let mime = mime_guess::from_path(path).first_or_octet_stream();
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, mime.as_ref())
-            .body(Body::from(content.data.into_owned()))
-            .unwrap();
-    }
+    // Try to serve the exact file first, and index.html for SPA routing
+    let (file, is_index) = match WebAssets::get(path) {
+        Some(file) => (file, path == "index.html"),
+        None => match WebAssets::get("index.html") {
+            Some(file) => (file, true),
+            None => {
+                // No embedded files found
+                return Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::from("Web UI not embedded in this build"))
+                    .unwrap();
+            }
+        },
+    };
 
-    // For SPA routing, serve index.html for non-file paths
-    if let Some(content) = WebAssets::get("index.html") {
+    // The files are fixed at build time, so their hash makes a strong ETag.
+    // index.html also depends on the base path it's served under.
+    let hash: String = file.metadata.sha256_hash()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let etag = if is_index {
+        format!("\"{}{}\"", hash, base_path)
+    } else {
+        format!("\"{}\"", hash)
+    };
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|candidate| candidate.trim() == etag));
+    if not_modified {
         return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-            .body(Body::from(content.data.into_owned()))
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, etag)
+            .body(Body::empty())
             .unwrap();
     }
 
-    // No embedded files found
+    let (mime, body) = if is_index {
+        (
+            "text/html; charset=utf-8".to_string(),
+            index_html_with_base(&file.data, &base_path),
+        )
+    } else {
+        (
+            mime_guess::from_path(path)
+                .first_or_octet_stream()
+                .to_string(),
+            file.data.into_owned(),
+        )
+    };
     Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::from("Web UI not embedded in this build"))
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, mime)
+        .header(header::ETAG, etag)
+        .body(Body::from(body))
         .unwrap()
 }
 
@@ -690,7 +953,7 @@ async fn serve_embedded_file(uri: Uri) -> Response<Body> {
 // ============================================================================
 
 #[cfg(not(feature = "embed-web"))]
-fn build_app_with_web_ui(api_routes: Router) -> Router {
+fn build_app_with_web_ui(api_routes: Router, base_path: &str) -> Router {
     // Determine web UI directory
     let web_dir = std::env::var("CLIPPER_WEB_DIR").unwrap_or_else(|_| {
         // Check common locations for the web UI
@@ -710,28 +973,41 @@ fn build_app_with_web_ui(api_routes: Router) -> Router {
 
     tracing::info!("Web UI directory: {}", web_dir);
 
-    // Serve static files and fall back to index.html for SPA routing
-    let serve_dir =
-        ServeDir::new(&web_dir).not_found_service(tower::service_fn(move |req: Request<Body>| {
+    // Serve static files and fall back to index.html for SPA routing. The
+    // index is always served by the fallback so it gets the base path, and
+    // `fallback` (unlike `not_found_service`) keeps its 200 status.
+    let base_path = base_path.to_string();
+    let serve_dir = ServeDir::new(&web_dir)
+        .append_index_html_on_directories(false)
+        .fallback(tower::service_fn(move |req: Request<Body>| {
             let web_dir = web_dir.clone();
-            async move { serve_index_html_from_fs(&web_dir, req.uri().clone()).await }
+            let base_path = base_path.clone();
+            async move { serve_index_html_from_fs(&web_dir, &base_path, req.uri().clone()).await }
         }));
+    let web_ui = ServiceBuilder::new()
+        .layer(serve::compression_layer())
+        .layer(middleware::from_fn(web_ui_cache_headers))
+        .service(serve_dir);
 
     Router::new()
         .merge(api_routes)
-        .fallback_service(serve_dir)
+        .fallback_service(web_ui)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
 }
 
 #[cfg(not(feature = "embed-web"))]
-async fn serve_index_html_from_fs(web_dir: &str, _uri: Uri) -> Result<Response<Body>, Infallible> {
+async fn serve_index_html_from_fs(
+    web_dir: &str,
+    base_path: &str,
+    _uri: Uri,
+) -> Result<Response<Body>, Infallible> {
     let index_path = format!("{}/index.html", web_dir);
     match tokio::fs::read(&index_path).await {
         Ok(contents) => Ok(Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-            .body(Body::from(contents))
+            .body(Body::from(index_html_with_base(&contents, base_path)))
             .unwrap()),
         Err(_) => Ok(Response::builder()
             .status(StatusCode::NOT_FOUND)
@@ -740,6 +1016,48 @@ async fn serve_index_html_from_fs(web_dir: &str, _uri: Uri) -> Result<Response<B
     }
 }
 
+/// Set `Cache-Control` on web UI responses. Vite puts a content hash in the
+/// names of the files under `assets/`, so those never change; everything else
+/// (index.html, sw.js, favicon) is revalidated on every load.
+async fn web_ui_cache_headers(request: Request, next: Next) -> Response {
+    let hashed_asset = request.uri().path().starts_with("/assets/");
+    let mut response = next.run(request).await;
+
+    // A missing asset falls back to index.html, which must not be cached
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"text/html"));
+    let cache_control = if hashed_asset && !is_html {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+    if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+    }
+    response
+}
+
+/// Add a `<base>` element to the web UI's index.html when serving under a
+/// base path. The web UI resolves its assets and API requests against it.
+fn index_html_with_base(html: &[u8], base_path: &str) -> Vec<u8> {
+    if base_path.is_empty() {
+        return html.to_vec();
+    }
+    // The base path is validated to be plain path segments, safe in an attribute
+    String::from_utf8_lossy(html)
+        .replacen(
+            "<head>",
+            &format!("<head>\n    <base href=\"{}/\">", base_path),
+            1,
+        )
+        .into_bytes()
+}
+
 async fn shutdown_signal(parent_shutdown_rx: Option<tokio::sync::broadcast::Receiver<()>>) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
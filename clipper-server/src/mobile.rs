@@ -0,0 +1,301 @@
+//! Compact API for mobile clients.
+//!
+//! A keyboard extension runs with little memory and often on a slow
+//! connection, so these endpoints return only what it needs: one-line
+//! previews of the latest clips, and a clip's full text in bounded chunks
+//! fetched when the user picks it. Devices also register their push tokens
+//! here so a notification relay can reach them. Responses are compressed
+//! like the rest of the API (see `serve::compression_layer`).
+
+use axum::{
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use clipper_indexer::{ClipboardEntry, PagingParams, PushPlatform, PushToken, SearchFilters};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{ensure_visible, visible_filters},
+    auth::Credential,
+    error::{Result, ServerError},
+    state::AppState,
+};
+
+/// Clips returned by `GET /mobile/clips` when no limit is given
+const DEFAULT_LATEST_LIMIT: usize = 20;
+/// Most clips returned by one `GET /mobile/clips` request
+const MAX_LATEST_LIMIT: usize = 50;
+/// Longest clip preview, in characters
+const PREVIEW_CHARS: usize = 100;
+/// Chunk size used when the client doesn't ask for one, in bytes
+const DEFAULT_CHUNK_BYTES: usize = 16 * 1024;
+/// Largest chunk returned by `GET /mobile/clips/{id}/content`, in bytes
+const MAX_CHUNK_BYTES: usize = 64 * 1024;
+/// Largest accepted push token registration body, in bytes
+const MAX_PUSH_TOKEN_BODY_BYTES: usize = 4 * 1024;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/mobile/clips", get(latest_clips))
+        .route("/mobile/clips/{id}/content", get(clip_content))
+        .route(
+            "/mobile/push-tokens",
+            post(register_push_token).layer(DefaultBodyLimit::max(MAX_PUSH_TOKEN_BODY_BYTES)),
+        )
+        .route("/mobile/push-tokens/{id}", delete(delete_push_token))
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestClipsQuery {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// A clip as listed for a keyboard: enough to show and choose it
+#[derive(Debug, Serialize)]
+struct MobileClip {
+    id: String,
+    /// The start of the content on one line, whitespace collapsed
+    preview: String,
+    /// Size of the full content in bytes
+    size: usize,
+    /// Whether the preview is shorter than the content
+    truncated: bool,
+    created_at: String,
+    /// Set for image and file clips, which can't be typed by a keyboard
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    has_file: bool,
+}
+
+impl From<ClipboardEntry> for MobileClip {
+    fn from(entry: ClipboardEntry) -> Self {
+        let (preview, truncated) = preview(&entry.content);
+        Self {
+            id: entry.id,
+            preview,
+            size: entry.content.len(),
+            truncated,
+            created_at: entry.created_at.to_rfc3339(),
+            has_file: entry.file_attachment.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MobileClipsResponse {
+    clips: Vec<MobileClip>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentQuery {
+    /// Byte offset to start at; use `next_offset` from the previous chunk
+    #[serde(default)]
+    offset: usize,
+    #[serde(default)]
+    max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct ContentChunkResponse {
+    id: String,
+    offset: usize,
+    content: String,
+    /// Size of the full content in bytes
+    size: usize,
+    /// Offset of the next chunk, or null after the last one
+    next_offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegisterPushTokenRequest {
+    token: String,
+    platform: PushPlatform,
+    #[serde(default)]
+    device_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PushTokenResponse {
+    id: String,
+    platform: PushPlatform,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device_name: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl From<PushToken> for PushTokenResponse {
+    fn from(token: PushToken) -> Self {
+        Self {
+            id: token.id,
+            platform: token.platform,
+            device_name: token.device_name,
+            created_at: token.created_at.to_rfc3339(),
+            updated_at: token.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The first `PREVIEW_CHARS` characters of `content` on a single line, and
+/// whether anything was cut off
+pub(crate) fn preview(content: &str) -> (String, bool) {
+    // Lazily, since clips can be large and only the start is needed
+    let mut chars = content
+        .split_whitespace()
+        .flat_map(|word| std::iter::once(' ').chain(word.chars()))
+        .skip(1);
+    let preview: String = chars.by_ref().take(PREVIEW_CHARS).collect();
+    let truncated = chars.next().is_some();
+    (preview.trim_end().to_string(), truncated)
+}
+
+/// The chunk of `content` starting at byte `offset`, at most `max_bytes`
+/// long but never splitting a character, and the offset of the next chunk
+fn content_chunk(content: &str, offset: usize, max_bytes: usize) -> Result<(&str, Option<usize>)> {
+    if offset > content.len() || !content.is_char_boundary(offset) {
+        return Err(ServerError::InvalidInput(format!(
+            "Offset {} is not the start of a chunk",
+            offset
+        )));
+    }
+
+    let mut end = (offset + max_bytes).min(content.len());
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    // Always make progress, even if one character is larger than max_bytes
+    if end == offset && offset < content.len() {
+        end = offset + content[offset..].chars().next().map_or(0, char::len_utf8);
+    }
+
+    let next_offset = (end < content.len()).then_some(end);
+    Ok((&content[offset..end], next_offset))
+}
+
+/// The most recent clips, newest first
+async fn latest_clips(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Query(query): Query<LatestClipsQuery>,
+) -> Result<Json<MobileClipsResponse>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LATEST_LIMIT)
+        .clamp(1, MAX_LATEST_LIMIT);
+    let paging = PagingParams::new(1, limit).with_skip_total(true);
+    let filters = visible_filters(credential.as_deref(), SearchFilters::new());
+    let result = state.indexer.list_entries(filters, paging).await?;
+
+    Ok(Json(MobileClipsResponse {
+        clips: result.items.into_iter().map(MobileClip::from).collect(),
+    }))
+}
+
+/// One chunk of a clip's text, for pasting clips too large to fetch at once
+async fn clip_content(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+    Query(query): Query<ContentQuery>,
+) -> Result<Json<ContentChunkResponse>> {
+    let max_bytes = query
+        .max_bytes
+        .unwrap_or(DEFAULT_CHUNK_BYTES)
+        .clamp(1, MAX_CHUNK_BYTES);
+    let entry = state.indexer.get_entry(&id).await?;
+    ensure_visible(credential.as_deref(), &entry)?;
+    let (content, next_offset) = content_chunk(&entry.content, query.offset, max_bytes)?;
+
+    Ok(Json(ContentChunkResponse {
+        content: content.to_string(),
+        size: entry.content.len(),
+        offset: query.offset,
+        next_offset,
+        id: entry.id,
+    }))
+}
+
+/// Register this device's push token; re-registering updates it
+async fn register_push_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RegisterPushTokenRequest>,
+) -> Result<(StatusCode, Json<PushTokenResponse>)> {
+    let token = state
+        .indexer
+        .register_push_token(
+            &payload.token,
+            payload.platform,
+            payload.device_name.as_deref(),
+        )
+        .await?;
+    Ok((StatusCode::CREATED, Json(token.into())))
+}
+
+/// Stop sending notifications to a device
+async fn delete_push_token(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state.indexer.delete_push_token(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_collapses_whitespace() {
+        assert_eq!(
+            preview("  fn main() {\n\tprintln!();\n}\n"),
+            ("fn main() { println!(); }".to_string(), false)
+        );
+        assert_eq!(preview(""), (String::new(), false));
+    }
+
+    #[test]
+    fn test_preview_is_truncated() {
+        let content = "word ".repeat(50);
+        let (text, truncated) = preview(&content);
+        assert!(truncated);
+        assert!(text.chars().count() <= PREVIEW_CHARS);
+        assert!(text.starts_with("word word"));
+
+        let (text, truncated) = preview(&"长".repeat(PREVIEW_CHARS + 1));
+        assert!(truncated);
+        assert_eq!(text, "长".repeat(PREVIEW_CHARS));
+    }
+
+    #[test]
+    fn test_content_chunks_cover_content() {
+        let content = "héllo wörld";
+        let mut offset = 0;
+        let mut chunks = Vec::new();
+        loop {
+            let (chunk, next) = content_chunk(content, offset, 2).unwrap();
+            chunks.push(chunk);
+            match next {
+                Some(next) => offset = next,
+                None => break,
+            }
+        }
+        assert_eq!(chunks.concat(), content);
+        assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+    }
+
+    #[test]
+    fn test_content_chunk_makes_progress_on_wide_characters() {
+        let (chunk, next) = content_chunk("😀a", 0, 1).unwrap();
+        assert_eq!(chunk, "😀");
+        assert_eq!(next, Some(4));
+    }
+
+    #[test]
+    fn test_content_chunk_rejects_bad_offsets() {
+        assert!(content_chunk("héllo", 2, 10).is_err());
+        assert!(content_chunk("hello", 6, 10).is_err());
+        assert_eq!(content_chunk("hello", 5, 10).unwrap(), ("", None));
+    }
+}
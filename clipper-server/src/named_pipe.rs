@@ -0,0 +1,74 @@
+//! Named pipe transport for Windows.
+//!
+//! With `server.named_pipe` set, the server accepts HTTP connections on a
+//! named pipe instead of binding a TCP port, which avoids port conflicts and
+//! firewall prompts for a server that only local processes talk to. Every
+//! pipe instance gets the same user-only DACL that `clipper-security` puts on
+//! the data directories, and remote clients are rejected.
+
+use std::io;
+use std::time::Duration;
+
+use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+/// Delay before retrying after a pipe instance couldn't be created
+const CREATE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Accepts connections on a named pipe, for use with `axum::serve`
+pub struct NamedPipeListener {
+    name: String,
+    /// The instance waiting for the next client
+    next: NamedPipeServer,
+}
+
+impl NamedPipeListener {
+    /// Create the first instance of the pipe. Fails if another process
+    /// already owns a pipe with this name.
+    pub fn bind(name: &str) -> io::Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            next: create_instance(name, true)?,
+        })
+    }
+}
+
+fn create_instance(name: &str, first: bool) -> io::Result<NamedPipeServer> {
+    let mut attributes = clipper_security::UserOnlySecurityAttributes::new()?;
+    let mut options = ServerOptions::new();
+    options
+        .first_pipe_instance(first)
+        .reject_remote_clients(true);
+    // SAFETY: `attributes` points at valid SECURITY_ATTRIBUTES and outlives
+    // the call; the pipe keeps its own copy of the security descriptor
+    unsafe { options.create_with_security_attributes_raw(name, attributes.as_mut_ptr()) }
+}
+
+impl axum::serve::Listener for NamedPipeListener {
+    type Io = NamedPipeServer;
+    type Addr = String;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            if let Err(e) = self.next.connect().await {
+                // The client went away before the connection completed
+                tracing::debug!("Named pipe connection failed: {}", e);
+                let _ = self.next.disconnect();
+                continue;
+            }
+
+            // Create the next instance before handing this one out, so the
+            // pipe name never disappears while clients may be connecting
+            match create_instance(&self.name, false) {
+                Ok(next) => return (std::mem::replace(&mut self.next, next), self.name.clone()),
+                Err(e) => {
+                    tracing::error!("Failed to create named pipe instance: {}", e);
+                    tokio::time::sleep(CREATE_RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.name.clone())
+    }
+}
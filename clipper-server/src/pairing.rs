@@ -0,0 +1,247 @@
+//! Pairing flow for per-device tokens.
+//!
+//! A client holding the shared bearer token asks for a short-lived pairing
+//! code and shows it, e.g. as a QR code. The new device redeems the code for
+//! its own token, which can later be revoked without rotating the shared
+//! token.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use clipper_indexer::PairedDevice;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{
+    auth::Credential,
+    error::{Result, ServerError},
+    state::AppState,
+};
+
+/// Characters used in pairing codes: upper case letters and digits without
+/// look-alikes, so codes are easy to read out and type
+const PAIRING_CODE_CHARS: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+const PAIRING_CODE_LENGTH: usize = 8;
+
+/// How long a pairing code can be redeemed
+const PAIRING_CODE_TTL_SECS: i64 = 300;
+
+/// Outstanding pairing codes and their expiry times. Codes are kept in
+/// memory only; a server restart invalidates them.
+#[derive(Debug, Default)]
+pub struct PairingCodes {
+    codes: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl PairingCodes {
+    /// Issue a new single-use code, returned with its expiry time
+    pub fn issue(&self) -> (String, DateTime<Utc>) {
+        let mut rng = rand::rng();
+        let code: String = (0..PAIRING_CODE_LENGTH)
+            .map(|_| PAIRING_CODE_CHARS[rng.random_range(0..PAIRING_CODE_CHARS.len())] as char)
+            .collect();
+        let expires_at = Utc::now() + chrono::Duration::seconds(PAIRING_CODE_TTL_SECS);
+
+        let mut codes = self.codes.lock().unwrap();
+        let now = Utc::now();
+        codes.retain(|_, expires_at| *expires_at > now);
+        codes.insert(code.clone(), expires_at);
+
+        (code, expires_at)
+    }
+
+    /// Use up a code. Case, spaces and dashes are ignored.
+    ///
+    /// # Returns
+    /// false if the code is unknown, already used or expired
+    pub fn redeem(&self, code: &str) -> bool {
+        let code: String = code
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        match self.codes.lock().unwrap().remove(&code) {
+            Some(expires_at) => expires_at > Utc::now(),
+            None => false,
+        }
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/pairing", post(create_pairing_code))
+        // Redeeming a code is how a device gets a token (no auth required)
+        .route("/pairing/claim", post(claim_pairing_code))
+        .route("/paired-devices", get(list_paired_devices))
+        .route("/paired-devices/{id}", delete(revoke_paired_device))
+}
+
+/// Pairing is pointless on a server without a bearer token
+pub(crate) fn require_auth_enabled(state: &AppState) -> Result<()> {
+    if !state.config.get().auth.is_enabled() {
+        return Err(ServerError::FeatureDisabled(
+            "Device pairing requires a bearer token. Set CLIPPER_BEARER_TOKEN to enable."
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fail unless the request used the shared bearer token rather than a paired
+/// device token, so a lost device can't pair more devices or revoke the others
+fn require_shared_token(state: &AppState, credential: Option<&Credential>) -> Result<()> {
+    require_auth_enabled(state)?;
+    match credential {
+        Some(Credential::SharedToken) => Ok(()),
+        _ => Err(ServerError::Forbidden(
+            "Managing paired devices requires the shared bearer token".to_string(),
+        )),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PairingCodeResponse {
+    code: String,
+    expires_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaimPairingCodeRequest {
+    code: String,
+    /// Name shown in the paired device list, e.g. the device's hostname
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ClaimPairingCodeResponse {
+    /// The device's own bearer token; it is not shown again
+    token: String,
+    device: PairedDeviceResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PairedDeviceResponse {
+    id: String,
+    name: String,
+    created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used_at: Option<String>,
+}
+
+impl From<PairedDevice> for PairedDeviceResponse {
+    fn from(device: PairedDevice) -> Self {
+        Self {
+            id: device.id,
+            name: device.name,
+            created_at: device.created_at.to_rfc3339(),
+            last_used_at: device.last_used_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+/// Issue a pairing code for a new device
+async fn create_pairing_code(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<(StatusCode, Json<PairingCodeResponse>)> {
+    require_shared_token(&state, credential.as_deref())?;
+
+    let (code, expires_at) = state.pairing_codes.issue();
+    Ok((
+        StatusCode::CREATED,
+        Json(PairingCodeResponse {
+            code,
+            expires_at: expires_at.to_rfc3339(),
+        }),
+    ))
+}
+
+/// Redeem a pairing code for a device token
+async fn claim_pairing_code(
+    State(state): State<AppState>,
+    Json(payload): Json<ClaimPairingCodeRequest>,
+) -> Result<(StatusCode, Json<ClaimPairingCodeResponse>)> {
+    require_auth_enabled(&state)?;
+    if !state.pairing_codes.redeem(&payload.code) {
+        return Err(ServerError::Unauthorized(
+            "Invalid or expired pairing code".to_string(),
+        ));
+    }
+
+    let (device, token) = state.indexer.create_paired_device(&payload.name).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(ClaimPairingCodeResponse {
+            token,
+            device: device.into(),
+        }),
+    ))
+}
+
+/// List paired devices, most recently paired first
+async fn list_paired_devices(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+) -> Result<Json<Vec<PairedDeviceResponse>>> {
+    require_shared_token(&state, credential.as_deref())?;
+
+    let devices = state.indexer.list_paired_devices().await?;
+    Ok(Json(devices.into_iter().map(Into::into).collect()))
+}
+
+/// Revoke a paired device. Its token stops working right away and its
+/// WebSocket connections are closed.
+async fn revoke_paired_device(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    require_shared_token(&state, credential.as_deref())?;
+
+    state.indexer.revoke_paired_device(&id).await?;
+    state.notify_device_revoked(id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairing_code_is_single_use() {
+        let codes = PairingCodes::default();
+        let (code, _) = codes.issue();
+        assert_eq!(code.len(), PAIRING_CODE_LENGTH);
+
+        assert!(codes.redeem(&code));
+        assert!(!codes.redeem(&code));
+    }
+
+    #[test]
+    fn test_pairing_code_ignores_case_and_separators() {
+        let codes = PairingCodes::default();
+        let (code, _) = codes.issue();
+        let typed = format!("{}-{}", &code[..4], &code[4..]).to_lowercase();
+
+        assert!(codes.redeem(&typed));
+    }
+
+    #[test]
+    fn test_expired_pairing_code_is_rejected() {
+        let codes = PairingCodes::default();
+        codes.codes.lock().unwrap().insert(
+            "ABCDEFGH".to_string(),
+            Utc::now() - chrono::Duration::seconds(1),
+        );
+
+        assert!(!codes.redeem("ABCDEFGH"));
+        assert!(!codes.redeem("UNKNOWN2"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Pushing clips to the clipboard of a connected device.
+//!
+//! A device names itself when it opens the WebSocket (`/ws?device=<name>`),
+//! using the same name as its `$host:` tag and `GET /devices`. With auth
+//! enabled, only a paired device may do so, under the name it was paired
+//! with. `POST
+//! /devices/{name}/clipboard` sends a clip to that device's connections as a
+//! `set_clipboard` message, and the device puts the clip on its clipboard.
+//! Pushes are not queued, so the device has to be connected.
+
+use axum::{
+    extract::{Path, State},
+    routing::post,
+    Extension, Json, Router,
+};
+use clipper_indexer::Visibility;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use crate::{
+    api::ensure_visible,
+    auth::Credential,
+    error::{Result, ServerError},
+    state::AppState,
+};
+
+/// A clip sent to a device's clipboard, as the WebSocket message the device
+/// receives
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "set_clipboard")]
+pub struct ClipboardPush {
+    /// Name of the device the clip is for
+    #[serde(skip)]
+    pub device: String,
+    pub id: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// Visibility of the clip, so only connections that may see it get it
+    #[serde(skip)]
+    pub visibility: Visibility,
+}
+
+/// Names of the devices with an open WebSocket, and the channel pushes reach
+/// their connections through
+#[derive(Debug)]
+pub struct ConnectedDevices {
+    connections: Mutex<HashMap<String, usize>>,
+    pushes: broadcast::Sender<ClipboardPush>,
+}
+
+impl Default for ConnectedDevices {
+    fn default() -> Self {
+        let (pushes, _) = broadcast::channel(16);
+        Self {
+            connections: Mutex::default(),
+            pushes,
+        }
+    }
+}
+
+impl ConnectedDevices {
+    /// Count a new connection of the device `name`, returning the pushes for
+    /// every device; the connection forwards those meant for `name`
+    pub fn connect(&self, name: &str) -> broadcast::Receiver<ClipboardPush> {
+        *self
+            .connections
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default() += 1;
+        self.pushes.subscribe()
+    }
+
+    /// Forget a connection of the device `name`
+    pub fn disconnect(&self, name: &str) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(count) = connections.get_mut(name) {
+            *count -= 1;
+            if *count == 0 {
+                connections.remove(name);
+            }
+        }
+    }
+
+    /// Number of open connections of the device `name`
+    pub fn connection_count(&self, name: &str) -> usize {
+        self.connections
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Send `push` to its device's connections
+    ///
+    /// # Returns
+    /// The number of connections it was sent to, 0 if the device isn't
+    /// connected
+    pub fn push(&self, push: ClipboardPush) -> usize {
+        let connections = self.connection_count(&push.device);
+        if connections > 0 {
+            let _ = self.pushes.send(push);
+        }
+        connections
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/devices/{id}/clipboard", post(push_to_clipboard))
+}
+
+#[derive(Debug, Deserialize)]
+struct PushToClipboardRequest {
+    clip_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PushToClipboardResponse {
+    /// Number of the device's connections the clip was sent to
+    connections: usize,
+}
+
+/// Put a clip on the clipboard of a connected device
+async fn push_to_clipboard(
+    State(state): State<AppState>,
+    credential: Option<Extension<Credential>>,
+    Path(device): Path<String>,
+    Json(payload): Json<PushToClipboardRequest>,
+) -> Result<Json<PushToClipboardResponse>> {
+    let entry = state.indexer.get_entry(&payload.clip_id).await?;
+    ensure_visible(credential.as_deref(), &entry)?;
+    let connections = state.connected_devices.push(ClipboardPush {
+        device: device.clone(),
+        id: entry.id,
+        content: entry.content,
+        tags: entry.tags,
+        visibility: entry.visibility,
+    });
+    if connections == 0 {
+        return Err(ServerError::NotFound(format!(
+            "Device {} is not connected",
+            device
+        )));
+    }
+    Ok(Json(PushToClipboardResponse { connections }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_for(device: &str) -> ClipboardPush {
+        ClipboardPush {
+            device: device.to_string(),
+            id: "abc".to_string(),
+            content: "hello".to_string(),
+            tags: vec!["$host:phone".to_string()],
+            visibility: Visibility::Private,
+        }
+    }
+
+    #[test]
+    fn test_connected_devices() {
+        let devices = ConnectedDevices::default();
+        assert_eq!(devices.push(push_for("laptop")), 0);
+
+        let mut first = devices.connect("laptop");
+        let _second = devices.connect("laptop");
+        assert_eq!(devices.connection_count("laptop"), 2);
+        assert_eq!(devices.push(push_for("laptop")), 2);
+        assert_eq!(first.try_recv().unwrap().device, "laptop");
+        assert_eq!(devices.push(push_for("desktop")), 0);
+        assert!(first.try_recv().is_err());
+
+        devices.disconnect("laptop");
+        devices.disconnect("laptop");
+        assert_eq!(devices.connection_count("laptop"), 0);
+        assert_eq!(devices.push(push_for("laptop")), 0);
+
+        assert_eq!(
+            serde_json::to_value(push_for("laptop")).unwrap(),
+            serde_json::json!({
+                "type": "set_clipboard",
+                "id": "abc",
+                "content": "hello",
+                "tags": ["$host:phone"],
+            })
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! `x-request-id` propagation.
+//!
+//! [`propagate_request_id`] takes the request ID a client sent, or makes one
+//! up, returns it in the response, runs the request in a span carrying it so
+//! every log line of the request includes it, and makes it available to
+//! [`ServerError`](crate::ServerError) responses through [`current`].
+//! Handlers add the clip a request is about to the span with
+//! [`record_clip_id`]. With
+//! the `otel` feature the span also joins the caller's trace if the request
+//! has a W3C `traceparent` header.
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+/// Header carrying the request ID
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest request ID accepted from a client; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The request being handled
+struct RequestContext {
+    id: String,
+    span: tracing::Span,
+}
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// The ID of the request being handled, if called while handling one
+pub fn current() -> Option<String> {
+    REQUEST.try_with(|request| request.id.clone()).ok()
+}
+
+/// Record the clip the request being handled is about as the `clip_id`
+/// field of its span, so the request's log lines carry it
+pub fn record_clip_id(clip_id: &str) {
+    let _ = REQUEST.try_with(|request| {
+        request.span.record("clip_id", clip_id);
+    });
+}
+
+/// Middleware assigning each request an ID. Apply it outside the other
+/// layers so their logs and errors carry the ID too.
+pub async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let request_id =
+        client_request_id(request.headers()).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    // Always valid: it was either a valid header value or is a UUID
+    let header_value = HeaderValue::from_str(&request_id).expect("valid request ID");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header_value.clone());
+
+    let span = request_span(&request, &request_id);
+    let context = RequestContext {
+        id: request_id,
+        span: span.clone(),
+    };
+    let mut response = REQUEST
+        .scope(context, next.run(request).instrument(span))
+        .await;
+    response
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER, header_value);
+    response
+}
+
+/// The request ID sent by the client, if it's reasonable to log
+fn client_request_id(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| value.to_string())
+}
+
+#[cfg(not(feature = "otel"))]
+fn request_span(_request: &Request, request_id: &str) -> tracing::Span {
+    tracing::info_span!(
+        "http",
+        request_id = request_id,
+        clip_id = tracing::field::Empty
+    )
+}
+
+#[cfg(feature = "otel")]
+fn request_span(request: &Request, request_id: &str) -> tracing::Span {
+    use opentelemetry::propagation::Extractor;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|name| name.as_str()).collect()
+        }
+    }
+
+    let span = tracing::info_span!(
+        "http",
+        request_id = request_id,
+        clip_id = tracing::field::Empty,
+        otel.name = %format!("{} {}", request.method(), request.uri().path()),
+        otel.kind = "server",
+        http.request.method = %request.method(),
+        url.path = request.uri().path(),
+    );
+    let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    let _ = span.set_parent(parent);
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_request_id() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_request_id(&headers), None);
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("abc-123"));
+        assert_eq!(client_request_id(&headers), Some("abc-123".to_string()));
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("a b"));
+        assert_eq!(client_request_id(&headers), None);
+
+        let too_long = "x".repeat(MAX_REQUEST_ID_LEN + 1);
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(&too_long).unwrap());
+        assert_eq!(client_request_id(&headers), None);
+    }
+}
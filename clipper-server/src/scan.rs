@@ -0,0 +1,389 @@
+//! Malware scanning of uploads. With `scan.clamd` set, uploaded files are
+//! streamed to a ClamAV daemon with its `INSTREAM` command; with
+//! `scan.command` set, they're written to a temporary file and the command
+//! is run on it. Flagged files are rejected, and with `scan.action =
+//! "quarantine"` a copy is kept for an administrator to look at.
+
+use crate::config::{ScanAction, ScanConfig};
+use crate::error::{Result, ServerError};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Size of the chunks an upload is streamed to clamd in
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Longest clamd reply that is read
+const MAX_CLAMD_REPLY_BYTES: u64 = 4096;
+
+/// The result of scanning a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Flagged, with what the scanner found, e.g. `Eicar-Signature`
+    Flagged(String),
+}
+
+/// Scan an upload if scanning is set up. Flagged uploads are rejected, and
+/// quarantined first with [`ScanAction::Quarantine`]. Uploads that can't be
+/// scanned are rejected too, unless `fail_open` is set.
+pub async fn check_upload(config: &ScanConfig, filename: &str, content: &[u8]) -> Result<()> {
+    if !config.is_enabled() {
+        return Ok(());
+    }
+
+    match scan(config, content).await {
+        Ok(ScanVerdict::Clean) => Ok(()),
+        Ok(ScanVerdict::Flagged(threat)) => {
+            tracing::warn!("Upload {:?} flagged by malware scan: {}", filename, threat);
+            if config.action == ScanAction::Quarantine {
+                match quarantine(
+                    Path::new(&config.quarantine_dir),
+                    filename,
+                    content,
+                    &threat,
+                )
+                .await
+                {
+                    Ok(path) => {
+                        tracing::warn!("Quarantined upload {:?} as {}", filename, path.display())
+                    }
+                    Err(e) => tracing::error!("Failed to quarantine upload {:?}: {}", filename, e),
+                }
+            }
+            Err(ServerError::UploadRejected(format!(
+                "File rejected by malware scan: {}",
+                threat
+            )))
+        }
+        Err(e) if config.fail_open => {
+            tracing::warn!(
+                "Malware scan of upload {:?} failed, accepting it: {}",
+                filename,
+                e
+            );
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("Malware scan of upload {:?} failed: {}", filename, e);
+            Err(ServerError::ScanFailed(
+                "The file couldn't be scanned for malware. Try again later.".to_string(),
+            ))
+        }
+    }
+}
+
+/// Scan `content` with the scanner `config` sets up, giving up after
+/// `timeout_secs`
+pub async fn scan(config: &ScanConfig, content: &[u8]) -> std::result::Result<ScanVerdict, String> {
+    let scan = async {
+        if let Some(clamd) = config.clamd.as_deref() {
+            scan_with_clamd(clamd, content).await
+        } else if let Some(command) = &config.command {
+            scan_with_command(command, content).await
+        } else {
+            Ok(ScanVerdict::Clean)
+        }
+    };
+    tokio::time::timeout(config.timeout(), scan)
+        .await
+        .map_err(|_| format!("Scan timed out after {} seconds", config.timeout_secs))?
+}
+
+async fn scan_with_clamd(
+    address: &str,
+    content: &[u8],
+) -> std::result::Result<ScanVerdict, String> {
+    let error = |e: std::io::Error| format!("ClamAV daemon at {}: {}", address, e);
+
+    #[cfg(unix)]
+    if address.contains('/') {
+        let stream = tokio::net::UnixStream::connect(address)
+            .await
+            .map_err(error)?;
+        let reply = clamd_instream(stream, content).await.map_err(error)?;
+        return parse_clamd_reply(&reply);
+    }
+
+    let stream = tokio::net::TcpStream::connect(address)
+        .await
+        .map_err(error)?;
+    let reply = clamd_instream(stream, content).await.map_err(error)?;
+    parse_clamd_reply(&reply)
+}
+
+/// Send `content` with clamd's `INSTREAM` command: chunks each after its
+/// length as a 4-byte big-endian number, ended by a zero length. The reply
+/// is terminated by a NUL byte, since the command starts with `z`.
+async fn clamd_instream<S>(mut stream: S, content: &[u8]) -> std::io::Result<String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(b"zINSTREAM\0").await?;
+    for chunk in content.chunks(CLAMD_CHUNK_SIZE) {
+        stream
+            .write_all(&(chunk.len() as u32).to_be_bytes())
+            .await?;
+        stream.write_all(chunk).await?;
+    }
+    stream.write_all(&0u32.to_be_bytes()).await?;
+    stream.flush().await?;
+
+    let mut reply = Vec::new();
+    stream
+        .take(MAX_CLAMD_REPLY_BYTES)
+        .read_to_end(&mut reply)
+        .await?;
+    let reply = String::from_utf8_lossy(&reply);
+    Ok(reply.trim_end_matches('\0').trim().to_string())
+}
+
+/// The verdict in a clamd reply: `stream: OK`, `stream: <name> FOUND`, or
+/// an error message ending in `ERROR`
+fn parse_clamd_reply(reply: &str) -> std::result::Result<ScanVerdict, String> {
+    let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+    if result == "OK" {
+        Ok(ScanVerdict::Clean)
+    } else if let Some(threat) = result.strip_suffix(" FOUND") {
+        Ok(ScanVerdict::Flagged(threat.trim().to_string()))
+    } else {
+        Err(format!("ClamAV daemon replied {:?}", reply))
+    }
+}
+
+async fn scan_with_command(
+    command: &Path,
+    content: &[u8],
+) -> std::result::Result<ScanVerdict, String> {
+    let file = TempFile::create(content)
+        .await
+        .map_err(|e| format!("Failed to write the file to scan: {}", e))?;
+
+    let output = tokio::process::Command::new(command)
+        .arg(&file.0)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", command.display(), e))?;
+
+    match output.status.code() {
+        Some(0) => Ok(ScanVerdict::Clean),
+        Some(1) => Ok(ScanVerdict::Flagged(command_threat(
+            &String::from_utf8_lossy(&output.stdout),
+        ))),
+        _ => Err(format!(
+            "{} exited with {}: {}",
+            command.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// What a scan command found: the first line of its output, with
+/// `clamscan`'s `<path>: <name> FOUND` reduced to the name
+fn command_threat(stdout: &str) -> String {
+    let line = stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let threat = match line.strip_suffix(" FOUND") {
+        Some(found) => found.rsplit(": ").next().unwrap_or(found),
+        None => line,
+    };
+    if threat.is_empty() {
+        "flagged by the scan command".to_string()
+    } else {
+        threat.to_string()
+    }
+}
+
+/// An upload written out for a scan command, removed when dropped, also
+/// when the scan times out
+struct TempFile(PathBuf);
+
+impl TempFile {
+    async fn create(content: &[u8]) -> std::io::Result<Self> {
+        let file = TempFile(
+            std::env::temp_dir().join(format!("clipper-scan-{}", uuid::Uuid::new_v4().simple())),
+        );
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        // Uploads may be private; other users of the machine shouldn't read them
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut handle = options.open(&file.0).await?;
+        handle.write_all(content).await?;
+        handle.flush().await?;
+        Ok(file)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Keep a flagged upload in `dir`, with a `.json` file next to it giving
+/// its name and what the scan found. The copy gets no extension of its own,
+/// so it isn't opened by accident. Returns the path of the copy.
+pub async fn quarantine(
+    dir: &Path,
+    filename: &str,
+    content: &[u8],
+    threat: &str,
+) -> std::io::Result<PathBuf> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    let now = chrono::Utc::now();
+    let path = dir.join(format!(
+        "{}_{}",
+        now.format("%Y%m%d_%H%M%S"),
+        uuid::Uuid::new_v4().simple()
+    ));
+    tokio::fs::write(&path, content).await?;
+
+    let info = serde_json::json!({
+        "filename": filename,
+        "threat": threat,
+        "size": content.len(),
+        "quarantined_at": now.to_rfc3339(),
+    });
+    tokio::fs::write(
+        path.with_extension("json"),
+        serde_json::to_vec_pretty(&info)?,
+    )
+    .await?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clamd_reply() {
+        assert_eq!(parse_clamd_reply("stream: OK"), Ok(ScanVerdict::Clean));
+        assert_eq!(
+            parse_clamd_reply("stream: Eicar-Signature FOUND"),
+            Ok(ScanVerdict::Flagged("Eicar-Signature".to_string()))
+        );
+        assert!(parse_clamd_reply("INSTREAM size limit exceeded. ERROR").is_err());
+    }
+
+    #[test]
+    fn test_command_threat() {
+        assert_eq!(
+            command_threat("/tmp/clipper-scan-1: Eicar-Signature FOUND\n\nScanned files: 1\n"),
+            "Eicar-Signature"
+        );
+        assert_eq!(command_threat("\n  suspicious macro\n"), "suspicious macro");
+        assert_eq!(command_threat(""), "flagged by the scan command");
+    }
+
+    #[tokio::test]
+    async fn test_clamd_instream() {
+        let (client, mut server) = tokio::io::duplex(1024);
+        let content = vec![b'x'; CLAMD_CHUNK_SIZE + 10];
+
+        let daemon = tokio::spawn(async move {
+            let mut command = [0u8; 10];
+            server.read_exact(&mut command).await.unwrap();
+            assert_eq!(&command, b"zINSTREAM\0");
+            let mut received = Vec::new();
+            loop {
+                let length = server.read_u32().await.unwrap() as usize;
+                if length == 0 {
+                    break;
+                }
+                let mut chunk = vec![0u8; length];
+                server.read_exact(&mut chunk).await.unwrap();
+                received.extend(chunk);
+            }
+            server.write_all(b"stream: OK\0").await.unwrap();
+            received
+        });
+
+        let reply = clamd_instream(client, &content).await.unwrap();
+        assert_eq!(reply, "stream: OK");
+        assert_eq!(daemon.await.unwrap(), content);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scan_with_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("scan.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             if grep -q MALWARE \"$1\"; then echo \"$1: Test-Signature FOUND\"; exit 1; fi\n\
+             if grep -q BROKEN \"$1\"; then echo 'no database' >&2; exit 2; fi\n",
+        )
+        .unwrap();
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = ScanConfig {
+            command: Some(script),
+            ..ScanConfig::default()
+        };
+        assert_eq!(scan(&config, b"hello").await, Ok(ScanVerdict::Clean));
+        assert_eq!(
+            scan(&config, b"MALWARE").await,
+            Ok(ScanVerdict::Flagged("Test-Signature".to_string()))
+        );
+        let error = scan(&config, b"BROKEN").await.unwrap_err();
+        assert!(error.contains("no database"), "{}", error);
+
+        // The temporary files are gone
+        let leftovers = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with("clipper-scan-")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_quarantine() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let quarantine_dir = dir.path().join("quarantine");
+        let config = ScanConfig {
+            clamd: Some("127.0.0.1:1".to_string()),
+            action: ScanAction::Quarantine,
+            quarantine_dir: quarantine_dir.to_string_lossy().into_owned(),
+            ..ScanConfig::default()
+        };
+
+        // No daemon listens there, so the upload can't be scanned
+        let result = check_upload(&config, "report.pdf", b"content").await;
+        assert!(matches!(result, Err(ServerError::ScanFailed(_))));
+        assert!(!quarantine_dir.exists());
+        let config = ScanConfig {
+            fail_open: true,
+            ..config
+        };
+        assert!(
+            check_upload(&config, "report.pdf", b"content")
+                .await
+                .is_ok()
+        );
+
+        let path = quarantine(&quarantine_dir, "report.pdf", b"content", "Test-Signature")
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"content");
+        let info: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(path.with_extension("json")).unwrap()).unwrap();
+        assert_eq!(info["filename"], "report.pdf");
+        assert_eq!(info["threat"], "Test-Signature");
+        assert_eq!(info["size"], 7);
+    }
+}
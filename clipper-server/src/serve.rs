@@ -0,0 +1,281 @@
+//! Building blocks for running the server, shared by the `clipper-server`
+//! binary and applications that embed the server (e.g. `clipper-cli serve`).
+
+use std::future::Future;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, Uri},
+    middleware,
+};
+use clipper_indexer::ClipperIndexer;
+use tower::ServiceExt;
+use tower_http::{
+    compression::{
+        CompressionLayer, DefaultPredicate, Predicate,
+        predicate::{And, NotForContentType},
+    },
+    cors::CorsLayer,
+    trace::TraceLayer,
+};
+
+use crate::{
+    admin, api, auth_middleware, canonical_url,
+    cleanup::{run_clip_cleanup_task, run_sensitive_cleanup_task, run_short_url_cleanup_task},
+    config::{ServerConfig, normalize_base_path},
+    enrichment,
+    error::{Result, ServerError},
+    health, mobile, pairing,
+    plugins::PluginManager,
+    remote_clipboard, request_id,
+    state::AppState,
+    web_push::{self, WebPushSender},
+    websocket,
+};
+
+/// Initialize the indexer, secure the data directories, and start the
+/// background cleanup tasks.
+pub async fn create_state(config: &ServerConfig) -> Result<AppState> {
+    let db_path = std::path::Path::new(&config.database.path);
+    let storage_path = std::path::Path::new(&config.storage.path);
+    prepare_data_directory(db_path, "database")?;
+    prepare_data_directory(storage_path, "storage")?;
+
+    let indexer = ClipperIndexer::new(&config.database.path, &config.storage.path).await?;
+
+    // Secure the data directories and fix any incorrect permissions
+    // On Unix: checks and fixes permissions to 0700/0600
+    // On Windows: sets DACL to grant access only to current user
+
+    secure_data_directory(db_path, "database").await;
+    secure_data_directory(storage_path, "storage").await;
+
+    // Create application state
+    let mut state = AppState::new(indexer, config.clone());
+
+    // Load plugins if a plugin directory is configured
+    if let Some(plugins_dir) = &config.plugins.dir {
+        let plugins = PluginManager::load(plugins_dir)?;
+        tracing::info!(
+            "Loaded {} plugin(s) from {}",
+            plugins.names().len(),
+            plugins_dir.display()
+        );
+        state = state.with_plugins(plugins);
+    }
+
+    // Start clip cleanup task. It waits while cleanup is disabled, so a
+    // configuration reload can turn it on.
+    {
+        let cleanup_state = state.clone();
+        tokio::spawn(async move {
+            run_clip_cleanup_task(cleanup_state).await;
+        });
+    }
+
+    // Start short URL cleanup task (always runs to clean expired short URLs)
+    {
+        let short_url_cleanup_state = state.clone();
+        tokio::spawn(async move {
+            run_short_url_cleanup_task(short_url_cleanup_state).await;
+        });
+    }
+
+    // Start sensitive clip cleanup task. It does nothing while sensitive
+    // clips don't expire early, so a configuration reload can turn it on.
+    {
+        let sensitive_cleanup_state = state.clone();
+        tokio::spawn(async move {
+            run_sensitive_cleanup_task(sensitive_cleanup_state).await;
+        });
+    }
+
+    // Start the enrichment dispatcher. It skips clips while enrichment is
+    // disabled, so a configuration reload can turn it on.
+    tokio::spawn(enrichment::run_enrichment_dispatcher(state.clone()));
+
+    // Start the canonical URL dispatcher, which likewise skips clips while
+    // it is disabled
+    tokio::spawn(canonical_url::run_canonical_url_dispatcher(state.clone()));
+
+    // Start the Web Push dispatcher if a VAPID key is configured
+    if let Some(sender) = WebPushSender::from_config(&config.web_push)? {
+        tokio::spawn(web_push::run_web_push_dispatcher(state.clone(), sender));
+    }
+
+    // Start the mobile push relay if APNs or FCM credentials are configured
+    #[cfg(feature = "push-relay")]
+    if let Some(relay) = crate::push_relay::PushRelay::from_config(&config.push_relay)? {
+        tokio::spawn(crate::push_relay::run_push_relay(state.clone(), relay));
+    }
+    #[cfg(not(feature = "push-relay"))]
+    if config.push_relay.is_configured() {
+        tracing::warn!("Push relay is configured but the server was built without `push-relay`");
+    }
+
+    // Log auth status
+    if config.auth.is_enabled() {
+        tracing::info!("Authentication enabled (Bearer token required)");
+    } else {
+        tracing::info!("Authentication disabled (open access)");
+    }
+
+    Ok(state)
+}
+
+/// Create a data directory if needed and check that it's writable, so a
+/// read-only volume stops the server at startup with an explanation rather
+/// than failing the first write
+fn prepare_data_directory(path: &std::path::Path, name: &str) -> Result<()> {
+    clipper_security::secure_create_dir_all(path)
+        .map_err(|e| data_directory_error(path, name, "can't be created", e))?;
+    tempfile::tempfile_in(path)
+        .map_err(|e| data_directory_error(path, name, "isn't writable", e))?;
+    Ok(())
+}
+
+fn data_directory_error(
+    path: &std::path::Path,
+    name: &str,
+    problem: &str,
+    err: std::io::Error,
+) -> ServerError {
+    let hint = match err.kind() {
+        std::io::ErrorKind::ReadOnlyFilesystem => {
+            " The volume is mounted read-only; mount it read-write, \
+             or set CLIPPER_DATA_DIR to a writable directory."
+        }
+        std::io::ErrorKind::PermissionDenied => {
+            " Give the user the server runs as write access, \
+             or set CLIPPER_DATA_DIR to a directory it can write to."
+        }
+        _ => "",
+    };
+    ServerError::DataDirectory(format!(
+        "The {} directory {} {}: {}.{}",
+        name,
+        path.display(),
+        problem,
+        err,
+        hint
+    ))
+}
+
+/// Fix the permissions in a data directory. Where that fails, e.g. on shared
+/// filesystems that don't support chmod, log what's wrong instead.
+async fn secure_data_directory(path: &std::path::Path, name: &'static str) {
+    // On a large storage directory this takes a while, so keep it off the runtime threads
+    match clipper_security::secure_directory_recursive_async(path, |msg| tracing::warn!("{}", msg))
+        .await
+    {
+        Ok(count) if count > 0 => {
+            tracing::info!("Fixed permissions on {} items in {} directory", count, name);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!("Failed to secure {} directory: {}", name, e);
+
+            let path = path.to_path_buf();
+            let Ok(report) = tokio::task::spawn_blocking(move || {
+                clipper_security::audit_directory_recursive(&path)
+            })
+            .await
+            else {
+                return;
+            };
+            for issue in &report.issues {
+                tracing::warn!("Insecure {} directory: {}", name, issue);
+            }
+        }
+    }
+}
+
+/// Build the REST API and WebSocket routes with authentication applied.
+pub fn api_router(state: AppState) -> Router {
+    Router::new()
+        .merge(health::routes())
+        .merge(api::routes(&state.config))
+        .merge(admin::routes())
+        .merge(pairing::routes())
+        .merge(remote_clipboard::routes())
+        .merge(mobile::routes())
+        .merge(web_push::routes())
+        .merge(websocket::routes())
+        .layer(compression_layer())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state)
+}
+
+/// Compress responses with gzip, Brotli or zstd, whichever the client prefers.
+/// Export archives are gzipped already, and small bodies aren't worth it.
+pub fn compression_layer() -> CompressionLayer<And<DefaultPredicate, NotForContentType>> {
+    CompressionLayer::new().compress_when(
+        DefaultPredicate::new().and(NotForContentType::const_new("application/gzip")),
+    )
+}
+
+/// Serve `app` under `base_path` (`server.base_path`), e.g. `/clipper/clips`
+/// instead of `/clips`. Returns `app` unchanged for an empty base path.
+pub fn nest_under_base_path(app: Router, base_path: &str) -> Router {
+    let base_path = normalize_base_path(base_path);
+    if base_path.is_empty() {
+        return app;
+    }
+
+    // `nest` matches "/clipper" but not "/clipper/", which is where browsers
+    // land behind most proxies, so route that to the root explicitly
+    let root = app.clone().map_request(|mut request: Request<Body>| {
+        let query = request
+            .uri()
+            .query()
+            .map(|query| format!("?{}", query))
+            .unwrap_or_default();
+        *request.uri_mut() = format!("/{}", query)
+            .parse::<Uri>()
+            .unwrap_or_else(|_| Uri::from_static("/"));
+        request
+    });
+
+    Router::new()
+        .nest(&base_path, app)
+        .route_service(&format!("{}/", base_path), root)
+}
+
+/// Run a plain HTTP server (no TLS, no web UI) until `shutdown` completes.
+///
+/// This is what `clipper-cli serve` uses; the `clipper-server` binary adds
+/// TLS, ACME and web UI serving on top of the same routes.
+pub async fn run_http(
+    config: ServerConfig,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let addr = config
+        .socket_addr()
+        .map_err(|e| ServerError::InvalidInput(format!("Invalid listen address: {}", e)))?;
+
+    let state = create_state(&config).await?;
+    let app = api_router(state)
+        .layer(CorsLayer::permissive())
+        .layer(TraceLayer::new_for_http());
+    let app = nest_under_base_path(app, &config.server.base_path)
+        .layer(middleware::from_fn(request_id::propagate_request_id));
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Failed to bind to {}: {}", addr, e)))?;
+
+    tracing::info!("HTTP server listening on {}", addr);
+
+    // Withdrawn from the network when the server stops
+    #[cfg(feature = "mdns")]
+    let _advertiser = crate::discovery::advertise(&config, None);
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Server failed: {}", e)))
+}
@@ -0,0 +1,842 @@
+//! Headless setup: running the server as a system service.
+//!
+//! - `clipper-server init` creates the data directory and a config file
+//!   using it, both readable only by the current user
+//! - `clipper-server install-service` does the same, then installs and starts
+//!   a systemd unit (Linux), launchd job (macOS) or Windows service
+//! - `clipper-server uninstall-service` stops and removes the service again,
+//!   keeping the data and config
+//!
+//! System-wide services need root (or an elevated prompt on Windows); with
+//! `--user`, the service runs as the current user instead.
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use rand::Rng;
+
+/// Name of the systemd unit and the Windows service
+pub const SERVICE_NAME: &str = "clipper-server";
+
+/// Label of the launchd job
+pub const LAUNCHD_LABEL: &str = "codes.unwritten.clipper.server";
+
+/// Length of the bearer token `init` generates
+const BEARER_TOKEN_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Args)]
+pub struct InitArgs {
+    /// Set up for the current user rather than the whole system
+    #[arg(long)]
+    pub user: bool,
+
+    /// Data directory (default depends on the platform, see the README)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct InstallArgs {
+    #[command(flatten)]
+    pub init: InitArgs,
+
+    /// Print the service definition instead of installing it
+    #[arg(long)]
+    pub print: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct UninstallArgs {
+    /// Remove the service installed with `install-service --user`
+    #[arg(long)]
+    pub user: bool,
+}
+
+/// Where the service keeps its config and data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceLayout {
+    pub user: bool,
+    pub config_path: PathBuf,
+    pub data_dir: PathBuf,
+}
+
+impl ServiceLayout {
+    /// The platform defaults, unless `--config` or `--data-dir` say otherwise.
+    /// Both paths are made absolute, since services don't start in the
+    /// current directory.
+    pub fn new(args: &InitArgs, config_path: Option<&Path>) -> io::Result<Self> {
+        let config_path = match config_path {
+            Some(path) => path.to_path_buf(),
+            None => default_config_path(args.user)?,
+        };
+        let data_dir = match &args.data_dir {
+            Some(path) => path.clone(),
+            None => default_data_dir(args.user)?,
+        };
+        Ok(Self {
+            user: args.user,
+            config_path: std::path::absolute(config_path)?,
+            data_dir: std::path::absolute(data_dir)?,
+        })
+    }
+
+    /// Log file of the service. systemd collects the output in the journal;
+    /// elsewhere it would be lost.
+    pub fn log_file(&self) -> Option<PathBuf> {
+        if cfg!(target_os = "linux") {
+            None
+        } else {
+            Some(self.data_dir.join("logs").join("clipper-server.log"))
+        }
+    }
+
+    /// The config file `init` writes, keeping everything in the data directory
+    pub fn config_file(&self, bearer_token: &str) -> String {
+        let mut file = format!(
+            r#"# Created by `clipper-server init`. See config.toml.example for all settings.
+
+[database]
+path = {}
+
+[storage]
+path = {}
+
+[auth]
+bearer_token = {}
+
+[admin]
+backup_dir = {}
+
+[acme]
+certs_dir = {}
+"#,
+            toml_string(self.data_dir.join("db")),
+            toml_string(self.data_dir.join("storage")),
+            toml_string(bearer_token),
+            toml_string(self.data_dir.join("backups")),
+            toml_string(self.data_dir.join("certs")),
+        );
+        if let Some(log_file) = self.log_file() {
+            file.push_str(&format!("\n[log]\nfile = {}\n", toml_string(&log_file)));
+        }
+        file
+    }
+
+    /// Arguments the service starts the server with
+    fn server_args(&self) -> Vec<&OsStr> {
+        vec![OsStr::new("--config"), self.config_path.as_os_str()]
+    }
+
+    /// systemd unit running the server
+    pub fn systemd_unit(&self, exe: &Path) -> String {
+        let exec_start = std::iter::once(exe.as_os_str())
+            .chain(self.server_args())
+            .map(|arg| format!("\"{}\"", arg.to_string_lossy().replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut unit = format!(
+            r#"[Unit]
+Description=Clipper Server
+Documentation=https://github.com/windoze/clipper
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+ExecStart={}
+ExecReload=/bin/kill -HUP $MAINPID
+WorkingDirectory={}
+Restart=on-failure
+RestartSec=5
+UMask=0077
+"#,
+            exec_start,
+            self.data_dir.display(),
+        );
+        // Sandboxing needs privileges user services don't have
+        if !self.user {
+            unit.push_str(&format!(
+                r#"NoNewPrivileges=yes
+PrivateTmp=yes
+ProtectSystem=strict
+ProtectHome=read-only
+ReadWritePaths="{}"
+"#,
+                self.data_dir.display(),
+            ));
+        }
+        unit.push_str(&format!(
+            "\n[Install]\nWantedBy={}\n",
+            if self.user {
+                "default.target"
+            } else {
+                "multi-user.target"
+            }
+        ));
+        unit
+    }
+
+    /// launchd property list running the server
+    pub fn launchd_plist(&self, exe: &Path) -> String {
+        let program_arguments: String = std::iter::once(exe.as_os_str())
+            .chain(self.server_args())
+            .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+            .collect();
+        // Output from before logging is set up, e.g. configuration errors
+        let stderr_path = self.data_dir.join("logs").join("launchd.log");
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+{}    </array>
+    <key>WorkingDirectory</key>
+    <string>{}</string>
+    <key>StandardErrorPath</key>
+    <string>{}</string>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+</dict>
+</plist>
+"#,
+            LAUNCHD_LABEL,
+            program_arguments,
+            xml_escape(self.data_dir.as_os_str()),
+            xml_escape(stderr_path.as_os_str()),
+        )
+    }
+
+    /// Command line the Windows service starts the server with
+    pub fn windows_command_line(&self, exe: &Path) -> String {
+        format!(
+            "\"{}\" --config \"{}\" run-service",
+            exe.display(),
+            self.config_path.display()
+        )
+    }
+}
+
+/// `clipper-server init`
+pub fn init(args: &InitArgs, config_path: Option<&Path>) -> io::Result<()> {
+    prepare(&ServiceLayout::new(args, config_path)?)
+}
+
+/// Create the data directory and, unless there is one already, a config
+/// file with a new bearer token
+fn prepare(layout: &ServiceLayout) -> io::Result<()> {
+    clipper_security::secure_create_dir_all(&layout.data_dir)?;
+    clipper_security::secure_directory(&layout.data_dir)?;
+    if let Some(log_dir) = layout.log_file().as_deref().and_then(Path::parent) {
+        clipper_security::secure_create_dir_all(log_dir)?;
+    }
+    println!("Data directory: {}", layout.data_dir.display());
+
+    if layout.config_path.exists() {
+        println!(
+            "Keeping existing config file {}",
+            layout.config_path.display()
+        );
+        return Ok(());
+    }
+
+    if let Some(config_dir) = layout.config_path.parent() {
+        clipper_security::secure_create_dir_all(config_dir)?;
+    }
+    let bearer_token = generate_bearer_token();
+    clipper_security::secure_write(&layout.config_path, layout.config_file(&bearer_token))?;
+    println!("Created config file {}", layout.config_path.display());
+    println!(
+        "Clients need this bearer token to connect: {}",
+        bearer_token
+    );
+    Ok(())
+}
+
+/// `clipper-server install-service`
+pub fn install(args: &InstallArgs, config_path: Option<&Path>) -> io::Result<()> {
+    let layout = ServiceLayout::new(&args.init, config_path)?;
+    let exe = std::env::current_exe()?;
+
+    if args.print {
+        print!("{}", platform::definition(&layout, &exe)?);
+        return Ok(());
+    }
+
+    platform::check_supported(layout.user)?;
+    prepare(&layout)?;
+    platform::install(&layout, &exe)
+}
+
+/// `clipper-server uninstall-service`
+pub fn uninstall(args: &UninstallArgs) -> io::Result<()> {
+    platform::uninstall(args.user)
+}
+
+fn default_config_path(user: bool) -> io::Result<PathBuf> {
+    if cfg!(target_os = "linux") {
+        if user {
+            Ok(user_dir(dirs::config_dir())?
+                .join(SERVICE_NAME)
+                .join("config.toml"))
+        } else {
+            Ok(PathBuf::from("/etc/clipper-server/config.toml"))
+        }
+    } else {
+        Ok(base_dir(user)?.join("config.toml"))
+    }
+}
+
+fn default_data_dir(user: bool) -> io::Result<PathBuf> {
+    if cfg!(target_os = "linux") {
+        if user {
+            Ok(user_dir(dirs::data_dir())?.join(SERVICE_NAME))
+        } else {
+            Ok(PathBuf::from("/var/lib/clipper-server"))
+        }
+    } else {
+        Ok(base_dir(user)?.join("data"))
+    }
+}
+
+/// Directory holding both the config and the data on macOS and Windows
+fn base_dir(user: bool) -> io::Result<PathBuf> {
+    let parent = if user {
+        user_dir(dirs::data_dir())?
+    } else if cfg!(windows) {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+    } else {
+        PathBuf::from("/Library/Application Support")
+    };
+    Ok(parent.join("Clipper Server"))
+}
+
+fn user_dir(dir: Option<PathBuf>) -> io::Result<PathBuf> {
+    dir.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "Couldn't determine the user's data directory",
+        )
+    })
+}
+
+fn generate_bearer_token() -> String {
+    rand::rng()
+        .sample_iter(rand::distr::Alphanumeric)
+        .take(BEARER_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+fn toml_string(value: impl AsRef<OsStr>) -> String {
+    toml::Value::String(value.as_ref().to_string_lossy().into_owned()).to_string()
+}
+
+fn xml_escape(value: &OsStr) -> String {
+    value
+        .to_string_lossy()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Run a system tool, failing unless it succeeds
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn run<I, S>(program: &str, args: I) -> io::Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+    let status = command
+        .status()
+        .map_err(|e| io::Error::new(e.kind(), format!("Failed to run {}: {}", program, e)))?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{:?} failed with {}",
+            command, status
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::*;
+
+    const UNIT_FILE: &str = "clipper-server.service";
+
+    pub fn check_supported(_user: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn definition(layout: &ServiceLayout, exe: &Path) -> io::Result<String> {
+        Ok(layout.systemd_unit(exe))
+    }
+
+    pub fn install(layout: &ServiceLayout, exe: &Path) -> io::Result<()> {
+        let unit_path = unit_path(layout.user)?;
+        if let Some(unit_dir) = unit_path.parent() {
+            std::fs::create_dir_all(unit_dir)?;
+        }
+        std::fs::write(&unit_path, layout.systemd_unit(exe))?;
+        println!("Installed {}", unit_path.display());
+
+        systemctl(layout.user, &["daemon-reload"])?;
+        systemctl(layout.user, &["enable", "--now", UNIT_FILE])?;
+        println!("Started {}", UNIT_FILE);
+        if layout.user {
+            println!(
+                "User services stop when you log out; `loginctl enable-linger` keeps it running"
+            );
+        }
+        Ok(())
+    }
+
+    pub fn uninstall(user: bool) -> io::Result<()> {
+        let unit_path = unit_path(user)?;
+        if !unit_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No service installed at {}", unit_path.display()),
+            ));
+        }
+
+        systemctl(user, &["disable", "--now", UNIT_FILE])?;
+        std::fs::remove_file(&unit_path)?;
+        systemctl(user, &["daemon-reload"])?;
+        println!("Removed {}", unit_path.display());
+        Ok(())
+    }
+
+    fn unit_path(user: bool) -> io::Result<PathBuf> {
+        let dir = if user {
+            user_dir(dirs::config_dir())?.join("systemd").join("user")
+        } else {
+            PathBuf::from("/etc/systemd/system")
+        };
+        Ok(dir.join(UNIT_FILE))
+    }
+
+    fn systemctl(user: bool, args: &[&str]) -> io::Result<()> {
+        let scope: &[&str] = if user { &["--user"] } else { &[] };
+        run("systemctl", scope.iter().chain(args))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub fn check_supported(_user: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn definition(layout: &ServiceLayout, exe: &Path) -> io::Result<String> {
+        Ok(layout.launchd_plist(exe))
+    }
+
+    pub fn install(layout: &ServiceLayout, exe: &Path) -> io::Result<()> {
+        let plist_path = plist_path(layout.user)?;
+        if let Some(plist_dir) = plist_path.parent() {
+            std::fs::create_dir_all(plist_dir)?;
+        }
+        std::fs::write(&plist_path, layout.launchd_plist(exe))?;
+        println!("Installed {}", plist_path.display());
+
+        run(
+            "launchctl",
+            [OsStr::new("load"), OsStr::new("-w"), plist_path.as_os_str()],
+        )?;
+        println!("Started {}", LAUNCHD_LABEL);
+        Ok(())
+    }
+
+    pub fn uninstall(user: bool) -> io::Result<()> {
+        let plist_path = plist_path(user)?;
+        if !plist_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No service installed at {}", plist_path.display()),
+            ));
+        }
+
+        run(
+            "launchctl",
+            [
+                OsStr::new("unload"),
+                OsStr::new("-w"),
+                plist_path.as_os_str(),
+            ],
+        )?;
+        std::fs::remove_file(&plist_path)?;
+        println!("Removed {}", plist_path.display());
+        Ok(())
+    }
+
+    fn plist_path(user: bool) -> io::Result<PathBuf> {
+        let dir = if user {
+            user_dir(dirs::home_dir())?
+                .join("Library")
+                .join("LaunchAgents")
+        } else {
+            PathBuf::from("/Library/LaunchDaemons")
+        };
+        Ok(dir.join(format!("{}.plist", LAUNCHD_LABEL)))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    /// SID of the LocalSystem account Windows services run as
+    const LOCAL_SYSTEM_SID: &str = "*S-1-5-18";
+
+    pub fn check_supported(user: bool) -> io::Result<()> {
+        if user {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Windows services run for the whole system; \
+                 run install-service from an elevated prompt without --user",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn definition(layout: &ServiceLayout, exe: &Path) -> io::Result<String> {
+        check_supported(layout.user)?;
+        Ok(format!(
+            "sc.exe create {} binPath= \"{}\" start= auto DisplayName= \"Clipper Server\"\n",
+            SERVICE_NAME,
+            layout.windows_command_line(exe).replace('"', "\\\"")
+        ))
+    }
+
+    pub fn install(layout: &ServiceLayout, exe: &Path) -> io::Result<()> {
+        // `prepare` secured the data directory and config file for the current
+        // user, so let the service account in as well
+        run(
+            "icacls",
+            [
+                layout.data_dir.as_os_str(),
+                OsStr::new("/grant"),
+                OsStr::new(&format!("{}:(OI)(CI)F", LOCAL_SYSTEM_SID)),
+                OsStr::new("/T"),
+                OsStr::new("/Q"),
+            ],
+        )?;
+        run(
+            "icacls",
+            [
+                layout.config_path.as_os_str(),
+                OsStr::new("/grant"),
+                OsStr::new(&format!("{}:R", LOCAL_SYSTEM_SID)),
+                OsStr::new("/Q"),
+            ],
+        )?;
+
+        run(
+            "sc.exe",
+            [
+                "create",
+                SERVICE_NAME,
+                "binPath=",
+                &layout.windows_command_line(exe),
+                "start=",
+                "auto",
+                "DisplayName=",
+                "Clipper Server",
+            ],
+        )?;
+        run(
+            "sc.exe",
+            [
+                "description",
+                SERVICE_NAME,
+                "Clipper clipboard server with REST API and WebSocket support",
+            ],
+        )?;
+        println!("Installed service {}", SERVICE_NAME);
+
+        run("sc.exe", ["start", SERVICE_NAME])?;
+        println!("Started service {}", SERVICE_NAME);
+        Ok(())
+    }
+
+    pub fn uninstall(user: bool) -> io::Result<()> {
+        check_supported(user)?;
+
+        // Fails if the service isn't running, which is fine
+        let _ = run("sc.exe", ["stop", SERVICE_NAME]);
+        run("sc.exe", ["delete", SERVICE_NAME])?;
+        println!("Removed service {}", SERVICE_NAME);
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Installing a service isn't supported on this platform",
+        )
+    }
+
+    pub fn check_supported(_user: bool) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn definition(_layout: &ServiceLayout, _exe: &Path) -> io::Result<String> {
+        Err(unsupported())
+    }
+
+    pub fn install(_layout: &ServiceLayout, _exe: &Path) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn uninstall(_user: bool) -> io::Result<()> {
+        Err(unsupported())
+    }
+}
+
+/// Running under the Windows service control manager, which starts the
+/// server with `run-service`
+#[cfg(windows)]
+pub mod windows {
+    use std::ffi::OsString;
+    use std::io;
+    use std::sync::{OnceLock, mpsc};
+    use std::time::Duration;
+
+    use tokio::sync::broadcast;
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus,
+        ServiceType,
+    };
+    use windows_service::service_control_handler::{
+        self, ServiceControlHandlerResult, ServiceStatusHandle,
+    };
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    use super::SERVICE_NAME;
+
+    /// How long stopping may take before Windows gives up on the service
+    const STOP_WAIT_HINT: Duration = Duration::from_secs(30);
+
+    struct ServiceHost {
+        shutdown_tx: broadcast::Sender<()>,
+        started_tx: mpsc::Sender<io::Result<()>>,
+        status_handle: OnceLock<ServiceStatusHandle>,
+    }
+
+    static HOST: OnceLock<ServiceHost> = OnceLock::new();
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    /// Connect to the service control manager. The returned receiver gets a
+    /// message when Windows asks the service to stop.
+    pub fn start() -> io::Result<broadcast::Receiver<()>> {
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        let dispatcher_tx = started_tx.clone();
+        if HOST
+            .set(ServiceHost {
+                shutdown_tx,
+                started_tx,
+                status_handle: OnceLock::new(),
+            })
+            .is_err()
+        {
+            return Err(io::Error::other("Service already started"));
+        }
+
+        // Blocks until the service has stopped
+        std::thread::spawn(move || {
+            if let Err(err) = service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+                let _ = dispatcher_tx.send(Err(io::Error::other(format!(
+                    "Failed to connect to the service control manager: {}",
+                    err
+                ))));
+            }
+        });
+
+        started_rx
+            .recv()
+            .map_err(|_| io::Error::other("Service dispatcher exited"))??;
+        Ok(shutdown_rx)
+    }
+
+    /// Tell Windows the server has stopped
+    pub fn stopped() {
+        let _ = set_state(ServiceState::Stopped);
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Some(host) = HOST.get() {
+            let _ = host.started_tx.send(register(host));
+        }
+    }
+
+    fn register(host: &'static ServiceHost) -> io::Result<()> {
+        let status_handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    let _ = set_state(ServiceState::StopPending);
+                    let _ = host.shutdown_tx.send(());
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })
+            .map_err(io::Error::other)?;
+        let _ = host.status_handle.set(status_handle);
+        set_state(ServiceState::Running)
+    }
+
+    fn set_state(state: ServiceState) -> io::Result<()> {
+        let Some(status_handle) = HOST.get().and_then(|host| host.status_handle.get()) else {
+            return Ok(());
+        };
+        let controls_accepted = if state == ServiceState::Running {
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+        } else {
+            ServiceControlAccept::empty()
+        };
+        let wait_hint = if state == ServiceState::StopPending {
+            STOP_WAIT_HINT
+        } else {
+            Duration::ZERO
+        };
+        status_handle
+            .set_service_status(ServiceStatus {
+                service_type: ServiceType::OWN_PROCESS,
+                current_state: state,
+                controls_accepted,
+                exit_code: ServiceExitCode::Win32(0),
+                checkpoint: 0,
+                wait_hint,
+                process_id: None,
+            })
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Cli, Command, ServerConfig};
+    use clap::Parser;
+
+    fn layout(dir: &Path, user: bool) -> ServiceLayout {
+        ServiceLayout::new(
+            &InitArgs {
+                user,
+                data_dir: Some(dir.join("data")),
+            },
+            Some(&dir.join("config").join("config.toml")),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_prepare() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = layout(dir.path(), false);
+        prepare(&layout).unwrap();
+        assert!(layout.data_dir.is_dir());
+
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "--config",
+            layout.config_path.to_str().unwrap(),
+        ]);
+        let config = ServerConfig::load(cli).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            PathBuf::from(&config.database.path),
+            layout.data_dir.join("db")
+        );
+        assert_eq!(
+            PathBuf::from(&config.storage.path),
+            layout.data_dir.join("storage")
+        );
+        assert_eq!(config.log.file, layout.log_file());
+        let bearer_token = config.auth.bearer_token.unwrap();
+        assert_eq!(bearer_token.expose().len(), BEARER_TOKEN_LENGTH);
+
+        // An existing config file is left alone
+        let contents = std::fs::read_to_string(&layout.config_path).unwrap();
+        prepare(&layout).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&layout.config_path).unwrap(),
+            contents
+        );
+    }
+
+    #[test]
+    fn test_install_service_command() {
+        let cli = Cli::parse_from([
+            "clipper-server",
+            "install-service",
+            "--user",
+            "--data-dir",
+            "/srv/clipper",
+            "--print",
+        ]);
+        let Some(Command::InstallService(args)) = cli.command else {
+            panic!("Expected install-service");
+        };
+        assert!(args.init.user);
+        assert_eq!(args.init.data_dir, Some(PathBuf::from("/srv/clipper")));
+        assert!(args.print);
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = Path::new("/usr/local/bin/clipper-server");
+
+        let unit = layout(dir.path(), false).systemd_unit(exe);
+        assert!(unit.contains(&format!(
+            "ExecStart=\"/usr/local/bin/clipper-server\" \"--config\" \"{}\"",
+            dir.path().join("config").join("config.toml").display()
+        )));
+        assert!(unit.contains(&format!(
+            "ReadWritePaths=\"{}\"",
+            dir.path().join("data").display()
+        )));
+        assert!(unit.contains("WantedBy=multi-user.target"));
+
+        let unit = layout(dir.path(), true).systemd_unit(exe);
+        assert!(!unit.contains("ProtectSystem"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let dir = tempfile::tempdir().unwrap();
+        let plist = layout(dir.path(), false).launchd_plist(Path::new("/opt/clipper & co/server"));
+        assert!(plist.contains(&format!("<string>{}</string>", LAUNCHD_LABEL)));
+        assert!(plist.contains("<string>/opt/clipper &amp; co/server</string>"));
+        assert!(plist.contains("<string>--config</string>"));
+    }
+}
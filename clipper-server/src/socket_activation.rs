@@ -0,0 +1,85 @@
+//! systemd socket activation. With a `clipper-server.socket` unit, systemd
+//! listens on the port and hands the socket over when the server starts
+//! (`LISTEN_PID`/`LISTEN_FDS`, see sd_listen_fds(3)). The server then needs
+//! no privileges to serve port 80, and connections made while it restarts
+//! wait instead of failing.
+
+use std::io;
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once the passed socket has been taken, so it's only wrapped once
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// The listening socket passed by the service manager, if there is one.
+/// Only the first call gets it.
+pub fn take_listener() -> io::Result<Option<TcpListener>> {
+    if TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+    let Some(count) = passed_socket_count(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+    ) else {
+        return Ok(None);
+    };
+    if count > 1 {
+        tracing::warn!(
+            "{} sockets passed by the service manager; only the first is used",
+            count
+        );
+    }
+    listener_from_fd()
+}
+
+/// How many sockets were passed to this process, given `LISTEN_PID` and
+/// `LISTEN_FDS`. They're meant for the process with that ID only, not for
+/// children that inherited the variables.
+fn passed_socket_count(listen_pid: Option<&str>, listen_fds: Option<&str>) -> Option<usize> {
+    let listen_pid: u32 = listen_pid?.trim().parse().ok()?;
+    if listen_pid != std::process::id() {
+        return None;
+    }
+    listen_fds?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|count: &usize| *count > 0)
+}
+
+#[cfg(unix)]
+fn listener_from_fd() -> io::Result<Option<TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+
+    /// The first passed socket, after stdin, stdout and stderr
+    const LISTEN_FDS_START: i32 = 3;
+
+    // SAFETY: the service manager passed an open socket as this descriptor,
+    // and `TAKEN` makes sure only one listener owns it
+    let listener = unsafe { TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    // Fails if the descriptor isn't a TCP socket after all
+    listener.local_addr()?;
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+fn listener_from_fd() -> io::Result<Option<TcpListener>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passed_socket_count() {
+        let pid = std::process::id().to_string();
+        assert_eq!(passed_socket_count(Some(&pid), Some("1")), Some(1));
+        assert_eq!(passed_socket_count(Some(&pid), Some("2")), Some(2));
+        assert_eq!(passed_socket_count(Some(&pid), Some("0")), None);
+        assert_eq!(passed_socket_count(Some(&pid), None), None);
+        assert_eq!(passed_socket_count(Some("1"), Some("1")), None);
+        assert_eq!(passed_socket_count(None, Some("1")), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! Splitting a clip into several for `POST /clips/{id}/split`
+//!
+//! A clip holding a list, such as URLs or TODOs copied as one blob, is cut
+//! into one part per line, per delimiter or per regex match. Parts are
+//! trimmed unless asked not to, and blank parts are dropped. The indexer
+//! stores the parts as new clips in one transaction.
+
+use regex::RegexBuilder;
+use serde::Deserialize;
+
+use crate::error::{Result, ServerError};
+
+/// Most parts one clip can be split into
+pub const MAX_PARTS: usize = 1000;
+
+/// Largest compiled regex, in bytes, so a pattern can't use much memory
+const MAX_REGEX_SIZE: usize = 1024 * 1024;
+
+/// Where a clip is split
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum Separator {
+    /// At line breaks, `\n` or `\r\n`
+    Lines,
+    /// At every occurrence of a string
+    Delimiter { delimiter: String },
+    /// At every match of a regular expression
+    Regex { pattern: String },
+}
+
+impl Separator {
+    /// Split `text` into its non-blank parts, trimmed if `trim` is set
+    ///
+    /// Fails with `InvalidInput` for an empty delimiter, an invalid regex or
+    /// more than `MAX_PARTS` parts.
+    pub fn split(&self, text: &str, trim: bool) -> Result<Vec<String>> {
+        let parts: Vec<&str> = match self {
+            Separator::Lines => text.lines().collect(),
+            Separator::Delimiter { delimiter } => {
+                if delimiter.is_empty() {
+                    return Err(ServerError::InvalidInput(
+                        "Delimiter cannot be empty".to_string(),
+                    ));
+                }
+                text.split(delimiter.as_str()).collect()
+            }
+            Separator::Regex { pattern } => {
+                let regex = RegexBuilder::new(pattern)
+                    .size_limit(MAX_REGEX_SIZE)
+                    .build()
+                    .map_err(|e| ServerError::InvalidInput(format!("Invalid regex: {}", e)))?;
+                regex.split(text).collect()
+            }
+        };
+
+        let parts: Vec<String> = parts
+            .into_iter()
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| if trim { part.trim() } else { part }.to_string())
+            .collect();
+        if parts.len() > MAX_PARTS {
+            return Err(ServerError::InvalidInput(format!(
+                "Clips can be split into at most {} parts",
+                MAX_PARTS
+            )));
+        }
+        Ok(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let text = "https://a.example\r\n\n  https://b.example  \n";
+        assert_eq!(
+            Separator::Lines.split(text, true).unwrap(),
+            ["https://a.example", "https://b.example"]
+        );
+        assert_eq!(
+            Separator::Lines.split(text, false).unwrap(),
+            ["https://a.example", "  https://b.example  "]
+        );
+
+        let delimiter = Separator::Delimiter {
+            delimiter: ";".to_string(),
+        };
+        assert_eq!(delimiter.split("a; b;;c", true).unwrap(), ["a", "b", "c"]);
+
+        let regex = Separator::Regex {
+            pattern: r"(?m)^- \[ \] ".to_string(),
+        };
+        assert_eq!(
+            regex.split("- [ ] milk\n- [ ] eggs\n", true).unwrap(),
+            ["milk", "eggs"]
+        );
+    }
+
+    #[test]
+    fn test_split_rejects_bad_separators() {
+        let empty = Separator::Delimiter {
+            delimiter: String::new(),
+        };
+        assert!(matches!(
+            empty.split("a", true),
+            Err(ServerError::InvalidInput(_))
+        ));
+        let invalid = Separator::Regex {
+            pattern: "(".to_string(),
+        };
+        assert!(matches!(
+            invalid.split("a", true),
+            Err(ServerError::InvalidInput(_))
+        ));
+        let text = "x\n".repeat(MAX_PARTS + 1);
+        assert!(matches!(
+            Separator::Lines.split(&text, true),
+            Err(ServerError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_separator_from_json() {
+        let separator: Separator =
+            serde_json::from_str(r#"{"by": "delimiter", "delimiter": ","}"#).unwrap();
+        assert_eq!(
+            separator,
+            Separator::Delimiter {
+                delimiter: ",".to_string()
+            }
+        );
+    }
+}
@@ -1,10 +1,16 @@
-use clipper_indexer::ClipperIndexer;
+use chrono::{DateTime, Utc};
+use clipper_indexer::{ClipboardEntry, ClipperIndexer, Visibility};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use tokio::sync::broadcast;
 
-use crate::config::ServerConfig;
+use crate::config::{ConfigReloader, ServerConfig, SharedConfig};
+use crate::pairing::PairingCodes;
+use crate::plugins::PluginManager;
+use crate::remote_clipboard::ConnectedDevices;
+#[cfg(feature = "tls")]
+use crate::tls::{CertificateExpiry, CertificateReloader};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -15,41 +21,85 @@ pub struct AppState {
     /// Number of active WebSocket connections
     pub ws_connection_count: Arc<AtomicUsize>,
     /// Server configuration
-    pub config: Arc<ServerConfig>,
+    pub config: SharedConfig,
+    /// Set by the server binary, which knows where the configuration came from
+    pub config_reloader: Arc<OnceLock<ConfigReloader>>,
+    /// Plugins run on clip events
+    pub plugins: Arc<PluginManager>,
+    /// Outstanding device pairing codes
+    pub pairing_codes: Arc<PairingCodes>,
+    /// IDs of revoked paired devices, so their WebSocket connections close
+    pub device_revocations: broadcast::Sender<String>,
+    /// Devices with an open WebSocket, for pushing clips to their clipboard
+    pub connected_devices: Arc<ConnectedDevices>,
+    /// Set by the HTTPS server when it serves a certificate from disk
+    #[cfg(feature = "tls")]
+    pub tls_reloader: Arc<OnceLock<CertificateReloader>>,
+    /// Expiry of the certificate the HTTPS server serves, checked by `/readyz`
+    #[cfg(feature = "tls")]
+    pub tls_expiry: CertificateExpiry,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClipUpdate {
+    /// `updated_at` is the server time the change was published, at or after
+    /// the clip's stored `updated_at`. Clients use it as the watermark for
+    /// recovering clips missed while disconnected.
     NewClip {
         id: String,
         content: String,
         tags: Vec<String>,
+        updated_at: DateTime<Utc>,
     },
     UpdatedClip {
         id: String,
+        updated_at: DateTime<Utc>,
     },
+    /// `visibility` is what the clip had, for hiding the deletion from
+    /// guests who couldn't see the clip; it isn't sent to clients.
     DeletedClip {
         id: String,
+        #[serde(skip)]
+        visibility: Visibility,
     },
+    /// `visibilities` holds the visibility of each of `ids`, likewise
     ClipsCleanedUp {
         ids: Vec<String>,
         count: usize,
+        #[serde(skip)]
+        visibilities: Vec<Visibility>,
     },
 }
 
 impl AppState {
     pub fn new(indexer: ClipperIndexer, config: ServerConfig) -> Self {
         let (tx, _) = broadcast::channel(100);
+        let (revocations_tx, _) = broadcast::channel(16);
         Self {
             indexer: Arc::new(indexer),
             clip_updates: tx,
             start_time: Instant::now(),
             ws_connection_count: Arc::new(AtomicUsize::new(0)),
-            config: Arc::new(config),
+            config: SharedConfig::new(config),
+            config_reloader: Arc::new(OnceLock::new()),
+            plugins: Arc::new(PluginManager::default()),
+            pairing_codes: Arc::new(PairingCodes::default()),
+            device_revocations: revocations_tx,
+            connected_devices: Arc::new(ConnectedDevices::default()),
+            #[cfg(feature = "tls")]
+            tls_reloader: Arc::new(OnceLock::new()),
+            #[cfg(feature = "tls")]
+            tls_expiry: CertificateExpiry::default(),
         }
     }
 
+    /// Use `plugins` for clip event hooks
+    pub fn with_plugins(mut self, plugins: PluginManager) -> Self {
+        self.plugins = Arc::new(plugins);
+        self
+    }
+
     /// Get uptime in seconds
     pub fn uptime_secs(&self) -> u64 {
         self.start_time.elapsed().as_secs()
@@ -71,23 +121,38 @@ impl AppState {
     }
 
     pub fn notify_new_clip(&self, id: String, content: String, tags: Vec<String>) {
-        let _ = self
-            .clip_updates
-            .send(ClipUpdate::NewClip { id, content, tags });
+        let _ = self.clip_updates.send(ClipUpdate::NewClip {
+            id,
+            content,
+            tags,
+            updated_at: Utc::now(),
+        });
     }
 
     pub fn notify_updated_clip(&self, id: String) {
-        let _ = self.clip_updates.send(ClipUpdate::UpdatedClip { id });
-    }
-
-    pub fn notify_deleted_clip(&self, id: String) {
-        let _ = self.clip_updates.send(ClipUpdate::DeletedClip { id });
+        let _ = self.clip_updates.send(ClipUpdate::UpdatedClip {
+            id,
+            updated_at: Utc::now(),
+        });
     }
 
-    pub fn notify_clips_cleaned_up(&self, ids: Vec<String>) {
-        let count = ids.len();
+    pub fn notify_deleted_clip(&self, id: String, visibility: Visibility) {
         let _ = self
             .clip_updates
-            .send(ClipUpdate::ClipsCleanedUp { ids, count });
+            .send(ClipUpdate::DeletedClip { id, visibility });
+    }
+
+    pub fn notify_device_revoked(&self, id: String) {
+        let _ = self.device_revocations.send(id);
+    }
+
+    pub fn notify_clips_cleaned_up(&self, entries: &[ClipboardEntry]) {
+        let ids = entries.iter().map(|entry| entry.id.clone()).collect();
+        let visibilities = entries.iter().map(|entry| entry.visibility).collect();
+        let _ = self.clip_updates.send(ClipUpdate::ClipsCleanedUp {
+            ids,
+            count: entries.len(),
+            visibilities,
+        });
     }
 }
@@ -5,7 +5,7 @@
 //! certificate management.
 
 #[cfg(feature = "tls")]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 #[cfg(feature = "tls")]
 use std::sync::Arc;
 
@@ -130,16 +130,160 @@ impl TlsManager {
     }
 }
 
+/// When the certificate being served expires.
+///
+/// The HTTPS server updates it whenever it loads a certificate, so `/readyz`
+/// can report one that is about to expire, e.g. because renewal is failing.
+#[cfg(feature = "tls")]
+#[derive(Clone, Default)]
+pub struct CertificateExpiry(Arc<std::sync::RwLock<Option<chrono::DateTime<chrono::Utc>>>>);
+
+#[cfg(feature = "tls")]
+impl CertificateExpiry {
+    /// Record the expiry of the first certificate in a PEM chain
+    pub fn update(&self, cert_pem: &str) {
+        let not_after = certificate_not_after(cert_pem);
+        if not_after.is_none() {
+            tracing::warn!("Could not read the expiry date of the TLS certificate");
+        }
+        *self
+            .0
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = not_after;
+    }
+
+    /// Record the expiry of the certificate in a PEM file
+    pub async fn update_from_file(&self, cert_path: &Path) {
+        match tokio::fs::read_to_string(cert_path).await {
+            Ok(cert_pem) => self.update(&cert_pem),
+            Err(e) => tracing::warn!(
+                "Could not read {} for its expiry date: {}",
+                cert_path.display(),
+                e
+            ),
+        }
+    }
+
+    /// When the certificate expires, or `None` if no certificate has been
+    /// loaded (or its expiry couldn't be read)
+    pub fn not_after(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        *self
+            .0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Expiry of the first certificate in a PEM chain
+#[cfg(feature = "tls")]
+pub fn certificate_not_after(cert_pem: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use x509_parser::prelude::*;
+
+    let (_, pem) = x509_parser::pem::parse_x509_pem(cert_pem.as_bytes()).ok()?;
+    let (_, cert) = X509Certificate::from_der(&pem.contents).ok()?;
+    chrono::DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+}
+
+/// Reloads a manually managed certificate from disk on demand.
+///
+/// The HTTPS server installs one in `AppState::tls_reloader` when
+/// `tls.cert_path` and `tls.key_path` are set, so `POST /admin/reload-tls`
+/// and SIGHUP can pick up a renewed certificate right away instead of waiting
+/// for the periodic reload.
+#[cfg(feature = "tls")]
+#[derive(Clone)]
+pub struct CertificateReloader {
+    config: RustlsConfig,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    expiry: CertificateExpiry,
+}
+
+#[cfg(feature = "tls")]
+impl CertificateReloader {
+    /// `expiry` is updated whenever a certificate is reloaded
+    pub fn new(
+        config: RustlsConfig,
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        expiry: CertificateExpiry,
+    ) -> Self {
+        Self {
+            config,
+            cert_path,
+            key_path,
+            expiry,
+        }
+    }
+
+    /// Load the certificate and key files again. On failure the server keeps
+    /// using the current certificate.
+    pub async fn reload(&self) -> TlsResult<()> {
+        tracing::info!(
+            "Reloading TLS certificate from {} and key from {}",
+            self.cert_path.display(),
+            self.key_path.display()
+        );
+
+        self.config
+            .reload_from_pem_file(&self.cert_path, &self.key_path)
+            .await
+            .map_err(|e| TlsError::Configuration(e.to_string()))?;
+        self.expiry.update_from_file(&self.cert_path).await;
+
+        tracing::info!("TLS certificate reloaded successfully");
+        Ok(())
+    }
+}
+
+/// SHA-256 fingerprint of the first certificate in a PEM chain, formatted as
+/// uppercase hex pairs separated by colons (e.g. "AB:CD:EF:...").
+///
+/// This matches the fingerprints clients show and store when trusting a
+/// self-signed certificate.
+#[cfg(feature = "tls")]
+pub fn certificate_fingerprint(cert_pem: &str) -> Option<String> {
+    use sha2::{Digest, Sha256};
+
+    let cert = rustls_pemfile::certs(&mut cert_pem.as_bytes())
+        .next()?
+        .ok()?;
+    let digest = Sha256::digest(cert.as_ref());
+    Some(
+        digest
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+/// How long a self-signed certificate is valid. Apple platforms reject
+/// server certificates valid for more than 825 days.
+#[cfg(feature = "acme")]
+const SELF_SIGNED_VALIDITY_DAYS: i64 = 825;
+
+/// A stored self-signed certificate is replaced when it has fewer days left
+#[cfg(feature = "acme")]
+const SELF_SIGNED_RENEWAL_DAYS: i64 = 30;
+
+/// Name the self-signed certificate is kept under in the certificate storage
+#[cfg(feature = "acme")]
+const SELF_SIGNED_STORAGE_NAME: &str = "self-signed";
+
 /// Generate a self-signed certificate for development/testing.
 ///
-/// This is useful for local development when you don't have a real certificate.
+/// Besides `domain`, the certificate covers the machine's hostname (and its
+/// `.local` mDNS name), `localhost` and the loopback and LAN addresses, so
+/// mobile clients on the LAN accept it for whichever name they connect to.
 #[cfg(feature = "acme")]
 pub fn generate_self_signed_cert(domain: &str) -> TlsResult<(String, String)> {
-    use rcgen::{CertificateParams, DnType, KeyPair};
+    use rcgen::{date_time_ymd, CertificateParams, DnType, ExtendedKeyUsagePurpose, KeyPair};
 
     tracing::info!("Generating self-signed certificate for {}", domain);
 
-    let mut params = CertificateParams::new(vec![domain.to_string()])
+    // Names that parse as IP addresses become IP address SANs
+    let mut params = CertificateParams::new(self_signed_names(domain))
         .map_err(|e| TlsError::Configuration(e.to_string()))?;
     params
         .distinguished_name
@@ -147,6 +291,15 @@ pub fn generate_self_signed_cert(domain: &str) -> TlsResult<(String, String)> {
     params
         .distinguished_name
         .push(DnType::OrganizationName, "Clipper Self-Signed");
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+
+    let date = |date: chrono::DateTime<chrono::Utc>| {
+        use chrono::Datelike;
+        date_time_ymd(date.year(), date.month() as u8, date.day() as u8)
+    };
+    let now = chrono::Utc::now();
+    params.not_before = date(now - chrono::Duration::days(1));
+    params.not_after = date(now + chrono::Duration::days(SELF_SIGNED_VALIDITY_DAYS - 1));
 
     let key_pair = KeyPair::generate().map_err(|e| TlsError::Configuration(e.to_string()))?;
     let cert = params
@@ -161,6 +314,147 @@ pub fn generate_self_signed_cert(domain: &str) -> TlsResult<(String, String)> {
     Ok((cert_pem, key_pem))
 }
 
+/// Load the self-signed certificate kept in `storage`, or generate and store
+/// a new one.
+///
+/// Reusing the certificate keeps its fingerprint stable, so clients that
+/// pinned it keep working across restarts. It is only replaced when it is
+/// about to expire or doesn't cover `domain`; delete it from the
+/// certificate directory to pick up new LAN addresses.
+#[cfg(feature = "acme")]
+pub fn load_or_generate_self_signed_cert(
+    domain: &str,
+    storage: &dyn crate::cert_storage::CertStorage,
+) -> TlsResult<(String, String)> {
+    let storage_error = |e: crate::cert_storage::StorageError| {
+        TlsError::Configuration(format!("Certificate storage: {}", e))
+    };
+
+    if let (Some(cert_pem), Some(key_pem)) = (
+        storage
+            .load_certificate(SELF_SIGNED_STORAGE_NAME)
+            .map_err(storage_error)?,
+        storage
+            .load_private_key(SELF_SIGNED_STORAGE_NAME)
+            .map_err(storage_error)?,
+    ) {
+        match self_signed_cert_names(&cert_pem) {
+            Some((names, days_remaining))
+                if days_remaining >= SELF_SIGNED_RENEWAL_DAYS
+                    && names.contains(&domain.to_ascii_lowercase()) =>
+            {
+                let missing: Vec<String> = self_signed_names(domain)
+                    .into_iter()
+                    .filter(|name| !names.contains(name))
+                    .collect();
+                if !missing.is_empty() {
+                    tracing::info!(
+                        "Stored self-signed certificate doesn't cover {}; delete it to generate a new one",
+                        missing.join(", ")
+                    );
+                }
+                tracing::info!("Using stored self-signed certificate");
+                return Ok((cert_pem, key_pem));
+            }
+            _ => tracing::info!("Stored self-signed certificate is expiring or outdated"),
+        }
+    }
+
+    let (cert_pem, key_pem) = generate_self_signed_cert(domain)?;
+    storage
+        .store_certificate(SELF_SIGNED_STORAGE_NAME, &cert_pem)
+        .map_err(storage_error)?;
+    storage
+        .store_private_key(SELF_SIGNED_STORAGE_NAME, &key_pem)
+        .map_err(storage_error)?;
+
+    Ok((cert_pem, key_pem))
+}
+
+/// Names for a self-signed certificate for `domain`, lower case and without
+/// duplicates
+#[cfg(feature = "acme")]
+fn self_signed_names(domain: &str) -> Vec<String> {
+    use std::net::IpAddr;
+
+    let mut candidates = vec![
+        domain.to_ascii_lowercase(),
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+
+    // The hostname goes in as a DNS name, so skip it if it can't be one
+    let hostname = gethostname::gethostname()
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    if !hostname.is_empty()
+        && hostname
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    {
+        candidates.push(hostname.clone());
+        candidates.push(format!("{}.local", hostname.trim_end_matches(".local")));
+    }
+
+    // LAN addresses, skipping link-local ones that change with every boot
+    if let Ok(interfaces) = local_ip_address::list_afinet_netifas() {
+        for (_, ip) in interfaces {
+            let usable = match ip {
+                IpAddr::V4(ipv4) => !ipv4.is_loopback() && !ipv4.is_link_local(),
+                IpAddr::V6(ipv6) => !ipv6.is_loopback() && !ipv6.is_unicast_link_local(),
+            };
+            if usable {
+                candidates.push(ip.to_string());
+            }
+        }
+    }
+
+    let mut names = Vec::new();
+    for name in candidates {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The subject alternative names (lower case) of the first certificate in a
+/// PEM chain, and the days until it expires
+#[cfg(feature = "acme")]
+fn self_signed_cert_names(cert_pem: &str) -> Option<(Vec<String>, i64)> {
+    use x509_parser::prelude::*;
+
+    let (_, pem) = x509_parser::pem::parse_x509_pem(cert_pem.as_bytes()).ok()?;
+    let (_, cert) = X509Certificate::from_der(&pem.contents).ok()?;
+
+    let names = match cert.subject_alternative_name().ok()? {
+        Some(san) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(name) => Some(name.to_ascii_lowercase()),
+                GeneralName::IPAddress(bytes) => match bytes.len() {
+                    4 => <[u8; 4]>::try_from(*bytes)
+                        .ok()
+                        .map(|octets| std::net::IpAddr::from(octets).to_string()),
+                    16 => <[u8; 16]>::try_from(*bytes)
+                        .ok()
+                        .map(|octets| std::net::IpAddr::from(octets).to_string()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let days_remaining =
+        (cert.validity().not_after.timestamp() - chrono::Utc::now().timestamp()) / 86400;
+    Some((names, days_remaining))
+}
+
 /// Shared state for TLS configuration that can be hot-reloaded.
 #[cfg(feature = "tls")]
 #[derive(Clone)]
@@ -204,4 +498,58 @@ mod tests {
         assert!(key_pem.contains("BEGIN PRIVATE KEY"));
         assert!(key_pem.contains("END PRIVATE KEY"));
     }
+
+    #[test]
+    fn test_self_signed_cert_names() {
+        let (cert_pem, _) = generate_self_signed_cert("Clipper.example.com").unwrap();
+
+        let (names, days_remaining) = self_signed_cert_names(&cert_pem).unwrap();
+        assert!(names.contains(&"clipper.example.com".to_string()));
+        assert!(names.contains(&"localhost".to_string()));
+        assert!(names.contains(&"127.0.0.1".to_string()));
+        assert!(names.contains(&"::1".to_string()));
+        assert!(days_remaining > SELF_SIGNED_VALIDITY_DAYS - 3);
+        assert!(days_remaining < SELF_SIGNED_VALIDITY_DAYS);
+    }
+
+    #[test]
+    fn test_self_signed_cert_is_stored() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::cert_storage::FileStorage::new(temp_dir.path().to_path_buf());
+
+        let (cert_pem, key_pem) = load_or_generate_self_signed_cert("localhost", &storage).unwrap();
+        assert_eq!(
+            load_or_generate_self_signed_cert("localhost", &storage).unwrap(),
+            (cert_pem.clone(), key_pem)
+        );
+
+        // A different domain needs a new certificate
+        let (other_pem, _) = load_or_generate_self_signed_cert("example.com", &storage).unwrap();
+        assert_ne!(other_pem, cert_pem);
+    }
+
+    #[test]
+    fn test_certificate_fingerprint() {
+        let (cert_pem, _) = generate_self_signed_cert("localhost").unwrap();
+
+        let fingerprint = certificate_fingerprint(&cert_pem).unwrap();
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+        assert_eq!(fingerprint, fingerprint.to_uppercase());
+        assert!(certificate_fingerprint("not a certificate").is_none());
+    }
+
+    #[test]
+    fn test_certificate_expiry() {
+        let (cert_pem, _) = generate_self_signed_cert("localhost").unwrap();
+
+        let expiry = CertificateExpiry::default();
+        assert_eq!(expiry.not_after(), None);
+        expiry.update(&cert_pem);
+        let days_remaining = (expiry.not_after().unwrap() - chrono::Utc::now()).num_days();
+        assert!(days_remaining > SELF_SIGNED_VALIDITY_DAYS - 3);
+        assert!(days_remaining < SELF_SIGNED_VALIDITY_DAYS);
+
+        expiry.update("not a certificate");
+        assert_eq!(expiry.not_after(), None);
+    }
 }
@@ -0,0 +1,128 @@
+//! Built-in text transforms for `POST /clips/{id}/transform`
+//!
+//! A request lists one or more transforms that are applied in order, so
+//! `["trim", "base64_encode"]` trims the clip before encoding it. The result
+//! is stored as a new clip; the source clip is left unchanged.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, ServerError};
+
+/// A built-in text transform
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Uppercase,
+    Lowercase,
+    /// Remove leading and trailing whitespace
+    Trim,
+    Base64Encode,
+    /// Decode standard base64; the decoded bytes must be UTF-8 text
+    Base64Decode,
+    /// Re-indent JSON, keeping the key order
+    JsonPretty,
+    /// Remove insignificant whitespace from JSON
+    JsonMinify,
+    /// Percent-encode everything except unreserved characters
+    UrlEncode,
+    UrlDecode,
+}
+
+impl Transform {
+    /// Apply the transform to `text`
+    ///
+    /// Fails with `InvalidInput` if the text can't be decoded or parsed.
+    pub fn apply(self, text: &str) -> Result<String> {
+        match self {
+            Transform::Uppercase => Ok(text.to_uppercase()),
+            Transform::Lowercase => Ok(text.to_lowercase()),
+            Transform::Trim => Ok(text.trim().to_string()),
+            Transform::Base64Encode => Ok(BASE64.encode(text)),
+            Transform::Base64Decode => {
+                let bytes = BASE64
+                    .decode(text.trim())
+                    .map_err(|e| ServerError::InvalidInput(format!("Invalid base64: {}", e)))?;
+                String::from_utf8(bytes).map_err(|_| {
+                    ServerError::InvalidInput("Decoded base64 is not UTF-8 text".to_string())
+                })
+            }
+            Transform::JsonPretty => {
+                serde_json::to_string_pretty(&parse_json(text)?).map_err(json_error)
+            }
+            Transform::JsonMinify => serde_json::to_string(&parse_json(text)?).map_err(json_error),
+            Transform::UrlEncode => Ok(urlencoding::encode(text).into_owned()),
+            Transform::UrlDecode => urlencoding::decode(text)
+                .map(|decoded| decoded.into_owned())
+                .map_err(|_| {
+                    ServerError::InvalidInput("URL-decoded text is not UTF-8".to_string())
+                }),
+        }
+    }
+}
+
+/// Apply `transforms` to `text` in order
+pub fn apply_all(transforms: &[Transform], text: &str) -> Result<String> {
+    transforms
+        .iter()
+        .try_fold(text.to_string(), |text, transform| transform.apply(&text))
+}
+
+fn parse_json(text: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(text)
+        .map_err(|e| ServerError::InvalidInput(format!("Invalid JSON: {}", e)))
+}
+
+fn json_error(e: serde_json::Error) -> ServerError {
+    ServerError::Internal(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_all() {
+        assert_eq!(
+            apply_all(&[Transform::Trim, Transform::Uppercase], "  hello \n").unwrap(),
+            "HELLO"
+        );
+        assert_eq!(
+            apply_all(&[Transform::Base64Encode, Transform::Base64Decode], "héllo").unwrap(),
+            "héllo"
+        );
+        assert_eq!(
+            Transform::UrlEncode.apply("a b&c=d").unwrap(),
+            "a%20b%26c%3Dd"
+        );
+        assert_eq!(Transform::UrlDecode.apply("a%20b").unwrap(), "a b");
+    }
+
+    #[test]
+    fn test_json_keeps_key_order() {
+        let pretty = Transform::JsonPretty
+            .apply(r#"{"b":1,"a":[true]}"#)
+            .unwrap();
+        assert_eq!(pretty, "{\n  \"b\": 1,\n  \"a\": [\n    true\n  ]\n}");
+        assert_eq!(
+            Transform::JsonMinify.apply(&pretty).unwrap(),
+            r#"{"b":1,"a":[true]}"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        for (transform, text) in [
+            (Transform::Base64Decode, "not base64!"),
+            (Transform::Base64Decode, "/w=="),
+            (Transform::JsonPretty, "{"),
+            (Transform::UrlDecode, "%ff"),
+        ] {
+            assert!(matches!(
+                transform.apply(text),
+                Err(ServerError::InvalidInput(_))
+            ));
+        }
+    }
+}
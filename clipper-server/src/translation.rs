@@ -0,0 +1,216 @@
+//! Translating clips for `POST /clips/{id}/translate`
+//!
+//! The clip is sent to the provider set in `[translation]`: a LibreTranslate
+//! instance or the DeepL API. Target languages are ISO 639-1 codes such as
+//! `de`, optionally with a region or script such as `pt-BR` or `zh-Hans`;
+//! the provider works out the source language.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    config::{TranslationConfig, TranslationProvider},
+    error::{Result, ServerError},
+};
+
+/// A clip's text in another language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+    pub text: String,
+    /// Language the provider detected the text to be in, lowercase
+    pub source_language: Option<String>,
+}
+
+/// Check that `code` looks like a language code: two or three letters,
+/// optionally followed by `-` and a region or script
+pub fn validate_language_code(code: &str) -> Result<()> {
+    let mut parts = code.split('-');
+    let language = parts.next().unwrap_or_default();
+    let valid = (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (2..=4).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return Err(ServerError::InvalidInput(format!(
+            "Invalid language code: {:?}",
+            code
+        )));
+    }
+    Ok(())
+}
+
+/// Translate `text` into the language `target` with the configured provider
+///
+/// Fails with `FeatureDisabled` when no provider is set and with
+/// `TranslationFailed` when the provider can't be reached or returns an
+/// error.
+pub async fn translate(
+    config: &TranslationConfig,
+    text: &str,
+    target: &str,
+) -> Result<Translation> {
+    let (url, body) = match config.provider {
+        TranslationProvider::None => {
+            return Err(ServerError::FeatureDisabled(
+                "Translation is not configured on this server".to_string(),
+            ));
+        }
+        TranslationProvider::LibreTranslate => {
+            let mut body = json!({
+                "q": text,
+                "source": "auto",
+                "target": target.to_lowercase(),
+                "format": "text",
+            });
+            if let Some(api_key) = &config.api_key {
+                body["api_key"] = api_key.expose().into();
+            }
+            (format!("{}/translate", config.endpoint()), body)
+        }
+        TranslationProvider::DeepL => (
+            format!("{}/v2/translate", config.endpoint()),
+            json!({
+                "text": [text],
+                "target_lang": target.to_uppercase(),
+            }),
+        ),
+    };
+
+    let mut request = reqwest::Client::new()
+        .post(&url)
+        .timeout(config.timeout())
+        .header("content-type", "application/json")
+        .body(body.to_string());
+    if config.provider == TranslationProvider::DeepL
+        && let Some(api_key) = &config.api_key
+    {
+        request = request.header(
+            "authorization",
+            format!("DeepL-Auth-Key {}", api_key.expose()),
+        );
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ServerError::TranslationFailed(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(ServerError::TranslationFailed(format!(
+            "Provider returned {}: {}",
+            status.as_u16(),
+            text.trim()
+        )));
+    }
+    match config.provider {
+        TranslationProvider::DeepL => parse_deepl(&text),
+        _ => parse_libretranslate(&text),
+    }
+}
+
+fn parse_libretranslate(body: &str) -> Result<Translation> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Response {
+        translated_text: String,
+        #[serde(default)]
+        detected_language: Option<DetectedLanguage>,
+    }
+
+    #[derive(Deserialize)]
+    struct DetectedLanguage {
+        language: String,
+    }
+
+    let response: Response = serde_json::from_str(body)
+        .map_err(|e| ServerError::TranslationFailed(format!("Invalid response: {}", e)))?;
+    Ok(Translation {
+        text: response.translated_text,
+        source_language: response
+            .detected_language
+            .map(|detected| detected.language.to_lowercase()),
+    })
+}
+
+fn parse_deepl(body: &str) -> Result<Translation> {
+    #[derive(Deserialize)]
+    struct Response {
+        translations: Vec<DeepLTranslation>,
+    }
+
+    #[derive(Deserialize)]
+    struct DeepLTranslation {
+        text: String,
+        #[serde(default)]
+        detected_source_language: Option<String>,
+    }
+
+    let response: Response = serde_json::from_str(body)
+        .map_err(|e| ServerError::TranslationFailed(format!("Invalid response: {}", e)))?;
+    let translation =
+        response.translations.into_iter().next().ok_or_else(|| {
+            ServerError::TranslationFailed("Response has no translation".to_string())
+        })?;
+    Ok(Translation {
+        text: translation.text,
+        source_language: translation
+            .detected_source_language
+            .map(|language| language.to_lowercase()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_language_code() {
+        for code in ["en", "DE", "pt-BR", "zh-Hans", "fil"] {
+            assert!(validate_language_code(code).is_ok(), "{}", code);
+        }
+        for code in ["", "e", "english", "en-", "en_US", "12", "en-US-x-private"] {
+            assert!(
+                matches!(
+                    validate_language_code(code),
+                    Err(ServerError::InvalidInput(_))
+                ),
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let translation = parse_libretranslate(
+            r#"{"translatedText": "Hallo Welt", "detectedLanguage": {"confidence": 90, "language": "en"}}"#,
+        )
+        .unwrap();
+        assert_eq!(translation.text, "Hallo Welt");
+        assert_eq!(translation.source_language.as_deref(), Some("en"));
+        assert_eq!(
+            parse_libretranslate(r#"{"translatedText": "Hallo"}"#)
+                .unwrap()
+                .source_language,
+            None
+        );
+
+        let translation = parse_deepl(
+            r#"{"translations": [{"detected_source_language": "EN", "text": "Hallo Welt"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(translation.text, "Hallo Welt");
+        assert_eq!(translation.source_language.as_deref(), Some("en"));
+
+        assert!(matches!(
+            parse_deepl(r#"{"translations": []}"#),
+            Err(ServerError::TranslationFailed(_))
+        ));
+        assert!(matches!(
+            parse_libretranslate(r#"{"error": "Invalid target language"}"#),
+            Err(ServerError::TranslationFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,608 @@
+//! Web Push notifications for the web UI.
+//!
+//! The web UI's service worker subscribes with the browser's push service
+//! and registers the subscription here. While a VAPID key is configured, a
+//! background task sends every subscription a notification when a clip is
+//! created, so new clips are announced even when no Clipper tab is open.
+//! Payloads are encrypted as described in RFC 8291 and requests are signed
+//! with VAPID (RFC 8292).
+
+use std::time::Duration;
+
+use aes_gcm::{aead::Aead, Aes128Gcm, KeyInit};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use clipper_indexer::WebPushSubscription;
+use hkdf::Hkdf;
+use p256::{
+    ecdsa::{signature::Signer, Signature, SigningKey},
+    elliptic_curve::sec1::ToEncodedPoint,
+    PublicKey, SecretKey,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    config::WebPushConfig,
+    error::{Result, ServerError},
+    state::{AppState, ClipUpdate},
+};
+
+/// How long push services keep a notification for an offline browser
+const NOTIFICATION_TTL_SECS: u64 = 24 * 3600;
+/// Longest clip preview in a notification, in characters
+const PREVIEW_CHARS: usize = 120;
+/// Lifetime of a VAPID token; push services reject tokens valid for more
+/// than 24 hours
+const VAPID_TOKEN_TTL_SECS: i64 = 12 * 3600;
+/// Record size in the aes128gcm header. Notifications are sent as a single
+/// record, so this also bounds the payload.
+const RECORD_SIZE: u32 = 4096;
+/// Length of an uncompressed P-256 public key
+const PUBLIC_KEY_LENGTH: usize = 65;
+/// Length of a subscription's authentication secret
+const AUTH_SECRET_LENGTH: usize = 16;
+/// Timeout for one request to a push service
+const PUSH_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/web-push/vapid-public-key", get(vapid_public_key))
+        .route(
+            "/web-push/subscriptions",
+            post(subscribe).delete(unsubscribe),
+        )
+}
+
+/// The server's VAPID key pair, which identifies it to push services
+#[derive(Clone)]
+pub struct VapidKey {
+    signing_key: SigningKey,
+    /// Uncompressed public key, base64url encoded. Browsers subscribe with
+    /// it as the `applicationServerKey`.
+    public_key: String,
+}
+
+impl VapidKey {
+    /// Load a raw P-256 private key, base64url encoded as printed by
+    /// `npx web-push generate-vapid-keys`
+    pub fn from_base64(private_key: &str) -> Result<Self> {
+        let signing_key = URL_SAFE_NO_PAD
+            .decode(private_key.trim().trim_end_matches('='))
+            .ok()
+            .and_then(|bytes| SigningKey::from_slice(&bytes).ok())
+            .ok_or_else(|| {
+                ServerError::InvalidInput(
+                    "VAPID private key must be a base64url encoded P-256 private key".to_string(),
+                )
+            })?;
+        let public_key =
+            URL_SAFE_NO_PAD.encode(signing_key.verifying_key().to_encoded_point(false));
+
+        Ok(Self {
+            signing_key,
+            public_key,
+        })
+    }
+
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// `Authorization` header for a request to `endpoint`: a signed JWT
+    /// for the endpoint's origin and the public key to check it with
+    fn authorization(&self, endpoint: &str, subject: Option<&str>) -> Result<String> {
+        let mut claims = serde_json::json!({
+            "aud": endpoint_origin(endpoint)?,
+            "exp": Utc::now().timestamp() + VAPID_TOKEN_TTL_SECS,
+        });
+        if let Some(subject) = subject {
+            claims["sub"] = subject.into();
+        }
+
+        let header = serde_json::json!({ "typ": "JWT", "alg": "ES256" });
+        Ok(format!(
+            "vapid t={}, k={}",
+            sign_es256_jwt(&self.signing_key, &header, &claims),
+            self.public_key
+        ))
+    }
+}
+
+/// Sign a compact JWT with ES256 (ECDSA on P-256 with SHA-256)
+pub(crate) fn sign_es256_jwt(
+    key: &SigningKey,
+    header: &serde_json::Value,
+    claims: &serde_json::Value,
+) -> String {
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature: Signature = key.sign(signing_input.as_bytes());
+    format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes())
+    )
+}
+
+/// Outcome of sending a notification to one subscription or device
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    Sent,
+    /// The push service no longer knows the subscription or device token,
+    /// e.g. because the browser unsubscribed or the app was uninstalled
+    Expired,
+}
+
+/// Sends notifications to Web Push subscriptions
+#[derive(Clone)]
+pub struct WebPushSender {
+    key: VapidKey,
+    subject: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebPushSender {
+    /// Create a sender from the configuration, or None if Web Push is not
+    /// configured
+    pub fn from_config(config: &WebPushConfig) -> Result<Option<Self>> {
+        if !config.is_enabled() {
+            return Ok(None);
+        }
+        let key = VapidKey::from_base64(config.vapid_private_key.as_deref().unwrap_or_default())?;
+
+        let client = reqwest::Client::builder()
+            .timeout(PUSH_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ServerError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Some(Self {
+            key,
+            subject: config.subject.clone(),
+            client,
+        }))
+    }
+
+    /// Encrypt `payload` for `subscription` and post it to its push service
+    async fn send(&self, subscription: &WebPushSubscription, payload: &[u8]) -> Result<Delivery> {
+        let (ua_public, auth_secret) = decode_keys(&subscription.p256dh, &subscription.auth)?;
+        let body = encrypt(payload, &ua_public, &auth_secret)?;
+        let authorization = self
+            .key
+            .authorization(&subscription.endpoint, self.subject.as_deref())?;
+
+        let response = self
+            .client
+            .post(&subscription.endpoint)
+            .header("Authorization", authorization)
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("TTL", NOTIFICATION_TTL_SECS.to_string())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ServerError::Internal(format!("Push request failed: {}", e)))?;
+
+        match response.status().as_u16() {
+            200..=299 => Ok(Delivery::Sent),
+            404 | 410 => Ok(Delivery::Expired),
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                Err(ServerError::Internal(format!(
+                    "Push service returned {}: {}",
+                    status,
+                    text.trim()
+                )))
+            }
+        }
+    }
+}
+
+/// Send a notification to every subscription for each new clip. Runs until
+/// the clip update channel is closed.
+pub async fn run_web_push_dispatcher(state: AppState, sender: WebPushSender) {
+    let mut updates = state.clip_updates.subscribe();
+    tracing::info!("Web Push notifications enabled");
+
+    loop {
+        match updates.recv().await {
+            Ok(ClipUpdate::NewClip { id, content, .. }) => {
+                // Sending can be slow, so don't hold up the next update
+                tokio::spawn(notify_subscribers(
+                    state.clone(),
+                    sender.clone(),
+                    notification_payload(&id, &content),
+                ));
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Web Push dispatcher skipped {} clip updates", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn notify_subscribers(state: AppState, sender: WebPushSender, payload: Vec<u8>) {
+    let subscriptions = match state.indexer.list_web_push_subscriptions().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            tracing::error!("Failed to list Web Push subscriptions: {}", e);
+            return;
+        }
+    };
+
+    let (sender, payload) = (&sender, &payload);
+    let sends = subscriptions.iter().map(|subscription| async move {
+        (subscription, sender.send(subscription, payload).await)
+    });
+    for (subscription, result) in futures::future::join_all(sends).await {
+        match result {
+            Ok(Delivery::Sent) => {}
+            Ok(Delivery::Expired) => {
+                tracing::info!("Removing expired Web Push subscription {}", subscription.id);
+                if let Err(e) = state
+                    .indexer
+                    .delete_web_push_subscription(&subscription.endpoint)
+                    .await
+                {
+                    tracing::warn!("Failed to remove Web Push subscription: {}", e);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to send Web Push notification to {}: {}",
+                    subscription.id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// The notification sent for a new clip, read by the web UI's service worker
+fn notification_payload(id: &str, content: &str) -> Vec<u8> {
+    serde_json::json!({ "type": "new_clip", "id": id, "preview": notification_preview(content) })
+        .to_string()
+        .into_bytes()
+}
+
+/// The start of a clip on one line, shortened with an ellipsis to fit in a
+/// notification
+pub(crate) fn notification_preview(content: &str) -> String {
+    let mut preview: String = content
+        .split_whitespace()
+        .flat_map(|word| std::iter::once(' ').chain(word.chars()))
+        .skip(1)
+        .take(PREVIEW_CHARS + 1)
+        .collect();
+    if preview.chars().count() > PREVIEW_CHARS {
+        preview = preview.chars().take(PREVIEW_CHARS - 1).collect();
+        preview.push('…');
+    }
+    preview
+}
+
+/// `scheme://host[:port]` of a push endpoint, the audience of VAPID tokens
+fn endpoint_origin(endpoint: &str) -> Result<&str> {
+    let host_start = endpoint
+        .find("://")
+        .map(|i| i + 3)
+        .ok_or_else(|| ServerError::InvalidInput(format!("Invalid push endpoint: {}", endpoint)))?;
+    let host_end = endpoint[host_start..]
+        .find(['/', '?', '#'])
+        .map_or(endpoint.len(), |i| host_start + i);
+    Ok(&endpoint[..host_end])
+}
+
+/// Decode and check a subscription's `p256dh` public key and `auth` secret
+fn decode_keys(p256dh: &str, auth: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    let decode = |key: &str| {
+        URL_SAFE_NO_PAD
+            .decode(key.trim().trim_end_matches('='))
+            .ok()
+    };
+
+    let ua_public = decode(p256dh)
+        .filter(|key| key.len() == PUBLIC_KEY_LENGTH && PublicKey::from_sec1_bytes(key).is_ok())
+        .ok_or_else(|| {
+            ServerError::InvalidInput("p256dh must be an uncompressed P-256 public key".to_string())
+        })?;
+    let auth_secret = decode(auth)
+        .filter(|secret| secret.len() == AUTH_SECRET_LENGTH)
+        .ok_or_else(|| {
+            ServerError::InvalidInput(format!("auth must be a {} byte secret", AUTH_SECRET_LENGTH))
+        })?;
+
+    Ok((ua_public, auth_secret))
+}
+
+/// Encrypt `payload` with the aes128gcm content coding, using a new key
+/// pair and salt for every message
+fn encrypt(payload: &[u8], ua_public: &[u8], auth_secret: &[u8]) -> Result<Vec<u8>> {
+    let mut rng = rand::rng();
+    let as_secret = loop {
+        let mut bytes = [0u8; 32];
+        rng.fill(&mut bytes);
+        // Fails only for the rare values outside the curve order
+        if let Ok(secret) = SecretKey::from_slice(&bytes) {
+            break secret;
+        }
+    };
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt);
+
+    encrypt_with(payload, ua_public, auth_secret, &as_secret, &salt)
+}
+
+/// Encrypt `payload` as a single aes128gcm record (RFC 8291 section 3),
+/// with the application server key pair `as_secret` and `salt`
+fn encrypt_with(
+    payload: &[u8],
+    ua_public: &[u8],
+    auth_secret: &[u8],
+    as_secret: &SecretKey,
+    salt: &[u8; 16],
+) -> Result<Vec<u8>> {
+    // The record holds the payload, a delimiter byte and the 16 byte tag
+    if payload.len() + 17 > RECORD_SIZE as usize {
+        return Err(ServerError::PayloadTooLarge(
+            "Push notification payload is too large".to_string(),
+        ));
+    }
+
+    let ua_key = PublicKey::from_sec1_bytes(ua_public)
+        .map_err(|_| ServerError::InvalidInput("Invalid p256dh key".to_string()))?;
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let shared_secret =
+        p256::ecdh::diffie_hellman(as_secret.to_nonzero_scalar(), ua_key.as_affine());
+
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(ua_public);
+    key_info.extend_from_slice(as_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(auth_secret), shared_secret.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), &ikm);
+    let mut content_key = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut content_key)
+        .and_then(|()| hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+
+    // 0x02 marks the last (and only) record
+    let mut record = payload.to_vec();
+    record.push(2);
+    let ciphertext = Aes128Gcm::new(&content_key.into())
+        .encrypt(&nonce.into(), record.as_slice())
+        .map_err(|e| ServerError::Internal(format!("Failed to encrypt notification: {}", e)))?;
+
+    let mut body = Vec::with_capacity(16 + 4 + 1 + PUBLIC_KEY_LENGTH + ciphertext.len());
+    body.extend_from_slice(salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(PUBLIC_KEY_LENGTH as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+/// A browser's `PushSubscription`, as serialized by `toJSON()`
+#[derive(Debug, Deserialize)]
+struct SubscribeRequest {
+    endpoint: String,
+    keys: SubscriptionKeys,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionKeys {
+    p256dh: String,
+    auth: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnsubscribeRequest {
+    endpoint: String,
+}
+
+#[derive(Debug, Serialize)]
+struct VapidPublicKeyResponse {
+    public_key: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SubscriptionResponse {
+    id: String,
+    endpoint: String,
+    created_at: String,
+}
+
+impl From<WebPushSubscription> for SubscriptionResponse {
+    fn from(subscription: WebPushSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            endpoint: subscription.endpoint,
+            created_at: subscription.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The configured VAPID key; fails if Web Push is disabled
+fn configured_key(state: &AppState) -> Result<VapidKey> {
+    let config = state.config.get();
+    if !config.web_push.is_enabled() {
+        return Err(ServerError::FeatureDisabled(
+            "Web Push is not configured. Set CLIPPER_WEB_PUSH_VAPID_KEY to enable.".to_string(),
+        ));
+    }
+    VapidKey::from_base64(
+        config
+            .web_push
+            .vapid_private_key
+            .as_deref()
+            .unwrap_or_default(),
+    )
+}
+
+/// The key browsers need to subscribe to notifications from this server
+async fn vapid_public_key(State(state): State<AppState>) -> Result<Json<VapidPublicKeyResponse>> {
+    let key = configured_key(&state)?;
+    Ok(Json(VapidPublicKeyResponse {
+        public_key: key.public_key().to_string(),
+    }))
+}
+
+/// Register a browser's push subscription
+async fn subscribe(
+    State(state): State<AppState>,
+    Json(payload): Json<SubscribeRequest>,
+) -> Result<(StatusCode, Json<SubscriptionResponse>)> {
+    configured_key(&state)?;
+    decode_keys(&payload.keys.p256dh, &payload.keys.auth)?;
+
+    let subscription = state
+        .indexer
+        .add_web_push_subscription(&payload.endpoint, &payload.keys.p256dh, &payload.keys.auth)
+        .await?;
+    Ok((StatusCode::CREATED, Json(subscription.into())))
+}
+
+/// Remove a browser's push subscription
+async fn unsubscribe(
+    State(state): State<AppState>,
+    Json(payload): Json<UnsubscribeRequest>,
+) -> Result<StatusCode> {
+    state
+        .indexer
+        .delete_web_push_subscription(&payload.endpoint)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::{signature::Verifier, VerifyingKey};
+
+    fn decode(value: &str) -> Vec<u8> {
+        URL_SAFE_NO_PAD.decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_matches_rfc8291_example() {
+        // Section 5 of RFC 8291
+        let as_secret =
+            SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw")).unwrap();
+        let salt: [u8; 16] = decode("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap();
+        let (ua_public, auth_secret) = decode_keys(
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            "BTBZMqHH6r4Tts7J_aSIgg",
+        )
+        .unwrap();
+
+        let body = encrypt_with(
+            b"When I grow up, I want to be a watermelon",
+            &ua_public,
+            &auth_secret,
+            &as_secret,
+            &salt,
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn test_decode_keys_rejects_malformed_keys() {
+        let p256dh =
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+        assert!(decode_keys(p256dh, "BTBZMqHH6r4Tts7J_aSIgg").is_ok());
+        assert!(decode_keys(p256dh, "c2hvcnQ").is_err());
+        assert!(decode_keys("BCVxsr7N", "BTBZMqHH6r4Tts7J_aSIgg").is_err());
+        assert!(decode_keys("not base64!", "BTBZMqHH6r4Tts7J_aSIgg").is_err());
+    }
+
+    #[test]
+    fn test_vapid_authorization_is_signed() {
+        let key = VapidKey::from_base64("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw").unwrap();
+        assert_eq!(
+            key.public_key(),
+            "BP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A8"
+        );
+
+        let header = key
+            .authorization(
+                "https://push.example.com/send/abc?x=1",
+                Some("mailto:admin@example.com"),
+            )
+            .unwrap();
+        let (token, public_key) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .unwrap();
+        assert_eq!(public_key, key.public_key());
+
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        let claims: serde_json::Value =
+            serde_json::from_slice(&decode(signing_input.split('.').nth(1).unwrap())).unwrap();
+        assert_eq!(claims["aud"], "https://push.example.com");
+        assert_eq!(claims["sub"], "mailto:admin@example.com");
+        assert!(claims["exp"].as_i64().unwrap() > Utc::now().timestamp());
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&decode(public_key)).unwrap();
+        let signature = Signature::from_slice(&decode(signature)).unwrap();
+        assert!(verifying_key
+            .verify(signing_input.as_bytes(), &signature)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_vapid_key_is_rejected() {
+        assert!(VapidKey::from_base64("").is_err());
+        assert!(VapidKey::from_base64("not a key").is_err());
+    }
+
+    #[test]
+    fn test_endpoint_origin() {
+        assert_eq!(
+            endpoint_origin("https://fcm.googleapis.com/fcm/send/abc").unwrap(),
+            "https://fcm.googleapis.com"
+        );
+        assert_eq!(
+            endpoint_origin("https://push.example.com:8443").unwrap(),
+            "https://push.example.com:8443"
+        );
+        assert!(endpoint_origin("push.example.com").is_err());
+    }
+
+    #[test]
+    fn test_notification_payload_preview() {
+        let payload: serde_json::Value =
+            serde_json::from_slice(&notification_payload("abc", "  hello\n\tworld ")).unwrap();
+        assert_eq!(payload["type"], "new_clip");
+        assert_eq!(payload["id"], "abc");
+        assert_eq!(payload["preview"], "hello world");
+
+        let payload: serde_json::Value =
+            serde_json::from_slice(&notification_payload("abc", &"x".repeat(500))).unwrap();
+        let preview = payload["preview"].as_str().unwrap();
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS);
+        assert!(preview.ends_with('…'));
+    }
+}
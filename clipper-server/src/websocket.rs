@@ -1,7 +1,7 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
     },
     response::Response,
     routing::get,
@@ -10,10 +10,11 @@ use axum::{
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{error, info, warn};
 
-use crate::state::AppState;
+use crate::auth::{self, Credential};
+use crate::state::{AppState, ClipUpdate};
 
 /// Heartbeat interval - server sends ping every 30 seconds
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
@@ -39,19 +40,106 @@ pub enum ServerAuthResponse {
     AuthError { message: String },
 }
 
+/// Query parameters of `GET /ws`
+#[derive(Debug, Default, Deserialize)]
+pub struct WebSocketQuery {
+    /// Comma-separated tags; when set, the connection only hears about new
+    /// clips carrying at least one of them
+    #[serde(default)]
+    tags: Option<String>,
+    /// Name of the device connecting, so clips can be pushed to its
+    /// clipboard with `POST /devices/{name}/clipboard`
+    #[serde(default)]
+    device: Option<String>,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new().route("/ws", get(websocket_handler))
 }
 
-async fn websocket_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+async fn websocket_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(query): Query<WebSocketQuery>,
+) -> Response {
+    let sync_tags = parse_sync_tags(query.tags.as_deref());
+    let device = query
+        .device
+        .map(|device| device.trim().to_string())
+        .filter(|device| !device.is_empty());
+    ws.on_upgrade(|socket| handle_websocket(socket, state, sync_tags, device))
+}
+
+/// Tags of a `tags` query parameter, without blanks and duplicates
+fn parse_sync_tags(tags: Option<&str>) -> Vec<String> {
+    let mut sync_tags: Vec<String> = Vec::new();
+    for tag in tags.unwrap_or_default().split(',').map(str::trim) {
+        if !tag.is_empty() && !sync_tags.iter().any(|existing| existing == tag) {
+            sync_tags.push(tag.to_string());
+        }
+    }
+    sync_tags
+}
+
+/// Whether a new clip with `tags` passes the connection's tag filter
+fn matches_sync_tags(sync_tags: &[String], tags: &[String]) -> bool {
+    sync_tags.is_empty() || tags.iter().any(|tag| sync_tags.contains(tag))
+}
+
+/// Whether a connection signed in with `credential` may take the clipboard
+/// pushes for the device `name`: only the paired device of that name may,
+/// so the shared and guest tokens can't listen in on a device's pushes
+fn may_take_pushes(credential: &Credential, name: &str) -> bool {
+    matches!(credential, Credential::PairedDevice(device) if device.name == name)
+}
+
+/// The part of `update` about clips `credential` can see, if any
+async fn visible_update(
+    state: &AppState,
+    credential: &Credential,
+    update: ClipUpdate,
+) -> Option<ClipUpdate> {
+    match update {
+        ClipUpdate::NewClip { ref id, .. } | ClipUpdate::UpdatedClip { ref id, .. } => state
+            .indexer
+            .get_entry(id)
+            .await
+            .is_ok_and(|entry| credential.can_see(entry.visibility))
+            .then_some(update),
+        ClipUpdate::DeletedClip { visibility, .. } => {
+            credential.can_see(visibility).then_some(update)
+        }
+        ClipUpdate::ClipsCleanedUp {
+            ids, visibilities, ..
+        } => {
+            let (ids, visibilities): (Vec<_>, Vec<_>) = ids
+                .into_iter()
+                .zip(visibilities)
+                .filter(|(_, visibility)| credential.can_see(*visibility))
+                .unzip();
+            (!ids.is_empty()).then_some(ClipUpdate::ClipsCleanedUp {
+                count: ids.len(),
+                ids,
+                visibilities,
+            })
+        }
+    }
 }
 
-async fn handle_websocket(socket: WebSocket, state: AppState) {
+async fn handle_websocket(
+    socket: WebSocket,
+    state: AppState,
+    sync_tags: Vec<String>,
+    mut device: Option<String>,
+) {
     let (mut sender, mut receiver) = socket.split();
 
     // Check if authentication is required
-    let auth_required = state.config.auth.is_enabled();
+    let auth_required = state.config.get().auth.is_enabled();
+    // Paired device the connection signed in as, if any
+    let mut paired_device_id = None;
+    // How the connection signed in, if auth is required
+    let mut credential = None;
 
     if auth_required {
         // Wait for auth message from client
@@ -65,10 +153,9 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
                         match serde_json::from_str::<ClientMessage>(&text) {
                             Ok(ClientMessage::Auth { token }) => {
                                 // Validate the token
-                                if state.config.auth.validate_token(&token) {
-                                    return Ok(());
-                                } else {
-                                    return Err("Invalid bearer token".to_string());
+                                match auth::authenticate(&state, &token).await {
+                                    Some(credential) => return Ok(credential),
+                                    None => return Err("Invalid bearer token".to_string()),
                                 }
                             }
                             Err(e) => {
@@ -94,7 +181,18 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
         .await;
 
         match auth_result {
-            Ok(Ok(())) => {
+            Ok(Ok(signed_in)) => {
+                if let Credential::PairedDevice(device) = &signed_in {
+                    paired_device_id = Some(device.id.clone());
+                }
+                if let Some(name) = device.take_if(|name| !may_take_pushes(&signed_in, name)) {
+                    warn!(
+                        "WebSocket: not taking clipboard pushes for {}: only the paired device of that name may",
+                        name
+                    );
+                }
+                credential = Some(signed_in);
+
                 // Auth successful, send success response
                 let response = serde_json::to_string(&ServerAuthResponse::AuthSuccess).unwrap();
                 if sender.send(Message::Text(response.into())).await.is_err() {
@@ -131,6 +229,7 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
 
     // Subscribe to clip updates
     let mut rx = state.clip_updates.subscribe();
+    let mut revocations_rx = state.device_revocations.subscribe();
 
     // Create a channel for sending messages (updates + heartbeat pings)
     let (msg_tx, mut msg_rx) = mpsc::unbounded_channel::<Message>();
@@ -149,12 +248,82 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
         }
     });
 
+    // Close the connection when the paired device it signed in as is revoked
+    let revoke_task = paired_device_id.map(|device_id| {
+        let close_tx = msg_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match revocations_rx.recv().await {
+                    Ok(id) if id == device_id => {
+                        info!("WebSocket: paired device {} revoked, closing", device_id);
+                        let _ = close_tx.send(Message::Close(None));
+                        break;
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    });
+
+    // Forward clips pushed to this device's clipboard
+    let push_task = device.as_ref().map(|device| {
+        let mut pushes_rx = state.connected_devices.connect(device);
+        let device = device.clone();
+        let push_credential = credential.clone();
+        let push_tx = msg_tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match pushes_rx.recv().await {
+                    Ok(push)
+                        if push.device == device
+                            && push_credential
+                                .as_ref()
+                                .is_none_or(|credential| credential.can_see(push.visibility)) =>
+                    {
+                        let json = match serde_json::to_string(&push) {
+                            Ok(json) => json,
+                            Err(e) => {
+                                error!("Failed to serialize clipboard push: {}", e);
+                                continue;
+                            }
+                        };
+                        if push_tx.send(Message::Text(json.into())).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    });
+
     // Clone sender for updates task
     let updates_tx = msg_tx;
 
     // Spawn a task to forward updates to the message channel
+    let updates_state = state.clone();
     let updates_task = tokio::spawn(async move {
         while let Ok(update) = rx.recv().await {
+            // Devices syncing only some tags skip the other new clips
+            if let ClipUpdate::NewClip { tags, .. } = &update
+                && !matches_sync_tags(&sync_tags, tags)
+            {
+                continue;
+            }
+
+            // Guests only hear about clips they can see
+            let update = match &credential {
+                Some(credential) if credential.visibility_filter().is_some() => {
+                    match visible_update(&updates_state, credential, update).await {
+                        Some(update) => update,
+                        None => continue,
+                    }
+                }
+                _ => update,
+            };
+
             let json = match serde_json::to_string(&update) {
                 Ok(json) => json,
                 Err(e) => {
@@ -172,7 +341,8 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
     // Spawn a task to send messages from the channel to WebSocket
     let mut send_task = tokio::spawn(async move {
         while let Some(msg) = msg_rx.recv().await {
-            if sender.send(msg).await.is_err() {
+            let is_close = matches!(msg, Message::Close(_));
+            if sender.send(msg).await.is_err() || is_close {
                 break;
             }
         }
@@ -216,9 +386,39 @@ async fn handle_websocket(socket: WebSocket, state: AppState) {
     // Clean up
     heartbeat_task.abort();
     updates_task.abort();
+    if let Some(revoke_task) = revoke_task {
+        revoke_task.abort();
+    }
+    if let Some(push_task) = push_task {
+        push_task.abort();
+    }
+    if let Some(device) = &device {
+        state.connected_devices.disconnect(device);
+    }
 
     // Track disconnection
     state.ws_disconnect();
 
     info!("WebSocket connection closed");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_tags() {
+        assert!(parse_sync_tags(None).is_empty());
+        assert!(parse_sync_tags(Some(" , ")).is_empty());
+        assert_eq!(
+            parse_sync_tags(Some("work, $host:laptop,work,")),
+            vec!["work".to_string(), "$host:laptop".to_string()]
+        );
+
+        let tags = vec!["work".to_string(), "urgent".to_string()];
+        assert!(matches_sync_tags(&[], &tags));
+        assert!(matches_sync_tags(&["urgent".to_string()], &tags));
+        assert!(!matches_sync_tags(&["personal".to_string()], &tags));
+        assert!(!matches_sync_tags(&["work".to_string()], &[]));
+    }
+}
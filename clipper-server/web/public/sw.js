@@ -0,0 +1,38 @@
+// Service worker for Web Push notifications (see web_push.rs in clipper-server).
+// Kept separate from the app bundle so it can live at the web UI root and
+// receive pushes for the whole UI, including under a `server.base_path`.
+
+self.addEventListener("push", (event) => {
+  let data = {};
+  try {
+    data = event.data ? event.data.json() : {};
+  } catch {
+    // Not a notification from the server
+    return;
+  }
+  if (data.type !== "new_clip") return;
+
+  event.waitUntil(
+    self.registration.showNotification("Clipper", {
+      body: data.preview || "",
+      icon: new URL("favicon.svg", self.registration.scope).href,
+      // Replace the previous notification instead of piling them up
+      tag: "clipper-new-clip",
+      data: { id: data.id },
+    })
+  );
+});
+
+self.addEventListener("notificationclick", (event) => {
+  event.notification.close();
+
+  // Focus an open Clipper tab, or open one
+  event.waitUntil(
+    self.clients.matchAll({ type: "window", includeUncontrolled: true }).then((windows) => {
+      for (const client of windows) {
+        if ("focus" in client) return client.focus();
+      }
+      return self.clients.openWindow(self.registration.scope);
+    })
+  );
+});
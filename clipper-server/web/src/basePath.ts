@@ -0,0 +1,6 @@
+/**
+ * Path the server is mounted under (`server.base_path`), e.g. "/clipper", or
+ * "" at the root. The server injects it into index.html as a <base> element.
+ */
+export const BASE_PATH = (document.querySelector("base")?.getAttribute("href") ?? "/")
+  .replace(/\/$/, "");
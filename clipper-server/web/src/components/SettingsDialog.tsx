@@ -6,6 +6,8 @@ import {
   SYNTAX_THEMES,
 } from "@unwritten-codes/clipper-ui";
 import type { Language, Theme, SyntaxTheme } from "@unwritten-codes/clipper-ui";
+import { WebPushSettings } from "./WebPushSettings";
+import { BASE_PATH } from "../basePath";
 
 // Storage key for the auth token (same as in main.tsx)
 const AUTH_TOKEN_KEY = "clipper-web-token";
@@ -143,7 +145,7 @@ export function SettingsDialog({
       if (token) {
         headers["Authorization"] = `Bearer ${token}`;
       }
-      const response = await fetch("/version", { headers });
+      const response = await fetch(`${BASE_PATH}/version`, { headers });
       if (response.ok) {
         const data = await response.json();
         setServerInfo(data);
@@ -163,7 +165,7 @@ export function SettingsDialog({
         headers["Authorization"] = `Bearer ${token}`;
       }
       // Fetch with page_size=1 just to get the total count
-      const response = await fetch("/clips?page=1&page_size=1", { headers });
+      const response = await fetch(`${BASE_PATH}/clips?page=1&page_size=1`, { headers });
       if (response.ok) {
         const data = await response.json();
         setTotalClips(data.total);
@@ -251,72 +253,76 @@ export function SettingsDialog({
   ];
 
   const renderAppearanceTab = () => (
-    <div className="settings-section">
-      <h3>{t("settings.appearance")}</h3>
-
-      {/* Theme */}
-      <div className="settings-field">
-        <label>{t("settings.theme")}</label>
-        <div className="theme-selector">
-          <button
-            className={`theme-option ${theme === "light" ? "active" : ""}`}
-            onClick={() => onThemeChange("light")}
-          >
-            <span className="theme-icon">☀️</span>
-            <span>{t("settings.theme.light")}</span>
-          </button>
-          <button
-            className={`theme-option ${theme === "dark" ? "active" : ""}`}
-            onClick={() => onThemeChange("dark")}
-          >
-            <span className="theme-icon">🌙</span>
-            <span>{t("settings.theme.dark")}</span>
-          </button>
-          <button
-            className={`theme-option ${theme === "auto" ? "active" : ""}`}
-            onClick={() => onThemeChange("auto")}
+    <>
+      <div className="settings-section">
+        <h3>{t("settings.appearance")}</h3>
+
+        {/* Theme */}
+        <div className="settings-field">
+          <label>{t("settings.theme")}</label>
+          <div className="theme-selector">
+            <button
+              className={`theme-option ${theme === "light" ? "active" : ""}`}
+              onClick={() => onThemeChange("light")}
+            >
+              <span className="theme-icon">☀️</span>
+              <span>{t("settings.theme.light")}</span>
+            </button>
+            <button
+              className={`theme-option ${theme === "dark" ? "active" : ""}`}
+              onClick={() => onThemeChange("dark")}
+            >
+              <span className="theme-icon">🌙</span>
+              <span>{t("settings.theme.dark")}</span>
+            </button>
+            <button
+              className={`theme-option ${theme === "auto" ? "active" : ""}`}
+              onClick={() => onThemeChange("auto")}
+            >
+              <span className="theme-icon">💻</span>
+              <span>{t("settings.theme.auto")}</span>
+            </button>
+          </div>
+          <p className="settings-hint">{t("settings.theme.hint")}</p>
+        </div>
+
+        {/* Language */}
+        <div className="settings-field">
+          <label>{t("settings.language")}</label>
+          <select
+            className="settings-select"
+            value={language}
+            onChange={(e) => setLanguage(e.target.value as Language)}
           >
-            <span className="theme-icon">💻</span>
-            <span>{t("settings.theme.auto")}</span>
-          </button>
+            {supportedLanguages.map((lang) => (
+              <option key={lang} value={lang}>
+                {languageNames[lang]}
+              </option>
+            ))}
+          </select>
+          <p className="settings-hint">{t("settings.language.hint")}</p>
         </div>
-        <p className="settings-hint">{t("settings.theme.hint")}</p>
-      </div>
 
-      {/* Language */}
-      <div className="settings-field">
-        <label>{t("settings.language")}</label>
-        <select
-          className="settings-select"
-          value={language}
-          onChange={(e) => setLanguage(e.target.value as Language)}
-        >
-          {supportedLanguages.map((lang) => (
-            <option key={lang} value={lang}>
-              {languageNames[lang]}
-            </option>
-          ))}
-        </select>
-        <p className="settings-hint">{t("settings.language.hint")}</p>
+        {/* Syntax Theme */}
+        <div className="settings-field">
+          <label>{t("settings.syntaxTheme")}</label>
+          <select
+            className="settings-select"
+            value={syntaxTheme}
+            onChange={(e) => onSyntaxThemeChange(e.target.value as SyntaxTheme)}
+          >
+            {SYNTAX_THEMES.map((themeOption) => (
+              <option key={themeOption} value={themeOption}>
+                {t(`settings.syntaxTheme.${themeOption}` as const)}
+              </option>
+            ))}
+          </select>
+          <p className="settings-hint">{t("settings.syntaxTheme.hint")}</p>
+        </div>
       </div>
 
-      {/* Syntax Theme */}
-      <div className="settings-field">
-        <label>{t("settings.syntaxTheme")}</label>
-        <select
-          className="settings-select"
-          value={syntaxTheme}
-          onChange={(e) => onSyntaxThemeChange(e.target.value as SyntaxTheme)}
-        >
-          {SYNTAX_THEMES.map((themeOption) => (
-            <option key={themeOption} value={themeOption}>
-              {t(`settings.syntaxTheme.${themeOption}` as const)}
-            </option>
-          ))}
-        </select>
-        <p className="settings-hint">{t("settings.syntaxTheme.hint")}</p>
-      </div>
-    </div>
+      <WebPushSettings isOpen={isOpen} />
+    </>
   );
 
   const renderAboutTab = () => (
@@ -0,0 +1,43 @@
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import { useWebPush } from "../hooks/useWebPush";
+
+interface WebPushSettingsProps {
+  isOpen: boolean;
+}
+
+// New clip notifications delivered through the browser's push service
+export function WebPushSettings({ isOpen }: WebPushSettingsProps) {
+  const { t } = useI18n();
+  const { status, subscribed, busy, error, subscribe, unsubscribe } = useWebPush(isOpen);
+
+  if (status === "loading") return null;
+
+  return (
+    <div className="settings-section">
+      <h3>{t("settings.webPush")}</h3>
+      <div className="settings-field">
+        {status === "unsupported" ? (
+          <p className="settings-hint">{t("settings.webPush.unsupported")}</p>
+        ) : status === "not-configured" ? (
+          <p className="settings-hint">{t("settings.webPush.notConfigured")}</p>
+        ) : status === "denied" ? (
+          <p className="settings-hint">{t("settings.webPush.denied")}</p>
+        ) : (
+          <>
+            <button
+              className={`settings-btn ${subscribed ? "secondary" : "primary"}`}
+              onClick={subscribed ? unsubscribe : subscribe}
+              disabled={busy}
+            >
+              {subscribed ? t("settings.webPush.disable") : t("settings.webPush.enable")}
+            </button>
+            <p className="settings-hint">
+              {subscribed ? t("settings.webPush.on") : t("settings.webPush.hint")}
+            </p>
+          </>
+        )}
+        {error && <p className="settings-error">{t("settings.webPush.error")}</p>}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,155 @@
+import { useState, useEffect, useCallback } from "react";
+import { BASE_PATH } from "../basePath";
+
+// Storage key for the auth token (same as in main.tsx)
+const AUTH_TOKEN_KEY = "clipper-web-token";
+
+export type WebPushStatus = "loading" | "unsupported" | "not-configured" | "denied" | "ready";
+
+function authHeaders(): Record<string, string> {
+  const headers: Record<string, string> = {};
+  const token = localStorage.getItem(AUTH_TOKEN_KEY);
+  if (token) {
+    headers["Authorization"] = `Bearer ${token}`;
+  }
+  return headers;
+}
+
+// Push requires a service worker, which browsers only allow in secure contexts
+function isWebPushSupported(): boolean {
+  return (
+    window.isSecureContext &&
+    "serviceWorker" in navigator &&
+    "PushManager" in window &&
+    "Notification" in window
+  );
+}
+
+function decodeBase64Url(value: string): Uint8Array<ArrayBuffer> {
+  const base64 = value.replace(/-/g, "+").replace(/_/g, "/");
+  const padded = base64 + "=".repeat((4 - (base64.length % 4)) % 4);
+  return Uint8Array.from(atob(padded), (c) => c.charCodeAt(0));
+}
+
+function sameKey(a: ArrayBuffer | null, b: Uint8Array): boolean {
+  if (!a || a.byteLength !== b.length) return false;
+  const bytes = new Uint8Array(a);
+  return bytes.every((byte, i) => byte === b[i]);
+}
+
+async function getSubscription(): Promise<PushSubscription | null> {
+  const registration = await navigator.serviceWorker.getRegistration(`${BASE_PATH}/`);
+  return (await registration?.pushManager.getSubscription()) ?? null;
+}
+
+/**
+ * Hook to manage this browser's Web Push subscription for new clip
+ * notifications. Only loads while `active`, e.g. while the settings dialog
+ * is open.
+ */
+export function useWebPush(active: boolean) {
+  const [status, setStatus] = useState<WebPushStatus>("loading");
+  const [publicKey, setPublicKey] = useState<string | null>(null);
+  const [subscribed, setSubscribed] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState(false);
+
+  useEffect(() => {
+    if (!active) return;
+
+    const load = async () => {
+      setError(false);
+      if (!isWebPushSupported()) {
+        setStatus("unsupported");
+        return;
+      }
+      try {
+        const response = await fetch(`${BASE_PATH}/web-push/vapid-public-key`, { headers: authHeaders() });
+        if (!response.ok) {
+          setStatus("not-configured");
+          return;
+        }
+        const data: { public_key: string } = await response.json();
+        setPublicKey(data.public_key);
+        setSubscribed((await getSubscription()) !== null);
+        setStatus(Notification.permission === "denied" ? "denied" : "ready");
+      } catch (e) {
+        console.warn("Failed to load Web Push status:", e);
+        setStatus("not-configured");
+      }
+    };
+
+    load();
+  }, [active]);
+
+  const subscribe = useCallback(async () => {
+    if (!publicKey) return;
+    setBusy(true);
+    setError(false);
+    try {
+      const permission = await Notification.requestPermission();
+      if (permission !== "granted") {
+        if (permission === "denied") setStatus("denied");
+        return;
+      }
+
+      const registration = await navigator.serviceWorker.register(`${BASE_PATH}/sw.js`);
+      await navigator.serviceWorker.ready;
+
+      // A subscription made with a previous server key can't receive notifications
+      const applicationServerKey = decodeBase64Url(publicKey);
+      let subscription = await registration.pushManager.getSubscription();
+      if (subscription && !sameKey(subscription.options.applicationServerKey, applicationServerKey)) {
+        await subscription.unsubscribe();
+        subscription = null;
+      }
+      if (!subscription) {
+        subscription = await registration.pushManager.subscribe({
+          userVisibleOnly: true,
+          applicationServerKey,
+        });
+      }
+
+      const response = await fetch(`${BASE_PATH}/web-push/subscriptions`, {
+        method: "POST",
+        headers: { ...authHeaders(), "Content-Type": "application/json" },
+        body: JSON.stringify(subscription.toJSON()),
+      });
+      if (!response.ok) {
+        await subscription.unsubscribe();
+        throw new Error(`Server returned ${response.status}`);
+      }
+      setSubscribed(true);
+    } catch (e) {
+      console.error("Failed to subscribe to notifications:", e);
+      setError(true);
+    } finally {
+      setBusy(false);
+    }
+  }, [publicKey]);
+
+  const unsubscribe = useCallback(async () => {
+    setBusy(true);
+    setError(false);
+    try {
+      const subscription = await getSubscription();
+      if (subscription) {
+        // The server may already have dropped an expired subscription
+        await fetch(`${BASE_PATH}/web-push/subscriptions`, {
+          method: "DELETE",
+          headers: { ...authHeaders(), "Content-Type": "application/json" },
+          body: JSON.stringify({ endpoint: subscription.endpoint }),
+        });
+        await subscription.unsubscribe();
+      }
+      setSubscribed(false);
+    } catch (e) {
+      console.error("Failed to unsubscribe from notifications:", e);
+      setError(true);
+    } finally {
+      setBusy(false);
+    }
+  }, []);
+
+  return { status, subscribed, busy, error, subscribe, unsubscribe };
+}
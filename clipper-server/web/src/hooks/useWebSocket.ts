@@ -1,4 +1,5 @@
 import { useEffect, useRef, useCallback, useState } from "react";
+import { BASE_PATH } from "../basePath";
 
 export interface ClipNotification {
   type: "new_clip" | "updated_clip" | "deleted_clip" | "clips_cleaned_up";
@@ -42,7 +43,7 @@ export function isSecureContext(): boolean {
 function getWebSocketUrl(): string {
   const protocol = window.location.protocol === "https:" ? "wss:" : "ws:";
   const host = window.location.host;
-  return `${protocol}//${host}/ws`;
+  return `${protocol}//${host}${BASE_PATH}/ws`;
 }
 
 /**
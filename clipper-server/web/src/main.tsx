@@ -12,20 +12,21 @@ import { ServerConfigWrapper } from "./components/ServerConfigWrapper";
 import { LoginScreen } from "./components/LoginScreen";
 import App from "./App";
 import "./App.css";
+import { BASE_PATH } from "./basePath";
 
 // Storage key for the auth token
 const AUTH_TOKEN_KEY = "clipper-web-token";
 
 // Create the REST API client for the web UI
 const api = createRestApiClient({
-  baseUrl: "",
+  baseUrl: BASE_PATH,
   token: localStorage.getItem(AUTH_TOKEN_KEY) || undefined,
 });
 
 // Check if server requires authentication
 async function checkAuthRequired(): Promise<boolean> {
   try {
-    const response = await fetch("/auth/check");
+    const response = await fetch(`${BASE_PATH}/auth/check`);
     if (response.ok) {
       const data = await response.json();
       return data.auth_required === true;
@@ -39,7 +40,7 @@ async function checkAuthRequired(): Promise<boolean> {
 // Validate the current token by making a test request
 async function validateToken(token: string): Promise<boolean> {
   try {
-    const response = await fetch("/clips?page=1&page_size=1", {
+    const response = await fetch(`${BASE_PATH}/clips?page=1&page_size=1`, {
       headers: { Authorization: `Bearer ${token}` },
     });
     return response.ok;
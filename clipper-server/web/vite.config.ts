@@ -5,6 +5,8 @@ import path from "path";
 // https://vite.dev/config/
 export default defineConfig({
   plugins: [react()],
+  // Relative asset URLs, so the build also works under `server.base_path`
+  base: "./",
   resolve: {
     dedupe: ["react", "react-dom"],
   },
@@ -46,6 +48,10 @@ export default defineConfig({
         target: "http://localhost:3000",
         changeOrigin: true,
       },
+      "/web-push": {
+        target: "http://localhost:3000",
+        changeOrigin: true,
+      },
       "/ws": {
         target: "http://localhost:3000",
         changeOrigin: true,
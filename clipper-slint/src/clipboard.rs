@@ -0,0 +1,103 @@
+//! System clipboard integration: saving what is copied as clips, and copying
+//! clips back
+
+use chrono::Utc;
+use clipper_client::ClipperClient;
+use clipper_clipboard::{
+    ClipboardContent, ClipboardEvent, ClipboardEvents, ClipboardWatcher, MonitorOptions,
+    file_list_content, hostname_tag,
+};
+use std::sync::{Arc, Mutex};
+
+use crate::ClientState;
+use crate::settings::SettingsManager;
+
+/// What this app last copied back, so the monitor doesn't save it again
+#[derive(Default)]
+pub struct LastCopied {
+    text: Mutex<String>,
+    image: Mutex<Vec<u8>>,
+}
+
+impl LastCopied {
+    pub fn set_text(&self, text: String) {
+        *self.text.lock().unwrap() = text;
+    }
+
+    pub fn set_image(&self, png_bytes: Vec<u8>) {
+        *self.image.lock().unwrap() = png_bytes;
+    }
+
+    fn is_copied(&self, content: &ClipboardContent) -> bool {
+        match content {
+            ClipboardContent::Text(text) => *self.text.lock().unwrap() == *text,
+            ClipboardContent::Image(png_bytes) => *self.image.lock().unwrap() == *png_bytes,
+            _ => false,
+        }
+    }
+}
+
+/// Watch the clipboard on a thread of its own and save each new copy as a
+/// clip on the current server. The list picks new clips up from the
+/// notification subscription.
+pub fn start_clipboard_monitor(
+    client_state: Arc<ClientState>,
+    runtime: tokio::runtime::Handle,
+    settings: Arc<SettingsManager>,
+    last_copied: Arc<LastCopied>,
+) {
+    std::thread::spawn(move || {
+        let events = ClipboardEvents::new(ClipboardWatcher::new(), MonitorOptions::default());
+        for event in events {
+            let ClipboardEvent::Copied(content) = event else {
+                continue;
+            };
+            // Skip what was just copied back from the list
+            if last_copied.is_copied(&content) {
+                continue;
+            }
+
+            // Get the client each time to pick up server restarts
+            let client = client_state.get();
+            match content {
+                ClipboardContent::Text(text) => {
+                    runtime.spawn(save_text(client, text, vec![hostname_tag()]));
+                }
+                ClipboardContent::Image(png_bytes) => {
+                    let max_size = settings.get_max_upload_size_mb() * 1024 * 1024;
+                    if png_bytes.len() as u64 > max_size {
+                        eprintln!(
+                            "[clipper-slint] Image size ({:.2} MB) exceeds maximum allowed size ({} MB), skipping upload",
+                            png_bytes.len() as f64 / (1024.0 * 1024.0),
+                            settings.get_max_upload_size_mb()
+                        );
+                        continue;
+                    }
+                    runtime.spawn(save_image(client, png_bytes));
+                }
+                ClipboardContent::Files(paths) => {
+                    let tags = vec!["$filelist".to_string(), hostname_tag()];
+                    runtime.spawn(save_text(client, file_list_content(&paths), tags));
+                }
+                ClipboardContent::Empty => {}
+            }
+        }
+    });
+}
+
+async fn save_text(client: ClipperClient, text: String, tags: Vec<String>) {
+    if let Err(e) = client.create_clip(text, tags, None, None).await {
+        eprintln!("[clipper-slint] Failed to create clip from text: {}", e);
+    }
+}
+
+async fn save_image(client: ClipperClient, png_bytes: Vec<u8>) {
+    let filename = format!("screenshot-{}.png", Utc::now().format("%Y-%m-%d-%H-%M-%S"));
+    let tags = vec!["$image".to_string(), hostname_tag()];
+    if let Err(e) = client
+        .upload_file_bytes(png_bytes, filename, tags, None)
+        .await
+    {
+        eprintln!("[clipper-slint] Failed to create clip from image: {}", e);
+    }
+}
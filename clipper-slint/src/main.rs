@@ -1,15 +1,25 @@
 use anyhow::{Context, Result, anyhow};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
-use clipper_client::{Clip, ClipperClient, SearchFilters};
-use slint::{ComponentHandle, ModelRc, SharedString, VecModel};
+use clipper_client::models::PagedResult;
+use clipper_client::{
+    Clip, ClipNotification, ClipperClient, ConnectionStatus, ReconnectConfig, SearchFilters,
+};
+use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
 use std::collections::HashMap;
 use std::env;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock, Weak as ArcWeak};
+use tokio::sync::mpsc;
 
+mod clipboard;
 mod server;
 mod settings;
+mod shortcuts;
+mod tray;
 
+use clipboard::LastCopied;
 use server::{ServerManager, get_local_ip_addresses};
 use settings::{SettingsManager, Theme};
 
@@ -46,8 +56,9 @@ impl ClientState {
     }
 }
 
-const PAGE_SIZE: usize = 200;
+const PAGE_SIZE: usize = 50;
 const FAVORITE_TAG: &str = "$favorite";
+const IMAGE_TAG: &str = "$image";
 
 fn main() -> Result<()> {
     // Set restrictive permissions for newly created files and directories.
@@ -115,10 +126,13 @@ fn main() -> Result<()> {
         app.set_local_ip_addresses(ips.join(", ").into());
     }
 
+    let last_copied = Arc::new(LastCopied::default());
     let controller = AppController::new(
         Arc::clone(&client_state),
         runtime.handle().clone(),
         app.as_weak(),
+        Arc::clone(&last_copied),
+        Arc::clone(&settings),
     );
 
     // Settings callbacks
@@ -193,6 +207,7 @@ fn main() -> Result<()> {
         let client_state_clone = Arc::clone(&client_state);
         let app_weak = app.as_weak();
         let runtime_handle = runtime.handle().clone();
+        let controller_clone = controller.clone();
         app.on_listen_on_all_changed(move |value| {
             if let Err(e) = settings_clone.set_listen_on_all_interfaces(value) {
                 eprintln!("[clipper-slint] Failed to save listen setting: {}", e);
@@ -204,10 +219,12 @@ fn main() -> Result<()> {
                 let sm = Arc::clone(server_manager);
                 let client_state = Arc::clone(&client_state_clone);
                 let app_weak = app_weak.clone();
+                let controller = controller_clone.clone();
                 runtime_handle.spawn(async move {
                     match sm.restart().await {
                         Ok(result) => {
                             client_state.update(result.url.clone(), result.token);
+                            controller.subscribe();
                             eprintln!(
                                 "[clipper-slint] Server restarted with new network settings at {}",
                                 result.url
@@ -258,6 +275,7 @@ fn main() -> Result<()> {
                     match sm.start().await {
                         Ok(result) => {
                             client_state.update(result.url.clone(), result.token);
+                            controller.subscribe();
                             eprintln!("[clipper-slint] Server restarted after clearing data");
                             // Update UI and reload clips
                             let _ = slint::invoke_from_event_loop(move || {
@@ -321,9 +339,82 @@ fn main() -> Result<()> {
         });
     }
 
+    {
+        let controller = controller.clone();
+        app.on_load_more(move || {
+            controller.load_more();
+        });
+    }
+
+    {
+        let controller = controller.clone();
+        app.on_copy_clip(move |id| {
+            controller.copy_clip(id.to_string());
+        });
+    }
+
+    {
+        let controller = controller.clone();
+        app.on_open_detail(move |id| {
+            controller.open_detail(id.to_string());
+        });
+    }
+
+    {
+        let controller = controller.clone();
+        app.on_save_clip(move |id, tags, notes| {
+            controller.save_clip(id.to_string(), tags.to_string(), notes.to_string());
+        });
+    }
+
+    {
+        let controller = controller.clone();
+        app.on_delete_clip(move |id| {
+            controller.delete_clip(id.to_string());
+        });
+    }
+
+    {
+        let controller = controller.clone();
+        app.on_download_attachment(move |id| {
+            controller.download_attachment(id.to_string());
+        });
+    }
+
     controller.load_clips();
+    controller.subscribe();
+    clipboard::start_clipboard_monitor(
+        Arc::clone(&client_state),
+        runtime.handle().clone(),
+        Arc::clone(&settings),
+        last_copied,
+    );
 
-    app.run().map_err(|e| anyhow!("UI error: {e}"))?;
+    // Closing the window hides it while the tray icon can bring it back
+    let tray_active = Arc::new(AtomicBool::new(false));
+    tray::start_tray(app.as_weak(), Arc::clone(&tray_active));
+    app.window().on_close_requested(move || {
+        if !tray_active.load(Ordering::SeqCst) {
+            let _ = slint::quit_event_loop();
+        }
+        slint::CloseRequestResponse::HideWindow
+    });
+
+    // Kept alive so the shortcut stays registered
+    let _shortcut_manager =
+        match shortcuts::register_toggle_shortcut(&settings.get().global_shortcut, app.as_weak()) {
+            Ok(manager) => Some(manager),
+            Err(e) => {
+                eprintln!(
+                    "[clipper-slint] Failed to register global shortcut: {:#}",
+                    e
+                );
+                None
+            }
+        };
+
+    app.show().map_err(|e| anyhow!("UI error: {e}"))?;
+    slint::run_event_loop_until_quit().map_err(|e| anyhow!("UI error: {e}"))?;
 
     // Stop the bundled server if running
     if let Some(server_manager) = server_manager {
@@ -380,12 +471,61 @@ struct PreparedFilters {
     search_filters: SearchFilters,
 }
 
+impl PreparedFilters {
+    /// Fetch one page of the clips matching the filters
+    async fn fetch_page(
+        self,
+        client: &ClipperClient,
+        page: usize,
+    ) -> clipper_client::Result<PagedResult> {
+        if self.query.is_empty() {
+            client
+                .list_clips(self.search_filters, page, PAGE_SIZE)
+                .await
+        } else {
+            client
+                .search_clips(&self.query, self.search_filters, page, PAGE_SIZE)
+                .await
+        }
+    }
+}
+
+/// Pages of the clip list loaded so far
+#[derive(Default)]
+struct PagingState {
+    /// Bumped whenever the list is reloaded, so pages fetched for an older
+    /// list are dropped
+    generation: u64,
+    pages: usize,
+    loaded: usize,
+    total: usize,
+    has_more: bool,
+    /// Whether the next page is being fetched
+    loading_more: bool,
+}
+
+impl PagingState {
+    /// Record a fetched page; returns the status text
+    fn add_page(&mut self, page: usize, result: &PagedResult) -> String {
+        self.pages = page;
+        self.loaded += result.items.len();
+        self.total = result.total;
+        self.has_more = result.has_more.unwrap_or(page < result.total_pages);
+        format!("Showing {} of {} clip(s)", self.loaded, self.total)
+    }
+}
+
 struct AppController {
     client_state: Arc<ClientState>,
     runtime: tokio::runtime::Handle,
     ui: slint::Weak<App>,
     filters: Mutex<FilterState>,
     cache: Arc<Mutex<HashMap<String, Clip>>>,
+    paging: Arc<Mutex<PagingState>>,
+    /// The notification subscription to the current server
+    subscription: Mutex<Option<tokio::task::AbortHandle>>,
+    last_copied: Arc<LastCopied>,
+    settings: Arc<SettingsManager>,
 }
 
 impl AppController {
@@ -393,6 +533,8 @@ impl AppController {
         client_state: Arc<ClientState>,
         runtime: tokio::runtime::Handle,
         ui: slint::Weak<App>,
+        last_copied: Arc<LastCopied>,
+        settings: Arc<SettingsManager>,
     ) -> Arc<Self> {
         Arc::new(Self {
             client_state,
@@ -400,9 +542,83 @@ impl AppController {
             ui,
             filters: Mutex::new(FilterState::default()),
             cache: Arc::new(Mutex::new(HashMap::new())),
+            paging: Arc::new(Mutex::new(PagingState::default())),
+            subscription: Mutex::new(None),
+            last_copied,
+            settings,
         })
     }
 
+    /// Subscribe to notifications from the current server, replacing any
+    /// earlier subscription, so the list reloads when clips change elsewhere
+    fn subscribe(self: &Arc<Self>) {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let ui = self.ui.clone();
+        let weak_self: ArcWeak<Self> = Arc::downgrade(self);
+        // Set after the first connection; clips deleted while disconnected
+        // aren't notified, so reconnecting reloads the list
+        let connected_before = AtomicBool::new(false);
+        let on_status = move |status: ConnectionStatus| {
+            let text = match status {
+                ConnectionStatus::Connecting => "Connecting...".to_string(),
+                ConnectionStatus::Connected => {
+                    if connected_before.swap(true, Ordering::SeqCst)
+                        && let Some(controller) = weak_self.upgrade()
+                    {
+                        controller.load_clips();
+                    }
+                    "Live".to_string()
+                }
+                ConnectionStatus::Disconnected { error: Some(e) } => format!("Disconnected: {e}"),
+                ConnectionStatus::Disconnected { error: None } => "Disconnected".to_string(),
+                ConnectionStatus::Reconnecting { attempt, delay } => format!(
+                    "Reconnecting in {:.0}s (attempt {attempt})",
+                    delay.as_secs_f64()
+                ),
+                // Recovery is off (see below), so it can't fail
+                ConnectionStatus::RecoveryFailed { .. } => return,
+            };
+            update_connection_status(&ui, SharedString::from(text));
+        };
+
+        // The list is reloaded on reconnect, so missed clips needn't be
+        // recovered one by one
+        let config = ReconnectConfig {
+            recover_missed: false,
+            ..ReconnectConfig::default()
+        };
+        let handle = {
+            let _guard = self.runtime.enter();
+            self.client_state
+                .get()
+                .subscribe_notifications_with_reconnect(tx, config, on_status)
+        };
+        if let Some(previous) = self
+            .subscription
+            .lock()
+            .unwrap()
+            .replace(handle.abort_handle())
+        {
+            previous.abort();
+        }
+
+        let weak_self: ArcWeak<Self> = Arc::downgrade(self);
+        self.runtime.spawn(async move {
+            while let Some(notification) = rx.recv().await {
+                let mut changed = changes_clips(&notification);
+                // Reload once for a burst of notifications, e.g. a cleanup
+                while let Ok(notification) = rx.try_recv() {
+                    changed |= changes_clips(&notification);
+                }
+                if changed && let Some(controller) = weak_self.upgrade() {
+                    controller.load_clips();
+                }
+            }
+        });
+    }
+
+    /// Load the first page of clips matching the filters, replacing the list
     fn load_clips(self: &Arc<Self>) {
         let prepared = {
             let filters = self.filters.lock().unwrap().clone();
@@ -417,23 +633,27 @@ impl AppController {
             }
         };
 
+        let generation = {
+            let mut paging = self.paging.lock().unwrap();
+            paging.generation += 1;
+            paging.loading_more = false;
+            paging.generation
+        };
+
         update_status(&self.ui, SharedString::from("Loading clips..."));
 
         let client = self.client_state.get();
         let ui = self.ui.clone();
         let cache = self.cache.clone();
-        let query = prepared.query;
-        let search_filters = prepared.search_filters;
+        let paging = self.paging.clone();
 
         self.runtime.spawn(async move {
-            let response = if query.is_empty() {
-                client.list_clips(search_filters, 1, PAGE_SIZE).await
-            } else {
-                client
-                    .search_clips(&query, search_filters, 1, PAGE_SIZE)
-                    .await
-            };
+            let response = prepared.fetch_page(&client, 1).await;
 
+            let mut paging = paging.lock().unwrap();
+            if paging.generation != generation {
+                return;
+            }
             match response {
                 Ok(result) => {
                     {
@@ -443,18 +663,74 @@ impl AppController {
                             cache_guard.insert(clip.id.clone(), clip.clone());
                         }
                     }
+                    *paging = PagingState {
+                        generation,
+                        ..PagingState::default()
+                    };
+                    let status = paging.add_page(1, &result);
                     let entries: Vec<ClipEntryData> =
                         result.items.iter().map(clip_to_ui_entry).collect();
-                    update_clip_list(&ui, entries);
+                    update_clip_list(&ui, entries, paging.has_more);
+                    update_status(&ui, SharedString::from(status));
+                }
+                Err(err) => {
                     update_status(
                         &ui,
-                        SharedString::from(format!("Showing {} clip(s)", result.items.len())),
+                        SharedString::from(format!("Failed to load clips: {err}")),
                     );
                 }
+            }
+        });
+    }
+
+    /// Append the next page of clips to the list, if there is one
+    fn load_more(self: &Arc<Self>) {
+        let Ok(prepared) = self.filters.lock().unwrap().prepare() else {
+            return;
+        };
+
+        let (generation, page) = {
+            let mut paging = self.paging.lock().unwrap();
+            if !paging.has_more || paging.loading_more {
+                return;
+            }
+            paging.loading_more = true;
+            (paging.generation, paging.pages + 1)
+        };
+
+        update_status(&self.ui, SharedString::from("Loading more clips..."));
+
+        let client = self.client_state.get();
+        let ui = self.ui.clone();
+        let cache = self.cache.clone();
+        let paging = self.paging.clone();
+
+        self.runtime.spawn(async move {
+            let response = prepared.fetch_page(&client, page).await;
+
+            let mut paging = paging.lock().unwrap();
+            if paging.generation != generation {
+                return;
+            }
+            paging.loading_more = false;
+            match response {
+                Ok(result) => {
+                    {
+                        let mut cache_guard = cache.lock().unwrap();
+                        for clip in &result.items {
+                            cache_guard.insert(clip.id.clone(), clip.clone());
+                        }
+                    }
+                    let status = paging.add_page(page, &result);
+                    let entries: Vec<ClipEntryData> =
+                        result.items.iter().map(clip_to_ui_entry).collect();
+                    append_clip_list(&ui, entries, paging.has_more);
+                    update_status(&ui, SharedString::from(status));
+                }
                 Err(err) => {
                     update_status(
                         &ui,
-                        SharedString::from(format!("Failed to load clips: {err}")),
+                        SharedString::from(format!("Failed to load more clips: {err}")),
                     );
                 }
             }
@@ -523,7 +799,10 @@ impl AppController {
         let weak_self: ArcWeak<Self> = Arc::downgrade(self);
 
         self.runtime.spawn(async move {
-            match client.update_clip(&clip.id, Some(tags), None, None).await {
+            match client
+                .update_clip(&clip.id, Some(tags), None, None, None, None)
+                .await
+            {
                 Ok(updated) => {
                     {
                         let mut cache_guard = cache.lock().unwrap();
@@ -550,6 +829,208 @@ impl AppController {
             }
         });
     }
+
+    /// Show a clip in the detail dialog
+    fn open_detail(self: &Arc<Self>, id: String) {
+        let clip = {
+            let cache = self.cache.lock().unwrap();
+            cache.get(&id).cloned()
+        };
+
+        let Some(clip) = clip else {
+            update_status(
+                &self.ui,
+                SharedString::from("Clip not found in current list"),
+            );
+            return;
+        };
+
+        show_detail(&self.ui, Some(clip_to_detail(&clip)));
+    }
+
+    /// Save the tags (comma-separated) and notes edited in the detail dialog
+    fn save_clip(self: &Arc<Self>, id: String, tags: String, notes: String) {
+        let tags: Vec<String> = tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        update_status(&self.ui, SharedString::from("Saving clip..."));
+
+        let client = self.client_state.get();
+        let ui = self.ui.clone();
+        let cache = self.cache.clone();
+        let weak_self: ArcWeak<Self> = Arc::downgrade(self);
+
+        self.runtime.spawn(async move {
+            match client
+                .update_clip(&id, Some(tags), Some(notes), None, None, None)
+                .await
+            {
+                Ok(updated) => {
+                    {
+                        let mut cache_guard = cache.lock().unwrap();
+                        cache_guard.insert(updated.id.clone(), updated.clone());
+                    }
+                    show_detail(&ui, None);
+                    update_status(&ui, SharedString::from("Clip updated"));
+
+                    if let Some(controller) = weak_self.upgrade() {
+                        controller.load_clips();
+                    }
+                }
+                Err(err) => {
+                    update_status(
+                        &ui,
+                        SharedString::from(format!("Failed to update clip: {err}")),
+                    );
+                }
+            }
+        });
+    }
+
+    fn delete_clip(self: &Arc<Self>, id: String) {
+        update_status(&self.ui, SharedString::from("Deleting clip..."));
+
+        let client = self.client_state.get();
+        let ui = self.ui.clone();
+        let cache = self.cache.clone();
+        let weak_self: ArcWeak<Self> = Arc::downgrade(self);
+
+        self.runtime.spawn(async move {
+            match client.delete_clip(&id).await {
+                Ok(()) => {
+                    cache.lock().unwrap().remove(&id);
+                    show_detail(&ui, None);
+                    update_status(&ui, SharedString::from("Clip deleted"));
+
+                    if let Some(controller) = weak_self.upgrade() {
+                        controller.load_clips();
+                    }
+                }
+                Err(err) => {
+                    update_status(
+                        &ui,
+                        SharedString::from(format!("Failed to delete clip: {err}")),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Save a clip's attachment to the default save location from the
+    /// settings, or the downloads folder
+    fn download_attachment(self: &Arc<Self>, id: String) {
+        let clip = {
+            let cache = self.cache.lock().unwrap();
+            cache.get(&id).cloned()
+        };
+
+        let Some(filename) = clip.as_ref().and_then(attachment_name) else {
+            update_status(&self.ui, SharedString::from("Clip has no attachment"));
+            return;
+        };
+
+        let Some(dir) = self
+            .settings
+            .get()
+            .default_save_location
+            .map(PathBuf::from)
+            .or_else(dirs::download_dir)
+            .or_else(dirs::home_dir)
+        else {
+            update_status(
+                &self.ui,
+                SharedString::from("No folder to save the attachment to"),
+            );
+            return;
+        };
+
+        update_status(&self.ui, SharedString::from("Downloading attachment..."));
+
+        let client = self.client_state.get();
+        let ui = self.ui.clone();
+
+        self.runtime.spawn(async move {
+            let path = unique_path(&dir, &filename);
+            let result = async {
+                let mut file = tokio::fs::File::create(&path).await?;
+                client.download_file_to_writer(&id, &mut file).await?;
+                anyhow::Ok(())
+            }
+            .await;
+
+            let status = match result {
+                Ok(()) => format!("Saved to {}", path.display()),
+                Err(err) => {
+                    // Don't leave a partial file behind
+                    let _ = tokio::fs::remove_file(&path).await;
+                    format!("Failed to download attachment: {err}")
+                }
+            };
+            update_status(&ui, SharedString::from(status));
+        });
+    }
+
+    /// Put a clip back on the clipboard: its text, or the image of an
+    /// `$image` clip
+    fn copy_clip(self: &Arc<Self>, id: String) {
+        let clip = {
+            let cache = self.cache.lock().unwrap();
+            cache.get(&id).cloned()
+        };
+
+        let Some(clip) = clip else {
+            update_status(
+                &self.ui,
+                SharedString::from("Clip not found in current list"),
+            );
+            return;
+        };
+
+        if !clip.tags.iter().any(|tag| tag == IMAGE_TAG) {
+            self.last_copied.set_text(clip.content.clone());
+            let status = match clipper_clipboard::set_clipboard_text(&clip.content) {
+                Ok(()) => "Copied to clipboard".to_string(),
+                Err(err) => format!("Failed to copy: {err}"),
+            };
+            update_status(&self.ui, SharedString::from(status));
+            return;
+        }
+
+        update_status(&self.ui, SharedString::from("Downloading image..."));
+
+        let client = self.client_state.get();
+        let ui = self.ui.clone();
+        let last_copied = Arc::clone(&self.last_copied);
+
+        self.runtime.spawn(async move {
+            let status = match client.download_file(&clip.id).await {
+                Ok(image_bytes) => {
+                    last_copied.set_image(image_bytes.clone());
+                    match clipper_clipboard::set_clipboard_image(&image_bytes) {
+                        Ok(()) => "Copied image to clipboard".to_string(),
+                        Err(err) => format!("Failed to copy image: {err}"),
+                    }
+                }
+                Err(err) => format!("Failed to download image: {err}"),
+            };
+            update_status(&ui, SharedString::from(status));
+        });
+    }
+}
+
+/// Whether a notification changes the clips the list may show
+fn changes_clips(notification: &ClipNotification) -> bool {
+    match notification {
+        ClipNotification::NewClip { .. }
+        | ClipNotification::UpdatedClip { .. }
+        | ClipNotification::DeletedClip { .. }
+        | ClipNotification::ClipsCleanedUp { .. } => true,
+        // This app has no clipboard to put pushed clips on
+        ClipNotification::SetClipboard { .. } => false,
+    }
 }
 
 fn parse_date(input: &str, label: &str) -> Result<Option<DateTime<Utc>>, String> {
@@ -581,6 +1062,53 @@ fn clip_to_ui_entry(clip: &Clip) -> ClipEntryData {
     }
 }
 
+fn clip_to_detail(clip: &Clip) -> ClipDetailData {
+    ClipDetailData {
+        id: clip.id.clone().into(),
+        content: clip.content.clone().into(),
+        created_at: format_timestamp(&clip.created_at).into(),
+        tags: clip.tags.join(", ").into(),
+        notes: clip.additional_notes.clone().unwrap_or_default().into(),
+        attachment: attachment_name(clip).unwrap_or_default().into(),
+    }
+}
+
+/// File name of a clip's attachment, without any directories
+fn attachment_name(clip: &Clip) -> Option<String> {
+    let name = clip
+        .original_filename
+        .as_deref()
+        .or(clip.file_attachment.as_deref())?;
+    Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// A path in `dir` for `filename` that isn't taken yet, numbering the name
+/// like "notes (1).txt" when needed
+fn unique_path(dir: &Path, filename: &str) -> PathBuf {
+    let path = dir.join(filename);
+    if !path.exists() {
+        return path;
+    }
+
+    let name = Path::new(filename);
+    let stem = name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| filename.to_string());
+    let extension = name
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string());
+    (1..)
+        .map(|n| match &extension {
+            Some(ext) => dir.join(format!("{stem} ({n}).{ext}")),
+            None => dir.join(format!("{stem} ({n})")),
+        })
+        .find(|path| !path.exists())
+        .expect("ran out of file names")
+}
+
 fn format_timestamp(value: &str) -> String {
     chrono::DateTime::parse_from_rfc3339(value)
         .map(|dt| dt.with_timezone(&Utc).format("%Y-%m-%d %H:%M").to_string())
@@ -596,12 +1124,56 @@ fn update_status(ui: &slint::Weak<App>, text: SharedString) {
     });
 }
 
-fn update_clip_list(ui: &slint::Weak<App>, clips: Vec<ClipEntryData>) {
+/// Open the detail dialog on a clip, or close it with `None`
+fn show_detail(ui: &slint::Weak<App>, detail: Option<ClipDetailData>) {
+    let ui = ui.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ui.upgrade() {
+            match detail {
+                Some(detail) => {
+                    app.set_detail(detail);
+                    app.set_detail_visible(true);
+                }
+                None => app.set_detail_visible(false),
+            }
+        }
+    });
+}
+
+fn update_connection_status(ui: &slint::Weak<App>, text: SharedString) {
+    let ui = ui.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ui.upgrade() {
+            app.set_connection_status(text.clone());
+        }
+    });
+}
+
+fn update_clip_list(ui: &slint::Weak<App>, clips: Vec<ClipEntryData>, has_more: bool) {
     let ui = ui.clone();
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ui.upgrade() {
             let model: ModelRc<ClipEntryData> = Rc::new(VecModel::from(clips)).into();
             app.set_clips(model.clone());
+            app.set_has_more_clips(has_more);
+        }
+    });
+}
+
+fn append_clip_list(ui: &slint::Weak<App>, clips: Vec<ClipEntryData>, has_more: bool) {
+    let ui = ui.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ui.upgrade() {
+            let model = app.get_clips();
+            match model.as_any().downcast_ref::<VecModel<ClipEntryData>>() {
+                Some(list) => list.extend(clips),
+                None => {
+                    let mut all: Vec<ClipEntryData> = model.iter().collect();
+                    all.extend(clips);
+                    app.set_clips(Rc::new(VecModel::from(all)).into());
+                }
+            }
+            app.set_has_more_clips(has_more);
         }
     });
 }
@@ -1,8 +1,10 @@
 use anyhow::{Context, Result};
+use clipper_security::secrets::{CLIENT_SECRETS_SERVICE, SECRETS_FILE_NAME};
+use clipper_security::{ClientSecrets, SecretStore, SecretString};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 // Use the same app identifier as the Tauri app to share settings
 const APP_IDENTIFIER: &str = "codes.unwritten.clipper";
@@ -23,6 +25,8 @@ pub enum Theme {
 /// All fields use camelCase to match the Tauri app's format.
 /// Fields not supported by clipper-slint are still deserialized to maintain
 /// compatibility when reading/writing the shared settings file.
+/// The tokens and trusted certificates are kept in the secret store instead of
+/// the file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
@@ -70,7 +74,7 @@ pub struct Settings {
     #[serde(default = "default_notifications_enabled")]
     pub notifications_enabled: bool,
 
-    /// Global shortcut to toggle window visibility (not used by clipper-slint)
+    /// Global shortcut to toggle window visibility
     #[serde(default = "default_global_shortcut")]
     pub global_shortcut: String,
 
@@ -106,6 +110,14 @@ pub struct Settings {
     #[serde(default)]
     pub trusted_certificates: HashMap<String, String>,
 
+    /// Named server profiles (not used by clipper-slint)
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub server_profiles: serde_json::Value,
+
+    /// Tokens of the server profiles, kept so saving doesn't remove them
+    #[serde(skip)]
+    profile_tokens: BTreeMap<String, SecretString>,
+
     /// Enable debug logging to log file (not used by clipper-slint)
     #[serde(default)]
     pub debug_logging: bool,
@@ -169,14 +181,39 @@ impl Default for Settings {
             settings_window_geometry: serde_json::Value::Object(Default::default()),
             main_window_geometry: serde_json::Value::Object(Default::default()),
             trusted_certificates: HashMap::new(),
+            server_profiles: serde_json::Value::Null,
+            profile_tokens: BTreeMap::new(),
             debug_logging: false,
         }
     }
 }
 
+impl Settings {
+    /// Move the tokens and trusted certificates out of the settings
+    fn take_secrets(&mut self) -> ClientSecrets {
+        ClientSecrets {
+            external_server_token: self.external_server_token.take().map(SecretString::new),
+            bundled_server_token: self.bundled_server_token.take().map(SecretString::new),
+            trusted_certificates: std::mem::take(&mut self.trusted_certificates),
+            profile_tokens: std::mem::take(&mut self.profile_tokens),
+        }
+    }
+
+    fn set_secrets(&mut self, secrets: ClientSecrets) {
+        self.external_server_token = secrets.external_server_token.map(SecretString::into_string);
+        self.bundled_server_token = secrets.bundled_server_token.map(SecretString::into_string);
+        self.trusted_certificates = secrets.trusted_certificates;
+        self.profile_tokens = secrets.profile_tokens;
+    }
+}
+
 pub struct SettingsManager {
     settings_path: PathBuf,
     settings: RwLock<Settings>,
+    /// Tokens and trusted certificates, shared with the Tauri app
+    secrets: SecretStore,
+    /// Secrets as last loaded or saved, so unchanged ones aren't rewritten
+    stored_secrets: Mutex<ClientSecrets>,
 }
 
 impl SettingsManager {
@@ -185,7 +222,8 @@ impl SettingsManager {
             .context("Failed to get config directory")?
             .join(APP_IDENTIFIER);
 
-        std::fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+        clipper_security::secure_create_dir_all(&config_dir)
+            .context("Failed to create config directory")?;
 
         // Secure the config directory and fix any incorrect permissions
         match clipper_security::secure_directory_recursive(&config_dir, |msg| {
@@ -202,12 +240,26 @@ impl SettingsManager {
         }
 
         let settings_path = config_dir.join(SETTINGS_FILE);
-        let settings = Self::load_settings(&settings_path);
+        let mut settings = Self::load_settings(&settings_path);
+
+        // Secrets written by older versions are moved out of settings.json
+        let secrets = SecretStore::new(CLIENT_SECRETS_SERVICE, config_dir.join(SECRETS_FILE_NAME));
+        let plaintext = settings.take_secrets();
+        let migrate = !plaintext.is_empty();
+        let stored_secrets =
+            ClientSecrets::load(&secrets, plaintext).context("Failed to load secrets")?;
+        settings.set_secrets(stored_secrets.clone());
 
-        Ok(Self {
+        let manager = Self {
             settings_path,
             settings: RwLock::new(settings),
-        })
+            secrets,
+            stored_secrets: Mutex::new(stored_secrets),
+        };
+        if migrate {
+            manager.save()?;
+        }
+        Ok(manager)
     }
 
     fn load_settings(path: &PathBuf) -> Settings {
@@ -233,10 +285,21 @@ impl SettingsManager {
     }
 
     pub fn save(&self) -> Result<()> {
-        let settings = self.settings.read().unwrap().clone();
+        let mut settings = self.settings.read().unwrap().clone();
+
+        let secrets = settings.take_secrets();
+        {
+            let mut stored = self.stored_secrets.lock().unwrap();
+            secrets
+                .save(&self.secrets, &stored)
+                .context("Failed to save secrets")?;
+            *stored = secrets;
+        }
+
         let content =
             serde_json::to_string_pretty(&settings).context("Failed to serialize settings")?;
-        std::fs::write(&self.settings_path, content).context("Failed to write settings file")?;
+        clipper_security::secure_write(&self.settings_path, content)
+            .context("Failed to write settings file")?;
         Ok(())
     }
 
@@ -0,0 +1,29 @@
+//! Global shortcut that toggles the window from any app
+
+use anyhow::{Context, Result};
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+use crate::App;
+use crate::tray::toggle_window;
+
+/// Register `shortcut` (e.g. "Ctrl+Shift+V") to toggle the window. It stays
+/// registered while the returned manager is alive.
+pub fn register_toggle_shortcut(
+    shortcut: &str,
+    ui: slint::Weak<App>,
+) -> Result<GlobalHotKeyManager> {
+    let hotkey: HotKey = shortcut
+        .parse()
+        .with_context(|| format!("Invalid shortcut {:?}", shortcut))?;
+    let id = hotkey.id();
+    let manager = GlobalHotKeyManager::new()?;
+    manager.register(hotkey)?;
+
+    GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+        if event.id == id && event.state == HotKeyState::Pressed {
+            toggle_window(&ui);
+        }
+    }));
+    Ok(manager)
+}
@@ -0,0 +1,118 @@
+//! System tray icon, so the app can keep running with its window hidden
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use slint::ComponentHandle;
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+use crate::App;
+
+#[cfg(not(target_os = "linux"))]
+thread_local! {
+    /// The tray icon, kept alive on the main thread
+    static TRAY: std::cell::RefCell<Option<TrayIcon>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Show the tray icon; `tray_active` is set once it is shown
+///
+/// On Linux the icon lives on a GTK thread of its own. Elsewhere it has to be
+/// created on the main thread once the event loop runs.
+pub fn start_tray(ui: slint::Weak<App>, tray_active: Arc<AtomicBool>) {
+    #[cfg(target_os = "linux")]
+    std::thread::spawn(move || {
+        if let Err(e) = gtk::init() {
+            eprintln!(
+                "[clipper-slint] Failed to initialize GTK for the tray icon: {}",
+                e
+            );
+            return;
+        }
+        match build_tray(ui) {
+            Ok(_tray) => {
+                tray_active.store(true, Ordering::SeqCst);
+                gtk::main();
+            }
+            Err(e) => eprintln!("[clipper-slint] Failed to create tray icon: {}", e),
+        }
+    });
+
+    #[cfg(not(target_os = "linux"))]
+    slint::Timer::single_shot(std::time::Duration::ZERO, move || match build_tray(ui) {
+        Ok(tray) => {
+            tray_active.store(true, Ordering::SeqCst);
+            TRAY.with(|cell| *cell.borrow_mut() = Some(tray));
+        }
+        Err(e) => eprintln!("[clipper-slint] Failed to create tray icon: {}", e),
+    });
+}
+
+/// Build the tray icon and its menu, and route their events to the window
+fn build_tray(ui: slint::Weak<App>) -> Result<TrayIcon> {
+    let menu = Menu::new();
+    let toggle = MenuItem::new("Show/Hide Clipper", true, None);
+    let quit = MenuItem::new("Quit", true, None);
+    menu.append_items(&[&toggle, &PredefinedMenuItem::separator(), &quit])?;
+
+    let toggle_id = toggle.id().clone();
+    let quit_id = quit.id().clone();
+    let menu_ui = ui.clone();
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        if event.id == toggle_id {
+            toggle_window(&menu_ui);
+        } else if event.id == quit_id {
+            let _ = slint::invoke_from_event_loop(|| {
+                let _ = slint::quit_event_loop();
+            });
+        }
+    }));
+
+    // Left click toggles the window (not reported on Linux, where the menu
+    // opens instead)
+    TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+        if let TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        } = event
+        {
+            toggle_window(&ui);
+        }
+    }));
+
+    let tray = TrayIconBuilder::new()
+        .with_icon(tray_icon()?)
+        .with_tooltip("Clipper")
+        .with_menu(Box::new(menu))
+        .build()?;
+    Ok(tray)
+}
+
+fn tray_icon() -> Result<Icon> {
+    let image = image::load_from_memory_with_format(
+        include_bytes!("../ui/icons/tray-icon.png"),
+        image::ImageFormat::Png,
+    )?
+    .into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(Icon::from_rgba(image.into_raw(), width, height)?)
+}
+
+/// Show the window if it's hidden, and hide it otherwise
+pub fn toggle_window(ui: &slint::Weak<App>) {
+    let ui = ui.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ui.upgrade() {
+            let result = if app.window().is_visible() {
+                app.hide()
+            } else {
+                app.show()
+            };
+            if let Err(e) = result {
+                eprintln!("[clipper-slint] Failed to toggle window: {}", e);
+            }
+        }
+    });
+}
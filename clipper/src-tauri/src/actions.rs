@@ -0,0 +1,318 @@
+//! User-defined clip actions
+//!
+//! Actions are declared in the `actions` array of settings.json and appear in
+//! the clip context menu (and in the tray when `showInTray` is set):
+//!
+//! ```json
+//! "actions": [
+//!   { "name": "Open in VS Code", "type": "command", "command": "code {file}" },
+//!   { "name": "Translate", "type": "http", "url": "https://example.com/translate?q={content}" }
+//! ]
+//! ```
+//!
+//! The command, URL, headers and body may contain these placeholders:
+//! `{id}`, `{content}`, `{notes}`, `{language}`, `{tags}` (comma-separated),
+//! `{clip}` (the clip as JSON) and `{file}` (a file holding the clip content,
+//! or its file attachment). Values are shell-quoted in commands and
+//! percent-encoded in URLs; headers and bodies get them as-is.
+//!
+//! `{file}` is written to its own folder in the app cache directory, readable
+//! only by the current user, and removed once the command exits or the
+//! request completes.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use clipper_client::{Clip, ClipperClient, SearchFilters};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::settings::{ClipAction, ClipActionKind, SettingsManager};
+use crate::state::AppState;
+
+/// Folder in the app cache directory holding the `{file}` of running actions
+const CACHE_DIR: &str = "actions";
+
+/// Run `action` for `clip`
+///
+/// Commands are started in the background and their exit status is only
+/// logged; HTTP requests fail if the server returns an error status.
+pub async fn run_action(
+    app: &AppHandle,
+    action: &ClipAction,
+    clip: &Clip,
+    client: &ClipperClient,
+) -> Result<(), String> {
+    let file = if uses_placeholder(action, "file") {
+        Some(write_clip_file(app, clip, client).await?)
+    } else {
+        None
+    };
+
+    let result = match placeholder_values(clip, file.as_ref()) {
+        Ok(values) => match &action.kind {
+            ClipActionKind::Command { command } => {
+                // The command removes the file once it exits
+                let command = expand(command, &values, shell_quote);
+                return run_command(&action.name, &command, file);
+            }
+            ClipActionKind::Http {
+                url,
+                method,
+                headers,
+                body,
+            } => send_request(url, method, headers, body.as_deref(), &values).await,
+        },
+        Err(e) => Err(e),
+    };
+
+    if let Some(file) = file {
+        remove_clip_file(&file).await;
+    }
+    result
+}
+
+/// Send the HTTP request of an action, failing on an error status
+async fn send_request(
+    url: &str,
+    method: &str,
+    headers: &std::collections::HashMap<String, String>,
+    body: Option<&str>,
+    values: &[(&'static str, String)],
+) -> Result<(), String> {
+    let url = expand(url, values, |v| urlencoding::encode(v).into_owned());
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", method))?;
+
+    let mut request = reqwest::Client::new().request(method, &url);
+    for (name, value) in headers {
+        request = request.header(name, expand(value, values, str::to_string));
+    }
+    if let Some(body) = body {
+        request = request.body(expand(body, values, str::to_string));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Request failed with status {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Run the action named `name` for the most recent clip (used by the tray)
+///
+/// Failures are logged and reported to the frontend as `action-failed`.
+pub async fn run_on_latest_clip(app: AppHandle, name: String) {
+    if let Err(error) = run_latest(&app, &name).await {
+        log::error!("Action '{}' failed: {}", name, error);
+        let _ = app.emit(
+            "action-failed",
+            serde_json::json!({ "name": name, "error": error }),
+        );
+    }
+}
+
+async fn run_latest(app: &AppHandle, name: &str) -> Result<(), String> {
+    let action = app
+        .state::<SettingsManager>()
+        .get_actions()
+        .into_iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("Action not found: {}", name))?;
+    let client = app.state::<AppState>().client();
+    let clip = client
+        .list_clips(SearchFilters::new(), 1, 1)
+        .await
+        .map_err(|e| e.to_string())?
+        .items
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No clips yet".to_string())?;
+    run_action(app, &action, &clip, &client).await
+}
+
+/// Whether any template of the action contains `{name}`
+fn uses_placeholder(action: &ClipAction, name: &str) -> bool {
+    let placeholder = format!("{{{}}}", name);
+    match &action.kind {
+        ClipActionKind::Command { command } => command.contains(&placeholder),
+        ClipActionKind::Http {
+            url, headers, body, ..
+        } => {
+            url.contains(&placeholder)
+                || headers.values().any(|v| v.contains(&placeholder))
+                || body.as_ref().is_some_and(|b| b.contains(&placeholder))
+        }
+    }
+}
+
+fn placeholder_values(
+    clip: &Clip,
+    file: Option<&PathBuf>,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let clip_json =
+        serde_json::to_string(clip).map_err(|e| format!("Failed to serialize clip: {}", e))?;
+    Ok(vec![
+        ("id", clip.id.clone()),
+        ("content", clip.content.clone()),
+        ("notes", clip.additional_notes.clone().unwrap_or_default()),
+        ("language", clip.language.clone().unwrap_or_default()),
+        ("tags", clip.tags.join(",")),
+        ("clip", clip_json),
+        (
+            "file",
+            file.map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        ),
+    ])
+}
+
+/// Replace `{name}` placeholders in one pass, so values that contain
+/// placeholders themselves are left alone. Unknown placeholders are kept.
+fn expand(
+    template: &str,
+    values: &[(&'static str, String)],
+    escape: impl Fn(&str) -> String,
+) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (end, escape(value)))
+        });
+        match value {
+            Some((end, value)) => {
+                result.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn shell_quote(s: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// Start a shell command and log its exit status when it finishes, then
+/// remove `file`
+fn run_command(name: &str, command: &str, file: Option<PathBuf>) -> Result<(), String> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    let child = cmd
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+
+    let name = name.to_string();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            if let Some(file) = file {
+                tauri::async_runtime::spawn(async move { remove_clip_file(&file).await });
+            }
+            return Err(format!("Failed to run action '{}': {}", name, e));
+        }
+    };
+    tauri::async_runtime::spawn(async move {
+        match child.wait_with_output().await {
+            Ok(output) if !output.status.success() => log::warn!(
+                "Action '{}' exited with {}: {}",
+                name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Ok(_) => {}
+            Err(e) => log::warn!("Action '{}' failed: {}", name, e),
+        }
+        if let Some(file) = file {
+            remove_clip_file(&file).await;
+        }
+    });
+    Ok(())
+}
+
+/// Write the clip's attachment, or its text content, to a new folder in the
+/// cache that only the current user can access
+async fn write_clip_file(
+    app: &AppHandle,
+    clip: &Clip,
+    client: &ClipperClient,
+) -> Result<PathBuf, String> {
+    static NEXT_DIR: AtomicU64 = AtomicU64::new(0);
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get cache directory: {}", e))?
+        .join(CACHE_DIR)
+        .join(format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+    clipper_security::secure_create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+
+    let (file_name, bytes) = if clip.file_attachment.is_some() {
+        let bytes = match client.download_file(&clip.id).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let _ = tokio::fs::remove_dir_all(&dir).await;
+                return Err(format!("Failed to download file: {}", e));
+            }
+        };
+        let file_name = clip
+            .original_filename
+            .as_deref()
+            .and_then(|name| Path::new(name).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "attachment".to_string());
+        (file_name, bytes)
+    } else {
+        ("clip.txt".to_string(), clip.content.clone().into_bytes())
+    };
+
+    let path = dir.join(file_name);
+    if let Err(e) = clipper_security::secure_write(&path, bytes) {
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        return Err(format!("Failed to write file: {}", e));
+    }
+    Ok(path)
+}
+
+/// Remove a file written by `write_clip_file`, along with its folder
+async fn remove_clip_file(path: &Path) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if let Err(e) = tokio::fs::remove_dir_all(dir).await {
+        log::warn!("Failed to remove {}: {}", dir.display(), e);
+    }
+}
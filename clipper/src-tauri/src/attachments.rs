@@ -0,0 +1,187 @@
+//! Attachment cache for showing attachments in the file manager
+//!
+//! `reveal` downloads a clip's file attachment into the app's cache directory
+//! and shows it selected in Finder, Explorer or the Linux file manager, from
+//! where it can be dragged into other apps. The cache folders and files are
+//! created with owner-only permissions by clipper-security. Each clip gets its
+//! own folder, so attachments keep their original names. `drag_out.rs` drags
+//! the same cached files out of the window.
+//!
+//! Attachments are downloaded once and reused. Folders unused for `MAX_AGE`
+//! are removed, then the least recently used until the cache fits in
+//! `MAX_CACHE_BYTES`; this runs at startup and after each download.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+/// Folder in the app cache directory holding the attachments
+const CACHE_DIR: &str = "attachments";
+
+/// How long an attachment is kept after it was last revealed
+const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Largest total size of the cached attachments
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Download a clip's attachment to the cache (unless already there) and show
+/// it in the file manager
+pub async fn reveal(app: &AppHandle, clip_id: &str) -> Result<(), String> {
+    let path = cached_attachment(app, clip_id).await?;
+    tauri_plugin_opener::reveal_item_in_dir(&path)
+        .map_err(|e| format!("Failed to show the file: {}", e))
+}
+
+/// Evict old attachments in the background, never removing `keep`
+pub fn spawn_eviction(app: &AppHandle, keep: Option<PathBuf>) {
+    let Ok(cache_dir) = cache_dir(app) else {
+        return;
+    };
+    tauri::async_runtime::spawn_blocking(move || evict(&cache_dir, keep.as_deref()));
+}
+
+fn cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join(CACHE_DIR))
+        .map_err(|e| format!("Failed to get cache directory: {}", e))
+}
+
+/// Whether `path` is a cached attachment, such as one dragged out of the
+/// window and dropped back on it
+pub fn is_cached(app: &AppHandle, path: &Path) -> bool {
+    cache_dir(app).is_ok_and(|dir| path.starts_with(dir))
+}
+
+/// Path of a clip's attachment in the cache, downloading it first unless
+/// already there
+pub async fn cached_attachment(app: &AppHandle, clip_id: &str) -> Result<PathBuf, String> {
+    // The ID names a folder, so it must not lead outside the cache
+    if !is_plain_file_name(clip_id) {
+        return Err("Invalid clip ID".to_string());
+    }
+    let client = app.state::<AppState>().client();
+    let clip = client
+        .get_clip(clip_id)
+        .await
+        .map_err(|e| format!("Failed to get clip: {}", e))?;
+    let Some(stored_name) = clip.file_attachment.as_deref() else {
+        return Err("Clip has no attachment".to_string());
+    };
+    let filename = sanitize_file_name(clip.original_filename.as_deref().unwrap_or(stored_name));
+
+    let dir = cache_dir(app)?.join(clip_id);
+    let path = dir.join(&filename);
+    if path.exists() {
+        // Mark it as recently used for eviction
+        let _ = std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(path);
+    }
+
+    clipper_security::secure_create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    // Download next to the target, so a failed download isn't mistaken for
+    // a cached attachment
+    let partial = dir.join(format!("{}.part", filename));
+    let _ = tokio::fs::remove_file(&partial).await;
+    let file = clipper_security::secure_create_file(&partial)
+        .map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut file = tokio::fs::File::from_std(file);
+    if let Err(e) = client.download_file_to_writer(clip_id, &mut file).await {
+        drop(file);
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e.to_string());
+    }
+    drop(file);
+    tokio::fs::rename(&partial, &path)
+        .await
+        .map_err(|e| format!("Failed to save file: {}", e))?;
+
+    spawn_eviction(app, Some(path.clone()));
+    Ok(path)
+}
+
+/// Whether `name` is a single path component
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+}
+
+/// The file name part of `name`, with characters Windows doesn't allow in
+/// file names replaced
+fn sanitize_file_name(name: &str) -> String {
+    let name = Path::new(name)
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if is_plain_file_name(&name) {
+        name
+    } else {
+        "attachment".to_string()
+    }
+}
+
+/// Remove the clip folders unused for `MAX_AGE`, then the least recently used
+/// until the cache fits in `MAX_CACHE_BYTES`
+fn evict(cache_dir: &Path, keep: Option<&Path>) {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    let mut folders: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let (last_used, size) = folder_usage(&path);
+            (last_used, size, path)
+        })
+        .collect();
+    folders.sort_by_key(|(last_used, _, _)| *last_used);
+
+    let mut total: u64 = folders.iter().map(|(_, size, _)| size).sum();
+    for (last_used, size, path) in folders {
+        if keep.is_some_and(|keep| keep.starts_with(&path)) {
+            continue;
+        }
+        let expired = now.duration_since(last_used).unwrap_or_default() > MAX_AGE;
+        if !expired && total <= MAX_CACHE_BYTES {
+            continue;
+        }
+        match std::fs::remove_dir_all(&path) {
+            Ok(()) => total -= size,
+            Err(e) => log::warn!("Failed to evict cached attachment: {}", e),
+        }
+    }
+}
+
+/// When a clip folder's files were last used, and their total size
+fn folder_usage(dir: &Path) -> (SystemTime, u64) {
+    let mut last_used = SystemTime::UNIX_EPOCH;
+    let mut size = 0;
+    for metadata in std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok())
+    {
+        if let Ok(modified) = metadata.modified() {
+            last_used = last_used.max(modified);
+        }
+        size += metadata.len();
+    }
+    (last_used, size)
+}
@@ -2,6 +2,11 @@
 #[allow(unused_imports)]
 use std::path::PathBuf;
 
+// Autostart entries start the app in the tray (the macOS plist spells the
+// flag out)
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+use crate::launch_args::HIDDEN;
+
 #[cfg(target_os = "macos")]
 const LAUNCH_AGENT_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
@@ -12,6 +17,7 @@ const LAUNCH_AGENT_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
     <key>ProgramArguments</key>
     <array>
         <string>{}</string>
+        <string>--hidden</string>
     </array>
     <key>RunAtLoad</key>
     <true/>
@@ -160,10 +166,11 @@ async fn set_auto_launch_linux(enabled: bool) -> Result<(), String> {
             r#"[Desktop Entry]
 Type=Application
 Name=Clipper
-Exec={}
+Exec={} {}
 X-GNOME-Autostart-enabled=true
 "#,
-            exe_path.to_string_lossy()
+            exe_path.to_string_lossy(),
+            HIDDEN
         );
 
         tokio::fs::write(&autostart_path, desktop_content)
@@ -204,8 +211,9 @@ async fn set_auto_launch_windows(enabled: bool) -> Result<(), String> {
     if enabled {
         let exe_path =
             std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
+        let command = format!("\"{}\" {}", exe_path.to_string_lossy(), HIDDEN);
         run_key
-            .set_value("Clipper", &exe_path.to_string_lossy().to_string())
+            .set_value("Clipper", &command)
             .map_err(|e| format!("Failed to set registry value: {}", e))?;
     } else {
         // Ignore error if value doesn't exist
@@ -1,129 +1,398 @@
+#[cfg(target_os = "linux")]
+use crate::settings::RestoreSelection;
+use crate::settings::{CopiedFilesMode, SettingsManager, SyncConflictPolicy};
 use crate::state::AppState;
-use arboard::Clipboard;
-use chrono::Utc;
-use gethostname::gethostname;
-use image::{ImageBuffer, Rgba};
-use std::io::Cursor;
-use std::path::PathBuf;
-use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::time::Duration;
+use chrono::{DateTime, Utc};
+use clipper_client::Clip;
+#[cfg(target_os = "linux")]
+use clipper_clipboard::primary::{PRIMARY_SELECTION_TAG, set_primary_selection_text};
+use clipper_clipboard::{
+    ClipboardContent, ClipboardEvent, MonitorOptions, POLL_INTERVAL, file_list_content,
+    hostname_tag, watch_clipboard,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
-/// Get the hostname tag in the format `$host:<hostname>`
-fn get_hostname_tag() -> String {
-    let hostname = gethostname().to_string_lossy().to_string();
-    format!("$host:{}", hostname)
+/// Paste stack: while enabled, every copied text is queued, and each pop
+/// puts the oldest queued item back on the clipboard
+#[derive(Default)]
+pub struct PasteStack {
+    enabled: AtomicBool,
+    items: Mutex<VecDeque<String>>,
 }
 
-const POLL_INTERVAL_MS: u64 = 500;
+/// Paste stack state sent to the frontend with `paste-stack-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteStackStatus {
+    pub enabled: bool,
+    pub count: usize,
+}
+
+impl PasteStack {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Turn stack mode on or off; the queue starts empty either way
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+        self.clear();
+    }
+
+    /// Queue copied text (ignored unless stack mode is on)
+    pub fn push(&self, text: String) {
+        if self.is_enabled() {
+            self.items.lock().unwrap().push_back(text);
+        }
+    }
+
+    pub fn pop(&self) -> Option<String> {
+        self.items.lock().unwrap().pop_front()
+    }
+
+    pub fn clear(&self) {
+        self.items.lock().unwrap().clear();
+    }
 
-/// Represents the type of clipboard content
-#[derive(Clone, PartialEq)]
-enum ClipboardContent {
-    Text(String),
-    Image(Vec<u8>),      // PNG-encoded bytes
-    Files(Vec<PathBuf>), // File paths from clipboard (e.g., copied from Finder/Explorer)
-    Empty,
+    pub fn status(&self) -> PasteStackStatus {
+        PasteStackStatus {
+            enabled: self.is_enabled(),
+            count: self.items.lock().unwrap().len(),
+        }
+    }
 }
 
-/// Convert arboard ImageData to PNG bytes
-fn image_data_to_png(image_data: &arboard::ImageData) -> Option<Vec<u8>> {
-    let width = image_data.width as u32;
-    let height = image_data.height as u32;
+/// Toggle stack mode (used by the paste stack shortcut and the tray)
+pub fn toggle_paste_stack(app: &AppHandle) {
+    let paste_stack = &app.state::<AppState>().paste_stack;
+    paste_stack.set_enabled(!paste_stack.is_enabled());
+    notify_paste_stack_changed(app);
+}
 
-    // Create an image buffer from RGBA bytes
-    let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
-        ImageBuffer::from_raw(width, height, image_data.bytes.to_vec())?;
+/// Put the next queued item on the clipboard without creating a new clip
+///
+/// Returns the item, or `None` if the queue is empty.
+pub fn pop_paste_stack(app: &AppHandle) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
+    let Some(text) = state.paste_stack.pop() else {
+        return Ok(None);
+    };
+    // Mark as synced first so the clipboard monitor doesn't queue it again
+    state.set_last_synced_content(text.clone());
+    set_clipboard_content(&text)?;
+    notify_paste_stack_changed(app);
+    Ok(Some(text))
+}
 
-    // Encode to PNG
-    let mut png_bytes = Cursor::new(Vec::new());
-    img.write_to(&mut png_bytes, image::ImageFormat::Png).ok()?;
+/// Tell the frontend and the tray that the paste stack changed
+pub fn notify_paste_stack_changed(app: &AppHandle) {
+    let status = app.state::<AppState>().paste_stack.status();
+    let _ = app.emit("paste-stack-changed", &status);
+    if let Err(e) = crate::tray::refresh_tray(app) {
+        log::warn!("[clipboard] Failed to update tray menu: {}", e);
+    }
+}
 
-    Some(png_bytes.into_inner())
+/// Separator put between appended copies
+const APPEND_SEPARATOR: &str = "\n";
+
+/// Append mode: while enabled, the first copied text creates a clip as usual
+/// and each later copy is appended to that clip instead of creating a new one
+#[derive(Default)]
+pub struct AppendMode {
+    enabled: AtomicBool,
+    /// Bumped on every toggle, so a target from an earlier run is not reused
+    session: AtomicU64,
+    /// Clip that copies are appended to, with the session it belongs to;
+    /// locked for the whole append so copies land in order
+    target: tokio::sync::Mutex<Option<(u64, String)>>,
 }
 
-/// Result of attempting to get clipboard content
-enum ClipboardResult {
-    /// Successfully retrieved content
-    Content(ClipboardContent),
-    /// Clipboard access failed - handle may need recreation
-    AccessError(String),
+/// Append mode state sent to the frontend with `append-mode-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendModeStatus {
+    pub enabled: bool,
 }
 
-/// Get current clipboard content (text, image, or files)
-/// Returns ClipboardResult to indicate whether the clipboard handle is still valid
-/// Priority: files > images > text (files take highest priority since copying files
-/// in Finder/Explorer also provides text fallback with filenames)
-fn get_clipboard_content(clipboard: &mut Clipboard) -> ClipboardResult {
-    // Try to get file list first (highest priority)
-    // When copying files in Finder/Explorer, the clipboard contains both file URIs and text fallback
-    match clipboard.get().file_list() {
-        Ok(files) => {
-            if !files.is_empty() {
-                return ClipboardResult::Content(ClipboardContent::Files(files));
-            }
-        }
-        Err(arboard::Error::ContentNotAvailable) => {
-            // No file content, this is normal - try image
-        }
-        Err(e) => {
-            // Other errors might indicate clipboard handle issues
-            eprintln!("[clipboard] File list access error: {}", e);
+impl AppendMode {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Turn append mode on or off; the next copy starts a new clip either way
+    pub fn set_enabled(&self, enabled: bool) {
+        self.session.fetch_add(1, Ordering::SeqCst);
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn status(&self) -> AppendModeStatus {
+        AppendModeStatus {
+            enabled: self.is_enabled(),
         }
     }
+}
+
+/// Toggle append mode (used by the append mode shortcut and the tray)
+pub fn toggle_append_mode(app: &AppHandle) {
+    let append_mode = &app.state::<AppState>().append_mode;
+    append_mode.set_enabled(!append_mode.is_enabled());
+    notify_append_mode_changed(app);
+}
+
+/// Tell the frontend and the tray that append mode was turned on or off
+pub fn notify_append_mode_changed(app: &AppHandle) {
+    let status = app.state::<AppState>().append_mode.status();
+    let _ = app.emit("append-mode-changed", &status);
+    if let Err(e) = crate::tray::refresh_tray(app) {
+        log::warn!("[clipboard] Failed to update tray menu: {}", e);
+    }
+}
 
-    // Try to get image (second priority)
-    match clipboard.get_image() {
-        Ok(image_data) => {
-            if let Some(png_bytes) = image_data_to_png(&image_data) {
-                return ClipboardResult::Content(ClipboardContent::Image(png_bytes));
+/// Save copied text in append mode: append it to the target clip, or create
+/// a new clip and make it the target when there is none or the append fails
+/// (for example because the clip was deleted)
+async fn append_copied_text(
+    app: &AppHandle,
+    append_mode: &AppendMode,
+    client: &clipper_client::ClipperClient,
+    text: String,
+    hostname_tag: String,
+) {
+    let mut target = append_mode.target.lock().await;
+    let session = append_mode.session.load(Ordering::SeqCst);
+    if let Some((_, id)) = target.as_ref().filter(|(s, _)| *s == session) {
+        match client
+            .append_to_clip(id, text.clone(), APPEND_SEPARATOR)
+            .await
+        {
+            Ok(clip) => {
+                let _ = app.emit("clip-updated", serde_json::json!({ "id": clip.id }));
+                return;
+            }
+            Err(e) => {
+                eprintln!("[clipboard] Failed to append to clip {}: {}", id, e);
             }
         }
-        Err(arboard::Error::ContentNotAvailable) => {
-            // No image content, this is normal - try text
+    }
+    match client
+        .create_clip(text, vec![hostname_tag], None, None)
+        .await
+    {
+        Ok(clip) => {
+            // A copy made after append mode was turned off starts no chain
+            if append_mode.is_enabled() {
+                *target = Some((session, clip.id.clone()));
+            }
+            let _ = app.emit("clip-created", &clip);
         }
         Err(e) => {
-            // Other errors might indicate clipboard handle issues
-            eprintln!("[clipboard] Image access error: {}", e);
+            eprintln!("[clipboard] Failed to create clip from text: {}", e);
         }
     }
+}
+
+/// How long `set_capture_paused` pauses clipboard capture
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CapturePauseDuration {
+    FifteenMinutes,
+    OneHour,
+    UntilResumed,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum PauseState {
+    #[default]
+    Capturing,
+    Until(DateTime<Utc>),
+    UntilResumed,
+}
 
-    // Fall back to text (lowest priority)
-    match clipboard.get_text() {
-        Ok(text) => {
-            if !text.is_empty() {
-                return ClipboardResult::Content(ClipboardContent::Text(text));
+/// Capture pause (incognito mode): while paused, the clipboard monitor
+/// doesn't save anything that is copied
+#[derive(Default)]
+pub struct CapturePause {
+    state: Mutex<PauseState>,
+}
+
+/// Capture pause state sent to the frontend with `capture-pause-changed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturePauseStatus {
+    pub paused: bool,
+    /// When capture resumes by itself; `None` if paused until resumed
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl CapturePause {
+    /// Pause for `duration`, or resume with `None`
+    pub fn set(&self, duration: Option<CapturePauseDuration>) {
+        let now = Utc::now();
+        *self.state.lock().unwrap() = match duration {
+            None => PauseState::Capturing,
+            Some(CapturePauseDuration::FifteenMinutes) => {
+                PauseState::Until(now + chrono::Duration::minutes(15))
             }
-            ClipboardResult::Content(ClipboardContent::Empty)
-        }
-        Err(arboard::Error::ContentNotAvailable) => {
-            // No text content either
-            ClipboardResult::Content(ClipboardContent::Empty)
+            Some(CapturePauseDuration::OneHour) => {
+                PauseState::Until(now + chrono::Duration::hours(1))
+            }
+            Some(CapturePauseDuration::UntilResumed) => PauseState::UntilResumed,
+        };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.status().paused
+    }
+
+    /// Resume if the pause has run out; returns whether it did
+    fn resume_if_expired(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            PauseState::Until(until) if until <= Utc::now() => {
+                *state = PauseState::Capturing;
+                true
+            }
+            _ => false,
         }
-        Err(e) => {
-            // Access error - clipboard handle may be stale
-            ClipboardResult::AccessError(format!("Clipboard text access error: {}", e))
+    }
+
+    pub fn status(&self) -> CapturePauseStatus {
+        match *self.state.lock().unwrap() {
+            PauseState::Until(until) if until > Utc::now() => CapturePauseStatus {
+                paused: true,
+                until: Some(until),
+            },
+            PauseState::UntilResumed => CapturePauseStatus {
+                paused: true,
+                until: None,
+            },
+            _ => CapturePauseStatus {
+                paused: false,
+                until: None,
+            },
         }
     }
 }
 
-/// Try to create a new clipboard handle, with retry logic
-fn create_clipboard() -> Option<Clipboard> {
-    match Clipboard::new() {
-        Ok(cb) => Some(cb),
-        Err(e) => {
-            eprintln!("[clipboard] Failed to create clipboard handle: {}", e);
+/// Pause clipboard capture for `duration`, or resume it with `None`
+pub fn set_capture_paused(app: &AppHandle, duration: Option<CapturePauseDuration>) {
+    app.state::<AppState>().capture_pause.set(duration);
+    notify_capture_pause_changed(app);
+}
+
+/// Tell the frontend and the tray that capture was paused or resumed
+fn notify_capture_pause_changed(app: &AppHandle) {
+    let status = app.state::<AppState>().capture_pause.status();
+    let _ = app.emit("capture-pause-changed", &status);
+    if let Err(e) = crate::tray::refresh_tray(app) {
+        log::warn!("[clipboard] Failed to update tray menu: {}", e);
+    }
+}
+
+/// How long after text is copied here a clip from another device counts as
+/// copied at the same time
+const SYNC_CONFLICT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Text last copied on this device, kept for a moment so a clip another
+/// device copied at about the same time doesn't silently replace it on the
+/// clipboard (see `SyncConflictPolicy`)
+#[derive(Default)]
+pub struct LocalCopy {
+    copy: Mutex<Option<CopyRecord>>,
+}
+
+struct CopyRecord {
+    content: String,
+    copied_at: Instant,
+    /// Whether the server sent the clip back. Clips from other devices that
+    /// arrive before that were stored before it.
+    stored: bool,
+}
+
+impl LocalCopy {
+    /// Remember text that was just copied here
+    pub fn record(&self, content: String) {
+        *self.copy.lock().unwrap() = Some(CopyRecord {
+            content,
+            copied_at: Instant::now(),
+            stored: false,
+        });
+    }
+
+    /// Note that the server sent back a clip made on this device
+    pub fn mark_stored(&self, content: &str) {
+        if let Some(copy) = self.copy.lock().unwrap().as_mut()
+            && copy.content == content
+        {
+            copy.stored = true;
+        }
+    }
+
+    /// Decide whether a text clip from another device may replace the
+    /// clipboard. Returns the text copied here if it stays instead.
+    pub fn resolve_remote(&self, content: &str, policy: SyncConflictPolicy) -> Option<String> {
+        let mut guard = self.copy.lock().unwrap();
+        let copy = guard.as_ref()?;
+        if copy.content == content || copy.copied_at.elapsed() > SYNC_CONFLICT_WINDOW {
+            *guard = None;
+            return None;
+        }
+        let keep_local = match policy {
+            SyncConflictPolicy::KeepBoth | SyncConflictPolicy::Prompt => true,
+            // Not stored yet means the server stores it after the other clip
+            SyncConflictPolicy::NewestWins => !copy.stored,
+        };
+        if keep_local {
+            Some(copy.content.clone())
+        } else {
+            *guard = None;
             None
         }
     }
 }
 
+/// Save text from the primary selection as a new clip
+#[cfg(target_os = "linux")]
+fn save_primary_selection(app: &AppHandle, rt: &tokio::runtime::Runtime, text: String) {
+    let client = app.state::<AppState>().client();
+    let app_handle = app.clone();
+    let tags = vec![PRIMARY_SELECTION_TAG.to_string(), hostname_tag()];
+    rt.spawn(async move {
+        match client.create_clip(text, tags, None, None).await {
+            Ok(clip) => {
+                let _ = app_handle.emit("clip-created", &clip);
+            }
+            Err(e) => {
+                eprintln!(
+                    "[clipboard] Failed to create clip from primary selection: {}",
+                    e
+                );
+            }
+        }
+    });
+}
+
 pub fn start_clipboard_monitor(app: AppHandle) {
     let state = app.state::<AppState>();
     let last_synced = Arc::clone(&state.last_synced_content);
     let last_synced_image = Arc::clone(&state.last_synced_image);
-    let last_content = Arc::new(std::sync::Mutex::new(ClipboardContent::Empty));
+    let paste_stack = Arc::clone(&state.paste_stack);
+    let append_mode = Arc::clone(&state.append_mode);
+    let capturing_screenshot = Arc::clone(&state.capturing_screenshot);
+    let capture_pause = Arc::clone(&state.capture_pause);
+    let local_copy = Arc::clone(&state.local_copy);
+    let max_image_size = Arc::clone(&state.max_image_size_bytes);
+    let options = MonitorOptions {
+        capture_images: Arc::clone(&state.capture_images),
+        capture_primary_selection: Arc::clone(&state.capture_primary_selection),
+    };
     // Get a reference to the max upload size (AtomicU64 wrapped in Arc)
     let max_upload_size_arc = state.max_upload_size_arc();
 
@@ -137,94 +406,52 @@ pub fn start_clipboard_monitor(app: AppHandle) {
             }
         };
 
-        let mut clipboard: Option<Clipboard> = create_clipboard();
-        let mut consecutive_errors: u32 = 0;
-        const MAX_CONSECUTIVE_ERRORS: u32 = 10;
-        const ERROR_BACKOFF_MS: u64 = 1000;
-
-        // Initialize with current clipboard content if we have a handle
-        if let Some(ref mut cb) = clipboard
-            && let ClipboardResult::Content(content) = get_clipboard_content(cb)
-        {
-            // Handle potential mutex poisoning gracefully
-            if let Ok(mut guard) = last_content.lock() {
-                *guard = content;
-            }
-        }
+        let events = watch_clipboard(options);
+        // Last text saved from the primary selection, so copying the selected
+        // text doesn't save it again
+        #[cfg(target_os = "linux")]
+        let mut saved_primary_selection: Option<String> = None;
 
         loop {
-            // Use longer sleep if we're experiencing errors
-            let sleep_duration = if consecutive_errors > 0 {
-                Duration::from_millis(ERROR_BACKOFF_MS * consecutive_errors as u64)
-            } else {
-                Duration::from_millis(POLL_INTERVAL_MS)
+            // Wake up between events too, to resume capture when a pause ends
+            let event = match events.recv_timeout(POLL_INTERVAL) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
             };
-            std::thread::sleep(sleep_duration);
-
-            // Ensure we have a valid clipboard handle
-            if clipboard.is_none() {
-                eprintln!("[clipboard] Attempting to recreate clipboard handle...");
-                clipboard = create_clipboard();
-                if clipboard.is_some() {
-                    eprintln!("[clipboard] Successfully recreated clipboard handle after error");
-                    consecutive_errors = 0;
-                } else {
-                    consecutive_errors = consecutive_errors.saturating_add(1);
-                    if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-                        eprintln!(
-                            "[clipboard] Failed to recreate clipboard handle after {} attempts, backing off",
-                            consecutive_errors
-                        );
-                    }
-                    continue;
-                }
-            }
-
-            let cb = clipboard.as_mut().unwrap();
-            let current_content = match get_clipboard_content(cb) {
-                ClipboardResult::Content(content) => {
-                    consecutive_errors = 0;
-                    content
-                }
-                ClipboardResult::AccessError(err) => {
-                    consecutive_errors = consecutive_errors.saturating_add(1);
-                    eprintln!(
-                        "[clipboard] Access error (attempt {}): {}",
-                        consecutive_errors, err
-                    );
 
-                    // Invalidate the clipboard handle so it gets recreated
-                    clipboard = None;
+            if capture_pause.resume_if_expired() {
+                notify_capture_pause_changed(&app);
+            }
 
-                    if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
-                        eprintln!(
-                            "[clipboard] Access failing repeatedly ({} times), will keep retrying with backoff",
-                            consecutive_errors
-                        );
-                    }
-                    continue;
-                }
+            let Some(event) = event else {
+                continue;
             };
-
-            // Skip if clipboard is empty
-            if current_content == ClipboardContent::Empty {
+            // While capture is paused, what is copied isn't saved; the event
+            // stream keeps track of it, so it isn't saved after resuming either
+            if capture_pause.is_paused() {
                 continue;
             }
 
-            // Handle potential mutex poisoning gracefully
-            let last = match last_content.lock() {
-                Ok(guard) => guard.clone(),
-                Err(poisoned) => {
-                    eprintln!("[clipboard] last_content mutex was poisoned, recovering");
-                    poisoned.into_inner().clone()
+            let current_content = match event {
+                ClipboardEvent::Copied(content) => content,
+                #[cfg(target_os = "linux")]
+                ClipboardEvent::Selected(text) => {
+                    // Skip text that was just restored
+                    let restored = match last_synced.lock() {
+                        Ok(guard) => *guard == text,
+                        Err(poisoned) => *poisoned.into_inner() == text,
+                    };
+                    if !restored {
+                        saved_primary_selection = Some(text.clone());
+                        save_primary_selection(&app, &rt, text);
+                    }
+                    continue;
                 }
+                #[cfg(not(target_os = "linux"))]
+                ClipboardEvent::Selected(_) => continue,
             };
 
-            // Skip if content hasn't changed
-            if current_content == last {
-                continue;
-            }
-
             // For text content, check if it was just synced from server (avoid loop)
             if let ClipboardContent::Text(ref text) = current_content {
                 let synced = match last_synced.lock() {
@@ -235,14 +462,18 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                     }
                 };
                 if *text == synced {
-                    match last_content.lock() {
-                        Ok(mut guard) => *guard = current_content,
-                        Err(poisoned) => *poisoned.into_inner() = current_content,
-                    }
                     continue;
                 }
             }
 
+            // Leave images to the screenshot capture while it waits for a snip;
+            // it uploads the snip itself and marks it as synced
+            if matches!(current_content, ClipboardContent::Image(_))
+                && capturing_screenshot.load(Ordering::SeqCst)
+            {
+                continue;
+            }
+
             // For image content, check if it was just synced from server (avoid loop)
             if let ClipboardContent::Image(ref png_bytes) = current_content {
                 let synced_image = match last_synced_image.lock() {
@@ -253,44 +484,61 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                     }
                 };
                 if *png_bytes == synced_image {
-                    match last_content.lock() {
-                        Ok(mut guard) => *guard = current_content,
-                        Err(poisoned) => *poisoned.into_inner() = current_content,
-                    }
                     continue;
                 }
             }
 
-            // Content changed, update last content
-            match last_content.lock() {
-                Ok(mut guard) => *guard = current_content.clone(),
-                Err(poisoned) => *poisoned.into_inner() = current_content.clone(),
-            }
-
             // Get a fresh client from the app state each time to pick up URL changes
             let client = app.state::<AppState>().client();
             let app_handle = app.clone();
 
             match current_content {
                 ClipboardContent::Text(text) => {
-                    let hostname_tag = get_hostname_tag();
-                    rt.spawn(async move {
-                        match client
-                            .create_clip(text, vec![hostname_tag], None, None)
-                            .await
-                        {
-                            Ok(clip) => {
-                                let _ = app_handle.emit("clip-created", &clip);
-                            }
-                            Err(e) => {
-                                eprintln!("[clipboard] Failed to create clip from text: {}", e);
+                    local_copy.record(text.clone());
+                    if paste_stack.is_enabled() {
+                        paste_stack.push(text.clone());
+                        notify_paste_stack_changed(&app);
+                    }
+                    #[cfg(target_os = "linux")]
+                    // Selected text that is then copied was saved with the selection
+                    if !append_mode.is_enabled() && saved_primary_selection.as_ref() == Some(&text)
+                    {
+                        continue;
+                    }
+                    let hostname_tag = hostname_tag();
+                    if append_mode.is_enabled() {
+                        let append_mode = Arc::clone(&append_mode);
+                        rt.spawn(async move {
+                            append_copied_text(
+                                &app_handle,
+                                &append_mode,
+                                &client,
+                                text,
+                                hostname_tag,
+                            )
+                            .await;
+                        });
+                    } else {
+                        rt.spawn(async move {
+                            match client
+                                .create_clip(text, vec![hostname_tag], None, None)
+                                .await
+                            {
+                                Ok(clip) => {
+                                    let _ = app_handle.emit("clip-created", &clip);
+                                }
+                                Err(e) => {
+                                    eprintln!("[clipboard] Failed to create clip from text: {}", e);
+                                }
                             }
-                        }
-                    });
+                        });
+                    }
                 }
                 ClipboardContent::Image(png_bytes) => {
-                    // Check size limit before uploading
-                    let max_size = max_upload_size_arc.load(Ordering::SeqCst);
+                    // Check size limits before uploading
+                    let max_size = max_upload_size_arc
+                        .load(Ordering::SeqCst)
+                        .min(max_image_size.load(Ordering::SeqCst));
                     if png_bytes.len() as u64 > max_size {
                         let max_size_mb = max_size as f64 / (1024.0 * 1024.0);
                         let file_size_mb = png_bytes.len() as f64 / (1024.0 * 1024.0);
@@ -302,7 +550,7 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                     }
                     let filename =
                         format!("screenshot-{}.png", Utc::now().format("%Y-%m-%d-%H-%M-%S"));
-                    let hostname_tag = get_hostname_tag();
+                    let hostname_tag = hostname_tag();
                     rt.spawn(async move {
                         match client
                             .upload_file_bytes(
@@ -323,11 +571,26 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                     });
                 }
                 ClipboardContent::Files(paths) => {
-                    // Upload files copied from Finder/Explorer
-                    let max_size = max_upload_size_arc.load(Ordering::SeqCst);
-                    let hostname_tag = get_hostname_tag();
+                    // Upload or list files copied from Finder/Explorer
+                    let settings = app.state::<SettingsManager>().get();
+                    let upload = match settings.copied_files {
+                        CopiedFilesMode::Upload => true,
+                        CopiedFilesMode::List => false,
+                        CopiedFilesMode::Off => continue,
+                    };
+                    let server_max_size = max_upload_size_arc.load(Ordering::SeqCst);
+                    let max_size =
+                        server_max_size.min(settings.max_copied_file_size_mb * 1024 * 1024);
+                    let hostname_tag = hostname_tag();
                     rt.spawn(async move {
+                        // Files that aren't uploaded, saved together as a list
+                        let mut listed = Vec::new();
                         for path in paths {
+                            if !upload {
+                                listed.push(path);
+                                continue;
+                            }
+
                             // Check if file exists and get metadata
                             let metadata = match tokio::fs::metadata(&path).await {
                                 Ok(m) => m,
@@ -341,35 +604,37 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                                 }
                             };
 
-                            // Skip directories
+                            // List directories
                             if metadata.is_dir() {
-                                eprintln!(
-                                    "[clipboard] Skipping directory: {}",
-                                    path.display()
-                                );
+                                listed.push(path);
                                 continue;
                             }
 
                             // Check file size
                             if metadata.len() > max_size {
-                                let file_size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-                                let max_size_mb = max_size / (1024 * 1024);
-                                eprintln!(
-                                    "[clipboard] File {} ({:.2} MB) exceeds maximum allowed size ({} MB), skipping",
-                                    path.display(),
-                                    file_size_mb,
-                                    max_size_mb
-                                );
-                                // Emit error event for toast notification
-                                let _ = app_handle.emit(
-                                    "file-upload-error",
-                                    serde_json::json!({
-                                        "path": path.to_string_lossy(),
-                                        "error": "file_too_large",
-                                        "size_mb": file_size_mb,
-                                        "max_size_mb": max_size_mb
-                                    }),
-                                );
+                                // Only tell the user when the server couldn't take it;
+                                // the threshold setting is a choice, not an error
+                                if metadata.len() > server_max_size {
+                                    let file_size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
+                                    let max_size_mb = server_max_size / (1024 * 1024);
+                                    eprintln!(
+                                        "[clipboard] File {} ({:.2} MB) exceeds maximum allowed size ({} MB), listing it instead",
+                                        path.display(),
+                                        file_size_mb,
+                                        max_size_mb
+                                    );
+                                    // Emit error event for toast notification
+                                    let _ = app_handle.emit(
+                                        "file-upload-error",
+                                        serde_json::json!({
+                                            "path": path.to_string_lossy(),
+                                            "error": "file_too_large",
+                                            "size_mb": file_size_mb,
+                                            "max_size_mb": max_size_mb
+                                        }),
+                                    );
+                                }
+                                listed.push(path);
                                 continue;
                             }
 
@@ -415,6 +680,26 @@ pub fn start_clipboard_monitor(app: AppHandle) {
                                 }
                             }
                         }
+
+                        if listed.is_empty() {
+                            return;
+                        }
+                        match client
+                            .create_clip(
+                                file_list_content(&listed),
+                                vec!["$filelist".to_string(), hostname_tag],
+                                None,
+                                None,
+                            )
+                            .await
+                        {
+                            Ok(clip) => {
+                                let _ = app_handle.emit("clip-created", &clip);
+                            }
+                            Err(e) => {
+                                eprintln!("[clipboard] Failed to create clip from file list: {}", e);
+                            }
+                        }
                     });
                 }
                 ClipboardContent::Empty => {}
@@ -423,35 +708,58 @@ pub fn start_clipboard_monitor(app: AppHandle) {
     });
 }
 
+/// Get the clipboard image as PNG bytes, if it holds one
+#[cfg(windows)]
+pub fn get_clipboard_image_png() -> Option<Vec<u8>> {
+    let image_data = arboard::Clipboard::new().ok()?.get_image().ok()?;
+    clipper_clipboard::image_data_to_png(&image_data)
+}
+
 pub fn set_clipboard_content(content: &str) -> Result<(), String> {
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(content).map_err(|e| e.to_string())
+    clipper_clipboard::set_clipboard_text(content).map_err(|e| e.to_string())
+}
+
+/// Put text copied from the history on the clipboard, or on Linux on the
+/// selections chosen with `restore_selection`
+pub fn set_restored_text(app: &AppHandle, content: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        let selection = app.state::<SettingsManager>().get().restore_selection;
+        if selection != RestoreSelection::Primary {
+            set_clipboard_content(content)?;
+        }
+        if selection != RestoreSelection::Clipboard {
+            set_primary_selection_text(content).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = app;
+        set_clipboard_content(content)
+    }
+}
+
+/// Put a clip (text or image) on the clipboard without creating a new clip
+pub async fn copy_clip_to_clipboard(app: &AppHandle, clip: &Clip) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    // Mark the content as synced so the clipboard monitor doesn't create a new clip
+    if clip.tags.iter().any(|tag| tag == "$image") {
+        let bytes = state
+            .client()
+            .download_file(&clip.id)
+            .await
+            .map_err(|e| format!("Failed to download image: {}", e))?;
+        state.set_last_synced_image(bytes.clone());
+        set_clipboard_image(&bytes)
+    } else {
+        state.set_last_synced_content(clip.content.clone());
+        set_restored_text(app, &clip.content)
+    }
 }
 
 /// Set image content to the system clipboard from PNG bytes
 pub fn set_clipboard_image(png_bytes: &[u8]) -> Result<(), String> {
-    use std::io::Cursor;
-
-    // Decode PNG bytes to get image dimensions and RGBA data
-    let img = image::ImageReader::new(Cursor::new(png_bytes))
-        .with_guessed_format()
-        .map_err(|e| format!("Failed to read image format: {}", e))?
-        .decode()
-        .map_err(|e| format!("Failed to decode image: {}", e))?;
-
-    let rgba = img.to_rgba8();
-    let (width, height) = rgba.dimensions();
-
-    // Create arboard ImageData
-    let image_data = arboard::ImageData {
-        width: width as usize,
-        height: height as usize,
-        bytes: rgba.into_raw().into(),
-    };
-
-    // Set to clipboard
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard
-        .set_image(image_data)
+    clipper_clipboard::set_clipboard_image(png_bytes)
         .map_err(|e| format!("Failed to set clipboard image: {}", e))
 }
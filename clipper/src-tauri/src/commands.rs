@@ -1,10 +1,25 @@
+use crate::actions;
 use crate::autolaunch;
+use crate::clipboard::{
+    self, AppendModeStatus, CapturePauseDuration, CapturePauseStatus, PasteStackStatus,
+};
+use crate::deep_link::{DeepLink, PendingDeepLink};
+use crate::p2p::P2pManager;
+use crate::palette;
+use crate::quick_note;
 use crate::server::ServerManager;
-use crate::settings::{Settings, SettingsManager};
+use crate::settings::{ClipAction, Settings, SettingsManager, ShortcutAction};
+use crate::shortcuts::{self, ShortcutBinding};
 use crate::state::AppState;
+use crate::window_placement;
 use chrono::{DateTime, Utc};
 use clipper_client::models::PagedResult;
-use clipper_client::{Clip, ImportResult, SearchFilters, ServerInfo, fetch_server_certificate};
+use clipper_client::{
+    CleanupResult, Clip, ClipperClient, DiscoveredServer, ImportResult, MergedCursor, MergedResult,
+    MultiClipperClient, PairedDevice, PeerInfo, SearchFilters, ServerInfo, Transform,
+    fetch_server_certificate,
+};
+use clipper_security::SecretString;
 use gethostname::gethostname;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -23,6 +38,9 @@ pub struct SearchFiltersInput {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub source_device: Option<String>,
+    #[serde(default)]
+    pub skip_total: bool,
 }
 
 impl SearchFiltersInput {
@@ -45,6 +63,12 @@ impl SearchFiltersInput {
             filters.tags = Some(tags);
         }
 
+        if let Some(device) = self.source_device.filter(|d| !d.is_empty()) {
+            filters.source_device = Some(device);
+        }
+
+        filters.skip_total = self.skip_total;
+
         filters
     }
 }
@@ -78,6 +102,65 @@ pub async fn search_clips(
         .map_err(|e| e.to_string())
 }
 
+/// List (empty query) or search the clips of the current server and every
+/// server profile as one history, each clip labelled with its server
+#[tauri::command]
+pub async fn search_all_servers(
+    state: State<'_, AppState>,
+    settings_manager: State<'_, SettingsManager>,
+    query: String,
+    filters: SearchFiltersInput,
+    cursor: Option<MergedCursor>,
+    limit: usize,
+) -> Result<MergedResult, String> {
+    let client = all_servers_client(&state, &settings_manager);
+    let filters = filters.into_search_filters();
+    let result = if query.trim().is_empty() {
+        client.list_clips(filters, cursor.as_ref(), limit).await
+    } else {
+        client
+            .search_clips(&query, filters, cursor.as_ref(), limit)
+            .await
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Client for the current server, labelled with its profile name ("bundled"
+/// for the bundled server), and the other server profiles
+fn all_servers_client(state: &AppState, settings_manager: &SettingsManager) -> MultiClipperClient {
+    let settings = settings_manager.get();
+    let current_profile = settings
+        .server_profiles
+        .iter()
+        .find(|profile| !settings.use_bundled_server && profile.url == settings.server_address);
+    let current_name = match current_profile {
+        Some(profile) => profile.name.clone(),
+        None if settings.use_bundled_server => "bundled".to_string(),
+        None => settings.server_address.clone(),
+    };
+
+    let trusted = settings_manager.get_trusted_certificates();
+    let mut client = MultiClipperClient::new().with_source(current_name.clone(), state.client());
+    for profile in &settings.server_profiles {
+        // The current server is already there
+        if profile.name == current_name {
+            continue;
+        }
+        client.add_source(
+            &profile.name,
+            ClipperClient::new_with_trusted_certs(
+                &profile.url,
+                profile
+                    .token
+                    .as_ref()
+                    .map(|token| token.expose().to_string()),
+                trusted.clone(),
+            ),
+        );
+    }
+    client
+}
+
 #[tauri::command]
 pub async fn create_clip(
     state: State<'_, AppState>,
@@ -102,14 +185,68 @@ pub async fn update_clip(
     tags: Option<Vec<String>>,
     additional_notes: Option<String>,
     language: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+) -> Result<Clip, String> {
+    let client = state.client();
+    client
+        .update_clip(&id, tags, additional_notes, language, color, icon)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply text transforms to a clip, creating a new derived clip
+#[tauri::command]
+pub async fn transform_clip(
+    state: State<'_, AppState>,
+    id: String,
+    transforms: Vec<Transform>,
+) -> Result<Clip, String> {
+    let client = state.client();
+    client
+        .transform_clip(&id, &transforms)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Translate a clip with the server's translation provider, creating a new
+/// derived clip
+#[tauri::command]
+pub async fn translate_clip(
+    state: State<'_, AppState>,
+    id: String,
+    to: String,
 ) -> Result<Clip, String> {
     let client = state.client();
     client
-        .update_clip(&id, tags, additional_notes, language)
+        .translate_clip(&id, &to)
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn list_actions(settings_manager: State<'_, SettingsManager>) -> Vec<ClipAction> {
+    settings_manager.get_actions()
+}
+
+#[tauri::command]
+pub async fn run_action(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings_manager: State<'_, SettingsManager>,
+    name: String,
+    clip_id: String,
+) -> Result<(), String> {
+    let action = settings_manager
+        .get_actions()
+        .into_iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("Action not found: {}", name))?;
+    let client = state.client();
+    let clip = client.get_clip(&clip_id).await.map_err(|e| e.to_string())?;
+    actions::run_action(&app, &action, &clip, &client).await
+}
+
 #[tauri::command]
 pub async fn delete_clip(state: State<'_, AppState>, id: String) -> Result<(), String> {
     let client = state.client();
@@ -124,12 +261,14 @@ pub async fn get_clip(state: State<'_, AppState>, id: String) -> Result<Clip, St
 
 /// Copy content to clipboard without creating a new clip on the server.
 /// This marks the content as "synced" so the clipboard monitor won't create a duplicate.
+/// On Linux it goes on the selections chosen with `restore_selection`.
 #[tauri::command]
-pub fn copy_to_clipboard(state: State<'_, AppState>, content: String) -> Result<(), String> {
-    use arboard::Clipboard;
-
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(&content).map_err(|e| e.to_string())?;
+pub fn copy_to_clipboard(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<(), String> {
+    clipboard::set_restored_text(&app, &content)?;
 
     // Mark this content as synced to prevent clipboard monitor from creating a duplicate
     state.set_last_synced_content(content);
@@ -217,7 +356,7 @@ pub fn get_file_url(state: State<'_, AppState>, clip_id: String) -> String {
     use urlencoding::encode;
     let base_url = format!("{}/clips/{}/file", state.base_url(), clip_id);
     match state.token() {
-        Some(token) => format!("{}?token={}", base_url, encode(&token)),
+        Some(token) => format!("{}?token={}", base_url, encode(token.expose())),
         None => base_url,
     }
 }
@@ -322,13 +461,20 @@ pub fn get_settings(settings_manager: State<'_, SettingsManager>) -> Settings {
 }
 
 /// Save settings
-/// Note: This only saves settings to disk. Server restart (when token/cleanup changes)
-/// is handled by the frontend when the settings dialog is closed, via switch_to_bundled_server.
+/// Note: This only saves settings to disk and applies auto-launch, image capture and
+/// P2P sync changes.
+/// Server restart (when token/cleanup changes) is handled by the frontend when the
+/// settings dialog is closed, via switch_to_bundled_server.
 #[tauri::command]
 pub async fn save_settings(
+    app: tauri::AppHandle,
     settings_manager: State<'_, SettingsManager>,
+    p2p_manager: State<'_, P2pManager>,
+    state: State<'_, AppState>,
     settings: Settings,
 ) -> Result<(), String> {
+    settings.check_server_profiles()?;
+
     // Handle auto-launch setting change
     let current = settings_manager.get();
     if current.start_on_login != settings.start_on_login {
@@ -336,7 +482,24 @@ pub async fn save_settings(
     }
 
     // Save settings to disk
-    settings_manager.update(settings).await
+    settings_manager.update(settings.clone()).await?;
+
+    // The tray lists the server profiles
+    if current.server_profiles != settings.server_profiles
+        && let Err(e) = crate::tray::refresh_tray(&app)
+    {
+        log::warn!("Failed to refresh tray menu: {}", e);
+    }
+
+    state.apply_capture_settings(&settings);
+
+    // The WebSocket subscribes with the tags this device syncs
+    if current.sync_tags != settings.sync_tags {
+        state.signal_ws_reconnect();
+    }
+
+    // Start or stop P2P sync (no-op unless its settings changed)
+    p2p_manager.apply_settings(&app, &settings).await
 }
 
 /// Browse for a directory (for default save location)
@@ -400,7 +563,7 @@ pub async fn clear_all_data(
     let token = settings_manager.get_bundled_server_token();
 
     // 5. Update the client with the new URL and token
-    state.set_server_url_with_token(&new_url, token);
+    state.switch_server(&new_url, token);
 
     // 6. Emit event to refresh the clip list in the main window
     let _ = app.emit("data-cleared", ());
@@ -412,9 +575,14 @@ pub async fn clear_all_data(
     Ok(())
 }
 
-/// Export all clips to a tar.gz archive file
+/// Export all clips to a tar.gz archive file, without the sensitive ones if
+/// `exclude_sensitive` is set
 #[tauri::command]
-pub async fn export_clips(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+pub async fn export_clips(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    exclude_sensitive: bool,
+) -> Result<String, String> {
     use tauri_plugin_dialog::DialogExt;
 
     // Generate default filename with timestamp
@@ -438,10 +606,12 @@ pub async fn export_clips(app: tauri::AppHandle, state: State<'_, AppState>) ->
     let client = state.client();
     let path_str = save_path.to_string();
 
-    client
-        .export_to_file(&path_str)
-        .await
-        .map_err(|e| e.to_string())?;
+    if exclude_sensitive {
+        client.export_non_sensitive_to_file(&path_str).await
+    } else {
+        client.export_to_file(&path_str).await
+    }
+    .map_err(|e| e.to_string())?;
 
     log::debug!("[clipper] Exported clips to {}", path_str);
     Ok(path_str)
@@ -498,8 +668,6 @@ pub async fn switch_to_bundled_server(
     settings_manager: State<'_, SettingsManager>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
-    use tauri::Emitter;
-
     log::debug!("[clipper] Switching to bundled server...");
 
     // Always restart the server to pick up any configuration changes (token, cleanup, etc.)
@@ -518,15 +686,14 @@ pub async fn switch_to_bundled_server(
     let token = settings_manager.get_bundled_server_token();
 
     // Update the client to use the bundled server URL with token
-    state.set_server_url_with_token(&server_url, token);
+    state.switch_server(&server_url, token);
 
     // Update settings to remember this choice
     let mut settings = settings_manager.get();
     settings.use_bundled_server = true;
     settings_manager.update(settings).await?;
 
-    // Emit event to refresh the clip list
-    let _ = app.emit("server-switched", ());
+    emit_server_changed(&app, &server_url, true);
 
     log::info!("Switched to bundled server at {}", server_url);
     Ok(server_url)
@@ -543,40 +710,42 @@ pub async fn switch_to_external_server(
     state: State<'_, AppState>,
     server_url: String,
 ) -> Result<Option<String>, String> {
-    use tauri::Emitter;
-
     log::debug!(
         "[clipper] Switching to external server at {}...",
         server_url
     );
 
+    // Get token from settings
+    let token = settings_manager.get_external_server_token();
+
+    // Update the client to use the external server URL with token, before
+    // stopping the bundled server so nothing is sent to a stopped server
+    state.switch_server(&server_url, token.clone());
+
     // Stop the bundled server if running
     if server_manager.is_running().await {
         server_manager.stop().await?;
     }
 
-    // Get token from settings
-    let token = settings_manager.get_external_server_token();
-
-    // Update the client to use the external server URL with token
-    state.set_server_url_with_token(&server_url, token.clone());
-
     // Update settings to remember this choice and the external URL
     let mut settings = settings_manager.get();
     settings.use_bundled_server = false;
     settings.server_address = server_url.clone();
     settings_manager.update(settings).await?;
 
-    // Emit event to refresh the clip list
-    let _ = app.emit("server-switched", ());
+    emit_server_changed(&app, &server_url, false);
 
     log::info!("Switched to external server at {}", server_url);
 
     // Check if the external server is reachable (but don't block the switch)
     // Use current trusted fingerprints for certificate verification
     let trusted_fingerprints = state.get_trusted_fingerprints();
-    let connection_error =
-        check_server_reachable(&server_url, token.as_deref(), trusted_fingerprints).await;
+    let connection_error = check_server_reachable(
+        &server_url,
+        token.as_ref().map(SecretString::expose),
+        trusted_fingerprints,
+    )
+    .await;
 
     if let Some(ref err) = connection_error {
         log::warn!(
@@ -588,6 +757,50 @@ pub async fn switch_to_external_server(
     Ok(connection_error)
 }
 
+/// Switch to a named server profile, making it the external server
+/// Returns Ok(None) if the server is reachable, or Ok(Some(error_message)) if unreachable
+#[tauri::command]
+pub async fn switch_to_server_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<Option<String>, String> {
+    use tauri::Manager;
+
+    let settings_manager = app.state::<SettingsManager>();
+    let profile = settings_manager
+        .get_server_profile(&name)
+        .ok_or_else(|| format!("Unknown server profile: {}", name))?;
+
+    // The profile's token becomes the external server token
+    let mut settings = settings_manager.get();
+    settings.external_server_token = profile.token;
+    settings_manager.update(settings).await?;
+
+    log::debug!("[clipper] Switching to server profile {}...", name);
+    switch_to_external_server(
+        app.clone(),
+        app.state(),
+        app.state(),
+        app.state(),
+        profile.url,
+    )
+    .await
+}
+
+/// Tell the frontend the app now talks to another server, so it can reload
+/// everything that came from the old one, and check the server in the tray
+fn emit_server_changed(app: &tauri::AppHandle, url: &str, bundled: bool) {
+    use tauri::Emitter;
+
+    let _ = app.emit(
+        "server-changed",
+        serde_json::json!({ "url": url, "bundled": bundled }),
+    );
+    if let Err(e) = crate::tray::refresh_tray(app) {
+        log::warn!("Failed to refresh tray menu: {}", e);
+    }
+}
+
 /// Check if a server is reachable by calling its health endpoint
 /// Returns None if reachable, Some(error_message) if not
 async fn check_server_reachable(
@@ -656,8 +869,6 @@ pub async fn toggle_listen_on_all_interfaces(
     state: State<'_, AppState>,
     listen_on_all: bool,
 ) -> Result<String, String> {
-    use tauri::Emitter;
-
     log::debug!(
         "[clipper] Toggling listen_on_all_interfaces to {}...",
         listen_on_all
@@ -676,10 +887,9 @@ pub async fn toggle_listen_on_all_interfaces(
         // Get token - always use if set (server requires it when configured)
         let token = settings_manager.get_bundled_server_token();
 
-        state.set_server_url_with_token(&new_url, token);
+        state.switch_server(&new_url, token);
 
-        // Emit event to refresh the clip list
-        let _ = app.emit("server-switched", ());
+        emit_server_changed(&app, &new_url, true);
 
         log::debug!(
             "[clipper] Server restarted with listen_on_all_interfaces={}",
@@ -703,29 +913,192 @@ pub fn get_websocket_status(state: State<'_, AppState>) -> bool {
     state.is_websocket_connected()
 }
 
-/// Update the global shortcut
+/// Get whether clipboard capture is paused, and until when
 #[tauri::command]
-pub fn update_global_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<(), String> {
-    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+pub fn get_capture_paused(state: State<'_, AppState>) -> CapturePauseStatus {
+    state.capture_pause.status()
+}
 
-    // Parse the new shortcut
-    let new_shortcut = crate::parse_shortcut(&shortcut)
-        .ok_or_else(|| format!("Invalid shortcut format: {}", shortcut))?;
+/// Pause clipboard capture for a while, or resume it with `null`
+#[tauri::command]
+pub fn set_capture_paused(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    duration: Option<CapturePauseDuration>,
+) -> CapturePauseStatus {
+    clipboard::set_capture_paused(&app, duration);
+    state.capture_pause.status()
+}
 
-    // Unregister all existing shortcuts
-    app.global_shortcut()
-        .unregister_all()
-        .map_err(|e| format!("Failed to unregister shortcuts: {}", e))?;
+/// Paste a clip into the app that was focused before the Clipper window
+#[tauri::command]
+pub async fn paste_clip(app: tauri::AppHandle, clip_id: String) -> Result<(), String> {
+    crate::paste::paste_clip(&app, &clip_id).await
+}
 
-    // Register the new shortcut
-    app.global_shortcut()
-        .register(new_shortcut)
-        .map_err(|e| format!("Failed to register shortcut '{}': {}", shortcut, e))?;
+/// Show a clip's attachment in the file manager, downloading it to the cache
+/// first, so it can be dragged into other apps
+#[tauri::command]
+pub async fn reveal_attachment(app: tauri::AppHandle, clip_id: String) -> Result<(), String> {
+    crate::attachments::reveal(&app, &clip_id).await
+}
+
+/// Drag a clip's attachment out of the window as a file, downloading it to
+/// the cache first; returns when the drag is over
+#[tauri::command]
+pub async fn drag_attachment(
+    app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    clip_id: String,
+) -> Result<(), String> {
+    let path = crate::attachments::cached_attachment(&app, &clip_id).await?;
+    crate::drag_out::drag_file(&window, path).await
+}
 
-    log::debug!("[clipper] Global shortcut updated to: {}", shortcut);
+/// Take a screenshot and save it as an image clip tagged `$screenshot`
+///
+/// Returns `None` if the capture was cancelled.
+#[tauri::command]
+pub async fn capture_screenshot(app: tauri::AppHandle) -> Result<Option<Clip>, String> {
+    crate::screenshot::capture(&app).await
+}
+
+/// Get the shortcut bound to each action
+#[tauri::command]
+pub fn get_shortcuts(settings_manager: State<'_, SettingsManager>) -> Vec<ShortcutBinding> {
+    shortcuts::bindings(&settings_manager.get())
+}
+
+/// Bind an action to a global shortcut (empty to unbind) and save it
+#[tauri::command]
+pub async fn update_shortcut(
+    app: tauri::AppHandle,
+    action: ShortcutAction,
+    shortcut: String,
+) -> Result<(), String> {
+    shortcuts::update(&app, action, shortcut.clone()).await?;
+    log::debug!(
+        "[clipper] Shortcut for {:?} updated to: {}",
+        action,
+        shortcut
+    );
     Ok(())
 }
 
+/// Get whether paste stack mode is on and how many items are queued
+#[tauri::command]
+pub fn get_paste_stack(state: State<'_, AppState>) -> PasteStackStatus {
+    state.paste_stack.status()
+}
+
+/// Turn paste stack mode on or off, discarding queued items
+#[tauri::command]
+pub fn set_paste_stack_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> PasteStackStatus {
+    state.paste_stack.set_enabled(enabled);
+    clipboard::notify_paste_stack_changed(&app);
+    state.paste_stack.status()
+}
+
+/// Put the next paste stack item on the clipboard
+#[tauri::command]
+pub fn pop_paste_stack(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    clipboard::pop_paste_stack(&app)
+}
+
+/// Discard the queued paste stack items, leaving stack mode on
+#[tauri::command]
+pub fn clear_paste_stack(app: tauri::AppHandle, state: State<'_, AppState>) {
+    state.paste_stack.clear();
+    clipboard::notify_paste_stack_changed(&app);
+}
+
+/// Get whether append mode is on
+#[tauri::command]
+pub fn get_append_mode(state: State<'_, AppState>) -> AppendModeStatus {
+    state.append_mode.status()
+}
+
+/// Turn append mode on or off; the next copy starts a new clip either way
+#[tauri::command]
+pub fn set_append_mode_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> AppendModeStatus {
+    state.append_mode.set_enabled(enabled);
+    clipboard::notify_append_mode_changed(&app);
+    state.append_mode.status()
+}
+
+/// Open the quick note popup
+///
+/// Async so the window isn't created on the main thread, which deadlocks on
+/// Windows.
+#[tauri::command]
+pub async fn show_quick_note(app: tauri::AppHandle) -> Result<(), String> {
+    quick_note::show(&app)
+}
+
+/// Close the quick note popup without saving
+#[tauri::command]
+pub fn hide_quick_note(app: tauri::AppHandle) {
+    quick_note::hide(&app);
+}
+
+/// Save the quick note as a clip and close the popup
+#[tauri::command]
+pub async fn submit_quick_note(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<Clip, String> {
+    if content.trim().is_empty() {
+        return Err("Note is empty".to_string());
+    }
+    let clip = state
+        .client()
+        .create_clip(content, vec![get_hostname_tag()], None, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    quick_note::hide(&app);
+    Ok(clip)
+}
+
+/// Open the clip palette
+///
+/// Async for the same reason as `show_quick_note`.
+#[tauri::command]
+pub async fn show_palette(app: tauri::AppHandle) -> Result<(), String> {
+    palette::show(&app)
+}
+
+/// Close the clip palette
+#[tauri::command]
+pub fn hide_palette(app: tauri::AppHandle) {
+    palette::hide(&app);
+}
+
+/// List the clips matching a palette query (the most recent if blank)
+#[tauri::command]
+pub async fn search_palette(app: tauri::AppHandle, query: String) -> Result<Vec<Clip>, String> {
+    palette::search(&app, &query).await
+}
+
+/// Put the clip picked in the palette on the clipboard and close the palette,
+/// pasting it into the previously focused app if `paste` is set
+#[tauri::command]
+pub async fn pick_palette_clip(
+    app: tauri::AppHandle,
+    clip_id: String,
+    paste: bool,
+) -> Result<(), String> {
+    palette::pick(&app, &clip_id, paste).await
+}
+
 /// Get server info (including max upload size) from the connected server
 #[tauri::command]
 pub async fn get_server_info(state: State<'_, AppState>) -> Result<ServerInfo, String> {
@@ -744,6 +1117,167 @@ pub fn get_max_upload_size_bytes(state: State<'_, AppState>) -> u64 {
     state.get_max_upload_size_bytes()
 }
 
+/// Find Clipper servers on the local network over mDNS
+#[tauri::command]
+pub async fn discover_servers() -> Result<Vec<DiscoveredServer>, String> {
+    clipper_client::discover_servers(std::time::Duration::from_secs(3))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============ Device Pairing Commands ============
+
+/// A pairing code along with a QR code for the new device to scan
+#[derive(Debug, Clone, Serialize)]
+pub struct PairingCodeInfo {
+    pub code: String,
+    pub expires_at: String,
+    /// Pairing link encoded in the QR code
+    pub pairing_url: String,
+    /// QR code as an SVG document
+    pub qr_svg: String,
+}
+
+/// Build the link shown in the pairing QR code
+fn pairing_url(server_url: &str, code: &str) -> String {
+    format!(
+        "clipper://pair?server={}&code={}",
+        urlencoding::encode(server_url.trim_end_matches('/')),
+        urlencoding::encode(code)
+    )
+}
+
+/// Split a pairing link into its server URL and code. Plain codes are
+/// returned as-is with no server.
+fn parse_pairing_input(input: &str) -> (Option<String>, String) {
+    let input = input.trim();
+    let url = match reqwest::Url::parse(input) {
+        Ok(url) if url.scheme() == "clipper" && url.host_str() == Some("pair") => url,
+        _ => return (None, input.to_string()),
+    };
+
+    let mut server = None;
+    let mut code = String::new();
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "server" => server = Some(value.into_owned()),
+            "code" => code = value.into_owned(),
+            _ => {}
+        }
+    }
+    (server, code)
+}
+
+/// Issue a pairing code on the connected server.
+/// `server_url` is the address the new device should use, e.g. a LAN URL
+/// of the bundled server; it is only used in the QR code.
+#[tauri::command]
+pub async fn create_pairing_code(
+    state: State<'_, AppState>,
+    server_url: String,
+) -> Result<PairingCodeInfo, String> {
+    use qrcode::QrCode;
+    use qrcode::render::svg;
+
+    let client = state.client();
+    let pairing = client
+        .create_pairing_code()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let pairing_url = pairing_url(&server_url, &pairing.code);
+    let qr_svg = QrCode::new(pairing_url.as_bytes())
+        .map_err(|e| format!("Failed to create QR code: {}", e))?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+
+    Ok(PairingCodeInfo {
+        code: pairing.code,
+        expires_at: pairing.expires_at,
+        pairing_url,
+        qr_svg,
+    })
+}
+
+/// Result of redeeming a pairing code
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimedPairing {
+    /// Server URL from the pairing link, if one was given
+    pub server_url: Option<String>,
+    pub token: String,
+}
+
+/// Redeem a pairing code (or pairing link) for this device's own token.
+/// The device is named after this machine's hostname.
+#[tauri::command]
+pub async fn claim_pairing_code(
+    state: State<'_, AppState>,
+    server_url: String,
+    code: String,
+) -> Result<ClaimedPairing, String> {
+    let (link_server_url, code) = parse_pairing_input(&code);
+    if code.is_empty() {
+        return Err("Pairing code is required".to_string());
+    }
+    let target_url = link_server_url.clone().unwrap_or(server_url);
+
+    let client = clipper_client::ClipperClient::new_with_trusted_certs(
+        target_url,
+        None,
+        state.get_trusted_fingerprints(),
+    );
+    let name = gethostname().to_string_lossy().to_string();
+    let paired = client
+        .claim_pairing_code(&code, &name)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ClaimedPairing {
+        server_url: link_server_url,
+        token: paired.token,
+    })
+}
+
+/// List devices paired with the connected server
+#[tauri::command]
+pub async fn list_paired_devices(state: State<'_, AppState>) -> Result<Vec<PairedDevice>, String> {
+    let client = state.client();
+    client
+        .list_paired_devices()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Revoke a paired device so its token stops working
+#[tauri::command]
+pub async fn revoke_paired_device(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let client = state.client();
+    client
+        .revoke_paired_device(&id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run cleanup on the connected server now, or with `dry_run` list the clips
+/// it would delete
+#[tauri::command]
+pub async fn run_cleanup(
+    state: State<'_, AppState>,
+    dry_run: bool,
+) -> Result<CleanupResult, String> {
+    let client = state.client();
+    client.run_cleanup(dry_run).await.map_err(|e| e.to_string())
+}
+
+// ============ P2P Sync Commands ============
+
+/// Get the peers currently connected over P2P sync
+#[tauri::command]
+pub async fn get_p2p_peers(p2p_manager: State<'_, P2pManager>) -> Result<Vec<PeerInfo>, String> {
+    Ok(p2p_manager.peers().await)
+}
+
 // ============ App Info Commands ============
 
 /// Get the current app version
@@ -1218,3 +1752,20 @@ pub async fn ensure_window_size(
 
     Ok(())
 }
+
+/// Center the main window on the monitor with the mouse cursor
+#[tauri::command]
+pub fn center_main_window(app: tauri::AppHandle) -> Result<(), String> {
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window not found")?;
+    window_placement::center_on_cursor_monitor(&window).map_err(|e| e.to_string())
+}
+
+/// Take the `clipper://` link waiting to be opened, if any
+#[tauri::command]
+pub fn take_pending_deep_link(pending: State<'_, PendingDeepLink>) -> Option<DeepLink> {
+    pending.take()
+}
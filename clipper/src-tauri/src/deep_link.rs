@@ -0,0 +1,174 @@
+//! `clipper://` links
+//!
+//! - `clipper://clip/<id>` opens the main window on a clip
+//! - `clipper://search?q=<query>` opens the main window with a search
+//!
+//! The scheme is declared in `Info.plist` on macOS, where links arrive as
+//! `RunEvent::Opened`. On Windows and Linux it's registered for the current
+//! user at startup, and links arrive as an argument of a new process, which
+//! the single-instance plugin forwards to the running app.
+//!
+//! A link is kept as pending until the main window's frontend takes it with
+//! `take_pending_deep_link`, so links that open the app aren't lost while the
+//! frontend loads. The `deep-link` event tells a loaded frontend to take it.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+/// URI scheme of the links
+pub const SCHEME: &str = "clipper";
+
+/// Where a link points
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DeepLink {
+    Clip { id: String },
+    Search { query: String },
+}
+
+impl DeepLink {
+    /// Parse a `clipper://` link, ignoring other URLs and unknown links
+    pub fn parse(url: &Url) -> Option<Self> {
+        if url.scheme() != SCHEME {
+            return None;
+        }
+        match url.host_str()? {
+            "clip" => {
+                let id = url.path().trim_matches('/');
+                (!id.is_empty() && !id.contains('/')).then(|| DeepLink::Clip { id: id.to_string() })
+            }
+            "search" => {
+                let query = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "q")
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap_or_default();
+                Some(DeepLink::Search { query })
+            }
+            _ => None,
+        }
+    }
+
+    /// The first link among command-line arguments
+    pub fn find_in_args<I, S>(args: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        args.into_iter()
+            .filter_map(|arg| Url::parse(arg.as_ref()).ok())
+            .find_map(|url| Self::parse(&url))
+    }
+}
+
+/// The link waiting for the frontend
+pub struct PendingDeepLink(Mutex<Option<DeepLink>>);
+
+impl PendingDeepLink {
+    pub fn new(link: Option<DeepLink>) -> Self {
+        Self(Mutex::new(link))
+    }
+
+    pub fn take(&self) -> Option<DeepLink> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// Show the main window and hand the link to its frontend
+pub fn open(app: &AppHandle, link: DeepLink) {
+    log::info!("Opening {} link", SCHEME);
+    *app.state::<PendingDeepLink>().0.lock().unwrap() = Some(link);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        #[cfg(target_os = "macos")]
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+    }
+    let _ = app.emit("deep-link", ());
+}
+
+/// Register this executable as the handler of `clipper://` links for the
+/// current user, so the registration follows the app when it moves
+#[cfg(target_os = "windows")]
+pub async fn register() -> Result<(), String> {
+    use winreg::RegKey;
+    use winreg::enums::*;
+
+    let exe_path =
+        std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu
+        .create_subkey(format!(r"Software\Classes\{}", SCHEME))
+        .map_err(|e| format!("Failed to create registry key: {}", e))?;
+    key.set_value("", &"URL:Clipper")
+        .and_then(|_| key.set_value("URL Protocol", &""))
+        .map_err(|e| format!("Failed to set registry value: {}", e))?;
+    let (command, _) = key
+        .create_subkey(r"shell\open\command")
+        .map_err(|e| format!("Failed to create registry key: {}", e))?;
+    command
+        .set_value("", &format!("\"{}\" \"%1\"", exe_path.to_string_lossy()))
+        .map_err(|e| format!("Failed to set registry value: {}", e))
+}
+
+/// Register this executable as the handler of `clipper://` links for the
+/// current user, with a hidden desktop entry and `xdg-mime`
+#[cfg(target_os = "linux")]
+pub async fn register() -> Result<(), String> {
+    const DESKTOP_FILE: &str = "clipper-url-handler.desktop";
+
+    let data_dir = std::env::var("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            std::path::PathBuf::from(home).join(".local").join("share")
+        });
+    let applications_dir = data_dir.join("applications");
+    tokio::fs::create_dir_all(&applications_dir)
+        .await
+        .map_err(|e| format!("Failed to create applications directory: {}", e))?;
+
+    let exe_path =
+        std::env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let desktop_content = format!(
+        r#"[Desktop Entry]
+Type=Application
+Name=Clipper
+Exec="{}" %u
+NoDisplay=true
+MimeType=x-scheme-handler/{};
+"#,
+        exe_path.to_string_lossy(),
+        SCHEME
+    );
+    tokio::fs::write(applications_dir.join(DESKTOP_FILE), desktop_content)
+        .await
+        .map_err(|e| format!("Failed to write desktop entry: {}", e))?;
+
+    let output = tokio::process::Command::new("xdg-mime")
+        .args([
+            "default",
+            DESKTOP_FILE,
+            &format!("x-scheme-handler/{}", SCHEME),
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "xdg-mime failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// On macOS the scheme is declared in `Info.plist`
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub async fn register() -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! Dragging attachments out of the main window
+//!
+//! Text clips are dragged by the webview itself. Attachments have to be
+//! dragged as real files, which the webview can't do, so the frontend cancels
+//! its drag and asks for a native one instead, with the attachment downloaded
+//! to the cache by `attachments.rs`. The drag has to start while the mouse
+//! button is still down.
+//!
+//! - macOS: `-[NSView dragFile:fromRect:slideBack:event:]` with the current
+//!   mouse event
+//! - Windows: `SHDoDragDrop` with a shell data object for the file and the
+//!   default drop source
+//! - Linux: a GTK drag from the window offering the file's `text/uri-list`
+
+use std::path::{Path, PathBuf};
+
+use tauri::WebviewWindow;
+use tokio::sync::oneshot;
+
+/// Tells the waiting command how the drag went
+type Done = oneshot::Sender<Result<(), String>>;
+
+/// Drag a file out of the window, returning when the drag is over
+pub async fn drag_file(window: &WebviewWindow, path: PathBuf) -> Result<(), String> {
+    let (done, result) = oneshot::channel();
+    let drag_window = window.clone();
+    // Drags run on the UI thread
+    window
+        .run_on_main_thread(move || platform::drag_file(&drag_window, &path, done))
+        .map_err(|e| format!("Failed to start dragging: {}", e))?;
+    result
+        .await
+        .map_err(|_| "Dragging was interrupted".to_string())?
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::{NSPoint, NSRect, NSSize, NSString};
+
+    use super::*;
+
+    /// Size of the dragged file icon
+    const ICON_SIZE: f64 = 32.0;
+
+    pub fn drag_file(window: &WebviewWindow, path: &Path, done: Done) {
+        // The drag runs its own event loop, so it's over when this returns
+        let _ = done.send(unsafe { drag(window, path) });
+    }
+
+    unsafe fn drag(window: &WebviewWindow, path: &Path) -> Result<(), String> {
+        let view = window
+            .ns_view()
+            .map_err(|e| format!("Failed to get window view: {}", e))?
+            as *mut AnyObject;
+        let app: *mut AnyObject = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+        let event: *mut AnyObject = unsafe { msg_send![app, currentEvent] };
+        if event.is_null() {
+            return Err("No mouse event to drag from".to_string());
+        }
+
+        // Put the file icon under the cursor
+        let location: NSPoint = unsafe { msg_send![event, locationInWindow] };
+        let point: NSPoint = unsafe {
+            msg_send![view, convertPoint: location, fromView: std::ptr::null::<AnyObject>()]
+        };
+        let rect = NSRect::new(
+            NSPoint::new(point.x - ICON_SIZE / 2.0, point.y - ICON_SIZE / 2.0),
+            NSSize::new(ICON_SIZE, ICON_SIZE),
+        );
+        let filename = NSString::from_str(&path.to_string_lossy());
+        let dragged: bool = unsafe {
+            msg_send![view, dragFile: &*filename, fromRect: rect, slideBack: true, event: event]
+        };
+        if dragged {
+            Ok(())
+        } else {
+            Err("The file could not be dragged".to_string())
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows_sys::Win32::System::Ole::{DROPEFFECT, DROPEFFECT_COPY};
+    use windows_sys::Win32::UI::Shell::{
+        ILCreateFromPathW, ILFree, SHCreateDataObject, SHDoDragDrop,
+    };
+    use windows_sys::core::GUID;
+
+    use super::*;
+
+    const IID_IDATAOBJECT: GUID = GUID::from_u128(0x0000010e_0000_0000_c000_000000000046);
+
+    /// Start of every COM interface's vtable, for releasing the data object
+    #[repr(C)]
+    struct IUnknownVtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(*mut c_void) -> u32,
+    }
+
+    pub fn drag_file(window: &WebviewWindow, path: &Path, done: Done) {
+        // The drag runs its own message loop, so it's over when this returns
+        let _ = done.send(unsafe { drag(window, path) });
+    }
+
+    unsafe fn drag(window: &WebviewWindow, path: &Path) -> Result<(), String> {
+        let hwnd = window
+            .hwnd()
+            .map_err(|e| format!("Failed to get window handle: {}", e))?
+            .0;
+        let wide: Vec<u16> = path
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let pidl = unsafe { ILCreateFromPathW(wide.as_ptr()) };
+        if pidl.is_null() {
+            return Err("Failed to get the file's shell item".to_string());
+        }
+        let mut data: *mut c_void = std::ptr::null_mut();
+        let items = [pidl.cast_const()];
+        let hr = unsafe {
+            SHCreateDataObject(
+                std::ptr::null(),
+                1,
+                items.as_ptr(),
+                std::ptr::null_mut(),
+                &IID_IDATAOBJECT,
+                &mut data,
+            )
+        };
+        if hr < 0 {
+            unsafe { ILFree(pidl) };
+            return Err(format!("Failed to create drag data: {:#010x}", hr));
+        }
+
+        let mut effect: DROPEFFECT = 0;
+        let hr = unsafe {
+            SHDoDragDrop(
+                hwnd as _,
+                data,
+                std::ptr::null_mut(),
+                DROPEFFECT_COPY,
+                &mut effect,
+            )
+        };
+        unsafe {
+            let vtbl = *(data as *const *const IUnknownVtbl);
+            ((*vtbl).release)(data);
+            ILFree(pidl);
+        }
+        if hr < 0 {
+            Err(format!("Dragging failed: {:#010x}", hr))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use gtk::gdk::DragAction;
+    use gtk::glib::SignalHandlerId;
+    use gtk::prelude::*;
+    use gtk::{TargetEntry, TargetFlags, TargetList};
+
+    use super::*;
+
+    pub fn drag_file(window: &WebviewWindow, path: &Path, done: Done) {
+        let done = Rc::new(RefCell::new(Some(done)));
+        if let Err(e) = drag(window, path, done.clone())
+            && let Some(done) = done.borrow_mut().take()
+        {
+            let _ = done.send(Err(e));
+        }
+    }
+
+    /// Start the drag; `done` is sent when it ends
+    fn drag(
+        window: &WebviewWindow,
+        path: &Path,
+        done: Rc<RefCell<Option<Done>>>,
+    ) -> Result<(), String> {
+        let gtk_window = window
+            .gtk_window()
+            .map_err(|e| format!("Failed to get GTK window: {}", e))?;
+        let uri = gtk::glib::filename_to_uri(path, None)
+            .map_err(|e| format!("Failed to get file URI: {}", e))?;
+
+        // The handlers only serve this drag, so they're removed when it ends
+        let handlers: Rc<RefCell<Vec<SignalHandlerId>>> = Rc::default();
+        let data_handler = gtk_window.connect_drag_data_get(move |_, _, data, _, _| {
+            data.set_uris(&[uri.as_str()]);
+        });
+        let end_handlers = handlers.clone();
+        let end_handler = gtk_window.connect_drag_end(move |gtk_window, _| {
+            for handler in end_handlers.borrow_mut().drain(..) {
+                gtk_window.disconnect(handler);
+            }
+            if let Some(done) = done.borrow_mut().take() {
+                let _ = done.send(Ok(()));
+            }
+        });
+        handlers.borrow_mut().extend([data_handler, end_handler]);
+
+        let targets =
+            TargetList::new(&[TargetEntry::new("text/uri-list", TargetFlags::OTHER_APP, 0)]);
+        if gtk_window
+            .drag_begin_with_coordinates(&targets, DragAction::COPY, 1, None, -1, -1)
+            .is_none()
+        {
+            for handler in handlers.borrow_mut().drain(..) {
+                gtk_window.disconnect(handler);
+            }
+            return Err("Failed to start dragging".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    pub fn drag_file(_window: &WebviewWindow, _path: &Path, done: Done) {
+        let _ = done.send(Err(
+            "Dragging files is not supported on this platform".to_string()
+        ));
+    }
+}
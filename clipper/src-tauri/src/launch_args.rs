@@ -0,0 +1,56 @@
+//! Command-line flags of the desktop app
+//!
+//! - `--hidden`: start in the tray without showing the main window (used by
+//!   the autostart entries from `autolaunch.rs`)
+//! - `--minimized`: start with the main window minimized
+//! - `--no-server`: don't start the bundled server this time; the clips are
+//!   read from the server address in the settings instead
+//! - `--show`: show and focus the main window
+//!
+//! A second launch hands its flags to the running instance, which shows the
+//! main window unless they include `--hidden`. Unknown arguments, like the
+//! `-psn_` argument older macOS versions pass, are ignored.
+
+/// Flag that starts the app in the tray
+pub const HIDDEN: &str = "--hidden";
+
+/// Flags given on the command line
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchArgs {
+    pub hidden: bool,
+    pub minimized: bool,
+    pub no_server: bool,
+    pub show: bool,
+}
+
+impl LaunchArgs {
+    /// Parse the arguments after the program name
+    pub fn parse<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut launch = Self::default();
+        for arg in args {
+            match arg.as_ref() {
+                HIDDEN => launch.hidden = true,
+                "--minimized" => launch.minimized = true,
+                "--no-server" => launch.no_server = true,
+                "--show" => launch.show = true,
+                _ => {}
+            }
+        }
+        launch
+    }
+
+    /// The flags this process was started with
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Whether a second launch with these flags should bring up the main
+    /// window of the running instance
+    pub fn shows_window(&self) -> bool {
+        self.show || !self.hidden
+    }
+}
@@ -1,24 +1,41 @@
+mod actions;
+mod attachments;
 mod autolaunch;
 mod clipboard;
 mod commands;
+mod deep_link;
+mod drag_out;
+mod launch_args;
 mod migration;
+mod p2p;
+mod palette;
+mod paste;
+mod quick_note;
+mod screenshot;
 mod server;
 mod settings;
+mod shortcuts;
 mod state;
 mod tray;
 mod tray_i18n;
 mod websocket;
+mod window_placement;
 
+use clipper_client::{CacheConfig, ResponseCache};
+use clipper_security::SecretString;
+use deep_link::{DeepLink, PendingDeepLink};
 use gethostname::gethostname;
+use launch_args::LaunchArgs;
 use log::{error, info, warn};
 use rand::Rng;
 use server::{ServerManager, get_server_data_dir};
 use settings::{MainWindowGeometry, SETTINGS_FILE_NAME, SettingsManager, get_app_config_dir};
 use state::AppState;
+use std::sync::Arc;
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 use tauri::{DragDropEvent, Emitter, Manager, RunEvent};
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
 
 /// Get the hostname tag in the format `$host:<hostname>`
 fn get_hostname_tag() -> String {
@@ -241,6 +258,17 @@ pub fn run() {
     // This is required for TLS certificate operations
     let _ = rustls::crypto::ring::default_provider().install_default();
 
+    // Trace requests to the server if an OTLP endpoint is set. The guard
+    // lives until run() returns; spans are also flushed in batches meanwhile.
+    #[cfg(feature = "otel")]
+    let _telemetry = clipper_client::init_otlp_tracing("clipper-desktop").unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        None
+    });
+
+    // Flags like --hidden from autostart entries
+    let launch = LaunchArgs::from_env();
+
     // Read debug_logging setting early, before the app is fully initialized
     let debug_logging_enabled = read_debug_logging_setting();
 
@@ -288,13 +316,21 @@ pub fn run() {
         )
         // Single instance plugin must be registered FIRST (after log)
         .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
-            // When a second instance is launched, show the existing window
-            if let Some(window) = app.get_webview_window("main") {
+            // When a second instance is launched, show the existing window,
+            // unless it was only started in the tray (e.g. by autostart)
+            let second_launch = LaunchArgs::parse(argv.iter().skip(1));
+            if second_launch.shows_window()
+                && let Some(window) = app.get_webview_window("main")
+            {
+                let _ = window.unminimize();
                 let _ = window.show();
                 let _ = window.set_focus();
                 #[cfg(target_os = "macos")]
                 let _ = app.set_activation_policy(ActivationPolicy::Regular);
             }
+            if let Some(link) = DeepLink::find_in_args(argv.iter().skip(1)) {
+                deep_link::open(app, link);
+            }
             // Emit event to frontend with the args from the second instance
             let _ = app.emit("single-instance", SingleInstancePayload { args: argv, cwd });
         }))
@@ -337,6 +373,16 @@ pub fn run() {
             // Register settings manager BEFORE starting server (server needs it for port persistence)
             app.manage(settings_manager.clone());
 
+            // Keep a clipper:// link the app was started with for the frontend
+            let startup_link = DeepLink::find_in_args(std::env::args().skip(1));
+            let opened_by_link = startup_link.is_some();
+            app.manage(PendingDeepLink::new(startup_link));
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = deep_link::register().await {
+                    warn!("Failed to register {}:// links: {}", deep_link::SCHEME, e);
+                }
+            });
+
             // Get the server data directory for the bundled server
             let server_data_dir = get_server_data_dir(app.handle())?;
             let server_manager = ServerManager::new(server_data_dir);
@@ -352,7 +398,7 @@ pub fn run() {
                 let bundled_token = match settings_manager.get_bundled_server_token() {
                     Some(token) => token,
                     None => {
-                        let token = generate_secure_token();
+                        let token = SecretString::new(generate_secure_token());
                         info!("Generated new token for bundled server");
                         tauri::async_runtime::block_on(async {
                             if let Err(e) = settings_manager.set_bundled_server_token(token.clone()).await {
@@ -363,22 +409,27 @@ pub fn run() {
                     }
                 };
 
-                let url = tauri::async_runtime::block_on(async {
-                    match server_manager.start(&app_handle_for_server).await {
-                        Ok(url) => {
-                            info!("Bundled server started at: {}", url);
-                            url
-                        }
-                        Err(e) => {
-                            error!(
-                                "Failed to start bundled server: {}. Falling back to settings.",
-                                e
-                            );
-                            // Fall back to settings if bundled server fails
-                            settings_manager.get().server_address.clone()
+                let url = if launch.no_server {
+                    info!("Not starting the bundled server (--no-server)");
+                    settings_manager.get().server_address.clone()
+                } else {
+                    tauri::async_runtime::block_on(async {
+                        match server_manager.start(&app_handle_for_server).await {
+                            Ok(url) => {
+                                info!("Bundled server started at: {}", url);
+                                url
+                            }
+                            Err(e) => {
+                                error!(
+                                    "Failed to start bundled server: {}. Falling back to settings.",
+                                    e
+                                );
+                                // Fall back to settings if bundled server fails
+                                settings_manager.get().server_address.clone()
+                            }
                         }
-                    }
-                });
+                    })
+                };
                 (url, Some(bundled_token))
             } else {
                 let external_url = settings_manager.get().server_address.clone();
@@ -392,12 +443,28 @@ pub fn run() {
 
             // Create app state with the server URL, token, and trusted certificates
             let trusted_certs = settings_manager.get_trusted_certificates();
-            let app_state = AppState::new_with_trusted_certs(&server_url, token, trusted_certs);
+            // Cache clip list/get responses so unchanged pages are revalidated via ETag
+            // instead of being downloaded again
+            let mut cache_config = CacheConfig::new();
+            if let Ok(cache_dir) = app.path().app_cache_dir() {
+                cache_config = cache_config.with_disk_dir(cache_dir.join("responses"));
+            }
+            let response_cache = Arc::new(ResponseCache::new(cache_config));
+            let app_state = AppState::new_with_trusted_certs(
+                &server_url,
+                token,
+                trusted_certs,
+                response_cache,
+            );
+            app_state.apply_capture_settings(&settings_manager.get());
             app.manage(app_state);
+            attachments::spawn_eviction(app.handle(), None);
 
-            // Handle window visibility based on settings
+            // Handle window visibility based on settings and launch flags; a
+            // clipper:// link always opens the window
             let settings = settings_manager.get();
-            if !settings.open_on_startup
+            if !opened_by_link
+                && (launch.hidden || !settings.open_on_startup)
                 && let Some(window) = app_handle.get_webview_window("main")
             {
                 let _ = window.hide();
@@ -408,22 +475,14 @@ pub fn run() {
             // Restore main window geometry from settings
             if let Some(window) = app_handle.get_webview_window("main") {
                 let geometry = settings_manager.get_main_window_geometry();
+                window_placement::restore_geometry(&window, &geometry);
 
-                // Restore size if saved
-                if let (Some(width), Some(height)) = (geometry.width, geometry.height) {
-                    let size = tauri::LogicalSize::new(width as f64, height as f64);
-                    let _ = window.set_size(size);
-                }
-
-                // Restore position if saved
-                if let (Some(x), Some(y)) = (geometry.x, geometry.y) {
-                    let position = tauri::LogicalPosition::new(x as f64, y as f64);
-                    let _ = window.set_position(position);
-                }
-
-                // Restore maximized state if saved
-                if let Some(true) = geometry.maximized {
-                    let _ = window.maximize();
+                if launch.minimized
+                    && !launch.hidden
+                    && !opened_by_link
+                    && settings.open_on_startup
+                {
+                    let _ = window.minimize();
                 }
             }
 
@@ -455,12 +514,28 @@ pub fn run() {
                 });
             }
 
+            // Registered before the WebSocket listener starts, which broadcasts to peers
+            app.manage(p2p::P2pManager::default());
+
             // Start WebSocket listener
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 websocket::start_websocket_listener(app_handle).await;
             });
 
+            // Start P2P sync if enabled
+            let app_handle_p2p = app.handle().clone();
+            let settings_p2p = settings_manager.get();
+            tauri::async_runtime::spawn(async move {
+                let p2p_manager = app_handle_p2p.state::<p2p::P2pManager>();
+                if let Err(e) = p2p_manager
+                    .apply_settings(&app_handle_p2p, &settings_p2p)
+                    .await
+                {
+                    error!("{}", e);
+                }
+            });
+
             // Handle window close - hide instead of quit
             let window = app.get_webview_window("main").unwrap();
             window.on_window_event(move |event| {
@@ -471,50 +546,19 @@ pub fn run() {
                 }
             });
 
-            // Register global shortcut to toggle window visibility
-            // Use shortcut from settings, or default to Cmd+Shift+V on macOS, Ctrl+Shift+V on others
-            let shortcut_str = settings_manager.get().global_shortcut;
-            let shortcut = parse_shortcut(&shortcut_str).unwrap_or_else(|| {
-                #[cfg(target_os = "macos")]
-                {
-                    Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyV)
-                }
-                #[cfg(not(target_os = "macos"))]
-                {
-                    Shortcut::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyV)
-                }
-            });
-
+            // Register the global shortcuts from settings; each one runs the
+            // action it's bound to
             let app_handle = app.handle().clone();
             app.handle().plugin(
                 tauri_plugin_global_shortcut::Builder::new()
-                    .with_handler(move |_app, _shortcut, event| {
-                        if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed
-                            && let Some(window) = app_handle.get_webview_window("main")
-                        {
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                                #[cfg(target_os = "macos")]
-                                let _ =
-                                    app_handle.set_activation_policy(ActivationPolicy::Accessory);
-                            } else {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                                #[cfg(target_os = "macos")]
-                                let _ = app_handle.set_activation_policy(ActivationPolicy::Regular);
-                            }
+                    .with_handler(move |_app, shortcut, event| {
+                        if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                            shortcuts::handle(&app_handle, shortcut);
                         }
                     })
                     .build(),
             )?;
-
-            // Register the shortcut
-            if let Err(e) = app.global_shortcut().register(shortcut) {
-                error!(
-                    "Failed to register global shortcut '{}': {}",
-                    shortcut_str, e
-                );
-            }
+            shortcuts::register_all(app.handle());
 
             Ok(())
         })
@@ -523,12 +567,23 @@ pub fn run() {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
                     api.prevent_close();
                     let _ = window.hide();
+                    if window.label() == quick_note::LABEL || window.label() == palette::LABEL {
+                        return;
+                    }
                     // Hide dock icon on macOS when window is closed
                     #[cfg(target_os = "macos")]
                     let _ = window
                         .app_handle()
                         .set_activation_policy(ActivationPolicy::Accessory);
                 }
+                // The quick note popup goes away when clicking elsewhere
+                tauri::WindowEvent::Focused(false) if window.label() == quick_note::LABEL => {
+                    let _ = window.hide();
+                }
+                // So does the palette
+                tauri::WindowEvent::Focused(false) if window.label() == palette::LABEL => {
+                    let _ = window.hide();
+                }
                 tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
                     // Save window geometry when moved or resized
                     // Only save for the main window
@@ -575,7 +630,12 @@ pub fn run() {
                     let app = window.app_handle().clone();
                     let state = app.state::<AppState>();
                     let client = state.client();
-                    let paths = paths.clone();
+                    // Attachments dragged out of the window are clips already
+                    let paths: Vec<_> = paths
+                        .iter()
+                        .filter(|path| !attachments::is_cached(&app, path))
+                        .cloned()
+                        .collect();
 
                     // Process all files sequentially in a single async task to avoid race conditions
                     tauri::async_runtime::spawn(async move {
@@ -673,12 +733,20 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::list_clips,
             commands::search_clips,
+            commands::search_all_servers,
             commands::create_clip,
             commands::update_clip,
+            commands::transform_clip,
+            commands::translate_clip,
+            commands::list_actions,
+            commands::run_action,
             commands::delete_clip,
             commands::get_clip,
             commands::copy_to_clipboard,
             commands::copy_image_to_clipboard,
+            commands::paste_clip,
+            commands::get_capture_paused,
+            commands::set_capture_paused,
             commands::upload_file,
             commands::get_file_url,
             commands::get_file_data_url,
@@ -694,13 +762,38 @@ pub fn run() {
             commands::import_clips,
             commands::switch_to_bundled_server,
             commands::switch_to_external_server,
+            commands::switch_to_server_profile,
             commands::get_local_ip_addresses,
             commands::toggle_listen_on_all_interfaces,
             commands::update_tray_language,
-            commands::update_global_shortcut,
+            commands::reveal_attachment,
+            commands::drag_attachment,
+            commands::capture_screenshot,
+            commands::get_shortcuts,
+            commands::update_shortcut,
+            commands::get_paste_stack,
+            commands::set_paste_stack_enabled,
+            commands::pop_paste_stack,
+            commands::clear_paste_stack,
+            commands::get_append_mode,
+            commands::set_append_mode_enabled,
+            commands::show_quick_note,
+            commands::hide_quick_note,
+            commands::submit_quick_note,
+            commands::show_palette,
+            commands::hide_palette,
+            commands::search_palette,
+            commands::pick_palette_clip,
             commands::get_websocket_status,
             commands::get_server_info,
             commands::get_max_upload_size_bytes,
+            commands::discover_servers,
+            commands::create_pairing_code,
+            commands::claim_pairing_code,
+            commands::list_paired_devices,
+            commands::revoke_paired_device,
+            commands::run_cleanup,
+            commands::get_p2p_peers,
             commands::get_app_version,
             commands::check_for_updates,
             commands::install_update,
@@ -709,6 +802,8 @@ pub fn run() {
             commands::untrust_certificate,
             commands::get_trusted_certificates,
             commands::ensure_window_size,
+            commands::take_pending_deep_link,
+            commands::center_main_window,
             commands::quit_app,
             commands::restart_app,
         ])
@@ -716,8 +811,8 @@ pub fn run() {
         .expect("error while building tauri application");
 
     // Run the app with exit handler to stop the bundled server
-    app.run(|app_handle, event| {
-        if let RunEvent::Exit = event {
+    app.run(|app_handle, event| match event {
+        RunEvent::Exit => {
             // Stop the bundled server when the app exits
             let server_manager = app_handle.state::<ServerManager>();
             tauri::async_runtime::block_on(async {
@@ -726,5 +821,13 @@ pub fn run() {
                 }
             });
         }
+        // clipper:// links on macOS
+        #[cfg(target_os = "macos")]
+        RunEvent::Opened { urls } => {
+            if let Some(link) = urls.iter().find_map(DeepLink::parse) {
+                deep_link::open(app_handle, link);
+            }
+        }
+        _ => {}
     });
 }
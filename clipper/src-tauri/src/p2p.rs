@@ -0,0 +1,120 @@
+//! Serverless peer-to-peer sync
+//!
+//! When enabled in settings, runs a [`P2pNode`] that exchanges text clips
+//! with other Clipper instances on the LAN configured with the same sync key.
+//! Clips copied on this machine are broadcast once they reach the server (see
+//! websocket.rs). Clips received from a peer are saved with their original
+//! tags, so the WebSocket listener puts them on the clipboard as usual and,
+//! since they carry the peer's `$host:` tag, doesn't send them back out.
+
+use crate::settings::Settings;
+use crate::state::AppState;
+use clipper_client::{P2pConfig, P2pNode, PeerEvent, PeerInfo, SyncedClip};
+use gethostname::gethostname;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, mpsc};
+
+/// Emitted to the frontend whenever a peer connects or disconnects
+pub const PEERS_CHANGED_EVENT: &str = "p2p-peers-changed";
+
+struct RunningNode {
+    node: P2pNode,
+    sync_key: String,
+}
+
+/// Owns the P2P node while P2P sync is enabled
+#[derive(Default)]
+pub struct P2pManager {
+    running: Mutex<Option<RunningNode>>,
+}
+
+impl P2pManager {
+    /// Start, restart or stop the node to match the settings
+    pub async fn apply_settings(&self, app: &AppHandle, settings: &Settings) -> Result<(), String> {
+        let sync_key = settings
+            .p2p_sync_key
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string();
+        let mut running = self.running.lock().await;
+
+        if !settings.p2p_sync_enabled || sync_key.is_empty() {
+            if running.take().is_some() {
+                log::info!("P2P sync stopped");
+                let _ = app.emit(PEERS_CHANGED_EVENT, ());
+            }
+            return Ok(());
+        }
+        if running.as_ref().is_some_and(|r| r.sync_key == sync_key) {
+            return Ok(());
+        }
+
+        // Stop the old node first so peers see it leave before the new one joins
+        if running.take().is_some() {
+            let _ = app.emit(PEERS_CHANGED_EVENT, ());
+        }
+
+        let config = P2pConfig {
+            name: gethostname().to_string_lossy().to_string(),
+            sync_key: sync_key.clone(),
+            port: 0,
+        };
+        let (node, events) = P2pNode::start(config)
+            .await
+            .map_err(|e| format!("Failed to start P2P sync: {}", e))?;
+        log::info!("P2P sync started on port {}", node.port());
+
+        tauri::async_runtime::spawn(handle_events(app.clone(), events));
+        *running = Some(RunningNode { node, sync_key });
+        Ok(())
+    }
+
+    /// Send a clip to every connected peer
+    pub async fn broadcast(&self, clip: SyncedClip) {
+        if let Some(running) = self.running.lock().await.as_ref() {
+            running.node.broadcast(clip);
+        }
+    }
+
+    /// Currently connected peers
+    pub async fn peers(&self) -> Vec<PeerInfo> {
+        self.running
+            .lock()
+            .await
+            .as_ref()
+            .map(|r| r.node.peers())
+            .unwrap_or_default()
+    }
+}
+
+/// Save clips received from peers and tell the frontend about peer changes.
+/// Ends when the node is stopped.
+async fn handle_events(app: AppHandle, mut events: mpsc::UnboundedReceiver<PeerEvent>) {
+    while let Some(event) = events.recv().await {
+        match event {
+            PeerEvent::Connected(peer) => {
+                log::info!("P2P peer connected: {} ({})", peer.name, peer.address);
+                let _ = app.emit(PEERS_CHANGED_EVENT, ());
+            }
+            PeerEvent::Disconnected(peer) => {
+                log::info!("P2P peer disconnected: {}", peer.name);
+                let _ = app.emit(PEERS_CHANGED_EVENT, ());
+            }
+            PeerEvent::Clip { from, clip } => {
+                let client = app.state::<AppState>().client();
+                if let Err(e) = client
+                    .create_clip(
+                        clip.content,
+                        clip.tags,
+                        clip.additional_notes,
+                        clip.language,
+                    )
+                    .await
+                {
+                    log::warn!("Failed to save clip from P2P peer {}: {}", from.name, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Clip palette
+//!
+//! A slim spotlight-style window with a search field and a result list, for
+//! getting a clip back without opening the main window. It loads the same
+//! frontend with `?window=palette`, opens centered on the monitor with the
+//! mouse cursor and hides itself when it loses focus.
+
+use clipper_client::{Clip, SearchFilters};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::clipboard;
+use crate::paste;
+use crate::state::AppState;
+use crate::window_placement;
+
+/// Label of the palette window
+pub const LABEL: &str = "palette";
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 420.0;
+
+/// How many clips the palette lists
+const MAX_RESULTS: usize = 20;
+
+/// Show the palette window (creating it on first use) and focus it
+pub fn show(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => create(app).map_err(|e| format!("Failed to create palette window: {}", e))?,
+    };
+    if let Err(e) = window_placement::center_on_cursor_monitor(&window) {
+        log::warn!("Failed to move palette to the cursor's monitor: {}", e);
+    }
+    let _ = window.show();
+    let _ = window.unminimize();
+    // As with the quick note, focusing only works on Windows because the
+    // shortcut or tray click gives the process the right to take the foreground
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Hide the palette window, if open
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Show the palette window if hidden, hide it otherwise
+///
+/// Like `quick_note::toggle`, the window is shown from a separate task so
+/// creating it in a shortcut or tray handler doesn't deadlock on Windows.
+pub fn toggle(app: &AppHandle) {
+    let visible = app
+        .get_webview_window(LABEL)
+        .is_some_and(|w| w.is_visible().unwrap_or(false));
+    if visible {
+        hide(app);
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = show(&app) {
+            log::error!("{}", e);
+        }
+    });
+}
+
+/// The clips matching `query`, or the most recent clips if it's blank
+pub async fn search(app: &AppHandle, query: &str) -> Result<Vec<Clip>, String> {
+    let client = app.state::<AppState>().client();
+    let filters = SearchFilters::new().with_skip_total();
+    let result = if query.trim().is_empty() {
+        client.list_clips(filters, 1, MAX_RESULTS).await
+    } else {
+        client
+            .search_clips(query.trim(), filters, 1, MAX_RESULTS)
+            .await
+    };
+    result.map(|page| page.items).map_err(|e| e.to_string())
+}
+
+/// Put a clip on the clipboard and hide the palette; with `paste`, also paste
+/// it into the app that was focused before the palette opened
+pub async fn pick(app: &AppHandle, clip_id: &str, paste: bool) -> Result<(), String> {
+    let clip = app
+        .state::<AppState>()
+        .client()
+        .get_clip(clip_id)
+        .await
+        .map_err(|e| format!("Failed to get clip: {}", e))?;
+    clipboard::copy_clip_to_clipboard(app, &clip).await?;
+
+    hide(app);
+    if paste {
+        paste::paste_after_focus_returns().await?;
+    }
+    Ok(())
+}
+
+fn create(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    WebviewWindowBuilder::new(
+        app,
+        LABEL,
+        WebviewUrl::App("index.html?window=palette".into()),
+    )
+    .title("Clipper")
+    .inner_size(WIDTH, HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .shadow(true)
+    .focused(true)
+    .visible(false)
+    .build()
+}
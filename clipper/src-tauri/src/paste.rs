@@ -0,0 +1,101 @@
+//! Paste into the previously focused app
+//!
+//! Pasting a clip puts it on the clipboard, hides the Clipper window so the
+//! app that was focused before gets focus back, and sends that app the paste
+//! keystroke, so picking a clip is a one-step paste. The keystroke is sent
+//! with the platform's own tools:
+//!
+//! - macOS: `osascript` (System Events), which needs the Accessibility
+//!   permission
+//! - Linux: `wtype` on Wayland, otherwise `xdotool`
+//! - Windows: PowerShell `SendKeys`
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::clipboard;
+use crate::shortcuts;
+use crate::state::AppState;
+
+/// How long to wait after hiding the window for focus to return to the
+/// previous app, so the keystroke doesn't go to Clipper
+const FOCUS_RETURN_DELAY: Duration = Duration::from_millis(200);
+
+/// Put a clip on the clipboard and paste it into the previously focused app
+pub async fn paste_clip(app: &AppHandle, clip_id: &str) -> Result<(), String> {
+    let clip = app
+        .state::<AppState>()
+        .client()
+        .get_clip(clip_id)
+        .await
+        .map_err(|e| format!("Failed to get clip: {}", e))?;
+    clipboard::copy_clip_to_clipboard(app, &clip).await?;
+
+    shortcuts::hide_window(app);
+    paste_after_focus_returns().await
+}
+
+/// Send the paste keystroke once a just-hidden Clipper window has given focus
+/// back to the previous app
+pub async fn paste_after_focus_returns() -> Result<(), String> {
+    tokio::time::sleep(FOCUS_RETURN_DELAY).await;
+    send_paste_keystroke().await
+}
+
+#[cfg(target_os = "macos")]
+async fn send_paste_keystroke() -> Result<(), String> {
+    run(
+        "osascript",
+        &[
+            "-e",
+            "tell application \"System Events\" to keystroke \"v\" using command down",
+        ],
+    )
+    .await
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+async fn send_paste_keystroke() -> Result<(), String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        run("wtype", &["-M", "ctrl", "v", "-m", "ctrl"]).await
+    } else {
+        run("xdotool", &["key", "--clearmodifiers", "ctrl+v"]).await
+    }
+}
+
+#[cfg(windows)]
+async fn send_paste_keystroke() -> Result<(), String> {
+    run(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             [System.Windows.Forms.SendKeys]::SendWait('^v')",
+        ],
+    )
+    .await
+}
+
+/// Run a keystroke tool, failing if it's missing or exits unsuccessfully
+async fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("{} is not installed", program),
+            _ => format!("Failed to run {}: {}", program, e),
+        })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,73 @@
+//! Quick note popup
+//!
+//! A small always-on-top window for typing a clip without opening the main
+//! window. It loads the same frontend with `?window=quick-note`, which renders
+//! only the note editor, and hides itself when it loses focus.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+/// Label of the quick note window
+pub const LABEL: &str = "quick-note";
+
+const WIDTH: f64 = 480.0;
+const HEIGHT: f64 = 200.0;
+
+/// Show the quick note window (creating it on first use) and focus it
+pub fn show(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => create(app).map_err(|e| format!("Failed to create quick note window: {}", e))?,
+    };
+    let _ = window.center();
+    let _ = window.show();
+    let _ = window.unminimize();
+    // On Windows, focusing only works because the shortcut or tray click that
+    // got us here gives the process the right to take the foreground
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Hide the quick note window, if open
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Show the quick note window if hidden, hide it otherwise
+///
+/// Safe to call from shortcut and tray handlers: the window is shown from a
+/// separate task, since creating it in an event handler deadlocks on Windows.
+pub fn toggle(app: &AppHandle) {
+    let visible = app
+        .get_webview_window(LABEL)
+        .is_some_and(|w| w.is_visible().unwrap_or(false));
+    if visible {
+        hide(app);
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = show(&app) {
+            log::error!("{}", e);
+        }
+    });
+}
+
+fn create(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    WebviewWindowBuilder::new(
+        app,
+        LABEL,
+        WebviewUrl::App("index.html?window=quick-note".into()),
+    )
+    .title("Quick Note")
+    .inner_size(WIDTH, HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible_on_all_workspaces(true)
+    .shadow(true)
+    .focused(true)
+    .visible(false)
+    .build()
+}
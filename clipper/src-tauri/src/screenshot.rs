@@ -0,0 +1,177 @@
+//! Screenshot capture
+//!
+//! Screenshots are taken with the platform's own interactive capture tool,
+//! so the user picks a region or window the usual way:
+//!
+//! - macOS: `screencapture -i` (drag a region, or press Space for a window)
+//! - Linux: the first available of `gnome-screenshot`, `spectacle`, `scrot`
+//!   and `maim`
+//! - Windows: the Snipping Tool overlay (`ms-screenclip:`), which puts the
+//!   snip on the clipboard
+//!
+//! The image is uploaded as a clip tagged `$image` and `$screenshot`.
+
+use chrono::Utc;
+use clipper_client::Clip;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::state::AppState;
+
+/// Take a screenshot, upload it and emit `screenshot-captured`
+///
+/// Returns `None` if the user cancelled the capture.
+pub async fn capture(app: &AppHandle) -> Result<Option<Clip>, String> {
+    let Some(png_bytes) = capture_png(app).await? else {
+        return Ok(None);
+    };
+
+    let state = app.state::<AppState>();
+    let max_size = state.get_max_upload_size_bytes();
+    if png_bytes.len() as u64 > max_size {
+        return Err(format!(
+            "Screenshot ({:.2} MB) exceeds maximum allowed size ({:.2} MB)",
+            png_bytes.len() as f64 / (1024.0 * 1024.0),
+            max_size as f64 / (1024.0 * 1024.0)
+        ));
+    }
+
+    let filename = format!("screenshot-{}.png", Utc::now().format("%Y-%m-%d-%H-%M-%S"));
+    let clip = state
+        .client()
+        .upload_file_bytes(
+            png_bytes,
+            filename,
+            vec![
+                "$image".to_string(),
+                "$screenshot".to_string(),
+                crate::get_hostname_tag(),
+            ],
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to upload screenshot: {}", e))?;
+
+    let _ = app.emit("screenshot-captured", &clip);
+    Ok(Some(clip))
+}
+
+/// Capture in the background (from a shortcut or the tray), reporting
+/// failures to the frontend with `screenshot-failed`
+pub fn spawn_capture(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = capture(&app).await {
+            log::error!("Failed to capture screenshot: {}", e);
+            let _ = app.emit("screenshot-failed", e);
+        }
+    });
+}
+
+/// Folder in the app cache directory the capture tools write to
+#[cfg(not(windows))]
+const CACHE_DIR: &str = "screenshots";
+
+/// Run the capture tool and return the PNG it wrote
+///
+/// The tool writes to a folder in the app cache directory that only the
+/// current user can access, and the file is removed once read.
+#[cfg(not(windows))]
+async fn capture_png(app: &AppHandle) -> Result<Option<Vec<u8>>, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get cache directory: {}", e))?
+        .join(CACHE_DIR);
+    clipper_security::secure_create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    let path = dir.join(format!("{}.png", Utc::now().timestamp_millis()));
+    let path_str = path.to_string_lossy().into_owned();
+
+    #[cfg(target_os = "macos")]
+    let tools: &[(&str, &[&str])] = &[("screencapture", &["-i", "-x"])];
+    #[cfg(not(target_os = "macos"))]
+    let tools: &[(&str, &[&str])] = &[
+        ("gnome-screenshot", &["-a", "-f"]),
+        ("spectacle", &["-b", "-n", "-r", "-o"]),
+        ("scrot", &["-s"]),
+        ("maim", &["-s"]),
+    ];
+
+    let mut found = false;
+    for (program, args) in tools {
+        let status = tokio::process::Command::new(program)
+            .args(*args)
+            .arg(&path_str)
+            .status()
+            .await;
+        match status {
+            Ok(status) if status.success() => {
+                found = true;
+                break;
+            }
+            // Some tools also fail when the capture is cancelled
+            Ok(status) => {
+                log::info!("{} exited with {}", program, status);
+                let _ = tokio::fs::remove_file(&path).await;
+                return Ok(None);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to run {}: {}", program, e)),
+        }
+    }
+    if !found {
+        return Err("No screenshot tool found".to_string());
+    }
+
+    // The tools exit without writing the file when the capture is cancelled
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => {
+            let _ = tokio::fs::remove_file(&path).await;
+            Ok((!bytes.is_empty()).then_some(bytes))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// How long to wait for the user to finish a snip
+#[cfg(windows)]
+const SNIP_TIMEOUT_SECS: u64 = 60;
+
+/// Open the snipping overlay and wait for the snip to appear on the clipboard
+///
+/// The clipboard monitor leaves images alone meanwhile, so the snip is only
+/// uploaded once, as a screenshot.
+#[cfg(windows)]
+async fn capture_png(app: &AppHandle) -> Result<Option<Vec<u8>>, String> {
+    use crate::clipboard::get_clipboard_image_png;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    let state = app.state::<AppState>();
+    let before = get_clipboard_image_png();
+    state.capturing_screenshot.store(true, Ordering::SeqCst);
+
+    let result = async {
+        tokio::process::Command::new("explorer")
+            .arg("ms-screenclip:")
+            .status()
+            .await
+            .map_err(|e| format!("Failed to open the snipping overlay: {}", e))?;
+
+        for _ in 0..SNIP_TIMEOUT_SECS * 2 {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            if let Some(png_bytes) = get_clipboard_image_png()
+                && Some(&png_bytes) != before.as_ref()
+            {
+                // Mark as synced so the monitor doesn't upload it again
+                state.set_last_synced_image(png_bytes.clone());
+                return Ok(Some(png_bytes));
+            }
+        }
+        Ok(None)
+    }
+    .await;
+
+    state.capturing_screenshot.store(false, Ordering::SeqCst);
+    result
+}
@@ -208,9 +208,11 @@ impl ServerManager {
         // Secure the data directories and fix any incorrect permissions
         // On Unix: checks and fixes permissions to 0700/0600
         // On Windows: sets DACL to grant access only to current user
-        match clipper_security::secure_directory_recursive(&self.db_path, |msg| {
+        match clipper_security::secure_directory_recursive_async(&self.db_path, |msg| {
             log::warn!("{}", msg)
-        }) {
+        })
+        .await
+        {
             Ok(count) if count > 0 => {
                 log::info!("Fixed permissions on {} items in database directory", count);
             }
@@ -218,9 +220,11 @@ impl ServerManager {
             _ => {}
         }
 
-        match clipper_security::secure_directory_recursive(&self.storage_path, |msg| {
+        match clipper_security::secure_directory_recursive_async(&self.storage_path, |msg| {
             log::warn!("{}", msg)
-        }) {
+        })
+        .await
+        {
             Ok(count) if count > 0 => {
                 log::info!("Fixed permissions on {} items in storage directory", count);
             }
@@ -336,7 +340,10 @@ impl ServerManager {
 
         // Add bearer token if external access is enabled and token is set
         if let Some(ref token) = bundled_server_token {
-            env_vars.push(("CLIPPER_BEARER_TOKEN".to_string(), token.clone()));
+            env_vars.push((
+                "CLIPPER_BEARER_TOKEN".to_string(),
+                token.expose().to_string(),
+            ));
         }
 
         // Only arg needed is the parent pipe handle (not sensitive)
@@ -1,6 +1,9 @@
+use clipper_security::secrets::{CLIENT_SECRETS_SERVICE, SECRETS_FILE_NAME};
+use clipper_security::{ClientSecrets, SecretStore, SecretString};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use tauri::Manager;
 use tokio::fs;
 
@@ -32,6 +35,49 @@ pub enum SyntaxTheme {
     Gruvbox,
 }
 
+/// What the clipboard monitor does with files copied in Finder/Explorer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CopiedFilesMode {
+    /// Upload files up to `maxCopiedFileSizeMb` as `$file` clips; larger
+    /// files and folders are listed
+    #[default]
+    Upload,
+    /// Save the paths as a `$filelist` text clip
+    List,
+    /// Ignore copied files
+    Off,
+}
+
+/// What happens when a text clip from another device arrives while
+/// something copied here moments ago is still on the clipboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncConflictPolicy {
+    /// Keep this device's copy on the clipboard; the other clip is only
+    /// added to the history
+    #[default]
+    KeepBoth,
+    /// Put whichever clip the server stored last on the clipboard, so every
+    /// device ends up with the same one
+    NewestWins,
+    /// Keep this device's copy and ask which one to use
+    Prompt,
+}
+
+/// Which selections a restored clip is put on. Only Linux has a primary
+/// selection; elsewhere clips always go on the clipboard.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreSelection {
+    /// The clipboard, pasted with Ctrl+V
+    #[default]
+    Clipboard,
+    /// The primary selection, pasted with the middle mouse button
+    Primary,
+    Both,
+}
+
 /// Settings dialog window geometry
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -62,6 +108,132 @@ pub struct MainWindowGeometry {
     pub maximized: Option<bool>,
 }
 
+/// Something a global shortcut can trigger; see `shortcuts.rs`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutAction {
+    /// Show or hide the main window (bound by `globalShortcut`)
+    ToggleWindow,
+    /// Put the most recent clip on the clipboard
+    PasteLastClip,
+    /// Replace the clipboard content with its plain text
+    PastePlainText,
+    /// Show the main window with the search box focused
+    QuickPick,
+    /// Take a screenshot and save it as a clip
+    CaptureScreenshot,
+    /// Show or hide the quick note popup
+    QuickNote,
+    /// Show or hide the clip palette
+    Palette,
+    PasteStackToggle,
+    PasteStackPop,
+    /// Turn append mode, where copies are appended to one clip, on or off
+    AppendModeToggle,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 10] = [
+        ShortcutAction::ToggleWindow,
+        ShortcutAction::PasteLastClip,
+        ShortcutAction::PastePlainText,
+        ShortcutAction::QuickPick,
+        ShortcutAction::CaptureScreenshot,
+        ShortcutAction::QuickNote,
+        ShortcutAction::Palette,
+        ShortcutAction::PasteStackToggle,
+        ShortcutAction::PasteStackPop,
+        ShortcutAction::AppendModeToggle,
+    ];
+
+    /// The binding used when settings don't set one (empty means unbound)
+    pub fn default_binding(self) -> String {
+        match self {
+            ShortcutAction::ToggleWindow => default_global_shortcut(),
+            ShortcutAction::QuickNote => format!("{}+Alt+N", PRIMARY_MODIFIER),
+            ShortcutAction::Palette => format!("{}+Alt+P", PRIMARY_MODIFIER),
+            ShortcutAction::PasteStackToggle => format!("{}+Alt+S", PRIMARY_MODIFIER),
+            ShortcutAction::PasteStackPop => format!("{}+Alt+V", PRIMARY_MODIFIER),
+            ShortcutAction::AppendModeToggle => format!("{}+Alt+A", PRIMARY_MODIFIER),
+            // Unbound until the user picks a shortcut
+            ShortcutAction::PasteLastClip
+            | ShortcutAction::PastePlainText
+            | ShortcutAction::QuickPick
+            | ShortcutAction::CaptureScreenshot => String::new(),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+const PRIMARY_MODIFIER: &str = "Command";
+#[cfg(not(target_os = "macos"))]
+const PRIMARY_MODIFIER: &str = "Ctrl";
+
+/// What a user-defined action does
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ClipActionKind {
+    /// Run a shell command (`sh -c`, or `cmd /C` on Windows)
+    Command { command: String },
+    /// Send an HTTP request
+    Http {
+        url: String,
+        /// HTTP method (default: GET)
+        #[serde(default = "default_action_http_method")]
+        method: String,
+        #[serde(default)]
+        headers: std::collections::HashMap<String, String>,
+        #[serde(default)]
+        body: Option<String>,
+    },
+}
+
+/// A user-defined action shown in the clip context menu
+///
+/// The command, URL, headers and body may contain placeholders such as
+/// `{content}`; see `actions.rs`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipAction {
+    /// Menu label; also identifies the action
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: ClipActionKind,
+    /// Also list the action in the tray menu, where it runs on the latest clip
+    #[serde(default)]
+    pub show_in_tray: bool,
+}
+
+/// A named external server, for switching between e.g. a work server and a
+/// home server
+///
+/// Certificates are trusted per host, so each profile's server keeps its own
+/// trusted certificate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerProfile {
+    /// Name shown in the settings and the tray; also identifies the profile
+    pub name: String,
+    /// Server URL
+    pub url: String,
+    /// Bearer token
+    #[serde(default)]
+    pub token: Option<SecretString>,
+}
+
+impl ServerProfile {
+    /// Whether `name` can name a profile: not blank, and on one line so it
+    /// fits in the tray menu
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.trim().is_empty() && !name.contains(['\n', '\r'])
+    }
+}
+
+/// The app settings, saved in settings.json
+///
+/// The tokens (including those of `server_profiles`) and
+/// `trusted_certificates` are kept in the secret store instead, and are
+/// left out of settings.json.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
@@ -97,6 +269,14 @@ pub struct Settings {
     /// Global shortcut to toggle window visibility (e.g., "CmdOrCtrl+Shift+V")
     #[serde(default = "default_global_shortcut")]
     pub global_shortcut: String,
+    /// Whether the window toggle shortcut shows the main window centered on
+    /// the monitor with the mouse cursor, instead of where it was last
+    #[serde(default)]
+    pub window_follows_cursor: bool,
+    /// Global shortcuts for the other actions; missing actions use their
+    /// default binding and an empty string leaves an action unbound
+    #[serde(default)]
+    pub shortcuts: BTreeMap<ShortcutAction, String>,
     /// Whether to enable automatic cleanup of old clips (bundled server only)
     #[serde(default)]
     pub cleanup_enabled: bool,
@@ -105,13 +285,45 @@ pub struct Settings {
     pub cleanup_retention_days: u32,
     /// Bearer token for external server authentication
     #[serde(default)]
-    pub external_server_token: Option<String>,
+    pub external_server_token: Option<SecretString>,
+    /// Named external servers to switch between
+    #[serde(default)]
+    pub server_profiles: Vec<ServerProfile>,
     /// Bearer token for bundled server when external access is enabled
     #[serde(default)]
-    pub bundled_server_token: Option<String>,
+    pub bundled_server_token: Option<SecretString>,
     /// Maximum upload size in MB for bundled server (default: 10)
     #[serde(default = "default_max_upload_size_mb")]
     pub max_upload_size_mb: u64,
+    /// Whether the clipboard monitor saves copied images (e.g. screenshots)
+    #[serde(default = "default_capture_images")]
+    pub capture_images: bool,
+    /// Largest copied image saved, in MB of PNG; the server's upload limit
+    /// still applies (default: 10)
+    #[serde(default = "default_max_image_size_mb")]
+    pub max_image_size_mb: u64,
+    /// What the clipboard monitor does with copied files
+    #[serde(default)]
+    pub copied_files: CopiedFilesMode,
+    /// Largest copied file uploaded in upload mode, in MB; the server's
+    /// upload limit still applies (default: 10)
+    #[serde(default = "default_max_copied_file_size_mb")]
+    pub max_copied_file_size_mb: u64,
+    /// Whether the clipboard monitor also saves text selected with the mouse
+    /// (the primary selection, Linux only)
+    #[serde(default)]
+    pub capture_primary_selection: bool,
+    /// Where clips copied from the history are put (Linux only)
+    #[serde(default)]
+    pub restore_selection: RestoreSelection,
+    /// What to do with a clip from another device that arrives right after
+    /// something was copied here
+    #[serde(default)]
+    pub sync_conflict_policy: SyncConflictPolicy,
+    /// Only clips with one of these tags are synced to this device; empty
+    /// to sync every clip. Clips copied here are always synced back.
+    #[serde(default)]
+    pub sync_tags: Vec<String>,
     /// Settings dialog window geometry (size and position)
     #[serde(default)]
     pub settings_window_geometry: SettingsWindowGeometry,
@@ -147,6 +359,19 @@ pub struct Settings {
     /// Default: 2
     #[serde(default = "default_rocksdb_max_write_buffer_number")]
     pub rocksdb_max_write_buffer_number: u32,
+    /// User-defined clip actions (configured in settings.json only)
+    #[serde(default)]
+    pub actions: Vec<ClipAction>,
+    /// Exchange clips directly with other instances on the LAN
+    #[serde(default)]
+    pub p2p_sync_enabled: bool,
+    /// Shared secret for P2P sync; only instances with the same key connect
+    #[serde(default)]
+    pub p2p_sync_key: Option<String>,
+}
+
+fn default_action_http_method() -> String {
+    "GET".to_string()
 }
 
 fn default_cleanup_retention_days() -> u32 {
@@ -173,6 +398,18 @@ fn default_max_upload_size_mb() -> u64 {
     10
 }
 
+fn default_capture_images() -> bool {
+    true
+}
+
+fn default_max_image_size_mb() -> u64 {
+    10
+}
+
+fn default_max_copied_file_size_mb() -> u64 {
+    10
+}
+
 fn default_global_shortcut() -> String {
     #[cfg(target_os = "macos")]
     {
@@ -207,11 +444,22 @@ impl Default for Settings {
             language: None,
             notifications_enabled: true,
             global_shortcut: default_global_shortcut(),
+            window_follows_cursor: false,
+            shortcuts: BTreeMap::new(),
             cleanup_enabled: false,
             cleanup_retention_days: default_cleanup_retention_days(),
             external_server_token: None,
+            server_profiles: Vec::new(),
             bundled_server_token: None,
             max_upload_size_mb: default_max_upload_size_mb(),
+            capture_images: default_capture_images(),
+            max_image_size_mb: default_max_image_size_mb(),
+            copied_files: CopiedFilesMode::Upload,
+            max_copied_file_size_mb: default_max_copied_file_size_mb(),
+            capture_primary_selection: false,
+            restore_selection: RestoreSelection::Clipboard,
+            sync_conflict_policy: SyncConflictPolicy::KeepBoth,
+            sync_tags: Vec::new(),
             settings_window_geometry: SettingsWindowGeometry::default(),
             main_window_geometry: MainWindowGeometry::default(),
             trusted_certificates: std::collections::HashMap::new(),
@@ -220,6 +468,70 @@ impl Default for Settings {
             rocksdb_block_cache_mb: default_rocksdb_block_cache_mb(),
             rocksdb_write_buffer_mb: default_rocksdb_write_buffer_mb(),
             rocksdb_max_write_buffer_number: default_rocksdb_max_write_buffer_number(),
+            actions: Vec::new(),
+            p2p_sync_enabled: false,
+            p2p_sync_key: None,
+        }
+    }
+}
+
+impl Settings {
+    /// The shortcut bound to `action`, or an empty string if it's unbound
+    pub fn shortcut(&self, action: ShortcutAction) -> String {
+        match action {
+            // The window toggle falls back to the default rather than being
+            // left unbound, so the window can always be brought back
+            ShortcutAction::ToggleWindow
+                if crate::parse_shortcut(&self.global_shortcut).is_some() =>
+            {
+                self.global_shortcut.clone()
+            }
+            ShortcutAction::ToggleWindow => action.default_binding(),
+            _ => self
+                .shortcuts
+                .get(&action)
+                .cloned()
+                .unwrap_or_else(|| action.default_binding()),
+        }
+    }
+}
+
+impl Settings {
+    /// Check that the server profiles have valid, distinct names
+    pub fn check_server_profiles(&self) -> Result<(), String> {
+        let mut names = std::collections::HashSet::new();
+        for profile in &self.server_profiles {
+            if !ServerProfile::is_valid_name(&profile.name) {
+                return Err(format!("Invalid server profile name: {:?}", profile.name));
+            }
+            if !names.insert(profile.name.as_str()) {
+                return Err(format!("Duplicate server profile name: {}", profile.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the tokens and trusted certificates out of the settings
+    fn take_secrets(&mut self) -> ClientSecrets {
+        ClientSecrets {
+            external_server_token: self.external_server_token.take(),
+            bundled_server_token: self.bundled_server_token.take(),
+            trusted_certificates: std::mem::take(&mut self.trusted_certificates),
+            profile_tokens: self
+                .server_profiles
+                .iter_mut()
+                .filter_map(|profile| Some((profile.name.clone(), profile.token.take()?)))
+                .collect(),
+        }
+    }
+
+    fn set_secrets(&mut self, secrets: ClientSecrets) {
+        self.external_server_token = secrets.external_server_token;
+        self.bundled_server_token = secrets.bundled_server_token;
+        self.trusted_certificates = secrets.trusted_certificates;
+        let mut profile_tokens = secrets.profile_tokens;
+        for profile in &mut self.server_profiles {
+            profile.token = profile_tokens.remove(&profile.name);
         }
     }
 }
@@ -228,6 +540,10 @@ impl Default for Settings {
 pub struct SettingsManager {
     settings: Arc<RwLock<Settings>>,
     config_path: PathBuf,
+    /// Tokens and trusted certificates, in the OS keychain when available
+    secrets: SecretStore,
+    /// Secrets as last loaded or saved, so unchanged ones aren't rewritten
+    stored_secrets: Arc<Mutex<ClientSecrets>>,
 }
 
 #[allow(dead_code)]
@@ -235,9 +551,12 @@ impl SettingsManager {
     /// Create a new settings manager with the given config directory
     pub fn new(config_dir: PathBuf) -> Self {
         let config_path = config_dir.join(SETTINGS_FILE_NAME);
+        let secrets = SecretStore::new(CLIENT_SECRETS_SERVICE, config_dir.join(SECRETS_FILE_NAME));
         Self {
             settings: Arc::new(RwLock::new(Settings::default())),
             config_path,
+            secrets,
+            stored_secrets: Arc::new(Mutex::new(ClientSecrets::default())),
         }
     }
 
@@ -245,8 +564,7 @@ impl SettingsManager {
     pub async fn init(&self) -> Result<(), String> {
         // Ensure config directory exists
         if let Some(parent) = self.config_path.parent() {
-            fs::create_dir_all(parent)
-                .await
+            clipper_security::secure_create_dir_all(parent)
                 .map_err(|e| format!("Failed to create config directory: {}", e))?;
 
             // Secure the config directory and fix any incorrect permissions
@@ -261,17 +579,32 @@ impl SettingsManager {
         }
 
         // Load settings if file exists
-        if self.config_path.exists() {
+        let exists = self.config_path.exists();
+        let mut settings = if exists {
             let contents = fs::read_to_string(&self.config_path)
                 .await
                 .map_err(|e| format!("Failed to read settings file: {}", e))?;
 
-            let settings: Settings = serde_json::from_str(&contents)
-                .map_err(|e| format!("Failed to parse settings: {}", e))?;
-
-            *self.settings.write().unwrap() = settings;
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse settings: {}", e))?
         } else {
-            // Save default settings
+            Settings::default()
+        };
+
+        // Secrets written by older versions are moved out of settings.json
+        let plaintext = settings.take_secrets();
+        let migrate = !plaintext.is_empty();
+        let secrets = ClientSecrets::load(&self.secrets, plaintext)
+            .map_err(|e| format!("Failed to load secrets: {}", e))?;
+        log::debug!("Secrets are stored in {:?}", self.secrets.backend());
+        *self.stored_secrets.lock().unwrap() = secrets.clone();
+        settings.set_secrets(secrets);
+        *self.settings.write().unwrap() = settings;
+
+        if migrate {
+            log::info!("Moved tokens and trusted certificates out of the settings file");
+        }
+        if migrate || !exists {
             self.save().await?;
         }
 
@@ -291,12 +624,21 @@ impl SettingsManager {
 
     /// Save current settings to disk
     async fn save(&self) -> Result<(), String> {
-        let settings = self.get();
+        let mut settings = self.get();
+
+        let secrets = settings.take_secrets();
+        {
+            let mut stored = self.stored_secrets.lock().unwrap();
+            secrets
+                .save(&self.secrets, &stored)
+                .map_err(|e| format!("Failed to save secrets: {}", e))?;
+            *stored = secrets;
+        }
+
         let contents = serde_json::to_string_pretty(&settings)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&self.config_path, contents)
-            .await
+        clipper_security::secure_write(&self.config_path, contents)
             .map_err(|e| format!("Failed to write settings file: {}", e))?;
 
         Ok(())
@@ -331,12 +673,12 @@ impl SettingsManager {
     }
 
     /// Get the bundled server token (for external access auth)
-    pub fn get_bundled_server_token(&self) -> Option<String> {
+    pub fn get_bundled_server_token(&self) -> Option<SecretString> {
         self.settings.read().unwrap().bundled_server_token.clone()
     }
 
     /// Set and save the bundled server token
-    pub async fn set_bundled_server_token(&self, token: String) -> Result<(), String> {
+    pub async fn set_bundled_server_token(&self, token: SecretString) -> Result<(), String> {
         {
             self.settings.write().unwrap().bundled_server_token = Some(token);
         }
@@ -344,10 +686,21 @@ impl SettingsManager {
     }
 
     /// Get the external server token
-    pub fn get_external_server_token(&self) -> Option<String> {
+    pub fn get_external_server_token(&self) -> Option<SecretString> {
         self.settings.read().unwrap().external_server_token.clone()
     }
 
+    /// Get a server profile by name
+    pub fn get_server_profile(&self, name: &str) -> Option<ServerProfile> {
+        self.settings
+            .read()
+            .unwrap()
+            .server_profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+    }
+
     /// Get the maximum upload size in MB
     pub fn get_max_upload_size_mb(&self) -> u64 {
         self.settings.read().unwrap().max_upload_size_mb
@@ -378,6 +731,29 @@ impl SettingsManager {
         self.settings.read().unwrap().rocksdb_max_write_buffer_number
     }
 
+    /// Bind `action` to `shortcut` (empty to unbind) and save
+    pub async fn set_shortcut(
+        &self,
+        action: ShortcutAction,
+        shortcut: String,
+    ) -> Result<(), String> {
+        {
+            let mut settings = self.settings.write().unwrap();
+            match action {
+                ShortcutAction::ToggleWindow => settings.global_shortcut = shortcut,
+                _ => {
+                    settings.shortcuts.insert(action, shortcut);
+                }
+            }
+        }
+        self.save().await
+    }
+
+    /// Get the user-defined clip actions
+    pub fn get_actions(&self) -> Vec<ClipAction> {
+        self.settings.read().unwrap().actions.clone()
+    }
+
     /// Get all trusted certificate fingerprints
     pub fn get_trusted_certificates(&self) -> std::collections::HashMap<String, String> {
         self.settings.read().unwrap().trusted_certificates.clone()
@@ -0,0 +1,221 @@
+//! Global shortcut registry
+//!
+//! Every [`ShortcutAction`] can be bound to one global shortcut. The window
+//! toggle is stored in `globalShortcut` and the other bindings in the
+//! `shortcuts` map of settings.json; see [`Settings::shortcut`].
+
+use clipper_client::SearchFilters;
+use serde::Serialize;
+#[cfg(target_os = "macos")]
+use tauri::ActivationPolicy;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+use crate::clipboard;
+use crate::palette;
+use crate::parse_shortcut;
+use crate::quick_note;
+use crate::screenshot;
+use crate::settings::{Settings, SettingsManager, ShortcutAction};
+use crate::state::AppState;
+use crate::window_placement;
+
+/// An action and the shortcut bound to it, as listed by `get_shortcuts`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    /// Empty if the action is unbound
+    pub shortcut: String,
+}
+
+/// The binding of every action, in display order
+pub fn bindings(settings: &Settings) -> Vec<ShortcutBinding> {
+    ShortcutAction::ALL
+        .iter()
+        .map(|&action| ShortcutBinding {
+            action,
+            shortcut: settings.shortcut(action),
+        })
+        .collect()
+}
+
+/// Register every bound shortcut from settings
+///
+/// Invalid or unavailable shortcuts are logged and skipped.
+pub fn register_all(app: &AppHandle) {
+    let settings = app.state::<SettingsManager>().get();
+    for binding in bindings(&settings) {
+        if binding.shortcut.is_empty() {
+            continue;
+        }
+        match parse_shortcut(&binding.shortcut) {
+            Some(shortcut) => {
+                if let Err(e) = app.global_shortcut().register(shortcut) {
+                    log::error!(
+                        "Failed to register shortcut '{}' for {:?}: {}",
+                        binding.shortcut,
+                        binding.action,
+                        e
+                    );
+                }
+            }
+            None => log::warn!(
+                "Invalid shortcut '{}' for {:?}",
+                binding.shortcut,
+                binding.action
+            ),
+        }
+    }
+}
+
+/// Bind `action` to `shortcut` (empty to unbind), register it and save it
+///
+/// Fails without changing anything if the shortcut is invalid, already bound
+/// to another action, or can't be registered.
+pub async fn update(
+    app: &AppHandle,
+    action: ShortcutAction,
+    shortcut: String,
+) -> Result<(), String> {
+    let settings_manager = app.state::<SettingsManager>();
+    let settings = settings_manager.get();
+
+    let new = if shortcut.is_empty() {
+        if action == ShortcutAction::ToggleWindow {
+            return Err("The window toggle shortcut can't be removed".to_string());
+        }
+        None
+    } else {
+        Some(
+            parse_shortcut(&shortcut)
+                .ok_or_else(|| format!("Invalid shortcut format: {}", shortcut))?,
+        )
+    };
+    if let Some(new) = new
+        && let Some(other) = ShortcutAction::ALL
+            .into_iter()
+            .find(|&a| a != action && parse_shortcut(&settings.shortcut(a)) == Some(new))
+    {
+        return Err(format!(
+            "Shortcut '{}' is already used by {:?}",
+            shortcut, other
+        ));
+    }
+
+    let global_shortcut = app.global_shortcut();
+    let old = parse_shortcut(&settings.shortcut(action));
+    if let Some(old) = old {
+        let _ = global_shortcut.unregister(old);
+    }
+    if let Some(new) = new
+        && let Err(e) = global_shortcut.register(new)
+    {
+        if let Some(old) = old {
+            let _ = global_shortcut.register(old);
+        }
+        return Err(format!("Failed to register shortcut '{}': {}", shortcut, e));
+    }
+
+    settings_manager.set_shortcut(action, shortcut).await
+}
+
+/// Run the action bound to a pressed shortcut
+pub fn handle(app: &AppHandle, shortcut: &Shortcut) {
+    let settings = app.state::<SettingsManager>().get();
+    let Some(action) = ShortcutAction::ALL
+        .into_iter()
+        .find(|&a| parse_shortcut(&settings.shortcut(a)).as_ref() == Some(shortcut))
+    else {
+        return;
+    };
+
+    match action {
+        ShortcutAction::ToggleWindow => toggle_window(app),
+        ShortcutAction::PasteLastClip => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste_last_clip(&app).await {
+                    log::error!("Failed to paste last clip: {}", e);
+                }
+            });
+        }
+        ShortcutAction::PastePlainText => {
+            if let Err(e) = paste_plain_text(app) {
+                log::error!("Failed to convert clipboard to plain text: {}", e);
+            }
+        }
+        ShortcutAction::QuickPick => {
+            show_window(app);
+            let _ = app.emit("open-quick-pick", ());
+        }
+        ShortcutAction::CaptureScreenshot => screenshot::spawn_capture(app),
+        ShortcutAction::QuickNote => quick_note::toggle(app),
+        ShortcutAction::Palette => palette::toggle(app),
+        ShortcutAction::PasteStackToggle => clipboard::toggle_paste_stack(app),
+        ShortcutAction::PasteStackPop => {
+            if let Err(e) = clipboard::pop_paste_stack(app) {
+                log::error!("Failed to pop paste stack: {}", e);
+            }
+        }
+        ShortcutAction::AppendModeToggle => clipboard::toggle_append_mode(app),
+    }
+}
+
+fn toggle_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            hide_window(app);
+        } else {
+            show_window(app);
+        }
+    }
+}
+
+/// Hide the main window, giving focus back to the previous app
+pub fn hide_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+        #[cfg(target_os = "macos")]
+        let _ = app.set_activation_policy(ActivationPolicy::Accessory);
+    }
+}
+
+fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if !window.is_visible().unwrap_or(false)
+            && app.state::<SettingsManager>().get().window_follows_cursor
+            && let Err(e) = window_placement::center_on_cursor_monitor(&window)
+        {
+            log::warn!("Failed to move window to the cursor's monitor: {}", e);
+        }
+        let _ = window.show();
+        let _ = window.set_focus();
+        #[cfg(target_os = "macos")]
+        let _ = app.set_activation_policy(ActivationPolicy::Regular);
+    }
+}
+
+/// Put the most recent clip (text or image) on the clipboard
+async fn paste_last_clip(app: &AppHandle) -> Result<(), String> {
+    let clip = app
+        .state::<AppState>()
+        .client()
+        .list_clips(SearchFilters::new(), 1, 1)
+        .await
+        .map_err(|e| e.to_string())?
+        .items
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No clips yet".to_string())?;
+    clipboard::copy_clip_to_clipboard(app, &clip).await
+}
+
+/// Replace the clipboard content with its text, dropping any formatting
+fn paste_plain_text(app: &AppHandle) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    let text = clipboard.get_text().map_err(|e| e.to_string())?;
+    app.state::<AppState>()
+        .set_last_synced_content(text.clone());
+    clipboard.set_text(text).map_err(|e| e.to_string())
+}
@@ -1,7 +1,11 @@
-use clipper_client::ClipperClient;
+use crate::clipboard::{AppendMode, CapturePause, LocalCopy, PasteStack};
+use crate::settings::Settings;
+use clipper_client::{ClipperClient, ResponseCache};
+use clipper_security::SecretString;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::watch;
 
 pub struct AppState {
     client: RwLock<ClipperClient>,
@@ -9,12 +13,33 @@ pub struct AppState {
     /// Last synced image content (PNG bytes) to prevent duplicate uploads
     pub last_synced_image: Arc<Mutex<Vec<u8>>>,
     pub websocket_connected: Arc<AtomicBool>,
-    /// Counter that increments when WebSocket should reconnect (e.g., token changed)
-    pub ws_reconnect_counter: Arc<AtomicU64>,
+    /// Generation of the server connection, bumped together with replacing the
+    /// client so the WebSocket listener reconnects (e.g., server or token changed)
+    connection_generation: watch::Sender<u64>,
     /// Maximum upload size in bytes (from server config)
     max_upload_size_bytes: Arc<AtomicU64>,
     /// Trusted certificate fingerprints (host -> SHA-256 fingerprint)
     trusted_fingerprints: RwLock<HashMap<String, String>>,
+    /// ETag response cache shared by every client instance
+    response_cache: Arc<ResponseCache>,
+    /// Queue of copied text while paste stack mode is on
+    pub paste_stack: Arc<PasteStack>,
+    /// Clip that copied text is appended to while append mode is on
+    pub append_mode: Arc<AppendMode>,
+    /// Set while a screenshot capture waits for a snip on the clipboard
+    pub capturing_screenshot: Arc<AtomicBool>,
+    /// Whether clipboard capture is paused, and until when
+    pub capture_pause: Arc<CapturePause>,
+    /// Text just copied here, to notice clips from other devices copied at
+    /// the same time
+    pub local_copy: Arc<LocalCopy>,
+    /// Whether the clipboard monitor saves copied images (from settings)
+    pub capture_images: Arc<AtomicBool>,
+    /// Largest copied image the clipboard monitor saves (from settings)
+    pub max_image_size_bytes: Arc<AtomicU64>,
+    /// Whether the clipboard monitor saves the primary selection (from
+    /// settings, Linux only)
+    pub capture_primary_selection: Arc<AtomicBool>,
 }
 
 /// Default max upload size: 10MB
@@ -24,19 +49,33 @@ impl AppState {
     /// Create a new AppState with token and trusted certificates
     pub fn new_with_trusted_certs(
         base_url: &str,
-        token: Option<String>,
+        token: Option<SecretString>,
         trusted_fingerprints: HashMap<String, String>,
+        response_cache: Arc<ResponseCache>,
     ) -> Self {
-        let client =
-            ClipperClient::new_with_trusted_certs(base_url, token, trusted_fingerprints.clone());
+        let client = ClipperClient::new_with_trusted_certs(
+            base_url,
+            token.map(SecretString::into_string),
+            trusted_fingerprints.clone(),
+        )
+        .with_cache(response_cache.clone());
         Self {
             client: RwLock::new(client),
             last_synced_content: Arc::new(Mutex::new(String::new())),
             last_synced_image: Arc::new(Mutex::new(Vec::new())),
             websocket_connected: Arc::new(AtomicBool::new(false)),
-            ws_reconnect_counter: Arc::new(AtomicU64::new(0)),
+            connection_generation: watch::Sender::new(0),
             max_upload_size_bytes: Arc::new(AtomicU64::new(DEFAULT_MAX_UPLOAD_SIZE_BYTES)),
             trusted_fingerprints: RwLock::new(trusted_fingerprints),
+            response_cache,
+            paste_stack: Arc::new(PasteStack::default()),
+            append_mode: Arc::new(AppendMode::default()),
+            capturing_screenshot: Arc::new(AtomicBool::new(false)),
+            capture_pause: Arc::new(CapturePause::default()),
+            local_copy: Arc::new(LocalCopy::default()),
+            capture_images: Arc::new(AtomicBool::new(true)),
+            max_image_size_bytes: Arc::new(AtomicU64::new(DEFAULT_MAX_UPLOAD_SIZE_BYTES)),
+            capture_primary_selection: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -49,24 +88,35 @@ impl AppState {
     }
 
     /// Get the current token (if any)
-    pub fn token(&self) -> Option<String> {
-        self.client.read().unwrap().token().map(|s| s.to_string())
+    pub fn token(&self) -> Option<SecretString> {
+        self.client.read().unwrap().token().map(SecretString::from)
     }
 
-    /// Update the server URL (called when bundled server starts)
-    #[allow(dead_code)]
-    pub fn set_server_url(&self, url: &str) {
-        *self.client.write().unwrap() = ClipperClient::new(url);
+    /// The current client together with its connection generation
+    pub fn connection(&self) -> (ClipperClient, u64) {
+        let client = self.client.read().unwrap();
+        (client.clone(), *self.connection_generation.borrow())
     }
 
-    /// Update the server URL with optional token
-    /// This also signals the WebSocket to reconnect
-    pub fn set_server_url_with_token(&self, url: &str, token: Option<String>) {
-        // Get current trusted fingerprints
+    /// Switch to another server, or reconnect to the same one with a new token
+    ///
+    /// The client is replaced, the state learned from the old server is reset
+    /// and the WebSocket is signalled to reconnect in one step, so no request
+    /// sees the new client with the old server's state.
+    pub fn switch_server(&self, url: &str, token: Option<SecretString>) {
         let fingerprints = self.trusted_fingerprints.read().unwrap().clone();
-        let client = ClipperClient::new_with_trusted_certs(url, token, fingerprints);
-        *self.client.write().unwrap() = client;
-        // Signal WebSocket to reconnect with new credentials
+        let new_client = ClipperClient::new_with_trusted_certs(
+            url,
+            token.map(SecretString::into_string),
+            fingerprints,
+        )
+        .with_cache(self.response_cache.clone());
+
+        let mut client = self.client.write().unwrap();
+        *client = new_client;
+        self.websocket_connected.store(false, Ordering::SeqCst);
+        self.max_upload_size_bytes
+            .store(DEFAULT_MAX_UPLOAD_SIZE_BYTES, Ordering::SeqCst);
         self.signal_ws_reconnect();
     }
 
@@ -83,14 +133,21 @@ impl AppState {
         client.set_trusted_fingerprints(fingerprints);
     }
 
-    /// Signal the WebSocket listener to reconnect (e.g., after token change)
+    /// Signal the WebSocket listener to reconnect (e.g., after a certificate
+    /// was trusted)
     pub fn signal_ws_reconnect(&self) {
-        self.ws_reconnect_counter.fetch_add(1, Ordering::SeqCst);
+        self.connection_generation
+            .send_modify(|generation| *generation += 1);
+    }
+
+    /// Get the current connection generation
+    pub fn connection_generation(&self) -> u64 {
+        *self.connection_generation.borrow()
     }
 
-    /// Get the current reconnect counter value
-    pub fn ws_reconnect_counter(&self) -> u64 {
-        self.ws_reconnect_counter.load(Ordering::SeqCst)
+    /// Receiver that is notified whenever the connection generation changes
+    pub fn watch_connection(&self) -> watch::Receiver<u64> {
+        self.connection_generation.subscribe()
     }
 
     pub fn set_last_synced_content(&self, content: String) {
@@ -123,4 +180,14 @@ impl AppState {
     pub fn max_upload_size_arc(&self) -> Arc<AtomicU64> {
         Arc::clone(&self.max_upload_size_bytes)
     }
+
+    /// Apply the capture settings to the clipboard monitor
+    pub fn apply_capture_settings(&self, settings: &Settings) {
+        self.capture_images
+            .store(settings.capture_images, Ordering::SeqCst);
+        self.max_image_size_bytes
+            .store(settings.max_image_size_mb * 1024 * 1024, Ordering::SeqCst);
+        self.capture_primary_selection
+            .store(settings.capture_primary_selection, Ordering::SeqCst);
+    }
 }
@@ -1,60 +1,241 @@
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 use tauri::{
-    AppHandle, Emitter, Manager, include_image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    AppHandle, Emitter, Manager, Wry,
+    image::Image,
+    include_image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
 };
 
+use crate::clipboard::CapturePauseDuration;
+use crate::settings::{Settings, SettingsManager};
+use crate::state::AppState;
 use crate::tray_i18n::{Language, t};
+use crate::window_placement;
 
-pub fn setup_tray(app: &AppHandle, language: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let lang = Language::from_str(language);
+/// Prefix of the menu item IDs of user-defined actions
+const ACTION_ID_PREFIX: &str = "action:";
+
+/// Prefix of the menu item IDs of server profiles
+const PROFILE_ID_PREFIX: &str = "profile:";
 
-    let show_hide_item = MenuItem::with_id(
+/// The tray icon, dimmed while capture is paused
+fn tray_icon(paused: bool) -> Image<'static> {
+    let icon = include_image!("icons/tray-icon.png");
+    if !paused {
+        return icon;
+    }
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3] / 3])
+        .collect();
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+/// Submenu for picking the bundled server or a server profile, with the one
+/// in use checked
+fn build_server_menu(
+    app: &AppHandle,
+    lang: Language,
+    settings: &Settings,
+) -> tauri::Result<Submenu<Wry>> {
+    let menu = Submenu::new(app, t(lang, "tray.server"), true)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "server_bundled",
+        t(lang, "tray.bundledServer"),
+        true,
+        settings.use_bundled_server,
+        None::<&str>,
+    )?)?;
+    for profile in &settings.server_profiles {
+        let in_use = !settings.use_bundled_server && settings.server_address == profile.url;
+        menu.append(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", PROFILE_ID_PREFIX, profile.name),
+            &profile.name,
+            true,
+            in_use,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(menu)
+}
+
+/// Build the tray menu, with the capture pause, paste stack toggle,
+/// screenshot capture and the actions marked `showInTray` after Settings,
+/// and a server picker once there are server profiles
+fn build_menu(app: &AppHandle, lang: Language) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
         app,
         "show_hide",
         t(lang, "tray.showHide"),
         true,
         None::<&str>,
-    )?;
-    let settings_item = MenuItem::with_id(
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "move_window_here",
+        t(lang, "tray.moveWindowHere"),
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
         app,
         "settings",
         t(lang, "tray.settings"),
         true,
         None::<&str>,
-    )?;
-    let separator1 = PredefinedMenuItem::separator(app)?;
-    let check_updates_item = MenuItem::with_id(
+    )?)?;
+    let settings = app.state::<SettingsManager>().get();
+    if !settings.server_profiles.is_empty() {
+        menu.append(&build_server_menu(app, lang, &settings)?)?;
+    }
+
+    // Resume while paused (with the time capture resumes by itself),
+    // otherwise a submenu of pause durations
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    let capture_pause = app.state::<AppState>().capture_pause.status();
+    if capture_pause.paused {
+        let label = match capture_pause.until {
+            Some(until) => format!(
+                "{} ({} {})",
+                t(lang, "tray.resumeCapture"),
+                t(lang, "tray.pausedUntil"),
+                until.with_timezone(&chrono::Local).format("%H:%M")
+            ),
+            None => t(lang, "tray.resumeCapture").to_string(),
+        };
+        menu.append(&MenuItem::with_id(
+            app,
+            "resume_capture",
+            label,
+            true,
+            None::<&str>,
+        )?)?;
+    } else {
+        menu.append(&Submenu::with_items(
+            app,
+            t(lang, "tray.pauseCapture"),
+            true,
+            &[
+                &MenuItem::with_id(
+                    app,
+                    "pause_fifteen_minutes",
+                    t(lang, "tray.pauseFifteenMinutes"),
+                    true,
+                    None::<&str>,
+                )?,
+                &MenuItem::with_id(
+                    app,
+                    "pause_one_hour",
+                    t(lang, "tray.pauseOneHour"),
+                    true,
+                    None::<&str>,
+                )?,
+                &MenuItem::with_id(
+                    app,
+                    "pause_until_resumed",
+                    t(lang, "tray.pauseUntilResumed"),
+                    true,
+                    None::<&str>,
+                )?,
+            ],
+        )?)?;
+    }
+
+    // Checked while stack mode is on, with the number of queued items
+    let paste_stack = app.state::<AppState>().paste_stack.status();
+    let paste_stack_label = if paste_stack.enabled {
+        format!("{} ({})", t(lang, "tray.pasteStack"), paste_stack.count)
+    } else {
+        t(lang, "tray.pasteStack").to_string()
+    };
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "paste_stack",
+        paste_stack_label,
+        true,
+        paste_stack.enabled,
+        None::<&str>,
+    )?)?;
+    menu.append(&CheckMenuItem::with_id(
+        app,
+        "append_mode",
+        t(lang, "tray.appendMode"),
+        true,
+        app.state::<AppState>().append_mode.is_enabled(),
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "capture_screenshot",
+        t(lang, "tray.captureScreenshot"),
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quick_note",
+        t(lang, "tray.quickNote"),
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "palette",
+        t(lang, "tray.palette"),
+        true,
+        None::<&str>,
+    )?)?;
+
+    let actions = app.state::<SettingsManager>().get_actions();
+    for action in actions.iter().filter(|a| a.show_in_tray) {
+        let id = format!("{}{}", ACTION_ID_PREFIX, action.name);
+        menu.append(&MenuItem::with_id(
+            app,
+            id,
+            &action.name,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
         app,
         "check_updates",
         t(lang, "tray.checkUpdates"),
         true,
         None::<&str>,
-    )?;
-    let about_item = MenuItem::with_id(app, "about", t(lang, "tray.about"), true, None::<&str>)?;
-    let separator2 = PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, "quit", t(lang, "tray.quit"), true, None::<&str>)?;
-
-    let menu = Menu::with_items(
+    )?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "about",
+        t(lang, "tray.about"),
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
         app,
-        &[
-            &show_hide_item,
-            &settings_item,
-            &separator1,
-            &check_updates_item,
-            &about_item,
-            &separator2,
-            &quit_item,
-        ],
-    )?;
+        "quit",
+        t(lang, "tray.quit"),
+        true,
+        None::<&str>,
+    )?)?;
+    Ok(menu)
+}
 
-    // Use the tray icon embedded at compile time via include_image! macro
-    let tray_icon = include_image!("icons/tray-icon.png");
+pub fn setup_tray(app: &AppHandle, language: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app, Language::from_str(language))?;
 
-    let _tray = TrayIconBuilder::new()
-        .icon(tray_icon)
+    // Use the tray icon embedded at compile time via include_image! macro
+    let _tray = TrayIconBuilder::with_id("main")
+        .icon(tray_icon(false))
         .icon_as_template(true)
         .menu(&menu)
         .show_menu_on_left_click(true)
@@ -68,6 +249,19 @@ pub fn setup_tray(app: &AppHandle, language: &str) -> Result<(), Box<dyn std::er
                     let _ = window.set_focus();
                 }
             }
+            "move_window_here" => {
+                // The cursor is on the tray icon, so this is the monitor the
+                // user is looking at
+                if let Some(window) = app.get_webview_window("main") {
+                    if let Err(e) = window_placement::center_on_cursor_monitor(&window) {
+                        log::warn!("Failed to move window to the cursor's monitor: {}", e);
+                    }
+                    #[cfg(target_os = "macos")]
+                    let _ = app.set_activation_policy(ActivationPolicy::Regular);
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
             "settings" => {
                 // Show the window first if hidden
                 if let Some(window) = app.get_webview_window("main")
@@ -101,10 +295,74 @@ pub fn setup_tray(app: &AppHandle, language: &str) -> Result<(), Box<dyn std::er
                 // Emit event to open settings dialog on About tab and check for updates
                 let _ = app.emit("check-for-updates", ());
             }
+            "pause_fifteen_minutes" => {
+                crate::clipboard::set_capture_paused(
+                    app,
+                    Some(CapturePauseDuration::FifteenMinutes),
+                );
+            }
+            "pause_one_hour" => {
+                crate::clipboard::set_capture_paused(app, Some(CapturePauseDuration::OneHour));
+            }
+            "pause_until_resumed" => {
+                crate::clipboard::set_capture_paused(app, Some(CapturePauseDuration::UntilResumed));
+            }
+            "resume_capture" => {
+                crate::clipboard::set_capture_paused(app, None);
+            }
+            "paste_stack" => {
+                crate::clipboard::toggle_paste_stack(app);
+            }
+            "append_mode" => {
+                crate::clipboard::toggle_append_mode(app);
+            }
+            "capture_screenshot" => {
+                crate::screenshot::spawn_capture(app);
+            }
+            "quick_note" => {
+                crate::quick_note::toggle(app);
+            }
+            "palette" => {
+                crate::palette::toggle(app);
+            }
+            "server_bundled" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let result = crate::commands::switch_to_bundled_server(
+                        app.clone(),
+                        app.state(),
+                        app.state(),
+                        app.state(),
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("Failed to switch to the bundled server: {}", e);
+                    }
+                    // Restore the check marks if the switch failed
+                    let _ = refresh_tray(&app);
+                });
+            }
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some(name) = id.strip_prefix(ACTION_ID_PREFIX) {
+                    let app = app.clone();
+                    let name = name.to_string();
+                    tauri::async_runtime::spawn(crate::actions::run_on_latest_clip(app, name));
+                } else if let Some(name) = id.strip_prefix(PROFILE_ID_PREFIX) {
+                    let app = app.clone();
+                    let name = name.to_string();
+                    tauri::async_runtime::spawn(async move {
+                        match crate::commands::switch_to_server_profile(app.clone(), name).await {
+                            Ok(Some(e)) => log::warn!("Server profile is not reachable: {}", e),
+                            Ok(None) => {}
+                            Err(e) => log::error!("Failed to switch server profile: {}", e),
+                        }
+                        let _ = refresh_tray(&app);
+                    });
+                }
+            }
         })
         .on_tray_icon_event(|tray, event| {
             if let tauri::tray::TrayIconEvent::DoubleClick {
@@ -134,59 +392,35 @@ pub fn setup_tray(app: &AppHandle, language: &str) -> Result<(), Box<dyn std::er
     Ok(())
 }
 
-/// Update the tray menu language
+/// Update the tray menu language, the icon for the capture pause, and the
+/// tooltip for the capture pause or append mode
 pub fn update_tray_language(
     app: &AppHandle,
     language: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let lang = Language::from_str(language);
-
-    // Get all tray icons and update the first one (there should only be one)
-    let trays = app.tray_by_id("main");
-    if let Some(tray) = trays {
-        // Create new menu items with updated language
-        let show_hide_item = MenuItem::with_id(
-            app,
-            "show_hide",
-            t(lang, "tray.showHide"),
-            true,
-            None::<&str>,
-        )?;
-        let settings_item = MenuItem::with_id(
-            app,
-            "settings",
-            t(lang, "tray.settings"),
-            true,
-            None::<&str>,
-        )?;
-        let separator1 = PredefinedMenuItem::separator(app)?;
-        let check_updates_item = MenuItem::with_id(
-            app,
-            "check_updates",
-            t(lang, "tray.checkUpdates"),
-            true,
-            None::<&str>,
-        )?;
-        let about_item =
-            MenuItem::with_id(app, "about", t(lang, "tray.about"), true, None::<&str>)?;
-        let separator2 = PredefinedMenuItem::separator(app)?;
-        let quit_item = MenuItem::with_id(app, "quit", t(lang, "tray.quit"), true, None::<&str>)?;
+    if let Some(tray) = app.tray_by_id("main") {
+        let lang = Language::from_str(language);
+        tray.set_menu(Some(build_menu(app, lang)?))?;
 
-        let menu = Menu::with_items(
-            app,
-            &[
-                &show_hide_item,
-                &settings_item,
-                &separator1,
-                &check_updates_item,
-                &about_item,
-                &separator2,
-                &quit_item,
-            ],
-        )?;
-
-        tray.set_menu(Some(menu))?;
+        let state = app.state::<AppState>();
+        let paused = state.capture_pause.is_paused();
+        tray.set_icon(Some(tray_icon(paused)))?;
+        tray.set_icon_as_template(true)?;
+        let tooltip = if paused {
+            Some(t(lang, "tray.capturePaused"))
+        } else if state.append_mode.is_enabled() {
+            Some(t(lang, "tray.appending"))
+        } else {
+            None
+        };
+        tray.set_tooltip(tooltip)?;
     }
 
     Ok(())
 }
+
+/// Rebuild the tray menu in the language from settings
+pub fn refresh_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let language = app.state::<SettingsManager>().get().language;
+    update_tray_language(app, language.as_deref().unwrap_or("en"))
+}
@@ -23,14 +23,46 @@ pub fn get_translations(lang: Language) -> HashMap<&'static str, &'static str> {
     match lang {
         Language::En => {
             translations.insert("tray.showHide", "Open Clipper");
+            translations.insert("tray.moveWindowHere", "Move Window to This Screen");
             translations.insert("tray.settings", "Settings...");
+            translations.insert("tray.pasteStack", "Paste Stack");
+            translations.insert("tray.appendMode", "Append Copies to One Clip");
+            translations.insert("tray.pauseCapture", "Pause Capture");
+            translations.insert("tray.pauseFifteenMinutes", "For 15 Minutes");
+            translations.insert("tray.pauseOneHour", "For 1 Hour");
+            translations.insert("tray.pauseUntilResumed", "Until Resumed");
+            translations.insert("tray.resumeCapture", "Resume Capture");
+            translations.insert("tray.pausedUntil", "paused until");
+            translations.insert("tray.capturePaused", "Clipper (capture paused)");
+            translations.insert("tray.appending", "Clipper (appending copies)");
+            translations.insert("tray.captureScreenshot", "Capture Screenshot");
+            translations.insert("tray.quickNote", "Quick Note...");
+            translations.insert("tray.palette", "Search Clips...");
+            translations.insert("tray.server", "Server");
+            translations.insert("tray.bundledServer", "Bundled Server");
             translations.insert("tray.about", "About Clipper");
             translations.insert("tray.checkUpdates", "Check for Updates...");
             translations.insert("tray.quit", "Quit Application");
         }
         Language::Zh => {
             translations.insert("tray.showHide", "打开 Clipper");
+            translations.insert("tray.moveWindowHere", "将窗口移到此屏幕");
             translations.insert("tray.settings", "设置...");
+            translations.insert("tray.pasteStack", "粘贴栈");
+            translations.insert("tray.appendMode", "追加复制到同一剪贴");
+            translations.insert("tray.pauseCapture", "暂停记录");
+            translations.insert("tray.pauseFifteenMinutes", "15 分钟");
+            translations.insert("tray.pauseOneHour", "1 小时");
+            translations.insert("tray.pauseUntilResumed", "直到恢复");
+            translations.insert("tray.resumeCapture", "恢复记录");
+            translations.insert("tray.pausedUntil", "暂停至");
+            translations.insert("tray.capturePaused", "Clipper（已暂停记录）");
+            translations.insert("tray.appending", "Clipper（正在追加复制）");
+            translations.insert("tray.captureScreenshot", "截图");
+            translations.insert("tray.quickNote", "快速笔记...");
+            translations.insert("tray.palette", "搜索剪贴...");
+            translations.insert("tray.server", "服务器");
+            translations.insert("tray.bundledServer", "内置服务器");
             translations.insert("tray.about", "关于 Clipper");
             translations.insert("tray.checkUpdates", "检查更新...");
             translations.insert("tray.quit", "退出应用");
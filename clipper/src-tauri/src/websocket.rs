@@ -1,10 +1,13 @@
 use crate::clipboard::{set_clipboard_content, set_clipboard_image};
-use crate::settings::SettingsManager;
+use crate::p2p::P2pManager;
+use crate::settings::{SettingsManager, SyncConflictPolicy};
 use crate::state::AppState;
-use clipper_client::{fetch_server_certificate, ClipNotification};
+use clipper_client::{
+    fetch_server_certificate, ClientError, ClipNotification, ClipperClient, SyncedClip,
+};
 use gethostname::gethostname;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 /// Get the hostname tag in the format `$host:<hostname>`
 fn get_hostname_tag() -> String {
@@ -12,6 +15,44 @@ fn get_hostname_tag() -> String {
     format!("$host:{}", hostname)
 }
 
+/// Tags the WebSocket subscribes to: those this device syncs plus its own
+/// host tag, so clips copied here still come back. Empty to get every clip.
+fn subscription_tags(sync_tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = sync_tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if !tags.is_empty() {
+        tags.push(get_hostname_tag());
+    }
+    tags
+}
+
+/// Download an image clip and put it on the clipboard, in the background
+fn set_clipboard_image_clip(app: &AppHandle, client: &ClipperClient, clip_id: &str) {
+    let client = client.clone();
+    let clip_id = clip_id.to_string();
+    let app = app.clone();
+    tokio::spawn(async move {
+        match client.download_file(&clip_id).await {
+            Ok(image_bytes) => {
+                // Set last synced image BEFORE setting clipboard
+                // to prevent the clipboard monitor from uploading it again
+                let state = app.state::<AppState>();
+                state.set_last_synced_image(image_bytes.clone());
+
+                if let Err(e) = set_clipboard_image(&image_bytes) {
+                    log::warn!("Failed to set clipboard image: {}", e);
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to download image for clipboard: {}", e);
+            }
+        }
+    });
+}
+
 /// Emit WebSocket connection status to frontend
 fn emit_ws_status(app: &AppHandle, connected: bool) {
     let state = app.state::<AppState>();
@@ -22,18 +63,6 @@ fn emit_ws_status(app: &AppHandle, connected: bool) {
     );
 }
 
-/// Check if the error message indicates a certificate verification failure
-fn is_certificate_error(error_msg: &str) -> bool {
-    let lower = error_msg.to_lowercase();
-    lower.contains("certificate")
-        || lower.contains("ca used as end entity")
-        || lower.contains("webpki")
-        || lower.contains("invalid peer certificate")
-        || lower.contains("self signed")
-        || lower.contains("selfcertverify")
-        || lower.contains("unknownissuer")
-}
-
 /// Extract host and port from URL
 fn parse_url(url: &str) -> Option<(String, u16)> {
     // Handle URLs that might not have a scheme
@@ -112,17 +141,32 @@ async fn check_and_emit_certificate_trust(app: &AppHandle, url: &str) -> bool {
     false
 }
 
+/// Wait before retrying, cut short when the server connection changes
+async fn wait_to_retry(connection: &mut watch::Receiver<u64>, seconds: u64) {
+    let _ = tokio::time::timeout(
+        tokio::time::Duration::from_secs(seconds),
+        connection.changed(),
+    )
+    .await;
+}
+
 pub async fn start_websocket_listener(app: AppHandle) {
     let state = app.state::<AppState>();
+    let mut connection = state.watch_connection();
     let mut reconnect_delay = 1u64; // Start with 1 second delay
 
     loop {
-        let client = state.client().clone();
+        // Take the client and its generation together, so a server switch
+        // is noticed even if it happens while connecting
+        connection.borrow_and_update();
+        let (mut client, generation_at_connect) = state.connection();
+        client.set_sync_tags(subscription_tags(
+            &app.state::<SettingsManager>().get().sync_tags,
+        ));
+        // Named after the host, so clips can be pushed to this clipboard
+        client.set_device_name(Some(gethostname().to_string_lossy().to_string()));
         let (tx, mut rx) = mpsc::unbounded_channel::<ClipNotification>();
 
-        // Remember the current reconnect counter to detect changes
-        let reconnect_counter_at_connect = state.ws_reconnect_counter();
-
         match client.subscribe_notifications(tx).await {
             Ok(handle) => {
                 // Connected successfully
@@ -131,8 +175,8 @@ pub async fn start_websocket_listener(app: AppHandle) {
                 log::info!("WebSocket connected");
 
                 loop {
-                    // Check if we should reconnect (e.g., token changed)
-                    if state.ws_reconnect_counter() != reconnect_counter_at_connect {
+                    // Check if we should reconnect (e.g., server or token changed)
+                    if state.connection_generation() != generation_at_connect {
                         log::debug!("WebSocket: reconnect signal received, disconnecting...");
                         handle.abort();
                         break;
@@ -148,54 +192,70 @@ pub async fn start_websocket_listener(app: AppHandle) {
                     match recv_result {
                         Ok(Some(notification)) => {
                             match &notification {
-                                ClipNotification::NewClip { id, content, tags } => {
+                                ClipNotification::NewClip {
+                                    id, content, tags, ..
+                                } => {
                                     // Check if this clip originated from this machine
                                     let my_hostname_tag = get_hostname_tag();
                                     let is_from_this_machine =
                                         tags.iter().any(|t| t == &my_hostname_tag);
 
+                                    // Servers without tag filtering send every clip
+                                    if !client.matches_sync_tags(tags) {
+                                        log::debug!("Clip {} has none of the synced tags", id);
+                                        continue;
+                                    }
+
                                     // Check if this is an image clip
                                     let is_image_clip = tags.iter().any(|t| t == "$image");
 
+                                    // Whether text copied here at about the same time
+                                    // stays on the clipboard instead of this clip
+                                    let conflict_policy =
+                                        app.state::<SettingsManager>().get().sync_conflict_policy;
+                                    let kept_local = if is_from_this_machine || is_image_clip {
+                                        None
+                                    } else {
+                                        state.local_copy.resolve_remote(content, conflict_policy)
+                                    };
+
                                     if is_image_clip {
                                         // For image clips from OTHER machines, download and set to clipboard
                                         if !is_from_this_machine {
-                                            let client = state.client().clone();
-                                            let clip_id = id.clone();
-                                            let app_for_image = app.clone();
-                                            // Download image in background and set to clipboard
-                                            tokio::spawn(async move {
-                                                match client.download_file(&clip_id).await {
-                                                    Ok(image_bytes) => {
-                                                        // Set last synced image BEFORE setting clipboard
-                                                        // to prevent the clipboard monitor from uploading it again
-                                                        let state =
-                                                            app_for_image.state::<AppState>();
-                                                        state.set_last_synced_image(
-                                                            image_bytes.clone(),
-                                                        );
-
-                                                        if let Err(e) =
-                                                            set_clipboard_image(&image_bytes)
-                                                        {
-                                                            log::warn!(
-                                                                "Failed to set clipboard image: {}",
-                                                                e
-                                                            );
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        log::warn!(
-                                                            "Failed to download image for clipboard: {}",
-                                                            e
-                                                        );
-                                                    }
-                                                }
-                                            });
+                                            set_clipboard_image_clip(&app, &client, id);
                                         }
                                         // For image clips from THIS machine, don't touch clipboard
                                         // (the image is already there)
+                                    } else if is_from_this_machine
+                                        && tags.iter().any(|t| t == "$file" || t == "$filelist")
+                                    {
+                                        // Files copied on this machine are still on the
+                                        // clipboard; don't replace them with their paths
+                                    } else if let Some(local) = kept_local {
+                                        // Copied elsewhere at the same time as something
+                                        // here, which stays on the clipboard
+                                        log::debug!("Clip {} conflicts with a copy made here", id);
+                                        if conflict_policy != SyncConflictPolicy::NewestWins {
+                                            let device = tags
+                                                .iter()
+                                                .find_map(|tag| tag.strip_prefix("$host:"))
+                                                .unwrap_or_default();
+                                            let _ = app.emit(
+                                                "sync-conflict",
+                                                serde_json::json!({
+                                                    "id": id,
+                                                    "content": content,
+                                                    "device": device,
+                                                    "localContent": local,
+                                                    "policy": conflict_policy,
+                                                }),
+                                            );
+                                        }
                                     } else {
+                                        if is_from_this_machine {
+                                            state.local_copy.mark_stored(content);
+                                        }
+
                                         // For text clips, update system clipboard
                                         if let Err(e) = set_clipboard_content(content) {
                                             log::warn!("Failed to set clipboard: {}", e);
@@ -203,6 +263,19 @@ pub async fn start_websocket_listener(app: AppHandle) {
                                             // Update last synced content to prevent loop
                                             state.set_last_synced_content(content.clone());
                                         }
+
+                                        // Share clips copied here with P2P peers. Clips
+                                        // received from peers carry the peer's host tag,
+                                        // so they aren't sent back out.
+                                        if is_from_this_machine {
+                                            let clip = SyncedClip {
+                                                content: content.clone(),
+                                                tags: tags.clone(),
+                                                additional_notes: None,
+                                                language: None,
+                                            };
+                                            app.state::<P2pManager>().broadcast(clip).await;
+                                        }
                                     }
 
                                     // Emit event to frontend
@@ -215,7 +288,7 @@ pub async fn start_websocket_listener(app: AppHandle) {
                                         }),
                                     );
                                 }
-                                ClipNotification::UpdatedClip { id } => {
+                                ClipNotification::UpdatedClip { id, .. } => {
                                     let _ = app.emit("clip-updated", serde_json::json!({ "id": id }));
                                 }
                                 ClipNotification::DeletedClip { id } => {
@@ -230,6 +303,19 @@ pub async fn start_websocket_listener(app: AppHandle) {
                                         }),
                                     );
                                 }
+                                ClipNotification::SetClipboard { id, content, tags } => {
+                                    // Pushed to this device from another one
+                                    log::info!("Clip {} pushed to the clipboard", id);
+                                    if tags.iter().any(|t| t == "$image") {
+                                        set_clipboard_image_clip(&app, &client, id);
+                                    } else if let Err(e) = set_clipboard_content(content) {
+                                        log::warn!("Failed to set clipboard: {}", e);
+                                    } else {
+                                        state.set_last_synced_content(content.clone());
+                                    }
+                                    let _ = app
+                                        .emit("clipboard-pushed", serde_json::json!({ "id": id }));
+                                }
                             }
                         }
                         Ok(None) => {
@@ -252,18 +338,19 @@ pub async fn start_websocket_listener(app: AppHandle) {
             }
             Err(e) => {
                 emit_ws_status(&app, false);
-                let error_msg = e.to_string();
-                log::warn!("Cannot connect to server: {}", error_msg);
+                log::warn!("Cannot connect to server: {}", e);
 
-                // Check if this is a certificate error
-                if is_certificate_error(&error_msg) {
-                    let base_url = state.base_url();
+                // Check if this is a certificate error, unless the app has
+                // moved on to another server meanwhile
+                if matches!(e, ClientError::CertificateUntrusted { .. })
+                    && state.connection_generation() == generation_at_connect
+                {
                     // Check and emit certificate trust event
-                    if check_and_emit_certificate_trust(&app, &base_url).await {
+                    if check_and_emit_certificate_trust(&app, client.base_url()).await {
                         // Certificate trust is required, wait longer before retrying
                         // to give user time to trust the certificate
                         log::debug!("Waiting for certificate trust before retrying...");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        wait_to_retry(&mut connection, 5).await;
                         continue;
                     }
                 }
@@ -271,7 +358,7 @@ pub async fn start_websocket_listener(app: AppHandle) {
         }
 
         // If reconnect was signaled, reconnect immediately without delay
-        if state.ws_reconnect_counter() != reconnect_counter_at_connect {
+        if state.connection_generation() != generation_at_connect {
             log::debug!("Reconnecting to WebSocket immediately (credentials changed)...");
             reconnect_delay = 1;
             continue;
@@ -282,7 +369,12 @@ pub async fn start_websocket_listener(app: AppHandle) {
             "Reconnecting to WebSocket in {} seconds...",
             reconnect_delay
         );
-        tokio::time::sleep(tokio::time::Duration::from_secs(reconnect_delay)).await;
-        reconnect_delay = (reconnect_delay * 2).min(30);
+        wait_to_retry(&mut connection, reconnect_delay).await;
+        reconnect_delay = if state.connection_generation() != generation_at_connect {
+            // Switched servers while waiting; start over with a short delay
+            1
+        } else {
+            (reconnect_delay * 2).min(30)
+        };
     }
 }
@@ -0,0 +1,71 @@
+//! Main window placement across monitors
+//!
+//! The saved window position is only restored while it's still on a
+//! connected monitor, so unplugging a display doesn't leave the window
+//! off-screen. With `window_follows_cursor` on, the global shortcut shows the
+//! window centered on the monitor the mouse cursor is on instead.
+
+use tauri::{LogicalPosition, LogicalSize, Monitor, PhysicalPosition, WebviewWindow};
+
+use crate::settings::MainWindowGeometry;
+
+/// How much of the window's top-left corner, in logical pixels, must be on a
+/// monitor for a saved position to be restored; enough to grab the title bar
+const MIN_VISIBLE: i32 = 40;
+
+/// Restore the main window's saved size, position and maximized state
+pub fn restore_geometry(window: &WebviewWindow, geometry: &MainWindowGeometry) {
+    // Restore size if saved
+    if let (Some(width), Some(height)) = (geometry.width, geometry.height) {
+        let _ = window.set_size(LogicalSize::new(width as f64, height as f64));
+    }
+
+    // Restore position if saved and still on a connected monitor
+    if let (Some(x), Some(y)) = (geometry.x, geometry.y) {
+        let monitors = window.available_monitors().unwrap_or_default();
+        if monitors.iter().any(|m| contains_logical(m, x, y)) {
+            let _ = window.set_position(LogicalPosition::new(x as f64, y as f64));
+        } else {
+            log::info!("Saved window position is not on any monitor, centering the window");
+            let _ = window.center();
+        }
+    }
+
+    // Restore maximized state if saved
+    if let Some(true) = geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Center the window on the monitor the mouse cursor is on, falling back to
+/// the window's current monitor. Maximized windows are left where they are.
+pub fn center_on_cursor_monitor(window: &WebviewWindow) -> tauri::Result<()> {
+    if window.is_maximized().unwrap_or(false) {
+        return Ok(());
+    }
+    let cursor = window.cursor_position()?;
+    let Some(monitor) = window.monitor_from_point(cursor.x, cursor.y)? else {
+        return window.center();
+    };
+
+    // The window's physical size changes with the monitor's scale factor
+    let size = window
+        .outer_size()?
+        .to_logical::<f64>(window.scale_factor()?)
+        .to_physical::<f64>(monitor.scale_factor());
+    let area = monitor.work_area();
+    let x = area.position.x as f64 + (area.size.width as f64 - size.width).max(0.0) / 2.0;
+    let y = area.position.y as f64 + (area.size.height as f64 - size.height).max(0.0) / 2.0;
+    window.set_position(PhysicalPosition::new(x as i32, y as i32))
+}
+
+/// Whether the top-left corner of a window at logical `(x, y)` is on `monitor`
+fn contains_logical(monitor: &Monitor, x: i32, y: i32) -> bool {
+    let position = monitor.position().to_logical::<i32>(monitor.scale_factor());
+    let size = monitor.size().to_logical::<i32>(monitor.scale_factor());
+    let (x, y) = (x + MIN_VISIBLE, y + MIN_VISIBLE);
+    x >= position.x
+        && x < position.x + size.width
+        && y >= position.y
+        && y < position.y + size.height
+}
@@ -17,6 +17,7 @@ import {
   FavoriteToggle,
   FavoriteToggleHandle,
   ClipList,
+  Clip,
   Tag,
 } from "@unwritten-codes/clipper-ui";
 import { TitleBar } from "./components/TitleBar";
@@ -24,6 +25,7 @@ import { DropZone } from "./components/DropZone";
 import { SettingsDialog, useSettingsDialog } from "./components/SettingsDialog";
 import { CertificateConfirmDialog, CertificateInfo } from "./components/CertificateConfirmDialog";
 import { CertificateMismatchDialog, CertificateMismatchInfo } from "./components/CertificateMismatchDialog";
+import { SyncConflict, SyncConflictDialog } from "./components/SyncConflictDialog";
 import "./App.css";
 
 // Detect platform from user agent
@@ -34,11 +36,18 @@ function detectPlatform(): "macos" | "windows" | "linux" {
   return "linux";
 }
 
+// Payload of the server-changed event
+type ServerChangedPayload = { url: string; bundled: boolean };
+
+// A clipper:// link handed over by deep_link.rs
+type DeepLink = { kind: "clip"; id: string } | { kind: "search"; query: string };
+
 function App() {
   const { t } = useI18n();
   const [os] = useState(() => detectPlatform());
   const [isMaximized, setIsMaximized] = useState(false);
   const [wsConnected, setWsConnected] = useState(false);
+  const [appendMode, setAppendMode] = useState(false);
   const [useBundledServer, setUseBundledServer] = useState(true);
   // Certificate trust dialog state
   const [certificateDialogOpen, setCertificateDialogOpen] = useState(false);
@@ -48,6 +57,10 @@ function App() {
   const [mismatchDialogOpen, setMismatchDialogOpen] = useState(false);
   const [pendingMismatch, setPendingMismatch] = useState<CertificateMismatchInfo | null>(null);
   const [mismatchAccepting, setMismatchAccepting] = useState(false);
+  // Clip from another device waiting for the user to pick it or the local copy
+  const [syncConflict, setSyncConflict] = useState<SyncConflict | null>(null);
+  // Clip opened by a clipper://clip/<id> link, shown on its own until dismissed
+  const [linkedClip, setLinkedClip] = useState<Clip | null>(null);
   const {
     clips,
     loading,
@@ -158,14 +171,14 @@ function App() {
     invoke<boolean>("is_bundled_server").then(setUseBundledServer).catch(() => { });
   }, []);
 
-  // Listen for server switch events to update bundled server state
+  // Listen for server changes to update bundled server state
   useEffect(() => {
-    const unlistenServerSwitched = listen("server-switched", () => {
-      invoke<boolean>("is_bundled_server").then(setUseBundledServer).catch(() => { });
+    const unlistenServerChanged = listen<ServerChangedPayload>("server-changed", (event) => {
+      setUseBundledServer(event.payload.bundled);
     });
 
     return () => {
-      unlistenServerSwitched.then((fn) => fn());
+      unlistenServerChanged.then((fn) => fn());
     };
   }, []);
 
@@ -190,6 +203,59 @@ function App() {
     };
   }, [os, openSettings, refetch]);
 
+  // Open clipper:// links: a clip link shows that clip on its own, a search
+  // link runs the search
+  useEffect(() => {
+    const openPendingLink = async () => {
+      const link = await invoke<DeepLink | null>("take_pending_deep_link");
+      if (!link) return;
+      if (link.kind === "search") {
+        setLinkedClip(null);
+        setSearchQuery(link.query);
+        return;
+      }
+      try {
+        setLinkedClip(await api.getClip(link.id));
+      } catch {
+        showToast(t("deepLink.clipNotFound"), "error");
+      }
+    };
+    openPendingLink();
+    const unlistenDeepLink = listen("deep-link", openPendingLink);
+
+    return () => {
+      unlistenDeepLink.then((fn) => fn());
+    };
+  }, [api, setSearchQuery, showToast, t]);
+
+  // Searching or filtering goes back to the full list
+  useEffect(() => {
+    setLinkedClip(null);
+  }, [searchQuery, filters, favoritesOnly]);
+
+  // Keep the linked clip in step with edits made to it
+  const handleLinkedClipUpdated = useCallback((updatedClip: Clip, onUpdated?: () => void) => {
+    setLinkedClip(updatedClip);
+    handleClipUpdated(updatedClip, onUpdated);
+  }, [handleClipUpdated]);
+
+  const handleLinkedClipDeleted = useCallback((clipId: string, onDeleted?: () => void) => {
+    setLinkedClip(null);
+    handleClipDeleted(clipId, onDeleted);
+  }, [handleClipDeleted]);
+
+  // Focus the search box when the quick pick shortcut shows the window
+  useEffect(() => {
+    const unlistenQuickPick = listen("open-quick-pick", () => {
+      searchInputRef.current?.focus();
+      searchInputRef.current?.select();
+    });
+
+    return () => {
+      unlistenQuickPick.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for certificate trust required events
   useEffect(() => {
     const unlistenCertTrust = listen<CertificateInfo>("certificate-trust-required", (event) => {
@@ -279,13 +345,29 @@ function App() {
     setPendingMismatch(null);
   }, []);
 
-  // Listen for data-cleared and server-switched events to refresh clips
+  const handleSyncConflictUseRemote = useCallback(() => {
+    if (syncConflict) {
+      invoke("copy_to_clipboard", { content: syncConflict.content }).catch(() => {
+        showToast(t("toast.copyFailed"), "error");
+      });
+    }
+    setSyncConflict(null);
+  }, [syncConflict, showToast, t]);
+
+  const stopAppendMode = useCallback(() => {
+    invoke("set_append_mode_enabled", { enabled: false }).catch(() => { });
+  }, []);
+
+  // Last known paste stack mode, to only toast when it's toggled
+  const pasteStackEnabled = useRef(false);
+
+  // Listen for data-cleared and server-changed events to refresh clips
   useEffect(() => {
     const unlistenDataCleared = listen("data-cleared", () => {
       refetch();
     });
 
-    const unlistenServerSwitched = listen("server-switched", () => {
+    const unlistenServerChanged = listen("server-changed", () => {
       refetch();
     });
 
@@ -346,15 +428,88 @@ function App() {
       }
     );
 
+    // Listen for paste stack changes and report when stack mode is toggled
+    const unlistenPasteStackChanged = listen<{ enabled: boolean; count: number }>(
+      "paste-stack-changed",
+      (event) => {
+        const { enabled } = event.payload;
+        if (enabled !== pasteStackEnabled.current) {
+          pasteStackEnabled.current = enabled;
+          showToast(t(enabled ? "toast.pasteStackOn" : "toast.pasteStackOff"));
+        }
+      }
+    );
+
+    // Track append mode for the header badge and report when it's toggled
+    invoke<{ enabled: boolean }>("get_append_mode")
+      .then((status) => setAppendMode(status.enabled))
+      .catch(() => { });
+    const unlistenAppendModeChanged = listen<{ enabled: boolean }>(
+      "append-mode-changed",
+      (event) => {
+        const { enabled } = event.payload;
+        setAppendMode(enabled);
+        showToast(t(enabled ? "toast.appendModeOn" : "toast.appendModeOff"));
+      }
+    );
+
+    // Report when clipboard capture is paused from the tray, or resumes
+    const unlistenCapturePauseChanged = listen<{ paused: boolean; until: string | null }>(
+      "capture-pause-changed",
+      (event) => {
+        showToast(t(event.payload.paused ? "toast.capturePaused" : "toast.captureResumed"));
+      }
+    );
+
+    // Listen for screenshots taken with the capture shortcut
+    const unlistenScreenshotCaptured = listen("screenshot-captured", () => {
+      showToast(t("toast.screenshotSaved"));
+    });
+
+    const unlistenScreenshotFailed = listen<string>("screenshot-failed", (event) => {
+      showToast(t("toast.screenshotFailed").replace("{error}", event.payload), "error");
+    });
+
+    // Listen for clips from other devices that didn't replace a copy made here
+    const unlistenSyncConflict = listen<SyncConflict & { policy: string }>(
+      "sync-conflict",
+      (event) => {
+        const { policy, ...conflict } = event.payload;
+        if (policy === "prompt") {
+          setSyncConflict(conflict);
+        } else {
+          showToast(t("toast.syncConflictKept").replace("{device}", conflict.device));
+        }
+      }
+    );
+
+    // Listen for clips pushed to this device's clipboard
+    const unlistenClipboardPushed = listen("clipboard-pushed", () => {
+      showToast(t("toast.clipboardPushed"));
+    });
+
+    // Listen for failed tray actions
+    const unlistenActionFailed = listen<{ name: string; error: string }>("action-failed", (event) => {
+      showToast(t("toast.actionFailed").replace("{name}", event.payload.name), "error");
+    });
+
     return () => {
       unlistenDataCleared.then((fn) => fn());
-      unlistenServerSwitched.then((fn) => fn());
+      unlistenServerChanged.then((fn) => fn());
       unlistenNewClip.then((fn) => fn());
       unlistenClipUpdated.then((fn) => fn());
       unlistenClipDeleted.then((fn) => fn());
       unlistenClipsCleanedUp.then((fn) => fn());
       unlistenClipsImported.then((fn) => fn());
       unlistenFileUploadError.then((fn) => fn());
+      unlistenPasteStackChanged.then((fn) => fn());
+      unlistenAppendModeChanged.then((fn) => fn());
+      unlistenCapturePauseChanged.then((fn) => fn());
+      unlistenScreenshotCaptured.then((fn) => fn());
+      unlistenScreenshotFailed.then((fn) => fn());
+      unlistenSyncConflict.then((fn) => fn());
+      unlistenClipboardPushed.then((fn) => fn());
+      unlistenActionFailed.then((fn) => fn());
     };
   }, [refetch, showToast, t]);
 
@@ -517,6 +672,16 @@ function App() {
                     </svg>
                     {total}
                   </span>
+                  {appendMode && (
+                    <button
+                      className="header-append-mode"
+                      onClick={stopAppendMode}
+                      title={t("status.appendModeStop")}
+                      tabIndex={-1}
+                    >
+                      {t("status.appendMode")}
+                    </button>
+                  )}
                   <span
                     className={`header-ws-dot ${wsConnected ? "ws-connected" : "ws-disconnected"}`}
                     title={wsConnected ? t("status.wsConnected") : t("status.wsDisconnected")}
@@ -620,6 +785,16 @@ function App() {
                   </svg>
                   {total}
                 </span>
+                {appendMode && (
+                  <button
+                    className="header-append-mode"
+                    onClick={stopAppendMode}
+                    title={t("status.appendModeStop")}
+                    tabIndex={-1}
+                  >
+                    {t("status.appendMode")}
+                  </button>
+                )}
                 <span
                   className={`header-ws-dot ${wsConnected ? "ws-connected" : "ws-disconnected"}`}
                   title={wsConnected ? t("status.wsConnected") : t("status.wsDisconnected")}
@@ -704,18 +879,27 @@ function App() {
           </>
         )}
 
+        {linkedClip && (
+          <div className="linked-clip-bar">
+            <span>{t("deepLink.showingLinkedClip")}</span>
+            <button type="button" className="linked-clip-bar-button" onClick={() => setLinkedClip(null)}>
+              {t("deepLink.showAll")}
+            </button>
+          </div>
+        )}
+
         <main className="app-main">
           <ClipList
-            clips={clips}
-            loading={loading}
-            loadingMore={loadingMore}
-            error={error}
-            hasMore={hasMore}
-            total={total}
+            clips={linkedClip ? [linkedClip] : clips}
+            loading={linkedClip ? false : loading}
+            loadingMore={linkedClip ? false : loadingMore}
+            error={linkedClip ? null : error}
+            hasMore={linkedClip ? false : hasMore}
+            total={linkedClip ? 1 : total}
             onToggleFavorite={toggleFavorite}
             onLoadMore={loadMore}
-            onClipUpdated={handleClipUpdated}
-            onClipDeleted={handleClipDeleted}
+            onClipUpdated={linkedClip ? handleLinkedClipUpdated : handleClipUpdated}
+            onClipDeleted={linkedClip ? handleLinkedClipDeleted : handleClipDeleted}
             onBeforeClipModified={handleBeforeClipModified}
             onTagClick={handleAddTagFilter}
             onSetStartDate={handleSetStartDate}
@@ -748,6 +932,13 @@ function App() {
           onReject={handleMismatchReject}
           loading={mismatchAccepting}
         />
+
+        {/* Sync conflict dialog - a clip from another device arrived just after a copy here */}
+        <SyncConflictDialog
+          conflict={syncConflict}
+          onKeepLocal={() => setSyncConflict(null)}
+          onUseRemote={handleSyncConflictUseRemote}
+        />
       </div>
     </DropZone>
   );
@@ -1,5 +1,15 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { ClipperApi, Clip, PagedResult, PagedTagResult, SearchFilters } from "@unwritten-codes/clipper-ui";
+import type {
+  ClipperApi,
+  Clip,
+  ClipAction,
+  ClipTransform,
+  MergedCursor,
+  MergedResult,
+  PagedResult,
+  PagedTagResult,
+  SearchFilters,
+} from "@unwritten-codes/clipper-ui";
 
 /**
  * Create a Tauri API client that uses invoke commands
@@ -33,6 +43,20 @@ export function createTauriApiClient(): ClipperApi {
       });
     },
 
+    async searchAllServers(
+      query: string,
+      filters: SearchFilters,
+      cursor: MergedCursor | undefined,
+      limit: number
+    ): Promise<MergedResult> {
+      return invoke<MergedResult>("search_all_servers", {
+        query,
+        filters,
+        cursor: cursor ?? null,
+        limit,
+      });
+    },
+
     async getClip(id: string): Promise<Clip> {
       return invoke<Clip>("get_clip", { id });
     },
@@ -66,16 +90,36 @@ export function createTauriApiClient(): ClipperApi {
       id: string,
       tags?: string[],
       additionalNotes?: string | null,
-      language?: string | null
+      language?: string | null,
+      color?: string,
+      icon?: string
     ): Promise<Clip> {
       return invoke<Clip>("update_clip", {
         id,
         tags,
         additionalNotes,
         language,
+        color,
+        icon,
       });
     },
 
+    async transformClip(id: string, transforms: ClipTransform[]): Promise<Clip> {
+      return invoke<Clip>("transform_clip", { id, transforms });
+    },
+
+    async translateClip(id: string, to: string): Promise<Clip> {
+      return invoke<Clip>("translate_clip", { id, to });
+    },
+
+    async listActions(): Promise<ClipAction[]> {
+      return invoke<ClipAction[]>("list_actions");
+    },
+
+    async runAction(name: string, clipId: string): Promise<void> {
+      await invoke("run_action", { name, clipId });
+    },
+
     async deleteClip(id: string): Promise<void> {
       await invoke("delete_clip", { id });
     },
@@ -104,6 +148,18 @@ export function createTauriApiClient(): ClipperApi {
       await invoke("copy_image_to_clipboard", { clipId });
     },
 
+    async pasteClip(clipId: string): Promise<void> {
+      await invoke("paste_clip", { clipId });
+    },
+
+    async revealAttachment(clipId: string): Promise<void> {
+      await invoke("reveal_attachment", { clipId });
+    },
+
+    async dragAttachment(clipId: string): Promise<void> {
+      await invoke("drag_attachment", { clipId });
+    },
+
     async downloadFile(clipId: string, filename: string): Promise<void> {
       await invoke("download_file", { clipId, filename });
     },
@@ -0,0 +1,225 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import "./DevicePairing.css";
+
+interface PairingCodeInfo {
+  code: string;
+  expires_at: string;
+  pairing_url: string;
+  qr_svg: string;
+}
+
+interface ClaimedPairing {
+  server_url: string | null;
+  token: string;
+}
+
+interface PairedDevice {
+  id: string;
+  name: string;
+  created_at: string;
+  last_used_at?: string | null;
+}
+
+interface DevicePairingProps {
+  // URL new devices should connect to; encoded in the QR code
+  serverUrl: string;
+  // Show the "pair a device" button and the paired device list. Only works
+  // while connected with the server's shared token.
+  canManage: boolean;
+  // Show the field for redeeming a code from another device
+  canClaim: boolean;
+  onClaimed?: (token: string, serverUrl: string | null) => void;
+}
+
+// Pairing codes and per-device tokens (see pairing.rs in clipper-server)
+export function DevicePairing({ serverUrl, canManage, canClaim, onClaimed }: DevicePairingProps) {
+  const { t } = useI18n();
+  const [pairing, setPairing] = useState<PairingCodeInfo | null>(null);
+  const [devices, setDevices] = useState<PairedDevice[]>([]);
+  const [claimCode, setClaimCode] = useState("");
+  const [confirmRevokeId, setConfirmRevokeId] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadDevices = useCallback(async () => {
+    try {
+      setDevices(await invoke<PairedDevice[]>("list_paired_devices"));
+      setError(null);
+    } catch (e) {
+      setDevices([]);
+      setError(String(e));
+    }
+  }, []);
+
+  useEffect(() => {
+    if (canManage) {
+      loadDevices();
+    }
+  }, [canManage, loadDevices]);
+
+  // Drop the code once it expires, and refresh the list in case it was used
+  useEffect(() => {
+    if (!pairing) return;
+    const remaining = new Date(pairing.expires_at).getTime() - Date.now();
+    const timer = setTimeout(() => {
+      setPairing(null);
+      loadDevices();
+    }, Math.max(remaining, 0));
+    return () => clearTimeout(timer);
+  }, [pairing, loadDevices]);
+
+  const handleCreateCode = async () => {
+    setBusy(true);
+    setError(null);
+    try {
+      setPairing(await invoke<PairingCodeInfo>("create_pairing_code", { serverUrl }));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  // First click asks for confirmation, second click revokes
+  const handleRevoke = async (device: PairedDevice) => {
+    if (confirmRevokeId !== device.id) {
+      setConfirmRevokeId(device.id);
+      return;
+    }
+    setConfirmRevokeId(null);
+    try {
+      await invoke("revoke_paired_device", { id: device.id });
+      setDevices((prev) => prev.filter((d) => d.id !== device.id));
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleClaim = async () => {
+    if (!claimCode.trim()) return;
+    setBusy(true);
+    setError(null);
+    try {
+      const claimed = await invoke<ClaimedPairing>("claim_pairing_code", {
+        serverUrl,
+        code: claimCode,
+      });
+      setClaimCode("");
+      onClaimed?.(claimed.token, claimed.server_url);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const formatDate = (value: string) => new Date(value).toLocaleString();
+
+  return (
+    <div className="settings-field device-pairing">
+      <label>{t("settings.pairing")}</label>
+
+      {canManage && (
+        <>
+          {pairing ? (
+            <div className="device-pairing-code">
+              <img
+                className="device-pairing-qr"
+                src={`data:image/svg+xml;charset=utf-8,${encodeURIComponent(pairing.qr_svg)}`}
+                alt={pairing.pairing_url}
+              />
+              <div className="device-pairing-code-details">
+                <span className="device-pairing-code-value">{pairing.code}</span>
+                <span className="settings-hint">
+                  {t("settings.pairing.expires").replace(
+                    "{time}",
+                    new Date(pairing.expires_at).toLocaleTimeString()
+                  )}
+                </span>
+                <button
+                  type="button"
+                  className="settings-btn secondary"
+                  onClick={() => {
+                    setPairing(null);
+                    loadDevices();
+                  }}
+                >
+                  {t("settings.pairing.done")}
+                </button>
+              </div>
+            </div>
+          ) : (
+            <button
+              type="button"
+              className="settings-btn secondary"
+              onClick={handleCreateCode}
+              disabled={busy}
+            >
+              {t("settings.pairing.pairDevice")}
+            </button>
+          )}
+          <p className="settings-hint">{t("settings.pairing.hint")}</p>
+
+          {devices.length > 0 && (
+            <ul className="device-pairing-list">
+              {devices.map((device) => (
+                <li key={device.id} className="device-pairing-item">
+                  <div className="device-pairing-item-info">
+                    <span className="device-pairing-item-name">{device.name}</span>
+                    <span className="device-pairing-item-meta">
+                      {device.last_used_at
+                        ? t("settings.pairing.lastUsed").replace("{time}", formatDate(device.last_used_at))
+                        : t("settings.pairing.pairedAt").replace("{time}", formatDate(device.created_at))}
+                    </span>
+                  </div>
+                  <button
+                    type="button"
+                    className="settings-btn danger"
+                    onClick={() => handleRevoke(device)}
+                    onBlur={() => setConfirmRevokeId(null)}
+                  >
+                    {confirmRevokeId === device.id
+                      ? t("settings.pairing.revokeConfirm")
+                      : t("settings.pairing.revoke")}
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+        </>
+      )}
+
+      {canClaim && (
+        <>
+          <div className="device-pairing-claim">
+            <input
+              type="text"
+              value={claimCode}
+              onChange={(e) => setClaimCode(e.target.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") handleClaim();
+              }}
+              placeholder={t("settings.pairing.claim.placeholder")}
+              spellCheck={false}
+              autoCorrect="off"
+              autoCapitalize="off"
+            />
+            <button
+              type="button"
+              className="settings-btn secondary"
+              onClick={handleClaim}
+              disabled={busy || !claimCode.trim()}
+            >
+              {t("settings.pairing.claim")}
+            </button>
+          </div>
+          <p className="settings-hint">{t("settings.pairing.claim.hint")}</p>
+        </>
+      )}
+
+      {error && <p className="device-pairing-error">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,147 @@
+import { useCallback, useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import {
+  Clip,
+  TranslateFunction,
+  isSensitive,
+  useI18n,
+  useTheme,
+} from "@unwritten-codes/clipper-ui";
+import "./Palette.css";
+
+// Delay between typing and searching, so each keystroke isn't a request
+const SEARCH_DELAY_MS = 150;
+
+// One line summing up a clip in the result list
+function clipSummary(clip: Clip, t: TranslateFunction): string {
+  if (isSensitive(clip)) return t("palette.sensitive");
+  if (clip.file_attachment && clip.original_filename) return clip.original_filename;
+  const firstLine = clip.content.trim().split("\n", 1)[0];
+  return firstLine || t("palette.empty");
+}
+
+// Contents of the clip palette window (see palette.rs)
+export function Palette() {
+  const { t } = useI18n();
+  // Picks up the theme the main window saved to localStorage
+  useTheme();
+  const [query, setQuery] = useState("");
+  const [clips, setClips] = useState<Clip[]>([]);
+  const [selected, setSelected] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+  const listRef = useRef<HTMLUListElement>(null);
+  // Drops results of searches that finished after a newer one
+  const searchIdRef = useRef(0);
+
+  const search = useCallback(
+    async (text: string) => {
+      const id = ++searchIdRef.current;
+      try {
+        const results = await invoke<Clip[]>("search_palette", { query: text });
+        if (id !== searchIdRef.current) return;
+        setClips(results);
+        setSelected(0);
+        setError(null);
+      } catch (e) {
+        if (id !== searchIdRef.current) return;
+        setError(t("palette.error").replace("{error}", String(e)));
+      }
+    },
+    [t]
+  );
+
+  useEffect(() => {
+    const timer = setTimeout(() => search(query), SEARCH_DELAY_MS);
+    return () => clearTimeout(timer);
+  }, [query, search]);
+
+  // The window is hidden rather than closed, so start over with a fresh list
+  // every time it's shown again
+  useEffect(() => {
+    inputRef.current?.focus();
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        setQuery("");
+        search("");
+        inputRef.current?.focus();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [search]);
+
+  useEffect(() => {
+    listRef.current
+      ?.querySelector(".palette-item.selected")
+      ?.scrollIntoView({ block: "nearest" });
+  }, [selected]);
+
+  const pick = async (clip: Clip, paste: boolean) => {
+    try {
+      await invoke("pick_palette_clip", { clipId: clip.id, paste });
+    } catch (e) {
+      setError(t("palette.error").replace("{error}", String(e)));
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    if (e.nativeEvent.isComposing) return;
+    if (e.key === "ArrowDown") {
+      e.preventDefault();
+      setSelected((i) => Math.min(i + 1, clips.length - 1));
+    } else if (e.key === "ArrowUp") {
+      e.preventDefault();
+      setSelected((i) => Math.max(i - 1, 0));
+    } else if (e.key === "Enter") {
+      e.preventDefault();
+      const clip = clips[selected];
+      if (clip) pick(clip, e.ctrlKey || e.metaKey);
+    } else if (e.key === "Escape") {
+      e.preventDefault();
+      invoke("hide_palette");
+    }
+  };
+
+  return (
+    <div className="palette">
+      <input
+        ref={inputRef}
+        className="palette-input"
+        value={query}
+        onChange={(e) => setQuery(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder={t("search.placeholderNoTagSearch")}
+        spellCheck={false}
+      />
+      <ul ref={listRef} className="palette-list" role="listbox">
+        {clips.map((clip, index) => (
+          <li
+            key={clip.id}
+            className={`palette-item ${index === selected ? "selected" : ""}`}
+            role="option"
+            aria-selected={index === selected}
+            onMouseEnter={() => setSelected(index)}
+            onClick={(e) => pick(clip, e.ctrlKey || e.metaKey)}
+          >
+            {clipSummary(clip, t)}
+          </li>
+        ))}
+        {clips.length === 0 && !error && (
+          <li className="palette-empty">{t("clipList.empty")}</li>
+        )}
+      </ul>
+      <div className="palette-footer" data-tauri-drag-region>
+        {error ? (
+          <span className="palette-error">{error}</span>
+        ) : (
+          <span className="palette-hint" data-tauri-drag-region>
+            {t("palette.hint")}
+          </span>
+        )}
+      </div>
+    </div>
+  );
+}
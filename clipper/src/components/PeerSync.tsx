@@ -0,0 +1,164 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import "./PeerSync.css";
+
+interface PeerInfo {
+  id: string;
+  name: string;
+  address: string;
+}
+
+interface PeerSyncProps {
+  enabled: boolean;
+  syncKey: string | null;
+  onChange: (enabled: boolean, syncKey: string | null) => void;
+}
+
+// A 32-character key: short passphrases could be guessed from a captured handshake
+const generateSyncKey = () => {
+  const chars = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+  const array = new Uint8Array(32);
+  crypto.getRandomValues(array);
+  return Array.from(array, (n) => chars[n % chars.length]).join("");
+};
+
+// Serverless sync with other instances on the LAN (see p2p.rs in clipper-client)
+export function PeerSync({ enabled, syncKey, onChange }: PeerSyncProps) {
+  const { t } = useI18n();
+  const [keyDraft, setKeyDraft] = useState(syncKey ?? "");
+  const [showKey, setShowKey] = useState(false);
+  const [peers, setPeers] = useState<PeerInfo[]>([]);
+
+  useEffect(() => {
+    setKeyDraft(syncKey ?? "");
+  }, [syncKey]);
+
+  const loadPeers = useCallback(async () => {
+    try {
+      setPeers(await invoke<PeerInfo[]>("get_p2p_peers"));
+    } catch {
+      setPeers([]);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadPeers();
+    const unlisten = listen("p2p-peers-changed", () => {
+      loadPeers();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [enabled, syncKey, loadPeers]);
+
+  const handleToggle = (checked: boolean) => {
+    // Enabling without a key generates one to copy to the other devices
+    if (checked && !syncKey) {
+      onChange(true, generateSyncKey());
+      setShowKey(true);
+    } else {
+      onChange(checked, syncKey);
+    }
+  };
+
+  // The node restarts whenever the key changes, so only apply it once editing is done
+  const commitKey = () => {
+    const key = keyDraft.trim() || null;
+    if (key !== syncKey) {
+      onChange(enabled, key);
+    }
+  };
+
+  const handleGenerate = () => {
+    onChange(enabled, generateSyncKey());
+    setShowKey(true);
+  };
+
+  return (
+    <div className="settings-section">
+      <h3>{t("settings.p2p")}</h3>
+      <div className="settings-field settings-checkbox">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={enabled}
+            onChange={(e) => handleToggle(e.target.checked)}
+          />
+          <span className="checkbox-text">{t("settings.p2p.enabled")}</span>
+        </label>
+        <p className="settings-hint">{t("settings.p2p.hint")}</p>
+      </div>
+
+      {enabled && (
+        <>
+          <div className="settings-field">
+            <label htmlFor="p2pSyncKey">{t("settings.p2p.syncKey")}</label>
+            <div className="settings-password-input">
+              <input
+                id="p2pSyncKey"
+                type={showKey ? "text" : "password"}
+                value={keyDraft}
+                onChange={(e) => setKeyDraft(e.target.value)}
+                onBlur={commitKey}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") commitKey();
+                }}
+                placeholder={t("settings.p2p.syncKey.placeholder")}
+                autoComplete="off"
+                spellCheck={false}
+              />
+              <button
+                type="button"
+                className="password-toggle-button"
+                onClick={handleGenerate}
+                title={t("settings.token.generate")}
+              >
+                <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                  <path d="M23 4v6h-6"></path>
+                  <path d="M20.49 15a9 9 0 1 1-2.12-9.36L23 10"></path>
+                </svg>
+              </button>
+              <button
+                type="button"
+                className="password-toggle-button"
+                onClick={() => setShowKey(!showKey)}
+                title={showKey ? t("settings.token.hide") : t("settings.token.show")}
+              >
+                {showKey ? (
+                  <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                    <path d="M17.94 17.94A10.07 10.07 0 0 1 12 20c-7 0-11-8-11-8a18.45 18.45 0 0 1 5.06-5.94M9.9 4.24A9.12 9.12 0 0 1 12 4c7 0 11 8 11 8a18.5 18.5 0 0 1-2.16 3.19m-6.72-1.07a3 3 0 1 1-4.24-4.24"></path>
+                    <line x1="1" y1="1" x2="23" y2="23"></line>
+                  </svg>
+                ) : (
+                  <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                    <path d="M1 12s4-8 11-8 11 8 11 8-4 8-11 8-11-8-11-8z"></path>
+                    <circle cx="12" cy="12" r="3"></circle>
+                  </svg>
+                )}
+              </button>
+            </div>
+            <p className="settings-hint">{t("settings.p2p.syncKey.hint")}</p>
+          </div>
+
+          <div className="settings-field">
+            <label>{t("settings.p2p.peers")}</label>
+            {peers.length > 0 ? (
+              <ul className="peer-sync-list">
+                {peers.map((peer) => (
+                  <li key={peer.id} className="peer-sync-item">
+                    <span className="peer-sync-name">{peer.name}</span>
+                    <span className="peer-sync-address">{peer.address}</span>
+                  </li>
+                ))}
+              </ul>
+            ) : (
+              <p className="settings-hint">{t("settings.p2p.peers.empty")}</p>
+            )}
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
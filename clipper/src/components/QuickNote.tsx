@@ -0,0 +1,83 @@
+import { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { useI18n, useTheme } from "@unwritten-codes/clipper-ui";
+import "./QuickNote.css";
+
+// Contents of the quick note popup window (see quick_note.rs)
+export function QuickNote() {
+  const { t } = useI18n();
+  // Picks up the theme the main window saved to localStorage
+  useTheme();
+  const [content, setContent] = useState("");
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const textareaRef = useRef<HTMLTextAreaElement>(null);
+
+  // The window is hidden rather than closed, so refocus the editor every
+  // time it's shown again
+  useEffect(() => {
+    textareaRef.current?.focus();
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        textareaRef.current?.focus();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const close = () => {
+    setError(null);
+    invoke("hide_quick_note");
+  };
+
+  const submit = async () => {
+    if (!content.trim() || saving) return;
+    setSaving(true);
+    setError(null);
+    try {
+      await invoke("submit_quick_note", { content });
+      setContent("");
+    } catch (e) {
+      setError(t("quickNote.error").replace("{error}", String(e)));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
+    if (e.key === "Enter" && !e.shiftKey && !e.nativeEvent.isComposing) {
+      e.preventDefault();
+      submit();
+    } else if (e.key === "Escape") {
+      e.preventDefault();
+      close();
+    }
+  };
+
+  return (
+    <div className="quick-note" data-tauri-drag-region>
+      <textarea
+        ref={textareaRef}
+        className="quick-note-input"
+        value={content}
+        onChange={(e) => setContent(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder={t("quickNote.placeholder")}
+        disabled={saving}
+        spellCheck={false}
+      />
+      <div className="quick-note-footer" data-tauri-drag-region>
+        {error ? (
+          <span className="quick-note-error">{error}</span>
+        ) : (
+          <span className="quick-note-hint" data-tauri-drag-region>
+            {saving ? t("quickNote.saving") : t("quickNote.hint")}
+          </span>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,80 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import "./ServerDiscovery.css";
+
+interface DiscoveredServer {
+  name: string;
+  hostname: string;
+  url: string;
+  addresses: string[];
+  port: number;
+  version: string | null;
+  fingerprint: string | null;
+  auth_required: boolean;
+}
+
+interface ServerDiscoveryProps {
+  onSelect: (url: string) => void;
+}
+
+// Servers advertised over mDNS (see discovery.rs in clipper-server)
+export function ServerDiscovery({ onSelect }: ServerDiscoveryProps) {
+  const { t } = useI18n();
+  const [servers, setServers] = useState<DiscoveredServer[] | null>(null);
+  const [searching, setSearching] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSearch = async () => {
+    setSearching(true);
+    setError(null);
+    try {
+      setServers(await invoke<DiscoveredServer[]>("discover_servers"));
+    } catch (e) {
+      setServers(null);
+      setError(String(e));
+    } finally {
+      setSearching(false);
+    }
+  };
+
+  return (
+    <div className="server-discovery">
+      <button
+        type="button"
+        className="settings-btn secondary"
+        onClick={handleSearch}
+        disabled={searching}
+      >
+        {searching ? t("settings.discovery.searching") : t("settings.discovery.search")}
+      </button>
+
+      {servers && servers.length === 0 && (
+        <p className="settings-hint">{t("settings.discovery.empty")}</p>
+      )}
+
+      {servers && servers.length > 0 && (
+        <ul className="server-discovery-list">
+          {servers.map((server) => (
+            <li key={`${server.hostname}-${server.name}`}>
+              <button
+                type="button"
+                className="server-discovery-item"
+                onClick={() => onSelect(server.url)}
+                title={server.fingerprint ?? undefined}
+              >
+                <span className="server-discovery-name">{server.name}</span>
+                <span className="server-discovery-meta">
+                  {server.url}
+                  {server.auth_required && ` · ${t("settings.discovery.tokenRequired")}`}
+                </span>
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      {error && <p className="server-discovery-error">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,83 @@
+import { useState } from "react";
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import type { ServerProfile } from "./SettingsDialog";
+import "./ServerProfiles.css";
+
+interface ServerProfilesProps {
+  profiles: ServerProfile[];
+  serverAddress: string;
+  token: string | null;
+  onUse: (profile: ServerProfile) => void;
+  onChange: (profiles: ServerProfile[]) => void;
+}
+
+// Named external servers, also listed in the tray's Server menu
+export function ServerProfiles({ profiles, serverAddress, token, onUse, onChange }: ServerProfilesProps) {
+  const { t } = useI18n();
+  const [name, setName] = useState("");
+  const trimmedName = name.trim();
+
+  const handleSave = () => {
+    // Saving under an existing name updates that profile
+    const profile = { name: trimmedName, url: serverAddress, token };
+    const index = profiles.findIndex((p) => p.name === trimmedName);
+    onChange(index >= 0
+      ? profiles.map((p, i) => (i === index ? profile : p))
+      : [...profiles, profile]);
+    setName("");
+  };
+
+  return (
+    <div className="server-profiles">
+      {profiles.length > 0 && (
+        <ul className="server-profiles-list">
+          {profiles.map((profile) => (
+            <li key={profile.name} className={profile.url === serverAddress ? "current" : undefined}>
+              <button
+                type="button"
+                className="server-profiles-item"
+                onClick={() => onUse(profile)}
+                title={t("settings.serverProfiles.use")}
+              >
+                <span className="server-profiles-name">{profile.name}</span>
+                <span className="server-profiles-meta">{profile.url}</span>
+              </button>
+              <button
+                type="button"
+                className="server-profiles-delete"
+                onClick={() => onChange(profiles.filter((p) => p.name !== profile.name))}
+                title={t("settings.serverProfiles.delete")}
+                aria-label={t("settings.serverProfiles.delete")}
+              >
+                ×
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+
+      <div className="server-profiles-save">
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter" && trimmedName) handleSave();
+          }}
+          placeholder={t("settings.serverProfiles.name.placeholder")}
+          spellCheck={false}
+          autoCorrect="off"
+          autoCapitalize="off"
+        />
+        <button
+          type="button"
+          className="settings-btn secondary"
+          onClick={handleSave}
+          disabled={!trimmedName || !serverAddress.trim()}
+        >
+          {t("settings.serverProfiles.save")}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -11,10 +11,46 @@ import {
 import type { Language, SyntaxTheme } from "@unwritten-codes/clipper-ui";
 import { CertificateConfirmDialog, CertificateInfo } from "./CertificateConfirmDialog";
 import { CertificateMismatchDialog, CertificateMismatchInfo } from "./CertificateMismatchDialog";
+import { DevicePairing } from "./DevicePairing";
+import { PeerSync } from "./PeerSync";
+import { ServerDiscovery } from "./ServerDiscovery";
+import { ServerProfiles } from "./ServerProfiles";
 import { useEnsureWindowSize } from "../hooks/useEnsureWindowSize";
 
 export type ThemePreference = "light" | "dark" | "auto";
 
+/** What the clipboard monitor does with files copied in Finder/Explorer */
+export type CopiedFilesMode = "upload" | "list" | "off";
+
+const COPIED_FILES_MODES: CopiedFilesMode[] = ["upload", "list", "off"];
+
+/** What happens when another device's clip arrives just after a copy here */
+export type SyncConflictPolicy = "keep-both" | "newest-wins" | "prompt";
+
+const SYNC_CONFLICT_POLICIES: SyncConflictPolicy[] = ["keep-both", "newest-wins", "prompt"];
+
+/** Where clips copied from the history go on Linux */
+export type RestoreSelection = "clipboard" | "primary" | "both";
+
+const RESTORE_SELECTIONS: RestoreSelection[] = ["clipboard", "primary", "both"];
+
+/** What `run_cleanup` deleted, or would delete for a dry run */
+interface CleanupResult {
+  dry_run: boolean;
+  deleted_clips: number;
+  deleted_short_urls: number;
+  clips: {
+    id: string;
+    preview: string;
+    tags: string[];
+    original_filename?: string;
+    created_at: string;
+  }[];
+}
+
+/** Clips listed before asking to clean up */
+const CLEANUP_PREVIEW_CLIPS = 5;
+
 export interface SettingsWindowGeometry {
   width: number | null;
   height: number | null;
@@ -22,8 +58,35 @@ export interface SettingsWindowGeometry {
   y: number | null;
 }
 
+// Actions that can be bound to a global shortcut
+export type ShortcutAction =
+  | "toggleWindow"
+  | "pasteLastClip"
+  | "pastePlainText"
+  | "quickPick"
+  | "captureScreenshot"
+  | "quickNote"
+  | "palette"
+  | "pasteStackToggle"
+  | "pasteStackPop"
+  | "appendModeToggle";
+
+export interface ShortcutBinding {
+  action: ShortcutAction;
+  // Empty if the action is unbound
+  shortcut: string;
+}
+
+// A named external server (see ServerProfiles)
+export interface ServerProfile {
+  name: string;
+  url: string;
+  token: string | null;
+}
+
 export interface Settings {
   serverAddress: string;
+  serverProfiles: ServerProfile[];
   defaultSaveLocation: string | null;
   openOnStartup: boolean;
   startOnLogin: boolean;
@@ -34,12 +97,24 @@ export interface Settings {
   language: string | null;
   notificationsEnabled: boolean;
   globalShortcut: string;
+  windowFollowsCursor: boolean;
+  shortcuts: Partial<Record<ShortcutAction, string>>;
   cleanupEnabled: boolean;
   cleanupRetentionDays: number;
   externalServerToken: string | null;
   bundledServerToken: string | null;
   maxUploadSizeMb: number;
+  captureImages: boolean;
+  maxImageSizeMb: number;
+  copiedFiles: CopiedFilesMode;
+  maxCopiedFileSizeMb: number;
+  capturePrimarySelection: boolean;
+  restoreSelection: RestoreSelection;
+  syncConflictPolicy: SyncConflictPolicy;
+  syncTags: string[];
   settingsWindowGeometry: SettingsWindowGeometry;
+  p2pSyncEnabled: boolean;
+  p2pSyncKey: string | null;
 }
 
 interface ServerInfo {
@@ -94,6 +169,8 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
   const { showToast } = useToast();
   // Detect platform for default shortcut
   const isMac = navigator.platform.toUpperCase().indexOf("MAC") >= 0;
+  // Only Linux has a primary selection
+  const isLinux = navigator.platform.toUpperCase().indexOf("LINUX") >= 0;
   const defaultShortcut = isMac ? "Command+Shift+V" : "Ctrl+Shift+V";
 
   const [activeTab, setActiveTab] = useState<SettingsTab>("appearance");
@@ -101,6 +178,7 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
   const tabsRef = useRef<HTMLDivElement>(null);
   const [settings, setSettings] = useState<Settings>({
     serverAddress: "http://localhost:3000",
+    serverProfiles: [],
     defaultSaveLocation: null,
     openOnStartup: true,
     startOnLogin: false,
@@ -111,19 +189,34 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     language: null,
     notificationsEnabled: true,
     globalShortcut: defaultShortcut,
+    windowFollowsCursor: false,
+    shortcuts: {},
     cleanupEnabled: false,
     cleanupRetentionDays: 30,
     externalServerToken: null,
     bundledServerToken: null,
     maxUploadSizeMb: 10,
+    captureImages: true,
+    maxImageSizeMb: 10,
+    copiedFiles: "upload",
+    maxCopiedFileSizeMb: 10,
+    capturePrimarySelection: false,
+    restoreSelection: "clipboard",
+    syncConflictPolicy: "keep-both",
+    syncTags: [],
     settingsWindowGeometry: { width: null, height: null, x: null, y: null },
+    p2pSyncEnabled: false,
+    p2pSyncKey: null,
   });
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [serverUrl, setServerUrl] = useState<string>("");
   const [showClearConfirm, setShowClearConfirm] = useState(false);
   const [clearing, setClearing] = useState(false);
+  const [cleanupPreview, setCleanupPreview] = useState<CleanupResult | null>(null);
+  const [cleaningUp, setCleaningUp] = useState(false);
   const [exporting, setExporting] = useState(false);
+  const [excludeSensitive, setExcludeSensitive] = useState(false);
   const [importing, setImporting] = useState(false);
   const [localIpAddresses, setLocalIpAddresses] = useState<string[]>([]);
   const [togglingNetworkAccess, setTogglingNetworkAccess] = useState(false);
@@ -143,8 +236,12 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
   const [originalMaxUploadSizeMb, setOriginalMaxUploadSizeMb] = useState(10);
   // External server info (read-only, fetched from server)
   const [serverInfo, setServerInfo] = useState<ServerInfo | null>(null);
-  // Shortcut recording state
-  const [isRecordingShortcut, setIsRecordingShortcut] = useState(false);
+  // Synced tags as typed, until the field loses focus
+  const [syncTagsText, setSyncTagsText] = useState<string | null>(null);
+  // Global shortcuts and recording state
+  const [shortcutBindings, setShortcutBindings] = useState<ShortcutBinding[]>([]);
+  const [recordingAction, setRecordingAction] = useState<ShortcutAction | null>(null);
+  const isRecordingShortcut = recordingAction !== null;
   const [recordedKeys, setRecordedKeys] = useState<string[]>([]);
   const shortcutInputRef = useRef<HTMLDivElement>(null);
   // Update state
@@ -362,6 +459,7 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     setError(null);
     try {
       const loadedSettings = await invoke<Settings>("get_settings");
+      setShortcutBindings(await invoke<ShortcutBinding[]>("get_shortcuts"));
 
       // Generate a token for bundled server if one doesn't exist
       // This ensures the server always has authentication available
@@ -520,6 +618,19 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     await saveSettings(newSettings);
   };
 
+  // Handle synced tags change - save once the field loses focus
+  const handleSyncTagsBlur = async () => {
+    if (syncTagsText === null) return;
+    const syncTags = [
+      ...new Set(syncTagsText.split(",").map((tag) => tag.trim()).filter(Boolean)),
+    ];
+    setSyncTagsText(null);
+    if (syncTags.join(",") === settings.syncTags.join(",")) return;
+    const newSettings = { ...settings, syncTags };
+    setSettings(newSettings);
+    await saveSettings(newSettings);
+  };
+
   // Handle server mode change
   const handleServerModeChange = async (useBundled: boolean) => {
     if (useBundled === settings.useBundledServer) return;
@@ -688,12 +799,45 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     }
   };
 
+  // Handle "Clean now": list what cleanup would delete, then ask
+  const handlePreviewCleanup = async () => {
+    setCleaningUp(true);
+    setError(null);
+    try {
+      const result = await invoke<CleanupResult>("run_cleanup", { dryRun: true });
+      if (result.deleted_clips === 0 && result.deleted_short_urls === 0) {
+        showToast(t("settings.cleanNow.nothing"));
+      } else {
+        setCleanupPreview(result);
+      }
+    } catch (e) {
+      setError(`${t("settings.cleanNow.error")}: ${e}`);
+    } finally {
+      setCleaningUp(false);
+    }
+  };
+
+  // Handle confirmed cleanup. The server announces the deleted clips, which
+  // shows a toast and refreshes the list.
+  const handleCleanNow = async () => {
+    setCleaningUp(true);
+    setError(null);
+    try {
+      await invoke<CleanupResult>("run_cleanup", { dryRun: false });
+      setCleanupPreview(null);
+    } catch (e) {
+      setError(`${t("settings.cleanNow.error")}: ${e}`);
+    } finally {
+      setCleaningUp(false);
+    }
+  };
+
   // Handle export clips
   const handleExport = async () => {
     setExporting(true);
     setError(null);
     try {
-      const result = await invoke<string>("export_clips");
+      const result = await invoke<string>("export_clips", { excludeSensitive });
       showToast(t("toast.exportSuccess", { path: result }));
     } catch (e) {
       const errorMsg = String(e);
@@ -792,8 +936,22 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     setRecordedKeys(keys);
   }, [isRecordingShortcut, isMac]);
 
+  // Bind (or with an empty shortcut, unbind) an action. The backend registers
+  // and saves the binding, so only the local copy of the settings is updated.
+  const updateShortcut = useCallback(async (action: ShortcutAction, shortcut: string) => {
+    await invoke("update_shortcut", { action, shortcut });
+    setShortcutBindings((bindings) =>
+      bindings.map((b) => (b.action === action ? { ...b, shortcut } : b))
+    );
+    setSettings((current) =>
+      action === "toggleWindow"
+        ? { ...current, globalShortcut: shortcut }
+        : { ...current, shortcuts: { ...current.shortcuts, [action]: shortcut } }
+    );
+  }, []);
+
   const handleShortcutKeyUp = useCallback(async (_e: React.KeyboardEvent) => {
-    if (!isRecordingShortcut) return;
+    if (!recordingAction) return;
 
     // If we have a complete shortcut (at least one modifier + one key)
     if (recordedKeys.length >= 2) {
@@ -801,11 +959,8 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
 
       // Try to update the shortcut
       try {
-        await invoke("update_global_shortcut", { shortcut: shortcutStr });
-        const newSettings = { ...settings, globalShortcut: shortcutStr };
-        setSettings(newSettings);
-        await saveSettings(newSettings);
-        setIsRecordingShortcut(false);
+        await updateShortcut(recordingAction, shortcutStr);
+        setRecordingAction(null);
         setRecordedKeys([]);
         showToast(t("settings.shortcut.updated"));
       } catch (err) {
@@ -813,10 +968,20 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
         setRecordedKeys([]);
       }
     }
-  }, [isRecordingShortcut, recordedKeys, settings, showToast, t]);
+  }, [recordingAction, recordedKeys, updateShortcut, showToast, t]);
+
+  const handleClearShortcut = async (action: ShortcutAction) => {
+    setError(null);
+    try {
+      await updateShortcut(action, "");
+      showToast(t("settings.shortcut.updated"));
+    } catch (err) {
+      setError(`${t("settings.shortcut.error")}: ${err}`);
+    }
+  };
 
-  const startRecordingShortcut = () => {
-    setIsRecordingShortcut(true);
+  const startRecordingShortcut = (action: ShortcutAction) => {
+    setRecordingAction(action);
     setRecordedKeys([]);
     setError(null);
     // Focus the input after state update
@@ -826,7 +991,7 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
   };
 
   const cancelRecordingShortcut = () => {
-    setIsRecordingShortcut(false);
+    setRecordingAction(null);
     setRecordedKeys([]);
   };
 
@@ -943,6 +1108,13 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     return token;
   };
 
+  // Enabling P2P sync may also generate the key, so both are saved together
+  const handleP2pSyncChange = async (enabled: boolean, syncKey: string | null) => {
+    const newSettings = { ...settings, p2pSyncEnabled: enabled, p2pSyncKey: syncKey };
+    setSettings(newSettings);
+    await saveSettings(newSettings);
+  };
+
   // Handle generate token for bundled server
   const handleGenerateToken = async () => {
     const token = generateToken();
@@ -953,6 +1125,36 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
     setShowBundledToken(true);
   };
 
+  // Use the token from a redeemed pairing code, and the server address from
+  // the pairing link if one was pasted. Applied on close like a typed token.
+  const handlePairingClaimed = async (token: string, serverUrl: string | null) => {
+    const newSettings = {
+      ...settings,
+      externalServerToken: token,
+      serverAddress: serverUrl ?? settings.serverAddress,
+    };
+    setSettings(newSettings);
+    await saveSettings(newSettings);
+    showToast(t("settings.pairing.claimed"));
+  };
+
+  // Fill in a profile's server and token; applied on close like typed ones
+  const handleUseProfile = async (profile: ServerProfile) => {
+    const newSettings = {
+      ...settings,
+      serverAddress: profile.url,
+      externalServerToken: profile.token,
+    };
+    setSettings(newSettings);
+    await saveSettings(newSettings);
+  };
+
+  const handleProfilesChange = async (serverProfiles: ServerProfile[]) => {
+    const newSettings = { ...settings, serverProfiles };
+    setSettings(newSettings);
+    await saveSettings(newSettings);
+  };
+
   // Format shortcut for display (replace Ctrl/Command based on platform)
   const formatShortcutForDisplay = (shortcut: string) => {
     if (isMac) {
@@ -1076,44 +1278,245 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
           </p>
         </div>
 
+        <div className="settings-field settings-checkbox">
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.captureImages}
+              onChange={(e) => handleChange("captureImages", e.target.checked)}
+            />
+            <span className="checkbox-text">
+              {t("settings.captureImages")}
+            </span>
+          </label>
+          <p className="settings-hint">
+            {t("settings.captureImages.hint")}
+          </p>
+        </div>
+
+        {settings.captureImages && (
+          <div className="settings-field">
+            <label htmlFor="maxImageSizeMb">
+              {t("settings.maxImageSize")}
+            </label>
+            <input
+              id="maxImageSizeMb"
+              type="number"
+              min="1"
+              max="1024"
+              value={settings.maxImageSizeMb}
+              onChange={(e) =>
+                handleChange(
+                  "maxImageSizeMb",
+                  Math.max(1, Math.min(1024, parseInt(e.target.value) || 10))
+                )
+              }
+              className="settings-number-input"
+            />
+            <p className="settings-hint">
+              {t("settings.maxImageSize.hint")}
+            </p>
+          </div>
+        )}
+
         <div className="settings-field">
-          <label>{t("settings.globalShortcut")}</label>
-          <div className="shortcut-editor">
-            {isRecordingShortcut ? (
-              <div
-                ref={shortcutInputRef}
-                className="shortcut-input recording"
-                tabIndex={0}
-                onKeyDown={handleShortcutKeyDown}
-                onKeyUp={handleShortcutKeyUp}
-                onBlur={cancelRecordingShortcut}
+          <label htmlFor="copiedFiles">{t("settings.copiedFiles")}</label>
+          <select
+            id="copiedFiles"
+            value={settings.copiedFiles}
+            onChange={(e) => handleChange("copiedFiles", e.target.value)}
+            className="settings-select"
+          >
+            {COPIED_FILES_MODES.map((mode) => (
+              <option key={mode} value={mode}>
+                {t(`settings.copiedFiles.${mode}` as const)}
+              </option>
+            ))}
+          </select>
+          <p className="settings-hint">
+            {t("settings.copiedFiles.hint")}
+          </p>
+        </div>
+
+        {settings.copiedFiles === "upload" && (
+          <div className="settings-field">
+            <label htmlFor="maxCopiedFileSizeMb">
+              {t("settings.maxCopiedFileSize")}
+            </label>
+            <input
+              id="maxCopiedFileSizeMb"
+              type="number"
+              min="1"
+              max="1024"
+              value={settings.maxCopiedFileSizeMb}
+              onChange={(e) =>
+                handleChange(
+                  "maxCopiedFileSizeMb",
+                  Math.max(1, Math.min(1024, parseInt(e.target.value) || 10))
+                )
+              }
+              className="settings-number-input"
+            />
+            <p className="settings-hint">
+              {t("settings.maxCopiedFileSize.hint")}
+            </p>
+          </div>
+        )}
+
+        {isLinux && (
+          <>
+            <div className="settings-field settings-checkbox">
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={settings.capturePrimarySelection}
+                  onChange={(e) =>
+                    handleChange("capturePrimarySelection", e.target.checked)
+                  }
+                />
+                <span className="checkbox-text">
+                  {t("settings.capturePrimarySelection")}
+                </span>
+              </label>
+              <p className="settings-hint">
+                {t("settings.capturePrimarySelection.hint")}
+              </p>
+            </div>
+
+            <div className="settings-field">
+              <label htmlFor="restoreSelection">
+                {t("settings.restoreSelection")}
+              </label>
+              <select
+                id="restoreSelection"
+                value={settings.restoreSelection}
+                onChange={(e) => handleChange("restoreSelection", e.target.value)}
+                className="settings-select"
               >
-                {recordedKeys.length > 0
-                  ? formatShortcutForDisplay(recordedKeys.join("+"))
-                  : t("settings.globalShortcut.recording")}
+                {RESTORE_SELECTIONS.map((selection) => (
+                  <option key={selection} value={selection}>
+                    {t(`settings.restoreSelection.${selection}` as const)}
+                  </option>
+                ))}
+              </select>
+              <p className="settings-hint">
+                {t("settings.restoreSelection.hint")}
+              </p>
+            </div>
+          </>
+        )}
+
+        <div className="settings-field">
+          <label htmlFor="syncConflictPolicy">{t("settings.syncConflict")}</label>
+          <select
+            id="syncConflictPolicy"
+            value={settings.syncConflictPolicy}
+            onChange={(e) => handleChange("syncConflictPolicy", e.target.value)}
+            className="settings-select"
+          >
+            {SYNC_CONFLICT_POLICIES.map((policy) => (
+              <option key={policy} value={policy}>
+                {t(`settings.syncConflict.${policy}` as const)}
+              </option>
+            ))}
+          </select>
+          <p className="settings-hint">
+            {t("settings.syncConflict.hint")}
+          </p>
+        </div>
+
+        <div className="settings-field">
+          <label htmlFor="syncTags">{t("settings.syncTags")}</label>
+          <input
+            id="syncTags"
+            type="text"
+            value={syncTagsText ?? settings.syncTags.join(", ")}
+            onChange={(e) => setSyncTagsText(e.target.value)}
+            onBlur={handleSyncTagsBlur}
+            placeholder={t("settings.syncTags.placeholder")}
+            spellCheck={false}
+            autoCorrect="off"
+            autoCapitalize="off"
+          />
+          <p className="settings-hint">
+            {t("settings.syncTags.hint")}
+          </p>
+        </div>
+
+        <div className="settings-field">
+          <label>{t("settings.shortcuts")}</label>
+          <div className="shortcut-list">
+            {shortcutBindings.map(({ action, shortcut }) => (
+              <div key={action} className="shortcut-row">
+                <span className="shortcut-label">{t(`settings.shortcut.${action}`)}</span>
+                <div className="shortcut-editor">
+                  {recordingAction === action ? (
+                    <div
+                      ref={shortcutInputRef}
+                      className="shortcut-input recording"
+                      tabIndex={0}
+                      onKeyDown={handleShortcutKeyDown}
+                      onKeyUp={handleShortcutKeyUp}
+                      onBlur={cancelRecordingShortcut}
+                    >
+                      {recordedKeys.length > 0
+                        ? formatShortcutForDisplay(recordedKeys.join("+"))
+                        : t("settings.globalShortcut.recording")}
+                    </div>
+                  ) : (
+                    <button
+                      type="button"
+                      className={`shortcut-input${shortcut ? "" : " unbound"}`}
+                      onClick={() => startRecordingShortcut(action)}
+                    >
+                      {shortcut ? formatShortcutForDisplay(shortcut) : t("settings.shortcut.none")}
+                    </button>
+                  )}
+                  {recordingAction === action ? (
+                    <button
+                      type="button"
+                      className="shortcut-cancel"
+                      onMouseDown={(e) => e.preventDefault()}
+                      onClick={cancelRecordingShortcut}
+                    >
+                      {t("common.cancel")}
+                    </button>
+                  ) : (
+                    // The window toggle can't be unbound
+                    action !== "toggleWindow" && shortcut && (
+                      <button
+                        type="button"
+                        className="shortcut-cancel"
+                        onClick={() => handleClearShortcut(action)}
+                      >
+                        {t("settings.shortcut.clear")}
+                      </button>
+                    )
+                  )}
+                </div>
               </div>
-            ) : (
-              <button
-                type="button"
-                className="shortcut-input"
-                onClick={startRecordingShortcut}
-              >
-                {formatShortcutForDisplay(settings.globalShortcut)}
-              </button>
-            )}
-            {isRecordingShortcut && (
-              <button
-                type="button"
-                className="shortcut-cancel"
-                onMouseDown={(e) => e.preventDefault()}
-                onClick={cancelRecordingShortcut}
-              >
-                {t("common.cancel")}
-              </button>
-            )}
+            ))}
           </div>
           <p className="settings-hint">
-            {t("settings.globalShortcut.hint")}
+            {t("settings.shortcuts.hint")}
+          </p>
+        </div>
+
+        <div className="settings-field settings-checkbox">
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={settings.windowFollowsCursor}
+              onChange={(e) =>
+                handleChange("windowFollowsCursor", e.target.checked)
+              }
+            />
+            <span className="checkbox-text">
+              {t("settings.windowFollowsCursor")}
+            </span>
+          </label>
+          <p className="settings-hint">
+            {t("settings.windowFollowsCursor.hint")}
           </p>
         </div>
       </div>
@@ -1309,6 +1712,14 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
                 {t("settings.bundledServerToken.hint")}
               </p>
             </div>
+
+            {settings.bundledServerToken && localIpAddresses.length > 0 && (
+              <DevicePairing
+                serverUrl={`http://${localIpAddresses[0]}:${getServerPort()}`}
+                canManage
+                canClaim={false}
+              />
+            )}
           </>
         )}
 
@@ -1329,6 +1740,7 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
               <p className="settings-hint">
                 {t("settings.serverUrl.hint")}
               </p>
+              <ServerDiscovery onSelect={(url) => handleChange("serverAddress", url)} />
             </div>
 
             <div className="settings-field">
@@ -1366,6 +1778,27 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
               </p>
             </div>
 
+            <div className="settings-field">
+              <label>{t("settings.serverProfiles")}</label>
+              <ServerProfiles
+                profiles={settings.serverProfiles}
+                serverAddress={settings.serverAddress}
+                token={settings.externalServerToken}
+                onUse={handleUseProfile}
+                onChange={handleProfilesChange}
+              />
+              <p className="settings-hint">
+                {t("settings.serverProfiles.hint")}
+              </p>
+            </div>
+
+            <DevicePairing
+              serverUrl={settings.serverAddress}
+              canManage={!!settings.externalServerToken}
+              canClaim
+              onClaimed={handlePairingClaimed}
+            />
+
             {serverInfo && (
               <div className="settings-server-info">
                 <h4>{t("settings.serverInfo")}</h4>
@@ -1402,6 +1835,12 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
         )}
       </div>
 
+      <PeerSync
+        enabled={settings.p2pSyncEnabled}
+        syncKey={settings.p2pSyncKey}
+        onChange={handleP2pSyncChange}
+      />
+
       {settings.useBundledServer && (
         <div className="settings-section">
           <h3>{t("settings.storage")}</h3>
@@ -1527,6 +1966,18 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
               {t("settings.export.hint")}
             </p>
           </div>
+          <div className="settings-field settings-checkbox">
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={excludeSensitive}
+                onChange={(e) => setExcludeSensitive(e.target.checked)}
+              />
+              <span className="checkbox-text">
+                {t("settings.export.excludeSensitive")}
+              </span>
+            </label>
+          </div>
           <div className="settings-field">
             <label>{t("settings.import")}</label>
             <button
@@ -1547,6 +1998,65 @@ export function SettingsDialog({ isOpen, onClose, onThemeChange, onSyntaxThemeCh
       {settings.useBundledServer && (
         <div className="settings-section">
           <h3>{t("settings.dataManagement")}</h3>
+          <div className="settings-field">
+            <label>{t("settings.cleanNow")}</label>
+            {!cleanupPreview ? (
+              <>
+                <button
+                  type="button"
+                  className="settings-btn"
+                  onClick={handlePreviewCleanup}
+                  disabled={cleaningUp}
+                >
+                  {cleaningUp ? t("settings.cleanNow.checking") : t("settings.cleanNow.button")}
+                </button>
+                <p className="settings-hint">
+                  {t("settings.cleanNow.hint")}
+                </p>
+              </>
+            ) : (
+              <div className="clear-confirm">
+                <p className="clear-confirm-message">
+                  {t("settings.cleanNow.confirm", {
+                    count: cleanupPreview.deleted_clips,
+                    shortUrls: cleanupPreview.deleted_short_urls,
+                  })}
+                </p>
+                {cleanupPreview.clips.length > 0 && (
+                  <ul className="clear-confirm-message cleanup-preview-list">
+                    {cleanupPreview.clips.slice(0, CLEANUP_PREVIEW_CLIPS).map((clip) => (
+                      <li key={clip.id}>{clip.original_filename ?? clip.preview}</li>
+                    ))}
+                    {cleanupPreview.clips.length > CLEANUP_PREVIEW_CLIPS && (
+                      <li>
+                        {t("settings.cleanNow.more", {
+                          count: cleanupPreview.clips.length - CLEANUP_PREVIEW_CLIPS,
+                        })}
+                      </li>
+                    )}
+                  </ul>
+                )}
+                <div className="clear-confirm-buttons">
+                  <button
+                    type="button"
+                    className="settings-btn secondary"
+                    onClick={() => setCleanupPreview(null)}
+                    disabled={cleaningUp}
+                  >
+                    {t("common.cancel")}
+                  </button>
+                  <button
+                    type="button"
+                    className="settings-btn danger"
+                    onClick={handleCleanNow}
+                    disabled={cleaningUp}
+                  >
+                    {cleaningUp ? t("settings.cleanNow.cleaning") : t("settings.cleanNow.confirmButton")}
+                  </button>
+                </div>
+              </div>
+            )}
+          </div>
           <div className="settings-field">
             <label>{t("settings.clearAllData")}</label>
             {!showClearConfirm ? (
@@ -0,0 +1,58 @@
+import { useI18n } from "@unwritten-codes/clipper-ui";
+import { useEnsureWindowSize } from "../hooks/useEnsureWindowSize";
+import "./SyncConflictDialog.css";
+
+/** A clip from another device that arrived just after a copy here */
+export interface SyncConflict {
+  id: string;
+  content: string;
+  device: string;
+  localContent: string;
+}
+
+interface SyncConflictDialogProps {
+  conflict: SyncConflict | null;
+  onKeepLocal: () => void;
+  onUseRemote: () => void;
+}
+
+export function SyncConflictDialog({
+  conflict,
+  onKeepLocal,
+  onUseRemote,
+}: SyncConflictDialogProps) {
+  const { t } = useI18n();
+
+  useEnsureWindowSize(conflict !== null, 550, 500);
+
+  if (!conflict) return null;
+
+  return (
+    <div className="sync-conflict-backdrop" onClick={onKeepLocal}>
+      <div className="sync-conflict-dialog" onClick={(e) => e.stopPropagation()}>
+        <h2>{t("syncConflict.title")}</h2>
+        <p className="sync-conflict-message">
+          {t("syncConflict.message").replace("{device}", conflict.device)}
+        </p>
+
+        <div className="sync-conflict-choice">
+          <span className="sync-conflict-label">{t("syncConflict.local")}</span>
+          <pre className="sync-conflict-preview">{conflict.localContent}</pre>
+        </div>
+        <div className="sync-conflict-choice">
+          <span className="sync-conflict-label">{conflict.device}</span>
+          <pre className="sync-conflict-preview">{conflict.content}</pre>
+        </div>
+
+        <div className="sync-conflict-footer">
+          <button type="button" className="sync-conflict-btn secondary" onClick={onKeepLocal}>
+            {t("syncConflict.keepLocal")}
+          </button>
+          <button type="button" className="sync-conflict-btn primary" onClick={onUseRemote}>
+            {t("syncConflict.useRemote").replace("{device}", conflict.device)}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -55,8 +55,8 @@ export function useFetchCleanupConfig(): CleanupConfig | null {
     // Fetch on mount
     fetchConfig();
 
-    // Re-fetch when server is switched
-    const unlistenServerSwitched = listen("server-switched", () => {
+    // Re-fetch when the app switches servers
+    const unlistenServerChanged = listen("server-changed", () => {
       fetchConfig();
     });
 
@@ -66,7 +66,7 @@ export function useFetchCleanupConfig(): CleanupConfig | null {
     });
 
     return () => {
-      unlistenServerSwitched.then((fn) => fn());
+      unlistenServerChanged.then((fn) => fn());
       unlistenDataCleared.then((fn) => fn());
     };
   }, []);
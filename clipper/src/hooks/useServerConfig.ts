@@ -64,8 +64,8 @@ export function useFetchServerConfig(): ServerConfig | null {
     // Fetch on mount
     fetchConfig();
 
-    // Re-fetch when server is switched
-    const unlistenServerSwitched = listen("server-switched", () => {
+    // Re-fetch when the app switches servers
+    const unlistenServerChanged = listen("server-changed", () => {
       fetchConfig();
     });
 
@@ -75,7 +75,7 @@ export function useFetchServerConfig(): ServerConfig | null {
     });
 
     return () => {
-      unlistenServerSwitched.then((fn) => fn());
+      unlistenServerChanged.then((fn) => fn());
       unlistenDataCleared.then((fn) => fn());
     };
   }, []);
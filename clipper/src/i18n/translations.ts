@@ -19,7 +19,7 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.openOnStartup": "Open main window on startup",
     "settings.openOnStartup.hint": "Show the main window when the app starts. If disabled, the app will start minimized to the system tray.",
     "settings.startOnLogin": "Start application on login",
-    "settings.startOnLogin.hint": "Automatically start Clipper when you log in to your computer.",
+    "settings.startOnLogin.hint": "Automatically start Clipper in the system tray when you log in to your computer.",
 
     // Server
     "settings.server": "Server",
@@ -38,9 +38,19 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.serverUrl": "Server URL",
     "settings.serverUrl.placeholder": "http://localhost:3000",
     "settings.serverUrl.hint": "Enter the URL of your external clipper-server.",
+    "settings.discovery.search": "Find on Network",
+    "settings.discovery.searching": "Searching...",
+    "settings.discovery.empty": "No Clipper servers found on the local network.",
+    "settings.discovery.tokenRequired": "token required",
     "settings.serverToken": "Access Token",
     "settings.serverToken.placeholder": "Enter token (optional)",
     "settings.serverToken.hint": "Bearer token for authenticating with the external server. Leave empty if the server doesn't require authentication.",
+    "settings.serverProfiles": "Server Profiles",
+    "settings.serverProfiles.hint": "Save the server and token above under a name to switch back to them later, here or from the tray's Server menu. clipper-cli can use them with --profile.",
+    "settings.serverProfiles.name.placeholder": "Profile name, e.g. Work",
+    "settings.serverProfiles.save": "Save as Profile",
+    "settings.serverProfiles.use": "Use this server",
+    "settings.serverProfiles.delete": "Delete profile",
     "settings.bundledServerToken": "Access Token",
     "settings.bundledServerToken.placeholder": "Enter token (optional)",
     "settings.bundledServerToken.hint": "Set a token to require authentication when accessing the server from other devices. Leave empty to allow unauthenticated access.",
@@ -48,6 +58,31 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.token.hide": "Hide token",
     "settings.token.generate": "Generate token",
 
+    // Device Pairing
+    "settings.pairing": "Paired Devices",
+    "settings.pairing.pairDevice": "Pair a Device",
+    "settings.pairing.hint": "Show a one-time code to give another device its own token. Paired devices can be revoked without changing the access token.",
+    "settings.pairing.expires": "Expires at {time}",
+    "settings.pairing.done": "Done",
+    "settings.pairing.pairedAt": "Paired {time}",
+    "settings.pairing.lastUsed": "Last used {time}",
+    "settings.pairing.revoke": "Revoke",
+    "settings.pairing.revokeConfirm": "Confirm Revoke",
+    "settings.pairing.claim": "Pair",
+    "settings.pairing.claim.placeholder": "Pairing code or link",
+    "settings.pairing.claim.hint": "Enter a pairing code shown by another Clipper app to get a token for this device.",
+    "settings.pairing.claimed": "Paired with the server. Close settings to reconnect.",
+
+    // Peer-to-peer sync
+    "settings.p2p": "Peer-to-Peer Sync",
+    "settings.p2p.enabled": "Sync directly with other devices on this network",
+    "settings.p2p.hint": "Exchanges copied text with other Clipper apps on the local network that use the same sync key, without any server. Leave this off if your devices already share a server.",
+    "settings.p2p.syncKey": "Sync Key",
+    "settings.p2p.syncKey.placeholder": "Shared sync key",
+    "settings.p2p.syncKey.hint": "Enter the same key on every device. Connections are encrypted with it, so use a generated key rather than a password.",
+    "settings.p2p.peers": "Connected Devices",
+    "settings.p2p.peers.empty": "No other devices found yet.",
+
     // External Server Info
     "settings.serverInfo": "Server Configuration",
     "settings.serverInfo.version": "Server Version",
@@ -77,6 +112,7 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.export.button": "Export to File",
     "settings.export.exporting": "Exporting...",
     "settings.export.hint": "Export all clips and attachments to a tar.gz archive file.",
+    "settings.export.excludeSensitive": "Leave out sensitive clips",
     "settings.export.error": "Export failed",
     "settings.import": "Import Clips",
     "settings.import.button": "Import from File",
@@ -88,6 +124,16 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
 
     // Data Management
     "settings.dataManagement": "Data Management",
+    "settings.cleanNow": "Clean Up Now",
+    "settings.cleanNow.button": "Clean Now",
+    "settings.cleanNow.checking": "Checking...",
+    "settings.cleanNow.hint": "Delete the clips that auto-cleanup would delete, without waiting for the next run. You'll see what will be deleted first.",
+    "settings.cleanNow.nothing": "Nothing to clean up",
+    "settings.cleanNow.confirm": "This will delete {count} clips and {shortUrls} expired share links:",
+    "settings.cleanNow.more": "and {count} more",
+    "settings.cleanNow.cleaning": "Cleaning...",
+    "settings.cleanNow.confirmButton": "Delete",
+    "settings.cleanNow.error": "Cleanup failed",
     "settings.clearAllData": "Clear All Data",
     "settings.clearAllData.button": "Clear All Data",
     "settings.clearAllData.hint": "This will permanently delete all clips and attachments. This action cannot be undone.",
@@ -106,13 +152,53 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     // Notifications Settings
     "settings.notifications": "Show notifications",
     "settings.notifications.hint": "Show toast notifications for clipboard actions and sync events.",
+    "settings.captureImages": "Save copied images",
+    "settings.captureImages.hint": "Save images copied to the clipboard, such as screenshots, as image clips.",
+    "settings.maxImageSize": "Maximum image size (MB)",
+    "settings.maxImageSize.hint": "Larger copied images are not saved. The server's upload limit also applies.",
+    "settings.copiedFiles": "Copied files",
+    "settings.copiedFiles.upload": "Upload files",
+    "settings.copiedFiles.list": "Save file list only",
+    "settings.copiedFiles.off": "Ignore",
+    "settings.copiedFiles.hint": "What to save when files are copied in Finder or Explorer. Folders and files over the size limit are saved as a list of paths.",
+    "settings.maxCopiedFileSize": "Maximum file size to upload (MB)",
+    "settings.maxCopiedFileSize.hint": "Larger files are listed instead of uploaded. The server's upload limit also applies.",
+    "settings.capturePrimarySelection": "Save selected text",
+    "settings.capturePrimarySelection.hint": "Also save text selected with the mouse (the primary selection, pasted with the middle button), tagged $primary.",
+    "settings.restoreSelection": "Copy clips to",
+    "settings.restoreSelection.clipboard": "Clipboard",
+    "settings.restoreSelection.primary": "Primary selection",
+    "settings.restoreSelection.both": "Clipboard and primary selection",
+    "settings.restoreSelection.hint": "Where clips copied from the history go: the clipboard is pasted with Ctrl+V, the primary selection with the middle button.",
+    "settings.syncConflict": "Copies on two devices at once",
+    "settings.syncConflict.keep-both": "Keep this device's copy",
+    "settings.syncConflict.newest-wins": "Use the newest copy",
+    "settings.syncConflict.prompt": "Ask which one to use",
+    "settings.syncConflict.hint": "When a clip from another device arrives just after you copied something here. Both clips are saved either way; this decides which one is on the clipboard. The newest copy is the one the server received last, so every device ends up with the same one.",
+    "settings.syncTags": "Synced tags",
+    "settings.syncTags.placeholder": "All clips",
+    "settings.syncTags.hint": "Comma-separated tags. When set, only clips from other devices with at least one of these tags reach this device's clipboard and clip notifications. Clips copied here are always synced.",
 
     // Global Shortcut Settings
-    "settings.globalShortcut": "Global Shortcut",
-    "settings.globalShortcut.hint": "Keyboard shortcut to show/hide the main window from anywhere.",
+    "settings.shortcuts": "Global Shortcuts",
+    "settings.shortcuts.hint": "Keyboard shortcuts that work from anywhere. Click a shortcut to change it.",
     "settings.globalShortcut.recording": "Press keys...",
+    "settings.shortcut.toggleWindow": "Show/hide main window",
+    "settings.shortcut.pasteLastClip": "Copy latest clip to clipboard",
+    "settings.shortcut.pastePlainText": "Convert clipboard to plain text",
+    "settings.shortcut.quickPick": "Search clips",
+    "settings.shortcut.captureScreenshot": "Capture screenshot",
+    "settings.shortcut.quickNote": "Quick note",
+    "settings.shortcut.palette": "Clip palette",
+    "settings.shortcut.pasteStackToggle": "Toggle paste stack",
+    "settings.shortcut.pasteStackPop": "Next paste stack item",
+    "settings.shortcut.appendModeToggle": "Toggle append mode",
+    "settings.shortcut.none": "Not set",
+    "settings.shortcut.clear": "Clear",
     "settings.shortcut.updated": "Shortcut updated",
     "settings.shortcut.error": "Failed to set shortcut",
+    "settings.windowFollowsCursor": "Open on the screen with the mouse cursor",
+    "settings.windowFollowsCursor.hint": "When the show/hide shortcut opens the main window, center it on the screen the mouse cursor is on instead of where it was last.",
 
     // Connection Error - Tauri specific additions
     "connectionError.reason.bundledServer": "The bundled server failed to start",
@@ -130,6 +216,42 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     // File Upload Errors
     "toast.fileTooLarge": "File too large: {filename} ({size} MB). Maximum size is {maxSize} MB.",
     "toast.fileUploadFailed": "Failed to upload file: {filename}",
+    "toast.pasteStackOn": "Paste stack on: copied text is queued",
+    "toast.pasteStackOff": "Paste stack off",
+    "toast.appendModeOn": "Append mode on: copies are added to one clip",
+    "toast.appendModeOff": "Append mode off",
+    "status.appendMode": "Appending",
+    "status.appendModeStop": "Copies are appended to one clip. Click to stop.",
+    "toast.capturePaused": "Clipboard capture paused: copies are not saved",
+    "toast.captureResumed": "Clipboard capture resumed",
+    "toast.screenshotSaved": "Screenshot saved",
+    "toast.screenshotFailed": "Failed to capture screenshot: {error}",
+    "toast.syncConflictKept": "Kept your copy on the clipboard; the clip from {device} is in the history",
+    "toast.clipboardPushed": "A clip sent from another device is on the clipboard",
+
+    // Sync conflicts
+    "syncConflict.title": "Copied on two devices at once",
+    "syncConflict.message": "{device} copied something just as you did here. Both clips are saved; choose which one to keep on the clipboard.",
+    "syncConflict.local": "This device",
+    "syncConflict.keepLocal": "Keep mine",
+    "syncConflict.useRemote": "Use {device}'s",
+
+    // Quick Note
+    "quickNote.placeholder": "Type a note...",
+    "quickNote.hint": "Enter to save · Shift+Enter for a new line · Esc to close",
+    "quickNote.saving": "Saving...",
+    "quickNote.error": "Failed to save note: {error}",
+
+    // Deep links
+    "deepLink.showingLinkedClip": "Showing a linked clip",
+    "deepLink.showAll": "Show all clips",
+    "deepLink.clipNotFound": "The linked clip was not found",
+
+    // Palette
+    "palette.hint": "↑↓ to select · Enter to copy · Ctrl/Cmd+Enter to paste · Esc to close",
+    "palette.sensitive": "Sensitive clip",
+    "palette.empty": "(empty)",
+    "palette.error": "Failed: {error}",
 
     // Max Upload Size
     "settings.maxUploadSize": "Maximum Upload Size (MB)",
@@ -174,7 +296,7 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.openOnStartup": "启动时打开主窗口",
     "settings.openOnStartup.hint": "应用启动时显示主窗口。如果禁用，应用将最小化到系统托盘。",
     "settings.startOnLogin": "登录时启动应用",
-    "settings.startOnLogin.hint": "登录计算机时自动启动 Clipper。",
+    "settings.startOnLogin.hint": "登录计算机时自动在系统托盘中启动 Clipper。",
 
     // Server
     "settings.server": "服务器",
@@ -193,9 +315,19 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.serverUrl": "服务器地址",
     "settings.serverUrl.placeholder": "http://localhost:3000",
     "settings.serverUrl.hint": "输入外部 clipper-server 的地址。",
+    "settings.discovery.search": "在网络中查找",
+    "settings.discovery.searching": "正在查找...",
+    "settings.discovery.empty": "未在本地网络中找到 Clipper 服务器。",
+    "settings.discovery.tokenRequired": "需要令牌",
     "settings.serverToken": "访问令牌",
     "settings.serverToken.placeholder": "输入令牌（可选）",
     "settings.serverToken.hint": "用于外部服务器身份验证的令牌。如果服务器不需要身份验证，请留空。",
+    "settings.serverProfiles": "服务器配置",
+    "settings.serverProfiles.hint": "将上面的服务器和令牌以名称保存，之后可在此处或托盘的“服务器”菜单中切换回来。clipper-cli 可通过 --profile 使用它们。",
+    "settings.serverProfiles.name.placeholder": "配置名称，例如 工作",
+    "settings.serverProfiles.save": "保存为配置",
+    "settings.serverProfiles.use": "使用此服务器",
+    "settings.serverProfiles.delete": "删除配置",
     "settings.bundledServerToken": "访问令牌",
     "settings.bundledServerToken.placeholder": "输入令牌（可选）",
     "settings.bundledServerToken.hint": "设置令牌以在其他设备访问服务器时要求身份验证。留空则允许无需身份验证即可访问。",
@@ -203,6 +335,31 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.token.hide": "隐藏令牌",
     "settings.token.generate": "生成令牌",
 
+    // Device Pairing
+    "settings.pairing": "已配对设备",
+    "settings.pairing.pairDevice": "配对设备",
+    "settings.pairing.hint": "显示一次性配对码，为其他设备分配独立的令牌。已配对的设备可以单独撤销，无需更换访问令牌。",
+    "settings.pairing.expires": "{time} 过期",
+    "settings.pairing.done": "完成",
+    "settings.pairing.pairedAt": "配对于 {time}",
+    "settings.pairing.lastUsed": "最近使用于 {time}",
+    "settings.pairing.revoke": "撤销",
+    "settings.pairing.revokeConfirm": "确认撤销",
+    "settings.pairing.claim": "配对",
+    "settings.pairing.claim.placeholder": "配对码或配对链接",
+    "settings.pairing.claim.hint": "输入其他 Clipper 应用显示的配对码，为本设备获取令牌。",
+    "settings.pairing.claimed": "已与服务器配对。关闭设置后将重新连接。",
+
+    // Peer-to-peer sync
+    "settings.p2p": "点对点同步",
+    "settings.p2p.enabled": "与本网络中的其他设备直接同步",
+    "settings.p2p.hint": "无需服务器，直接与本地网络中使用相同同步密钥的其他 Clipper 应用交换复制的文本。如果设备已共用同一服务器，请保持关闭。",
+    "settings.p2p.syncKey": "同步密钥",
+    "settings.p2p.syncKey.placeholder": "共享的同步密钥",
+    "settings.p2p.syncKey.hint": "在每台设备上输入相同的密钥。连接使用该密钥加密，请使用生成的密钥而不是自定义密码。",
+    "settings.p2p.peers": "已连接设备",
+    "settings.p2p.peers.empty": "尚未发现其他设备。",
+
     // External Server Info
     "settings.serverInfo": "服务器配置",
     "settings.serverInfo.version": "服务器版本",
@@ -232,6 +389,7 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     "settings.export.button": "导出到文件",
     "settings.export.exporting": "导出中...",
     "settings.export.hint": "将所有剪贴和附件导出到 tar.gz 归档文件。",
+    "settings.export.excludeSensitive": "不导出敏感剪贴",
     "settings.export.error": "导出失败",
     "settings.import": "导入剪贴",
     "settings.import.button": "从文件导入",
@@ -243,6 +401,16 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
 
     // Data Management
     "settings.dataManagement": "数据管理",
+    "settings.cleanNow": "立即清理",
+    "settings.cleanNow.button": "立即清理",
+    "settings.cleanNow.checking": "检查中...",
+    "settings.cleanNow.hint": "立即删除自动清理将会删除的剪贴，无需等待下次运行。删除前会先列出要删除的内容。",
+    "settings.cleanNow.nothing": "没有需要清理的内容",
+    "settings.cleanNow.confirm": "将删除 {count} 条剪贴和 {shortUrls} 个过期的分享链接：",
+    "settings.cleanNow.more": "以及另外 {count} 条",
+    "settings.cleanNow.cleaning": "清理中...",
+    "settings.cleanNow.confirmButton": "删除",
+    "settings.cleanNow.error": "清理失败",
     "settings.clearAllData": "清除所有数据",
     "settings.clearAllData.button": "清除所有数据",
     "settings.clearAllData.hint": "这将永久删除所有剪贴和附件。此操作无法撤销。",
@@ -261,13 +429,53 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     // Notifications Settings
     "settings.notifications": "显示通知",
     "settings.notifications.hint": "显示剪贴板操作和同步事件的通知。",
+    "settings.captureImages": "保存复制的图片",
+    "settings.captureImages.hint": "将复制到剪贴板的图片（例如截图）保存为图片剪贴。",
+    "settings.maxImageSize": "最大图片大小 (MB)",
+    "settings.maxImageSize.hint": "超过此大小的图片不会被保存。服务器的上传限制同样适用。",
+    "settings.copiedFiles": "复制的文件",
+    "settings.copiedFiles.upload": "上传文件",
+    "settings.copiedFiles.list": "仅保存文件列表",
+    "settings.copiedFiles.off": "忽略",
+    "settings.copiedFiles.hint": "在访达或资源管理器中复制文件时保存的内容。文件夹和超过大小限制的文件会保存为路径列表。",
+    "settings.maxCopiedFileSize": "上传文件的最大大小 (MB)",
+    "settings.maxCopiedFileSize.hint": "更大的文件只会被列出，不会上传。服务器的上传限制同样适用。",
+    "settings.capturePrimarySelection": "保存选中的文本",
+    "settings.capturePrimarySelection.hint": "同时保存用鼠标选中的文本（主选区，用鼠标中键粘贴），并添加 $primary 标签。",
+    "settings.restoreSelection": "复制剪贴到",
+    "settings.restoreSelection.clipboard": "剪贴板",
+    "settings.restoreSelection.primary": "主选区",
+    "settings.restoreSelection.both": "剪贴板和主选区",
+    "settings.restoreSelection.hint": "从历史记录复制的剪贴放在哪里：剪贴板用 Ctrl+V 粘贴，主选区用鼠标中键粘贴。",
+    "settings.syncConflict": "两台设备同时复制",
+    "settings.syncConflict.keep-both": "保留本设备的内容",
+    "settings.syncConflict.newest-wins": "使用最新的内容",
+    "settings.syncConflict.prompt": "询问使用哪一个",
+    "settings.syncConflict.hint": "在这里复制后，其他设备的剪贴紧接着到达时的处理方式。两个剪贴都会保存，此设置决定剪贴板上保留哪一个。最新的内容是服务器最后收到的剪贴，因此所有设备最终都会得到同一个。",
+    "settings.syncTags": "同步的标签",
+    "settings.syncTags.placeholder": "所有剪贴",
+    "settings.syncTags.hint": "以逗号分隔的标签。设置后，只有带有其中至少一个标签的其他设备剪贴才会同步到本设备的剪贴板和剪贴通知。在这里复制的剪贴始终会同步。",
 
     // Global Shortcut Settings
-    "settings.globalShortcut": "全局快捷键",
-    "settings.globalShortcut.hint": "从任何位置显示/隐藏主窗口的快捷键。",
+    "settings.shortcuts": "全局快捷键",
+    "settings.shortcuts.hint": "在任何位置都可使用的快捷键。点击快捷键可进行修改。",
     "settings.globalShortcut.recording": "请按下快捷键...",
+    "settings.shortcut.toggleWindow": "显示/隐藏主窗口",
+    "settings.shortcut.pasteLastClip": "复制最新剪贴到剪贴板",
+    "settings.shortcut.pastePlainText": "将剪贴板转换为纯文本",
+    "settings.shortcut.quickPick": "搜索剪贴",
+    "settings.shortcut.captureScreenshot": "截图",
+    "settings.shortcut.quickNote": "快速笔记",
+    "settings.shortcut.palette": "剪贴面板",
+    "settings.shortcut.pasteStackToggle": "切换粘贴栈",
+    "settings.shortcut.pasteStackPop": "粘贴栈下一项",
+    "settings.shortcut.appendModeToggle": "切换追加模式",
+    "settings.shortcut.none": "未设置",
+    "settings.shortcut.clear": "清除",
     "settings.shortcut.updated": "快捷键已更新",
     "settings.shortcut.error": "设置快捷键失败",
+    "settings.windowFollowsCursor": "在鼠标所在的屏幕上打开",
+    "settings.windowFollowsCursor.hint": "使用显示/隐藏快捷键打开主窗口时，将其居中显示在鼠标所在的屏幕上，而不是上次的位置。",
 
     // Connection Error - Tauri specific additions
     "connectionError.reason.bundledServer": "内置服务器启动失败",
@@ -285,6 +493,42 @@ export const tauriExtraTranslations: Record<Language, Record<string, string>> =
     // File Upload Errors
     "toast.fileTooLarge": "文件过大：{filename}（{size} MB）。最大允许 {maxSize} MB。",
     "toast.fileUploadFailed": "上传文件失败：{filename}",
+    "toast.pasteStackOn": "粘贴栈已开启：复制的文本将依次排队",
+    "toast.pasteStackOff": "粘贴栈已关闭",
+    "toast.appendModeOn": "追加模式已开启：复制的内容将添加到同一剪贴",
+    "toast.appendModeOff": "追加模式已关闭",
+    "status.appendMode": "追加中",
+    "status.appendModeStop": "复制的内容将追加到同一剪贴，点击停止。",
+    "toast.capturePaused": "已暂停记录剪贴板：复制的内容不会被保存",
+    "toast.captureResumed": "已恢复记录剪贴板",
+    "toast.screenshotSaved": "截图已保存",
+    "toast.screenshotFailed": "截图失败：{error}",
+    "toast.syncConflictKept": "已在剪贴板上保留您的内容，来自 {device} 的剪贴在历史记录中",
+    "toast.clipboardPushed": "其他设备发送的剪贴已放到剪贴板上",
+
+    // Sync conflicts
+    "syncConflict.title": "两台设备同时复制",
+    "syncConflict.message": "{device} 在您复制的同时也复制了内容。两个剪贴都已保存，请选择剪贴板上保留哪一个。",
+    "syncConflict.local": "本设备",
+    "syncConflict.keepLocal": "保留我的",
+    "syncConflict.useRemote": "使用 {device} 的",
+
+    // Quick Note
+    "quickNote.placeholder": "输入笔记...",
+    "quickNote.hint": "Enter 保存 · Shift+Enter 换行 · Esc 关闭",
+    "quickNote.saving": "正在保存...",
+    "quickNote.error": "保存笔记失败：{error}",
+
+    // Deep links
+    "deepLink.showingLinkedClip": "正在显示链接的剪贴",
+    "deepLink.showAll": "显示全部剪贴",
+    "deepLink.clipNotFound": "未找到链接的剪贴",
+
+    // Palette
+    "palette.hint": "↑↓ 选择 · Enter 复制 · Ctrl/Cmd+Enter 粘贴 · Esc 关闭",
+    "palette.sensitive": "敏感剪贴",
+    "palette.empty": "（空）",
+    "palette.error": "失败：{error}",
 
     // Max Upload Size
     "settings.maxUploadSize": "最大上传大小 (MB)",
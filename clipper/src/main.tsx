@@ -4,6 +4,8 @@ import { I18nProvider, ApiProvider } from "@unwritten-codes/clipper-ui";
 import { TauriToastWrapper } from "./components/TauriToastWrapper";
 import { CleanupConfigWrapper } from "./components/CleanupConfigWrapper";
 import { ServerConfigWrapper } from "./components/ServerConfigWrapper";
+import { QuickNote } from "./components/QuickNote";
+import { Palette } from "./components/Palette";
 import { createTauriApiClient } from "./api/tauriClient";
 import { tauriExtraTranslations } from "./i18n/translations";
 import App from "./App";
@@ -20,21 +22,31 @@ document.documentElement.dataset.platform = detectPlatform();
 // Create the Tauri API client
 const api = createTauriApiClient();
 
+// The quick note popup and the palette load the same page with
+// ?window=quick-note and ?window=palette
+const windowKind = new URLSearchParams(window.location.search).get("window");
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
     <I18nProvider
       storageKey="clipper-tauri-language"
       extraTranslations={tauriExtraTranslations}
     >
-      <ApiProvider value={api}>
-        <TauriToastWrapper>
-          <CleanupConfigWrapper>
-            <ServerConfigWrapper>
-              <App />
-            </ServerConfigWrapper>
-          </CleanupConfigWrapper>
-        </TauriToastWrapper>
-      </ApiProvider>
+      {windowKind === "quick-note" ? (
+        <QuickNote />
+      ) : windowKind === "palette" ? (
+        <Palette />
+      ) : (
+        <ApiProvider value={api}>
+          <TauriToastWrapper>
+            <CleanupConfigWrapper>
+              <ServerConfigWrapper>
+                <App />
+              </ServerConfigWrapper>
+            </CleanupConfigWrapper>
+          </TauriToastWrapper>
+        </ApiProvider>
+      )}
     </I18nProvider>
   </React.StrictMode>
 );
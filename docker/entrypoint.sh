@@ -12,8 +12,9 @@
 set -e
 
 BACKUP_PATH="${CLIPPER_BACKUP_PATH:-/data/backup.tar.gz}"
-DB_PATH="${CLIPPER_DB_PATH:-/data/db}"
-STORAGE_PATH="${CLIPPER_STORAGE_PATH:-/data/storage}"
+DATA_DIR="${CLIPPER_DATA_DIR:-/data}"
+DB_PATH="${CLIPPER_DB_PATH:-${DATA_DIR}/db}"
+STORAGE_PATH="${CLIPPER_STORAGE_PATH:-${DATA_DIR}/storage}"
 INCLUDE_FILES="${CLIPPER_INCLUDE_FILES:-false}"
 
 # Function to create backup
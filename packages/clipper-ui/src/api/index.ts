@@ -1,5 +1,14 @@
 import { createContext, useContext } from "react";
-import { Clip, PagedResult, PagedTagResult, SearchFilters } from "../types";
+import {
+  Clip,
+  ClipAction,
+  ClipTransform,
+  MergedCursor,
+  MergedResult,
+  PagedResult,
+  PagedTagResult,
+  SearchFilters,
+} from "../types";
 
 /**
  * Convert an image blob to PNG format using canvas.
@@ -51,6 +60,16 @@ export interface ClipperApi {
     pageSize: number
   ): Promise<PagedResult>;
 
+  /** List (empty query) or search the clips of the current server and every server profile as one history (desktop app only)
+   * @param cursor - `next_cursor` of the previous page, or undefined for the first page
+   */
+  searchAllServers?: (
+    query: string,
+    filters: SearchFilters,
+    cursor: MergedCursor | undefined,
+    limit: number
+  ) => Promise<MergedResult>;
+
   /** Get a single clip by ID */
   getClip(id: string): Promise<Clip>;
 
@@ -65,14 +84,29 @@ export interface ClipperApi {
   /** Upload a file as a new clip */
   uploadFile(file: File, tags?: string[], additionalNotes?: string): Promise<Clip>;
 
-  /** Update clip tags, notes, and/or language */
+  /** Update clip tags, notes, language, color and/or icon (empty strings clear color and icon) */
   updateClip(
     id: string,
     tags?: string[],
     additionalNotes?: string | null,
-    language?: string | null
+    language?: string | null,
+    color?: string,
+    icon?: string
   ): Promise<Clip>;
 
+  /** Apply text transforms in order, creating a new clip derived from this one */
+  transformClip?: (id: string, transforms: ClipTransform[]) => Promise<Clip>;
+
+  /** Translate a clip into `to` with the server's translation provider, creating a new
+   * clip derived from this one (desktop app only) */
+  translateClip?: (id: string, to: string) => Promise<Clip>;
+
+  /** List user-defined actions (desktop app only) */
+  listActions?: () => Promise<ClipAction[]>;
+
+  /** Run a user-defined action for a clip (desktop app only) */
+  runAction?: (name: string, clipId: string) => Promise<void>;
+
   /** Delete a clip */
   deleteClip(id: string): Promise<void>;
 
@@ -91,6 +125,15 @@ export interface ClipperApi {
   /** Copy an image to clipboard by clip ID (optional, for desktop apps that can write images to clipboard) */
   copyImageToClipboard?: (clipId: string) => Promise<void>;
 
+  /** Paste a clip into the previously focused app, hiding the window (desktop app only) */
+  pasteClip?: (clipId: string) => Promise<void>;
+
+  /** Show a clip's file attachment in the file manager, so it can be dragged into other apps (desktop app only) */
+  revealAttachment?: (clipId: string) => Promise<void>;
+
+  /** Drag a clip's file attachment out of the window as a file; call from dragstart while the mouse button is down (desktop app only) */
+  dragAttachment?: (clipId: string) => Promise<void>;
+
   /** Download a file attachment */
   downloadFile(clipId: string, filename: string): Promise<void>;
 
@@ -179,7 +222,17 @@ export function createRestApiClient(
     }
     if (!response.ok) {
       const text = await response.text();
-      throw new Error(text || `HTTP ${response.status}`);
+      // The server reports errors as `{"error": "..."}`
+      let message = text;
+      try {
+        const body = JSON.parse(text);
+        if (typeof body?.error === "string") {
+          message = body.error;
+        }
+      } catch {
+        // Not JSON, show the text as is
+      }
+      throw new Error(message || `HTTP ${response.status}`);
     }
     return response.json();
   }
@@ -211,6 +264,12 @@ export function createRestApiClient(
       if (filters.tags && filters.tags.length > 0) {
         params.set("tags", filters.tags.join(","));
       }
+      if (filters.source_device) {
+        params.set("source_device", filters.source_device);
+      }
+      if (filters.skip_total) {
+        params.set("skip_total", "true");
+      }
 
       const response = await fetch(`${baseUrl}/clips?${params.toString()}`, {
         headers: getHeaders(),
@@ -241,6 +300,12 @@ export function createRestApiClient(
       if (filters.tags && filters.tags.length > 0) {
         params.set("tags", filters.tags.join(","));
       }
+      if (filters.source_device) {
+        params.set("source_device", filters.source_device);
+      }
+      if (filters.skip_total) {
+        params.set("skip_total", "true");
+      }
 
       const response = await fetch(
         `${baseUrl}/clips/search?${params.toString()}`,
@@ -311,7 +376,9 @@ export function createRestApiClient(
       id: string,
       tags?: string[],
       additionalNotes?: string | null,
-      language?: string | null
+      language?: string | null,
+      color?: string,
+      icon?: string
     ): Promise<Clip> {
       const body: Record<string, unknown> = {};
       if (tags !== undefined) {
@@ -323,6 +390,12 @@ export function createRestApiClient(
       if (language !== undefined) {
         body.language = language;
       }
+      if (color !== undefined) {
+        body.color = color;
+      }
+      if (icon !== undefined) {
+        body.icon = icon;
+      }
 
       const response = await fetch(`${baseUrl}/clips/${id}`, {
         method: "PUT",
@@ -332,6 +405,15 @@ export function createRestApiClient(
       return handleResponse<Clip>(response);
     },
 
+    async transformClip(id: string, transforms: ClipTransform[]): Promise<Clip> {
+      const response = await fetch(`${baseUrl}/clips/${id}/transform`, {
+        method: "POST",
+        headers: getHeaders("application/json"),
+        body: JSON.stringify({ transforms }),
+      });
+      return handleResponse<Clip>(response);
+    },
+
     async deleteClip(id: string): Promise<void> {
       const response = await fetch(`${baseUrl}/clips/${id}`, {
         method: "DELETE",
@@ -1,12 +1,12 @@
 import { useState, useEffect, useMemo, useRef, useCallback, memo } from "react";
 import hljs from "highlight.js";
-import { Clip, Tag, isFavorite, calculateAgeRatio, FAVORITE_TAG } from "../types";
+import { Clip, ClipAction, ClipTransform, Tag, isFavorite, isSensitive, calculateAgeRatio, FAVORITE_TAG, SENSITIVE_TAG, CLIP_TRANSFORMS } from "../types";
 import { ImagePopup } from "./ImagePopup";
 import { ShareDialog } from "./ShareDialog";
 import { LanguageSelector, LanguageId, LANGUAGES } from "./LanguageSelector";
 import { DateTag } from "./DateTag";
 import { Tooltip } from "./Tooltip";
-import { useI18n } from "../i18n";
+import { useI18n, languageNames } from "../i18n";
 import { useToast } from "./Toast";
 import { useApi } from "../api";
 import { useCleanupConfig } from "../hooks/useCleanupConfig";
@@ -188,12 +188,13 @@ export const ClipEntry = memo(function ClipEntry({
   onKeyboardExpandChange,
   onActivate,
 }: ClipEntryProps) {
-  const { t } = useI18n();
+  const { t, language } = useI18n();
   const { showToast } = useToast();
   const api = useApi();
   const cleanupConfig = useCleanupConfig();
   const serverConfig = useServerConfig();
   const favorite = isFavorite(clip);
+  const sensitive = isSensitive(clip);
   const entryRef = useRef<HTMLDivElement>(null);
 
   // Blur any focused element inside this clip entry when it becomes deactivated
@@ -219,6 +220,14 @@ export const ClipEntry = memo(function ClipEntry({
       "--clip-age-opacity": opacity,
     } as React.CSSProperties;
   }, [clip, cleanupConfig]);
+
+  // Color label is shown as a colored left border
+  const entryStyle = useMemo(() => {
+    if (!clip.color) {
+      return ageStyle;
+    }
+    return { ...ageStyle, borderLeft: `4px solid ${clip.color}` } as React.CSSProperties;
+  }, [ageStyle, clip.color]);
   // Show regular tags and $host: tags (with special styling)
   const displayTags = clip.tags.filter((tag) => !tag.startsWith("$") || tag.startsWith("$host:"));
 
@@ -276,6 +285,13 @@ export const ClipEntry = memo(function ClipEntry({
   const tagSuggestionsRef = useRef<HTMLDivElement>(null);
   const [tagToRemove, setTagToRemove] = useState<string | null>(null);
   const [removingTag, setRemovingTag] = useState(false);
+  const [contextMenuPosition, setContextMenuPosition] = useState<{ top: number; left: number } | null>(null);
+  const [contextMenuActions, setContextMenuActions] = useState<ClipAction[]>([]);
+  const [transforming, setTransforming] = useState(false);
+  const [translating, setTranslating] = useState(false);
+  // Sensitive content stays masked until clicked
+  const [revealed, setRevealed] = useState(false);
+  const masked = sensitive && !revealed;
 
   const isImage = clip.file_attachment && isImageFile(clip.file_attachment);
   const isLongContent = isLongContentByLines(clip.content);
@@ -342,6 +358,43 @@ export const ClipEntry = memo(function ClipEntry({
     }
   }, [clip.id, clip.file_attachment, isImage, api]);
 
+  // Paste into the previously focused app (desktop app only)
+  const handlePaste = useCallback(async () => {
+    if (!api.pasteClip) return;
+    try {
+      await api.pasteClip(clip.id);
+    } catch (err) {
+      console.error("Failed to paste clip:", err);
+      showToast(t("toast.pasteFailed"), "error");
+    }
+  }, [clip.id, api, showToast, t]);
+
+  // Show the attachment in the file manager (desktop app only)
+  const handleRevealAttachment = useCallback(async () => {
+    if (!api.revealAttachment) return;
+    try {
+      await api.revealAttachment(clip.id);
+    } catch (err) {
+      console.error("Failed to show attachment:", err);
+      showToast(t("toast.revealFailed"), "error");
+    }
+  }, [clip.id, api, showToast, t]);
+
+  // Drag the clip into other apps: text through the webview, attachments as
+  // real files through a native drag (desktop app only)
+  const handleDragStart = useCallback((e: React.DragEvent) => {
+    if (clip.file_attachment && api.dragAttachment) {
+      e.preventDefault();
+      api.dragAttachment(clip.id).catch((err) => {
+        console.error("Failed to drag attachment:", err);
+        showToast(t("toast.dragFailed"), "error");
+      });
+      return;
+    }
+    e.dataTransfer.effectAllowed = "copy";
+    e.dataTransfer.setData("text/plain", clip.content);
+  }, [clip.id, clip.file_attachment, clip.content, api, showToast, t]);
+
   // Handler for keyboard-triggered button activation
   const handleKeyboardButtonActivate = useCallback((action: ClipButtonAction) => {
     switch (action) {
@@ -409,19 +462,25 @@ export const ClipEntry = memo(function ClipEntry({
       setShowDeleteConfirm(true);
     };
 
+    const handlePasteRequest = () => {
+      handlePaste();
+    };
+
     const handleButtonActivate = (e: Event) => {
       const customEvent = e as CustomEvent<{ action: ClipButtonAction; buttonIndex: number }>;
       handleKeyboardButtonActivate(customEvent.detail.action);
     };
 
     entry.addEventListener("keyboard-delete-request", handleDeleteRequest);
+    entry.addEventListener("keyboard-paste-request", handlePasteRequest);
     entry.addEventListener("keyboard-button-activate", handleButtonActivate);
 
     return () => {
       entry.removeEventListener("keyboard-delete-request", handleDeleteRequest);
+      entry.removeEventListener("keyboard-paste-request", handlePasteRequest);
       entry.removeEventListener("keyboard-button-activate", handleButtonActivate);
     };
-  }, [handleKeyboardButtonActivate]);
+  }, [handleKeyboardButtonActivate, handlePaste]);
 
   const handleCopyClick = async (e: React.MouseEvent) => {
     e.stopPropagation();
@@ -833,6 +892,110 @@ export const ClipEntry = memo(function ClipEntry({
     }
   }, [clip.id, clip.tags, clip.additional_notes, api, onBeforeClipModified, onClipUpdated, showToast, t]);
 
+  // Transforms only apply to text clips
+  const canTransform = !clip.file_attachment && !!api.transformClip;
+
+  // Clips are translated into the UI language; sensitive clips never leave the server
+  const canTranslate =
+    !clip.file_attachment &&
+    !sensitive &&
+    !!api.translateClip &&
+    clip.detected_language !== language;
+
+  // Open the context menu on right-click with paste, the sensitive toggle, translation,
+  // transforms and user-defined actions
+  const handleContextMenu = async (e: React.MouseEvent) => {
+    e.preventDefault();
+    const position = { top: e.clientY, left: e.clientX };
+    let actions: ClipAction[] = [];
+    if (api.listActions) {
+      try {
+        actions = await api.listActions();
+      } catch (err) {
+        console.error("Failed to list actions:", err);
+      }
+    }
+    setContextMenuActions(actions);
+    setContextMenuPosition(position);
+  };
+
+  // Apply a transform; the result arrives as a new clip via the usual notifications
+  const handleTransform = async (transform: ClipTransform) => {
+    if (!api.transformClip) return;
+    setTransforming(true);
+    try {
+      await api.transformClip(clip.id, [transform]);
+      showToast(t("toast.clipTransformed"));
+      setContextMenuPosition(null);
+    } catch (err) {
+      console.error("Failed to transform clip:", err);
+      showToast(t("toast.transformFailed"), "error");
+    } finally {
+      setTransforming(false);
+    }
+  };
+
+  // Translate into the UI language; the result arrives as a new clip via the usual notifications
+  const handleTranslate = async () => {
+    if (!api.translateClip) return;
+    setTranslating(true);
+    try {
+      await api.translateClip(clip.id, language);
+      showToast(t("toast.clipTranslated"));
+      setContextMenuPosition(null);
+    } catch (err) {
+      console.error("Failed to translate clip:", err);
+      showToast(t("toast.translateFailed"), "error");
+    } finally {
+      setTranslating(false);
+    }
+  };
+
+  // Mark the clip as sensitive, or not. Marked clips are masked again.
+  const handleToggleSensitive = async () => {
+    setContextMenuPosition(null);
+    const newTags = sensitive
+      ? clip.tags.filter((tag) => tag !== SENSITIVE_TAG)
+      : [...clip.tags, SENSITIVE_TAG];
+    try {
+      onBeforeClipModified?.(clip.id);
+      const updatedClip = await api.updateClip(clip.id, newTags, clip.additional_notes || undefined);
+      setRevealed(false);
+      onClipUpdated?.(updatedClip);
+      showToast(t("toast.clipUpdated"));
+    } catch (err) {
+      console.error("Failed to update clip:", err);
+      showToast(t("toast.updateFailed"), "error");
+    }
+  };
+
+  // Run a user-defined action for this clip
+  const handleRunAction = async (action: ClipAction) => {
+    if (!api.runAction) return;
+    setContextMenuPosition(null);
+    try {
+      await api.runAction(action.name, clip.id);
+    } catch (err) {
+      console.error("Failed to run action:", err);
+      showToast(t("toast.actionFailed", { name: action.name }), "error");
+    }
+  };
+
+  // Close the context menu on Escape
+  useEffect(() => {
+    if (!contextMenuPosition) return;
+
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === "Escape") {
+        e.preventDefault();
+        setContextMenuPosition(null);
+      }
+    };
+
+    document.addEventListener("keydown", handleKeyDown);
+    return () => document.removeEventListener("keydown", handleKeyDown);
+  }, [contextMenuPosition]);
+
   // Handle click on the clip entry itself - activate it and toggle expand/collapse for long content
   const handleEntryClick = () => {
     // Always activate on click
@@ -862,13 +1025,30 @@ export const ClipEntry = memo(function ClipEntry({
       <div
         ref={entryRef}
         className={clipEntryClassName}
-        style={ageStyle}
+        style={entryStyle}
         onClick={handleEntryClick}
+        onContextMenu={handleContextMenu}
         data-clip-id={clip.id}
         tabIndex={isFocused ? 0 : -1}
       >
         <div className="clip-header">
           <div className="clip-header-left">
+            <span
+              className="clip-drag-handle"
+              draggable
+              onDragStart={handleDragStart}
+              title={t("tooltip.dragOut")}
+            >
+              <svg width="10" height="14" viewBox="0 0 10 14" fill="currentColor">
+                <circle cx="2.5" cy="2.5" r="1.5" />
+                <circle cx="7.5" cy="2.5" r="1.5" />
+                <circle cx="2.5" cy="7" r="1.5" />
+                <circle cx="7.5" cy="7" r="1.5" />
+                <circle cx="2.5" cy="11.5" r="1.5" />
+                <circle cx="7.5" cy="11.5" r="1.5" />
+              </svg>
+            </span>
+            {clip.icon && <span className="clip-icon">{clip.icon}</span>}
             <DateTag
               dateStr={clip.created_at}
               onSetStartDate={onSetStartDate}
@@ -997,7 +1177,18 @@ export const ClipEntry = memo(function ClipEntry({
           </div>
         </div>
 
-        {isImage && imageUrl ? (
+        {masked ? (
+          <button
+            type="button"
+            className="clip-sensitive-mask"
+            onClick={(e) => {
+              e.stopPropagation();
+              setRevealed(true);
+            }}
+          >
+            {t("clip.sensitive.reveal")}
+          </button>
+        ) : isImage && imageUrl ? (
           <div className="clip-image-container" onClick={handleImageClick}>
             <img
               src={imageUrl}
@@ -1171,6 +1362,9 @@ export const ClipEntry = memo(function ClipEntry({
             >
               {clip.original_filename || clip.file_attachment}
             </button>
+            {clip.truncated && (
+              <span className="attachment-truncated-note">{t("clip.truncated")}</span>
+            )}
           </div>
         )}
 
@@ -1383,6 +1577,80 @@ export const ClipEntry = memo(function ClipEntry({
           </div>
         </div>
       )}
+
+      {contextMenuPosition && (
+        <div className="clip-context-menu-backdrop" onClick={() => setContextMenuPosition(null)}>
+          <div
+            className="clip-context-menu"
+            style={{ top: contextMenuPosition.top, left: contextMenuPosition.left }}
+            onClick={(e) => e.stopPropagation()}
+          >
+            {api.pasteClip && (
+              <button
+                className="clip-context-menu-item"
+                onClick={() => {
+                  setContextMenuPosition(null);
+                  handlePaste();
+                }}
+              >
+                {t("clip.paste")}
+              </button>
+            )}
+            {api.revealAttachment && clip.file_attachment && (
+              <button
+                className="clip-context-menu-item"
+                onClick={() => {
+                  setContextMenuPosition(null);
+                  handleRevealAttachment();
+                }}
+              >
+                {t("clip.revealAttachment")}
+              </button>
+            )}
+            <button className="clip-context-menu-item" onClick={handleToggleSensitive}>
+              {sensitive ? t("clip.sensitive.unmark") : t("clip.sensitive.mark")}
+            </button>
+            {canTranslate && (
+              <button
+                className="clip-context-menu-item"
+                onClick={handleTranslate}
+                disabled={translating}
+              >
+                {t("clip.translate", { language: languageNames[language] })}
+              </button>
+            )}
+            {contextMenuActions.length > 0 && (
+              <>
+                <div className="clip-context-menu-title">{t("action.title")}</div>
+                {contextMenuActions.map((action) => (
+                  <button
+                    key={action.name}
+                    className="clip-context-menu-item"
+                    onClick={() => handleRunAction(action)}
+                  >
+                    {action.name}
+                  </button>
+                ))}
+              </>
+            )}
+            {canTransform && (
+              <>
+                <div className="clip-context-menu-title">{t("transform.title")}</div>
+                {CLIP_TRANSFORMS.map((transform) => (
+                  <button
+                    key={transform}
+                    className="clip-context-menu-item"
+                    onClick={() => handleTransform(transform)}
+                    disabled={transforming}
+                  >
+                    {t(`transform.${transform}`)}
+                  </button>
+                ))}
+              </>
+            )}
+          </div>
+        </div>
+      )}
     </>
   );
 });
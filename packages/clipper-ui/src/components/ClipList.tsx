@@ -4,6 +4,7 @@ import { ClipEntry } from "./ClipEntry";
 import { ConnectionError } from "./ConnectionError";
 import { ScrollPositionIndicator } from "./ScrollPositionIndicator";
 import { useI18n } from "../i18n";
+import { useApi } from "../api";
 import { useScrollAnchor } from "../hooks/useScrollAnchor";
 import { useKeyboardNavigation, ClipButtonAction } from "../hooks/useKeyboardNavigation";
 
@@ -135,6 +136,7 @@ export function ClipList({
   scrollContainerRef,
 }: ClipListProps) {
   const { t } = useI18n();
+  const api = useApi();
   const internalScrollRef = useRef<HTMLDivElement>(null);
   const scrollRef = scrollContainerRef || internalScrollRef;
   const loadMoreTriggerRef = useRef<HTMLDivElement>(null);
@@ -183,6 +185,13 @@ export function ClipList({
     }
   }, [clips]);
 
+  const handlePasteRequest = useCallback((clipId: string) => {
+    const clipEntry = document.querySelector(`[data-clip-id="${clipId}"]`);
+    if (clipEntry) {
+      clipEntry.dispatchEvent(new CustomEvent("keyboard-paste-request", { bubbles: false }));
+    }
+  }, []);
+
   const handleButtonActivate = useCallback((clipId: string, buttonIndex: number) => {
     const action = BUTTON_ACTIONS[buttonIndex];
     const clipEntry = document.querySelector(`[data-clip-id="${clipId}"]`);
@@ -207,6 +216,7 @@ export function ClipList({
     buttonCount: BUTTON_COUNT,
     onExpandToggle: handleExpandToggle,
     onDelete: handleDeleteRequest,
+    onPaste: api.pasteClip ? handlePasteRequest : undefined,
     onButtonActivate: handleButtonActivate,
     searchInputRef,
     enabled: keyboardNavigationEnabled,
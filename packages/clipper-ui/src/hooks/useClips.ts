@@ -75,6 +75,11 @@ export function useClips(): UseClipsReturn {
             FAVORITE_TAG,
           ];
         }
+        // The total is known from the first page, so don't count again
+        // while scrolling
+        if (append) {
+          effectiveFilters.skip_total = true;
+        }
 
         let result: PagedResult;
 
@@ -105,10 +110,10 @@ export function useClips(): UseClipsReturn {
           loading: false,
           loadingMore: false,
           error: null,
-          total: result.total,
+          total: append ? prev.total : result.total,
           page: result.page,
-          totalPages: result.total_pages,
-          hasMore: result.page < result.total_pages,
+          totalPages: append ? prev.totalPages : result.total_pages,
+          hasMore: result.has_more ?? result.page < result.total_pages,
         }));
       } catch (err) {
         setState((prev) => ({
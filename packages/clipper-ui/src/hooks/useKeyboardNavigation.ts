@@ -42,6 +42,8 @@ export interface UseKeyboardNavigationOptions {
   onExpandToggle?: (clipId: string) => void;
   /** Callback when a clip should be deleted */
   onDelete?: (clipId: string) => void;
+  /** Callback when a clip should be pasted into the previously focused app (Ctrl/Cmd+Enter) */
+  onPaste?: (clipId: string) => void;
   /** Callback when a button should be activated */
   onButtonActivate?: (clipId: string, buttonIndex: number) => void;
   /** Reference to the search input element */
@@ -70,6 +72,7 @@ export function useKeyboardNavigation({
   buttonCount,
   onExpandToggle,
   onDelete,
+  onPaste,
   onButtonActivate,
   searchInputRef,
   enabled = true,
@@ -692,6 +695,13 @@ export function useKeyboardNavigation({
         return;
       }
 
+      // Ctrl/Cmd+Enter pastes the clip into the previously focused app
+      if (key === "Enter" && (e.ctrlKey || e.metaKey) && onPaste) {
+        e.preventDefault();
+        onPaste(focusedClipId);
+        return;
+      }
+
       // Enter expands/collapses the clip
       if (key === "Enter") {
         e.preventDefault();
@@ -752,6 +762,7 @@ export function useKeyboardNavigation({
     clearFocus,
     onExpandToggle,
     onDelete,
+    onPaste,
     onButtonActivate,
   ]);
 
@@ -17,11 +17,18 @@ export const en = {
 
   // Clip Entry
   "clip.copy": "Click to copy",
+  "clip.paste": "Paste into previous app",
+  "clip.revealAttachment": "Show in folder",
+  "clip.sensitive.mark": "Mark as sensitive",
+  "clip.sensitive.unmark": "Not sensitive",
+  "clip.sensitive.reveal": "Sensitive content, click to show",
+  "clip.translate": "Translate to {language}",
   "clip.share": "Share clip",
   "clip.delete": "Delete clip",
   "clip.favorite.add": "Add to favorites",
   "clip.favorite.remove": "Remove from favorites",
   "clip.download": "Click to download",
+  "clip.truncated": "Text shortened; the attachment has all of it",
   "clip.delete_confirm": "Are you sure you want to delete this clip?",
   "clip.delete_protected": "This clip is protected.",
   "clip.delete_protected_favorite": "This clip is in favorites.",
@@ -113,6 +120,17 @@ export const en = {
   "settings.about.updateAvailable": "New version available: {version}",
   "settings.about.checkFailed": "Failed to check for updates",
 
+  // Settings - Web Push notifications (web only)
+  "settings.webPush": "Notifications",
+  "settings.webPush.enable": "Turn on notifications",
+  "settings.webPush.disable": "Turn off notifications",
+  "settings.webPush.on": "Notifications are on for this browser.",
+  "settings.webPush.hint": "Show a system notification when a clip is added, even when no Clipper tab is open.",
+  "settings.webPush.unsupported": "This browser doesn't support push notifications, or the page isn't served over HTTPS.",
+  "settings.webPush.notConfigured": "Push notifications are not configured on the server.",
+  "settings.webPush.denied": "Notifications are blocked for this site. Allow them in the browser settings.",
+  "settings.webPush.error": "Failed to change notification settings",
+
   // Notes Popup
   "editClip.notes": "Notes",
   "editClip.notes.placeholder": "Add notes about this clip...",
@@ -161,6 +179,7 @@ export const en = {
   "tooltip.sendClipboard": "Send clipboard content",
   "tooltip.viewNotes": "View notes",
   "tooltip.addNotes": "Add notes",
+  "tooltip.dragOut": "Drag into another app",
 
   // File Drop
   "fileDrop.hint": "Drop files here to upload",
@@ -171,6 +190,21 @@ export const en = {
   "status.wsDisconnected": "Disconnected",
   "status.wsUnavailable": "HTTPS required",
 
+  // Actions
+  "action.title": "Actions",
+
+  // Transforms
+  "transform.title": "Transform",
+  "transform.uppercase": "UPPERCASE",
+  "transform.lowercase": "lowercase",
+  "transform.trim": "Trim whitespace",
+  "transform.base64_encode": "Base64 encode",
+  "transform.base64_decode": "Base64 decode",
+  "transform.json_pretty": "Format JSON",
+  "transform.json_minify": "Minify JSON",
+  "transform.url_encode": "URL encode",
+  "transform.url_decode": "URL decode",
+
   // Toast Messages
   "toast.clipCopied": "Copied to clipboard",
   "toast.imageCopied": "Image copied to clipboard",
@@ -181,6 +215,14 @@ export const en = {
   "toast.newClip": "New clip added",
   "toast.clipUpdated": "Clip updated",
   "toast.updateFailed": "Failed to update clip",
+  "toast.clipTransformed": "Transformed clip added",
+  "toast.transformFailed": "Failed to transform clip",
+  "toast.clipTranslated": "Translated clip added",
+  "toast.translateFailed": "Failed to translate clip",
+  "toast.actionFailed": "Action \"{name}\" failed",
+  "toast.pasteFailed": "Failed to paste into the previous app",
+  "toast.revealFailed": "Failed to show the file",
+  "toast.dragFailed": "Failed to drag the file",
   "toast.clipsCleanedUp": "{count} old clips cleaned up",
   "toast.wsConnected": "Real-time sync connected",
   "toast.wsDisconnected": "Real-time sync disconnected",
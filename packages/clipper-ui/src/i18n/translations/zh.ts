@@ -17,11 +17,18 @@ export const zh = {
 
   // Clip Entry
   "clip.copy": "点击复制",
+  "clip.paste": "粘贴到上一个应用",
+  "clip.revealAttachment": "在文件夹中显示",
+  "clip.sensitive.mark": "标记为敏感",
+  "clip.sensitive.unmark": "取消敏感标记",
+  "clip.sensitive.reveal": "敏感内容，点击显示",
+  "clip.translate": "翻译为{language}",
   "clip.share": "分享剪贴",
   "clip.delete": "删除剪贴",
   "clip.favorite.add": "添加到收藏",
   "clip.favorite.remove": "从收藏中移除",
   "clip.download": "点击下载",
+  "clip.truncated": "文本已截短，附件中有完整内容",
   "clip.delete_confirm": "确定要删除这条剪贴吗？",
   "clip.delete_protected": "此剪贴受保护。",
   "clip.delete_protected_favorite": "此剪贴已收藏。",
@@ -112,6 +119,17 @@ export const zh = {
   "settings.about.updateAvailable": "有新版本可用：{version}",
   "settings.about.checkFailed": "检查更新失败",
 
+  // Settings - Web Push notifications (web only)
+  "settings.webPush": "通知",
+  "settings.webPush.enable": "开启通知",
+  "settings.webPush.disable": "关闭通知",
+  "settings.webPush.on": "此浏览器已开启通知。",
+  "settings.webPush.hint": "添加剪贴时显示系统通知，即使没有打开 Clipper 标签页。",
+  "settings.webPush.unsupported": "此浏览器不支持推送通知，或页面未通过 HTTPS 访问。",
+  "settings.webPush.notConfigured": "服务器未配置推送通知。",
+  "settings.webPush.denied": "此网站的通知已被阻止，请在浏览器设置中允许。",
+  "settings.webPush.error": "更改通知设置失败",
+
   // Notes Popup
   "editClip.notes": "备注",
   "editClip.notes.placeholder": "添加关于这条剪贴的备注...",
@@ -160,6 +178,7 @@ export const zh = {
   "tooltip.sendClipboard": "发送剪贴板内容",
   "tooltip.viewNotes": "查看备注",
   "tooltip.addNotes": "添加备注",
+  "tooltip.dragOut": "拖到其他应用",
 
   // File Drop
   "fileDrop.hint": "拖拽文件到此处上传",
@@ -170,6 +189,21 @@ export const zh = {
   "status.wsDisconnected": "已断开",
   "status.wsUnavailable": "需要 HTTPS",
 
+  // Actions
+  "action.title": "操作",
+
+  // Transforms
+  "transform.title": "转换",
+  "transform.uppercase": "转为大写",
+  "transform.lowercase": "转为小写",
+  "transform.trim": "去除首尾空白",
+  "transform.base64_encode": "Base64 编码",
+  "transform.base64_decode": "Base64 解码",
+  "transform.json_pretty": "格式化 JSON",
+  "transform.json_minify": "压缩 JSON",
+  "transform.url_encode": "URL 编码",
+  "transform.url_decode": "URL 解码",
+
   // Toast Messages
   "toast.clipCopied": "已复制到剪贴板",
   "toast.imageCopied": "图片已复制到剪贴板",
@@ -180,6 +214,14 @@ export const zh = {
   "toast.newClip": "新剪贴已添加",
   "toast.clipUpdated": "剪贴已更新",
   "toast.updateFailed": "更新剪贴失败",
+  "toast.clipTransformed": "已添加转换后的剪贴",
+  "toast.transformFailed": "转换剪贴失败",
+  "toast.clipTranslated": "已添加翻译后的剪贴",
+  "toast.translateFailed": "翻译剪贴失败",
+  "toast.actionFailed": "操作“{name}”失败",
+  "toast.pasteFailed": "粘贴到上一个应用失败",
+  "toast.revealFailed": "显示文件失败",
+  "toast.dragFailed": "拖动文件失败",
   "toast.clipsCleanedUp": "已清理 {count} 条旧剪贴",
   "toast.wsConnected": "实时同步已连接",
   "toast.wsDisconnected": "实时同步已断开",
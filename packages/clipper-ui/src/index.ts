@@ -1,14 +1,26 @@
 // Types
 export type {
   Clip,
+  ClipAction,
+  ClipTransform,
   PagedResult,
   PagedTagResult,
+  MergedCursor,
+  MergedResult,
+  SourcedClip,
   SearchFilters,
   CleanupConfig,
   ServerConfig,
   Tag,
 } from "./types";
-export { FAVORITE_TAG, isFavorite, calculateAgeRatio } from "./types";
+export {
+  CLIP_TRANSFORMS,
+  FAVORITE_TAG,
+  SENSITIVE_TAG,
+  isFavorite,
+  isSensitive,
+  calculateAgeRatio,
+} from "./types";
 
 // API
 export type { ClipperApi, RestApiClient, RestApiClientOptions } from "./api";
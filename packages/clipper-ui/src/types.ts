@@ -2,12 +2,37 @@ export interface Clip {
   id: string;
   content: string;
   created_at: string;
+  /** When tags, notes, language, color or icon were last changed (equals created_at if never edited) */
+  updated_at?: string;
   tags: string[];
   additional_notes?: string;
   file_attachment?: string;
   original_filename?: string;
   /** Optional language identifier for the clip content (e.g., "en", "zh", "rust", "python") */
   language?: string;
+  /** Optional color label, a hex color ("#e11d48") or a color name ("red") */
+  color?: string;
+  /** Optional icon shown next to the clip, e.g. an emoji */
+  icon?: string;
+  /** How the content is written; markdown clips are rendered on shared pages */
+  content_format?: "plain" | "markdown";
+  /** Device the clip was created on, taken from its `$host:` tag */
+  source_device?: string;
+  /** The text was too long to keep in the clip: `content` is its beginning,
+   * and the whole text is the file attachment */
+  truncated?: boolean;
+  /** Who can see the clip: only the owner, any authenticated user, or also
+   * anyone with a short URL (missing from older servers) */
+  visibility?: "private" | "shared" | "public";
+  /** Natural language the server detected in the content, e.g. "en" */
+  detected_language?: string;
+  /** One-line summary from the server's enrichment, if it's enabled */
+  summary?: string;
+  /** Tags the server's enrichment suggests, not yet added to `tags` */
+  suggested_tags?: string[];
+  /** Where the URL in `content` leads, without tracking parameters, if the
+   * server's URL canonicalization found it different */
+  canonical_url?: string;
   /** Highlighted content with search terms wrapped by highlight markers.
    * Only present in search results when highlight params are provided. */
   highlighted_content?: string;
@@ -29,16 +54,66 @@ export interface PagedTagResult {
 
 export interface PagedResult {
   items: Clip[];
+  /** Number of matching clips; only a lower bound when `skip_total` was set */
   total: number;
   page: number;
   page_size: number;
   total_pages: number;
+  /** Whether another page follows (missing from older servers) */
+  has_more?: boolean;
+}
+
+/** A clip and the name of the server it came from */
+export interface SourcedClip extends Clip {
+  source: string;
+}
+
+/** Where a merged page ends: the number of clips taken from each server */
+export interface MergedCursor {
+  offsets: Record<string, number>;
+}
+
+/** One page of clips merged from several servers */
+export interface MergedResult {
+  items: SourcedClip[];
+  /** Sum of the servers' totals */
+  total: number;
+  /** Cursor for the next page; missing when no server has more clips */
+  next_cursor?: MergedCursor;
+  /** Servers left out of this page */
+  errors?: { source: string; message: string }[];
 }
 
 export interface SearchFilters {
   start_date?: string;
   end_date?: string;
   tags?: string[];
+  /** Only clips created on this device */
+  source_device?: string;
+  /** Skip counting all matches, for pages after the first one */
+  skip_total?: boolean;
+}
+
+/** Built-in text transforms, in the order they are offered in menus */
+export const CLIP_TRANSFORMS = [
+  "uppercase",
+  "lowercase",
+  "trim",
+  "base64_encode",
+  "base64_decode",
+  "json_pretty",
+  "json_minify",
+  "url_encode",
+  "url_decode",
+] as const;
+
+export type ClipTransform = (typeof CLIP_TRANSFORMS)[number];
+
+/** A user-defined action from the desktop app settings */
+export interface ClipAction {
+  name: string;
+  type: "command" | "http";
+  showInTray?: boolean;
 }
 
 export const FAVORITE_TAG = "$favorite";
@@ -47,6 +122,13 @@ export function isFavorite(clip: Clip): boolean {
   return clip.tags.includes(FAVORITE_TAG);
 }
 
+/** Tag of clips holding secrets; the server expires them early */
+export const SENSITIVE_TAG = "$sensitive";
+
+export function isSensitive(clip: Clip): boolean {
+  return clip.tags.includes(SENSITIVE_TAG);
+}
+
 /**
  * Auto-cleanup configuration from the server.
  * Used to calculate time-to-autoclean for visual aging effect.